@@ -0,0 +1,240 @@
+//! App — one UI instance and everything it owns.
+//!
+//! An `App` is a SharedBuffer, the engine drawing it (on the process
+//! terminal or a session's connection) and the signal its TS side blocks on
+//! for events. Nothing about one app is reachable from another, so any
+//! number can run in one process — two embedded UIs, a server's sessions,
+//! tests in parallel:
+//!
+//! ```text
+//! App ─┬─ SharedBuffer ──────── its TS instance writes props, reads events
+//!      ├─ EventSignal ───────── wait_for_events() (TS event loop)
+//!      └─ Engine | Session ──── engine thread + reader / wake watcher threads
+//!                                 └─ thread-locals: timers, frame callbacks,
+//!                                    tasks, terminal output, waker
+//! ```
+//!
+//! Engine-side state that callbacks reach without a handle lives in
+//! thread-locals on the engine thread, which is the app's own — one thread
+//! per app makes them per-app too.
+//!
+//! The FFI exports in lib.rs are thin wrappers: `spark_*` drive the default
+//! app (the process terminal, started by `spark_init`), `spark_session_*` the
+//! session apps by id.
+//!
+//! ```ignore
+//! let app = App::start_session(buf, io)?;
+//! // TS event worker:
+//! app.wait_for_events();
+//! // after writing props:
+//! app.wake();
+//! ```
+
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::pipeline::terminal::osc;
+use crate::pipeline::{Engine, Session, SessionIo};
+use crate::renderer::StyledLine;
+use crate::shared_buffer::SharedBuffer;
+
+/// Rust→TS event notification: TS blocks in `wait`, the engine calls
+/// `notify` when it writes events to the ring buffer.
+#[derive(Debug, Default)]
+pub struct EventSignal {
+    ready: Mutex<bool>,
+    cvar: Condvar,
+}
+
+impl EventSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signal that events are ready.
+    pub fn notify(&self) {
+        let mut ready = self.ready.lock().unwrap_or_else(|e| e.into_inner());
+        *ready = true;
+        self.cvar.notify_one();
+    }
+
+    /// Block until `notify` (0% CPU), consuming the notification.
+    pub fn wait(&self) {
+        let mut ready = self.ready.lock().unwrap_or_else(|e| e.into_inner());
+        while !*ready {
+            ready = self.cvar.wait(ready).unwrap_or_else(|e| e.into_inner());
+        }
+        *ready = false;
+    }
+}
+
+/// What draws an app.
+enum Runner {
+    Process(Engine),
+    Session(Session),
+}
+
+/// One UI instance: its buffer, engine and events signal.
+pub struct App {
+    buf: &'static SharedBuffer,
+    events: Arc<EventSignal>,
+    runner: Runner,
+}
+
+impl App {
+    /// Start an app on the process terminal (stdin, stdout, the TTY).
+    pub fn start(buf: &'static SharedBuffer) -> io::Result<Self> {
+        let events = attach_events(buf);
+        let runner = Runner::Process(Engine::start(buf)?);
+        Ok(Self { buf, events, runner })
+    }
+
+    /// Start an app on a remote terminal's streams (see `pipeline::session`).
+    pub fn start_session(buf: &'static SharedBuffer, io: SessionIo) -> io::Result<Self> {
+        let events = attach_events(buf);
+        let runner = Runner::Session(Session::start(buf, io)?);
+        Ok(Self { buf, events, runner })
+    }
+
+    pub fn buffer(&self) -> &'static SharedBuffer {
+        self.buf
+    }
+
+    /// The signal raised when events are written, for waiting without
+    /// holding on to the app.
+    pub fn events(&self) -> Arc<EventSignal> {
+        self.events.clone()
+    }
+
+    /// Tell the engine the SharedBuffer changed.
+    pub fn wake(&self) {
+        match &self.runner {
+            Runner::Process(engine) => engine.wake(),
+            Runner::Session(session) => session.wake(),
+        }
+    }
+
+    /// Block until the engine has events for TS.
+    pub fn wait_for_events(&self) {
+        self.events.wait();
+    }
+
+    /// The remote terminal changed size. The process terminal's size comes
+    /// from SIGWINCH, so this is a no-op for it.
+    pub fn resize(&self, width: u16, height: u16) {
+        if let Runner::Session(session) = &self.runner {
+            session.resize(width, height);
+        }
+    }
+
+    /// Record the app's terminal output to an asciinema cast at `path`
+    /// (replacing a recording already running), or stop with `None`.
+    pub fn record_cast(&self, path: Option<&Path>) -> io::Result<()> {
+        match &self.runner {
+            Runner::Process(engine) => engine.record_cast(path),
+            Runner::Session(session) => session.record_cast(path),
+        }
+    }
+
+    /// Set the window title, ring the bell or post a desktop notification
+    /// on the app's terminal.
+    pub fn terminal(&self, command: osc::Command) {
+        match &self.runner {
+            Runner::Process(engine) => engine.terminal(command),
+            Runner::Session(session) => session.terminal(command),
+        }
+    }
+
+    /// Freeze `lines` into history above the live region, with the next
+    /// frame. Append mode only; other modes drop them.
+    pub fn println(&self, lines: Vec<StyledLine>) {
+        match &self.runner {
+            Runner::Process(engine) => engine.println(lines),
+            Runner::Session(session) => session.println(lines),
+        }
+    }
+
+    /// Stop the engine and restore its terminal. Wakes the TS event loop so
+    /// it can exit.
+    pub fn stop(&self) {
+        self.events.notify();
+        match &self.runner {
+            Runner::Process(engine) => engine.stop(),
+            Runner::Session(session) => session.stop(),
+        }
+    }
+
+    /// Wait for the engine to finish after `stop`. After this nothing
+    /// reads the SharedBuffer, and its memory can be freed.
+    pub fn join(&mut self) {
+        match &mut self.runner {
+            Runner::Process(engine) => engine.join(),
+            Runner::Session(session) => session.join(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        match &self.runner {
+            Runner::Process(engine) => engine.is_running(),
+            Runner::Session(session) => session.is_running(),
+        }
+    }
+}
+
+fn attach_events(buf: &SharedBuffer) -> Arc<EventSignal> {
+    let events = Arc::new(EventSignal::new());
+    buf.attach_event_signal(events.clone());
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_buffer::{calculate_buffer_size, H_MAX_NODES, H_TEXT_POOL_SIZE};
+
+    fn leaked_buffer() -> &'static SharedBuffer {
+        const NODES: usize = 4;
+        const TEXT_POOL: usize = 1024;
+        let data: &'static mut [u8] = vec![0u8; calculate_buffer_size(NODES, TEXT_POOL)].leak();
+        data[H_MAX_NODES..H_MAX_NODES + 4].copy_from_slice(&(NODES as u32).to_le_bytes());
+        data[H_TEXT_POOL_SIZE..H_TEXT_POOL_SIZE + 4].copy_from_slice(&(TEXT_POOL as u32).to_le_bytes());
+        Box::leak(Box::new(unsafe { SharedBuffer::from_raw(data.as_mut_ptr(), data.len()) }))
+    }
+
+    /// A session app, and the client end that keeps its input open.
+    fn start() -> (App, io::PipeWriter) {
+        let (input, client_keys) = io::pipe().unwrap();
+        let app = App::start_session(leaked_buffer(), SessionIo {
+            input: Box::new(input),
+            close_input: None,
+            output: Box::new(io::sink()),
+            width: 20,
+            height: 5,
+        })
+        .unwrap();
+        (app, client_keys)
+    }
+
+    #[test]
+    fn test_events_wake_only_their_own_app() {
+        let (first, second) = (leaked_buffer(), leaked_buffer());
+        let (first_events, second_events) = (attach_events(first), attach_events(second));
+
+        first.notify_ts();
+        assert!(*first_events.ready.lock().unwrap());
+        assert!(!*second_events.ready.lock().unwrap());
+        first_events.wait();
+        assert!(!*first_events.ready.lock().unwrap());
+    }
+
+    #[test]
+    fn test_stopping_one_app_leaves_the_other_running() {
+        let ((first, _first_keys), (second, _second_keys)) = (start(), start());
+        second.stop();
+        assert!(!second.is_running());
+        assert!(first.is_running());
+        // Stopping wakes its TS event loop
+        second.wait_for_events();
+    }
+}
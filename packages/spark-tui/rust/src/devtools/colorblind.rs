@@ -0,0 +1,171 @@
+//! Color-blind simulation overlay.
+//!
+//! `apply()` rewrites every RGB cell of the FrameBuffer as it would be seen
+//! with protanopia, deuteranopia or tritanopia. TS toggles it by writing a
+//! `ColorVision` to `H_COLOR_VISION`; the contrast audit lives in TS
+//! (`auditThemeContrast`), which owns the theme.
+//!
+//! Simulation uses the Machado et al. (2009) matrices at full severity,
+//! applied in linear RGB. Terminal-default and ANSI colors are left alone —
+//! their real value belongs to the terminal palette, not to us.
+
+use crate::renderer::FrameBuffer;
+use crate::shared_buffer::ColorVision;
+use crate::utils::Rgba;
+
+/// Every simulated deficiency.
+pub const SIMULATIONS: [ColorVision; 3] = [
+    ColorVision::Protanopia,
+    ColorVision::Deuteranopia,
+    ColorVision::Tritanopia,
+];
+
+type Matrix = [[f32; 3]; 3];
+
+const PROTANOPIA: Matrix = [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+];
+
+const DEUTERANOPIA: Matrix = [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.011820, 0.042940, 0.968881],
+];
+
+const TRITANOPIA: Matrix = [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.303900],
+];
+
+fn matrix(mode: ColorVision) -> Option<&'static Matrix> {
+    match mode {
+        ColorVision::None => None,
+        ColorVision::Protanopia => Some(&PROTANOPIA),
+        ColorVision::Deuteranopia => Some(&DEUTERANOPIA),
+        ColorVision::Tritanopia => Some(&TRITANOPIA),
+    }
+}
+
+// =============================================================================
+// Color math
+// =============================================================================
+
+fn to_linear(channel: i16) -> f32 {
+    let c = channel.clamp(0, 255) as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_srgb(linear: f32) -> i16 {
+    let l = linear.clamp(0.0, 1.0);
+    let c = if l <= 0.003_130_8 {
+        l * 12.92
+    } else {
+        1.055 * l.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as i16
+}
+
+/// Whether the color carries real RGB we can transform.
+fn is_rgb(color: Rgba) -> bool {
+    !color.is_terminal_default() && !color.is_ansi()
+}
+
+/// How `color` looks with the given deficiency. Alpha is preserved;
+/// terminal-default and ANSI colors are returned unchanged.
+pub fn simulate(color: Rgba, mode: ColorVision) -> Rgba {
+    let Some(m) = matrix(mode) else {
+        return color;
+    };
+    if !is_rgb(color) {
+        return color;
+    }
+
+    let rgb = [to_linear(color.r), to_linear(color.g), to_linear(color.b)];
+    let mix = |row: &[f32; 3]| row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+
+    Rgba {
+        r: to_srgb(mix(&m[0])),
+        g: to_srgb(mix(&m[1])),
+        b: to_srgb(mix(&m[2])),
+        a: color.a,
+    }
+}
+
+// =============================================================================
+// Overlay
+// =============================================================================
+
+/// Rewrite the frame as seen with `mode`. No-op for `ColorVision::None`.
+pub fn apply(frame: &mut FrameBuffer, mode: ColorVision) {
+    if mode == ColorVision::None {
+        return;
+    }
+
+    for y in 0..frame.height() {
+        for x in 0..frame.width() {
+            if let Some(cell) = frame.get_mut(x, y) {
+                cell.fg = simulate(cell.fg, mode);
+                cell.bg = simulate(cell.bg, mode);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulate_skips_palette_colors() {
+        for mode in SIMULATIONS {
+            assert_eq!(simulate(Rgba::TERMINAL_DEFAULT, mode), Rgba::TERMINAL_DEFAULT);
+            assert_eq!(simulate(Rgba::ansi(1), mode), Rgba::ansi(1));
+        }
+        assert_eq!(simulate(Rgba::RED, ColorVision::None), Rgba::RED);
+    }
+
+    #[test]
+    fn test_simulate_preserves_grays() {
+        // Each matrix row sums to 1, so neutral colors are unchanged
+        for mode in SIMULATIONS {
+            let gray = simulate(Rgba::GRAY, mode);
+            assert!((gray.r - 128).abs() <= 1 && (gray.g - 128).abs() <= 1 && (gray.b - 128).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_simulate_confuses_red_and_green() {
+        // Protans and deutans lose the red-green axis: both land on the
+        // same yellow-brown hue, with red darkened for protans
+        for mode in [ColorVision::Protanopia, ColorVision::Deuteranopia] {
+            let red = simulate(Rgba::RED, mode);
+            let green = simulate(Rgba::GREEN, mode);
+            assert!(red.b < red.g && green.b < green.g, "{mode:?}: {red:?} {green:?}");
+        }
+        let red = simulate(Rgba::new(255, 0, 0, 128), ColorVision::Protanopia);
+        assert!(red.r < 128 && red.a == 128);
+        // Tritans keep red and green apart
+        assert!(simulate(Rgba::RED, ColorVision::Tritanopia).r > 200);
+    }
+
+    #[test]
+    fn test_apply_rewrites_the_frame() {
+        let mut frame = FrameBuffer::new(4, 1);
+        frame.draw_text(0, 0, "ok", Rgba::BLACK, Some(Rgba::WHITE), Default::default(), None);
+        apply(&mut frame, ColorVision::None);
+        assert_eq!(frame.get(0, 0).unwrap().fg, Rgba::BLACK);
+
+        frame.draw_text(0, 0, "x", Rgba::RED, Some(Rgba::GREEN), Default::default(), None);
+        apply(&mut frame, ColorVision::Deuteranopia);
+        let cell = frame.get(0, 0).unwrap();
+        assert_ne!(cell.fg, Rgba::RED);
+        assert_ne!(cell.bg, Rgba::GREEN);
+    }
+}
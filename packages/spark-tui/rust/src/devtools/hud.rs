@@ -0,0 +1,160 @@
+//! Performance HUD — frame stats drawn over the top-right corner.
+//!
+//! TS toggles it with `H_PERF_HUD`; with a toggle key bound
+//! (`H_PERF_HUD_KEY`) the engine flips it on the keypress. While it shows,
+//! `fb_derived` draws:
+//!
+//! ```text
+//!  fps        58    frames in the second up to this one
+//!  layout  120µs    this frame
+//!  fbuf    340µs    ┐
+//!  render   80µs    │ the previous frame — this one isn't
+//!  cells      42    │ finished / written yet
+//!  bytes    1.2K    ┘
+//!  nodes     123
+//!  in p50  4.1ms    ┐ input to the frame that showed it,
+//!  in p95  9.8ms    │ over the last samples (pipeline::latency)
+//!  in p99 12.0ms    ┘
+//! ```
+//!
+//! Frames only start on change, so the HUD never causes one: an idle app's
+//! HUD keeps showing the last burst until something changes.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::renderer::FrameBuffer;
+use crate::shared_buffer::SharedBuffer;
+use crate::utils::{Attr, Rgba};
+
+/// Frames per second are counted over this trailing window.
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+const HUD_FG: Rgba = Rgba::rgb(230, 230, 230);
+const HUD_LABEL: Rgba = Rgba::rgb(140, 170, 200);
+const HUD_BG: Rgba = Rgba::rgb(25, 30, 40);
+
+/// Label column width.
+const LABEL_WIDTH: usize = 7;
+/// Value column width.
+const VALUE_WIDTH: usize = 6;
+/// One space either side of the columns.
+const HUD_WIDTH: u16 = (LABEL_WIDTH + VALUE_WIDTH + 2) as u16;
+
+/// Recent frame times, for the frame rate. Lives next to `fb_derived`.
+#[derive(Debug, Default)]
+pub struct PerfHud {
+    frames: VecDeque<Instant>,
+}
+
+impl PerfHud {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a frame at `now`; returns the frames in the window ending there.
+    pub fn record_frame(&mut self, now: Instant) -> usize {
+        while self.frames.front().is_some_and(|&t| now.duration_since(t) >= FPS_WINDOW) {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(now);
+        self.frames.len()
+    }
+}
+
+/// The HUD's rows as (label, value).
+pub fn rows(buf: &SharedBuffer, fps: usize) -> [(&'static str, String); 10] {
+    let (p50, p95, p99) = buf.input_latency_us();
+    [
+        ("fps", fps.to_string()),
+        ("layout", micros(buf.layout_time_us())),
+        ("fbuf", micros(buf.framebuffer_time_us())),
+        ("render", micros(buf.render_time_us())),
+        ("cells", buf.frame_cells().to_string()),
+        ("bytes", bytes(buf.frame_bytes())),
+        ("nodes", buf.node_count().to_string()),
+        ("in p50", micros(p50)),
+        ("in p95", micros(p95)),
+        ("in p99", micros(p99)),
+    ]
+}
+
+/// Draw the HUD if it's switched on.
+pub fn draw(frame: &mut FrameBuffer, buf: &SharedBuffer, hud: &mut PerfHud, now: Instant) {
+    if !buf.perf_hud() {
+        return;
+    }
+    let fps = hud.record_frame(now);
+    let rows = rows(buf, fps);
+    let width = HUD_WIDTH.min(frame.width());
+    let height = (rows.len() as u16).min(frame.height());
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let x = frame.width() - width;
+    frame.fill_rect(x, 0, width, height, HUD_BG, None);
+    for (y, (label, value)) in rows.iter().enumerate().take(height as usize) {
+        let y = y as u16;
+        frame.draw_text(x + 1, y, label, HUD_LABEL, Some(HUD_BG), Attr::NONE, None);
+        let value = format!("{:>width$}", value, width = VALUE_WIDTH);
+        frame.draw_text(x + 1 + LABEL_WIDTH as u16, y, &value, HUD_FG, Some(HUD_BG), Attr::BOLD, None);
+    }
+}
+
+fn micros(us: u32) -> String {
+    if us >= 1000 {
+        format!("{:.1}ms", us as f32 / 1000.0)
+    } else {
+        format!("{}µs", us)
+    }
+}
+
+fn bytes(count: u32) -> String {
+    const KB: f32 = 1024.0;
+    let count = count as f32;
+    if count >= KB * KB {
+        format!("{:.1}M", count / (KB * KB))
+    } else if count >= KB {
+        format!("{:.1}K", count / KB)
+    } else {
+        format!("{}B", count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_rate_counts_the_trailing_second() {
+        let mut hud = PerfHud::new();
+        let start = Instant::now();
+        for ms in 0..10 {
+            hud.record_frame(start + Duration::from_millis(ms * 100));
+        }
+        assert_eq!(hud.record_frame(start + Duration::from_millis(1000)), 10);
+        // A quiet spell empties the window
+        assert_eq!(hud.record_frame(start + Duration::from_secs(5)), 1);
+    }
+
+    #[test]
+    fn test_rows_show_input_latency() {
+        use crate::shared_buffer::test_support::TestBuffer;
+
+        let buf = TestBuffer::new(1, 64);
+        buf.set_input_latency(4100, 9800, 12000, 5000);
+        let rows = rows(&buf, 60);
+        let latency: Vec<(&str, &str)> = rows[7..].iter().map(|(label, value)| (*label, value.as_str())).collect();
+        assert_eq!(latency, [("in p50", "4.1ms"), ("in p95", "9.8ms"), ("in p99", "12.0ms")]);
+    }
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(micros(120), "120µs");
+        assert_eq!(micros(2500), "2.5ms");
+        assert_eq!(bytes(812), "812B");
+        assert_eq!(bytes(1229), "1.2K");
+        assert_eq!(bytes(3 * 1024 * 1024), "3.0M");
+    }
+}
@@ -0,0 +1,371 @@
+//! Component inspector — the live component tree over the UI.
+//!
+//! Opened from TS (`H_INSPECTOR`) or with its toggle key. While it's open
+//! the engine keeps input for itself:
+//!
+//! ```text
+//! ↑ ↓        previous / next node in tree order
+//! ← →        parent / first child
+//! click      the node under the mouse
+//! Esc, key   close
+//! ```
+//!
+//! and `fb_derived` draws a panel with the tree — index, type, layout size,
+//! screen position and key props per node — beside the selected node, whose
+//! region is tinted:
+//!
+//! ```text
+//!  inspector  ↑↓←→ click · esc
+//!  #0 box 80×24 @0,0
+//!    #1 text 12×1 @2,1 "Hello"     ← selected row, its region tinted
+//!    #2 input 20×1 @2,3 focus
+//! ```
+//!
+//! Positions are where the node landed on screen this frame (its visible
+//! part, from the hit regions); nodes that weren't painted show `-`.
+
+use std::collections::HashMap;
+
+use crate::framebuffer::HitRegion;
+use crate::input::parser::{KeyCode, KeyEvent, KeyState};
+use crate::renderer::FrameBuffer;
+use crate::shared_buffer::{
+    LiveRegion, SharedBuffer, COMPONENT_BOX, COMPONENT_INPUT, COMPONENT_NONE, COMPONENT_PROGRESS, COMPONENT_SELECT, COMPONENT_TEXT,
+};
+use crate::utils::{Attr, ClipRect, Rgba};
+
+/// Widest the panel gets.
+const PANEL_WIDTH: u16 = 48;
+/// Characters of text content shown per row.
+const TEXT_PREVIEW: usize = 16;
+/// Indent per tree level.
+const INDENT: usize = 2;
+
+const PANEL_FG: Rgba = Rgba::rgb(220, 220, 220);
+const PANEL_DIM: Rgba = Rgba::rgb(130, 140, 155);
+const PANEL_BG: Rgba = Rgba::rgb(22, 26, 34);
+const TITLE_BG: Rgba = Rgba::rgb(60, 80, 120);
+const SELECTED_BG: Rgba = Rgba::rgb(70, 110, 170);
+/// Tint over the selected node's cells.
+const HIGHLIGHT_BG: Rgba = Rgba::rgb(40, 90, 150);
+
+const TITLE: &str = " inspector  ↑↓←→ click · esc";
+
+/// One row of the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeRow {
+    pub index: usize,
+    pub depth: usize,
+}
+
+/// Every live component in tree order (roots by index, parents before
+/// their children, siblings in order), hidden ones included.
+pub fn tree(buf: &SharedBuffer) -> Vec<TreeRow> {
+    let node_count = buf.node_count();
+    let live = |i: usize| i < node_count && buf.component_type(i) != COMPONENT_NONE;
+    let roots: Vec<usize> = (0..node_count).filter(|&i| live(i) && buf.parent_index(i).is_none()).collect();
+
+    let mut rows = Vec::new();
+    let mut stack: Vec<TreeRow> = roots.iter().rev().map(|&index| TreeRow { index, depth: 0 }).collect();
+    while let Some(row) = stack.pop() {
+        rows.push(row);
+        let mut children = Vec::new();
+        let mut child = buf.first_child(row.index);
+        // Bounded by node_count in case the links are mid-update
+        while child >= 0 && children.len() < node_count {
+            if live(child as usize) {
+                children.push(child as usize);
+            }
+            child = buf.next_sibling(child as usize);
+        }
+        stack.extend(children.iter().rev().map(|&index| TreeRow { index, depth: row.depth + 1 }));
+    }
+    rows
+}
+
+/// Lower-case name of a COMPONENT_* type.
+pub fn type_name(component_type: u8) -> &'static str {
+    match component_type {
+        COMPONENT_BOX => "box",
+        COMPONENT_TEXT => "text",
+        COMPONENT_INPUT => "input",
+        COMPONENT_SELECT => "select",
+        COMPONENT_PROGRESS => "progress",
+        _ => "node",
+    }
+}
+
+/// Selection state; lives on the engine thread next to `fb_derived`.
+#[derive(Debug, Default)]
+pub struct Inspector {
+    selected: Option<usize>,
+    /// Where the last draw put the panel, for clicks on its rows.
+    panel: Option<Panel>,
+}
+
+/// The panel's columns and the node shown on each row below the title.
+#[derive(Debug)]
+struct Panel {
+    x: u16,
+    width: u16,
+    rows: Vec<usize>,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The selected node, once the inspector has been drawn or navigated.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Select `index` (a click; `None` keeps the current selection).
+    pub fn select(&mut self, index: Option<usize>) {
+        if index.is_some() {
+            self.selected = index;
+        }
+    }
+
+    /// A click at (`x`, `y`) over node `hit`: a panel row selects its
+    /// node, anywhere else selects the node under the mouse.
+    pub fn click(&mut self, x: u16, y: u16, hit: Option<usize>) {
+        let row = self.panel.as_ref().and_then(|panel| {
+            let inside = x >= panel.x && x < panel.x + panel.width;
+            if !inside {
+                return None;
+            }
+            // The title row selects nothing, but is still the panel
+            Some(y.checked_sub(1).and_then(|row| panel.rows.get(row as usize).copied()))
+        });
+        match row {
+            Some(row) => self.select(row),
+            None => self.select(hit),
+        }
+    }
+
+    /// Handle a key while open.
+    pub fn key(&mut self, buf: &SharedBuffer, key: &KeyEvent) {
+        if key.state == KeyState::Release {
+            return;
+        }
+        let rows = tree(buf);
+        let position = self.position(&rows);
+        let next = match key.code {
+            KeyCode::Escape => {
+                buf.set_inspector(false);
+                return;
+            }
+            KeyCode::Up => position.and_then(|p| p.checked_sub(1)).map(|p| rows[p].index),
+            KeyCode::Down => position.and_then(|p| rows.get(p + 1)).map(|row| row.index),
+            KeyCode::Left => self.selected.and_then(|i| buf.parent_index(i)),
+            KeyCode::Right => position
+                .and_then(|p| rows.get(p + 1))
+                .filter(|row| rows[position.unwrap_or(0)].depth < row.depth)
+                .map(|row| row.index),
+            _ => None,
+        };
+        self.select(next);
+    }
+
+    /// Row of the selection, falling back to the first row when the
+    /// selected node is gone.
+    fn position(&mut self, rows: &[TreeRow]) -> Option<usize> {
+        let found = self.selected.and_then(|i| rows.iter().position(|row| row.index == i));
+        let position = found.or(if rows.is_empty() { None } else { Some(0) });
+        self.selected = position.map(|p| rows[p].index);
+        position
+    }
+
+    /// Draw the panel and tint the selected node, if the inspector is open.
+    pub fn draw(&mut self, frame: &mut FrameBuffer, buf: &SharedBuffer, hit_regions: &[HitRegion]) {
+        if !buf.inspector() || frame.width() == 0 || frame.height() == 0 {
+            self.panel = None;
+            return;
+        }
+        let rows = tree(buf);
+        let position = self.position(&rows);
+        // A node's first region is its whole box (border strips follow)
+        let mut regions: HashMap<usize, &HitRegion> = HashMap::new();
+        for region in hit_regions {
+            regions.entry(region.component_index).or_insert(region);
+        }
+
+        let selected_region = self.selected.and_then(|i| regions.get(&i).copied());
+        if let Some(region) = selected_region {
+            tint(frame, region);
+        }
+
+        // Panel on the side away from the selected node
+        let width = PANEL_WIDTH.min(frame.width());
+        let node_center = selected_region.map(|r| r.x + r.width / 2).unwrap_or(0);
+        let x = if node_center < frame.width() / 2 { frame.width() - width } else { 0 };
+        let height = frame.height();
+        let clip = ClipRect::new(x as i32, 0, width, height);
+        frame.fill_rect(x, 0, width, height, PANEL_BG, None);
+        frame.fill_rect(x, 0, width, 1, TITLE_BG, None);
+        frame.draw_text(x, 0, TITLE, PANEL_FG, Some(TITLE_BG), Attr::BOLD, Some(&clip));
+
+        // Scroll the list so the selection stays in view
+        let visible_rows = height.saturating_sub(1) as usize;
+        let first = position.map(|p| (p + 1).saturating_sub(visible_rows)).unwrap_or(0);
+        let shown = rows.iter().skip(first).take(visible_rows).map(|row| row.index).collect();
+        self.panel = Some(Panel { x, width, rows: shown });
+        for (offset, row) in rows.iter().skip(first).take(visible_rows).enumerate() {
+            let y = offset as u16 + 1;
+            let selected = Some(first + offset) == position;
+            let bg = if selected { SELECTED_BG } else { PANEL_BG };
+            if selected {
+                frame.fill_rect(x, y, width, 1, bg, None);
+            }
+            let fg = if buf.visible(row.index) { PANEL_FG } else { PANEL_DIM };
+            let line = describe(buf, row, regions.get(&row.index).copied());
+            frame.draw_text(x + 1, y, &line, fg, Some(bg), Attr::NONE, Some(&clip));
+        }
+    }
+}
+
+/// `#3 text 12×1 @2,1 "Hello" [slider: Volume, 3 of 10] focus live`
+fn describe(buf: &SharedBuffer, row: &TreeRow, region: Option<&HitRegion>) -> String {
+    let i = row.index;
+    let mut line = format!(
+        "{}#{} {} {}×{} ",
+        " ".repeat(row.depth * INDENT),
+        i,
+        type_name(buf.component_type(i)),
+        buf.computed_width(i) as u32,
+        buf.computed_height(i) as u32,
+    );
+    match region {
+        Some(region) => line.push_str(&format!("@{},{}", region.x, region.y)),
+        None => line.push('-'),
+    }
+
+    let text = buf.text(i);
+    if !text.is_empty() {
+        let preview: String = text.chars().take(TEXT_PREVIEW).filter(|c| !c.is_control()).collect();
+        let more = if text.chars().count() > TEXT_PREVIEW { "…" } else { "" };
+        line.push_str(&format!(" \"{}{}\"", preview, more));
+    }
+    // Accessibility: `[button: Save]`, then the value as announced
+    let role = buf.a11y_role(i).name();
+    let label = buf.a11y_label(i);
+    let value = buf.a11y_value(i);
+    let name = match (role.is_empty(), label.is_empty()) {
+        (true, true) => String::new(),
+        (false, true) => role.to_string(),
+        (true, false) => label.to_string(),
+        (false, false) => format!("{}: {}", role, label),
+    };
+    match (name.is_empty(), value.is_empty()) {
+        (true, true) => {}
+        (false, true) => line.push_str(&format!(" [{}]", name)),
+        (true, false) => line.push_str(&format!(" [{}]", value)),
+        (false, false) => line.push_str(&format!(" [{}, {}]", name, value)),
+    }
+    for (flag, name) in [
+        (buf.focusable(i), "focus"),
+        (buf.is_scrollable(i), "scroll"),
+        (!buf.visible(i), "hidden"),
+        (buf.a11y_live(i) != LiveRegion::Off, "live"),
+    ] {
+        if flag {
+            line.push(' ');
+            line.push_str(name);
+        }
+    }
+    line
+}
+
+fn tint(frame: &mut FrameBuffer, region: &HitRegion) {
+    for y in region.y..region.y + region.height {
+        for x in region.x..region.x + region.width {
+            if let Some(cell) = frame.get_mut(x, y) {
+                cell.bg = HIGHLIGHT_BG;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::parser::Modifier;
+    use crate::shared_buffer::test_support::TestBuffer;
+    use crate::shared_buffer::{N_FIRST_CHILD, N_NEXT_SIBLING, N_A11Y_LIVE, N_A11Y_ROLE, A11yRole};
+
+    const MAX_NODES: usize = 8;
+    const TEXT_POOL: usize = 1024;
+
+    /// root(0) → [a(1) → [c(3)], b(2)]
+    const TREE: [(i32, i32, i32); 4] = [
+        // (parent, first child, next sibling)
+        (-1, 1, -1),
+        (0, 3, 2),
+        (0, -1, -1),
+        (1, -1, -1),
+    ];
+
+    fn create_buffer() -> TestBuffer {
+        let buf = TestBuffer::new(MAX_NODES, TEXT_POOL);
+        buf.set_node_count(TREE.len());
+        for (i, (parent, first_child, next_sibling)) in TREE.into_iter().enumerate() {
+            buf.node(i, if i == 3 { COMPONENT_TEXT } else { COMPONENT_BOX }, parent);
+            buf.put_i32(i, N_FIRST_CHILD, first_child);
+            buf.put_i32(i, N_NEXT_SIBLING, next_sibling);
+        }
+        buf
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent { code, modifiers: Modifier::NONE, state: KeyState::Press }
+    }
+
+    #[test]
+    fn test_tree_is_in_tree_order_with_depths() {
+        let buf = create_buffer();
+        let rows: Vec<(usize, usize)> = tree(&buf).iter().map(|r| (r.index, r.depth)).collect();
+        assert_eq!(rows, vec![(0, 0), (1, 1), (3, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn test_arrow_navigation() {
+        let buf = create_buffer();
+        buf.set_inspector(true);
+        let mut inspector = Inspector::new();
+
+        inspector.key(&buf, &press(KeyCode::Down));
+        assert_eq!(inspector.selected(), Some(1));
+        inspector.key(&buf, &press(KeyCode::Right));
+        assert_eq!(inspector.selected(), Some(3));
+        // No children: stays put
+        inspector.key(&buf, &press(KeyCode::Right));
+        assert_eq!(inspector.selected(), Some(3));
+        inspector.key(&buf, &press(KeyCode::Left));
+        assert_eq!(inspector.selected(), Some(1));
+        inspector.key(&buf, &press(KeyCode::Up));
+        assert_eq!(inspector.selected(), Some(0));
+
+        // Clicks on screen select what's under them
+        inspector.click(3, 3, Some(2));
+        assert_eq!(inspector.selected(), Some(2));
+
+        inspector.key(&buf, &press(KeyCode::Escape));
+        assert!(!buf.inspector());
+    }
+
+    #[test]
+    fn test_describe_shows_accessibility() {
+        let buf = create_buffer();
+        buf.put_u8(2, N_A11Y_ROLE, A11yRole::Button as u8);
+        assert!(buf.set_a11y_label(2, "Save"));
+        let row = TreeRow { index: 2, depth: 1 };
+        assert!(describe(&buf, &row, None).ends_with(" [button: Save]"));
+
+        buf.put_u8(2, N_A11Y_ROLE, A11yRole::Slider as u8);
+        assert!(buf.set_a11y_value(2, "3 of 10"));
+        buf.put_u8(2, N_A11Y_LIVE, LiveRegion::Polite as u8);
+        assert!(describe(&buf, &row, None).ends_with(" [slider: Save, 3 of 10] live"));
+    }
+}
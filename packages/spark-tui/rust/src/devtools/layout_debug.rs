@@ -0,0 +1,295 @@
+//! Layout debug overlay — what flexbox did, drawn over the frame.
+//!
+//! With `H_DEBUG_LAYOUT` set (`SharedBuffer::set_debug_layout`, TS
+//! `setDebugLayout`), `fb_derived` marks up every visible node like a
+//! browser's devtools:
+//!
+//! ```text
+//! ┌────────────────┐   outline: the border box, colored by depth
+//! │╱╱╱╱╱╱╱╱╱╱╱╱╱╱╱╱│   ╱ hatch: padding
+//! │╱╱Hello  ░░ Bye╱│   tint: gap between neighbouring children
+//! │╱╱╱╱╱╱╱╱╱╱╱╱╱╱╱╱│
+//! └────────────────┘
+//! ```
+//!
+//! Marks only go on blank cells, so content stays readable: an outline
+//! running through text recolors it instead. Gaps are the space between
+//! neighbouring in-flow children on the same line; the gap between wrapped
+//! lines isn't marked.
+
+use crate::renderer::FrameBuffer;
+use crate::shared_buffer::{Position, SharedBuffer, COMPONENT_NONE};
+use crate::utils::{Cell, ClipRect, Rgba};
+
+/// Outline colors, cycled by tree depth so nested boxes stand apart.
+const OUTLINE_COLORS: [Rgba; 4] = [
+    Rgba::rgb(255, 170, 60),
+    Rgba::rgb(90, 190, 255),
+    Rgba::rgb(250, 110, 200),
+    Rgba::rgb(240, 230, 90),
+];
+const PADDING_FG: Rgba = Rgba::rgb(120, 190, 110);
+const GAP_BG: Rgba = Rgba::rgb(110, 70, 150);
+
+const HATCH: char = '╱';
+
+/// A node's border box on screen (may extend past the edges).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+}
+
+impl Rect {
+    fn right(&self) -> i32 {
+        self.x + self.w
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.h
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+}
+
+/// Mark up the frame if the overlay is on.
+pub fn draw(frame: &mut FrameBuffer, buf: &SharedBuffer) {
+    if !buf.debug_layout() {
+        return;
+    }
+    let node_count = buf.node_count();
+    let shown = |i: usize| buf.component_type(i) != COMPONENT_NONE && buf.visible(i);
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    let mut roots = Vec::new();
+    for i in (0..node_count).filter(|&i| shown(i)) {
+        match buf.parent_index(i) {
+            Some(parent) if parent < node_count => children[parent].push(i),
+            _ => roots.push(i),
+        }
+    }
+    roots.sort_by_key(|&i| buf.paint_order(i));
+    for list in children.iter_mut() {
+        list.sort_by_key(|&i| buf.paint_order(i));
+    }
+
+    let screen = ClipRect::new(0, 0, frame.width(), frame.height());
+    for &root in &roots {
+        draw_node(frame, buf, root, &children, &screen, (0, 0), 0);
+    }
+}
+
+/// Same placement as `render_component`: parent's position plus the
+/// computed offset, less the parent's scroll.
+fn draw_node(
+    frame: &mut FrameBuffer,
+    buf: &SharedBuffer,
+    index: usize,
+    children: &[Vec<usize>],
+    parent_clip: &ClipRect,
+    parent_origin: (i32, i32),
+    depth: usize,
+) {
+    let rect = placement(buf, index, parent_origin);
+    if rect.w <= 0 || rect.h <= 0 {
+        return;
+    }
+    let Some(clip) = ClipRect::new(rect.x, rect.y, rect.w as u16, rect.h as u16).intersect(parent_clip) else {
+        return;
+    };
+
+    hatch_padding(frame, buf, index, rect, &clip);
+    tint_gaps(frame, buf, index, &children[index], rect, &clip);
+    outline(frame, rect, OUTLINE_COLORS[depth % OUTLINE_COLORS.len()], &clip);
+
+    for &child in &children[index] {
+        draw_node(frame, buf, child, children, &clip, (rect.x, rect.y), depth + 1);
+    }
+}
+
+fn placement(buf: &SharedBuffer, index: usize, (origin_x, origin_y): (i32, i32)) -> Rect {
+    let (scroll_x, scroll_y) = match buf.parent_index(index) {
+        Some(parent) if buf.is_scrollable(parent) => (buf.scroll_x(parent), buf.scroll_y(parent)),
+        _ => (0, 0),
+    };
+    Rect {
+        x: origin_x + buf.computed_x(index) as i32 - scroll_x,
+        y: origin_y + buf.computed_y(index) as i32 - scroll_y,
+        w: buf.computed_width(index) as i32,
+        h: buf.computed_height(index) as i32,
+    }
+}
+
+/// Hatch the ring between the border and the content box.
+fn hatch_padding(frame: &mut FrameBuffer, buf: &SharedBuffer, index: usize, rect: Rect, clip: &ClipRect) {
+    let inner = Rect {
+        x: rect.x + buf.border_inset_left(index) as i32,
+        y: rect.y + buf.border_inset_top(index) as i32,
+        w: rect.w - buf.border_inset_left(index) as i32 - buf.border_inset_right(index) as i32,
+        h: rect.h - buf.border_inset_top(index) as i32 - buf.border_inset_bottom(index) as i32,
+    };
+    let content = Rect {
+        x: inner.x + buf.padding_left(index) as i32,
+        y: inner.y + buf.padding_top(index) as i32,
+        w: inner.w - buf.padding_left(index) as i32 - buf.padding_right(index) as i32,
+        h: inner.h - buf.padding_top(index) as i32 - buf.padding_bottom(index) as i32,
+    };
+    if content == inner {
+        return;
+    }
+    for y in inner.y..inner.bottom() {
+        for x in inner.x..inner.right() {
+            if !content.contains(x, y) {
+                mark(frame, x, y, clip, |cell| {
+                    cell.char = HATCH as u32;
+                    cell.fg = PADDING_FG;
+                });
+            }
+        }
+    }
+}
+
+/// Tint the space between neighbouring in-flow children.
+fn tint_gaps(frame: &mut FrameBuffer, buf: &SharedBuffer, index: usize, children: &[usize], rect: Rect, clip: &ClipRect) {
+    let (column_gap, row_gap) = resolved_gap(buf, index);
+    if column_gap <= 0 && row_gap <= 0 {
+        return;
+    }
+    let in_flow: Vec<Rect> = children
+        .iter()
+        .filter(|&&child| Position::from(buf.position(child)) == Position::Relative)
+        .map(|&child| placement(buf, child, (rect.x, rect.y)))
+        .collect();
+    for pair in in_flow.windows(2) {
+        if let Some(gap) = gap_between(pair[0], pair[1], column_gap, row_gap) {
+            for y in gap.y..gap.bottom() {
+                for x in gap.x..gap.right() {
+                    mark(frame, x, y, clip, |cell| cell.bg = GAP_BG);
+                }
+            }
+        }
+    }
+}
+
+/// Column and row gap as layout applies them: the specific one, else `gap`.
+fn resolved_gap(buf: &SharedBuffer, index: usize) -> (i32, i32) {
+    let gap = buf.gap(index);
+    let column = buf.column_gap(index);
+    let row = buf.row_gap(index);
+    (
+        if column != 0.0 { column } else { gap } as i32,
+        if row != 0.0 { row } else { gap } as i32,
+    )
+}
+
+/// The gap strip between two siblings that sit side by side or one above
+/// the other (either order, for reversed directions).
+fn gap_between(a: Rect, b: Rect, column_gap: i32, row_gap: i32) -> Option<Rect> {
+    let (first_x, second_x) = if a.x <= b.x { (a, b) } else { (b, a) };
+    let (first_y, second_y) = if a.y <= b.y { (a, b) } else { (b, a) };
+    let top = a.y.max(b.y);
+    let bottom = a.bottom().min(b.bottom());
+    let left = a.x.max(b.x);
+    let right = a.right().min(b.right());
+
+    if column_gap > 0 && first_x.right() <= second_x.x && top < bottom {
+        let w = (second_x.x - first_x.right()).min(column_gap);
+        return (w > 0).then_some(Rect { x: first_x.right(), y: top, w, h: bottom - top });
+    }
+    if row_gap > 0 && first_y.bottom() <= second_y.y && left < right {
+        let h = (second_y.y - first_y.bottom()).min(row_gap);
+        return (h > 0).then_some(Rect { x: left, y: first_y.bottom(), w: right - left, h });
+    }
+    None
+}
+
+/// Box-drawing outline around the border box.
+fn outline(frame: &mut FrameBuffer, rect: Rect, color: Rgba, clip: &ClipRect) {
+    let (left, top, right, bottom) = (rect.x, rect.y, rect.right() - 1, rect.bottom() - 1);
+    let edge = |x: i32, y: i32| -> char {
+        match (x == left, x == right, y == top, y == bottom) {
+            _ if top == bottom => '─',
+            _ if left == right => '│',
+            (true, _, true, _) => '┌',
+            (_, true, true, _) => '┐',
+            (true, _, _, true) => '└',
+            (_, true, _, true) => '┘',
+            (_, _, true, _) | (_, _, _, true) => '─',
+            _ => '│',
+        }
+    };
+    let cells = (left..=right)
+        .flat_map(|x| [(x, top), (x, bottom)])
+        .chain((top + 1..bottom).flat_map(|y| [(left, y), (right, y)]));
+    for (x, y) in cells {
+        let ch = edge(x, y);
+        mark(frame, x, y, clip, |cell| {
+            if is_blank(cell.char) {
+                cell.char = ch as u32;
+            }
+            cell.fg = color;
+        });
+    }
+}
+
+fn is_blank(ch: u32) -> bool {
+    ch == 0 || ch == ' ' as u32
+}
+
+/// Apply `paint` to the cell at (`x`, `y`) if it's inside `clip` and on
+/// screen. Hatches and outlines only replace blank cells.
+fn mark(frame: &mut FrameBuffer, x: i32, y: i32, clip: &ClipRect, paint: impl FnOnce(&mut Cell)) {
+    if !clip.contains_signed(x, y) || x < 0 || y < 0 {
+        return;
+    }
+    if let Some(cell) = frame.get_mut(x as u16, y as u16) {
+        let blank = is_blank(cell.char);
+        let mut marked = *cell;
+        paint(&mut marked);
+        // A hatch never covers content; an outline only recolors it
+        if blank || marked.char == cell.char {
+            *cell = marked;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, w: i32, h: i32) -> Rect {
+        Rect { x, y, w, h }
+    }
+
+    #[test]
+    fn test_gap_between_siblings() {
+        // Row: a | gap 2 | b
+        assert_eq!(gap_between(rect(0, 0, 4, 3), rect(6, 0, 4, 2), 2, 0), Some(rect(4, 0, 2, 2)));
+        // Reversed order finds the same strip
+        assert_eq!(gap_between(rect(6, 0, 4, 2), rect(0, 0, 4, 3), 2, 0), Some(rect(4, 0, 2, 2)));
+        // Column: a above b, justify spacing beyond the gap isn't marked
+        assert_eq!(gap_between(rect(0, 0, 5, 1), rect(0, 5, 5, 1), 0, 1), Some(rect(0, 1, 5, 1)));
+        // Touching, or no gap configured
+        assert_eq!(gap_between(rect(0, 0, 4, 1), rect(4, 0, 4, 1), 1, 1), None);
+        assert_eq!(gap_between(rect(0, 0, 4, 1), rect(6, 0, 4, 1), 0, 0), None);
+    }
+
+    #[test]
+    fn test_outline_keeps_content() {
+        let mut frame = FrameBuffer::new(4, 3);
+        frame.draw_text(1, 0, "x", Rgba::rgb(0, 0, 0), None, crate::utils::Attr::NONE, None);
+        let clip = ClipRect::new(0, 0, 4, 3);
+        outline(&mut frame, rect(0, 0, 4, 3), OUTLINE_COLORS[0], &clip);
+
+        assert_eq!(frame.get(0, 0).map(|c| c.char), Some('┌' as u32));
+        assert_eq!(frame.get(1, 0).map(|c| (c.char, c.fg)), Some(('x' as u32, OUTLINE_COLORS[0])));
+        assert_eq!(frame.get(3, 2).map(|c| c.char), Some('┘' as u32));
+        assert_eq!(frame.get(0, 1).map(|c| c.char), Some('│' as u32));
+        // Interior untouched
+        assert_eq!(frame.get(1, 1).map(|c| c.char), Some(' ' as u32));
+    }
+}
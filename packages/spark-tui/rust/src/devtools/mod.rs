@@ -0,0 +1,11 @@
+//! Developer tools — diagnostics layered over the normal pipeline.
+//!
+//! Nothing here runs unless TS switches it on through the SharedBuffer
+//! config header. Tools post-process the FrameBuffer inside `fb_derived`,
+//! so they stay part of the single reactive render path.
+
+pub mod colorblind;
+pub mod hud;
+pub mod inspector;
+pub mod layout_debug;
+pub mod screenshot;
@@ -0,0 +1,200 @@
+//! Screenshot keybinding — save the current frame for bug reports and docs.
+//!
+//! TS binds a key through the config header (`H_SCREENSHOT_KEY`). When it is
+//! pressed the engine, instead of dispatching the key:
+//!
+//! 1. writes `spark-screenshot-<ms>.txt` and `.html` to the working directory,
+//!    plus `.png` if `SCREENSHOT_IMAGE` or `SCREENSHOT_COPY_IMAGE` is set,
+//! 2. with `SCREENSHOT_COPY_IMAGE`, puts the PNG itself on the clipboard if
+//!    the terminal accepts images there (kitty's OSC 5522),
+//! 3. otherwise copies a path to the clipboard (OSC 52) if asked: the text
+//!    file with `SCREENSHOT_COPY_PATH`, the PNG with `SCREENSHOT_COPY_IMAGE`,
+//! 4. raises `H_SCREENSHOT_TOAST` and pushes a Screenshot event.
+//!
+//! The toast is drawn by `draw_toast` inside `fb_derived`. TS clears the flag
+//! after a moment and wakes the engine — no timer lives on the Rust side.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::renderer::passthrough::{self, Multiplexer};
+use crate::renderer::FrameBuffer;
+use crate::utils::{Attr, Rgba};
+
+/// File name prefix for saved screenshots.
+pub const FILE_PREFIX: &str = "spark-screenshot-";
+
+const TOAST_FG: Rgba = Rgba::rgb(20, 20, 20);
+const TOAST_BG: Rgba = Rgba::rgb(120, 200, 120);
+const TOAST_ERROR_BG: Rgba = Rgba::rgb(220, 110, 110);
+
+/// Raw bytes per OSC 5522 data chunk (4096 once base64-encoded).
+const IMAGE_CHUNK: usize = 3072;
+
+/// Result of one capture, kept for the toast.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Capture {
+    /// All files were written; `path` is the text file's, `image` whether a `.png` sits beside it.
+    Saved { path: PathBuf, image: bool },
+    /// Writing failed; holds the error message.
+    Failed(String),
+}
+
+impl Capture {
+    /// One-line summary shown in the toast.
+    pub fn message(&self) -> String {
+        match self {
+            Self::Saved { path, image } => {
+                let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+                let extra = if *image { ".html, .png" } else { ".html" };
+                format!(" ✓ screenshot saved: {} (+ {}) ", name, extra)
+            }
+            Self::Failed(err) => format!(" ✗ screenshot failed: {} ", err),
+        }
+    }
+}
+
+/// Write `frame` as `<dir>/spark-screenshot-<timestamp_ms>.txt` and `.html`,
+/// and `.png` when `image` is set.
+pub fn capture(frame: &FrameBuffer, dir: &Path, timestamp_ms: u64, image: bool) -> Capture {
+    let stem = dir.join(format!("{FILE_PREFIX}{timestamp_ms}"));
+    let text_path = stem.with_extension("txt");
+    let written = std::fs::write(&text_path, frame.to_text())
+        .and_then(|()| std::fs::write(stem.with_extension("html"), frame.to_html()))
+        .and_then(|()| if image { std::fs::write(stem.with_extension("png"), frame.to_png()) } else { Ok(()) });
+    match written {
+        Ok(()) => Capture::Saved { path: text_path, image },
+        Err(e) => Capture::Failed(e.to_string()),
+    }
+}
+
+/// Copy `text` to the system clipboard through the terminal (OSC 52).
+pub fn copy_to_clipboard<W: Write>(out: &mut W, text: &str) -> io::Result<()> {
+    let osc = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    out.write_all(passthrough::wrap(&osc).as_bytes())?;
+    out.flush()
+}
+
+/// Whether the terminal accepts images on the clipboard (kitty's OSC 5522),
+/// and a multiplexer in between lets them through.
+pub fn supports_image_clipboard() -> bool {
+    let kitty = std::env::var_os("KITTY_WINDOW_ID").is_some() || std::env::var("TERM").is_ok_and(|t| t == "xterm-kitty");
+    kitty && passthrough::multiplexer().graphics()
+}
+
+/// Put a PNG on the system clipboard through kitty's clipboard protocol
+/// (OSC 5522): a write request, the data in base64 chunks, then an empty
+/// chunk to finish.
+pub fn copy_image_to_clipboard<W: Write>(out: &mut W, png: &[u8]) -> io::Result<()> {
+    write_image_clipboard(out, png, passthrough::multiplexer())
+}
+
+fn write_image_clipboard<W: Write>(out: &mut W, png: &[u8], mux: Multiplexer) -> io::Result<()> {
+    let mime = base64(b"image/png");
+    let mut osc = |seq: String| out.write_all(mux.wrap(&seq).as_bytes());
+    osc("\x1b]5522;type=write\x07".to_string())?;
+    for chunk in png.chunks(IMAGE_CHUNK) {
+        osc(format!("\x1b]5522;type=wdata:mime={};{}\x07", mime, base64(chunk)))?;
+    }
+    osc("\x1b]5522;type=wdata\x07".to_string())?;
+    out.flush()
+}
+
+/// Draw the confirmation toast in the bottom-right corner.
+pub fn draw_toast(frame: &mut FrameBuffer, capture: &Capture) {
+    if frame.width() == 0 || frame.height() == 0 {
+        return;
+    }
+    let message = capture.message();
+    let bg = match capture {
+        Capture::Saved { .. } => TOAST_BG,
+        Capture::Failed(_) => TOAST_ERROR_BG,
+    };
+    let width = (crate::renderer::string_width(&message) as u16).min(frame.width());
+    let x = frame.width() - width;
+    let y = frame.height() - 1;
+    frame.fill_rect(x, y, width, 1, bg, None);
+    frame.draw_text(x, y, &message, TOAST_FG, Some(bg), Attr::BOLD, None);
+}
+
+/// Standard base64 with padding (OSC 52 / 5522 payload).
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"/tmp/a.txt"), "L3RtcC9hLnR4dA==");
+    }
+
+    #[test]
+    fn test_capture_writes_both_files() {
+        let dir = std::env::temp_dir();
+        let mut frame = FrameBuffer::new(6, 1);
+        frame.draw_text(0, 0, "hi", Rgba::RED, None, Attr::NONE, None);
+
+        let capture = capture(&frame, &dir, 42, false);
+        let Capture::Saved { path, image: false } = &capture else { panic!("capture failed: {capture:?}") };
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "hi\n");
+        let html = path.with_extension("html");
+        assert!(std::fs::read_to_string(&html).unwrap().contains("hi"));
+        assert!(capture.message().contains("spark-screenshot-42.txt"));
+
+        let _ = std::fs::remove_file(path);
+        let _ = std::fs::remove_file(html);
+    }
+
+    #[test]
+    fn test_capture_writes_png() {
+        let dir = std::env::temp_dir();
+        let frame = FrameBuffer::new(2, 1);
+
+        let capture = capture(&frame, &dir, 43, true);
+        let Capture::Saved { path, image: true } = &capture else { panic!("capture failed: {capture:?}") };
+        let png = path.with_extension("png");
+        assert!(std::fs::read(&png).unwrap().starts_with(b"\x89PNG"));
+        assert!(capture.message().contains(".png"));
+
+        for ext in ["txt", "html", "png"] {
+            let _ = std::fs::remove_file(path.with_extension(ext));
+        }
+    }
+
+    #[test]
+    fn test_copy_image_chunks() {
+        let mut out = Vec::new();
+        write_image_clipboard(&mut out, &vec![0u8; IMAGE_CHUNK + 1], Multiplexer::None).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("\x1b]5522;type=write\x07"));
+        assert_eq!(out.matches("mime=aW1hZ2UvcG5n;").count(), 2);
+        assert!(out.ends_with("\x1b]5522;type=wdata\x07"));
+    }
+
+    #[test]
+    fn test_toast_in_bottom_right() {
+        let mut frame = FrameBuffer::new(80, 3);
+        draw_toast(&mut frame, &Capture::Failed("denied".into()));
+        assert_eq!(frame.get(79, 2).unwrap().bg, TOAST_ERROR_BG);
+        assert_eq!(frame.get(0, 2).unwrap().bg, Rgba::TERMINAL_DEFAULT);
+    }
+}
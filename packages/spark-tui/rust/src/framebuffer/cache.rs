@@ -0,0 +1,257 @@
+//! Render cache for static subtrees (`cache: true` on a box).
+//!
+//! A cached box keeps the cells its subtree painted last frame. Next frame,
+//! if nothing the subtree's rendering depends on has changed, those cells are
+//! blitted back in place of walking it again:
+//!
+//! ```text
+//! render_component(cached box)
+//!   │ snapshot key: placement + header state + ancestor records
+//!   │               + every visible descendant's record and text
+//!   │ + the cells already under its region (what it paints over)
+//!   ▼
+//! same as last frame? ──yes──→ blit cells, replay hit regions
+//!   │ no
+//!   ▼
+//! render the subtree, store cells + hit regions
+//! ```
+//!
+//! Invalidation compares raw node records rather than dirty flags: TS writes
+//! most props straight into the buffer without raising any, and Rust-side
+//! state (scroll, cursor, focus) lands in the records too. The key hashes
+//! every record it depends on (1 KB per node, the box's descendants and
+//! ancestors) as it reads them; only the hash is kept. That is far cheaper
+//! than re-wrapping and re-painting the subtree.
+//!
+//! Cell link ids index the frame's link table, which is rebuilt every frame,
+//! so snapshots keep the link targets alongside the cells and re-intern them
+//! when blitting.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hasher};
+
+use crate::renderer::{FrameBuffer, TerminalCursor};
+use crate::shared_buffer::SharedBuffer;
+use crate::utils::{Cell, ClipRect};
+use super::HitRegion;
+#[cfg(feature = "parallel")]
+use super::parallel::Band;
+
+/// What a cached box painted, and everything it was painted from.
+struct Entry {
+    key: u64,
+    underlay: Snapshot,
+    region: (u16, u16, u16, u16),
+    cells: Snapshot,
+    hit_regions: Vec<HitRegion>,
+    /// Native cursor of a focused input inside it
+    cursor: Option<TerminalCursor>,
+    /// Visited this frame; unvisited entries are dropped at the end of it.
+    seen: bool,
+}
+
+/// Snapshots of cached boxes by component index. Lives across frames next
+/// to the framebuffer derived.
+#[derive(Default)]
+pub struct RenderCache {
+    entries: HashMap<usize, Entry>,
+    /// One cache (and buffer) per band when frames are rasterized in bands:
+    /// a box's snapshot is keyed by the clip it was painted under.
+    #[cfg(feature = "parallel")]
+    bands: Vec<Band>,
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored snapshots, band caches included.
+    pub fn len(&self) -> usize {
+        let len = self.entries.len();
+        #[cfg(feature = "parallel")]
+        let len = len + self.bands.iter().map(|band| band.cache.len()).sum::<usize>();
+        len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `count` bands, keeping what the previous frame's bands stored.
+    #[cfg(feature = "parallel")]
+    pub(super) fn bands(&mut self, count: usize) -> &mut [Band] {
+        self.bands.resize_with(count, Band::default);
+        &mut self.bands
+    }
+
+    pub(super) fn begin_frame(&mut self) {
+        for entry in self.entries.values_mut() {
+            entry.seen = false;
+        }
+    }
+
+    /// Forget boxes that weren't rendered this frame (hidden, clipped out,
+    /// cache turned off or released).
+    pub(super) fn end_frame(&mut self) {
+        self.entries.retain(|_, entry| entry.seen);
+    }
+
+    /// Blit `index`'s snapshot if it is still valid. Returns whether it was.
+    pub(super) fn replay(
+        &mut self,
+        index: usize,
+        key: u64,
+        region: (u16, u16, u16, u16),
+        buffer: &mut FrameBuffer,
+        hit_regions: &mut Vec<HitRegion>,
+    ) -> bool {
+        let Some(entry) = self.entries.get_mut(&index) else {
+            return false;
+        };
+        entry.seen = true;
+        if entry.region != region || entry.key != key || entry.underlay != Snapshot::read(buffer, region) {
+            return false;
+        }
+        entry.cells.write(buffer, region);
+        hit_regions.extend_from_slice(&entry.hit_regions);
+        if entry.cursor.is_some() {
+            buffer.set_cursor(entry.cursor);
+        }
+        true
+    }
+
+    /// Remember what `index` painted since `repaint` began.
+    pub(super) fn store(&mut self, index: usize, repaint: Repaint, buffer: &FrameBuffer, hit_regions: &[HitRegion]) {
+        let Repaint { key, region, underlay, hits_from, cursor_before } = repaint;
+        // A native cursor the subtree placed replays with its cells
+        let cursor = buffer.cursor().filter(|_| cursor_before.is_none());
+        self.entries.insert(index, Entry {
+            key,
+            cells: Snapshot::read(buffer, region),
+            underlay,
+            region,
+            hit_regions: hit_regions[hits_from..].to_vec(),
+            cursor,
+            seen: true,
+        });
+    }
+}
+
+/// A cached box being painted afresh: what it paints over, and where its
+/// hit regions start.
+pub(super) struct Repaint {
+    key: u64,
+    region: (u16, u16, u16, u16),
+    underlay: Snapshot,
+    hits_from: usize,
+    cursor_before: Option<TerminalCursor>,
+}
+
+impl Repaint {
+    pub(super) fn begin(key: u64, region: (u16, u16, u16, u16), buffer: &FrameBuffer, hit_regions: &[HitRegion]) -> Self {
+        Self {
+            key,
+            region,
+            underlay: Snapshot::read(buffer, region),
+            hits_from: hit_regions.len(),
+            cursor_before: buffer.cursor(),
+        }
+    }
+}
+
+/// Hash of everything outside the framebuffer that `index`'s subtree
+/// renders from.
+pub(super) fn snapshot_key(
+    buf: &SharedBuffer,
+    index: usize,
+    child_map: &[Vec<usize>],
+    parent_clip: &ClipRect,
+    parent_screen_x: i32,
+    parent_screen_y: i32,
+) -> u64 {
+    let mut key = DefaultHasher::new();
+    for value in [parent_clip.x, parent_clip.y, parent_screen_x, parent_screen_y] {
+        key.write_i32(value);
+    }
+    key.write_u16(parent_clip.width);
+    key.write_u16(parent_clip.height);
+    key.write(&buf.focused_index().to_le_bytes());
+    key.write_u8(buf.unicode_level() as u8);
+    key.write_u8(buf.control_chars() as u8);
+
+    // Inherited colors, opacity and scroll come from the ancestors
+    let mut ancestor = buf.parent_index(index);
+    while let Some(parent) = ancestor {
+        key.write(buf.node_record(parent));
+        write_text(&mut key, buf.href(parent));
+        ancestor = buf.parent_index(parent);
+    }
+
+    let mut stack = vec![index];
+    while let Some(node) = stack.pop() {
+        key.write_u32(node as u32);
+        key.write(buf.node_record(node));
+        for text in [buf.text(node), buf.ghost_text(node), buf.preedit(node), buf.href(node)] {
+            write_text(&mut key, text);
+        }
+        if let Some(children) = child_map.get(node) {
+            key.write_u32(children.len() as u32);
+            stack.extend(children.iter().rev());
+        }
+    }
+    key.finish()
+}
+
+/// Length-prefixed, so adjacent texts can't trade bytes
+fn write_text(key: &mut DefaultHasher, text: &str) {
+    key.write_u32(text.len() as u32);
+    key.write(text.as_bytes());
+}
+
+/// A region's cells, with link ids numbered within the snapshot.
+#[derive(Debug, PartialEq)]
+pub(super) struct Snapshot {
+    cells: Vec<Cell>,
+    /// Targets of the snapshot's link ids (id `n` is `links[n - 1]`).
+    links: Vec<String>,
+}
+
+impl Snapshot {
+    pub(super) fn read(buffer: &FrameBuffer, (x, y, w, h): (u16, u16, u16, u16)) -> Self {
+        let mut cells = Vec::with_capacity(w as usize * h as usize);
+        let mut links: Vec<String> = Vec::new();
+        for row in y..y + h {
+            for col in x..x + w {
+                let mut cell = buffer.get(col, row).copied().unwrap_or_default();
+                if let Some(url) = buffer.link_url(cell.link) {
+                    let local = match links.iter().position(|link| link == url) {
+                        Some(pos) => pos,
+                        None => {
+                            links.push(url.to_string());
+                            links.len() - 1
+                        }
+                    };
+                    cell.link = local as u16 + 1;
+                }
+                cells.push(cell);
+            }
+        }
+        Self { cells, links }
+    }
+
+    fn write(&self, buffer: &mut FrameBuffer, (x, y, w, _): (u16, u16, u16, u16)) {
+        if w == 0 {
+            return;
+        }
+        let ids: Vec<u16> = self.links.iter().map(|url| buffer.intern_link(url)).collect();
+        for (row, line) in self.cells.chunks(w as usize).enumerate() {
+            for (col, cell) in line.iter().enumerate() {
+                if let Some(target) = buffer.get_mut(x + col as u16, y + row as u16) {
+                    let link = (cell.link as usize).checked_sub(1).map_or(0, |i| ids[i]);
+                    *target = Cell { link, ..*cell };
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Background fills — gradients and patterns.
+//!
+//! Solid backgrounds go straight through `FrameBuffer::fill_rect`. The other
+//! `BackgroundFill` kinds pick a color per cell between the node's two
+//! background colors. Positions are measured from the component's own
+//! top-left corner, not from its visible part, so a scrolled or clipped box
+//! shows the same slice of its fill it would show unclipped.
+
+use crate::renderer::FrameBuffer;
+use crate::shared_buffer::BackgroundFill;
+use crate::utils::{ClipRect, Rgba};
+
+/// Terminal cells are roughly twice as tall as they are wide. Gradient
+/// projection stretches y by this so a 45° gradient looks like 45°.
+const CELL_ASPECT: f32 = 2.0;
+
+/// Paint a non-solid background over `bounds` (the full component rect),
+/// limited to `clip`.
+#[allow(clippy::too_many_arguments)]
+pub fn paint_background(
+    buffer: &mut FrameBuffer,
+    fill: BackgroundFill,
+    from: Rgba,
+    to: Rgba,
+    angle: u16,
+    size: u16,
+    bounds: &ClipRect,
+    clip: &ClipRect,
+) {
+    let Some((vis_x, vis_y, vis_w, vis_h)) = clip.visible_on_screen() else {
+        return;
+    };
+    let gradient = Gradient::new(angle, bounds.width, bounds.height);
+
+    for y in vis_y..vis_y.saturating_add(vis_h) {
+        for x in vis_x..vis_x.saturating_add(vis_w) {
+            let lx = (x as i32 - bounds.x).max(0) as u16;
+            let ly = (y as i32 - bounds.y).max(0) as u16;
+            let color = match fill {
+                BackgroundFill::Solid => from,
+                BackgroundFill::LinearGradient => Rgba::lerp(from, to, gradient.at(lx, ly)),
+                _ => {
+                    if pattern_odd(fill, lx, ly, size) { to } else { from }
+                }
+            };
+            buffer.fill_rect(x, y, 1, 1, color, Some(clip));
+        }
+    }
+}
+
+/// Whether a cell falls in the second color of a pattern.
+fn pattern_odd(fill: BackgroundFill, lx: u16, ly: u16, size: u16) -> bool {
+    let size = size.max(1);
+    let (cx, cy) = (lx / size, ly / size);
+    match fill {
+        BackgroundFill::Checker => (cx + cy) % 2 == 1,
+        BackgroundFill::StripesHorizontal => cy % 2 == 1,
+        BackgroundFill::StripesVertical => cx % 2 == 1,
+        BackgroundFill::StripesDiagonal => ((lx + ly) / size) % 2 == 1,
+        BackgroundFill::Solid | BackgroundFill::LinearGradient => false,
+    }
+}
+
+/// Linear gradient projection over a component rect.
+struct Gradient {
+    dx: f32,
+    dy: f32,
+    min: f32,
+    span: f32,
+}
+
+impl Gradient {
+    /// `angle` in degrees: 0 = left→right, 90 = top→bottom.
+    fn new(angle: u16, width: u16, height: u16) -> Self {
+        let rad = (angle % 360) as f32 * std::f32::consts::PI / 180.0;
+        let (dx, dy) = (rad.cos(), rad.sin());
+        let (w, h) = (width as f32, height as f32 * CELL_ASPECT);
+
+        // Project the corners to find where t = 0 and t = 1 land
+        let corners = [0.0, w * dx, h * dy, w * dx + h * dy];
+        let min = corners.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = corners.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+        Self { dx, dy, min, span: (max - min).max(f32::EPSILON) }
+    }
+
+    /// Gradient position (0.0-1.0) at a cell's center.
+    fn at(&self, lx: u16, ly: u16) -> f32 {
+        let px = lx as f32 + 0.5;
+        let py = (ly as f32 + 0.5) * CELL_ASPECT;
+        ((px * self.dx + py * self.dy - self.min) / self.span).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Packed pure white doubles as the terminal-default marker
+    const LIGHT: Rgba = Rgba::rgb(240, 240, 240);
+
+    #[test]
+    fn test_horizontal_gradient_runs_left_to_right() {
+        let mut frame = FrameBuffer::new(10, 1);
+        let rect = ClipRect::new(0, 0, 10, 1);
+        paint_background(&mut frame, BackgroundFill::LinearGradient, Rgba::BLACK, LIGHT, 0, 1, &rect, &rect);
+
+        let first = frame.get(0, 0).unwrap().bg;
+        let last = frame.get(9, 0).unwrap().bg;
+        assert!(first.r < 20 && last.r > 220);
+        assert!(frame.get(4, 0).unwrap().bg.r < frame.get(5, 0).unwrap().bg.r);
+    }
+
+    #[test]
+    fn test_gradient_anchored_to_component_not_clip() {
+        // Component extends 5 cells off-screen to the left
+        let mut frame = FrameBuffer::new(5, 1);
+        let bounds = ClipRect::new(-5, 0, 10, 1);
+        let clip = ClipRect::new(0, 0, 5, 1);
+        paint_background(&mut frame, BackgroundFill::LinearGradient, Rgba::BLACK, LIGHT, 0, 1, &bounds, &clip);
+
+        // Left edge of the screen is the middle of the gradient
+        let mid = frame.get(0, 0).unwrap().bg;
+        assert!(mid.r > 100 && mid.r < 150);
+    }
+
+    #[test]
+    fn test_patterns() {
+        let a = Rgba::RED;
+        let b = Rgba::BLUE;
+        let rect = ClipRect::new(0, 0, 4, 4);
+
+        let mut frame = FrameBuffer::new(4, 4);
+        paint_background(&mut frame, BackgroundFill::Checker, a, b, 0, 2, &rect, &rect);
+        assert_eq!(frame.get(1, 1).unwrap().bg, a);
+        assert_eq!(frame.get(2, 0).unwrap().bg, b);
+        assert_eq!(frame.get(3, 3).unwrap().bg, a);
+
+        let mut frame = FrameBuffer::new(4, 4);
+        paint_background(&mut frame, BackgroundFill::StripesHorizontal, a, b, 0, 1, &rect, &rect);
+        assert_eq!(frame.get(3, 0).unwrap().bg, a);
+        assert_eq!(frame.get(0, 1).unwrap().bg, b);
+
+        let mut frame = FrameBuffer::new(4, 4);
+        paint_background(&mut frame, BackgroundFill::StripesVertical, a, b, 0, 1, &rect, &rect);
+        assert_eq!(frame.get(0, 3).unwrap().bg, a);
+        assert_eq!(frame.get(1, 0).unwrap().bg, b);
+    }
+}
@@ -3,7 +3,7 @@
 //! Components inherit fg/bg colors from ancestors. Opacity cascades
 //! (multiplies) down the tree.
 
-use crate::shared_buffer::SharedBuffer;
+use crate::shared_buffer::{BackgroundFill, PointerEvents, SharedBuffer};
 use crate::utils::Rgba;
 
 /// Get effective foreground color, walking up the parent chain.
@@ -34,6 +34,20 @@ pub fn get_inherited_bg(buf: &SharedBuffer, node: usize) -> Rgba {
     Rgba::TERMINAL_DEFAULT
 }
 
+/// Whether the node has no background of its own and sits inside an
+/// ancestor that paints a gradient or pattern. Such nodes must not repaint
+/// the inherited (start) color over the ancestor's fill.
+pub fn inherits_patterned_bg(buf: &SharedBuffer, node: usize) -> bool {
+    let mut current = Some(node);
+    while let Some(idx) = current {
+        if !Rgba::from_u32(buf.bg_color(idx)).is_terminal_default() {
+            return idx != node && buf.bg_fill(idx) != BackgroundFill::Solid;
+        }
+        current = buf.parent_index(idx);
+    }
+    false
+}
+
 /// Get effective opacity, multiplying up the parent chain.
 /// Opacity is stored as f32 (0.0-1.0) in SharedBuffer.
 pub fn get_effective_opacity(buf: &SharedBuffer, node: usize) -> f32 {
@@ -64,6 +78,19 @@ pub fn apply_opacity(color: Rgba, opacity: f32) -> Rgba {
     )
 }
 
+/// Whether the mouse can hit the node: its own `pointer_events`, or the
+/// nearest ancestor's that sets one (Auto if none does).
+pub fn takes_pointer_events(buf: &SharedBuffer, node: usize) -> bool {
+    let mut current = Some(node);
+    while let Some(idx) = current {
+        match buf.pointer_events(idx) {
+            PointerEvents::Inherit => current = buf.parent_index(idx),
+            setting => return setting != PointerEvents::None,
+        }
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,3 +115,4 @@ mod tests {
         assert_eq!(result, Rgba::TERMINAL_DEFAULT);
     }
 }
+
@@ -21,8 +21,13 @@
 
 mod render_tree;
 mod inheritance;
+mod fill;
+mod cache;
+#[cfg(feature = "parallel")]
+mod parallel;
 
-pub use render_tree::{compute_framebuffer, HitRegion};
+pub use render_tree::{compute_framebuffer, compute_framebuffer_cached, region_bands, HitRegion};
+pub use cache::RenderCache;
 
 // Re-export FrameBuffer from renderer for convenience
 pub use crate::renderer::FrameBuffer;
@@ -0,0 +1,256 @@
+//! Band-parallel rasterization (`parallel` feature).
+//!
+//! A large frame is cut into horizontal bands of rows. Each band walks the
+//! whole tree on a rayon worker, clipped to its rows, into a buffer of its
+//! own; the bands' rows are then copied into one frame:
+//!
+//! ```text
+//!          ┌─ band 0: rows  0..15 ─┐
+//! roots ───┼─ band 1: rows 15..30 ─┼──→ copy rows, re-intern links,
+//!          └─ band 2: rows 30..45 ─┘    stitch hit regions, paint order
+//! ```
+//!
+//! Every paint is clipped per cell, so a band's rows come out exactly as the
+//! serial pass paints them, and the merged frame doesn't depend on how many
+//! bands there were:
+//!
+//! - Hit regions cut at a band edge are stitched back into one, then put
+//!   back in paint order
+//! - The native cursor comes from the band it falls in
+//! - Each band keeps its own render cache: cached boxes are keyed by the
+//!   clip they were painted under. The whole-frame cache is left alone, so
+//!   its boxes are still cached when frames render in one piece again
+//! - Each band keeps its buffer across frames and clears only its own rows:
+//!   paints are clipped to them, so the other rows stay blank
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::renderer::FrameBuffer;
+use crate::shared_buffer::SharedBuffer;
+use crate::utils::ClipRect;
+use super::cache::RenderCache;
+use super::render_tree::{children_in_paint_order, render_component};
+use super::HitRegion;
+
+/// Painted nodes below which a frame renders in one piece: every band walks
+/// the tree, which small trees don't repay
+const MIN_NODES: usize = 512;
+
+/// Fewest rows a band takes
+const MIN_BAND_ROWS: u16 = 8;
+
+/// One band's render cache and the buffer it paints into, kept across frames.
+pub(super) struct Band {
+    pub(super) cache: RenderCache,
+    buffer: FrameBuffer,
+    /// Rows the buffer was last painted in: (y, height)
+    rows: (u16, u16),
+}
+
+impl Default for Band {
+    fn default() -> Self {
+        Self { cache: RenderCache::new(), buffer: FrameBuffer::new(0, 0), rows: (0, 0) }
+    }
+}
+
+/// Bands to rasterize a frame `height` rows tall in, None to render it in
+/// one piece.
+pub(super) fn band_count(height: u16, roots: &[usize], child_map: &[Vec<usize>]) -> Option<u16> {
+    let nodes = roots.len() + child_map.iter().map(Vec::len).sum::<usize>();
+    if nodes < MIN_NODES {
+        return None;
+    }
+    let bands = rayon::current_num_threads().min((height / MIN_BAND_ROWS) as usize) as u16;
+    (bands > 1).then_some(bands)
+}
+
+/// Render `roots` in `bands` bands of rows in parallel and merge them.
+pub(super) fn rasterize(
+    buf: &SharedBuffer,
+    width: u16,
+    height: u16,
+    roots: &[usize],
+    child_map: &[Vec<usize>],
+    cache: &mut RenderCache,
+    bands: u16,
+) -> (FrameBuffer, Vec<HitRegion>) {
+    let rows = height.div_ceil(bands.max(1));
+    let painted: Vec<Vec<HitRegion>> = cache
+        .bands(bands as usize)
+        .par_iter_mut()
+        .enumerate()
+        .map(|(index, band)| {
+            let y = (index as u16).saturating_mul(rows).min(height);
+            let band_rows = rows.min(height - y);
+            if (band.buffer.width(), band.buffer.height()) != (width, height) || band.rows != (y, band_rows) {
+                // Other rows than last frame: clear them all
+                band.buffer.resize(width, height);
+                band.rows = (y, band_rows);
+            } else {
+                band.buffer.clear_rows(y, band_rows);
+            }
+
+            let clip = ClipRect::new(0, y as i32, width, band_rows);
+            let mut hit_regions = Vec::new();
+            band.cache.begin_frame();
+            for &root in roots {
+                render_component(&mut band.buffer, buf, root, child_map, &mut hit_regions, &mut band.cache, &clip, 0, 0);
+            }
+            band.cache.end_frame();
+            hit_regions
+        })
+        .collect();
+
+    let mut frame = FrameBuffer::new(width, height);
+    let mut hit_regions: Vec<HitRegion> = Vec::new();
+    // Regions reaching the bottom of the previous band, by what would
+    // continue them into the next
+    let mut open: HashMap<(usize, u16, u16, bool), usize> = HashMap::new();
+    for (band, regions) in cache.bands(bands as usize).iter().zip(painted) {
+        let (y, band_rows) = band.rows;
+        frame.copy_rows(&band.buffer, y, band_rows);
+        if band.buffer.cursor().is_some() {
+            frame.set_cursor(band.buffer.cursor());
+        }
+
+        let mut reaching = HashMap::new();
+        for region in regions {
+            let key = (region.component_index, region.x, region.width, region.opaque);
+            let reaches_bottom = region.y + region.height == y + band_rows;
+            let at = match open.remove(&key) {
+                Some(at) if region.y == y => {
+                    hit_regions[at].height += region.height;
+                    at
+                }
+                _ => {
+                    hit_regions.push(region);
+                    hit_regions.len() - 1
+                }
+            };
+            if reaches_bottom {
+                reaching.insert(key, at);
+            }
+        }
+        open = reaching;
+    }
+
+    // Bands list their regions in paint order, each node's first; restore
+    // that order across bands (stable, so a node's own stay first)
+    let ranks = paint_ranks(buf, roots, child_map);
+    hit_regions.sort_by_key(|region| ranks[region.component_index]);
+
+    (frame, hit_regions)
+}
+
+/// Each node's position in the paint traversal, which is the order nodes
+/// push their hit regions in.
+fn paint_ranks(buf: &SharedBuffer, roots: &[usize], child_map: &[Vec<usize>]) -> Vec<u32> {
+    let mut ranks = vec![0; child_map.len()];
+    let mut next = 0;
+    let mut stack: Vec<usize> = roots.iter().rev().copied().collect();
+    while let Some(index) = stack.pop() {
+        ranks[index] = next;
+        next += 1;
+        let children: Vec<usize> = children_in_paint_order(buf, index, &child_map[index]).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::compute_framebuffer_cached;
+    use crate::shared_buffer::test_support::TestBuffer;
+    use crate::shared_buffer::{
+        COMPONENT_BOX, COMPONENT_TEXT, N_BG_COLOR, N_BORDER_STYLE, N_BORDER_WIDTH_BOTTOM, N_BORDER_WIDTH_LEFT,
+        N_BORDER_WIDTH_RIGHT, N_BORDER_WIDTH_TOP, N_CREATION_SEQ, N_RENDER_CACHE,
+    };
+    use crate::utils::Cell;
+
+    /// Cells with their link targets, which keep across link tables
+    fn cells(frame: &FrameBuffer) -> Vec<(Cell, Option<String>)> {
+        frame.cells().iter().map(|cell| (Cell { link: 0, ..*cell }, frame.link_url(cell.link).map(str::to_string))).collect()
+    }
+
+    #[test]
+    fn test_bands_merge_into_the_serial_frame() {
+        const RED: u32 = 0xFFFF0000;
+        const BLUE: u32 = 0xFF0000FF;
+        let (width, height) = (12, 24);
+        let nodes = 6;
+        let buf = TestBuffer::new(nodes, 256);
+        // Root [ linked red column | framed text | cached blue box [ red ] ]
+        let kinds = [
+            (0, -1, COMPONENT_BOX, 0),
+            (1, 0, COMPONENT_BOX, RED),
+            (2, 0, COMPONENT_BOX, 0),
+            (3, 2, COMPONENT_TEXT, 0),
+            (4, 0, COMPONENT_BOX, BLUE),
+            (5, 4, COMPONENT_BOX, RED),
+        ];
+        for (i, parent, comp, bg) in kinds {
+            buf.node(i, comp, parent);
+            buf.put_u32(i, N_CREATION_SEQ, i as u32 + 1);
+            buf.put_u32(i, N_BG_COLOR, bg);
+        }
+        // Transparent bordered box (index 2): its border sides are hit
+        // regions of their own, cut by every band
+        buf.put_u8(2, N_BORDER_STYLE, 1);
+        for side in [N_BORDER_WIDTH_TOP, N_BORDER_WIDTH_RIGHT, N_BORDER_WIDTH_BOTTOM, N_BORDER_WIDTH_LEFT] {
+            buf.put_u8(2, side, 1);
+        }
+        // Cached box (index 4) across the bottom two bands
+        buf.put_u8(4, N_RENDER_CACHE, 1);
+        let layout = [
+            (0, 0.0, 0.0, 12.0, 24.0),
+            (1, 0.0, 2.0, 4.0, 18.0),
+            (2, 4.0, 5.0, 8.0, 13.0),
+            (3, 1.0, 1.0, 6.0, 11.0),
+            (4, 0.0, 10.0, 12.0, 12.0),
+            (5, 2.0, 3.0, 3.0, 6.0),
+        ];
+        for (i, x, y, w, h) in layout {
+            buf.set_computed_x(i, x);
+            buf.set_computed_y(i, y);
+            buf.set_computed_width(i, w);
+            buf.set_computed_height(i, h);
+        }
+        assert!(buf.set_text(3, "alpha beta gamma delta epsilon zeta eta theta"));
+        assert!(buf.set_href(1, "https://a.example"));
+
+        // Small tree: the whole-frame cache stores the cached box
+        let mut cache = RenderCache::new();
+        let (serial, serial_hits) = compute_framebuffer_cached(&buf, width, height, &mut cache);
+        assert_eq!(cache.len(), 1);
+        let mut child_map = vec![Vec::new(); nodes];
+        for (i, parent, ..) in kinds.into_iter().skip(1) {
+            child_map[parent as usize].push(i);
+        }
+
+        for bands in [2, 3, 3, 5] {
+            // Twice at 3 bands: painted, then replayed from the band caches
+            let (frame, hits) = rasterize(&buf, width, height, &[0], &child_map, &mut cache, bands);
+            assert_eq!(cells(&frame), cells(&serial), "{bands} bands");
+            assert_eq!(frame.cursor(), serial.cursor());
+
+            // Same regions, in the same node order
+            let sorted = |hits: &[HitRegion]| {
+                let mut hits = hits.to_vec();
+                hits.sort_by_key(|r| (r.component_index, r.y, r.x, r.width, r.height, r.opaque));
+                hits
+            };
+            assert_eq!(sorted(&hits), sorted(&serial_hits), "{bands} bands");
+            let order = |hits: &[HitRegion]| {
+                let mut order: Vec<usize> = hits.iter().map(|r| r.component_index).collect();
+                order.dedup();
+                order
+            };
+            assert_eq!(order(&hits), order(&serial_hits));
+            // Whole-frame snapshot kept, plus one per band the box crosses
+            assert!(cache.len() >= 3, "{bands} bands");
+        }
+    }
+}
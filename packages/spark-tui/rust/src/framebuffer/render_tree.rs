@@ -15,17 +15,36 @@
 //! Positions can be negative (scrolled out of view). We use i32 throughout
 //! and only clamp to screen coordinates at final render time.
 //!
+//! A `sticky` child of a scroll container is then held inside the parent's
+//! content box on its pinned edge, and paints after its in-flow siblings.
+//!
 //! # Traversal Order
 //!
 //! 1. Build child map from hierarchy section
-//! 2. Sort children by z-index
-//! 3. DFS traversal: background → border → content → children → focus indicator
-
-use crate::renderer::FrameBuffer;
-use crate::shared_buffer::{SharedBuffer, BorderStyle, COMPONENT_BOX, COMPONENT_TEXT, COMPONENT_INPUT};
-use crate::utils::{Attr, ClipRect, Rgba};
-use crate::layout::{string_width, truncate_text, wrap_text_word};
-use super::inheritance::{get_inherited_fg, get_inherited_bg, get_effective_opacity, apply_opacity};
+//! 2. Sort children into paint order (z-index, then tree order, then creation)
+//! 3. DFS traversal: background (solid, gradient or pattern) → border → content → children → focus indicator
+//!
+//! Boxes with `cache` set are blitted from the render cache instead of
+//! traversed while their subtree is unchanged (see `cache`).
+//!
+//! With the `parallel` feature, large frames are rasterized in bands of rows
+//! across threads and merged (see `parallel`).
+
+use std::borrow::Cow;
+
+use crate::renderer::ansi::CursorShape;
+use crate::renderer::{FrameBuffer, RegionBand, TerminalCursor};
+use crate::shared_buffer::{SharedBuffer, BackgroundFill, PointerEvents, BorderStyle, ConfigFlags, CursorStyle, Sticky, TextAlign, TextDirection, TextWrap, UnicodeLevel, COMPONENT_BOX, COMPONENT_TEXT, COMPONENT_INPUT, COMPONENT_SELECT, COMPONENT_PROGRESS};
+use crate::utils::{control_picture, Attr, ClipRect, Rgba};
+use crate::layout::{
+    char_width, input_pictures, justify_line, paragraph_is_rtl, reorder_line, string_width, truncate_text,
+    visualize_controls, wrap_text_hyphenated, wrap_text_word,
+};
+use super::cache::{self, RenderCache};
+use super::fill;
+use super::inheritance::{
+    get_inherited_fg, get_inherited_bg, get_effective_opacity, apply_opacity, inherits_patterned_bg, takes_pointer_events,
+};
 
 // =============================================================================
 // Types
@@ -39,6 +58,9 @@ pub struct HitRegion {
     pub width: u16,
     pub height: u16,
     pub component_index: usize,
+    /// Takes every cell. Otherwise only cells nothing but its ancestors
+    /// was drawn on (see `HitGrid::fill_region`).
+    pub opaque: bool,
 }
 
 // Component types (from SharedBuffer constants)
@@ -46,8 +68,8 @@ const COMP_NONE: u8 = 0;
 const COMP_BOX: u8 = COMPONENT_BOX;
 const COMP_TEXT: u8 = COMPONENT_TEXT;
 const COMP_INPUT: u8 = COMPONENT_INPUT;
-const COMP_SELECT: u8 = 4;
-const COMP_PROGRESS: u8 = 5;
+const COMP_SELECT: u8 = COMPONENT_SELECT;
+const COMP_PROGRESS: u8 = COMPONENT_PROGRESS;
 
 // =============================================================================
 // Entry Point
@@ -61,12 +83,55 @@ pub fn compute_framebuffer(
     buf: &SharedBuffer,
     width: u16,
     height: u16,
+) -> (FrameBuffer, Vec<HitRegion>) {
+    compute_framebuffer_cached(buf, width, height, &mut RenderCache::new())
+}
+
+/// Rows each region takes in an inline or append frame, top to bottom.
+///
+/// Layout stacks the regions (see `N_REGION`); a region's band runs from
+/// the bottom of the one above to the bottom of its tallest root. Empty
+/// when nothing is visible.
+pub fn region_bands(buf: &SharedBuffer) -> Vec<RegionBand> {
+    let mut bands: Vec<RegionBand> = Vec::new();
+    let mut roots: Vec<usize> = (0..buf.node_count())
+        .filter(|&i| buf.component_type(i) != COMP_NONE && buf.visible(i) && buf.parent_index(i).is_none())
+        .collect();
+    roots.sort_by_key(|&i| buf.region(i));
+
+    for root in roots {
+        let region = buf.region(root);
+        let bottom = (buf.computed_y(root) + buf.computed_height(root)).max(0.0) as u16;
+        let width = (buf.computed_x(root) + buf.computed_width(root)).max(0.0) as u16;
+        match bands.last_mut() {
+            Some(band) if band.region == region => {
+                band.height = band.height.max(bottom.saturating_sub(band.y));
+                band.width = band.width.max(width);
+            }
+            last => {
+                let y = last.map_or(0, |band| band.y + band.height);
+                bands.push(RegionBand { region, y, height: bottom.saturating_sub(y), width });
+            }
+        }
+    }
+    bands
+}
+
+/// `compute_framebuffer`, reusing and refreshing the snapshots of cached
+/// boxes in `cache` (kept from frame to frame by the caller).
+pub fn compute_framebuffer_cached(
+    buf: &SharedBuffer,
+    width: u16,
+    height: u16,
+    cache: &mut RenderCache,
 ) -> (FrameBuffer, Vec<HitRegion>) {
     let mut buffer = FrameBuffer::new(width, height);
     let mut hit_regions = Vec::new();
 
     let node_count = buf.node_count();
     if node_count == 0 {
+        cache.begin_frame();
+        cache.end_frame();
         return (buffer, hit_regions);
     }
 
@@ -90,11 +155,20 @@ pub fn compute_framebuffer(
         }
     }
 
-    // Sort roots and children by z-index
-    roots.sort_by_key(|&idx| buf.z_index(idx));
+    // Sort roots and children into paint order: z-index, then tree order,
+    // then creation — never by index, which recycling reshuffles
+    roots.sort_by_key(|&idx| buf.paint_order(idx));
     for children in child_map.iter_mut() {
         if children.len() > 1 {
-            children.sort_by_key(|&idx| buf.z_index(idx));
+            children.sort_by_key(|&idx| buf.paint_order(idx));
+        }
+    }
+
+    // Large frames rasterize in bands, in parallel
+    #[cfg(feature = "parallel")]
+    {
+        if let Some(bands) = super::parallel::band_count(height, &roots, &child_map) {
+            return super::parallel::rasterize(buf, width, height, &roots, &child_map, cache, bands);
         }
     }
 
@@ -102,6 +176,7 @@ pub fn compute_framebuffer(
     let screen_clip = ClipRect::new(0, 0, width, height);
 
     // Render each root and its subtree
+    cache.begin_frame();
     for root_idx in &roots {
         render_component(
             &mut buffer,
@@ -109,10 +184,12 @@ pub fn compute_framebuffer(
             *root_idx,
             &child_map,
             &mut hit_regions,
+            cache,
             &screen_clip,
             0, 0,  // parent screen position
         );
     }
+    cache.end_frame();
 
     (buffer, hit_regions)
 }
@@ -127,12 +204,13 @@ pub fn compute_framebuffer(
 /// - `parent_clip`: The clipping rectangle from the parent
 /// - `parent_screen_x/y`: Parent's absolute screen position (i32, can be negative)
 #[allow(clippy::too_many_arguments)]
-fn render_component(
+pub(super) fn render_component(
     buffer: &mut FrameBuffer,
     buf: &SharedBuffer,
     index: usize,
     child_map: &[Vec<usize>],
     hit_regions: &mut Vec<HitRegion>,
+    cache: &mut RenderCache,
     parent_clip: &ClipRect,
     parent_screen_x: i32,
     parent_screen_y: i32,
@@ -168,6 +246,7 @@ fn render_component(
     // Calculate screen position (can be negative if scrolled out of view)
     let screen_x = parent_screen_x + rel_x - parent_scroll_x;
     let screen_y = parent_screen_y + rel_y - parent_scroll_y;
+    let screen_y = sticky_y(buf, index, parent_screen_y, screen_y, h);
 
     // Create component bounds (with signed x/y)
     let component_bounds = ClipRect::new(screen_x, screen_y, w, h);
@@ -183,6 +262,81 @@ fn render_component(
         Some(v) => v,
         None => return, // Nothing visible on screen
     };
+
+    // A link covers the node's whole visible region and every cell its
+    // descendants paint
+    let outer_link = buffer.link();
+    let href = buf.href(index);
+    if !href.is_empty() {
+        let link = buffer.intern_link(href);
+        let (vis_x, vis_y, vis_w, vis_h) = visible;
+        buffer.link_rect(vis_x, vis_y, vis_w, vis_h, link);
+        buffer.set_link(link);
+    }
+
+    let placement = Placement { screen_x, screen_y, w, h, bounds: component_bounds, clip: effective_clip, visible };
+
+    // Cached box: blit last frame's cells while nothing they came from changed
+    if buf.render_cache(index) {
+        let key = cache::snapshot_key(buf, index, child_map, parent_clip, parent_screen_x, parent_screen_y);
+        if !cache.replay(index, key, visible, buffer, hit_regions) {
+            let repaint = cache::Repaint::begin(key, visible, buffer, hit_regions);
+            paint_component(buffer, buf, index, child_map, hit_regions, cache, &placement);
+            cache.store(index, repaint, buffer, hit_regions);
+        }
+    } else {
+        paint_component(buffer, buf, index, child_map, hit_regions, cache, &placement);
+    }
+
+    buffer.set_link(outer_link);
+}
+
+/// Pin a sticky child of a scroll container to the edge of its viewport
+/// (the parent's content box) once scrolling would carry it past.
+fn sticky_y(buf: &SharedBuffer, index: usize, parent_screen_y: i32, screen_y: i32, h: u16) -> i32 {
+    let Some(parent) = buf.parent_index(index).filter(|&p| buf.is_scrollable(p)) else {
+        return screen_y;
+    };
+    match buf.sticky(index) {
+        Sticky::None => screen_y,
+        Sticky::Top => {
+            let top = parent_screen_y + buf.border_inset_top(parent) as i32 + buf.padding_top(parent) as i32;
+            screen_y.max(top)
+        }
+        Sticky::Bottom => {
+            let bottom = parent_screen_y + buf.computed_height(parent) as i32
+                - buf.border_inset_bottom(parent) as i32
+                - buf.padding_bottom(parent) as i32;
+            screen_y.min(bottom - h as i32)
+        }
+    }
+}
+
+/// Where a component lands on screen, as worked out by `render_component`.
+struct Placement {
+    screen_x: i32,
+    screen_y: i32,
+    w: u16,
+    h: u16,
+    /// Full border box (may extend off screen)
+    bounds: ClipRect,
+    /// Border box intersected with the parent clip
+    clip: ClipRect,
+    /// `clip` clamped to the screen
+    visible: (u16, u16, u16, u16),
+}
+
+/// Paint a placed component: background, borders, content, then children.
+fn paint_component(
+    buffer: &mut FrameBuffer,
+    buf: &SharedBuffer,
+    index: usize,
+    child_map: &[Vec<usize>],
+    hit_regions: &mut Vec<HitRegion>,
+    cache: &mut RenderCache,
+    placement: &Placement,
+) {
+    let Placement { screen_x, screen_y, w, h, bounds: component_bounds, clip: effective_clip, visible } = *placement;
     let (vis_x, vis_y, vis_w, vis_h) = visible;
 
     // Color inheritance + opacity
@@ -193,27 +347,56 @@ fn render_component(
     let effective_bg = apply_opacity(bg, opacity);
 
     // Background fill (at screen coordinates)
-    if effective_bg.a > 0 && !effective_bg.is_terminal_default() {
+    let bg_fill = buf.bg_fill(index);
+    let own_bg = Rgba::from_u32(buf.bg_color(index));
+    if bg_fill != BackgroundFill::Solid && !own_bg.is_terminal_default() {
+        let to = apply_opacity(Rgba::from_u32(buf.bg_color_2(index)), opacity);
+        let (angle, size) = (buf.bg_fill_angle(index), buf.bg_fill_size(index));
+        fill::paint_background(buffer, bg_fill, effective_bg, to, angle, size, &component_bounds, &effective_clip);
+    } else if effective_bg.a > 0 && !effective_bg.is_terminal_default() && !inherits_patterned_bg(buf, index) {
         buffer.fill_rect(vis_x, vis_y, vis_w, vis_h, effective_bg, Some(&effective_clip));
     }
 
-    // Collect hit region (use visible coordinates)
-    hit_regions.push(HitRegion {
-        x: vis_x,
-        y: vis_y,
-        width: vis_w,
-        height: vis_h,
-        component_index: index,
-    });
-
     // Render borders
     render_borders(buffer, buf, index, screen_x, screen_y, w, h, &effective_clip);
 
     // Calculate content area (inside borders + padding)
-    let border_t = if buf.border_top(index) > 0 { 1i32 } else { 0 };
-    let border_r = if buf.border_right(index) > 0 { 1i32 } else { 0 };
-    let border_b = if buf.border_bottom(index) > 0 { 1i32 } else { 0 };
-    let border_l = if buf.border_left(index) > 0 { 1i32 } else { 0 };
+    let border_t = buf.border_inset_top(index) as i32;
+    let border_r = buf.border_inset_right(index) as i32;
+    let border_b = buf.border_inset_bottom(index) as i32;
+    let border_l = buf.border_inset_left(index) as i32;
+
+    // Collect hit regions (visible coordinates), in paint order. A box with
+    // no background of its own is transparent to the mouse but for its
+    // border: it doesn't take cells from what's drawn beneath it. With
+    // `pointer-events: all` it takes them anyway (backdrops).
+    if takes_pointer_events(buf, index) {
+        let paints_background = !own_bg.is_terminal_default() && apply_opacity(own_bg, opacity).a > 0;
+        let opaque = buf.component_type(index) != COMP_BOX
+            || paints_background
+            || buf.pointer_events(index) == PointerEvents::All;
+        hit_regions.push(HitRegion {
+            x: vis_x,
+            y: vis_y,
+            width: vis_w,
+            height: vis_h,
+            component_index: index,
+            opaque,
+        });
+        if !opaque {
+            let (w, h) = (w as i32, h as i32);
+            let sides = [(0, 0, w, border_t), (0, h - border_b, w, border_b), (0, 0, border_l, h), (w - border_r, 0, border_r, h)];
+            for (dx, dy, side_w, side_h) in sides {
+                if side_w <= 0 || side_h <= 0 {
+                    continue;
+                }
+                let side = ClipRect::new(screen_x + dx, screen_y + dy, side_w as u16, side_h as u16);
+                if let Some((x, y, width, height)) = side.intersect(&effective_clip).and_then(|clip| clip.visible_on_screen()) {
+                    hit_regions.push(HitRegion { x, y, width, height, component_index: index, opaque: true });
+                }
+            }
+        }
+    }
 
     let pad_top = buf.padding_top(index) as i32;
     let pad_right = buf.padding_right(index) as i32;
@@ -231,7 +414,7 @@ fn render_component(
     let content_h = (h as i32 - total_top - total_bottom).max(0) as u16;
 
     if content_w == 0 || content_h == 0 {
-        render_children(buffer, buf, index, child_map, hit_regions, &effective_clip, screen_x, screen_y);
+        render_children(buffer, buf, index, child_map, hit_regions, cache, &effective_clip, screen_x, screen_y);
         return;
     }
 
@@ -239,7 +422,7 @@ fn render_component(
     let content_clip = match content_bounds.intersect(&effective_clip) {
         Some(clip) => clip,
         None => {
-            render_children(buffer, buf, index, child_map, hit_regions, &effective_clip, screen_x, screen_y);
+            render_children(buffer, buf, index, child_map, hit_regions, cache, &effective_clip, screen_x, screen_y);
             return;
         }
     };
@@ -268,17 +451,24 @@ fn render_component(
     // Render children - pass screen position (NOT content position)
     // Taffy positions children relative to parent's border box origin,
     // so child.location already includes border+padding offset
-    render_children(buffer, buf, index, child_map, hit_regions, &content_clip, screen_x, screen_y);
+    render_children(buffer, buf, index, child_map, hit_regions, cache, &content_clip, screen_x, screen_y);
 
     // Focus indicator
     render_focus_indicator(buffer, buf, index, screen_x, screen_y, w, comp_type, &effective_clip, effective_fg);
 
-    // Scrollbar
+    // Scrollbars: down the right edge and along the bottom one, inside the
+    // border; with both, each stops short of the corner they'd share
     if buf.is_scrollable(index) {
+        let corner = (buf.max_scroll_x(index) > 0.0 && buf.max_scroll_y(index) > 0.0) as i32;
         let scrollbar_x = (screen_x + w as i32 - 1 - border_r).max(0);
         let scrollbar_y = screen_y + border_t;
-        let scrollbar_h = (h as i32 - border_t - border_b).max(0) as u16;
-        render_scrollbar(buffer, buf, index, scrollbar_x, scrollbar_y, scrollbar_h, effective_fg, &effective_clip);
+        let scrollbar_h = (h as i32 - border_t - border_b - corner).max(0) as u16;
+        render_scrollbar(buffer, buf, index, ScrollAxis::Y, scrollbar_x, scrollbar_y, scrollbar_h, effective_fg, &effective_clip);
+
+        let scrollbar_x = screen_x + border_l;
+        let scrollbar_y = (screen_y + h as i32 - 1 - border_b).max(0);
+        let scrollbar_w = (w as i32 - border_l - border_r - corner).max(0) as u16;
+        render_scrollbar(buffer, buf, index, ScrollAxis::X, scrollbar_x, scrollbar_y, scrollbar_w, effective_fg, &effective_clip);
     }
 }
 
@@ -290,6 +480,7 @@ fn render_children(
     index: usize,
     child_map: &[Vec<usize>],
     hit_regions: &mut Vec<HitRegion>,
+    cache: &mut RenderCache,
     clip: &ClipRect,
     parent_screen_x: i32,
     parent_screen_y: i32,
@@ -303,13 +494,14 @@ fn render_children(
         return;
     }
 
-    for &child_idx in children {
+    for child_idx in children_in_paint_order(buf, index, children) {
         render_component(
             buffer,
             buf,
             child_idx,
             child_map,
             hit_regions,
+            cache,
             clip,
             parent_screen_x,
             parent_screen_y,
@@ -317,6 +509,18 @@ fn render_children(
     }
 }
 
+/// `children` of `index` in the order they paint: pinned children paint over
+/// the content scrolling beneath them.
+pub(super) fn children_in_paint_order<'a>(
+    buf: &'a SharedBuffer,
+    index: usize,
+    children: &'a [usize],
+) -> impl Iterator<Item = usize> + 'a {
+    let pinned = move |child: &&usize| buf.is_scrollable(index) && buf.sticky(**child) != Sticky::None;
+    let in_flow = children.iter().filter(move |c| !pinned(c));
+    in_flow.chain(children.iter().filter(move |c| pinned(c))).copied()
+}
+
 // =============================================================================
 // Border Rendering
 // =============================================================================
@@ -332,12 +536,14 @@ fn render_borders(
     clip: &ClipRect,
 ) {
     // Check if any borders exist
-    let has_top = buf.border_top(index) > 0;
-    let has_right = buf.border_right(index) > 0;
-    let has_bottom = buf.border_bottom(index) > 0;
-    let has_left = buf.border_left(index) > 0;
+    let sides = Sides {
+        top: buf.border_top(index) > 0,
+        right: buf.border_right(index) > 0,
+        bottom: buf.border_bottom(index) > 0,
+        left: buf.border_left(index) > 0,
+    };
 
-    if !has_top && !has_right && !has_bottom && !has_left {
+    if !sides.any() {
         return;
     }
 
@@ -347,16 +553,110 @@ fn render_borders(
         return;
     }
 
-    let (h_char, v_char, tl_char, tr_char, bl_char, br_char) = buf.border_chars(index);
+    // Early return if nothing visible on screen
+    if clip.visible_on_screen().is_none() {
+        return;
+    }
+
+    // Terminals without Unicode get ASCII borders whatever the style
+    let ascii = buf.unicode_level() == UnicodeLevel::Ascii;
+    let chars = |style: BorderStyle| {
+        if ascii { BorderStyle::Ascii.chars() } else { buf.border_chars_for(index, style) }
+    };
 
     // Get border color (convert from packed u32 to utils::Rgba)
     let border_color = Rgba::from_u32(buf.border_color(index));
 
-    // Early return if nothing visible on screen
-    if clip.visible_on_screen().is_none() {
+    let inner_style = buf.border_inner_style(index);
+    if inner_style == BorderStyle::None {
+        draw_ring(buffer, clip, screen_x, screen_y, w, h, sides, chars(style), border_color);
         return;
     }
 
+    // Framed: the optional fill under both lines, the outer line, then the
+    // inner line one cell further in on every bordered side
+    let fill = Rgba::from_u32(buf.border_frame_fill(index));
+    if fill.a > 0 && !fill.is_terminal_default() {
+        fill_frame_band(buffer, clip, screen_x, screen_y, w, h, sides, fill);
+    }
+    draw_ring(buffer, clip, screen_x, screen_y, w, h, sides, chars(style), border_color);
+
+    let inner_color = match buf.border_inner_color(index) {
+        0 => border_color,
+        packed => Rgba::from_u32(packed),
+    };
+    let (t, r, b, l) = (sides.top as i32, sides.right as i32, sides.bottom as i32, sides.left as i32);
+    let inner_w = w as i32 - l - r;
+    let inner_h = h as i32 - t - b;
+    if inner_w > 0 && inner_h > 0 {
+        let (x, y) = (screen_x + l, screen_y + t);
+        draw_ring(buffer, clip, x, y, inner_w as u16, inner_h as u16, sides, chars(inner_style), inner_color);
+    }
+}
+
+/// Which sides of a box carry a border.
+#[derive(Debug, Clone, Copy)]
+struct Sides {
+    top: bool,
+    right: bool,
+    bottom: bool,
+    left: bool,
+}
+
+impl Sides {
+    fn any(self) -> bool {
+        self.top || self.right || self.bottom || self.left
+    }
+}
+
+/// Paint the two-cell band a framed border occupies.
+#[allow(clippy::too_many_arguments)]
+fn fill_frame_band(
+    buffer: &mut FrameBuffer,
+    clip: &ClipRect,
+    screen_x: i32,
+    screen_y: i32,
+    w: u16,
+    h: u16,
+    sides: Sides,
+    color: Rgba,
+) {
+    const BAND: i32 = 2;
+    let (w, h) = (w as i32, h as i32);
+    let mut strips = Vec::with_capacity(4);
+    if sides.top { strips.push((screen_x, screen_y, w, BAND)); }
+    if sides.bottom { strips.push((screen_x, screen_y + h - BAND, w, BAND)); }
+    if sides.left { strips.push((screen_x, screen_y, BAND, h)); }
+    if sides.right { strips.push((screen_x + w - BAND, screen_y, BAND, h)); }
+
+    for (x, y, sw, sh) in strips {
+        if sw <= 0 || sh <= 0 {
+            continue;
+        }
+        let strip = ClipRect::new(x, y, sw.min(w) as u16, sh.min(h) as u16);
+        if let Some(visible) = strip.intersect(clip)
+            && let Some((vx, vy, vw, vh)) = visible.visible_on_screen()
+        {
+            buffer.fill_rect(vx, vy, vw, vh, color, Some(clip));
+        }
+    }
+}
+
+/// Draw one line of border around the given rect, on the given sides.
+#[allow(clippy::too_many_arguments)]
+fn draw_ring(
+    buffer: &mut FrameBuffer,
+    clip: &ClipRect,
+    screen_x: i32,
+    screen_y: i32,
+    w: u16,
+    h: u16,
+    sides: Sides,
+    (h_char, v_char, tl_char, tr_char, bl_char, br_char): (char, char, char, char, char, char),
+    border_color: Rgba,
+) {
+    let Sides { top: has_top, right: has_right, bottom: has_bottom, left: has_left } = sides;
+
     // Draw borders (only if visible on screen)
     // We need to check each position against the clip rect
 
@@ -446,7 +746,8 @@ fn render_text(
     fg: Rgba,
     clip: &ClipRect,
 ) {
-    let content = buf.text(index);
+    let content = visualize_controls(buf.text(index), buf.control_chars());
+    let content = content.as_ref();
     if content.is_empty() {
         return;
     }
@@ -454,30 +755,50 @@ fn render_text(
     let attrs = Attr::from_bits_truncate(buf.text_attrs(index));
     let align = buf.text_align(index);
     let wrap = buf.text_wrap(index);
+    let direction = buf.resolved_direction(index);
+    let is_rtl = |paragraph: &str| match direction {
+        TextDirection::Rtl => true,
+        TextDirection::Ltr => false,
+        _ => paragraph_is_rtl(paragraph),
+    };
 
-    // Handle text wrapping
-    let lines: Vec<String> = match wrap {
-        crate::shared_buffer::TextWrap::Wrap => {
-            wrap_text_word(content, content_w as usize)
-                .into_iter()
-                .map(|s| s.to_string())
-                .collect()
+    // Handle text wrapping. Lines keep their paragraph's direction and know
+    // whether they end it (justified text leaves those unstretched).
+    let lines: Vec<(String, bool, bool)> = match wrap {
+        TextWrap::Wrap | TextWrap::Hyphenate => {
+            let mut lines = Vec::new();
+            for paragraph in content.split('\n') {
+                let rtl = is_rtl(paragraph);
+                if paragraph.is_empty() {
+                    lines.push((String::new(), rtl, true));
+                    continue;
+                }
+                let wrapped = if wrap == TextWrap::Hyphenate {
+                    wrap_text_hyphenated(paragraph, content_w as usize)
+                } else {
+                    wrap_text_word(paragraph, content_w as usize)
+                };
+                let last = wrapped.len().saturating_sub(1);
+                lines.extend(wrapped.into_iter().enumerate().map(|(i, s)| (s, rtl, i == last)));
+            }
+            lines
         }
-        crate::shared_buffer::TextWrap::Truncate => {
+        TextWrap::Truncate => {
+            let rtl = is_rtl(content);
             let text_w = string_width(content);
             if text_w > content_w as usize {
-                vec![truncate_text(content, content_w as usize, "...")]
+                vec![(truncate_text(content, content_w as usize, "..."), rtl, true)]
             } else {
-                vec![content.to_string()]
+                vec![(content.to_string(), rtl, true)]
             }
         }
         _ => {
             // NoWrap: just split on newlines
-            content.lines().map(|s| s.to_string()).collect()
+            content.lines().map(|s| (s.to_string(), is_rtl(s), true)).collect()
         }
     };
 
-    for (line_idx, line) in lines.iter().enumerate() {
+    for (line_idx, (line, rtl, ends_paragraph)) in lines.iter().enumerate() {
         let line_y = content_y + line_idx as i32;
         if line_y >= content_y + content_h as i32 {
             break;
@@ -486,17 +807,23 @@ fn render_text(
             continue;
         }
 
-        let text_width = string_width(line) as u16;
+        let mut line = reorder_line(line, *rtl);
+        if align == TextAlign::Justify && !ends_paragraph {
+            line = Cow::Owned(justify_line(&line, content_w as usize));
+        }
+        let text_width = string_width(&line) as u16;
 
-        // Alignment
-        let draw_x = match align {
-            crate::shared_buffer::TextAlign::Center => content_x + (content_w.saturating_sub(text_width) / 2) as i32,
-            crate::shared_buffer::TextAlign::Right => content_x + content_w.saturating_sub(text_width) as i32,
+        // Alignment (start and end follow the paragraph's direction)
+        let draw_x = match (align, *rtl) {
+            (TextAlign::Center, _) => content_x + (content_w.saturating_sub(text_width) / 2) as i32,
+            (TextAlign::Right, _) | (TextAlign::Start | TextAlign::Justify, true) | (TextAlign::End, false) => {
+                content_x + content_w.saturating_sub(text_width) as i32
+            }
             _ => content_x, // Left
         };
 
         if draw_x >= 0 {
-            buffer.draw_text(draw_x as u16, line_y as u16, line, fg, None, attrs, Some(clip));
+            buffer.draw_text(draw_x as u16, line_y as u16, &line, fg, None, attrs, Some(clip));
         }
     }
 }
@@ -528,11 +855,36 @@ fn render_input(
     let content = buf.text(index);
     let attrs = Attr::from_bits_truncate(buf.text_attrs(index));
 
-    // Horizontal scroll offset
-    let scroll_x = buf.scroll_x(index) as usize;
+    // Horizontal scroll offset (moved on below while composing)
+    let mut scroll_x = buf.scroll_x(index) as usize;
+
+    // Visible text after scroll. Controls map to one picture each so
+    // cursor and selection positions still line up (layout measures the same).
+    let mut chars: Vec<char> = input_pictures(content).chars().collect();
+    let value_len = chars.len();
+
+    // Composition in progress: the preedit reads as if typed at the cursor,
+    // underlined, with the cursor after it
+    let preedit: Vec<char> = buf.preedit(index).chars().map(control_picture).collect();
+    let preedit_start = (buf.cursor_position(index) as usize).min(value_len);
+    if !preedit.is_empty() {
+        chars = chars[..preedit_start].iter()
+            .chain(&preedit)
+            .chain(&chars[preedit_start..])
+            .copied()
+            .collect();
+    }
+    let cursor_pos = if preedit.is_empty() {
+        buf.cursor_position(index) as usize
+    } else {
+        preedit_start + preedit.len()
+    };
+    // A preedit running past the edge scrolls the view with it, so what's
+    // being composed and the cursor after it stay in sight
+    if !preedit.is_empty() {
+        scroll_x = scroll_to_show(&chars, scroll_x, cursor_pos, content_w as usize);
+    }
 
-    // Visible text after scroll
-    let chars: Vec<char> = content.chars().collect();
     let visible_start = scroll_x.min(chars.len());
     let visible_chars: String = chars.iter().skip(visible_start).collect();
 
@@ -544,13 +896,66 @@ fn render_input(
     };
 
     // Draw text
-    buffer.draw_text(x, y, &display_text, fg, None, attrs, Some(clip));
+    let text_w = buffer.draw_text(x, y, &display_text, fg, None, attrs, Some(clip));
+
+    for (i, &ch) in preedit.iter().enumerate() {
+        let Some(column) = input_column(&chars, scroll_x, preedit_start + i) else { continue };
+        if column >= content_w as usize {
+            break;
+        }
+        if char_width(ch) > 0 {
+            buffer.set_cell(x + column as u16, y, ch as u32, fg, bg, attrs | Attr::UNDERLINE, Some(clip));
+        }
+    }
 
-    // Render selection highlighting
-    render_input_selection(buffer, buf, index, x, y, content_w, &chars, fg, bg, scroll_x, clip);
+    // Ghost text: dimmed suggestion past the logical value, only while the
+    // focused cursor sits at the end of the value
+    let ghost = visualize_controls(buf.ghost_text(index), buf.control_chars());
+    let ghost = ghost.as_ref();
+    let show_ghost = !ghost.is_empty()
+        && preedit.is_empty()
+        && buf.focused_index() == index as i32
+        && cursor_pos == value_len
+        && text_w < content_w;
+    if show_ghost {
+        let ghost_text = truncate_text(ghost, (content_w - text_w) as usize, "");
+        buffer.draw_text(x + text_w, y, &ghost_text, fg, None, Attr::DIM, Some(clip));
+    }
 
-    // Render cursor
-    render_input_cursor(buffer, buf, index, x, y, content_w, &chars, fg, bg, scroll_x, clip);
+    // Render selection highlighting (value positions, so not mid-composition)
+    if preedit.is_empty() {
+        render_input_selection(buffer, buf, index, x, y, content_w, &chars, fg, bg, scroll_x, clip);
+    }
+
+    // Render cursor (over the first ghost char when a suggestion is showing)
+    let cursor_chars: Vec<char> = if show_ghost {
+        chars.iter().copied().chain(ghost.chars()).collect()
+    } else {
+        chars
+    };
+    render_input_cursor(buffer, buf, index, x, y, content_w, &cursor_chars, cursor_pos, fg, bg, scroll_x, clip);
+}
+
+/// `scroll_x`, moved right just far enough for char `pos` to show within
+/// `width` cells.
+fn scroll_to_show(chars: &[char], scroll_x: usize, pos: usize, width: usize) -> usize {
+    let mut start = scroll_x.min(pos);
+    while start < pos && input_column(chars, start, pos).is_some_and(|column| column >= width) {
+        start += 1;
+    }
+    start
+}
+
+/// Display column of char `pos` in an input showing `chars` from char
+/// `scroll_x`, counting cells as `draw_text` lays them out. None when `pos`
+/// is scrolled out to the left.
+fn input_column(chars: &[char], scroll_x: usize, pos: usize) -> Option<usize> {
+    let start = scroll_x.min(chars.len());
+    if pos < start {
+        return None;
+    }
+    let drawn: usize = chars[start..pos.min(chars.len())].iter().map(|&c| char_width(c)).sum();
+    Some(drawn + pos.saturating_sub(chars.len()))
 }
 
 /// Render selection highlighting (inverse colors).
@@ -576,16 +981,17 @@ fn render_input_selection(
     }
 
     for pos in sel_start..sel_end {
-        let screen_pos = pos.saturating_sub(scroll_x);
-        if screen_pos >= content_w as usize {
+        let Some(column) = input_column(chars, scroll_x, pos) else { continue };
+        if column >= content_w as usize {
             break;
         }
-        if pos < scroll_x {
+        let ch = chars.get(pos).copied().unwrap_or(' ');
+        // Combining marks and joiners share their cluster's cell
+        if char_width(ch) == 0 {
             continue;
         }
 
-        let render_x = content_x + screen_pos as u16;
-        let ch = chars.get(pos).copied().unwrap_or(' ');
+        let render_x = content_x + column as u16;
 
         // INVERSE for selection
         buffer.set_cell(render_x, content_y, ch as u32, bg, fg, Attr::INVERSE, Some(clip));
@@ -602,6 +1008,7 @@ fn render_input_cursor(
     content_y: u16,
     content_w: u16,
     chars: &[char],
+    cursor_pos: usize,
     fg: Rgba,
     bg: Rgba,
     scroll_x: usize,
@@ -612,13 +1019,27 @@ fn render_input_cursor(
         return;
     }
 
-    let cursor_pos = buf.cursor_position(index) as usize;
-    let screen_pos = cursor_pos.saturating_sub(scroll_x);
-    if screen_pos >= content_w as usize {
+    let Some(column) = input_column(chars, scroll_x, cursor_pos) else { return };
+    if column >= content_w as usize {
+        return;
+    }
+
+    let render_x = content_x + column as u16;
+
+    // The terminal draws (and blinks) its own cursor at the focused input
+    if buf.config_flags().contains(ConfigFlags::NATIVE_CURSOR) {
+        if buf.focused_index() == index as i32 && clip.contains(render_x, content_y) {
+            let shape = match buf.cursor_style(index) {
+                CursorStyle::Block => CursorShape::Block,
+                CursorStyle::Bar => CursorShape::Bar,
+                CursorStyle::Underline => CursorShape::Underline,
+            };
+            let blinking = buf.cursor_blink_rate(index) > 0;
+            buffer.set_cursor(Some(TerminalCursor { x: render_x, y: content_y, shape, blinking }));
+        }
         return;
     }
 
-    let render_x = content_x + screen_pos as u16;
     let char_at_cursor = chars.get(cursor_pos).copied().unwrap_or(' ');
 
     let cursor_char = buf.cursor_char(index);
@@ -749,7 +1170,8 @@ fn render_select(
     let x = content_x as u16;
     let y = content_y as u16;
 
-    let content = buf.text(index);
+    let content = visualize_controls(buf.text(index), buf.control_chars());
+    let content = content.as_ref();
     let attrs = Attr::from_bits_truncate(buf.text_attrs(index));
 
     let indicator = " \u{25BC}"; // Down arrow
@@ -773,48 +1195,61 @@ fn render_select(
 const SCROLLBAR_TRACK: char = '░';
 const SCROLLBAR_THUMB: char = '█';
 
+/// Which way a scrollbar runs.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScrollAxis {
+    X,
+    Y,
+}
+
+/// Draw `index`'s scrollbar for `axis`, `len` cells long from (x, y) —
+/// nothing when there's nothing to scroll that way.
+#[allow(clippy::too_many_arguments)]
 fn render_scrollbar(
     buffer: &mut FrameBuffer,
     buf: &SharedBuffer,
     index: usize,
+    axis: ScrollAxis,
     x: i32,
     y: i32,
-    h: u16,
+    len: u16,
     fg: Rgba,
     clip: &ClipRect,
 ) {
-    let max_scroll_y = buf.max_scroll_y(index);
-    if max_scroll_y <= 0.0 || h == 0 || x < 0 || y < 0 {
+    let (max_scroll, scroll) = match axis {
+        ScrollAxis::X => (buf.max_scroll_x(index), buf.scroll_x(index) as f32),
+        ScrollAxis::Y => (buf.max_scroll_y(index), buf.scroll_y(index) as f32),
+    };
+    if max_scroll <= 0.0 || len == 0 || x < 0 || y < 0 {
         return;
     }
 
-    let x = x as u16;
-    let scroll_y = buf.scroll_y(index) as f32;
     let scrollbar_color = fg.dim(0.5);
 
     // Calculate thumb size and position
-    let total_content = max_scroll_y + h as f32;
-    let thumb_height = ((h as f32 / total_content) * h as f32).max(1.0) as u16;
-    let thumb_pos = if max_scroll_y > 0.0 {
-        ((scroll_y / max_scroll_y) * (h - thumb_height) as f32) as u16
-    } else {
-        0
+    let total_content = max_scroll + len as f32;
+    let thumb_len = ((len as f32 / total_content) * len as f32).max(1.0) as u16;
+    let thumb_pos = ((scroll / max_scroll) * (len - thumb_len) as f32) as u16;
+
+    let cell = |step: u16| match axis {
+        ScrollAxis::X => (x + step as i32, y),
+        ScrollAxis::Y => (x, y + step as i32),
+    };
+    let mut draw = |step: u16, ch: char, color: Rgba| {
+        let (cx, cy) = cell(step);
+        if cx >= 0 && cy >= 0 && clip.contains(cx as u16, cy as u16) {
+            buffer.draw_char(cx as u16, cy as u16, ch, color, None, Attr::NONE, Some(clip));
+        }
     };
 
     // Draw track
-    for row in 0..h {
-        let draw_y = y + row as i32;
-        if draw_y >= 0 && clip.contains(x, draw_y as u16) {
-            buffer.draw_char(x, draw_y as u16, SCROLLBAR_TRACK, scrollbar_color.dim(0.3), None, Attr::NONE, Some(clip));
-        }
+    for step in 0..len {
+        draw(step, SCROLLBAR_TRACK, scrollbar_color.dim(0.3));
     }
 
     // Draw thumb
-    for row in thumb_pos..(thumb_pos + thumb_height).min(h) {
-        let draw_y = y + row as i32;
-        if draw_y >= 0 && clip.contains(x, draw_y as u16) {
-            buffer.draw_char(x, draw_y as u16, SCROLLBAR_THUMB, scrollbar_color, None, Attr::NONE, Some(clip));
-        }
+    for step in thumb_pos..(thumb_pos + thumb_len).min(len) {
+        draw(step, SCROLLBAR_THUMB, scrollbar_color);
     }
 }
 
@@ -834,8 +1269,305 @@ mod tests {
             width: 30,
             height: 40,
             component_index: 5,
+            opaque: true,
         };
         assert_eq!(hr.x, 10);
         assert_eq!(hr.component_index, 5);
     }
+
+    #[test]
+    fn test_mouse_passes_through_transparent_boxes_and_pointer_events_none() {
+        use crate::input::mouse::HitGrid;
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::{N_BG_COLOR, N_CREATION_SEQ, N_POINTER_EVENTS, PointerEvents};
+
+        const RED: u32 = 0xFFFF0000;
+        const BLUE: u32 = 0xFF0000FF;
+        let buf = TestBuffer::new(4, 0);
+        let node = |i: usize, parent: i32, seq: u32, bg: u32| {
+            buf.node(i, COMP_BOX, parent);
+            buf.put_u32(i, N_CREATION_SEQ, seq);
+            buf.put_u32(i, N_BG_COLOR, bg);
+        };
+        // Transparent root; red box over its left half; a transparent overlay
+        // across the whole row holding a blue cell at the right end
+        node(0, -1, 1, 0);
+        node(1, 0, 2, RED);
+        node(2, 0, 3, 0);
+        node(3, 2, 4, BLUE);
+        for (i, x, w) in [(0, 0.0, 4.0), (1, 0.0, 2.0), (2, 0.0, 4.0), (3, 3.0, 1.0)] {
+            buf.set_computed_x(i, x);
+            buf.set_computed_width(i, w);
+            buf.set_computed_height(i, 1.0);
+        }
+        let hits = |buf: &SharedBuffer| {
+            let (_, regions) = compute_framebuffer(buf, 4, 1);
+            let mut grid = HitGrid::new(4, 1);
+            for region in &regions {
+                grid.fill_region(region, buf);
+            }
+            (0..4).map(|x| grid.hit_test(x, 0)).collect::<Vec<_>>()
+        };
+
+        // The overlay doesn't steal the red box's cells, only the root's
+        assert_eq!(hits(&buf), vec![Some(1), Some(1), Some(2), Some(3)]);
+
+        // pointer-events: none on the overlay carries down to the blue cell
+        buf.put_u8(2, N_POINTER_EVENTS, PointerEvents::None as u8);
+        assert_eq!(hits(&buf), vec![Some(1), Some(1), Some(0), Some(0)]);
+
+        // ...unless the child opts back in
+        buf.put_u8(3, N_POINTER_EVENTS, PointerEvents::Auto as u8);
+        assert_eq!(hits(&buf), vec![Some(1), Some(1), Some(0), Some(3)]);
+    }
+
+    #[test]
+    fn test_pointer_events_all_backdrop_catches_clicks_outside() {
+        use crate::input::mouse::HitGrid;
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::{N_BG_COLOR, N_CREATION_SEQ, N_POINTER_EVENTS, N_Z_INDEX};
+
+        const RED: u32 = 0xFFFF0000;
+        const BLUE: u32 = 0xFF0000FF;
+        // Root holding a red button at 0; a transparent full-width backdrop
+        // above everything holding a blue menu panel at 3
+        let buf = TestBuffer::new(4, 0);
+        for (i, parent, bg) in [(0, -1, 0), (1, 0, RED), (2, 0, 0), (3, 2, BLUE)] {
+            buf.node(i, COMP_BOX, parent);
+            buf.put_u32(i, N_CREATION_SEQ, i as u32 + 1);
+            buf.put_u32(i, N_BG_COLOR, bg);
+        }
+        buf.put_i32(2, N_Z_INDEX, 100);
+        for (i, x, w) in [(0, 0.0, 4.0), (1, 0.0, 1.0), (2, 0.0, 4.0), (3, 3.0, 1.0)] {
+            buf.set_computed_x(i, x);
+            buf.set_computed_width(i, w);
+            buf.set_computed_height(i, 1.0);
+        }
+        let hits = |buf: &SharedBuffer| {
+            let (_, regions) = compute_framebuffer(buf, 4, 1);
+            let mut grid = HitGrid::new(4, 1);
+            for region in &regions {
+                grid.fill_region(region, buf);
+            }
+            (0..4).map(|x| grid.hit_test(x, 0)).collect::<Vec<_>>()
+        };
+
+        // Transparent: a click outside the panel reaches the button beneath
+        assert_eq!(hits(&buf), vec![Some(1), Some(2), Some(2), Some(3)]);
+
+        // pointer-events: all — every click outside the panel is the backdrop's
+        buf.put_u8(2, N_POINTER_EVENTS, PointerEvents::All as u8);
+        assert_eq!(hits(&buf), vec![Some(2), Some(2), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_equal_z_paints_in_creation_order_across_index_reuse() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::{N_BG_COLOR, N_CREATION_SEQ};
+
+        const RED: u32 = 0xFFFF0000;
+        const BLUE: u32 = 0xFF0000FF;
+        let nodes = 3;
+        let buf = TestBuffer::new(nodes, 0);
+        let node = |i: usize, parent: i32, seq: u32, bg: u32| {
+            buf.node(i, COMP_BOX, parent);
+            buf.put_u32(i, N_CREATION_SEQ, seq);
+            buf.put_u32(i, N_BG_COLOR, bg);
+        };
+        // Root, then two overlapping siblings at the same z: red at index 1, blue at index 2
+        node(0, -1, 1, 0);
+        node(1, 0, 2, RED);
+        node(2, 0, 3, BLUE);
+        for i in 0..nodes {
+            buf.set_computed_width(i, 2.0);
+            buf.set_computed_height(i, 1.0);
+        }
+        let top = |buf: &SharedBuffer| compute_framebuffer(buf, 2, 1).0.get(0, 0).unwrap().bg;
+
+        assert_eq!(top(&buf), Rgba::from_u32(BLUE));
+
+        // Red destroyed and recreated into the same (lower) index: now the
+        // newest, it paints on top although blue's index is higher
+        node(1, 0, 4, RED);
+        assert_eq!(top(&buf), Rgba::from_u32(RED));
+        // Recreate blue too: order follows creation again
+        node(2, 0, 5, BLUE);
+        assert_eq!(top(&buf), Rgba::from_u32(BLUE));
+    }
+
+    #[test]
+    fn test_sticky_children_pin_to_the_scrolled_viewport() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::{N_BG_COLOR, N_CREATION_SEQ, N_IS_SCROLLABLE, N_STICKY};
+
+        const RED: u32 = 0xFFFF0000;
+        const GREEN: u32 = 0xFF00FF00;
+        const BLUE: u32 = 0xFF0000FF;
+        let buf = TestBuffer::new(4, 0);
+        let node = |i: usize, parent: i32, bg: u32, sticky: Sticky| {
+            buf.node(i, COMP_BOX, parent);
+            buf.put_u32(i, N_CREATION_SEQ, i as u32 + 1);
+            buf.put_u32(i, N_BG_COLOR, bg);
+            buf.put_u8(i, N_STICKY, sticky as u8);
+        };
+        // A 3-row viewport over a header, 5 blue rows and a footer
+        node(0, -1, 0, Sticky::None);
+        node(1, 0, RED, Sticky::Top);
+        node(2, 0, BLUE, Sticky::None);
+        node(3, 0, GREEN, Sticky::Bottom);
+        buf.put_u8(0, N_IS_SCROLLABLE, 1);
+        for (i, y, h) in [(0, 0.0, 3.0), (1, 0.0, 1.0), (2, 1.0, 5.0), (3, 6.0, 1.0)] {
+            buf.set_computed_y(i, y);
+            buf.set_computed_width(i, 1.0);
+            buf.set_computed_height(i, h);
+        }
+        let column = |buf: &SharedBuffer| {
+            let (frame, regions) = compute_framebuffer(buf, 1, 3);
+            let hit_top = regions.iter().rev().find(|r| r.y == 0).map(|r| r.component_index);
+            ((0..3).map(|y| frame.get(0, y).unwrap().bg).collect::<Vec<_>>(), hit_top)
+        };
+        let (red, green, blue) = (Rgba::from_u32(RED), Rgba::from_u32(GREEN), Rgba::from_u32(BLUE));
+
+        // Unscrolled: the header is in place, the footer waits at the bottom
+        assert_eq!(column(&buf).0, vec![red, blue, green]);
+
+        // Scrolled into the middle: the header stays on top, painted and hit
+        buf.set_scroll(0, 0, 2);
+        assert_eq!(column(&buf), (vec![red, blue, green], Some(1)));
+
+        // Scrolled to the end: the footer has reached its own place
+        buf.set_scroll(0, 0, 4);
+        assert_eq!(column(&buf).0, vec![red, blue, green]);
+
+        // Without sticky the header scrolls away
+        buf.put_u8(1, N_STICKY, Sticky::None as u8);
+        assert_eq!(column(&buf).0, vec![blue, blue, green]);
+    }
+
+    #[test]
+    fn test_cached_box_replays_until_its_subtree_changes() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::{N_BG_COLOR, N_RENDER_CACHE, N_VISIBLE};
+
+        const RED: u32 = 0xFFFF0000;
+        const BLUE: u32 = 0xFF0000FF;
+        const GREEN: u32 = 0xFF00FF00;
+        let buf = TestBuffer::new(3, 0);
+        // Root, a cached sidebar (index 1) and its child filling its left cell
+        for (i, parent, bg) in [(0, -1, 0), (1, 0, RED), (2, 1, BLUE)] {
+            buf.node(i, COMP_BOX, parent);
+            buf.put_u32(i, N_BG_COLOR, bg);
+        }
+        buf.put_u8(1, N_RENDER_CACHE, 1);
+        buf.set_computed_width(0, 3.0);
+        buf.set_computed_height(0, 1.0);
+        buf.set_computed_width(1, 2.0);
+        buf.set_computed_height(1, 1.0);
+        buf.set_computed_width(2, 1.0);
+        buf.set_computed_height(2, 1.0);
+
+        let mut cache = RenderCache::new();
+        let first = compute_framebuffer_cached(&buf, 3, 1, &mut cache);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(first, compute_framebuffer(&buf, 3, 1));
+        // Unchanged → replayed, hit regions included
+        assert_eq!(compute_framebuffer_cached(&buf, 3, 1, &mut cache), first);
+
+        // A descendant changes → repainted
+        buf.put_u32(2, N_BG_COLOR, GREEN);
+        let (changed, _) = compute_framebuffer_cached(&buf, 3, 1, &mut cache);
+        assert_eq!(changed.get(0, 0).unwrap().bg, Rgba::from_u32(GREEN));
+        assert_eq!(changed.get(1, 0).unwrap().bg, Rgba::from_u32(RED));
+
+        // Hidden → its snapshot is dropped
+        buf.put_u8(1, N_VISIBLE, 0);
+        compute_framebuffer_cached(&buf, 3, 1, &mut cache);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_href_links_the_subtree_cells() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::N_RENDER_CACHE;
+
+        let buf = TestBuffer::new(4, 64);
+        for (i, parent) in [(0, -1), (1, 0), (2, 1), (3, 0)] {
+            buf.node(i, COMP_BOX, parent);
+        }
+        // Root [ cached link box (index 1) with a plain child | other link ]
+        buf.put_u8(1, N_RENDER_CACHE, 1);
+        for (i, x, w) in [(0, 0.0, 4.0), (1, 0.0, 2.0), (2, 0.0, 1.0), (3, 3.0, 1.0)] {
+            buf.set_computed_x(i, x);
+            buf.set_computed_width(i, w);
+            buf.set_computed_height(i, 1.0);
+        }
+        assert!(buf.set_href(1, "https://a.example"));
+        assert!(buf.set_href(3, "https://b.example"));
+
+        let mut cache = RenderCache::new();
+        let first = compute_framebuffer_cached(&buf, 4, 1, &mut cache).0;
+        let url = |frame: &FrameBuffer, x: u16| frame.link_url(frame.get(x, 0).unwrap().link).map(str::to_string);
+        for frame in [&first, &compute_framebuffer_cached(&buf, 4, 1, &mut cache).0] {
+            // The child inherits its ancestor's link; the root has none
+            assert_eq!(url(frame, 0).as_deref(), Some("https://a.example"));
+            assert_eq!(url(frame, 1).as_deref(), Some("https://a.example"));
+            assert_eq!(url(frame, 2), None);
+            assert_eq!(url(frame, 3).as_deref(), Some("https://b.example"));
+        }
+    }
+
+    #[test]
+    fn test_native_cursor_at_focused_input() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::{FLAG_FOCUSABLE, H_CONFIG_FLAGS, N_CURSOR_STYLE, N_INTERACTION_FLAGS, N_RENDER_CACHE};
+
+        let buf = TestBuffer::new(2, 64);
+        buf.put_header_u32(H_CONFIG_FLAGS, ConfigFlags::NATIVE_CURSOR.bits());
+        buf.node(0, COMP_BOX, -1);
+        buf.node(1, COMPONENT_INPUT, 0);
+        // Cached root [ focused input, bar cursor after "ab" ]
+        buf.put_u8(0, N_RENDER_CACHE, 1);
+        buf.put_u8(1, N_INTERACTION_FLAGS, FLAG_FOCUSABLE);
+        buf.put_u8(1, N_CURSOR_STYLE, CursorStyle::Bar as u8);
+        for (i, x, w) in [(0, 0.0, 8.0), (1, 1.0, 6.0)] {
+            buf.set_computed_x(i, x);
+            buf.set_computed_width(i, w);
+            buf.set_computed_height(i, 1.0);
+        }
+        buf.set_cursor_position(1, 2);
+        buf.set_focused_index(1);
+
+        let mut cache = RenderCache::new();
+        let expected = TerminalCursor { x: 3, y: 0, shape: CursorShape::Bar, blinking: false };
+        for _ in 0..2 {
+            // Painted, then replayed from the cache
+            let frame = compute_framebuffer_cached(&buf, 8, 1, &mut cache).0;
+            assert_eq!(frame.cursor(), Some(expected));
+            // No cell drawn for it
+            assert_eq!(frame.get(3, 0), frame.get(4, 0));
+        }
+
+        buf.set_focused_index(-1);
+        assert_eq!(compute_framebuffer_cached(&buf, 8, 1, &mut cache).0.cursor(), None);
+    }
+
+    #[test]
+    fn test_preedit_scrolls_the_input() {
+        use crate::shared_buffer::test_support::TestBuffer;
+
+        // A 4-cell input holding "ab", composing "xyz" at the end
+        let buf = TestBuffer::new(1, 64);
+        buf.node(0, COMPONENT_INPUT, -1);
+        buf.set_computed_width(0, 4.0);
+        buf.set_computed_height(0, 1.0);
+        buf.set_text(0, "ab");
+        buf.set_cursor_position(0, 2);
+        buf.set_preedit(0, "xyz");
+
+        // The view moves on to the preedit, leaving a cell for the cursor
+        let frame = compute_framebuffer(&buf, 4, 1).0;
+        let row: String = (0..3).filter_map(|x| frame.get(x, 0)).filter_map(|cell| char::from_u32(cell.char)).collect();
+        assert_eq!(row, "xyz");
+        assert!(frame.get(0, 0).is_some_and(|cell| cell.attrs.contains(Attr::UNDERLINE)));
+    }
 }
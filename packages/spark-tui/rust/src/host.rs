@@ -0,0 +1,172 @@
+//! Component tree for hosts without the TS side.
+//!
+//! The TS registry (`ts/engine/registry.ts`) normally hands out node
+//! indices, links them into the tree and recycles them. A host driving the
+//! engine through the C ABI (Rust, Python, Go, …) gets the same from
+//! [`NodeRegistry`], writing to the SharedBuffer exactly what TS would:
+//!
+//! ```text
+//! spark_buffer_init(buf, len, max_nodes, pool)   header + node defaults
+//! spark_init(buf, len)                           start the engine
+//! root = spark_create_node(COMPONENT_BOX, -1)
+//! label = spark_create_node(COMPONENT_TEXT, root)
+//! spark_set_text(label, "hi", 2)
+//! spark_set_prop_f32(root, N_WIDTH, 40.0)
+//! spark_wake()                                   once per batch of writes
+//! spark_poll_event(out)                          after spark_wait_for_events()
+//! ```
+//!
+//! Props are set by their N_* offset, the same layout TS writes. Released
+//! indices are reused, each release bumping the index's generation (see
+//! [`ComponentRef`](crate::shared_buffer::ComponentRef)).
+
+use crate::shared_buffer::{
+    ComponentRef, SharedBuffer, COMPONENT_NONE, DIRTY_HIERARCHY, DIRTY_LAYOUT, N_COMPONENT_TYPE, N_CREATION_SEQ, N_GENERATION,
+};
+
+/// Index allocation over one SharedBuffer, as the TS registry does it.
+#[derive(Debug, Default)]
+pub struct NodeRegistry {
+    /// Released indices, reused before new ones
+    free: Vec<usize>,
+    /// Next never-used index
+    next: usize,
+    /// Nodes currently allocated
+    live: usize,
+    /// Last creation sequence handed out (N_CREATION_SEQ)
+    creation_seq: u32,
+}
+
+impl NodeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `index` holds a component created here and not destroyed
+    pub fn is_live(&self, buf: &SharedBuffer, index: usize) -> bool {
+        index < self.next && buf.component_type(index) != COMPONENT_NONE
+    }
+
+    /// Allocate a node of `component_type` (COMPONENT_*) under `parent`
+    /// (None = a root). None if the buffer is full, the type is
+    /// COMPONENT_NONE or the parent doesn't exist.
+    pub fn create(&mut self, buf: &SharedBuffer, component_type: u8, parent: Option<usize>) -> Option<usize> {
+        if component_type == COMPONENT_NONE || parent.is_some_and(|p| !self.is_live(buf, p)) {
+            return None;
+        }
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None if self.next < buf.max_nodes() => {
+                self.next += 1;
+                self.next - 1
+            }
+            None => return None,
+        };
+
+        buf.init_node(index);
+        buf.write_field_u8(index, N_COMPONENT_TYPE, component_type);
+        // Newest node so far: paints over equal-z siblings created before it
+        self.creation_seq += 1;
+        buf.write_field_u32(index, N_CREATION_SEQ, self.creation_seq);
+        if let Some(parent) = parent {
+            buf.link_child(index, parent);
+        }
+        buf.mark_dirty(index, DIRTY_HIERARCHY | DIRTY_LAYOUT);
+
+        self.live += 1;
+        buf.set_node_count(self.next);
+        Some(index)
+    }
+
+    /// Release `index` and everything under it. False if it isn't live.
+    pub fn destroy(&mut self, buf: &SharedBuffer, index: usize) -> bool {
+        if !self.is_live(buf, index) {
+            return false;
+        }
+        // Refs taken up front: releasing one child's subtree must leave its
+        // siblings alone, which debug builds check as each one's turn comes
+        let children: Vec<ComponentRef> = buf.iter_children(index).map(|child| buf.component_ref(child)).collect();
+        for child in children {
+            self.destroy(buf, buf.live_index(child));
+        }
+
+        buf.unlink_child(index);
+        buf.write_field_u8(index, N_COMPONENT_TYPE, COMPONENT_NONE);
+        buf.write_field_u32(index, N_GENERATION, buf.node_generation(index).wrapping_add(1));
+        buf.mark_dirty(index, DIRTY_HIERARCHY);
+        self.free.push(index);
+        self.live -= 1;
+
+        // Everything gone: start over from index 0
+        if self.live == 0 {
+            self.free.clear();
+            self.next = 0;
+            buf.set_node_count(0);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_buffer::{calculate_buffer_size, COMPONENT_BOX, COMPONENT_TEXT};
+
+    fn create_buffer(max_nodes: usize) -> (Vec<u8>, SharedBuffer) {
+        let len = calculate_buffer_size(max_nodes, 256);
+        let mut data = vec![0u8; len];
+        let buf = unsafe { SharedBuffer::initialize(data.as_mut_ptr(), len, max_nodes, 256) }.unwrap();
+        (data, buf)
+    }
+
+    #[test]
+    fn test_create_links_and_counts() {
+        let (_data, buf) = create_buffer(4);
+        let mut nodes = NodeRegistry::new();
+
+        let root = nodes.create(&buf, COMPONENT_BOX, None).unwrap();
+        let a = nodes.create(&buf, COMPONENT_TEXT, Some(root)).unwrap();
+        let b = nodes.create(&buf, COMPONENT_TEXT, Some(root)).unwrap();
+        assert_eq!(buf.node_count(), 3);
+        assert_eq!(buf.parent_index(a), Some(root));
+        assert_eq!(buf.iter_children(root).collect::<Vec<_>>(), vec![b, a]);
+        assert!(buf.width(a).is_nan());
+        assert!(buf.visible(a));
+
+        assert_eq!(nodes.create(&buf, COMPONENT_NONE, None), None);
+        assert_eq!(nodes.create(&buf, COMPONENT_BOX, Some(3)), None);
+        nodes.create(&buf, COMPONENT_BOX, None).unwrap();
+        assert_eq!(nodes.create(&buf, COMPONENT_BOX, None), None, "buffer full");
+    }
+
+    #[test]
+    fn test_destroy_recycles_with_new_generation() {
+        let (_data, buf) = create_buffer(4);
+        let mut nodes = NodeRegistry::new();
+
+        let root = nodes.create(&buf, COMPONENT_BOX, None).unwrap();
+        let panel = nodes.create(&buf, COMPONENT_BOX, Some(root)).unwrap();
+        let label = nodes.create(&buf, COMPONENT_TEXT, Some(panel)).unwrap();
+        let old = buf.component_ref(label);
+
+        assert!(nodes.destroy(&buf, panel));
+        assert!(!nodes.destroy(&buf, panel));
+        assert_eq!(buf.first_child(root), -1);
+        assert_eq!(buf.component_type(label), COMPONENT_NONE);
+        assert!(!buf.is_live(old));
+
+        // Last released is reused first; the old ref stays stale
+        let reused = nodes.create(&buf, COMPONENT_TEXT, Some(root)).unwrap();
+        assert_eq!(reused, panel);
+        let relabel = nodes.create(&buf, COMPONENT_TEXT, Some(reused)).unwrap();
+        assert_eq!(relabel, label);
+        assert!(!buf.is_live(old));
+        assert!(buf.is_live(buf.component_ref(relabel)));
+        assert_eq!(buf.node_count(), 3);
+
+        // Last one out resets the count
+        assert!(nodes.destroy(&buf, root));
+        assert_eq!(buf.node_count(), 0);
+        assert_eq!(nodes.create(&buf, COMPONENT_BOX, None), Some(0));
+    }
+}
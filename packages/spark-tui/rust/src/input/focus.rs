@@ -1,11 +1,89 @@
 //! Focus management system.
 //!
-//! Manages which component is focused, focus navigation (Tab/Shift+Tab),
-//! focus traps, focus history, and implicit focusable detection.
+//! Manages which component is focused, focus navigation (Tab/Shift+Tab and
+//! spatial arrow-key movement), focus traps, focus history, and implicit
+//! focusable detection.
 //!
 //! All state is stored in SharedBuffer interaction arrays.
 
-use crate::shared_buffer::SharedBuffer;
+use crate::shared_buffer::{ComponentRef, SharedBuffer};
+
+/// Perpendicular misalignment counts this much more than distance along the
+/// direction, so a neighbour in the same row beats a nearer one a row off.
+const CROSS_AXIS_WEIGHT: f32 = 2.0;
+
+/// Direction for spatial (arrow-key) focus navigation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl FocusDirection {
+    /// Unit scroll delta (dx, dy) for this direction.
+    pub fn delta(self) -> (i32, i32) {
+        match self {
+            Self::Up => (0, -1),
+            Self::Down => (0, 1),
+            Self::Left => (-1, 0),
+            Self::Right => (1, 0),
+        }
+    }
+}
+
+/// A node's layout box in root content coordinates (scroll offsets ignored,
+/// so nodes scrolled out of view are still reachable).
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+impl Rect {
+    fn of(buf: &SharedBuffer, index: usize) -> Self {
+        let (mut x, mut y) = (buf.computed_x(index), buf.computed_y(index));
+        let mut current = buf.parent_index(index);
+        while let Some(parent) = current {
+            x += buf.computed_x(parent);
+            y += buf.computed_y(parent);
+            current = buf.parent_index(parent);
+        }
+        Self { x, y, w: buf.computed_width(index), h: buf.computed_height(index) }
+    }
+
+    fn right(&self) -> f32 {
+        self.x + self.w
+    }
+
+    fn bottom(&self) -> f32 {
+        self.y + self.h
+    }
+}
+
+/// Distance between two spans on one axis; 0 when they overlap.
+fn span_gap(a_start: f32, a_end: f32, b_start: f32, b_end: f32) -> f32 {
+    (b_start - a_end).max(a_start - b_end).max(0.0)
+}
+
+/// How far `to` is from `from` moving in `dir`, lower is nearer.
+/// `None` if `to` does not lie in that direction (its center must be past
+/// `from`'s center).
+fn spatial_score(from: Rect, to: Rect, dir: FocusDirection) -> Option<f32> {
+    let (from_cx, from_cy) = (from.x + from.w / 2.0, from.y + from.h / 2.0);
+    let (to_cx, to_cy) = (to.x + to.w / 2.0, to.y + to.h / 2.0);
+
+    let (ahead, gap, cross) = match dir {
+        FocusDirection::Right => (to_cx > from_cx, to.x - from.right(), span_gap(from.y, from.bottom(), to.y, to.bottom())),
+        FocusDirection::Left => (to_cx < from_cx, from.x - to.right(), span_gap(from.y, from.bottom(), to.y, to.bottom())),
+        FocusDirection::Down => (to_cy > from_cy, to.y - from.bottom(), span_gap(from.x, from.right(), to.x, to.right())),
+        FocusDirection::Up => (to_cy < from_cy, from.y - to.bottom(), span_gap(from.x, from.right(), to.x, to.right())),
+    };
+    ahead.then_some(gap.max(0.0) + CROSS_AXIS_WEIGHT * cross)
+}
 
 // =============================================================================
 // Focus State
@@ -16,10 +94,19 @@ pub struct FocusManager {
     /// Currently focused component index. -1 = no focus.
     focused_index: i32,
     /// Focus trap stack: each entry is a container index whose children
-    /// are the only valid focus targets.
+    /// are the only valid focus targets. Takes precedence over containers
+    /// flagged `FLAG_FOCUS_TRAP`.
     trap_stack: Vec<usize>,
-    /// Focus history for save/restore (max 10).
-    history: Vec<i32>,
+    /// Focus history for save/restore (max 10); None = nothing was focused.
+    /// Refs, so a component destroyed meanwhile isn't mistaken for
+    /// whatever reused its index.
+    history: Vec<Option<ComponentRef>>,
+    /// Most recently created visible container flagged `FLAG_FOCUS_TRAP`,
+    /// found by `track_traps` when TS changes the tree.
+    flagged_trap: Option<ComponentRef>,
+    /// Traps focus was pulled into, outermost first. Each saved a history
+    /// entry, restored when it goes.
+    entered_traps: Vec<usize>,
 }
 
 impl FocusManager {
@@ -28,6 +115,8 @@ impl FocusManager {
             focused_index: -1,
             trap_stack: Vec::new(),
             history: Vec::new(),
+            flagged_trap: None,
+            entered_traps: Vec::new(),
         }
     }
 
@@ -119,26 +208,50 @@ impl FocusManager {
         self.focus(buf, prev);
     }
 
+    /// Focus the geometrically nearest focusable in `dir` (arrow-key
+    /// navigation for grid-like layouts). With nothing focused, focuses the
+    /// first focusable. Respects focus traps.
+    /// Returns true if focus moved.
+    pub fn focus_direction(&mut self, buf: &SharedBuffer, dir: FocusDirection) -> bool {
+        let focusables = self.get_focusable_list(buf);
+        let Some(current) = self.focused().filter(|c| focusables.contains(c)) else {
+            let Some(&first) = focusables.first() else { return false };
+            self.focus(buf, first);
+            return true;
+        };
+
+        let from = Rect::of(buf, current);
+        let nearest = focusables
+            .iter()
+            .filter(|&&i| i != current)
+            .filter_map(|&i| spatial_score(from, Rect::of(buf, i), dir).map(|score| (score, i)))
+            // Ties go to the earlier node in tab order
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        match nearest {
+            Some((_, next)) => {
+                self.focus(buf, next);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Get sorted list of focusable component indices.
     fn get_focusable_list(&self, buf: &SharedBuffer) -> Vec<usize> {
         let node_count = buf.node_count();
+        let trap = self.active_trap(buf);
         let mut focusables: Vec<(i32, usize)> = Vec::new();
 
         for i in 0..node_count {
-            if buf.component_type(i) == 0 || !buf.visible(i) {
+            if !can_focus(buf, i) {
                 continue;
             }
-            if !buf.focusable(i) {
-                // Check implicit focusable: scrollable boxes
-                if buf.is_scrollable(i) {
-                    // Implicit focusable (scroll boxes)
-                } else {
-                    continue;
-                }
-            }
 
             // Check focus trap
-            if !self.is_in_focus_trap(buf, i) {
+            if let Some(trap) = trap
+                && !is_within(buf, i, trap)
+            {
                 continue;
             }
 
@@ -150,22 +263,72 @@ impl FocusManager {
         focusables.into_iter().map(|(_, idx)| idx).collect()
     }
 
-    /// Check if a component is within the current focus trap.
-    fn is_in_focus_trap(&self, buf: &SharedBuffer, index: usize) -> bool {
-        if self.trap_stack.is_empty() {
-            return true; // No trap active
+    /// The focus trap in effect: the top of the trap stack, else the flagged
+    /// container `track_traps` last found, while it's still there and visible.
+    pub fn active_trap(&self, buf: &SharedBuffer) -> Option<usize> {
+        if let Some(&trap) = self.trap_stack.last() {
+            return Some(trap);
         }
+        self.flagged_trap
+            .and_then(|trap| buf.resolve(trap))
+            .filter(|&i| buf.visible(i) && buf.traps_focus(i))
+    }
 
-        let trap = *self.trap_stack.last().unwrap();
-        // Walk up parent chain to see if index is a descendant of trap container
-        let mut current = Some(index);
-        while let Some(idx) = current {
-            if idx == trap {
-                return true;
+    /// Find the flagged trap: the most recently created visible container
+    /// flagged `FLAG_FOCUS_TRAP` (TS `trapFocus`), so a menu opened from a
+    /// dialog traps inside the dialog. Scans every node, so it runs when TS
+    /// changes the tree, not per key.
+    pub fn track_traps(&mut self, buf: &SharedBuffer) {
+        self.flagged_trap = (0..buf.node_count())
+            .filter(|&i| buf.component_type(i) != 0 && buf.visible(i) && buf.traps_focus(i))
+            .max_by_key(|&i| buf.creation_seq(i))
+            .map(|i| buf.component_ref(i));
+    }
+
+    /// Keep focus inside the active trap: pull it in (to the trap's first
+    /// focusable, else nowhere) when a trap appears around something else,
+    /// and hand it back when the trap goes. Keys then never reach what an
+    /// overlay covers.
+    pub fn enforce_trap(&mut self, buf: &SharedBuffer) {
+        let active = self.active_trap(buf);
+
+        // Traps that closed, innermost first: a trap opened inside the
+        // active one is still open
+        let mut closed = 0;
+        while let Some(&entered) = self.entered_traps.last() {
+            if active.is_some_and(|trap| is_within(buf, trap, entered)) {
+                break;
+            }
+            self.entered_traps.pop();
+            closed += 1;
+        }
+        if closed > 0 {
+            // Back to where the outermost of them was opened
+            self.history.truncate(self.history.len().saturating_sub(closed - 1));
+            match active {
+                // Within the trap still open, else to its first focusable below
+                Some(trap) => {
+                    let saved = self.history.pop().flatten().and_then(|r| buf.resolve(r));
+                    if let Some(idx) = saved.filter(|&i| can_focus(buf, i) && is_within(buf, i, trap)) {
+                        self.focus(buf, idx);
+                    }
+                }
+                None => self.restore_focus(buf),
             }
-            current = buf.parent_index(idx);
         }
-        false
+
+        let Some(trap) = active else { return };
+        if self.focused().is_some_and(|f| is_within(buf, f, trap)) {
+            return;
+        }
+        if self.entered_traps.last() != Some(&trap) {
+            self.save_focus(buf);
+            self.entered_traps.push(trap);
+        }
+        match self.get_focusable_list(buf).first() {
+            Some(&first) => self.focus(buf, first),
+            None => self.blur(buf),
+        }
     }
 
     /// Push a focus trap (restrict focus to children of container).
@@ -179,22 +342,27 @@ impl FocusManager {
     }
 
     /// Save current focus to history.
-    pub fn save_focus(&mut self) {
+    pub fn save_focus(&mut self, buf: &SharedBuffer) {
         if self.history.len() >= 10 {
             self.history.remove(0);
         }
-        self.history.push(self.focused_index);
+        self.history.push(self.focused().map(|idx| buf.component_ref(idx)));
     }
 
-    /// Restore focus from history.
+    /// Restore focus from history: the latest entry still focusable,
+    /// skipping components that are gone (or whose index was reused) and
+    /// hidden. Nothing is focused if none is left.
     pub fn restore_focus(&mut self, buf: &SharedBuffer) {
-        if let Some(idx) = self.history.pop() {
-            if idx >= 0 {
-                self.focus(buf, idx as usize);
-            } else {
-                self.blur(buf);
+        if self.history.is_empty() {
+            return;
+        }
+        while let Some(saved) = self.history.pop() {
+            if let Some(idx) = saved.and_then(|r| buf.resolve(r)).filter(|&i| can_focus(buf, i)) {
+                self.focus(buf, idx);
+                return;
             }
         }
+        self.blur(buf);
     }
 
     /// Focus a component by click (focus-on-click).
@@ -215,6 +383,24 @@ impl FocusManager {
     }
 }
 
+/// Whether `index` can take focus: a visible node flagged focusable, or a
+/// scroll box (implicitly focusable).
+fn can_focus(buf: &SharedBuffer, index: usize) -> bool {
+    buf.component_type(index) != 0 && buf.visible(index) && (buf.focusable(index) || buf.is_scrollable(index))
+}
+
+/// Whether `index` is `container` or one of its descendants.
+fn is_within(buf: &SharedBuffer, index: usize, container: usize) -> bool {
+    let mut current = Some(index);
+    while let Some(idx) = current {
+        if idx == container {
+            return true;
+        }
+        current = buf.parent_index(idx);
+    }
+    false
+}
+
 impl Default for FocusManager {
     fn default() -> Self {
         Self::new()
@@ -224,6 +410,67 @@ impl Default for FocusManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared_buffer::test_support::TestBuffer;
+    use crate::shared_buffer::{COMPONENT_BOX, FLAG_FOCUSABLE, FLAG_FOCUS_TRAP, N_GENERATION, N_INTERACTION_FLAGS, N_PARENT_INDEX};
+
+    /// Root box (0) holding a 2×2 grid of focusable 8×2 cells:
+    /// 1 at (0,0), 2 at (10,0), 3 at (0,3), 4 at (10,3).
+    fn create_grid() -> TestBuffer {
+        let nodes = 5;
+        let buf = TestBuffer::new(nodes, 64);
+        for i in 0..nodes {
+            buf.node(i, COMPONENT_BOX, if i == 0 { -1 } else { 0 });
+            if i > 0 {
+                buf.put_u8(i, N_INTERACTION_FLAGS, FLAG_FOCUSABLE);
+            }
+        }
+        buf.set_computed_width(0, 18.0);
+        buf.set_computed_height(0, 5.0);
+        for (i, (x, y)) in [(0.0, 0.0), (10.0, 0.0), (0.0, 3.0), (10.0, 3.0)].into_iter().enumerate() {
+            buf.set_computed_x(i + 1, x);
+            buf.set_computed_y(i + 1, y);
+            buf.set_computed_width(i + 1, 8.0);
+            buf.set_computed_height(i + 1, 2.0);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_focus_direction_moves_through_grid() {
+        let buf = create_grid();
+        let mut fm = FocusManager::new();
+
+        // Nothing focused: first arrow lands on the first focusable
+        assert!(fm.focus_direction(&buf, FocusDirection::Right));
+        assert_eq!(fm.focused(), Some(1));
+
+        assert!(fm.focus_direction(&buf, FocusDirection::Right));
+        assert_eq!(fm.focused(), Some(2));
+        assert!(fm.focus_direction(&buf, FocusDirection::Down));
+        assert_eq!(fm.focused(), Some(4));
+        assert!(fm.focus_direction(&buf, FocusDirection::Left));
+        assert_eq!(fm.focused(), Some(3));
+        assert!(fm.focus_direction(&buf, FocusDirection::Up));
+        assert_eq!(fm.focused(), Some(1));
+
+        // Edge of the grid: focus stays put
+        assert!(!fm.focus_direction(&buf, FocusDirection::Up));
+        assert!(!fm.focus_direction(&buf, FocusDirection::Left));
+        assert_eq!(fm.focused(), Some(1));
+    }
+
+    #[test]
+    fn test_spatial_score_prefers_aligned_neighbour() {
+        let from = Rect { x: 0.0, y: 0.0, w: 4.0, h: 1.0 };
+        let same_row_far = Rect { x: 12.0, y: 0.0, w: 4.0, h: 1.0 };
+        let next_row_near = Rect { x: 5.0, y: 6.0, w: 4.0, h: 1.0 };
+        let behind = Rect { x: -6.0, y: 0.0, w: 4.0, h: 1.0 };
+
+        let far = spatial_score(from, same_row_far, FocusDirection::Right).unwrap();
+        let near = spatial_score(from, next_row_near, FocusDirection::Right).unwrap();
+        assert!(far < near);
+        assert_eq!(spatial_score(from, behind, FocusDirection::Right), None);
+    }
 
     #[test]
     fn test_focus_manager_new() {
@@ -243,15 +490,109 @@ mod tests {
         assert_eq!(fm.trap_stack[0], 5);
     }
 
+    #[test]
+    fn test_flagged_container_traps_tab() {
+        let buf = create_grid();
+        let mut fm = FocusManager::new();
+        // Cell 3 becomes a container holding cell 4
+        buf.put_i32(4, N_PARENT_INDEX, 3);
+        buf.put_u8(3, N_INTERACTION_FLAGS, FLAG_FOCUSABLE | FLAG_FOCUS_TRAP);
+        fm.track_traps(&buf);
+
+        assert_eq!(fm.active_trap(&buf), Some(3));
+        fm.focus_next(&buf);
+        assert_eq!(fm.focused(), Some(3));
+        fm.focus_next(&buf);
+        assert_eq!(fm.focused(), Some(4));
+        fm.focus_next(&buf);
+        assert_eq!(fm.focused(), Some(3));
+
+        // Focus outside a trap is pulled in, and handed back when it goes
+        fm.focus(&buf, 1);
+        fm.enforce_trap(&buf);
+        assert_eq!(fm.focused(), Some(3));
+        buf.put_u8(3, N_INTERACTION_FLAGS, FLAG_FOCUSABLE);
+        fm.enforce_trap(&buf);
+        assert_eq!(fm.focused(), Some(1));
+
+        // A released trap stops trapping before the next scan, even if
+        // its index is reused by a flagged node
+        buf.put_u8(3, N_INTERACTION_FLAGS, FLAG_FOCUSABLE | FLAG_FOCUS_TRAP);
+        fm.track_traps(&buf);
+        assert_eq!(fm.active_trap(&buf), Some(3));
+        buf.put_u32(3, N_GENERATION, 1);
+        assert_eq!(fm.active_trap(&buf), None);
+
+        // An explicit trap wins
+        fm.push_trap(0);
+        assert_eq!(fm.active_trap(&buf), Some(0));
+    }
+
     #[test]
     fn test_focus_history() {
+        let buf = create_grid();
         let mut fm = FocusManager::new();
-        fm.focused_index = 5;
-        fm.save_focus();
-        fm.focused_index = 10;
-        fm.save_focus();
+        fm.focused_index = 3;
+        fm.save_focus(&buf);
+        fm.focused_index = 4;
+        fm.save_focus(&buf);
         assert_eq!(fm.history.len(), 2);
-        assert_eq!(fm.history[0], 5);
-        assert_eq!(fm.history[1], 10);
+        assert_eq!(fm.history[0].map(|r| r.index), Some(3));
+        assert_eq!(fm.history[1].map(|r| r.index), Some(4));
+
+        // 4 was released (and its index maybe reused): skipped for 3
+        buf.put_u32(4, N_GENERATION, 1);
+        fm.restore_focus(&buf);
+        assert_eq!(fm.focused(), Some(3));
+        assert!(fm.history.is_empty());
+
+        // Nothing left that resolves: nothing focused
+        fm.save_focus(&buf);
+        buf.put_u32(3, N_GENERATION, 1);
+        fm.restore_focus(&buf);
+        assert_eq!(fm.focused(), None);
+    }
+
+    #[test]
+    fn test_closing_stacked_traps_skips_an_unmounted_return_target() {
+        let buf = create_grid();
+        let mut fm = FocusManager::new();
+        let trap = |i: usize, on: bool| {
+            let flags = if on { FLAG_FOCUSABLE | FLAG_FOCUS_TRAP } else { FLAG_FOCUSABLE };
+            buf.put_u8(i, N_INTERACTION_FLAGS, flags);
+        };
+        // Cell 2 is the outer trap, holding cell 3, the inner one
+        buf.put_i32(3, N_PARENT_INDEX, 2);
+        fm.focus(&buf, 1);
+        fm.save_focus(&buf);
+        fm.focus(&buf, 4);
+
+        // 4 opens the outer trap, which opens the inner one
+        trap(2, true);
+        fm.track_traps(&buf);
+        fm.enforce_trap(&buf);
+        assert_eq!(fm.focused(), Some(2));
+        trap(3, true);
+        fm.track_traps(&buf);
+        fm.enforce_trap(&buf);
+        assert_eq!(fm.focused(), Some(3));
+
+        // Closing the inner trap hands focus back inside the outer one
+        trap(3, false);
+        fm.track_traps(&buf);
+        fm.enforce_trap(&buf);
+        assert_eq!(fm.focused(), Some(2));
+
+        // The outer's return target went meanwhile: the entry before it
+        trap(3, true);
+        fm.track_traps(&buf);
+        fm.enforce_trap(&buf);
+        buf.put_u32(4, N_GENERATION, 1);
+        trap(2, false);
+        trap(3, false);
+        fm.track_traps(&buf);
+        fm.enforce_trap(&buf);
+        assert_eq!(fm.focused(), Some(1));
+        assert!(fm.history.is_empty());
     }
 }
@@ -3,14 +3,28 @@
 //! Routes parsed key events through the dispatch chain:
 //! 1. Ctrl+C → EXIT event
 //! 2. Non-press events → ring buffer for TS
-//! 3. Tab / Shift+Tab → focus navigation (consumed)
-//! 4. Focused input → text editing (insert, delete, cursor move)
-//! 5. Key event → ring buffer for TS onKey handlers
-//! 6. Framework defaults (arrow scroll, page scroll, home/end)
+//! 3. Tab / Shift+Tab → accept input ghost text, else to TS inside a box
+//!    capturing Tab (`FLAG_CAPTURE_TAB`), else focus navigation (consumed)
+//! 4. Focused input → text editing (insert, delete, cursor move), unless
+//!    TS edits it itself (INPUT_SCRIPTED) or it is disabled (`FLAG_DISABLED`)
+//! 5. App keymap (`state::keymap`) → an action that handled it, or a chord
+//!    held for its next key (consumed)
+//! 6. Key event → ring buffer for TS onKey handlers, targeting the focused
+//!    node; TS bubbles it up the ancestors, then to fallback handlers
+//! 7. Spatial navigation (`SPATIAL_NAVIGATION`): arrows move focus by direction
+//! 8. Framework defaults (arrow scroll, page scroll, home/end)
+//!
+//! Before step 2, focus is pulled into an active focus trap
+//! (`FocusManager::enforce_trap`), so an open overlay gets the key.
+//!
+//! Steps 7 and 8 use the keys of the keymap preset (`input::keymap`), so
+//! under vim `hjkl` scroll and move focus like the arrows.
 
-use crate::shared_buffer::{SharedBuffer, EventType};
+use crate::shared_buffer::{ConfigFlags, SharedBuffer, EventType, INPUT_SCRIPTED};
 use super::parser::{KeyEvent, KeyCode, Modifier, KeyState};
-use super::focus::FocusManager;
+use super::focus::{FocusDirection, FocusManager};
+use super::keymap::{NavAction, NavChord};
+use crate::state::keymap as app_keymap;
 use super::text_edit::TextEditor;
 use super::scroll::ScrollManager;
 
@@ -24,6 +38,7 @@ pub fn dispatch_key(
     focus: &mut FocusManager,
     editor: &mut TextEditor,
     scroll: &mut ScrollManager,
+    chord: &mut NavChord,
     key: &KeyEvent,
 ) -> bool {
     // 1. Ctrl+C → EXIT
@@ -32,6 +47,9 @@ pub fn dispatch_key(
         return true;
     }
 
+    // An overlay trapping focus takes it before anything below sees the key
+    focus.enforce_trap(buf);
+
     // 2. Non-press events → send to TS for handling
     if key.state != KeyState::Press {
         let target = focus.focused().unwrap_or(0);
@@ -39,8 +57,22 @@ pub fn dispatch_key(
         return false;
     }
 
-    // 3. Tab / Shift+Tab → focus navigation
+    // 3. Tab / Shift+Tab → accept ghost text, else focus navigation
     if key.code == KeyCode::Tab {
+        chord.clear();
+        if !key.modifiers.contains(Modifier::SHIFT)
+            && let Some(focused) = focus.focused()
+            && buf.component_type(focused) == COMP_INPUT
+            && editor.accept_ghost(buf, focused)
+        {
+            return true;
+        }
+        if let Some(focused) = focus.focused()
+            && captures_tab(buf, focused)
+        {
+            push_key_event(buf, focused as u16, &key.code, key.modifiers.bits());
+            return true;
+        }
         if key.modifiers.contains(Modifier::SHIFT) {
             focus.focus_previous(buf);
         } else {
@@ -49,67 +81,140 @@ pub fn dispatch_key(
         return true;
     }
 
-    // 4. Focused input → text editing
-    if let Some(focused) = focus.focused() {
-        let comp_type = buf.component_type(focused);
-        if comp_type == COMP_INPUT {
-            if editor.handle_key(buf, focused, key) {
-                return true;
-            }
-        }
+    // 4. Focused input → text editing (not while disabled)
+    if let Some(focused) = focus.focused()
+        && buf.component_type(focused) == COMP_INPUT
+        && buf.input_flags(focused) & INPUT_SCRIPTED == 0
+        && !buf.is_disabled(focused)
+        && editor.handle_key(buf, focused, key)
+    {
+        chord.clear();
+        return true;
+    }
+
+    // 5. App keymap (state::keymap) → an action took it, or a chord is pending
+    if app_keymap::dispatch(buf, focus.focused(), key) {
+        chord.clear();
+        return true;
     }
 
-    // 5. Write key event to ring buffer (TS dispatches onKey)
+    // 6. Write key event to ring buffer (TS dispatches onKey)
     // Default to root (0) if nothing is focused
     let target = focus.focused().unwrap_or(0);
     push_key_event(buf, target as u16, &key.code, key.modifiers.bits());
 
-    // 6. Framework defaults (arrow scroll, page scroll, home/end)
+    // Framework navigation under the keymap preset, unless TS took it over
+    let nav = chord.action(buf.keymap_preset(), key)
+        .filter(|action| buf.keymap_overrides() & action.bit() == 0);
+
+    // 7. Spatial navigation: a focused scroll box scrolls first and hands
+    // focus on at its edge
+    if let Some(dir) = nav.and_then(nav_direction)
+        && key.modifiers.is_empty()
+        && buf.config_flags().contains(ConfigFlags::SPATIAL_NAVIGATION)
+    {
+        let (dx, dy) = dir.delta();
+        let scrolled = focus.focused().is_some_and(|f| scroll.scroll_by(buf, f, dx, dy, false));
+        if !scrolled && focus.focus_direction(buf, dir)
+            && let Some(next) = focus.focused()
+        {
+            scroll.scroll_into_view(buf, next);
+        }
+        return true;
+    }
+
+    // 8. Framework defaults (scroll, paging, top/bottom)
     // Keyboard scroll does NOT chain to parent (only mouse wheel chains)
-    if let Some(focused) = focus.focused() {
-        match &key.code {
-            KeyCode::Up => {
-                scroll.scroll_by(buf, focused, 0, -1, false);
-                return true;
-            }
-            KeyCode::Down => {
-                scroll.scroll_by(buf, focused, 0, 1, false);
-                return true;
-            }
-            KeyCode::Left => {
-                scroll.scroll_by(buf, focused, -1, 0, false);
-                return true;
-            }
-            KeyCode::Right => {
-                scroll.scroll_by(buf, focused, 1, 0, false);
-                return true;
-            }
-            KeyCode::PageUp => {
-                let viewport_h = buf.computed_height(focused) as i32;
-                scroll.scroll_by(buf, focused, 0, -viewport_h, false);
-                return true;
-            }
-            KeyCode::PageDown => {
-                let viewport_h = buf.computed_height(focused) as i32;
-                scroll.scroll_by(buf, focused, 0, viewport_h, false);
-                return true;
-            }
-            KeyCode::Home => {
+    if let Some(action) = nav
+        && let Some(focused) = focus.focused()
+    {
+        let viewport_w = buf.computed_width(focused) as i32;
+        let viewport_h = buf.computed_height(focused) as i32;
+        match action {
+            NavAction::ScrollUp => scroll.scroll_by(buf, focused, 0, -1, false),
+            NavAction::ScrollDown => scroll.scroll_by(buf, focused, 0, 1, false),
+            NavAction::ScrollLeft => scroll.scroll_by(buf, focused, -1, 0, false),
+            NavAction::ScrollRight => scroll.scroll_by(buf, focused, 1, 0, false),
+            NavAction::PageUp => scroll.scroll_by(buf, focused, 0, -viewport_h, false),
+            NavAction::PageDown => scroll.scroll_by(buf, focused, 0, viewport_h, false),
+            NavAction::HalfPageUp => scroll.scroll_by(buf, focused, 0, -viewport_h / 2, false),
+            NavAction::HalfPageDown => scroll.scroll_by(buf, focused, 0, viewport_h / 2, false),
+            NavAction::Top => {
                 scroll.scroll_to(buf, focused, 0, 0);
-                return true;
+                true
             }
-            KeyCode::End => {
+            NavAction::Bottom => {
                 let max_y = buf.max_scroll_y(focused) as i32;
                 scroll.scroll_to(buf, focused, 0, max_y);
-                return true;
+                true
             }
-            _ => {}
-        }
+            NavAction::PageLeft => scroll.scroll_by(buf, focused, -viewport_w, 0, false),
+            NavAction::PageRight => scroll.scroll_by(buf, focused, viewport_w, 0, false),
+            NavAction::LeftEdge => {
+                scroll.scroll_to(buf, focused, 0, buf.scroll_y(focused));
+                true
+            }
+            NavAction::RightEdge => {
+                let max_x = buf.max_scroll_x(focused) as i32;
+                scroll.scroll_to(buf, focused, max_x, buf.scroll_y(focused));
+                true
+            }
+        };
+        return true;
     }
 
     false
 }
 
+/// Whether the focused node or one of its ancestors keeps Tab for itself.
+fn captures_tab(buf: &SharedBuffer, focused: usize) -> bool {
+    let mut node = Some(focused);
+    while let Some(i) = node {
+        if buf.captures_tab(i) {
+            return true;
+        }
+        node = buf.parent_index(i);
+    }
+    false
+}
+
+/// Spatial focus direction for a line-scroll action.
+fn nav_direction(action: NavAction) -> Option<FocusDirection> {
+    match action {
+        NavAction::ScrollUp => Some(FocusDirection::Up),
+        NavAction::ScrollDown => Some(FocusDirection::Down),
+        NavAction::ScrollLeft => Some(FocusDirection::Left),
+        NavAction::ScrollRight => Some(FocusDirection::Right),
+        _ => None,
+    }
+}
+
+/// Whether `key` is the app-level screenshot binding (`H_SCREENSHOT_KEY`).
+/// The engine intercepts it before `dispatch_key`, so it never reaches TS.
+pub fn is_screenshot_key(buf: &SharedBuffer, key: &KeyEvent) -> bool {
+    is_binding(key, buf.screenshot_key(), buf.screenshot_modifiers())
+}
+
+/// Whether `key` toggles the performance HUD (`H_PERF_HUD_KEY`). Intercepted
+/// like the screenshot key.
+pub fn is_perf_hud_key(buf: &SharedBuffer, key: &KeyEvent) -> bool {
+    is_binding(key, buf.perf_hud_key(), buf.perf_hud_modifiers())
+}
+
+/// Whether `key` toggles the component inspector (`H_INSPECTOR_KEY`).
+/// Intercepted like the screenshot key.
+pub fn is_inspector_key(buf: &SharedBuffer, key: &KeyEvent) -> bool {
+    is_binding(key, buf.inspector_key(), buf.inspector_modifiers())
+}
+
+/// Press of a header key binding (keycode 0 = unbound).
+fn is_binding(key: &KeyEvent, binding: u32, modifiers: u8) -> bool {
+    binding != 0
+        && key.state == KeyState::Press
+        && key_code_to_u32(&key.code) == binding
+        && key.modifiers.bits() == modifiers
+}
+
 /// Push a key event to the SharedBuffer event ring.
 fn push_key_event(buf: &SharedBuffer, target: u16, code: &KeyCode, modifiers: u8) {
     let keycode = key_code_to_u32(code);
@@ -152,4 +257,68 @@ mod tests {
         assert_eq!(key_code_to_u32(&KeyCode::Enter), 13);
         assert_eq!(key_code_to_u32(&KeyCode::F(5)), 0x2005);
     }
+
+    #[test]
+    fn test_capturing_box_keeps_tab() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::{COMPONENT_BOX, FLAG_CAPTURE_TAB, FLAG_FOCUSABLE, N_INTERACTION_FLAGS};
+
+        // Root (0) holding focusable boxes 1 and 2; 1 holds focusable 3
+        let buf = TestBuffer::new(4, 64);
+        for (i, parent) in [-1i32, 0, 0, 1].into_iter().enumerate() {
+            buf.node(i, COMPONENT_BOX, parent);
+            if i > 0 {
+                buf.put_u8(i, N_INTERACTION_FLAGS, FLAG_FOCUSABLE);
+            }
+        }
+        let mut focus = FocusManager::new();
+        let mut editor = TextEditor::new();
+        let mut scroll = ScrollManager::new();
+        let mut chord = NavChord::default();
+        let tab = KeyEvent { code: KeyCode::Tab, modifiers: Modifier::empty(), state: KeyState::Press };
+
+        focus.focus(&buf, 3);
+        assert!(dispatch_key(&buf, &mut focus, &mut editor, &mut scroll, &mut chord, &tab));
+        assert_ne!(focus.focused(), Some(3));
+
+        // With the flag on an ancestor, Tab stays put and reaches TS
+        buf.put_u8(1, N_INTERACTION_FLAGS, FLAG_FOCUSABLE | FLAG_CAPTURE_TAB);
+        focus.focus(&buf, 3);
+        while buf.pop_event().is_some() {}
+        assert!(dispatch_key(&buf, &mut focus, &mut editor, &mut scroll, &mut chord, &tab));
+        assert_eq!(focus.focused(), Some(3));
+        let event = buf.pop_event().expect("Tab passed on");
+        assert_eq!(event[0], EventType::Key as u8);
+        assert_eq!(u16::from_le_bytes([event[2], event[3]]), 3);
+    }
+
+    #[test]
+    fn test_left_right_scroll_a_column() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::{COMPONENT_BOX, FLAG_FOCUSABLE, H_CONFIG_FLAGS, N_INTERACTION_FLAGS, N_IS_SCROLLABLE, N_MAX_SCROLL_X};
+
+        // A focused box with 10 columns of overflow
+        let buf = TestBuffer::new(1, 64);
+        buf.node(0, COMPONENT_BOX, -1);
+        buf.put_u8(0, N_INTERACTION_FLAGS, FLAG_FOCUSABLE);
+        buf.put_u8(0, N_IS_SCROLLABLE, 1);
+        buf.put_f32(0, N_MAX_SCROLL_X, 10.0);
+        let mut focus = FocusManager::new();
+        let mut editor = TextEditor::new();
+        let mut scroll = ScrollManager::new();
+        let mut chord = NavChord::default();
+        let key = |code, modifiers| KeyEvent { code, modifiers, state: KeyState::Press };
+        focus.focus(&buf, 0);
+
+        let mut press = |k: KeyEvent| dispatch_key(&buf, &mut focus, &mut editor, &mut scroll, &mut chord, &k);
+        assert!(press(key(KeyCode::Right, Modifier::empty())));
+        assert!(press(key(KeyCode::Right, Modifier::empty())));
+        assert!(press(key(KeyCode::Left, Modifier::empty())));
+        assert_eq!(buf.scroll_x(0), 1);
+
+        // Under spatial navigation Shift keeps the arrows on the scroll
+        buf.put_header_u32(H_CONFIG_FLAGS, ConfigFlags::SPATIAL_NAVIGATION.bits());
+        assert!(press(key(KeyCode::Right, Modifier::SHIFT)));
+        assert_eq!((buf.scroll_x(0), buf.scroll_y(0)), (2, 0));
+    }
 }
@@ -0,0 +1,300 @@
+//! Key presets — which keys drive framework navigation and input editing.
+//!
+//! The preset (`H_KEYMAP_PRESET`) is chosen at mount. Every preset keeps the
+//! standard keys; vim and emacs add their own on top:
+//!
+//! | Action            | Standard      | Vim        | Emacs         |
+//! |-------------------|---------------|------------|---------------|
+//! | scroll ↑ ↓ ← →    | arrows        | k j h l    | Ctrl+P N B F  |
+//! | page up / down    | PgUp / PgDn   | Ctrl+B / F | Alt+V / Ctrl+V |
+//! | half page up/down | —             | Ctrl+U / D | —             |
+//! | top / bottom      | Home / End    | gg / G     | Alt+< / Alt+> |
+//! | page left / right | Shift+PgUp / Shift+PgDn | — | — |
+//! | left / right edge | Shift+Home / Shift+End  | — | — |
+//!
+//! ← → scroll a column at a time. Under spatial navigation the bare arrows
+//! move focus once the focused box can't scroll, and Shift+← → only scroll.
+//!
+//! Vim's `gg` is a chord (`NavChord`): a lone `g` waits for the next key.
+//!
+//! Input editing: vim inputs stay in insert mode and add Ctrl+H / Ctrl+U;
+//! emacs adds the readline keys (Ctrl+A/E/F/B/D/H/K/U, Alt+F/B).
+//!
+//! Apps take a navigation action over through the TS keymap registry, which
+//! sets its bit in `H_KEYMAP_OVERRIDES`; Rust then leaves those keys to TS.
+
+use crate::shared_buffer::KeymapPreset;
+use super::parser::{KeyCode, KeyEvent, Modifier};
+
+/// Framework navigation on the focused scrollable.
+///
+/// Order matches the TS keymap's `NAV_ACTIONS` (bit = discriminant).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum NavAction {
+    ScrollUp = 0,
+    ScrollDown = 1,
+    ScrollLeft = 2,
+    ScrollRight = 3,
+    PageUp = 4,
+    PageDown = 5,
+    HalfPageUp = 6,
+    HalfPageDown = 7,
+    Top = 8,
+    Bottom = 9,
+    PageLeft = 10,
+    PageRight = 11,
+    /// Scroll to the left edge, keeping the vertical offset
+    LeftEdge = 12,
+    /// Scroll to the right edge, keeping the vertical offset
+    RightEdge = 13,
+}
+
+impl NavAction {
+    /// Bit in `H_KEYMAP_OVERRIDES`.
+    #[inline]
+    pub fn bit(self) -> u16 {
+        1 << self as u8
+    }
+}
+
+/// Navigation chords under way: vim's `gg` (top). The key after a lone
+/// `g` completes the chord or drops it; there's no timeout.
+#[derive(Debug, Default)]
+pub struct NavChord {
+    /// The last key press to reach navigation was a lone `g`
+    after_g: bool,
+}
+
+impl NavChord {
+    /// Navigation action of `key`, a press reaching navigation, counting
+    /// the key before it.
+    pub fn action(&mut self, preset: KeymapPreset, key: &KeyEvent) -> Option<NavAction> {
+        let g = preset == KeymapPreset::Vim && key.code == KeyCode::Char('g') && key.modifiers.is_empty();
+        let completes = g && self.after_g;
+        self.after_g = g && !completes;
+        if completes {
+            Some(NavAction::Top)
+        } else {
+            nav_action(preset, key)
+        }
+    }
+
+    /// Drop a chord under way: the key went somewhere else.
+    pub fn clear(&mut self) {
+        self.after_g = false;
+    }
+}
+
+/// Input editing on the focused input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditAction {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Home,
+    End,
+    DeleteBackward,
+    DeleteForward,
+    DeleteWordBackward,
+    DeleteWordForward,
+    /// Delete from the cursor to the end of the value
+    KillToEnd,
+    /// Delete from the start of the value to the cursor
+    KillToStart,
+}
+
+/// Navigation action bound to `key` under `preset`.
+pub fn nav_action(preset: KeymapPreset, key: &KeyEvent) -> Option<NavAction> {
+    // Shift turns the paging keys sideways
+    if key.modifiers == Modifier::SHIFT {
+        let sideways = match key.code {
+            KeyCode::PageUp => Some(NavAction::PageLeft),
+            KeyCode::PageDown => Some(NavAction::PageRight),
+            KeyCode::Home => Some(NavAction::LeftEdge),
+            KeyCode::End => Some(NavAction::RightEdge),
+            _ => None,
+        };
+        if sideways.is_some() {
+            return sideways;
+        }
+    }
+
+    let standard = match key.code {
+        KeyCode::Up => Some(NavAction::ScrollUp),
+        KeyCode::Down => Some(NavAction::ScrollDown),
+        KeyCode::Left => Some(NavAction::ScrollLeft),
+        KeyCode::Right => Some(NavAction::ScrollRight),
+        KeyCode::PageUp => Some(NavAction::PageUp),
+        KeyCode::PageDown => Some(NavAction::PageDown),
+        KeyCode::Home => Some(NavAction::Top),
+        KeyCode::End => Some(NavAction::Bottom),
+        _ => None,
+    };
+    if standard.is_some() {
+        return standard;
+    }
+
+    let KeyCode::Char(ch) = key.code else { return None };
+    // Shift is implied by the character itself ('G', '<')
+    let mods = key.modifiers - Modifier::SHIFT;
+    match preset {
+        KeymapPreset::Standard => None,
+        KeymapPreset::Vim if mods.is_empty() => match ch {
+            'k' => Some(NavAction::ScrollUp),
+            'j' => Some(NavAction::ScrollDown),
+            'h' => Some(NavAction::ScrollLeft),
+            'l' => Some(NavAction::ScrollRight),
+            'G' => Some(NavAction::Bottom),
+            _ => None,
+        },
+        KeymapPreset::Vim if mods == Modifier::CTRL => match ch {
+            'b' => Some(NavAction::PageUp),
+            'f' => Some(NavAction::PageDown),
+            'u' => Some(NavAction::HalfPageUp),
+            'd' => Some(NavAction::HalfPageDown),
+            _ => None,
+        },
+        KeymapPreset::Emacs if mods == Modifier::CTRL => match ch {
+            'p' => Some(NavAction::ScrollUp),
+            'n' => Some(NavAction::ScrollDown),
+            'b' => Some(NavAction::ScrollLeft),
+            'f' => Some(NavAction::ScrollRight),
+            'v' => Some(NavAction::PageDown),
+            _ => None,
+        },
+        KeymapPreset::Emacs if mods == Modifier::ALT => match ch {
+            'v' => Some(NavAction::PageUp),
+            '<' => Some(NavAction::Top),
+            '>' => Some(NavAction::Bottom),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Editing action bound to `key` under `preset`. Plain characters, Enter
+/// and Escape are not editing actions.
+pub fn edit_action(preset: KeymapPreset, key: &KeyEvent) -> Option<EditAction> {
+    let ctrl = key.modifiers.contains(Modifier::CTRL);
+    let alt = key.modifiers.contains(Modifier::ALT);
+    let word_mod = ctrl || alt;
+
+    let standard = match key.code {
+        KeyCode::Char('w') if ctrl => Some(EditAction::DeleteWordBackward),
+        KeyCode::Char('d') if alt => Some(EditAction::DeleteWordForward),
+        KeyCode::Backspace if word_mod => Some(EditAction::DeleteWordBackward),
+        KeyCode::Delete if word_mod => Some(EditAction::DeleteWordForward),
+        KeyCode::Left if word_mod => Some(EditAction::WordLeft),
+        KeyCode::Right if word_mod => Some(EditAction::WordRight),
+        KeyCode::Backspace => Some(EditAction::DeleteBackward),
+        KeyCode::Delete => Some(EditAction::DeleteForward),
+        KeyCode::Left => Some(EditAction::Left),
+        KeyCode::Right => Some(EditAction::Right),
+        KeyCode::Home => Some(EditAction::Home),
+        KeyCode::End => Some(EditAction::End),
+        _ => None,
+    };
+    if standard.is_some() {
+        return standard;
+    }
+
+    let KeyCode::Char(ch) = key.code else { return None };
+    match preset {
+        KeymapPreset::Standard => None,
+        KeymapPreset::Vim if ctrl => match ch {
+            'h' => Some(EditAction::DeleteBackward),
+            'u' => Some(EditAction::KillToStart),
+            _ => None,
+        },
+        KeymapPreset::Emacs if ctrl => match ch {
+            'a' => Some(EditAction::Home),
+            'e' => Some(EditAction::End),
+            'b' => Some(EditAction::Left),
+            'f' => Some(EditAction::Right),
+            'h' => Some(EditAction::DeleteBackward),
+            'd' => Some(EditAction::DeleteForward),
+            'k' => Some(EditAction::KillToEnd),
+            'u' => Some(EditAction::KillToStart),
+            _ => None,
+        },
+        KeymapPreset::Emacs if alt => match ch {
+            'b' => Some(EditAction::WordLeft),
+            'f' => Some(EditAction::WordRight),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::parser::KeyState;
+
+    fn key(code: KeyCode, modifiers: Modifier) -> KeyEvent {
+        KeyEvent { code, modifiers, state: KeyState::Press }
+    }
+
+    #[test]
+    fn test_presets_extend_standard_navigation() {
+        let down = key(KeyCode::Down, Modifier::empty());
+        let j = key(KeyCode::Char('j'), Modifier::empty());
+        let ctrl_d = key(KeyCode::Char('d'), Modifier::CTRL);
+        for preset in [KeymapPreset::Standard, KeymapPreset::Vim, KeymapPreset::Emacs] {
+            assert_eq!(nav_action(preset, &down), Some(NavAction::ScrollDown));
+        }
+        assert_eq!(nav_action(KeymapPreset::Standard, &j), None);
+        assert_eq!(nav_action(KeymapPreset::Vim, &j), Some(NavAction::ScrollDown));
+        assert_eq!(nav_action(KeymapPreset::Vim, &ctrl_d), Some(NavAction::HalfPageDown));
+        // Kitty reports Shift with the uppercase letter; legacy input doesn't
+        assert_eq!(nav_action(KeymapPreset::Vim, &key(KeyCode::Char('G'), Modifier::SHIFT)), Some(NavAction::Bottom));
+        assert_eq!(nav_action(KeymapPreset::Emacs, &key(KeyCode::Char('n'), Modifier::CTRL)), Some(NavAction::ScrollDown));
+        assert_eq!(nav_action(KeymapPreset::Emacs, &key(KeyCode::Char('>'), Modifier::ALT)), Some(NavAction::Bottom));
+        assert_eq!(nav_action(KeymapPreset::Emacs, &j), None);
+    }
+
+    #[test]
+    fn test_vim_gg_chord() {
+        let g = key(KeyCode::Char('g'), Modifier::empty());
+        let j = key(KeyCode::Char('j'), Modifier::empty());
+        let mut chord = NavChord::default();
+        assert_eq!(chord.action(KeymapPreset::Vim, &g), None);
+        assert_eq!(chord.action(KeymapPreset::Vim, &g), Some(NavAction::Top));
+        // Completed: the next g starts over
+        assert_eq!(chord.action(KeymapPreset::Vim, &g), None);
+        // Another key in between drops it
+        assert_eq!(chord.action(KeymapPreset::Vim, &j), Some(NavAction::ScrollDown));
+        assert_eq!(chord.action(KeymapPreset::Vim, &g), None);
+        chord.clear();
+        assert_eq!(chord.action(KeymapPreset::Vim, &g), None);
+        // Only vim has it
+        assert_eq!(NavChord::default().action(KeymapPreset::Standard, &g), None);
+        assert_eq!(chord.action(KeymapPreset::Standard, &g), None);
+    }
+
+    #[test]
+    fn test_shift_turns_paging_sideways() {
+        let shift = |code| key(code, Modifier::SHIFT);
+        assert_eq!(nav_action(KeymapPreset::Standard, &shift(KeyCode::PageDown)), Some(NavAction::PageRight));
+        assert_eq!(nav_action(KeymapPreset::Vim, &shift(KeyCode::PageUp)), Some(NavAction::PageLeft));
+        assert_eq!(nav_action(KeymapPreset::Emacs, &shift(KeyCode::Home)), Some(NavAction::LeftEdge));
+        assert_eq!(nav_action(KeymapPreset::Standard, &shift(KeyCode::End)), Some(NavAction::RightEdge));
+        assert_eq!(nav_action(KeymapPreset::Standard, &key(KeyCode::End, Modifier::empty())), Some(NavAction::Bottom));
+    }
+
+    #[test]
+    fn test_edit_actions() {
+        let ctrl_a = key(KeyCode::Char('a'), Modifier::CTRL);
+        assert_eq!(edit_action(KeymapPreset::Standard, &ctrl_a), None);
+        assert_eq!(edit_action(KeymapPreset::Emacs, &ctrl_a), Some(EditAction::Home));
+        assert_eq!(edit_action(KeymapPreset::Vim, &key(KeyCode::Char('u'), Modifier::CTRL)), Some(EditAction::KillToStart));
+        assert_eq!(
+            edit_action(KeymapPreset::Vim, &key(KeyCode::Left, Modifier::ALT)),
+            Some(EditAction::WordLeft)
+        );
+        // Plain characters are inserted, not editing actions
+        assert_eq!(edit_action(KeymapPreset::Emacs, &key(KeyCode::Char('a'), Modifier::empty())), None);
+    }
+}
@@ -0,0 +1,358 @@
+//! Masked input editing.
+//!
+//! Number, Date and Pattern inputs (`InputType`) reject keystrokes that
+//! don't fit before they reach the text:
+//!
+//! - Number: digits, plus a leading `-` (INPUT_SIGNED) and one `.`
+//!   (INPUT_DECIMAL). Range checks are left to TS, which knows min/max.
+//! - Pattern: a fixed layout from the node's mask — `9` digit, `a` letter,
+//!   `*` either, anything else a literal the editor types itself
+//!   (`(999) 999-9999`).
+//! - Date: a pattern whose mask is the format (`DD/MM/YYYY`, `YYYY-MM-DD`);
+//!   `D`, `M` and `Y` are digit slots. Left/Right jump between segments and
+//!   Up/Down step the segment under the cursor, wrapping within its range.
+//!
+//! Layout masks edit the *raw* value — the characters in slots — and lay it
+//! back out, so deleting in the middle pulls later slots left across the
+//! literals instead of leaving the layout broken.
+
+use crate::shared_buffer::{SharedBuffer, InputType, INPUT_DECIMAL, INPUT_SIGNED};
+
+/// What a slot in a layout mask accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Digit,
+    Letter,
+    Alphanumeric,
+}
+
+impl Slot {
+    fn accepts(self, ch: char) -> bool {
+        match self {
+            Self::Digit => ch.is_ascii_digit(),
+            Self::Letter => ch.is_alphabetic(),
+            Self::Alphanumeric => ch.is_alphanumeric(),
+        }
+    }
+}
+
+/// How an input constrains its text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mask<'a> {
+    Number { signed: bool, decimal: bool },
+    Layout { mask: &'a str, date: bool },
+}
+
+impl<'a> Mask<'a> {
+    /// The mask of input `index`, None for free text.
+    pub fn of(buf: &'a SharedBuffer, index: usize) -> Option<Self> {
+        let flags = buf.input_flags(index);
+        match buf.input_type(index) {
+            InputType::Number => Some(Self::Number {
+                signed: flags & INPUT_SIGNED != 0,
+                decimal: flags & INPUT_DECIMAL != 0,
+            }),
+            InputType::Pattern | InputType::Date => {
+                let mask = buf.input_mask(index);
+                (!mask.is_empty()).then_some(Self::Layout { mask, date: buf.input_type(index) == InputType::Date })
+            }
+            _ => None,
+        }
+    }
+
+    /// `text` with `ch` typed at `cursor`, and the new cursor — None if the
+    /// mask rejects it.
+    pub fn insert(&self, text: &str, cursor: usize, ch: char) -> Option<(String, usize)> {
+        match *self {
+            Self::Number { signed, decimal } => {
+                let negative = text.starts_with('-');
+                let ok = match ch {
+                    '0'..='9' => !(negative && cursor == 0),
+                    '-' => signed && cursor == 0 && !negative,
+                    '.' => decimal && !text.contains('.') && !(negative && cursor == 0),
+                    _ => false,
+                };
+                if !ok {
+                    return None;
+                }
+                let mut chars: Vec<char> = text.chars().collect();
+                let cursor = cursor.min(chars.len());
+                chars.insert(cursor, ch);
+                Some((chars.into_iter().collect(), cursor + 1))
+            }
+            Self::Layout { mask, date } => {
+                let slots = slots(mask, date);
+                let mut raw = raw(mask, date, text);
+                let at = raw_index(mask, date, cursor).min(raw.len());
+                if raw.len() >= slots.len() {
+                    return None;
+                }
+                raw.insert(at, ch);
+                if !fits(&slots, &raw) {
+                    return None;
+                }
+                let formatted = layout(mask, date, &raw);
+                let cursor = slot_position(mask, date, at + 1).min(formatted.chars().count());
+                Some((formatted, cursor))
+            }
+        }
+    }
+
+    /// `text` without chars `[start, end)`, and the new cursor — None when
+    /// the removal would leave a layout mask holding what its slots reject.
+    pub fn delete(&self, text: &str, start: usize, end: usize) -> Option<(String, usize)> {
+        match *self {
+            Self::Number { .. } => {
+                let kept: String = text
+                    .chars()
+                    .enumerate()
+                    .filter(|&(i, _)| i < start || i >= end)
+                    .map(|(_, ch)| ch)
+                    .collect();
+                Some((kept, start))
+            }
+            Self::Layout { mask, date } => {
+                let mut raw = raw(mask, date, text);
+                let from = raw_index(mask, date, start).min(raw.len());
+                let to = raw_index(mask, date, end).min(raw.len());
+                if from >= to {
+                    return None;
+                }
+                raw.drain(from..to);
+                if !fits(&slots(mask, date), &raw) {
+                    return None;
+                }
+                let formatted = layout(mask, date, &raw);
+                let cursor = slot_position(mask, date, from).min(formatted.chars().count());
+                Some((formatted, cursor))
+            }
+        }
+    }
+
+    /// Position of the slot before `cursor` (for Backspace), skipping
+    /// literals. None at the start.
+    pub fn previous_slot(&self, cursor: usize) -> Option<usize> {
+        match *self {
+            Self::Number { .. } => cursor.checked_sub(1),
+            Self::Layout { mask, date } => {
+                let n = raw_index(mask, date, cursor);
+                (n > 0).then(|| slot_position(mask, date, n - 1))
+            }
+        }
+    }
+}
+
+// =============================================================================
+// LAYOUT MASKS
+// =============================================================================
+
+fn slot_of(ch: char, date: bool) -> Option<Slot> {
+    if date {
+        return matches!(ch, 'D' | 'M' | 'Y' | 'd' | 'm' | 'y').then_some(Slot::Digit);
+    }
+    match ch {
+        '9' => Some(Slot::Digit),
+        'a' => Some(Slot::Letter),
+        '*' => Some(Slot::Alphanumeric),
+        _ => None,
+    }
+}
+
+fn slots(mask: &str, date: bool) -> Vec<Slot> {
+    mask.chars().filter_map(|ch| slot_of(ch, date)).collect()
+}
+
+fn fits(slots: &[Slot], raw: &[char]) -> bool {
+    raw.len() <= slots.len() && raw.iter().zip(slots).all(|(&ch, slot)| slot.accepts(ch))
+}
+
+/// The characters of `text` that sit in slots
+fn raw(mask: &str, date: bool, text: &str) -> Vec<char> {
+    mask.chars()
+        .zip(text.chars())
+        .filter(|&(m, _)| slot_of(m, date).is_some())
+        .map(|(_, ch)| ch)
+        .collect()
+}
+
+/// Lay `raw` out over the mask. Literals appear once a slot after them is
+/// filled, so the text never ends in a separator nobody typed.
+fn layout(mask: &str, date: bool, raw: &[char]) -> String {
+    let mut out = String::new();
+    let mut next = raw.iter();
+    let mut pending = String::new();
+    for m in mask.chars() {
+        if slot_of(m, date).is_some() {
+            let Some(&ch) = next.next() else { break };
+            out.push_str(&pending);
+            pending.clear();
+            out.push(ch);
+        } else {
+            pending.push(m);
+        }
+    }
+    out
+}
+
+/// How many slots come before position `pos`
+fn raw_index(mask: &str, date: bool, pos: usize) -> usize {
+    mask.chars().take(pos).filter(|&m| slot_of(m, date).is_some()).count()
+}
+
+/// Position of slot `n` (the mask's length past the last slot)
+fn slot_position(mask: &str, date: bool, n: usize) -> usize {
+    mask.chars()
+        .enumerate()
+        .filter(|&(_, m)| slot_of(m, date).is_some())
+        .nth(n)
+        .map_or(mask.chars().count(), |(i, _)| i)
+}
+
+// =============================================================================
+// DATE SEGMENTS
+// =============================================================================
+
+/// Runs of one date letter: (start, end, letter)
+fn segments(mask: &str) -> Vec<(usize, usize, char)> {
+    let mut out: Vec<(usize, usize, char)> = Vec::new();
+    for (i, m) in mask.chars().enumerate() {
+        if slot_of(m, true).is_none() {
+            continue;
+        }
+        let letter = m.to_ascii_uppercase();
+        match out.last_mut() {
+            Some(last) if last.1 == i && last.2 == letter => last.1 = i + 1,
+            _ => out.push((i, i + 1, letter)),
+        }
+    }
+    out
+}
+
+/// Segment containing `cursor`, or the last one starting before it
+fn segment_at(segments: &[(usize, usize, char)], cursor: usize) -> Option<usize> {
+    segments.iter().rposition(|&(start, _, _)| start <= cursor).or((!segments.is_empty()).then_some(0))
+}
+
+/// Where Left (-1) or Right (+1) takes the cursor in a date: the start of
+/// the current segment, else the previous/next one. `len` is the text's
+/// length; the cursor never passes it.
+pub fn date_segment_move(mask: &str, len: usize, cursor: usize, direction: i32) -> usize {
+    let segs = segments(mask);
+    let Some(current) = segment_at(&segs, cursor) else { return cursor };
+    let target = if direction < 0 {
+        if cursor > segs[current].0 { segs[current].0 } else { current.checked_sub(1).map_or(0, |p| segs[p].0) }
+    } else {
+        segs.get(current + 1).map_or(segs[current].1, |next| next.0)
+    };
+    target.min(len)
+}
+
+/// Step the date segment under `cursor` by `delta`, wrapping in its range
+/// (day 1–31, month 1–12, year 0–9999, or 0–99 for two-digit years).
+/// An empty segment starts at its first (up) or last (down) value. None
+/// while earlier segments are still unfilled.
+pub fn date_segment_step(mask: &str, text: &str, cursor: usize, delta: i32) -> Option<(String, usize)> {
+    let segs = segments(mask);
+    let &(start, end, letter) = segs.get(segment_at(&segs, cursor)?)?;
+    let raw = raw(mask, true, text);
+    let from = raw_index(mask, true, start);
+    let width = end - start;
+    if raw.len() < from {
+        return None;
+    }
+
+    let (min, max) = match letter {
+        'D' => (1, 31),
+        'M' => (1, 12),
+        _ => (0, 10i64.pow(width as u32) - 1),
+    };
+    let current: String = raw.iter().skip(from).take(width).collect();
+    let value = match current.parse::<i64>() {
+        Ok(v) => (v - min + delta as i64).rem_euclid(max - min + 1) + min,
+        Err(_) if delta > 0 => min,
+        Err(_) => max,
+    };
+
+    let mut stepped = raw[..from].to_vec();
+    stepped.extend(format!("{value:0width$}").chars());
+    stepped.extend_from_slice(&raw[(from + width).min(raw.len())..]);
+    Some((layout(mask, true, &stepped), start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHONE: Mask = Mask::Layout { mask: "(999) 999-9999", date: false };
+    const DATE: Mask = Mask::Layout { mask: "DD/MM/YYYY", date: true };
+
+    fn type_all(mask: &Mask, keys: &str) -> String {
+        let mut text = String::new();
+        let mut cursor = 0;
+        for ch in keys.chars() {
+            if let Some((t, c)) = mask.insert(&text, cursor, ch) {
+                text = t;
+                cursor = c;
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn test_number_filters_keys() {
+        let int = Mask::Number { signed: false, decimal: false };
+        assert_eq!(type_all(&int, "12a-3.4"), "1234");
+
+        let real = Mask::Number { signed: true, decimal: true };
+        assert_eq!(type_all(&real, "-1.5.2-"), "-1.52");
+        // Nothing goes before the sign
+        assert_eq!(real.insert("-5", 0, '3'), None);
+    }
+
+    #[test]
+    fn test_pattern_lays_out_literals() {
+        assert_eq!(type_all(&PHONE, "555x1234567"), "(555) 123-4567");
+        assert_eq!(type_all(&PHONE, "55512345678"), "(555) 123-4567");
+        assert_eq!(type_all(&PHONE, "555"), "(555");
+
+        let code = Mask::Layout { mask: "aa-99", date: false };
+        assert_eq!(type_all(&code, "a1b23"), "ab-23");
+    }
+
+    #[test]
+    fn test_pattern_delete_pulls_slots_left() {
+        // Backspace after "(555) 1": removes the 1 and the literals it needed
+        let text = "(555) 1";
+        let start = PHONE.previous_slot(7).unwrap();
+        assert_eq!(PHONE.delete(text, start, 7), Some(("(555".into(), 4)));
+
+        // Deleting a middle digit shifts the rest across ") "
+        assert_eq!(PHONE.delete("(555) 123", 3, 4).map(|(t, _)| t), Some("(551) 23".into()));
+    }
+
+    #[test]
+    fn test_date_typing_and_cursor() {
+        assert_eq!(type_all(&DATE, "31122024"), "31/12/2024");
+        let (text, cursor) = DATE.insert("3", 1, '1').unwrap();
+        assert_eq!((text.as_str(), cursor), ("31", 2));
+        let (text, cursor) = DATE.insert("31", 2, '1').unwrap();
+        assert_eq!((text.as_str(), cursor), ("31/1", 4));
+    }
+
+    #[test]
+    fn test_date_segments() {
+        let mask = "DD/MM/YYYY";
+        assert_eq!(date_segment_move(mask, 10, 1, 1), 3);
+        assert_eq!(date_segment_move(mask, 10, 3, -1), 0);
+        assert_eq!(date_segment_move(mask, 10, 4, -1), 3);
+        assert_eq!(date_segment_move(mask, 10, 7, 1), 10);
+
+        assert_eq!(date_segment_step(mask, "31/12/2024", 0, 1), Some(("01/12/2024".into(), 0)));
+        assert_eq!(date_segment_step(mask, "31/12/2024", 4, 1), Some(("31/01/2024".into(), 3)));
+        assert_eq!(date_segment_step(mask, "31/12/2024", 8, -1), Some(("31/12/2023".into(), 6)));
+        // Empty segment starts at its range's end
+        assert_eq!(date_segment_step(mask, "05", 3, 1), Some(("05/01".into(), 3)));
+        // Month can't be set before the day
+        assert_eq!(date_segment_step(mask, "", 3, 1), None);
+    }
+}
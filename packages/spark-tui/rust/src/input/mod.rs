@@ -25,9 +25,12 @@
 pub mod parser;
 pub mod focus;
 pub mod keyboard;
+pub mod keymap;
+pub mod mask;
 pub mod mouse;
 pub mod scroll;
 pub mod text_edit;
+pub mod word;
 pub mod reader;
 
 pub use parser::{ParsedEvent, KeyEvent, MouseEvent, KeyCode, Modifier};
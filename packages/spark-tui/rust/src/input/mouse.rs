@@ -2,21 +2,42 @@
 //!
 //! Routes mouse events through:
 //! - HitGrid: O(1) lookup from (x, y) -> component_index
-//! - Hover tracking: enter/leave events
-//! - Click detection: press + release on same component
-//! - Scroll wheel: route to component under cursor
-
-use crate::shared_buffer::{SharedBuffer, EventType};
-use super::parser::{MouseEvent, MouseKind, MouseButton};
+//! - Hover tracking: enter/leave events for each node the mouse comes
+//!   into or goes out of, like the DOM's mouseenter/mouseleave
+//! - Click detection: press + release on same component; a right-click
+//!   also sends `ContextMenu`
+//! - Double-click: selects the word under the mouse in inputs
+//! - Scroll wheel: route to component under cursor; Shift+wheel and the
+//!   horizontal wheel scroll sideways
+//! - Hover payload: nearest tagged node + its region exported to the header,
+//!   announced to TS with a Hover event
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::framebuffer::HitRegion;
+use crate::layout::column_to_char;
+use crate::shared_buffer::{ComponentRef, SharedBuffer, EventType, COMPONENT_INPUT};
+use super::parser::{Modifier, MouseEvent, MouseKind, MouseButton};
 use super::focus::FocusManager;
 use super::scroll::ScrollManager;
+use super::word;
+
+/// Two clicks on the same component within this window form a double-click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
 
-/// Push a mouse event to the SharedBuffer event ring.
-fn push_mouse_event(buf: &SharedBuffer, event_type: EventType, component: u16, x: u16, y: u16, button: u8) {
+/// Push a mouse event to the SharedBuffer event ring: x, y, button, then a
+/// has-pixels flag and the pixel position for SGR-pixels terminals.
+fn push_mouse_event(buf: &SharedBuffer, event_type: EventType, component: u16, x: u16, y: u16, button: u8, pixel: Option<(u16, u16)>) {
     let mut data = [0u8; 16];
     data[0..2].copy_from_slice(&x.to_le_bytes());
     data[2..4].copy_from_slice(&y.to_le_bytes());
     data[4] = button;
+    if let Some((px, py)) = pixel {
+        data[5] = 1;
+        data[6..8].copy_from_slice(&px.to_le_bytes());
+        data[8..10].copy_from_slice(&py.to_le_bytes());
+    }
     buf.push_event(event_type, component, &data);
 }
 
@@ -28,6 +49,32 @@ fn push_scroll_event(buf: &SharedBuffer, component: u16, dx: i32, dy: i32) {
     buf.push_event(EventType::Scroll, component, &data);
 }
 
+/// Cells one wheel notch scrolls.
+const WHEEL_STEP: i32 = 3;
+
+/// Scroll delta of a wheel event. Shift turns the vertical wheel sideways,
+/// for mice without a horizontal one.
+fn wheel_delta(mouse: &MouseEvent) -> (i32, i32) {
+    let sideways = mouse.modifiers.contains(Modifier::SHIFT);
+    match mouse.kind {
+        MouseKind::ScrollUp if sideways => (-WHEEL_STEP, 0),
+        MouseKind::ScrollDown if sideways => (WHEEL_STEP, 0),
+        MouseKind::ScrollUp => (0, -WHEEL_STEP),
+        MouseKind::ScrollDown => (0, WHEEL_STEP),
+        MouseKind::ScrollLeft => (-WHEEL_STEP, 0),
+        MouseKind::ScrollRight => (WHEEL_STEP, 0),
+        _ => (0, 0),
+    }
+}
+
+/// Parent walk limit, as in TS event bubbling.
+const MAX_DEPTH: usize = 100;
+
+/// A hovered node and its ancestors, deepest first.
+fn hover_path(buf: &SharedBuffer, node: Option<usize>) -> Vec<usize> {
+    std::iter::successors(node, |&i| buf.parent_index(i)).take(MAX_DEPTH).collect()
+}
+
 // =============================================================================
 // HitGrid
 // =============================================================================
@@ -40,6 +87,8 @@ pub struct HitGrid {
     grid: Vec<i16>,
     width: u16,
     height: u16,
+    /// Screen rect each component was filled with (x, y, w, h).
+    bounds: HashMap<usize, (u16, u16, u16, u16)>,
 }
 
 impl HitGrid {
@@ -48,19 +97,45 @@ impl HitGrid {
             grid: vec![-1; width as usize * height as usize],
             width,
             height,
+            bounds: HashMap::new(),
         }
     }
 
     /// Fill a rectangle in the grid with a component index.
     pub fn fill_rect(&mut self, x: u16, y: u16, w: u16, h: u16, component_index: usize) {
+        self.fill_where(x, y, w, h, component_index, |_| true);
+    }
+
+    /// Fill a frame's hit region, over those filled before it. A region
+    /// that isn't opaque only takes cells that are empty or hold one of
+    /// its ancestors: an overlay's transparent parts leave what's beneath
+    /// clickable, while an unpainted box still catches clicks over its own
+    /// parent.
+    pub fn fill_region(&mut self, region: &HitRegion, buf: &SharedBuffer) {
+        let HitRegion { x, y, width, height, component_index, opaque } = *region;
+        if opaque {
+            return self.fill_rect(x, y, width, height, component_index);
+        }
+        let ancestors: Vec<i16> =
+            std::iter::successors(buf.parent_index(component_index), |&p| buf.parent_index(p)).map(|p| p as i16).collect();
+        self.fill_where(x, y, width, height, component_index, |cell| cell < 0 || ancestors.contains(&cell));
+    }
+
+    /// Fill the cells of a rectangle whose current value passes `takes`.
+    /// The first fill for a component records its bounds.
+    fn fill_where(&mut self, x: u16, y: u16, w: u16, h: u16, component_index: usize, takes: impl Fn(i16) -> bool) {
         let idx = component_index as i16;
+        self.bounds.entry(component_index).or_insert((x, y, w, h));
         let x2 = (x + w).min(self.width);
         let y2 = (y + h).min(self.height);
 
         for row in y..y2 {
             let row_start = row as usize * self.width as usize;
             for col in x..x2 {
-                self.grid[row_start + col as usize] = idx;
+                let cell = &mut self.grid[row_start + col as usize];
+                if takes(*cell) {
+                    *cell = idx;
+                }
             }
         }
     }
@@ -74,11 +149,17 @@ impl HitGrid {
         if idx >= 0 { Some(idx as usize) } else { None }
     }
 
+    /// Screen rect a component was last filled with.
+    pub fn bounds(&self, component_index: usize) -> Option<(u16, u16, u16, u16)> {
+        self.bounds.get(&component_index).copied()
+    }
+
     /// Clear the grid.
     pub fn clear(&mut self) {
         for cell in &mut self.grid {
             *cell = -1;
         }
+        self.bounds.clear();
     }
 
     /// Resize the grid (clears content).
@@ -98,10 +179,15 @@ impl HitGrid {
 pub struct MouseManager {
     /// Currently hovered component.
     hovered: Option<usize>,
-    /// Component that was pressed (for click detection).
-    pressed_component: Option<usize>,
+    /// Component that was pressed (for click detection). A ref, so one
+    /// destroyed before release doesn't click whatever reused its index.
+    pressed_component: Option<ComponentRef>,
     /// Button that was pressed.
     pressed_button: Option<MouseButton>,
+    /// Last click (component, time) for double-click detection.
+    last_click: Option<(ComponentRef, Instant)>,
+    /// Last hover payload published to the header (node, region).
+    hover_payload: Option<(usize, (u16, u16, u16, u16))>,
     /// The hit grid.
     pub hit_grid: HitGrid,
 }
@@ -112,6 +198,8 @@ impl MouseManager {
             hovered: None,
             pressed_component: None,
             pressed_button: None,
+            last_click: None,
+            hover_payload: None,
             hit_grid: HitGrid::new(width, height),
         }
     }
@@ -135,63 +223,76 @@ impl MouseManager {
                 self.handle_hover(buf, target);
 
                 if let Some(idx) = target {
-                    self.pressed_component = Some(idx);
+                    self.pressed_component = Some(buf.component_ref(idx));
                     self.pressed_button = Some(button);
 
                     // Set pressed state in SharedBuffer
                     buf.set_pressed(idx, true);
+                    buf.set_pressed_index(idx as i32);
 
                     // Write mouse down event
-                    push_mouse_event(buf, EventType::MouseDown, idx as u16, mouse.x, mouse.y, button as u8);
+                    push_mouse_event(buf, EventType::MouseDown, idx as u16, mouse.x, mouse.y, button as u8, mouse.pixel);
 
                     // Focus on click
                     focus.focus_by_click(buf, idx);
                 }
             }
             MouseKind::Release(button) => {
+                // Published before the events so TS reads the settled state
+                buf.set_pressed_index(-1);
                 if let Some(idx) = target {
                     // Write mouse up event
-                    push_mouse_event(buf, EventType::MouseUp, idx as u16, mouse.x, mouse.y, button as u8);
+                    push_mouse_event(buf, EventType::MouseUp, idx as u16, mouse.x, mouse.y, button as u8, mouse.pixel);
 
                     // Click detection: same component pressed and released
-                    if self.pressed_component == Some(idx)
+                    let pressed = buf.component_ref(idx);
+                    if self.pressed_component == Some(pressed)
                         && self.pressed_button == Some(button)
                     {
-                        push_mouse_event(buf, EventType::Click, idx as u16, mouse.x, mouse.y, button as u8);
+                        push_mouse_event(buf, EventType::Click, idx as u16, mouse.x, mouse.y, button as u8, mouse.pixel);
+                        // Right-click: the component's context menu
+                        if button == MouseButton::Right {
+                            push_mouse_event(buf, EventType::ContextMenu, idx as u16, mouse.x, mouse.y, button as u8, mouse.pixel);
+                        }
+
+                        let now = Instant::now();
+                        let double = button == MouseButton::Left
+                            && self.last_click.is_some_and(|(last, at)| {
+                                last == pressed && now.duration_since(at) < DOUBLE_CLICK_TIME
+                            });
+                        if double {
+                            self.select_word(buf, idx, mouse.x);
+                            self.last_click = None;
+                        } else {
+                            self.last_click = Some((pressed, now));
+                        }
                     }
                 }
 
                 // Clear pressed state
-                if let Some(prev) = self.pressed_component.take() {
+                if let Some(prev) = self.pressed_component.take().and_then(|r| buf.resolve(r)) {
                     buf.set_pressed(prev, false);
                 }
                 self.pressed_button = None;
             }
-            MouseKind::ScrollUp => {
+            MouseKind::ScrollUp | MouseKind::ScrollDown | MouseKind::ScrollLeft | MouseKind::ScrollRight => {
                 // Route to component under cursor, or focused scrollable
                 // Mouse scroll DOES chain to parent (natural UX)
-                if let Some(idx) = target {
-                    scroll.scroll_by(buf, idx, 0, -3, true);
-                    push_scroll_event(buf, idx as u16, 0, -3);
-                } else if let Some(focused) = focus.focused() {
-                    scroll.scroll_by(buf, focused, 0, -3, true);
-                    push_scroll_event(buf, focused as u16, 0, -3);
-                }
-            }
-            MouseKind::ScrollDown => {
-                // Mouse scroll DOES chain to parent (natural UX)
-                if let Some(idx) = target {
-                    scroll.scroll_by(buf, idx, 0, 3, true);
-                    push_scroll_event(buf, idx as u16, 0, 3);
-                } else if let Some(focused) = focus.focused() {
-                    scroll.scroll_by(buf, focused, 0, 3, true);
-                    push_scroll_event(buf, focused as u16, 0, 3);
+                let (dx, dy) = wheel_delta(mouse);
+                if let Some(idx) = target.or(focus.focused()) {
+                    scroll.scroll_by(buf, idx, dx, dy, true);
+                    push_scroll_event(buf, idx as u16, dx, dy);
                 }
             }
         }
     }
 
     /// Handle hover state changes (enter/leave events).
+    ///
+    /// The mouse is over a node while it's over any of its descendants, so
+    /// moving onto a child enters the child without leaving the parent.
+    /// Nodes left get `MouseLeave` deepest first, then nodes entered get
+    /// `MouseEnter` outermost first; neither bubbles.
     fn handle_hover(
         &mut self,
         buf: &SharedBuffer,
@@ -200,19 +301,80 @@ impl MouseManager {
         if target == self.hovered {
             return;
         }
+        buf.set_hovered_index(target.map_or(-1, |idx| idx as i32));
+
+        let left = hover_path(buf, self.hovered);
+        let entered = hover_path(buf, target);
 
         // Leave previous
         if let Some(prev) = self.hovered.take() {
             buf.set_hovered(prev, false);
-            push_mouse_event(buf, EventType::MouseLeave, prev as u16, 0, 0, 0);
+        }
+        for &node in left.iter().filter(|node| !entered.contains(node)) {
+            push_mouse_event(buf, EventType::MouseLeave, node as u16, 0, 0, 0, None);
         }
 
         // Enter new
         if let Some(idx) = target {
             buf.set_hovered(idx, true);
-            push_mouse_event(buf, EventType::MouseEnter, idx as u16, 0, 0, 0);
             self.hovered = Some(idx);
         }
+        for &node in entered.iter().rev().filter(|node| !left.contains(node)) {
+            push_mouse_event(buf, EventType::MouseEnter, node as u16, 0, 0, 0, None);
+        }
+
+        self.refresh_hover_payload(buf);
+    }
+
+    /// Double-click in an input: select the word under column `x`.
+    fn select_word(&self, buf: &SharedBuffer, index: usize, x: u16) {
+        if buf.component_type(index) != COMPONENT_INPUT {
+            return;
+        }
+        let Some((left, _, _, _)) = self.hit_grid.bounds(index) else {
+            return;
+        };
+
+        let content_x = left + buf.border_inset_left(index) as u16 + buf.padding_left(index) as u16;
+        // Wide characters take two columns: map the column to a char index
+        let text = buf.text(index);
+        let scroll_x = buf.scroll_x(index).max(0) as usize;
+        let scrolled = text.char_indices().nth(scroll_x).map_or("", |(byte, _)| &text[byte..]);
+        let pos = scroll_x + column_to_char(scrolled, x.saturating_sub(content_x) as usize);
+        let (start, end) = word::word_at(text, pos, buf.word_boundary(index));
+        if start < end {
+            buf.set_selection(index, start as i32, end as i32);
+            buf.set_cursor_position(index, end as i32);
+        }
+    }
+
+    /// Publish the hover payload for the current hover target.
+    ///
+    /// Walks up from the hovered node to the nearest ancestor with a hover
+    /// tag, so a tagged container exports one region for all its children.
+    /// Cleared when that ancestor isn't in the hit grid.
+    /// Only writes when the payload node or its region changed — call after
+    /// every hit grid rebuild so the region follows layout and scrolling.
+    pub fn refresh_hover_payload(&mut self, buf: &SharedBuffer) {
+        let mut node = self.hovered;
+        while let Some(idx) = node {
+            if buf.hover_tag(idx) != 0 {
+                break;
+            }
+            node = buf.parent_index(idx);
+        }
+
+        // A tagged node that drew nothing this frame has no region to export
+        let payload = node.and_then(|idx| Some((idx, self.hit_grid.bounds(idx)?)));
+        if payload == self.hover_payload {
+            return;
+        }
+        self.hover_payload = payload;
+
+        match payload {
+            Some((idx, region)) => buf.set_hover_payload(idx as i32, buf.hover_tag(idx), region),
+            None => buf.set_hover_payload(-1, 0, (0, 0, 0, 0)),
+        }
     }
 
     /// Resize the hit grid (e.g., on terminal resize).
@@ -250,6 +412,46 @@ mod tests {
         assert_eq!(grid.hit_test(2, 2), None); // Cleared after resize
     }
 
+    #[test]
+    fn test_hit_grid_bounds_tracking() {
+        let mut grid = HitGrid::new(10, 10);
+        grid.fill_rect(1, 2, 3, 4, 5);
+        assert_eq!(grid.bounds(5), Some((1, 2, 3, 4)));
+        assert_eq!(grid.bounds(6), None);
+
+        grid.clear();
+        assert_eq!(grid.bounds(5), None);
+    }
+
+    #[test]
+    fn test_enter_and_leave_each_node_once() {
+        use crate::shared_buffer::test_support::TestBuffer;
+
+        // 0 holds 1 and 2, 1 holds 3
+        let buf = TestBuffer::new(4, 0);
+        for (node, parent) in [(0, -1), (1, 0), (2, 0), (3, 1)] {
+            buf.set_parent_index(node, parent);
+        }
+        let mut mouse = MouseManager::new(4, 1);
+        for (x, node) in [(0, 3), (1, 1), (2, 2), (3, 0)] {
+            mouse.hit_grid.fill_rect(x, 0, 1, 1, node);
+        }
+        let mut hover = |x: u16| {
+            mouse.handle_hover(&buf, mouse.hit_grid.hit_test(x, 0));
+            std::iter::from_fn(|| buf.pop_event())
+                .map(|slot| (EventType::from(slot[0]), u16::from_le_bytes([slot[2], slot[3]])))
+                .collect::<Vec<_>>()
+        };
+
+        // Onto the innermost node: enters it and everything around it
+        assert_eq!(hover(0), vec![(EventType::MouseEnter, 0), (EventType::MouseEnter, 1), (EventType::MouseEnter, 3)]);
+        // Out to its parent: leaves only the child
+        assert_eq!(hover(1), vec![(EventType::MouseLeave, 3)]);
+        // Across to a sibling: the shared parent stays entered
+        assert_eq!(hover(2), vec![(EventType::MouseLeave, 1), (EventType::MouseEnter, 2)]);
+        assert_eq!(hover(3), vec![(EventType::MouseLeave, 2)]);
+    }
+
     #[test]
     fn test_hit_grid_bounds() {
         let grid = HitGrid::new(10, 10);
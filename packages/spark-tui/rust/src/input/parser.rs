@@ -3,8 +3,14 @@
 //! Parses raw stdin bytes into structured events:
 //! - CSI sequences (Arrow keys, Home, End, Insert, Delete, PageUp/Down, F1-F12)
 //! - SS3 sequences (F1-F4, alternate encodings)
-//! - SGR mouse (button, position, modifiers, press/release)
-//! - Kitty keyboard protocol (codepoint, modifiers, state)
+//! - Mouse: SGR (1006), SGR-pixels (1016), urxvt (1015) and X10 encodings,
+//!   buttons 1-3 and 8-11 (back/forward), wheel, motion, modifiers
+//! - DECRPM private mode reports (`CSI ? Ps ; Pm $ y`)
+//! - OSC 10/11 color reports (`OSC 11 ; rgb:RRRR/GGGG/BBBB ST`)
+//! - Color scheme change notifications (`CSI ? 997 ; Ps n`, mode 2031)
+//! - Kitty keyboard protocol (codepoint, modifiers, event type, associated
+//!   text — the composed character of a dead key or compose sequence)
+//! - UTF-8 text, including composed characters split across reads
 //! - Alt+key (ESC + char)
 //! - Control keys (bytes 0-31)
 //!
@@ -22,12 +28,33 @@ pub enum ParsedEvent {
     Key(KeyEvent),
     Mouse(MouseEvent),
     Resize(u16, u16),
+    /// Window-ops report `CSI 6 ; h ; w t` — cell size in pixels (width, height)
+    CellPixels(u16, u16),
+    /// Window-ops report `CSI 4 ; h ; w t` — text area in pixels (width, height)
+    WindowPixels(u16, u16),
+    /// DECRPM reply `CSI ? Ps ; Pm $ y` — private mode `Ps` is
+    /// 0 unrecognized, 1 set, 2 reset, 3 permanently set, 4 permanently reset
+    ModeReport(u16, u8),
+    /// OSC 10/11 reply — a terminal color as 8-bit RGB
+    ColorReport(TerminalColor, [u8; 3]),
+    /// `CSI ? 997 ; Ps n` — the terminal switched between its light and dark
+    /// palettes (mode 2031); the colors themselves need asking again
+    ColorSchemeChanged,
     FocusGained,
     FocusLost,
     Paste(String),
     None,
 }
 
+/// Which terminal color an OSC color report is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalColor {
+    /// OSC 10: default foreground
+    Foreground,
+    /// OSC 11: default background
+    Background,
+}
+
 /// A key event.
 #[derive(Debug, Clone, PartialEq)]
 pub struct KeyEvent {
@@ -78,13 +105,15 @@ bitflags::bitflags! {
     }
 }
 
-/// A mouse event.
+/// A mouse event. `x`/`y` are 0-indexed cells.
 #[derive(Debug, Clone, PartialEq)]
 pub struct MouseEvent {
     pub kind: MouseKind,
     pub x: u16,
     pub y: u16,
     pub modifiers: Modifier,
+    /// 0-indexed pixel position, from terminals reporting SGR-pixels (1016)
+    pub pixel: Option<(u16, u16)>,
 }
 
 /// Mouse event kind.
@@ -95,14 +124,22 @@ pub enum MouseKind {
     Move,
     ScrollUp,
     ScrollDown,
+    /// Horizontal wheel or tilt (buttons 6 and 7)
+    ScrollLeft,
+    ScrollRight,
 }
 
-/// Mouse button.
+/// Mouse button. `Back`..`Button11` are X11 buttons 8-11 (button code
+/// bit 128); 8 and 9 are the side buttons browsers use for back/forward.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MouseButton {
     Left,
     Middle,
     Right,
+    Back,
+    Forward,
+    Button10,
+    Button11,
 }
 
 // =============================================================================
@@ -112,11 +149,19 @@ pub enum MouseButton {
 /// Input parser state machine.
 pub struct InputParser {
     buf: Vec<u8>,
+    /// Cell size in pixels while SGR-pixels (1016) reporting is on
+    pixel_cell: Option<(u16, u16)>,
 }
 
 impl InputParser {
     pub fn new() -> Self {
-        Self { buf: Vec::with_capacity(64) }
+        Self { buf: Vec::with_capacity(64), pixel_cell: None }
+    }
+
+    /// SGR mouse reports are in pixels (mode 1016 on) and converted with
+    /// this cell size, or in cells (`None`).
+    pub fn set_pixel_mouse(&mut self, cell: Option<(u16, u16)>) {
+        self.pixel_cell = cell.filter(|&(w, h)| w > 0 && h > 0);
     }
 
     /// Parse a byte sequence into events.
@@ -145,9 +190,22 @@ impl InputParser {
     }
 
     /// Flush pending bytes as raw key events (timeout expired).
+    ///
+    /// A UTF-8 character still missing continuation bytes stays buffered:
+    /// IME and compose output can arrive split across reads, and its lead
+    /// byte alone is not a key.
+    /// An OSC reply still missing its terminator is dropped rather than
+    /// typed out.
     pub fn flush_pending(&mut self) -> Vec<ParsedEvent> {
         let mut events = Vec::new();
+        if self.buf.starts_with(b"\x1b]") && self.buf.get(2).is_some_and(u8::is_ascii_digit) {
+            self.buf.clear();
+            return events;
+        }
         while !self.buf.is_empty() {
+            if utf8_partial(&self.buf) {
+                break;
+            }
             let byte = self.buf.remove(0);
             events.push(ParsedEvent::Key(KeyEvent {
                 code: KeyCode::Char(byte as char),
@@ -193,7 +251,7 @@ impl InputParser {
             0x20..=0x7E => {
                 let ch = first as char;
                 self.consume(1);
-                ParseResult::Event(key(KeyCode::Char(ch), Modifier::NONE))
+                ParseResult::Event(char_key(ch, Modifier::NONE))
             }
             // UTF-8 multi-byte
             0x80..=0xFF => self.parse_utf8(),
@@ -214,11 +272,13 @@ impl InputParser {
             b'[' => self.parse_csi(),
             // SS3: ESC O
             b'O' => self.parse_ss3(),
+            // OSC: ESC ] digit (a lone ESC ] is Alt+])
+            b']' if self.buf.get(2).is_some_and(u8::is_ascii_digit) => self.parse_osc(),
             // Alt+char: ESC + printable
             0x20..=0x7E => {
                 let ch = self.buf[1] as char;
                 self.consume(2);
-                ParseResult::Event(key(KeyCode::Char(ch), Modifier::ALT))
+                ParseResult::Event(char_key(ch, Modifier::ALT))
             }
             // ESC ESC → Alt+Escape
             0x1B => {
@@ -233,6 +293,45 @@ impl InputParser {
         }
     }
 
+    /// OSC reply: `ESC ] Ps ; Pt` terminated by BEL or ST (`ESC \`). CAN or
+    /// SUB cancels it, and one running past `OSC_MAX_LEN` is no reply of
+    /// ours: both are dropped. An unterminated one waits for more input,
+    /// up to the flush.
+    fn parse_osc(&mut self) -> ParseResult {
+        let mut end = 2;
+        let terminator = loop {
+            if end > OSC_MAX_LEN {
+                self.consume(end);
+                return ParseResult::Event(ParsedEvent::None);
+            }
+            match self.buf.get(end) {
+                None => return ParseResult::Incomplete,
+                Some(0x18 | 0x1A) => {
+                    self.consume(end + 1);
+                    return ParseResult::Event(ParsedEvent::None);
+                }
+                Some(0x07) => break 1,
+                Some(0x1B) => match self.buf.get(end + 1) {
+                    None => return ParseResult::Incomplete,
+                    Some(b'\\') => break 2,
+                    // Cut short by another sequence: drop what came so far
+                    Some(_) => {
+                        self.consume(end);
+                        return ParseResult::Event(ParsedEvent::None);
+                    }
+                },
+                Some(_) => end += 1,
+            }
+        };
+
+        let event = std::str::from_utf8(&self.buf[2..end])
+            .ok()
+            .and_then(color_report)
+            .unwrap_or(ParsedEvent::None);
+        self.consume(end + terminator);
+        ParseResult::Event(event)
+    }
+
     fn parse_csi(&mut self) -> ParseResult {
         // Minimum: ESC [ X (3 bytes)
         if self.buf.len() < 3 {
@@ -273,17 +372,58 @@ impl InputParser {
         }
 
         let final_byte = self.buf[end];
+
+        // DECRPM: ESC [ ? Ps ; Pm $ y
+        if final_byte == b'y' && self.buf[2] == b'?' && self.buf[end - 1] == b'$' {
+            let params_str = String::from_utf8_lossy(&self.buf[3..end - 1]).to_string();
+            let mut parts = params_str.split(';').map(|s| s.parse::<u16>().unwrap_or(0));
+            let mode = parts.next().unwrap_or(0);
+            let state = parts.next().unwrap_or(0).min(u8::MAX as u16) as u8;
+            self.consume(end + 1);
+            return ParseResult::Event(ParsedEvent::ModeReport(mode, state));
+        }
+
+        // Color scheme notification: ESC [ ? 997 ; Ps n (1 dark, 2 light)
+        if final_byte == b'n' && self.buf[2..end].starts_with(b"?997;") {
+            self.consume(end + 1);
+            return ParseResult::Event(ParsedEvent::ColorSchemeChanged);
+        }
+
         let params_str = String::from_utf8_lossy(&self.buf[2..end]).to_string();
+        let consumed = end + 1;
+
+        // Kitty keyboard: final byte is 'u'
+        if final_byte == b'u' {
+            self.consume(consumed);
+            return ParseResult::Event(kitty_key(&params_str));
+        }
+
         let params: Vec<u32> = params_str
             .split(';')
             .map(|s| s.parse::<u32>().unwrap_or(0))
             .collect();
-        let consumed = end + 1;
 
-        // Kitty keyboard: final byte is 'u'
-        if final_byte == b'u' {
+        // Window-ops reports (answers to CSI 14t / 16t / 18t)
+        if final_byte == b't' {
+            self.consume(consumed);
+            let arg = |i: usize| params.get(i).copied().unwrap_or(0).min(u16::MAX as u32) as u16;
+            return ParseResult::Event(match params.first() {
+                Some(4) => ParsedEvent::WindowPixels(arg(2), arg(1)),
+                Some(6) => ParsedEvent::CellPixels(arg(2), arg(1)),
+                Some(8) => ParsedEvent::Resize(arg(2), arg(1)),
+                _ => ParsedEvent::None,
+            });
+        }
+
+        // urxvt mouse: ESC [ Cb ; Cx ; Cy M (Cb offset by 32, 1-indexed)
+        if final_byte == b'M' && params.len() == 3 {
             self.consume(consumed);
-            return self.parse_kitty_key(&params);
+            let cb = params[0].saturating_sub(32).min(u8::MAX as u32) as u16;
+            let base = cb & 3;
+            let x = params[1].saturating_sub(1).min(u16::MAX as u32) as u16;
+            let y = params[2].saturating_sub(1).min(u16::MAX as u32) as u16;
+            // Like X10, a release doesn't say which button
+            return ParseResult::Event(ParsedEvent::Mouse(mouse_event(cb, x, y, base == 3, None)));
         }
 
         let modifiers = if params.len() >= 2 && params[1] > 0 {
@@ -384,36 +524,12 @@ impl InputParser {
         let x = parts[1].saturating_sub(1); // 1-indexed → 0-indexed
         let y = parts[2].saturating_sub(1);
 
-        let mut modifiers = Modifier::NONE;
-        if cb & 4 != 0 { modifiers |= Modifier::SHIFT; }
-        if cb & 8 != 0 { modifiers |= Modifier::ALT; }
-        if cb & 16 != 0 { modifiers |= Modifier::CTRL; }
-
-        let base = cb & 3;
-        let kind = if cb & 64 != 0 {
-            // Scroll wheel
-            match base {
-                0 => MouseKind::ScrollUp,
-                1 => MouseKind::ScrollDown,
-                _ => MouseKind::ScrollUp,
-            }
-        } else if cb & 32 != 0 {
-            // Motion
-            MouseKind::Move
-        } else if is_release {
-            let button = match base { 0 => MouseButton::Left, 1 => MouseButton::Middle, _ => MouseButton::Right };
-            MouseKind::Release(button)
-        } else {
-            let button = match base { 0 => MouseButton::Left, 1 => MouseButton::Middle, _ => MouseButton::Right };
-            MouseKind::Press(button)
+        // SGR-pixels: same encoding, pixel coordinates
+        let event = match self.pixel_cell {
+            Some((cell_w, cell_h)) => mouse_event(cb, x / cell_w, y / cell_h, is_release, Some((x, y))),
+            None => mouse_event(cb, x, y, is_release, None),
         };
-
-        ParseResult::Event(ParsedEvent::Mouse(MouseEvent {
-            kind,
-            x,
-            y,
-            modifiers,
-        }))
+        ParseResult::Event(ParsedEvent::Mouse(event))
     }
 
     fn parse_x10_mouse(&mut self) -> ParseResult {
@@ -422,78 +538,26 @@ impl InputParser {
             return ParseResult::Incomplete;
         }
 
-        let cb = self.buf[3].wrapping_sub(32);
+        let cb = self.buf[3].wrapping_sub(32) as u16;
         let x = self.buf[4].wrapping_sub(33) as u16;
         let y = self.buf[5].wrapping_sub(33) as u16;
 
         self.consume(6);
 
-        let mut modifiers = Modifier::NONE;
-        if cb & 4 != 0 { modifiers |= Modifier::SHIFT; }
-        if cb & 8 != 0 { modifiers |= Modifier::ALT; }
-        if cb & 16 != 0 { modifiers |= Modifier::CTRL; }
-
-        let base = cb & 3;
-        let kind = if cb & 64 != 0 {
-            match base { 0 => MouseKind::ScrollUp, _ => MouseKind::ScrollDown }
-        } else if base == 3 {
-            MouseKind::Release(MouseButton::Left)
-        } else {
-            let button = match base { 0 => MouseButton::Left, 1 => MouseButton::Middle, _ => MouseButton::Right };
-            MouseKind::Press(button)
-        };
-
-        ParseResult::Event(ParsedEvent::Mouse(MouseEvent {
-            kind,
-            x,
-            y,
-            modifiers,
-        }))
-    }
-
-    fn parse_kitty_key(&self, params: &[u32]) -> ParseResult {
-        let codepoint = params.first().copied().unwrap_or(0);
-        let modifiers = if params.len() >= 2 { decode_modifier(params[1]) } else { Modifier::NONE };
-        let state = if params.len() >= 3 {
-            match params[2] {
-                2 => KeyState::Repeat,
-                3 => KeyState::Release,
-                _ => KeyState::Press,
-            }
-        } else {
-            KeyState::Press
-        };
-
-        let code = match codepoint {
-            9 => KeyCode::Tab,
-            13 => KeyCode::Enter,
-            27 => KeyCode::Escape,
-            127 => KeyCode::Backspace,
-            cp => {
-                if let Some(ch) = char::from_u32(cp) {
-                    KeyCode::Char(ch)
-                } else {
-                    KeyCode::Null
-                }
-            }
-        };
-
-        ParseResult::Event(ParsedEvent::Key(KeyEvent {
-            code,
-            modifiers,
-            state,
-        }))
+        // A release doesn't say which button
+        ParseResult::Event(ParsedEvent::Mouse(mouse_event(cb, x, y, cb & 3 == 3, None)))
     }
 
     fn parse_utf8(&mut self) -> ParseResult {
-        let first = self.buf[0];
-        let expected_len = if first & 0xE0 == 0xC0 { 2 }
-            else if first & 0xF0 == 0xE0 { 3 }
-            else if first & 0xF8 == 0xF0 { 4 }
-            else {
-                self.consume(1);
-                return ParseResult::None;
-            };
+        // Stray continuation byte, or a lead byte not followed by
+        // continuation bytes: no character (the caller drops the byte)
+        let Some(expected_len) = utf8_len(self.buf[0]) else {
+            return ParseResult::None;
+        };
+        let available = self.buf.len().min(expected_len);
+        if self.buf[1..available].iter().any(|&b| b & 0xC0 != 0x80) {
+            return ParseResult::None;
+        }
 
         if self.buf.len() < expected_len {
             return ParseResult::Incomplete;
@@ -530,6 +594,109 @@ enum ParseResult {
     None,
 }
 
+/// Expected length of the UTF-8 sequence led by `first`, None for ASCII and
+/// continuation bytes.
+fn utf8_len(first: u8) -> Option<usize> {
+    match first {
+        b if b & 0xE0 == 0xC0 => Some(2),
+        b if b & 0xF0 == 0xE0 => Some(3),
+        b if b & 0xF8 == 0xF0 => Some(4),
+        _ => None,
+    }
+}
+
+/// `bytes` is the start of a UTF-8 character that more bytes will complete.
+fn utf8_partial(bytes: &[u8]) -> bool {
+    utf8_len(bytes[0]).is_some_and(|len| {
+        bytes.len() < len && bytes[1..].iter().all(|&b| b & 0xC0 == 0x80)
+    })
+}
+
+/// Kitty keyboard protocol key: `CSI code[:alternates] ; mods[:event] ; text u`.
+///
+/// `text` holds the codepoints the key produced (colon-separated). When it
+/// is one character it is the key, so a dead key or compose sequence yields
+/// the composed character rather than the base key.
+fn kitty_key(params: &str) -> ParsedEvent {
+    let mut fields = params.split(';');
+    let sub = |field: Option<&str>, i: usize| -> u32 {
+        field.and_then(|f| f.split(':').nth(i)).and_then(|v| v.parse().ok()).unwrap_or(0)
+    };
+    let code_field = fields.next();
+    let mods_field = fields.next();
+    let text_field = fields.next();
+
+    let codepoint = sub(code_field, 0);
+    let modifiers = decode_modifier(sub(mods_field, 0));
+    let state = match sub(mods_field, 1) {
+        2 => KeyState::Repeat,
+        3 => KeyState::Release,
+        _ => KeyState::Press,
+    };
+
+    let mut text = text_field
+        .into_iter()
+        .flat_map(|f| f.split(':'))
+        .filter_map(|cp| cp.parse::<u32>().ok().and_then(char::from_u32));
+    let composed = match (text.next(), text.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    };
+
+    let code = match (codepoint, composed) {
+        (_, Some(ch)) => KeyCode::Char(ch),
+        (9, _) => KeyCode::Tab,
+        (13, _) => KeyCode::Enter,
+        (27, _) => KeyCode::Escape,
+        (127, _) => KeyCode::Backspace,
+        (cp, _) if KITTY_PRIVATE_USE.contains(&cp) => match kitty_functional(cp) {
+            Some(code) => code,
+            None => return ParsedEvent::None,
+        },
+        (cp, _) => char::from_u32(cp).map_or(KeyCode::Null, KeyCode::Char),
+    };
+
+    ParsedEvent::Key(KeyEvent { code, modifiers, state })
+}
+
+/// Kitty reports keys without a Unicode character in this private-use range.
+const KITTY_PRIVATE_USE: std::ops::RangeInclusive<u32> = 57344..=63743;
+
+/// The key for one of kitty's private-use codepoints: function keys F1-F35,
+/// and the keypad as the keys it types or moves like. Keys with no KeyCode
+/// (locks, Print, Pause, Menu, keypad Begin, media, modifiers like AltGr
+/// pressed on their own) are None and dropped, so they never reach an
+/// input as text or a handler as a key.
+fn kitty_functional(cp: u32) -> Option<KeyCode> {
+    const F1: u32 = 57364;
+    const F35: u32 = 57398;
+    const KP_0: u32 = 57399;
+    const KP_9: u32 = 57408;
+    Some(match cp {
+        57348 | 57425 => KeyCode::Insert,
+        57349 | 57426 => KeyCode::Delete,
+        57350 | 57417 => KeyCode::Left,
+        57351 | 57418 => KeyCode::Right,
+        57352 | 57419 => KeyCode::Up,
+        57353 | 57420 => KeyCode::Down,
+        57354 | 57421 => KeyCode::PageUp,
+        57355 | 57422 => KeyCode::PageDown,
+        57356 | 57423 => KeyCode::Home,
+        57357 | 57424 => KeyCode::End,
+        F1..=F35 => KeyCode::F((cp - F1 + 1) as u8),
+        KP_0..=KP_9 => KeyCode::Char(char::from(b'0' + (cp - KP_0) as u8)),
+        57409 => KeyCode::Char('.'),
+        57410 => KeyCode::Char('/'),
+        57411 => KeyCode::Char('*'),
+        57412 => KeyCode::Char('-'),
+        57413 => KeyCode::Char('+'),
+        57414 => KeyCode::Enter,
+        57415 => KeyCode::Char('='),
+        57416 => KeyCode::Char(','),
+        _ => return None,
+    })
+}
+
 fn key(code: KeyCode, modifiers: Modifier) -> ParsedEvent {
     ParsedEvent::Key(KeyEvent {
         code,
@@ -538,6 +705,81 @@ fn key(code: KeyCode, modifiers: Modifier) -> ParsedEvent {
     })
 }
 
+/// A character from legacy input. The byte doesn't carry Shift, but an
+/// uppercase letter was typed with it: report it as kitty does.
+fn char_key(ch: char, modifiers: Modifier) -> ParsedEvent {
+    let shift = if ch.is_ascii_uppercase() { Modifier::SHIFT } else { Modifier::NONE };
+    key(KeyCode::Char(ch), modifiers | shift)
+}
+
+/// Decode a mouse button code (the same bits in every encoding): 4/8/16
+/// shift/alt/ctrl, 32 motion, 64 wheel, 128 buttons 8-11, low two bits the
+/// button. `release` comes from the encoding (SGR `m`, legacy button 3).
+fn mouse_event(cb: u16, x: u16, y: u16, release: bool, pixel: Option<(u16, u16)>) -> MouseEvent {
+    let mut modifiers = Modifier::NONE;
+    if cb & 4 != 0 { modifiers |= Modifier::SHIFT; }
+    if cb & 8 != 0 { modifiers |= Modifier::ALT; }
+    if cb & 16 != 0 { modifiers |= Modifier::CTRL; }
+
+    let base = cb & 3;
+    let button = if cb & 128 != 0 {
+        match base { 0 => MouseButton::Back, 1 => MouseButton::Forward, 2 => MouseButton::Button10, _ => MouseButton::Button11 }
+    } else {
+        match base { 1 => MouseButton::Middle, 2 => MouseButton::Right, _ => MouseButton::Left }
+    };
+
+    let kind = if cb & 64 != 0 && cb & 128 == 0 {
+        // Scroll wheel
+        match base {
+            0 => MouseKind::ScrollUp,
+            1 => MouseKind::ScrollDown,
+            2 => MouseKind::ScrollLeft,
+            _ => MouseKind::ScrollRight,
+        }
+    } else if cb & 32 != 0 {
+        // Motion
+        MouseKind::Move
+    } else if release {
+        MouseKind::Release(button)
+    } else {
+        MouseKind::Press(button)
+    };
+
+    MouseEvent { kind, x, y, modifiers, pixel }
+}
+
+/// Longest OSC body waited on; a color report is about 30 bytes.
+const OSC_MAX_LEN: usize = 256;
+
+/// `10;rgb:…` or `11;rgb:…` → a color report.
+fn color_report(body: &str) -> Option<ParsedEvent> {
+    let mut parts = body.split(';');
+    let color = match parts.next()? {
+        "10" => TerminalColor::Foreground,
+        "11" => TerminalColor::Background,
+        _ => return None,
+    };
+    Some(ParsedEvent::ColorReport(color, rgb_spec(parts.next()?)?))
+}
+
+/// X11 color spec `rgb:R/G/B` (or `rgba:R/G/B/A`) with 1-4 hex digits per
+/// channel, scaled to 8 bits.
+fn rgb_spec(spec: &str) -> Option<[u8; 3]> {
+    let channels = spec.strip_prefix("rgb:").or_else(|| spec.strip_prefix("rgba:"))?;
+    let mut parts = channels.split('/');
+    let mut rgb = [0u8; 3];
+    for channel in &mut rgb {
+        let hex = parts.next()?;
+        if hex.is_empty() || hex.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        let max = (1u32 << (4 * hex.len())) - 1;
+        *channel = ((value * 255 + max / 2) / max) as u8;
+    }
+    Some(rgb)
+}
+
 /// Decode CSI modifier parameter (1-based).
 fn decode_modifier(param: u32) -> Modifier {
     let val = if param > 0 { param - 1 } else { 0 };
@@ -607,6 +849,14 @@ mod tests {
         assert_eq!(parse_bytes(b"\x1bx")[0], key(KeyCode::Char('x'), Modifier::ALT));
     }
 
+    #[test]
+    fn test_uppercase_letters_carry_shift() {
+        assert_eq!(parse_bytes(b"G")[0], key(KeyCode::Char('G'), Modifier::SHIFT));
+        assert_eq!(parse_bytes(b"\x1bN")[0], key(KeyCode::Char('N'), Modifier::ALT | Modifier::SHIFT));
+        // Shifted symbols have no unshifted form to tell apart
+        assert_eq!(parse_bytes(b"<")[0], key(KeyCode::Char('<'), Modifier::NONE));
+    }
+
     #[test]
     fn test_delete() {
         assert_eq!(parse_bytes(b"\x1b[3~")[0], key(KeyCode::Delete, Modifier::NONE));
@@ -649,6 +899,159 @@ mod tests {
         } else {
             panic!("Expected mouse event");
         }
+
+        // Horizontal wheel, and Shift held over the vertical one
+        let kinds: Vec<_> = parse_bytes(b"\x1b[<66;10;20M\x1b[<67;10;20M\x1b[<69;10;20M")
+            .iter()
+            .map(|event| match event {
+                ParsedEvent::Mouse(m) => (m.kind, m.modifiers),
+                _ => panic!("Expected mouse event"),
+            })
+            .collect();
+        assert_eq!(kinds, [
+            (MouseKind::ScrollLeft, Modifier::NONE),
+            (MouseKind::ScrollRight, Modifier::NONE),
+            (MouseKind::ScrollDown, Modifier::SHIFT),
+        ]);
+    }
+
+    #[test]
+    fn test_sgr_pixel_mouse() {
+        let mut parser = InputParser::new();
+        parser.set_pixel_mouse(Some((9, 18)));
+        // Pixel (100, 40) in 9×18 cells → cell (11, 2)
+        let events = parser.parse(b"\x1b[<0;101;41M");
+        let ParsedEvent::Mouse(m) = &events[0] else { panic!("Expected mouse event") };
+        assert_eq!((m.x, m.y, m.pixel), (11, 2, Some((100, 40))));
+
+        parser.set_pixel_mouse(None);
+        let ParsedEvent::Mouse(m) = &parser.parse(b"\x1b[<0;101;41M")[0] else { panic!("Expected mouse event") };
+        assert_eq!((m.x, m.y, m.pixel), (100, 40, None));
+    }
+
+    #[test]
+    fn test_legacy_mouse_encodings() {
+        // urxvt: Cb 32 = left press, 1-indexed decimal coordinates
+        let ParsedEvent::Mouse(m) = &parse_bytes(b"\x1b[32;300;5M")[0] else { panic!("Expected mouse event") };
+        assert_eq!((m.kind, m.x, m.y), (MouseKind::Press(MouseButton::Left), 299, 4));
+        let ParsedEvent::Mouse(m) = &parse_bytes(b"\x1b[35;300;5M")[0] else { panic!("Expected mouse event") };
+        assert_eq!(m.kind, MouseKind::Release(MouseButton::Left));
+
+        // X10: ctrl + right press at (0, 1)
+        let ParsedEvent::Mouse(m) = &parse_bytes(b"\x1b[M\x32\x21\x22")[0] else { panic!("Expected mouse event") };
+        assert_eq!((m.kind, m.x, m.y, m.modifiers), (MouseKind::Press(MouseButton::Right), 0, 1, Modifier::CTRL));
+    }
+
+    #[test]
+    fn test_extra_buttons() {
+        let kind = |bytes: &[u8]| match &parse_bytes(bytes)[0] {
+            ParsedEvent::Mouse(m) => m.kind,
+            other => panic!("Expected mouse event, got {:?}", other),
+        };
+        assert_eq!(kind(b"\x1b[<128;1;1M"), MouseKind::Press(MouseButton::Back));
+        assert_eq!(kind(b"\x1b[<129;1;1m"), MouseKind::Release(MouseButton::Forward));
+        assert_eq!(kind(b"\x1b[<131;1;1M"), MouseKind::Press(MouseButton::Button11));
+        // X10 button 9: 32 + 129
+        assert_eq!(kind(b"\x1b[M\xa1\x21\x21"), MouseKind::Press(MouseButton::Forward));
+    }
+
+    #[test]
+    fn test_mode_report() {
+        assert_eq!(parse_bytes(b"\x1b[?1016;2$y")[0], ParsedEvent::ModeReport(1016, 2));
+        assert_eq!(parse_bytes(b"\x1b[?1016;0$y")[0], ParsedEvent::ModeReport(1016, 0));
+    }
+
+    #[test]
+    fn test_window_ops_reports() {
+        assert_eq!(parse_bytes(b"\x1b[6;18;9t")[0], ParsedEvent::CellPixels(9, 18));
+        assert_eq!(parse_bytes(b"\x1b[4;720;1280t")[0], ParsedEvent::WindowPixels(1280, 720));
+        assert_eq!(parse_bytes(b"\x1b[8;24;80t")[0], ParsedEvent::Resize(80, 24));
+    }
+
+    #[test]
+    fn test_color_reports() {
+        assert_eq!(
+            parse_bytes(b"\x1b]11;rgb:1e1e/1e1e/2e2e\x1b\\")[0],
+            ParsedEvent::ColorReport(TerminalColor::Background, [0x1e, 0x1e, 0x2e]),
+        );
+        // BEL terminator, 8-bit channels
+        assert_eq!(
+            parse_bytes(b"\x1b]10;rgb:cd/d6/f4\x07")[0],
+            ParsedEvent::ColorReport(TerminalColor::Foreground, [0xcd, 0xd6, 0xf4]),
+        );
+        // Other OSC replies are consumed without an event
+        assert_eq!(parse_bytes(b"\x1b]4;12;rgb:8989/b4b4/fafa\x1b\\"), vec![ParsedEvent::None]);
+        // Split across reads
+        let mut parser = InputParser::new();
+        assert!(parser.parse(b"\x1b]11;rgb:ffff/").is_empty());
+        assert_eq!(
+            parser.parse(b"ffff/ffff\x1b\\"),
+            vec![ParsedEvent::ColorReport(TerminalColor::Background, [255, 255, 255])],
+        );
+        assert_eq!(parse_bytes(b"\x1b[?997;2n")[0], ParsedEvent::ColorSchemeChanged);
+        // Still Alt+]
+        assert_eq!(parse_bytes(b"\x1b]")[0], key(KeyCode::Char(']'), Modifier::ALT));
+    }
+
+    #[test]
+    fn test_unterminated_osc_is_dropped() {
+        // At the flush, without typing its bytes out
+        let mut parser = InputParser::new();
+        assert!(parser.parse(b"\x1b]11;rgb:ff").is_empty());
+        assert!(parser.flush_pending().is_empty());
+        assert!(!parser.has_pending());
+        assert_eq!(parser.parse(b"a"), vec![key(KeyCode::Char('a'), Modifier::NONE)]);
+
+        // Cancelled by CAN, keys after it kept
+        assert_eq!(parse_bytes(b"\x1b]11;rgb\x18a"), vec![ParsedEvent::None, key(KeyCode::Char('a'), Modifier::NONE)]);
+
+        // Past the longest reply
+        let mut parser = InputParser::new();
+        let mut long = b"\x1b]11;".to_vec();
+        long.resize(OSC_MAX_LEN + 8, b'0');
+        assert_eq!(parser.parse(&long).first(), Some(&ParsedEvent::None));
+        assert!(!parser.has_pending());
+    }
+
+    #[test]
+    fn test_utf8_split_across_reads() {
+        let mut parser = InputParser::new();
+        // 'é' (C3 A9) arrives in two reads; flushing between them must not
+        // turn the lead byte into a key
+        assert!(parser.parse(b"a\xC3").len() == 1);
+        assert!(parser.flush_pending().is_empty());
+        assert_eq!(parser.parse(b"\xA9"), vec![key(KeyCode::Char('é'), Modifier::NONE)]);
+
+        // A lead byte followed by ASCII, or a stray continuation byte, is
+        // dropped and the ASCII kept
+        assert_eq!(parse_bytes(b"\xC3x"), vec![key(KeyCode::Char('x'), Modifier::NONE)]);
+        assert_eq!(parse_bytes(b"\xA9x"), vec![key(KeyCode::Char('x'), Modifier::NONE)]);
+    }
+
+    #[test]
+    fn test_kitty_keys() {
+        // Plain key, Ctrl, and a release carried in the event-type subfield
+        assert_eq!(parse_bytes(b"\x1b[97u"), vec![key(KeyCode::Char('a'), Modifier::NONE)]);
+        assert_eq!(parse_bytes(b"\x1b[97;5u"), vec![key(KeyCode::Char('a'), Modifier::CTRL)]);
+        assert_eq!(
+            parse_bytes(b"\x1b[97;1:3u"),
+            vec![ParsedEvent::Key(KeyEvent {
+                code: KeyCode::Char('a'),
+                modifiers: Modifier::NONE,
+                state: KeyState::Release,
+            })]
+        );
+
+        // Dead key ´ then e: the associated text is the composed character
+        assert_eq!(parse_bytes(b"\x1b[101;1;233u"), vec![key(KeyCode::Char('é'), Modifier::NONE)]);
+
+        // Functional and keypad keys; AltGr or a media key alone is dropped
+        assert_eq!(parse_bytes(b"\x1b[57352u"), vec![key(KeyCode::Up, Modifier::NONE)]);
+        assert_eq!(parse_bytes(b"\x1b[57376u"), vec![key(KeyCode::F(13), Modifier::NONE)]);
+        assert_eq!(parse_bytes(b"\x1b[57404u"), vec![key(KeyCode::Char('5'), Modifier::NONE)]);
+        assert_eq!(parse_bytes(b"\x1b[57414u"), vec![key(KeyCode::Enter, Modifier::NONE)]);
+        assert_eq!(parse_bytes(b"\x1b[57453u"), vec![ParsedEvent::None]);
+        assert_eq!(parse_bytes(b"\x1b[57428u"), vec![ParsedEvent::None]);
     }
 
     #[test]
@@ -1,24 +1,48 @@
 //! stdin reader for raw terminal input.
 //!
-//! Reads raw bytes from stdin in a dedicated thread.
-//! Routes to the parser for escape sequence parsing.
+//! Reads raw bytes from stdin (or a session's input stream) in a
+//! dedicated thread. Routes to the parser for escape sequence parsing.
+//!
+//! On a Windows console the reader takes input records instead: with VT
+//! input enabled, key records carry the same escape sequences (keys, mouse,
+//! focus) as a unix TTY, and window records stand in for SIGWINCH.
 
+use std::any::Any;
 use std::io::{self, Read};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
+use std::time::Instant;
 use std::sync::mpsc::Sender;
 
+use crate::pipeline::session::CloseInput;
+use crate::pipeline::terminal::osc::Command;
+use crate::renderer::StyledLine;
+use crate::task::TaskId;
+
 
-/// Messages from stdin reader and wake watcher to the engine thread.
+/// Messages from stdin reader, wake watcher and task workers to the engine thread.
 pub enum StdinMessage {
-    /// Raw bytes from stdin.
-    Data(Vec<u8>),
+    /// Raw bytes from stdin, with the time they were read.
+    Data(Vec<u8>, Instant),
     /// TS wrote to SharedBuffer — wake flag detected by wake watcher.
     Wake,
-    /// Terminal was resized (SIGWINCH).
+    /// Terminal was resized (SIGWINCH, or a console window record on Windows).
     Resize(u16, u16),
-    /// stdin closed or error.
+    /// A spawned task finished (see `crate::task`).
+    TaskDone(TaskId, Box<dyn Any + Send>),
+    /// The earliest timer is due (see `crate::state::timers`).
+    Timers,
+    /// Start recording the terminal output to a cast file, or stop with
+    /// `None` (see `renderer::cast`). The outcome goes back on the sender.
+    RecordCast(Option<PathBuf>, Sender<io::Result<()>>),
+    /// Set the title, ring the bell or notify (see `pipeline::terminal::osc`).
+    Terminal(Command),
+    /// Lines to freeze into history above the live region (append mode
+    /// only; see `renderer::append`).
+    History(Vec<StyledLine>),
+    /// stdin (or a session's input) closed or error.
     Closed,
 }
 
@@ -30,6 +54,8 @@ pub enum StdinMessage {
 pub struct StdinReader {
     handle: Option<JoinHandle<()>>,
     running: Arc<AtomicBool>,
+    /// Ends a read blocked on a session's input, so `stop` can join
+    close: Option<CloseInput>,
 }
 
 impl StdinReader {
@@ -50,18 +76,113 @@ impl StdinReader {
         Ok(Self {
             handle: Some(handle),
             running,
+            close: None,
         })
     }
 
+    /// Spawn a reader on another input stream (a session's connection)
+    /// instead of stdin. Bytes go through the same messages. With `close`,
+    /// stopping the reader ends its pending read and joins the thread.
+    pub fn spawn_from(
+        input: Box<dyn Read + Send>,
+        close: Option<CloseInput>,
+        tx: Sender<StdinMessage>,
+    ) -> io::Result<Self> {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = running.clone();
+
+        let handle = thread::Builder::new()
+            .name("spark-session-input".to_string())
+            .spawn(move || {
+                Self::read_bytes(input, running_clone, tx);
+            })?;
+
+        Ok(Self {
+            handle: Some(handle),
+            running,
+            close,
+        })
+    }
+
+    #[cfg(not(windows))]
+    fn read_loop(running: Arc<AtomicBool>, tx: Sender<StdinMessage>) {
+        Self::read_bytes(io::stdin(), running, tx);
+    }
+
+    /// Console input records → byte messages and resizes.
+    #[cfg(windows)]
     fn read_loop(running: Arc<AtomicBool>, tx: Sender<StdinMessage>) {
-        let stdin = io::stdin();
+        use windows_sys::Win32::System::Console::*;
+
+        let input = unsafe { GetStdHandle(STD_INPUT_HANDLE) };
+        let mut mode = 0;
+        if unsafe { GetConsoleMode(input, &mut mode) } == 0 {
+            // Redirected stdin: plain bytes
+            return Self::read_bytes(io::stdin(), running, tx);
+        }
+
+        let mut records = [INPUT_RECORD::default(); 64];
+        // UTF-16 units of the sequences read so far (a surrogate pair can straddle two reads)
+        let mut units: Vec<u16> = Vec::new();
+        let flush = |units: &mut Vec<u16>, now: Instant| -> bool {
+            let keep = match units.last() {
+                Some(&unit) if (0xD800..0xDC00).contains(&unit) => units.pop(),
+                _ => None,
+            };
+            let sent = units.is_empty()
+                || tx.send(StdinMessage::Data(String::from_utf16_lossy(units).into_bytes(), now)).is_ok();
+            units.clear();
+            units.extend(keep);
+            sent
+        };
+
+        while running.load(Ordering::SeqCst) {
+            let mut count = 0u32;
+            if unsafe { ReadConsoleInputW(input, records.as_mut_ptr(), records.len() as u32, &mut count) } == 0 {
+                let _ = tx.send(StdinMessage::Closed);
+                break;
+            }
+            let now = Instant::now();
+
+            for record in &records[..count as usize] {
+                match record.EventType as u32 {
+                    KEY_EVENT => {
+                        let key = unsafe { record.Event.KeyEvent };
+                        let unit = unsafe { key.uChar.UnicodeChar };
+                        // Key-ups and bare modifiers carry nothing
+                        if key.bKeyDown != 0 && unit != 0 {
+                            units.extend(std::iter::repeat_n(unit, key.wRepeatCount.max(1) as usize));
+                        }
+                    }
+                    WINDOW_BUFFER_SIZE_EVENT => {
+                        // Keep ordering: input before the resize goes first
+                        if !flush(&mut units, now) {
+                            return;
+                        }
+                        if let Some((w, h)) = get_terminal_size()
+                            && tx.send(StdinMessage::Resize(w, h)).is_err()
+                        {
+                            return;
+                        }
+                    }
+                    // Focus and menu records: VT input reports focus as sequences
+                    _ => {}
+                }
+            }
+            if !flush(&mut units, now) {
+                break;
+            }
+        }
+    }
+
+    fn read_bytes(mut input: impl Read, running: Arc<AtomicBool>, tx: Sender<StdinMessage>) {
         let mut buf = [0u8; 256];
 
         while running.load(Ordering::SeqCst) {
             // Use a non-blocking approach: try to read with a timeout
             // On Unix, stdin.read() blocks until data is available.
             // We rely on the running flag + drop to stop the thread.
-            match stdin.lock().read(&mut buf) {
+            match input.read(&mut buf) {
                 Ok(0) => {
                     // EOF
                     let _ = tx.send(StdinMessage::Closed);
@@ -69,7 +190,7 @@ impl StdinReader {
                 }
                 Ok(n) => {
                     let data = buf[..n].to_vec();
-                    if tx.send(StdinMessage::Data(data)).is_err() {
+                    if tx.send(StdinMessage::Data(data, Instant::now())).is_err() {
                         break; // Channel closed
                     }
                 }
@@ -88,10 +209,17 @@ impl StdinReader {
     pub fn stop(&mut self) {
         self.running.store(false, Ordering::SeqCst);
         if let Some(handle) = self.handle.take() {
-            // Note: the thread may be blocked on stdin.read().
-            // On most systems, dropping the thread handle is sufficient.
-            // The thread will exit when the process exits or stdin closes.
-            let _ = handle;
+            match self.close.take() {
+                // A session's input: end the read and wait for the thread
+                Some(close) => {
+                    close();
+                    let _ = handle.join();
+                }
+                // Note: the thread may be blocked on stdin.read().
+                // On most systems, dropping the thread handle is sufficient.
+                // The thread will exit when the process exits or stdin closes.
+                None => drop(handle),
+            }
         }
     }
 
@@ -125,9 +253,23 @@ pub fn get_terminal_size() -> Option<(u16, u16)> {
     }
 }
 
-#[cfg(not(unix))]
+/// Get current terminal size from the console's visible window.
+#[cfg(windows)]
 pub fn get_terminal_size() -> Option<(u16, u16)> {
-    None // Windows would need different handling
+    use windows_sys::Win32::System::Console::*;
+
+    let mut info: CONSOLE_SCREEN_BUFFER_INFO = unsafe { std::mem::zeroed() };
+    if unsafe { GetConsoleScreenBufferInfo(GetStdHandle(STD_OUTPUT_HANDLE), &mut info) } == 0 {
+        return None;
+    }
+    let window = info.srWindow;
+    let (w, h) = (window.Right - window.Left + 1, window.Bottom - window.Top + 1);
+    (w > 0 && h > 0).then_some((w as u16, h as u16))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn get_terminal_size() -> Option<(u16, u16)> {
+    None
 }
 
 /// Module-level static for signal handler communication.
@@ -239,6 +381,9 @@ impl Drop for ResizeWatcher {
 }
 
 /// Dummy resize watcher for non-Unix platforms.
+///
+/// Windows has no SIGWINCH: size changes arrive as console input records,
+/// which the stdin reader turns into `Resize` messages itself.
 #[cfg(not(unix))]
 pub struct ResizeWatcher;
 
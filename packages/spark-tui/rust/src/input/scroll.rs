@@ -2,8 +2,32 @@
 //!
 //! Handles keyboard scroll (arrows, page, home/end), mouse wheel,
 //! scroll-into-view, and scroll chaining (bubble to parent at boundary).
+//! Chaining goes axis by axis: a list at its bottom hands a downward wheel
+//! to its parent while still taking sideways ones itself.
+//!
+//! Nodes with `N_SMOOTH_SCROLL` set don't jump: the clamped offset becomes a
+//! spring target (`N_SCROLL_TARGET_*`) and a ScrollAnimate event tells TS to
+//! ease the real offset there on its animation clock.
+//!
+//! Nodes with `SCROLL_STICK_BOTTOM` that are scrolled to the bottom follow
+//! it as layout grows their content (logs, chat), and nodes with
+//! `SCROLL_EVENTS` report every offset Rust commits as a ScrollChange event
+//! (so TS can window what it renders).
+//!
+//! Scrolled nodes anchor to the first child showing at the top of their
+//! viewport: when layout moves it (rows inserted or resized above), the
+//! offset moves with it, so what's being read stays put. Not while sticking
+//! to the bottom, nor with `SCROLL_NO_ANCHOR`.
+//!
+//! Nodes with `SCROLL_REACH_END` push a ReachEnd event when their offset
+//! comes within `N_REACH_END_THRESHOLD` rows of the bottom, whether scrolled
+//! there or brought there by layout (so lists can fetch more). Content that
+//! changes size while still that close reports again, which keeps a list
+//! loading until it fills its viewport.
 
-use crate::shared_buffer::SharedBuffer;
+use crate::shared_buffer::{
+    SharedBuffer, COMPONENT_NONE, SCROLL_EVENTS, SCROLL_NO_ANCHOR, SCROLL_REACH_END, SCROLL_STICK_BOTTOM,
+};
 
 /// Scroll manager.
 pub struct ScrollManager;
@@ -25,7 +49,7 @@ impl ScrollManager {
         let clamped_x = x.clamp(0, max_x.max(0));
         let clamped_y = y.clamp(0, max_y.max(0));
 
-        buf.set_scroll(index, clamped_x, clamped_y);
+        commit_scroll(buf, index, clamped_x, clamped_y);
     }
 
     /// Scroll by a delta, clamped to valid range.
@@ -34,88 +58,213 @@ impl ScrollManager {
     /// `allow_chain`: if true, scroll chains to parent when at boundary (mouse behavior).
     ///                if false, scroll stops at boundary (keyboard behavior).
     pub fn scroll_by(&self, buf: &SharedBuffer, index: usize, dx: i32, dy: i32, allow_chain: bool) -> bool {
-        if !buf.is_scrollable(index) {
-            // Try scroll chaining: walk up to find scrollable parent (only if allowed)
-            if allow_chain {
-                return self.try_chain_scroll(buf, index, dx, dy);
-            }
-            return false;
-        }
-
-        let current_x = buf.scroll_x(index);
-        let current_y = buf.scroll_y(index);
-        let max_x = buf.max_scroll_x(index) as i32;
-        let max_y = buf.max_scroll_y(index) as i32;
+        let (rest_x, rest_y) = scroll_axes(buf, index, dx, dy);
+        let changed = (rest_x, rest_y) != (dx, dy);
 
-        let new_x = (current_x + dx).clamp(0, max_x.max(0));
-        let new_y = (current_y + dy).clamp(0, max_y.max(0));
-
-        let changed = new_x != current_x || new_y != current_y;
-
-        if changed {
-            buf.set_scroll(index, new_x, new_y);
-        } else if allow_chain {
-            // At boundary: try chaining to parent (only for mouse scroll)
-            return self.try_chain_scroll(buf, index, dx, dy);
+        // At boundary: try chaining to parent (only for mouse scroll), with
+        // whichever axes this node couldn't take
+        if allow_chain && (rest_x != 0 || rest_y != 0) {
+            return self.try_chain_scroll(buf, index, rest_x, rest_y) || changed;
         }
 
         changed
     }
 
-    /// Walk up parent chain to find a scrollable parent and scroll it.
-    fn try_chain_scroll(&self, buf: &SharedBuffer, index: usize, dx: i32, dy: i32) -> bool {
+    /// Walk up parent chain to find scrollable parents and scroll them, each
+    /// axis by the nearest ancestor that can still move along it.
+    fn try_chain_scroll(&self, buf: &SharedBuffer, index: usize, mut dx: i32, mut dy: i32) -> bool {
+        let mut changed = false;
         let mut current = buf.parent_index(index);
         while let Some(parent_idx) = current {
-            if buf.is_scrollable(parent_idx) {
-                let curr_x = buf.scroll_x(parent_idx);
-                let curr_y = buf.scroll_y(parent_idx);
-                let max_x = buf.max_scroll_x(parent_idx) as i32;
-                let max_y = buf.max_scroll_y(parent_idx) as i32;
-
-                let new_x = (curr_x + dx).clamp(0, max_x.max(0));
-                let new_y = (curr_y + dy).clamp(0, max_y.max(0));
-
-                if new_x != curr_x || new_y != curr_y {
-                    buf.set_scroll(parent_idx, new_x, new_y);
-                    return true;
-                }
+            if dx == 0 && dy == 0 {
+                break;
             }
+            let rest = scroll_axes(buf, parent_idx, dx, dy);
+            changed |= rest != (dx, dy);
+            (dx, dy) = rest;
             current = buf.parent_index(parent_idx);
         }
-        false
+        changed
     }
 
     /// Scroll to make a component visible within its scrollable parent.
     pub fn scroll_into_view(&self, buf: &SharedBuffer, index: usize) {
+        // Layout positions are relative to the parent: sum them up to the
+        // nearest scrollable ancestor to place the node in its content
+        let (mut x, mut y) = (buf.computed_x(index) as i32, buf.computed_y(index) as i32);
         let mut current = buf.parent_index(index);
         while let Some(parent_idx) = current {
             if buf.is_scrollable(parent_idx) {
-                let child_y = buf.computed_y(index) as i32;
-                let child_h = buf.computed_height(index) as i32;
-                let parent_y = buf.computed_y(parent_idx) as i32;
-                let parent_h = buf.computed_height(parent_idx) as i32;
-                let scroll_y = buf.scroll_y(parent_idx);
-
-                let child_top = child_y - parent_y + scroll_y;
-                let child_bottom = child_top + child_h;
-
-                if child_top < scroll_y {
-                    // Child is above viewport
-                    let max_y = buf.max_scroll_y(parent_idx) as i32;
-                    buf.set_scroll(parent_idx, buf.scroll_x(parent_idx), child_top.clamp(0, max_y));
-                } else if child_bottom > scroll_y + parent_h {
-                    // Child is below viewport
-                    let new_y = child_bottom - parent_h;
-                    let max_y = buf.max_scroll_y(parent_idx) as i32;
-                    buf.set_scroll(parent_idx, buf.scroll_x(parent_idx), new_y.clamp(0, max_y));
+                // Both axes against where the scroll is headed
+                let (scroll_x, scroll_y) = scroll_position(buf, parent_idx);
+                let new_x = reveal(
+                    x,
+                    buf.computed_width(index) as i32,
+                    scroll_x,
+                    buf.computed_width(parent_idx) as i32,
+                    buf.max_scroll_x(parent_idx),
+                );
+                let new_y = reveal(
+                    y,
+                    buf.computed_height(index) as i32,
+                    scroll_y,
+                    buf.computed_height(parent_idx) as i32,
+                    buf.max_scroll_y(parent_idx),
+                );
+                if (new_x, new_y) != (scroll_x, scroll_y) {
+                    commit_scroll(buf, parent_idx, new_x, new_y);
                 }
                 break;
             }
+            x += buf.computed_x(parent_idx) as i32;
+            y += buf.computed_y(parent_idx) as i32;
             current = buf.parent_index(parent_idx);
         }
     }
 }
 
+/// Offset along one axis that brings `start..start + size` into a viewport
+/// `viewport` long at `offset`: unchanged if it's already in view.
+fn reveal(start: i32, size: i32, offset: i32, viewport: i32, max: f32) -> i32 {
+    let target = if start < offset {
+        start
+    } else if start + size > offset + viewport {
+        start + size - viewport
+    } else {
+        return offset;
+    };
+    target.clamp(0, (max as i32).max(0))
+}
+
+/// Where a node's scroll is headed: the pending spring target while a smooth
+/// scroll is animating, else the current offset. Deltas build on this so
+/// quick wheel ticks accumulate instead of restarting from mid-flight.
+fn scroll_position(buf: &SharedBuffer, index: usize) -> (i32, i32) {
+    if buf.smooth_scroll(index) && buf.scroll_animating(index) {
+        buf.scroll_target(index)
+    } else {
+        (buf.scroll_x(index), buf.scroll_y(index))
+    }
+}
+
+/// Scroll `index` by whichever of `dx`/`dy` it can still move along;
+/// returns the deltas it couldn't take (0 for axes it moved on).
+fn scroll_axes(buf: &SharedBuffer, index: usize, dx: i32, dy: i32) -> (i32, i32) {
+    if !buf.is_scrollable(index) {
+        return (dx, dy);
+    }
+    let (current_x, current_y) = scroll_position(buf, index);
+    let new_x = (current_x + dx).clamp(0, (buf.max_scroll_x(index) as i32).max(0));
+    let new_y = (current_y + dy).clamp(0, (buf.max_scroll_y(index) as i32).max(0));
+    if new_x != current_x || new_y != current_y {
+        commit_scroll(buf, index, new_x, new_y);
+    }
+    (if new_x == current_x { dx } else { 0 }, if new_y == current_y { dy } else { 0 })
+}
+
+/// Apply an already-clamped offset: directly, or as a spring target for TS.
+fn commit_scroll(buf: &SharedBuffer, index: usize, x: i32, y: i32) {
+    let was_near = near_end(buf, index);
+    if buf.smooth_scroll(index) {
+        buf.set_scroll_target(index, x, y);
+        buf.push_scroll_animate_event(index as u16);
+    } else {
+        buf.set_scroll(index, x, y);
+        if buf.scroll_flags(index) & SCROLL_EVENTS != 0 {
+            buf.push_scroll_change_event(index as u16, x, y);
+        }
+    }
+    reach_end(buf, index, was_near);
+}
+
+/// Whether `index`'s offset (or where it's headed) is within its reach-end
+/// threshold of the bottom.
+pub fn near_end(buf: &SharedBuffer, index: usize) -> bool {
+    let (_, y) = scroll_position(buf, index);
+    buf.max_scroll_y(index) as i32 - y <= buf.reach_end_threshold(index) as i32
+}
+
+/// Push a ReachEnd event if `index` asks for them and is now near its end
+/// but `was_near` says it wasn't.
+pub fn reach_end(buf: &SharedBuffer, index: usize, was_near: bool) {
+    if buf.scroll_flags(index) & SCROLL_REACH_END == 0 || was_near || !near_end(buf, index) {
+        return;
+    }
+    let (_, y) = scroll_position(buf, index);
+    buf.push_reach_end_event(index as u16, (buf.max_scroll_y(index) as i32 - y).max(0));
+}
+
+/// After layout set a node's new `max_scroll_x`/`max_scroll_y`: pull an
+/// offset past them back, on both axes, as content shrinks.
+pub fn clamp_scroll(buf: &SharedBuffer, index: usize) {
+    let (x, y) = (buf.scroll_x(index), buf.scroll_y(index));
+    let max_x = (buf.max_scroll_x(index) as i32).max(0);
+    let max_y = (buf.max_scroll_y(index) as i32).max(0);
+    let (clamped_x, clamped_y) = (x.clamp(0, max_x), y.clamp(0, max_y));
+    if (clamped_x, clamped_y) != (x, y) {
+        buf.set_scroll(index, clamped_x, clamped_y);
+        if buf.scroll_flags(index) & SCROLL_EVENTS != 0 {
+            buf.push_scroll_change_event(index as u16, clamped_x, clamped_y);
+        }
+    }
+}
+
+/// After layout set a node's new `max_scroll_y`: if it sticks to the bottom
+/// and was at the old one, scroll to the new one.
+pub fn stick_to_bottom(buf: &SharedBuffer, index: usize, old_max_y: f32) {
+    if buf.scroll_flags(index) & SCROLL_STICK_BOTTOM == 0 || !buf.is_scrollable(index) {
+        return;
+    }
+    let (x, y) = scroll_position(buf, index);
+    let max_y = buf.max_scroll_y(index) as i32;
+    if y >= old_max_y as i32 && y != max_y {
+        commit_scroll(buf, index, x, max_y.max(0));
+    }
+}
+
+/// Before layout moves anything: the child `index` keeps in view across the
+/// pass (the first of `children` still showing below the top of the
+/// viewport) and that child's current y. None at the top, or while sticking
+/// to the bottom.
+pub fn scroll_anchor(buf: &SharedBuffer, index: usize, children: &[usize]) -> Option<(usize, f32)> {
+    if !buf.is_scrollable(index) || buf.scroll_flags(index) & SCROLL_NO_ANCHOR != 0 {
+        return None;
+    }
+    let (_, y) = scroll_position(buf, index);
+    let at_bottom = y >= buf.max_scroll_y(index) as i32;
+    if y <= 0 || (at_bottom && buf.scroll_flags(index) & SCROLL_STICK_BOTTOM != 0) {
+        return None;
+    }
+    // Child positions include the border and padding the offset scrolls past
+    let viewport_top = (y + buf.border_inset_top(index) as i32 + buf.padding_top(index) as i32) as f32;
+    children
+        .iter()
+        .find(|&&child| buf.computed_y(child) + buf.computed_height(child) > viewport_top)
+        .map(|&child| (child, buf.computed_y(child)))
+}
+
+/// After layout: move `index`'s offset by however far its anchor moved, so
+/// the anchor shows where it did before. Clamps the offset like
+/// `clamp_scroll` (which layout skips for anchored nodes).
+pub fn keep_anchor(buf: &SharedBuffer, index: usize, (anchor, old_y): (usize, f32)) {
+    let kept = buf.component_type(anchor) != COMPONENT_NONE && buf.parent_index(anchor) == Some(index);
+    let delta = if kept { (buf.computed_y(anchor) - old_y) as i32 } else { 0 };
+    if delta == 0 {
+        return clamp_scroll(buf, index);
+    }
+    let max_y = (buf.max_scroll_y(index) as i32).max(0);
+    let (x, y) = (buf.scroll_x(index), buf.scroll_y(index));
+    // Straight to the new offset: easing there would show the content move
+    buf.set_scroll(index, x, (y + delta).clamp(0, max_y));
+    if buf.smooth_scroll(index) && buf.scroll_animating(index) {
+        let (target_x, target_y) = buf.scroll_target(index);
+        buf.set_scroll_target(index, target_x, (target_y + delta).clamp(0, max_y));
+    }
+    if buf.scroll_flags(index) & SCROLL_EVENTS != 0 {
+        buf.push_scroll_change_event(index as u16, x, buf.scroll_y(index));
+    }
+}
+
 impl Default for ScrollManager {
     fn default() -> Self {
         Self::new()
@@ -125,9 +274,142 @@ impl Default for ScrollManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared_buffer::test_support::TestBuffer;
+    use crate::shared_buffer::{
+        N_IS_SCROLLABLE, N_MAX_SCROLL_X, N_MAX_SCROLL_Y, N_PARENT_INDEX, N_REACH_END_THRESHOLD, N_SCROLL_FLAGS,
+        N_SMOOTH_SCROLL,
+    };
+
+    /// Text pool bytes in test buffers
+    const POOL: usize = 64;
+
+    /// One scrollable node with 20 rows of overflow.
+    fn create_buffer(smooth: bool) -> TestBuffer {
+        let buf = TestBuffer::new(1, POOL);
+        buf.put_u8(0, N_IS_SCROLLABLE, 1);
+        buf.put_f32(0, N_MAX_SCROLL_Y, 20.0);
+        buf.put_u8(0, N_SMOOTH_SCROLL, smooth as u8);
+        buf
+    }
 
     #[test]
     fn test_scroll_manager_new() {
         let _sm = ScrollManager::new();
     }
+
+    #[test]
+    fn test_scroll_by_jumps_without_smooth() {
+        let buf = create_buffer(false);
+        assert!(ScrollManager::new().scroll_by(&buf, 0, 0, 5, false));
+        assert_eq!(buf.scroll_y(0), 5);
+        assert!(!buf.scroll_animating(0));
+    }
+
+    #[test]
+    fn test_smooth_scroll_sets_target_and_accumulates() {
+        let buf = create_buffer(true);
+        let sm = ScrollManager::new();
+
+        assert!(sm.scroll_by(&buf, 0, 0, 5, false));
+        assert_eq!(buf.scroll_y(0), 0, "offset is left for TS to spring");
+        assert!(buf.scroll_animating(0));
+        assert_eq!(buf.scroll_target(0), (0, 5));
+        assert_eq!(buf.event_write_idx(), 1);
+
+        // A second tick builds on the pending target and clamps to max
+        assert!(sm.scroll_by(&buf, 0, 0, 30, false));
+        assert_eq!(buf.scroll_target(0), (0, 20));
+        assert!(!sm.scroll_by(&buf, 0, 0, 1, false));
+    }
+
+    #[test]
+    fn test_stick_to_bottom_follows_growth_until_scrolled_up() {
+        let buf = create_buffer(false);
+        buf.put_u8(0, N_SCROLL_FLAGS, SCROLL_STICK_BOTTOM | SCROLL_EVENTS);
+        let sm = ScrollManager::new();
+        sm.scroll_to(&buf, 0, 0, 20);
+        assert_eq!(buf.event_write_idx(), 1, "scroll change reported");
+
+        // Content grew by 10 rows while at the bottom
+        buf.set_output_scroll(0, true, 0.0, 30.0);
+        stick_to_bottom(&buf, 0, 20.0);
+        assert_eq!(buf.scroll_y(0), 30);
+        assert_eq!(buf.event_write_idx(), 2);
+
+        // Scrolled up: growth leaves the offset alone
+        assert!(sm.scroll_by(&buf, 0, 0, -5, false));
+        buf.set_output_scroll(0, true, 0.0, 40.0);
+        stick_to_bottom(&buf, 0, 30.0);
+        assert_eq!(buf.scroll_y(0), 25);
+    }
+
+    #[test]
+    fn test_chaining_hands_each_axis_to_the_node_that_can_take_it() {
+        // Node 0 scrolls vertically, its child 1 only sideways
+        let buf = TestBuffer::new(2, POOL);
+        let (outer, inner) = (0, 1);
+        buf.put_u8(outer, N_IS_SCROLLABLE, 1);
+        buf.put_i32(outer, N_PARENT_INDEX, -1);
+        buf.put_f32(outer, N_MAX_SCROLL_Y, 20.0);
+        buf.put_u8(inner, N_IS_SCROLLABLE, 1);
+        buf.put_i32(inner, N_PARENT_INDEX, 0);
+        buf.put_f32(inner, N_MAX_SCROLL_X, 10.0);
+        let sm = ScrollManager::new();
+
+        assert!(sm.scroll_by(&buf, 1, 3, 5, true));
+        assert_eq!(scroll_position(&buf, 1), (3, 0));
+        assert_eq!(scroll_position(&buf, 0), (0, 5));
+
+        // Past the inner right edge, the rest goes nowhere; keys never chain
+        assert!(sm.scroll_by(&buf, 1, 20, 0, true));
+        assert_eq!(scroll_position(&buf, 1), (10, 0));
+        assert!(!sm.scroll_by(&buf, 1, 0, 5, false));
+        assert_eq!(scroll_position(&buf, 0), (0, 5));
+    }
+
+    #[test]
+    fn test_scroll_into_view_reveals_both_axes_from_the_pending_target() {
+        // A 10x5 smooth viewport (0) holding a wrapper (1) at row 8 whose
+        // child (2) sits at (12, 1): (12, 9) in the viewport's content
+        let buf = TestBuffer::new(3, POOL);
+        buf.put_u8(0, N_IS_SCROLLABLE, 1);
+        buf.put_i32(0, N_PARENT_INDEX, -1);
+        buf.put_f32(0, N_MAX_SCROLL_X, 10.0);
+        buf.put_f32(0, N_MAX_SCROLL_Y, 20.0);
+        buf.put_u8(0, N_SMOOTH_SCROLL, 1);
+        buf.put_i32(1, N_PARENT_INDEX, 0);
+        buf.put_i32(2, N_PARENT_INDEX, 1);
+        for (i, x, y, w, h) in [(0, 0.0, 0.0, 10.0, 5.0), (1, 0.0, 8.0, 20.0, 2.0), (2, 12.0, 1.0, 3.0, 1.0)] {
+            buf.set_computed_x(i, x);
+            buf.set_computed_y(i, y);
+            buf.set_computed_width(i, w);
+            buf.set_computed_height(i, h);
+        }
+        // Still springing from the top toward row 15
+        buf.set_scroll_target(0, 0, 15);
+
+        ScrollManager::new().scroll_into_view(&buf, 2);
+        assert_eq!(buf.scroll_target(0), (5, 9));
+        assert_eq!((buf.scroll_x(0), buf.scroll_y(0)), (0, 0));
+    }
+
+    #[test]
+    fn test_reach_end_fires_on_entering_the_threshold() {
+        let buf = create_buffer(false);
+        buf.put_u8(0, N_SCROLL_FLAGS, SCROLL_REACH_END);
+        buf.put_u8(0, N_REACH_END_THRESHOLD, 3);
+        let sm = ScrollManager::new();
+
+        sm.scroll_by(&buf, 0, 0, 10, false);
+        assert_eq!(buf.event_write_idx(), 0);
+        sm.scroll_by(&buf, 0, 0, 8, false);
+        assert_eq!(buf.event_write_idx(), 1, "2 rows short of the end");
+        sm.scroll_by(&buf, 0, 0, 1, false);
+        assert_eq!(buf.event_write_idx(), 1, "already reported");
+
+        // Leaving and coming back reports again
+        sm.scroll_by(&buf, 0, 0, -10, false);
+        sm.scroll_to(&buf, 0, 0, 20);
+        assert_eq!(buf.event_write_idx(), 2);
+    }
 }
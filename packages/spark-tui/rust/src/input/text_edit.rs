@@ -3,10 +3,35 @@
 //! Handles character insertion, deletion, cursor movement,
 //! maxLength enforcement, and fires value change events.
 //!
+//! Positions are char indices, but the cursor moves and deletes by
+//! grapheme cluster (`layout::text_measure`), so an emoji ZWJ sequence or a
+//! letter with combining marks is one step; maxLength counts clusters.
+//!
+//! Ghost text (inline completion) is written by TS into the node's ghost
+//! slot. Right at the end of the value accepts it (Tab is routed here by
+//! the keyboard dispatch chain); any edit or Escape dismisses it.
+//!
+//! Word motion (Ctrl/Alt+Left/Right) and word deletion (Ctrl+W,
+//! Ctrl/Alt+Backspace, Ctrl+Delete, Alt+D) follow the node's
+//! `WordBoundary` rule via the shared helpers in `input::word`. Which keys
+//! trigger which edit comes from the keymap preset (`input::keymap`).
+//!
+//! Number, Date and Pattern inputs go through `input::mask`, which rejects
+//! keystrokes that don't fit; dates also move and step by segment.
+//!
+//! While TS shows the placeholder (INPUT_PLACEHOLDER) the text slot holds
+//! it, so the value reads as empty until the first edit replaces it.
+//!
 //! All text editing happens directly in SharedBuffer's text pool.
 
-use crate::shared_buffer::{SharedBuffer, EventType};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::layout::text_measure::{grapheme_count, next_grapheme_boundary, prev_grapheme_boundary};
+use crate::shared_buffer::{SharedBuffer, EventType, INPUT_PLACEHOLDER};
+use super::keymap::{edit_action, EditAction};
+use super::mask::{date_segment_move, date_segment_step, Mask};
 use super::parser::{KeyEvent, KeyCode, Modifier};
+use super::word::{find_word_end, find_word_start};
 
 // =============================================================================
 // EVENT HELPERS
@@ -50,6 +75,15 @@ impl TextEditor {
         index: usize,
         key: &KeyEvent,
     ) -> bool {
+        if key.modifiers.is_empty() && self.date_key(buf, index, &key.code) {
+            return true;
+        }
+
+        if let Some(action) = edit_action(buf.keymap_preset(), key) {
+            self.apply(buf, index, action);
+            return true;
+        }
+
         match &key.code {
             KeyCode::Char(ch) => {
                 if key.modifiers.contains(Modifier::CTRL) || key.modifiers.contains(Modifier::ALT) {
@@ -58,36 +92,12 @@ impl TextEditor {
                 self.insert_char(buf, index, *ch);
                 true
             }
-            KeyCode::Backspace => {
-                self.delete_backward(buf, index);
-                true
-            }
-            KeyCode::Delete => {
-                self.delete_forward(buf, index);
-                true
-            }
-            KeyCode::Left => {
-                self.move_cursor(buf, index, -1);
-                true
-            }
-            KeyCode::Right => {
-                self.move_cursor(buf, index, 1);
-                true
-            }
-            KeyCode::Home => {
-                buf.set_cursor_position(index, 0);
-                true
-            }
-            KeyCode::End => {
-                let len = self.char_count(buf, index);
-                buf.set_cursor_position(index, len as i32);
-                true
-            }
             KeyCode::Enter => {
                 push_submit_event(buf, index as u16);
                 true
             }
             KeyCode::Escape => {
+                buf.clear_ghost_text(index);
                 push_cancel_event(buf, index as u16);
                 true
             }
@@ -95,6 +105,105 @@ impl TextEditor {
         }
     }
 
+    /// Left/Right between date segments, Up/Down to step one. Returns true
+    /// if the key was a date key for a date input.
+    fn date_key(&self, buf: &SharedBuffer, index: usize, code: &KeyCode) -> bool {
+        let Some(Mask::Layout { mask, date: true }) = Mask::of(buf, index) else {
+            return false;
+        };
+        let value = self.value(buf, index);
+        let cursor = (buf.cursor_position(index).max(0) as usize).min(value.chars().count());
+        match code {
+            KeyCode::Left | KeyCode::Right => {
+                let direction = if *code == KeyCode::Left { -1 } else { 1 };
+                let pos = date_segment_move(mask, value.chars().count(), cursor, direction);
+                buf.set_cursor_position(index, pos as i32);
+            }
+            KeyCode::Up | KeyCode::Down => {
+                let delta = if *code == KeyCode::Up { 1 } else { -1 };
+                if let Some((text, pos)) = date_segment_step(mask, value, cursor, delta) {
+                    self.replace(buf, index, &text, pos);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// The input's value: its text, or empty while the placeholder shows.
+    fn value<'a>(&self, buf: &'a SharedBuffer, index: usize) -> &'a str {
+        if buf.input_flags(index) & INPUT_PLACEHOLDER != 0 { "" } else { buf.text(index) }
+    }
+
+    /// Write a new value (replacing any placeholder), move the cursor and
+    /// tell TS. Returns false if the pool is full.
+    fn replace(&self, buf: &SharedBuffer, index: usize, text: &str, cursor: usize) -> bool {
+        if !buf.set_text(index, text) {
+            return false;
+        }
+        buf.set_input_flags(index, buf.input_flags(index) & !INPUT_PLACEHOLDER);
+        buf.clear_ghost_text(index);
+        buf.set_cursor_position(index, cursor as i32);
+        push_value_change_event(buf, index as u16);
+        true
+    }
+
+    /// Run an editing action at the cursor.
+    fn apply(&self, buf: &SharedBuffer, index: usize, action: EditAction) {
+        let len = self.char_count(buf, index);
+        let cursor = (buf.cursor_position(index).max(0) as usize).min(len);
+        match action {
+            EditAction::Left => self.move_cursor(buf, index, -1),
+            EditAction::Right => {
+                if !self.accept_ghost(buf, index) {
+                    self.move_cursor(buf, index, 1);
+                }
+            }
+            EditAction::WordLeft => {
+                let pos = find_word_start(self.value(buf, index), cursor, buf.word_boundary(index));
+                buf.set_cursor_position(index, pos as i32);
+            }
+            EditAction::WordRight => {
+                let pos = find_word_end(self.value(buf, index), cursor, buf.word_boundary(index));
+                buf.set_cursor_position(index, pos as i32);
+            }
+            EditAction::Home => buf.set_cursor_position(index, 0),
+            EditAction::End => buf.set_cursor_position(index, len as i32),
+            EditAction::DeleteBackward => self.delete_backward(buf, index),
+            EditAction::DeleteForward => self.delete_forward(buf, index),
+            EditAction::DeleteWordBackward => self.delete_word_backward(buf, index),
+            EditAction::DeleteWordForward => self.delete_word_forward(buf, index),
+            EditAction::KillToEnd => self.delete_range(buf, index, cursor, len),
+            EditAction::KillToStart => self.delete_range(buf, index, 0, cursor),
+        }
+    }
+
+    /// Accept the ghost text if it is showing (cursor at end of value).
+    /// Returns true if a suggestion was accepted.
+    pub fn accept_ghost(&self, buf: &SharedBuffer, index: usize) -> bool {
+        let ghost = buf.ghost_text(index);
+        if ghost.is_empty() {
+            return false;
+        }
+
+        let len = self.char_count(buf, index);
+        if buf.cursor_position(index) as usize != len {
+            return false;
+        }
+
+        // Respect maxLength — accept as many clusters of the suggestion as fit
+        let value = self.value(buf, index);
+        let max_len = buf.max_length(index) as usize;
+        let room = if max_len > 0 { max_len.saturating_sub(grapheme_count(value)) } else { usize::MAX };
+        let accepted: String = ghost.graphemes(true).take(room).collect();
+        if accepted.is_empty() {
+            return false;
+        }
+
+        let new_text = format!("{}{}", value, accepted);
+        self.replace(buf, index, &new_text, len + accepted.chars().count())
+    }
+
     /// Insert a character at the cursor position.
     fn insert_char(
         &self,
@@ -102,26 +211,28 @@ impl TextEditor {
         index: usize,
         ch: char,
     ) {
-        let content = buf.text(index).to_string();
-        let chars: Vec<char> = content.chars().collect();
-        let cursor = (buf.cursor_position(index) as usize).min(chars.len());
+        let content = self.value(buf, index);
+        let len = content.chars().count();
+        let cursor = (buf.cursor_position(index).max(0) as usize).min(len);
 
-        // Check maxLength
+        let edit = match Mask::of(buf, index) {
+            Some(mask) => mask.insert(content, cursor, ch),
+            None => {
+                let mut chars: Vec<char> = content.chars().collect();
+                chars.insert(cursor, ch);
+                Some((chars.into_iter().collect(), cursor + 1))
+            }
+        };
+        let Some((new_text, pos)) = edit else { return };
+
+        // maxLength counts clusters: a combining mark joining the last
+        // letter still fits
         let max_len = buf.max_length(index) as usize;
-        if max_len > 0 && chars.len() >= max_len {
+        let count = grapheme_count(&new_text);
+        if max_len > 0 && count > max_len && count > grapheme_count(content) {
             return;
         }
-
-        // Build new string
-        let mut new_chars = chars;
-        new_chars.insert(cursor, ch);
-        let new_text: String = new_chars.into_iter().collect();
-
-        // Write back to SharedBuffer
-        if buf.set_text(index, &new_text) {
-            buf.set_cursor_position(index, (cursor + 1) as i32);
-            push_value_change_event(buf, index as u16);
-        }
+        self.replace(buf, index, &new_text, pos);
     }
 
     /// Delete character before cursor (Backspace).
@@ -130,21 +241,18 @@ impl TextEditor {
         buf: &SharedBuffer,
         index: usize,
     ) {
-        let content = buf.text(index).to_string();
-        let chars: Vec<char> = content.chars().collect();
-        let cursor = (buf.cursor_position(index) as usize).min(chars.len());
-
-        if cursor == 0 {
-            return;
-        }
-
-        let mut new_chars = chars;
-        new_chars.remove(cursor - 1);
-        let new_text: String = new_chars.into_iter().collect();
-
-        if buf.set_text(index, &new_text) {
-            buf.set_cursor_position(index, (cursor - 1) as i32);
-            push_value_change_event(buf, index as u16);
+        let cursor = (buf.cursor_position(index).max(0) as usize).min(self.char_count(buf, index));
+        // Masks skip back over literals to the slot before the cursor
+        match Mask::of(buf, index) {
+            Some(mask) => {
+                if let Some(start) = mask.previous_slot(cursor) {
+                    self.delete_range(buf, index, start, start + 1);
+                }
+            }
+            None => {
+                let start = prev_grapheme_boundary(self.value(buf, index), cursor);
+                self.delete_range(buf, index, start, cursor);
+            }
         }
     }
 
@@ -154,35 +262,69 @@ impl TextEditor {
         buf: &SharedBuffer,
         index: usize,
     ) {
-        let content = buf.text(index).to_string();
-        let chars: Vec<char> = content.chars().collect();
-        let cursor = (buf.cursor_position(index) as usize).min(chars.len());
+        let cursor = (buf.cursor_position(index).max(0) as usize).min(self.char_count(buf, index));
+        // The cursor stays put: the range starts at it
+        let end = match Mask::of(buf, index) {
+            Some(_) => cursor + 1,
+            None => next_grapheme_boundary(self.value(buf, index), cursor),
+        };
+        self.delete_range(buf, index, cursor, end);
+    }
+
+    /// Delete from the start of the current/previous word to the cursor.
+    fn delete_word_backward(&self, buf: &SharedBuffer, index: usize) {
+        let cursor = (buf.cursor_position(index).max(0) as usize).min(self.char_count(buf, index));
+        let start = find_word_start(self.value(buf, index), cursor, buf.word_boundary(index));
+        self.delete_range(buf, index, start, cursor);
+    }
+
+    /// Delete from the cursor to the end of the current/next word.
+    fn delete_word_forward(&self, buf: &SharedBuffer, index: usize) {
+        let cursor = (buf.cursor_position(index).max(0) as usize).min(self.char_count(buf, index));
+        let end = find_word_end(self.value(buf, index), cursor, buf.word_boundary(index));
+        self.delete_range(buf, index, cursor, end);
+    }
 
-        if cursor >= chars.len() {
+    /// Remove chars `[start, end)` and leave the cursor at `start`.
+    fn delete_range(&self, buf: &SharedBuffer, index: usize, start: usize, end: usize) {
+        let end = end.min(self.char_count(buf, index));
+        if start >= end {
             return;
         }
 
-        let mut new_chars = chars;
-        new_chars.remove(cursor);
-        let new_text: String = new_chars.into_iter().collect();
-
-        if buf.set_text(index, &new_text) {
-            // Cursor stays at same position
-            push_value_change_event(buf, index as u16);
+        let content = self.value(buf, index);
+        let edit = match Mask::of(buf, index) {
+            Some(mask) => mask.delete(content, start, end),
+            None => {
+                let kept: String = content
+                    .chars()
+                    .enumerate()
+                    .filter(|&(i, _)| i < start || i >= end)
+                    .map(|(_, ch)| ch)
+                    .collect();
+                Some((kept, start))
+            }
+        };
+        if let Some((new_text, pos)) = edit {
+            self.replace(buf, index, &new_text, pos);
         }
     }
 
-    /// Move cursor by delta (-1 for left, +1 for right).
+    /// Move the cursor one grapheme cluster (-1 for left, +1 for right).
     fn move_cursor(&self, buf: &SharedBuffer, index: usize, delta: i32) {
-        let len = self.char_count(buf, index) as i32;
-        let current = buf.cursor_position(index);
-        let new_pos = (current + delta).clamp(0, len);
-        buf.set_cursor_position(index, new_pos);
+        let value = self.value(buf, index);
+        let current = (buf.cursor_position(index).max(0) as usize).min(value.chars().count());
+        let new_pos = if delta < 0 {
+            prev_grapheme_boundary(value, current)
+        } else {
+            next_grapheme_boundary(value, current)
+        };
+        buf.set_cursor_position(index, new_pos as i32);
     }
 
     /// Get the character count of the text content.
     fn char_count(&self, buf: &SharedBuffer, index: usize) -> usize {
-        buf.text(index).chars().count()
+        self.value(buf, index).chars().count()
     }
 }
 
@@ -196,8 +338,187 @@ impl Default for TextEditor {
 mod tests {
     use super::*;
 
+    use crate::shared_buffer::test_support::TestBuffer;
+    use crate::shared_buffer::{InputType, KeymapPreset, N_INPUT_TYPE, N_MAX_LENGTH};
+    use crate::input::parser::KeyState;
+
+    /// One input (node 0) and a spare slot
+    fn create_buffer() -> TestBuffer {
+        let buf = TestBuffer::new(2, 4096);
+        buf.set_node_count(1);
+        buf
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent { code, modifiers: Modifier::empty(), state: KeyState::Press }
+    }
+
     #[test]
     fn test_text_editor_new() {
         let _te = TextEditor::new();
     }
+
+    #[test]
+    fn test_ghost_accept_with_right() {
+        let buf = create_buffer();
+        let mut editor = TextEditor::new();
+        buf.set_text(0, "git ch");
+        buf.set_cursor_position(0, 6);
+        buf.set_ghost_text(0, "eckout");
+
+        assert!(editor.handle_key(&buf, 0, &press(KeyCode::Right)));
+        assert_eq!(buf.text(0), "git checkout");
+        assert_eq!(buf.cursor_position(0), 12);
+        assert_eq!(buf.ghost_text(0), "");
+    }
+
+    #[test]
+    fn test_ghost_not_accepted_mid_value() {
+        let buf = create_buffer();
+        let mut editor = TextEditor::new();
+        buf.set_text(0, "abc");
+        buf.set_cursor_position(0, 1);
+        buf.set_ghost_text(0, "def");
+
+        assert!(editor.handle_key(&buf, 0, &press(KeyCode::Right)));
+        assert_eq!(buf.text(0), "abc");
+        assert_eq!(buf.cursor_position(0), 2);
+        assert_eq!(buf.ghost_text(0), "def");
+    }
+
+    #[test]
+    fn test_ghost_dismissed_on_typing() {
+        let buf = create_buffer();
+        let mut editor = TextEditor::new();
+        buf.set_text(0, "ab");
+        buf.set_cursor_position(0, 2);
+        buf.set_ghost_text(0, "cd");
+
+        editor.handle_key(&buf, 0, &press(KeyCode::Char('x')));
+        assert_eq!(buf.text(0), "abx");
+        assert_eq!(buf.ghost_text(0), "");
+    }
+
+    #[test]
+    fn test_grapheme_cluster_editing() {
+        let buf = create_buffer();
+        let mut editor = TextEditor::new();
+        // a, 👩‍👩‍👧 (5 chars), b
+        buf.set_text(0, "a\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}b");
+        buf.set_cursor_position(0, 7);
+
+        editor.handle_key(&buf, 0, &press(KeyCode::Left));
+        assert_eq!(buf.cursor_position(0), 6);
+        editor.handle_key(&buf, 0, &press(KeyCode::Left));
+        assert_eq!(buf.cursor_position(0), 1);
+        editor.handle_key(&buf, 0, &press(KeyCode::Delete));
+        assert_eq!(buf.text(0), "ab");
+
+        // A combining mark joins the letter before it, within maxLength
+        buf.put_u8(0, N_MAX_LENGTH, 2);
+        buf.set_cursor_position(0, 1);
+        editor.handle_key(&buf, 0, &press(KeyCode::Char('\u{301}')));
+        assert_eq!(buf.text(0), "a\u{301}b");
+        editor.handle_key(&buf, 0, &press(KeyCode::Char('x')));
+        assert_eq!(buf.text(0), "a\u{301}b");
+        editor.handle_key(&buf, 0, &press(KeyCode::Backspace));
+        assert_eq!(buf.text(0), "b");
+        assert_eq!(buf.cursor_position(0), 0);
+    }
+
+    #[test]
+    fn test_word_motion_and_deletion() {
+        let buf = create_buffer();
+        let mut editor = TextEditor::new();
+        buf.set_text(0, "cd /usr/local_bin");
+        buf.set_cursor_position(0, 17);
+        let ctrl = |code| KeyEvent { code, modifiers: Modifier::CTRL, state: KeyState::Press };
+
+        editor.handle_key(&buf, 0, &ctrl(KeyCode::Left));
+        assert_eq!(buf.cursor_position(0), 8);
+
+        editor.handle_key(&buf, 0, &ctrl(KeyCode::Right));
+        assert_eq!(buf.cursor_position(0), 17);
+
+        editor.handle_key(&buf, 0, &ctrl(KeyCode::Char('w')));
+        assert_eq!(buf.text(0), "cd /usr/");
+        assert_eq!(buf.cursor_position(0), 8);
+    }
+
+    #[test]
+    fn test_ghost_respects_max_length() {
+        let buf = create_buffer();
+        buf.put_u8(0, N_MAX_LENGTH, 4);
+        let editor = TextEditor::new();
+        buf.set_text(0, "ab");
+        buf.set_cursor_position(0, 2);
+        buf.set_ghost_text(0, "cdef");
+
+        assert!(editor.accept_ghost(&buf, 0));
+        assert_eq!(buf.text(0), "abcd");
+    }
+
+    #[test]
+    fn test_emacs_preset_editing() {
+        let buf = create_buffer();
+        let mut editor = TextEditor::new();
+        buf.set_text(0, "hello world");
+        buf.set_cursor_position(0, 5);
+        let ctrl = |ch| KeyEvent { code: KeyCode::Char(ch), modifiers: Modifier::CTRL, state: KeyState::Press };
+
+        // Standard preset leaves Ctrl+K to TS
+        assert!(!editor.handle_key(&buf, 0, &ctrl('k')));
+
+        buf.set_keymap_preset(KeymapPreset::Emacs);
+        assert!(editor.handle_key(&buf, 0, &ctrl('k')));
+        assert_eq!(buf.text(0), "hello");
+
+        editor.handle_key(&buf, 0, &ctrl('a'));
+        assert_eq!(buf.cursor_position(0), 0);
+        editor.handle_key(&buf, 0, &ctrl('f'));
+        editor.handle_key(&buf, 0, &ctrl('f'));
+        editor.handle_key(&buf, 0, &ctrl('u'));
+        assert_eq!(buf.text(0), "llo");
+        assert_eq!(buf.cursor_position(0), 0);
+    }
+
+    #[test]
+    fn test_pattern_input_replaces_placeholder() {
+        let buf = create_buffer();
+        buf.put_u8(0, N_INPUT_TYPE, InputType::Pattern as u8);
+        buf.set_input_mask(0, "(999) 999-9999");
+        buf.set_text(0, "Phone");
+        buf.set_input_flags(0, INPUT_PLACEHOLDER);
+        let mut editor = TextEditor::new();
+
+        for ch in "555x12".chars() {
+            editor.handle_key(&buf, 0, &press(KeyCode::Char(ch)));
+        }
+        assert_eq!(buf.text(0), "(555) 12");
+        assert_eq!(buf.input_flags(0) & INPUT_PLACEHOLDER, 0);
+
+        // Backspace over the 1 also drops the ") " it brought in
+        editor.handle_key(&buf, 0, &press(KeyCode::Backspace));
+        editor.handle_key(&buf, 0, &press(KeyCode::Backspace));
+        assert_eq!(buf.text(0), "(555");
+        assert_eq!(buf.cursor_position(0), 4);
+    }
+
+    #[test]
+    fn test_date_input_segments() {
+        let buf = create_buffer();
+        buf.put_u8(0, N_INPUT_TYPE, InputType::Date as u8);
+        buf.set_input_mask(0, "DD/MM/YYYY");
+        buf.set_text(0, "31/12/2024");
+        buf.set_cursor_position(0, 0);
+        let mut editor = TextEditor::new();
+
+        assert!(editor.handle_key(&buf, 0, &press(KeyCode::Right)));
+        assert_eq!(buf.cursor_position(0), 3);
+        assert!(editor.handle_key(&buf, 0, &press(KeyCode::Up)));
+        assert_eq!(buf.text(0), "31/01/2024");
+        assert!(editor.handle_key(&buf, 0, &press(KeyCode::Left)));
+        assert!(editor.handle_key(&buf, 0, &press(KeyCode::Down)));
+        assert_eq!(buf.text(0), "30/01/2024");
+    }
 }
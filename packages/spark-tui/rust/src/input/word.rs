@@ -0,0 +1,128 @@
+//! Word boundaries for text editing and selection.
+//!
+//! One implementation shared by Ctrl+arrow motion, word deletion and
+//! double-click selection. What counts as a word is chosen per node
+//! through `WordBoundary` (default: Unicode UAX #29 word boundaries).
+//!
+//! All positions are char indices, matching the cursor position stored
+//! in the SharedBuffer.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::shared_buffer::WordBoundary;
+
+/// Char ranges `[start, end)` of every word in `text`, in order.
+pub fn word_spans(text: &str, rule: WordBoundary) -> Vec<(usize, usize)> {
+    match rule {
+        WordBoundary::Unicode => {
+            let mut spans = Vec::new();
+            let mut pos = 0;
+            for segment in text.split_word_bounds() {
+                let len = segment.chars().count();
+                // UAX #29 also yields whitespace and punctuation segments
+                if segment.chars().any(char::is_alphanumeric) {
+                    spans.push((pos, pos + len));
+                }
+                pos += len;
+            }
+            spans
+        }
+        WordBoundary::Alphanumeric => char_runs(text, char::is_alphanumeric),
+        WordBoundary::Identifier => char_runs(text, |c| c.is_alphanumeric() || c == '_'),
+        WordBoundary::Whitespace => char_runs(text, |c| !c.is_whitespace()),
+    }
+}
+
+/// Runs of chars matching `is_word`.
+fn char_runs(text: &str, is_word: impl Fn(char) -> bool) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    let mut len = 0;
+    for (i, ch) in text.chars().enumerate() {
+        match (is_word(ch), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+        len = i + 1;
+    }
+    if let Some(s) = start {
+        spans.push((s, len));
+    }
+    spans
+}
+
+/// Start of the word at or before `pos` (Ctrl+Left, Ctrl+Backspace).
+pub fn find_word_start(text: &str, pos: usize, rule: WordBoundary) -> usize {
+    word_spans(text, rule)
+        .into_iter()
+        .rev()
+        .find(|&(start, _)| start < pos)
+        .map_or(0, |(start, _)| start)
+}
+
+/// End of the word at or after `pos` (Ctrl+Right, Ctrl+Delete).
+pub fn find_word_end(text: &str, pos: usize, rule: WordBoundary) -> usize {
+    word_spans(text, rule)
+        .into_iter()
+        .find(|&(_, end)| end > pos)
+        .map_or_else(|| text.chars().count(), |(_, end)| end)
+}
+
+/// The word under `pos` (double-click). A position just past a word
+/// selects that word; between words yields an empty range at `pos`.
+pub fn word_at(text: &str, pos: usize, rule: WordBoundary) -> (usize, usize) {
+    word_spans(text, rule)
+        .into_iter()
+        .find(|&(start, end)| start <= pos && pos <= end)
+        .unwrap_or((pos, pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_words() {
+        let text = "hello, wörld_x 3.14";
+        assert_eq!(word_spans(text, WordBoundary::Unicode), vec![(0, 5), (7, 14), (15, 19)]);
+    }
+
+    #[test]
+    fn test_cjk_words_are_separate() {
+        // UAX #29 splits ideographs individually
+        assert_eq!(word_spans("漢字 ok", WordBoundary::Unicode), vec![(0, 1), (1, 2), (3, 5)]);
+    }
+
+    #[test]
+    fn test_rules_differ_on_identifiers_and_paths() {
+        let text = "/usr/local_bin x";
+        assert_eq!(word_spans(text, WordBoundary::Alphanumeric), vec![(1, 4), (5, 10), (11, 14), (15, 16)]);
+        assert_eq!(word_spans(text, WordBoundary::Identifier), vec![(1, 4), (5, 14), (15, 16)]);
+        assert_eq!(word_spans(text, WordBoundary::Whitespace), vec![(0, 14), (15, 16)]);
+    }
+
+    #[test]
+    fn test_motion() {
+        let text = "foo bar  baz";
+        let rule = WordBoundary::Unicode;
+        assert_eq!(find_word_start(text, 12, rule), 9);
+        assert_eq!(find_word_start(text, 9, rule), 4);
+        assert_eq!(find_word_start(text, 5, rule), 4);
+        assert_eq!(find_word_start(text, 0, rule), 0);
+        assert_eq!(find_word_end(text, 0, rule), 3);
+        assert_eq!(find_word_end(text, 3, rule), 7);
+        assert_eq!(find_word_end(text, 12, rule), 12);
+    }
+
+    #[test]
+    fn test_word_at() {
+        let text = "foo bar";
+        assert_eq!(word_at(text, 5, WordBoundary::Unicode), (4, 7));
+        assert_eq!(word_at(text, 3, WordBoundary::Unicode), (0, 3));
+        assert_eq!(word_at("a  b", 2, WordBoundary::Unicode), (2, 2));
+    }
+}
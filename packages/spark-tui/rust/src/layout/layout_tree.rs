@@ -26,23 +26,29 @@
 //! - RoundTree: pixel snapping
 //! - PrintTree: debug output
 
+use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 
 use taffy::prelude::*;
 use taffy::style::GenericGridTemplateComponent;
 use taffy::{
     compute_cached_layout, compute_flexbox_layout, compute_grid_layout, compute_hidden_layout,
-    compute_leaf_layout, compute_root_layout, round_layout, Cache, CacheTree, Layout,
+    compute_leaf_layout, compute_root_layout, round_layout, Cache, CacheTree, CoreStyle, Layout,
     LayoutFlexboxContainer, LayoutGridContainer, LayoutInput, LayoutOutput, LayoutPartialTree,
-    NodeId, PrintTree, RoundTree, TraversePartialTree, TraverseTree,
+    NodeId, PrintTree, ResolveOrZero, RoundTree, TraversePartialTree, TraverseTree,
 };
 
 use crate::shared_buffer::{
-    SharedBuffer, RenderMode, COMPONENT_BOX, COMPONENT_INPUT, COMPONENT_NONE, COMPONENT_TEXT, DIRTY_LAYOUT,
+    SharedBuffer, ControlChars, PercentBasis, RenderMode, COMPONENT_BOX, COMPONENT_INPUT, COMPONENT_NONE, COMPONENT_TEXT,
+    DIRTY_HIERARCHY, DIRTY_LAYOUT, DIRTY_TEXT, N_HEIGHT_BASIS, N_MAX_HEIGHT_BASIS, N_MAX_WIDTH_BASIS, N_MIN_HEIGHT_BASIS, N_MIN_WIDTH_BASIS,
+    N_WIDTH_BASIS, TextDirection, TextWrap, SCROLL_REACH_END,
 };
 
-use super::text_measure::{measure_text_height, string_width};
+use crate::input::scroll::{clamp_scroll, keep_anchor, near_end, reach_end, scroll_anchor, stick_to_bottom};
+
+use super::text_measure::{input_pictures, visualize_controls, MeasureCache};
 
 // =============================================================================
 // CONSTANTS
@@ -52,61 +58,228 @@ const DISPLAY_NONE: u8 = 0;
 const DISPLAY_FLEX: u8 = 1;
 const DISPLAY_GRID: u8 = 2;
 
+/// Rows per line of text; a line's baseline is its bottom edge
+const TEXT_LINE_HEIGHT: f32 = 1.0;
+
 // =============================================================================
 // LAYOUT CONTEXT (thread-local, reused across frames)
 // =============================================================================
 
 pub struct LayoutContext {
     children: Vec<Vec<usize>>,
+    /// Children lists of the layout before, to see which changed
+    prev_children: Vec<Vec<usize>>,
     cache: Vec<Cache>,
     unrounded: Vec<Layout>,
     final_layout: Vec<Layout>,
     roots: Vec<usize>,
+    /// Whether each node was in the tree (a live, visible component) last layout
+    attached: Vec<bool>,
+    /// Node count last layout
+    node_count: usize,
+    /// What every node's layout depended on last time; None before the first
+    env: Option<LayoutEnv>,
+    /// Nodes with a `PercentBasis::Named` dimension, kept as they're dirtied
+    named: BTreeSet<usize>,
+    /// Whether each node's direction resolves to right to left, this layout
+    rtl: Vec<bool>,
+    /// Text measurements, kept across layouts
+    measure_cache: MeasureCache,
+}
+
+/// Header state every node's layout can depend on: a change invalidates all
+/// of it, and the engine lays out again even with no node dirty
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct LayoutEnv {
+    terminal: (u32, u32),
+    render_mode: RenderMode,
+    inline_max_height: u16,
+    cell_aspect: f32,
+    control_chars: ControlChars,
+}
+
+impl LayoutEnv {
+    pub(crate) fn read(buf: &SharedBuffer) -> Self {
+        Self {
+            terminal: (buf.terminal_width(), buf.terminal_height()),
+            render_mode: buf.render_mode(),
+            inline_max_height: buf.inline_max_height(),
+            cell_aspect: buf.cell_aspect(),
+            control_chars: buf.control_chars(),
+        }
+    }
 }
 
 impl LayoutContext {
     fn new() -> Self {
         Self {
             children: Vec::new(),
+            prev_children: Vec::new(),
             cache: Vec::new(),
             unrounded: Vec::new(),
             final_layout: Vec::new(),
             roots: Vec::new(),
+            attached: Vec::new(),
+            node_count: 0,
+            env: None,
+            named: BTreeSet::new(),
+            rtl: Vec::new(),
+            measure_cache: MeasureCache::new(),
         }
     }
 
     fn ensure_capacity(&mut self, count: usize) {
         if count > self.cache.len() {
             self.children.resize_with(count, Vec::new);
+            self.prev_children.resize_with(count, Vec::new);
             self.cache.resize_with(count, Cache::new);
             self.unrounded.resize_with(count, || Layout::with_order(0));
             self.final_layout.resize_with(count, || Layout::with_order(0));
+            self.attached.resize(count, false);
+            self.rtl.resize(count, false);
+        }
+    }
+
+    /// Resolve every node's inherited direction once, top down, rather than
+    /// walking its ancestors for each style read.
+    fn resolve_directions(&mut self, buf: &SharedBuffer) {
+        let mut stack: Vec<usize> = self.roots.clone();
+        for &root in &self.roots {
+            // A root may still inherit from a hidden parent
+            self.rtl[root] = buf.resolved_direction(root) == TextDirection::Rtl;
+        }
+        while let Some(i) = stack.pop() {
+            for &child in &self.children[i] {
+                self.rtl[child] = match buf.direction(child) {
+                    TextDirection::Inherit => self.rtl[i],
+                    direction => direction == TextDirection::Rtl,
+                };
+            }
+            stack.extend_from_slice(&self.children[i]);
+        }
+    }
+
+    /// Stack the roots' regions (N_REGION) top to bottom, in ascending
+    /// order: each region starts below the tallest root of the one before.
+    fn stack_regions(&mut self, buf: &SharedBuffer) {
+        let mut regions: Vec<u8> = self.roots.iter().map(|&root| buf.region(root)).collect();
+        regions.sort_unstable();
+        regions.dedup();
+
+        let mut top = 0.0f32;
+        for region in regions {
+            let mut bottom = top;
+            for &root in self.roots.iter().filter(|&&root| buf.region(root) == region) {
+                let layout = &mut self.final_layout[root];
+                layout.location.y += top;
+                bottom = bottom.max(layout.location.y + layout.size.height);
+            }
+            top = bottom;
         }
     }
 
-    /// Clear cache ONLY for nodes with DIRTY_LAYOUT flag set.
-    /// This is the key to using Taffy's caching properly with reactive dirty tracking.
-    fn clear_dirty_caches(&mut self, _buf: &SharedBuffer, node_count: usize) {
-        // Clear ALL caches when layout runs.
-        // This is needed because text size changes affect ancestor layouts.
-        // TODO: Optimize by walking parent chain for dirty nodes only.
-        for i in 0..node_count.min(self.cache.len()) {
+    /// Bring the tree and Taffy's caches up to date with what changed since
+    /// the last layout, and nothing more. Styles are read straight from the
+    /// buffer, so a changed node only needs its cached layouts dropped — its
+    /// own, its ancestors' (they sized around it) and its descendants'
+    /// (they inherit its direction). The hierarchy is rebuilt only when a
+    /// node came, went or moved.
+    fn invalidate(&mut self, buf: &SharedBuffer, node_count: usize) {
+        let env = LayoutEnv::read(buf);
+        let full = self.env != Some(env);
+        self.env = Some(env);
+
+        let mut dirty = Vec::new();
+        let mut structural = full || node_count != self.node_count;
+        for i in 0..node_count {
+            let flags = buf.dirty_flags(i);
+            if flags & DIRTY_TEXT != 0 {
+                self.measure_cache.invalidate_node(i);
+            }
+            if flags & (DIRTY_LAYOUT | DIRTY_TEXT | DIRTY_HIERARCHY) == 0 {
+                continue;
+            }
+            buf.clear_dirty(i);
+            dirty.push(i);
+            structural |= flags & DIRTY_HIERARCHY != 0 || self.attached[i] != is_attached(buf, i);
+        }
+        // Who sizes against a named ancestor changes only with a node's props
+        if full {
+            self.named = (0..node_count).filter(|&i| has_named_basis(buf, i)).collect();
+        } else {
+            self.named.split_off(&node_count);
+            for &i in &dirty {
+                if has_named_basis(buf, i) {
+                    self.named.insert(i);
+                } else {
+                    self.named.remove(&i);
+                }
+            }
+        }
+        // Slots past the old count may hold caches of nodes released long ago
+        for i in self.node_count..node_count {
             self.cache[i].clear();
         }
+        self.node_count = node_count;
+
+        if structural {
+            std::mem::swap(&mut self.children, &mut self.prev_children);
+            self.rebuild_hierarchy(buf, node_count);
+            for i in 0..node_count {
+                self.attached[i] = is_attached(buf, i);
+                if self.children[i] != self.prev_children[i] {
+                    dirty.push(i);
+                }
+            }
+        }
+
+        if full {
+            for cache in self.cache.iter_mut().take(node_count) {
+                cache.clear();
+            }
+            return;
+        }
+        for &i in &dirty {
+            self.clear_ancestors(buf, i, node_count);
+            self.clear_subtree(i);
+        }
+    }
+
+    /// Drop the cached layouts of `node` and everything above it
+    fn clear_ancestors(&mut self, buf: &SharedBuffer, node: usize, node_count: usize) {
+        let mut current = Some(node);
+        // Bounded by the node count, in case of a cycle mid-update
+        for _ in 0..node_count {
+            let Some(i) = current.filter(|&i| i < node_count) else { break };
+            self.cache[i].clear();
+            current = buf.parent_index(i);
+        }
+    }
+
+    /// Drop the cached layouts of everything below `node`
+    fn clear_subtree(&mut self, node: usize) {
+        let mut stack = self.children[node].clone();
+        while let Some(i) = stack.pop() {
+            self.cache[i].clear();
+            stack.extend_from_slice(&self.children[i]);
+        }
     }
 
-    /// Build children lists from parent indices.
+    /// Build children lists from parent indices, in index order unless keyed
+    /// lists placed their nodes (`N_SLOT_ANCHOR`).
     fn rebuild_hierarchy(&mut self, buf: &SharedBuffer, node_count: usize) {
         self.roots.clear();
         for children in self.children.iter_mut().take(node_count) {
             children.clear();
         }
 
+        let mut slotted = false;
         for i in 0..node_count {
             let comp_type = buf.component_type(i);
             if comp_type == COMPONENT_NONE || !buf.visible(i) {
                 continue;
             }
+            slotted |= buf.has_slot(i);
 
             match buf.parent_index(i) {
                 Some(parent) if parent < node_count => {
@@ -119,9 +292,22 @@ impl LayoutContext {
                 _ => self.roots.push(i),
             }
         }
+
+        if slotted {
+            // Stable: unslotted siblings keep index order
+            for children in self.children.iter_mut().take(node_count) {
+                children.sort_by_key(|&child| buf.sibling_order(child));
+            }
+            self.roots.sort_by_key(|&root| buf.sibling_order(root));
+        }
     }
 }
 
+/// A live, visible component: part of the layout tree
+fn is_attached(buf: &SharedBuffer, i: usize) -> bool {
+    buf.component_type(i) != COMPONENT_NONE && buf.visible(i)
+}
+
 thread_local! {
     static LAYOUT_CONTEXT: RefCell<LayoutContext> = RefCell::new(LayoutContext::new());
 }
@@ -241,13 +427,17 @@ impl ExactSizeIterator for AutoTrackIter {
 #[derive(Clone, Copy)]
 pub struct NodeStyle<'a> {
     buf: &'a SharedBuffer,
+    /// Every node's last rounded layout, for `PercentBasis::Named`
+    layouts: &'a [Layout],
+    /// Every node's resolved direction (`LayoutContext::rtl`)
+    rtl: &'a [bool],
     idx: usize,
 }
 
 impl<'a> NodeStyle<'a> {
     #[inline]
-    fn new(buf: &'a SharedBuffer, idx: usize) -> Self {
-        Self { buf, idx }
+    fn new(buf: &'a SharedBuffer, ctx: &'a LayoutContext, idx: usize) -> Self {
+        Self { buf, layouts: &ctx.final_layout, rtl: &ctx.rtl, idx }
     }
 
     /// f32 → Dimension: NaN=auto, negative=percent, positive=length
@@ -283,6 +473,59 @@ impl<'a> NodeStyle<'a> {
             LengthPercentage::length(val)
         }
     }
+
+    /// A dimension whose percentage is of something other than the parent
+    /// (`field` is its N_*_BASIS), turned into a length; others pass through.
+    fn resolve_basis(&self, val: f32, field: usize, horizontal: bool) -> f32 {
+        if !(val < 0.0) {
+            return val;
+        }
+        let basis = match self.buf.percent_basis(self.idx, field) {
+            PercentBasis::Parent => return val,
+            PercentBasis::ViewportWidth => self.buf.terminal_width() as f32,
+            PercentBasis::ViewportHeight => self.buf.terminal_height() as f32,
+            PercentBasis::Named => match named_ancestor(self.buf, self.idx).and_then(|a| self.layouts.get(a)) {
+                Some(layout) if horizontal => layout.size.width,
+                Some(layout) => layout.size.height,
+                None => return val,
+            },
+        };
+        -val / 100.0 * basis
+    }
+}
+
+/// The node `idx` sizes its `Named` percentages against, if it is one of
+/// its ancestors (anything else falls back to the parent). The ancestor's
+/// size is its last layout's; `compute_layout` lays out again when that moved.
+fn named_ancestor(buf: &SharedBuffer, idx: usize) -> Option<usize> {
+    let target = buf.relative_to(idx)?;
+    let mut node = buf.parent_index(idx);
+    // Bounded by the node count, so a corrupt parent chain can't spin
+    for _ in 0..buf.node_count() {
+        match node {
+            Some(n) if n == target => return Some(n),
+            Some(n) => node = buf.parent_index(n),
+            None => return None,
+        }
+    }
+    None
+}
+
+const BASIS_FIELDS: [usize; 6] =
+    [N_WIDTH_BASIS, N_HEIGHT_BASIS, N_MIN_WIDTH_BASIS, N_MIN_HEIGHT_BASIS, N_MAX_WIDTH_BASIS, N_MAX_HEIGHT_BASIS];
+
+/// Whether a live node sizes some dimension against a named ancestor
+fn has_named_basis(buf: &SharedBuffer, i: usize) -> bool {
+    buf.component_type(i) != COMPONENT_NONE
+        && BASIS_FIELDS.iter().any(|&field| buf.percent_basis(i, field) == PercentBasis::Named)
+}
+
+/// The size in `layouts` of the ancestor each of the `named` nodes sizes against
+fn named_basis_sizes(buf: &SharedBuffer, layouts: &[Layout], named: &BTreeSet<usize>) -> Vec<Option<taffy::Size<f32>>> {
+    named
+        .iter()
+        .map(|&i| named_ancestor(buf, i).and_then(|a| layouts.get(a)).map(|layout| layout.size))
+        .collect()
 }
 
 // -----------------------------------------------------------------------------
@@ -311,12 +554,12 @@ impl taffy::CoreStyle for NodeStyle<'_> {
     }
 
     fn overflow(&self) -> taffy::Point<taffy::Overflow> {
-        let o = match self.buf.overflow(self.idx) {
+        let axis = |o: u8| match o {
             1 => taffy::Overflow::Clip,
             2 | 3 => taffy::Overflow::Scroll,
             _ => taffy::Overflow::Visible,
         };
-        taffy::Point { x: o, y: o }
+        taffy::Point { x: axis(self.buf.overflow_x(self.idx)), y: axis(self.buf.overflow_y(self.idx)) }
     }
 
     fn scrollbar_width(&self) -> f32 { 0.0 }
@@ -344,28 +587,35 @@ impl taffy::CoreStyle for NodeStyle<'_> {
             return taffy::Size { width: Dimension::auto(), height: Dimension::auto() };
         }
         taffy::Size {
-            width: Self::to_dim(self.buf.width(self.idx)),
-            height: Self::to_dim(self.buf.height(self.idx)),
+            width: Self::to_dim(self.resolve_basis(self.buf.width(self.idx), N_WIDTH_BASIS, true)),
+            height: Self::to_dim(self.resolve_basis(self.buf.height(self.idx), N_HEIGHT_BASIS, false)),
         }
     }
 
     fn min_size(&self) -> taffy::Size<Dimension> {
         taffy::Size {
-            width: Self::to_dim(self.buf.min_width(self.idx)),
-            height: Self::to_dim(self.buf.min_height(self.idx)),
+            width: Self::to_dim(self.resolve_basis(self.buf.min_width(self.idx), N_MIN_WIDTH_BASIS, true)),
+            height: Self::to_dim(self.resolve_basis(self.buf.min_height(self.idx), N_MIN_HEIGHT_BASIS, false)),
         }
     }
 
     fn max_size(&self) -> taffy::Size<Dimension> {
         taffy::Size {
-            width: Self::to_dim(self.buf.max_width(self.idx)),
-            height: Self::to_dim(self.buf.max_height(self.idx)),
+            width: Self::to_dim(self.resolve_basis(self.buf.max_width(self.idx), N_MAX_WIDTH_BASIS, true)),
+            height: Self::to_dim(self.resolve_basis(self.buf.max_height(self.idx), N_MAX_HEIGHT_BASIS, false)),
         }
     }
 
     fn aspect_ratio(&self) -> Option<f32> {
         let v = self.buf.aspect_ratio(self.idx);
-        if v.is_nan() || v <= 0.0 { None } else { Some(v) }
+        if v.is_nan() || v <= 0.0 {
+            None
+        } else if self.buf.aspect_cells(self.idx) {
+            // Width ÷ height on screen → in cells: a cell is `cell_aspect` times taller than wide
+            Some(v * self.buf.cell_aspect())
+        } else {
+            Some(v)
+        }
     }
 
     fn margin(&self) -> taffy::Rect<LengthPercentageAuto> {
@@ -388,10 +638,10 @@ impl taffy::CoreStyle for NodeStyle<'_> {
 
     fn border(&self) -> taffy::Rect<LengthPercentage> {
         taffy::Rect {
-            top: LengthPercentage::length(if self.buf.border_top(self.idx) > 0 { 1.0 } else { 0.0 }),
-            right: LengthPercentage::length(if self.buf.border_right(self.idx) > 0 { 1.0 } else { 0.0 }),
-            bottom: LengthPercentage::length(if self.buf.border_bottom(self.idx) > 0 { 1.0 } else { 0.0 }),
-            left: LengthPercentage::length(if self.buf.border_left(self.idx) > 0 { 1.0 } else { 0.0 }),
+            top: LengthPercentage::length(self.buf.border_inset_top(self.idx) as f32),
+            right: LengthPercentage::length(self.buf.border_inset_right(self.idx) as f32),
+            bottom: LengthPercentage::length(self.buf.border_inset_bottom(self.idx) as f32),
+            left: LengthPercentage::length(self.buf.border_inset_left(self.idx) as f32),
         }
     }
 }
@@ -402,10 +652,14 @@ impl taffy::CoreStyle for NodeStyle<'_> {
 
 impl taffy::FlexboxContainerStyle for NodeStyle<'_> {
     fn flex_direction(&self) -> FlexDirection {
+        // Rows of a right-to-left box start at its right edge
+        let rtl = self.rtl[self.idx];
         match self.buf.flex_direction(self.idx) {
             1 => FlexDirection::Column,
+            2 if rtl => FlexDirection::Row,
             2 => FlexDirection::RowReverse,
             3 => FlexDirection::ColumnReverse,
+            _ if rtl => FlexDirection::RowReverse,
             _ => FlexDirection::Row,
         }
     }
@@ -429,27 +683,25 @@ impl taffy::FlexboxContainerStyle for NodeStyle<'_> {
     }
 
     fn align_content(&self) -> Option<AlignContent> {
-        match self.buf.align_content(self.idx) {
-            0 => Some(AlignContent::FlexStart),
-            1 => Some(AlignContent::FlexEnd),
-            2 => Some(AlignContent::Center),
-            3 => Some(AlignContent::Stretch),
-            4 => Some(AlignContent::SpaceBetween),
-            5 => Some(AlignContent::SpaceAround),
-            6 => Some(AlignContent::SpaceEvenly),
-            _ => None,
-        }
+        Some(match self.buf.align_content(self.idx) {
+            crate::shared_buffer::AlignContent::Start => AlignContent::FlexStart,
+            crate::shared_buffer::AlignContent::End => AlignContent::FlexEnd,
+            crate::shared_buffer::AlignContent::Center => AlignContent::Center,
+            crate::shared_buffer::AlignContent::SpaceBetween => AlignContent::SpaceBetween,
+            crate::shared_buffer::AlignContent::SpaceAround => AlignContent::SpaceAround,
+            crate::shared_buffer::AlignContent::SpaceEvenly => AlignContent::SpaceEvenly,
+            crate::shared_buffer::AlignContent::Stretch => AlignContent::Stretch,
+        })
     }
 
     fn align_items(&self) -> Option<AlignItems> {
-        match self.buf.align_items(self.idx) {
-            0 => Some(AlignItems::FlexStart),
-            1 => Some(AlignItems::FlexEnd),
-            2 => Some(AlignItems::Center),
-            3 => Some(AlignItems::Stretch),
-            4 => Some(AlignItems::Baseline),
-            _ => None,
-        }
+        Some(match self.buf.align_items(self.idx) {
+            crate::shared_buffer::AlignItems::Start => AlignItems::FlexStart,
+            crate::shared_buffer::AlignItems::End => AlignItems::FlexEnd,
+            crate::shared_buffer::AlignItems::Center => AlignItems::Center,
+            crate::shared_buffer::AlignItems::Baseline => AlignItems::Baseline,
+            crate::shared_buffer::AlignItems::Stretch => AlignItems::Stretch,
+        })
     }
 
     fn justify_content(&self) -> Option<JustifyContent> {
@@ -484,12 +736,12 @@ impl taffy::FlexboxItemStyle for NodeStyle<'_> {
 
     fn align_self(&self) -> Option<AlignSelf> {
         match self.buf.align_self(self.idx) {
-            1 => Some(AlignSelf::FlexStart),
-            2 => Some(AlignSelf::FlexEnd),
-            3 => Some(AlignSelf::Center),
-            4 => Some(AlignSelf::Stretch),
-            5 => Some(AlignSelf::Baseline),
-            _ => None,
+            crate::shared_buffer::AlignSelf::Auto => None,
+            crate::shared_buffer::AlignSelf::Start => Some(AlignSelf::FlexStart),
+            crate::shared_buffer::AlignSelf::End => Some(AlignSelf::FlexEnd),
+            crate::shared_buffer::AlignSelf::Center => Some(AlignSelf::Center),
+            crate::shared_buffer::AlignSelf::Baseline => Some(AlignSelf::Baseline),
+            crate::shared_buffer::AlignSelf::Stretch => Some(AlignSelf::Stretch),
         }
     }
 }
@@ -578,17 +830,16 @@ impl taffy::GridContainerStyle for NodeStyle<'_> {
 
 impl taffy::GridItemStyle for NodeStyle<'_> {
     fn grid_row(&self) -> taffy::Line<GridPlacement<Arc<str>>> {
-        taffy::Line {
-            start: to_placement(self.buf.grid_row_start(self.idx)),
-            end: to_placement(self.buf.grid_row_end(self.idx)),
-        }
+        let tracks = self.buf.parent_index(self.idx).map_or(0, |p| self.buf.grid_row_count(p));
+        let (start, end) = clamp_placement(self.buf.grid_row_start(self.idx), self.buf.grid_row_end(self.idx), tracks);
+        taffy::Line { start: to_placement(start), end: to_placement(end) }
     }
 
     fn grid_column(&self) -> taffy::Line<GridPlacement<Arc<str>>> {
-        taffy::Line {
-            start: to_placement(self.buf.grid_column_start(self.idx)),
-            end: to_placement(self.buf.grid_column_end(self.idx)),
-        }
+        let tracks = self.buf.parent_index(self.idx).map_or(0, |p| self.buf.grid_column_count(p));
+        let (start, end) =
+            clamp_placement(self.buf.grid_column_start(self.idx), self.buf.grid_column_end(self.idx), tracks);
+        taffy::Line { start: to_placement(start), end: to_placement(end) }
     }
 
     fn align_self(&self) -> Option<AlignSelf> {
@@ -610,6 +861,22 @@ impl taffy::GridItemStyle for NodeStyle<'_> {
 // HELPER FUNCTIONS
 // =============================================================================
 
+/// Keep a start/end placement (line, negative = span, 0 = auto) inside the
+/// parent's `tracks` explicit tracks, if it declares any: spans shrink to the
+/// room left, lines past the last one move back onto the grid. Without this a
+/// `colSpan` wider than the template spills into implicit auto tracks.
+fn clamp_placement(start: i16, end: i16, tracks: u8) -> (i16, i16) {
+    if tracks == 0 {
+        return (start, end);
+    }
+    let tracks = tracks as i16;
+    let last_line = tracks + 1;
+    let start = if start > 0 { start.min(tracks) } else { start.max(-tracks) };
+    let room = if start > 0 { last_line - start } else { tracks };
+    let end = if end > 0 { end.min(last_line) } else { end.max(-room) };
+    (start, end)
+}
+
 #[inline]
 fn to_placement(val: i16) -> GridPlacement<Arc<str>> {
     if val == 0 {
@@ -645,13 +912,35 @@ pub struct LayoutTree<'a> {
 }
 
 impl<'a> LayoutTree<'a> {
-    fn write_output(&self, node_count: usize) {
+    /// Write computed layouts back; returns how many nodes got a new rect.
+    fn write_output(&self, node_count: usize) -> u32 {
+        // Scroll anchors, taken from where things are on screen now
+        let anchors: Vec<(usize, (usize, f32))> = (0..node_count)
+            .filter(|&i| self.buf.component_type(i) != COMPONENT_NONE)
+            .filter_map(|i| scroll_anchor(self.buf, i, &self.ctx.children[i]).map(|anchor| (i, anchor)))
+            .collect();
+
+        // Reach-end watchers, and whether each was already reported as near
+        let mut ends = Vec::new();
+        let mut changed = 0;
         for i in 0..node_count {
             if self.buf.component_type(i) == COMPONENT_NONE {
                 continue;
             }
 
             let layout = self.ctx.final_layout[i];
+            if self.buf.computed_x(i) != layout.location.x
+                || self.buf.computed_y(i) != layout.location.y
+                || self.buf.computed_width(i) != layout.size.width
+                || self.buf.computed_height(i) != layout.size.height
+            {
+                changed += 1;
+            }
+            // Near the end with content of the same size: nothing new to report
+            if self.buf.scroll_flags(i) & SCROLL_REACH_END != 0 {
+                let same_size = self.buf.content_height(i) == layout.content_size.height;
+                ends.push((i, same_size && near_end(self.buf, i)));
+            }
             self.buf.set_computed_x(i, layout.location.x);
             self.buf.set_computed_y(i, layout.location.y);
             self.buf.set_computed_width(i, layout.size.width);
@@ -673,19 +962,36 @@ impl<'a> LayoutTree<'a> {
                 );
             }
 
+            // Each axis scrolls on its own: a clipped axis has nothing to scroll
             let has_children = !self.ctx.children[i].is_empty();
-            let overflow = self.buf.overflow(i);
-            let max_scroll_x = (children_max_x - layout.size.width).max(0.0);
-            let max_scroll_y = (children_max_y - layout.size.height).max(0.0);
-            let scrollable = match overflow {
+            let axis_scrolls = |overflow: u8, max_scroll: f32| match overflow {
                 1 => false,                    // clip
                 2 | 3 => true,                 // scroll/auto
-                _ => has_children && (max_scroll_x > 0.0 || max_scroll_y > 0.0),
+                _ => has_children && max_scroll > 0.0,
             };
-
-            self.buf.set_output_scroll(i, scrollable, max_scroll_x, max_scroll_y);
+            let max_scroll_x = (children_max_x - layout.size.width).max(0.0);
+            let max_scroll_y = (children_max_y - layout.size.height).max(0.0);
+            let scrolls_x = axis_scrolls(self.buf.overflow_x(i), max_scroll_x);
+            let scrolls_y = axis_scrolls(self.buf.overflow_y(i), max_scroll_y);
+            let max_scroll_x = if scrolls_x { max_scroll_x } else { 0.0 };
+            let max_scroll_y = if scrolls_y { max_scroll_y } else { 0.0 };
+
+            let old_max_y = self.buf.max_scroll_y(i);
+            self.buf.set_output_scroll(i, scrolls_x || scrolls_y, max_scroll_x, max_scroll_y);
+            // Anchored nodes clamp once their anchor has moved them
+            if !anchors.iter().any(|&(node, _)| node == i) {
+                clamp_scroll(self.buf, i);
+            }
+            stick_to_bottom(self.buf, i, old_max_y);
             self.buf.clear_dirty(i);
         }
+        for (i, anchor) in anchors {
+            keep_anchor(self.buf, i, anchor);
+        }
+        for (i, was_near) in ends {
+            reach_end(self.buf, i, was_near);
+        }
+        changed
     }
 }
 
@@ -747,7 +1053,7 @@ impl LayoutPartialTree for LayoutTree<'_> {
     type CustomIdent = Arc<str>;
 
     fn get_core_container_style(&self, node: NodeId) -> Self::CoreContainerStyle<'_> {
-        NodeStyle::new(self.buf, usize::from(node))
+        NodeStyle::new(self.buf, self.ctx, usize::from(node))
     }
 
     fn set_unrounded_layout(&mut self, node: NodeId, layout: &Layout) {
@@ -774,10 +1080,27 @@ impl LayoutPartialTree for LayoutTree<'_> {
                     _ => compute_hidden_layout(tree, node), // Unknown = hidden (fail visible)
                 },
                 COMPONENT_TEXT | COMPONENT_INPUT => {
-                    let style = NodeStyle::new(tree.buf, idx);
-                    let text = tree.buf.text(idx);
+                    let ctx = &mut *tree.ctx;
+                    let style = NodeStyle {
+                        buf: tree.buf,
+                        layouts: &ctx.final_layout,
+                        rtl: &ctx.rtl,
+                        idx,
+                    };
+                    // Inputs draw one picture per control in either mode
+                    let mut text = if comp == COMPONENT_INPUT {
+                        input_pictures(tree.buf.text(idx))
+                    } else {
+                        visualize_controls(tree.buf.text(idx), tree.buf.control_chars())
+                    };
+                    // Unbroken soft hyphens aren't drawn
+                    if tree.buf.text_wrap(idx) == TextWrap::Hyphenate {
+                        text = Cow::Owned(text.replace('\u{AD}', ""));
+                    }
+                    let text = text.as_ref();
+                    let measure_cache = &mut ctx.measure_cache;
 
-                    compute_leaf_layout(
+                    let output = compute_leaf_layout(
                         inputs,
                         &style,
                         |_, _| 0.0, // resolve_calc_value (no-op, we don't use calc())
@@ -793,12 +1116,23 @@ impl LayoutPartialTree for LayoutTree<'_> {
                                     AvailableSpace::MaxContent => usize::MAX,
                                 },
                             };
-                            taffy::Size {
-                                width: string_width(text) as f32,
-                                height: measure_text_height(text, max_w) as f32,
-                            }
+                            let (width, height) = measure_cache.measure(idx, text, max_w);
+                            taffy::Size { width: width as f32, height: height as f32 }
                         },
-                    )
+                    );
+                    if text.is_empty() {
+                        return output;
+                    }
+
+                    // Baseline: the bottom of the first line, below the top padding and border.
+                    // Taffy aligns `baseline` rows on it and bubbles it up through flex containers.
+                    let parent_width = inputs.parent_size.width;
+                    let inset_top = style.padding().top.resolve_or_zero(parent_width, |_, _| 0.0)
+                        + style.border().top.resolve_or_zero(parent_width, |_, _| 0.0);
+                    LayoutOutput {
+                        first_baselines: taffy::Point { x: None, y: Some(inset_top + TEXT_LINE_HEIGHT) },
+                        ..output
+                    }
                 }
                 _ => compute_hidden_layout(tree, node),
             }
@@ -857,11 +1191,11 @@ impl LayoutFlexboxContainer for LayoutTree<'_> {
     type FlexboxItemStyle<'a> = NodeStyle<'a> where Self: 'a;
 
     fn get_flexbox_container_style(&self, node: NodeId) -> Self::FlexboxContainerStyle<'_> {
-        NodeStyle::new(self.buf, usize::from(node))
+        NodeStyle::new(self.buf, self.ctx, usize::from(node))
     }
 
     fn get_flexbox_child_style(&self, child: NodeId) -> Self::FlexboxItemStyle<'_> {
-        NodeStyle::new(self.buf, usize::from(child))
+        NodeStyle::new(self.buf, self.ctx, usize::from(child))
     }
 }
 
@@ -874,11 +1208,11 @@ impl LayoutGridContainer for LayoutTree<'_> {
     type GridItemStyle<'a> = NodeStyle<'a> where Self: 'a;
 
     fn get_grid_container_style(&self, node: NodeId) -> Self::GridContainerStyle<'_> {
-        NodeStyle::new(self.buf, usize::from(node))
+        NodeStyle::new(self.buf, self.ctx, usize::from(node))
     }
 
     fn get_grid_child_style(&self, child: NodeId) -> Self::GridItemStyle<'_> {
-        NodeStyle::new(self.buf, usize::from(child))
+        NodeStyle::new(self.buf, self.ctx, usize::from(child))
     }
 }
 
@@ -905,6 +1239,10 @@ impl RoundTree for LayoutTree<'_> {
 
 /// Compute layout for all nodes in the SharedBuffer.
 ///
+/// Incremental: only nodes flagged DIRTY_LAYOUT, DIRTY_TEXT or
+/// DIRTY_HIERARCHY since the last call (and what depends on them) are laid
+/// out again; the rest come from Taffy's caches. The flags are cleared here.
+///
 /// Returns the number of nodes processed.
 pub fn compute_layout(buf: &SharedBuffer) -> u32 {
     let node_count = buf.node_count();
@@ -912,8 +1250,8 @@ pub fn compute_layout(buf: &SharedBuffer) -> u32 {
     LAYOUT_CONTEXT.with(|cell| {
         let mut ctx = cell.borrow_mut();
         ctx.ensure_capacity(node_count);
-        ctx.clear_dirty_caches(buf, node_count);
-        ctx.rebuild_hierarchy(buf, node_count);
+        ctx.invalidate(buf, node_count);
+        ctx.resolve_directions(buf);
 
         let mut tree = LayoutTree { buf, ctx: &mut *ctx };
 
@@ -924,25 +1262,56 @@ pub fn compute_layout(buf: &SharedBuffer) -> u32 {
         // Available space depends on render mode:
         // - Fullscreen: use terminal dimensions
         // - Inline/Append: width from terminal, height unbounded (content determines)
+        //   unless inline has a max height, which % heights then resolve against
         let render_mode = buf.render_mode();
         let available = taffy::Size {
             width: AvailableSpace::Definite(buf.terminal_width() as f32),
             height: match render_mode {
                 RenderMode::Diff => AvailableSpace::Definite(buf.terminal_height() as f32),
+                RenderMode::Inline if buf.inline_max_height() > 0 => {
+                    AvailableSpace::Definite(buf.inline_max_height() as f32)
+                }
                 RenderMode::Inline | RenderMode::Append => AvailableSpace::MaxContent,
             },
         };
 
         let roots = tree.ctx.roots.clone();
-        for &root in &roots {
-            compute_root_layout(&mut tree, NodeId::from(root), available);
+        // Named-ancestor percentages size against the ancestor's last layout:
+        // when this one resized it, once more with the new size
+        let named_before = named_basis_sizes(buf, &tree.ctx.final_layout, &tree.ctx.named);
+        for pass in 0..2 {
+            if pass > 0 {
+                let named_after = named_basis_sizes(buf, &tree.ctx.final_layout, &tree.ctx.named);
+                let moved: Vec<usize> = tree.ctx.named.iter().zip(named_after.iter().zip(&named_before))
+                    .filter(|(_, (after, before))| after != before)
+                    .map(|(&i, _)| i)
+                    .collect();
+                if moved.is_empty() {
+                    break;
+                }
+                // Just those nodes lay out again, and what contains them
+                for i in moved {
+                    tree.ctx.clear_ancestors(buf, i, node_count);
+                    tree.ctx.clear_subtree(i);
+                }
+            }
+            for &root in &roots {
+                compute_root_layout(&mut tree, NodeId::from(root), available);
+            }
+            for &root in &roots {
+                round_layout(&mut tree, NodeId::from(root));
+            }
         }
-        for &root in &roots {
-            round_layout(&mut tree, NodeId::from(root));
+        // Inline/Append: regions share the output, one below the other
+        if render_mode != RenderMode::Diff {
+            tree.ctx.stack_regions(buf);
         }
 
-        tree.write_output(node_count);
+        let changed = tree.write_output(node_count);
         buf.increment_layout_count();
+        if changed > 0 && buf.layout_events() {
+            buf.push_layout_change_event(changed);
+        }
 
         node_count as u32
     })
@@ -950,5 +1319,505 @@ pub fn compute_layout(buf: &SharedBuffer) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    // Tests go here
+    use super::*;
+    use crate::shared_buffer::test_support::TestBuffer;
+
+    #[test]
+    fn test_clamp_placement_to_explicit_tracks() {
+        // No explicit tracks: untouched
+        assert_eq!(clamp_placement(2, -9, 0), (2, -9));
+        // Span wider than the grid shrinks; from line 2 only two tracks remain
+        assert_eq!(clamp_placement(0, -5, 3), (0, -3));
+        assert_eq!(clamp_placement(2, -5, 3), (2, -2));
+        // Lines past the end move onto the last track
+        assert_eq!(clamp_placement(5, 9, 3), (3, 4));
+        // Valid placements pass through
+        assert_eq!(clamp_placement(1, 3, 3), (1, 3));
+    }
+
+    #[test]
+    fn test_keyed_slots_reorder_siblings() {
+        // 0: root; 1: header; 2: list anchor (unused slot); 3: footer; 4, 5: list items
+        let nodes = 6;
+        let buf = TestBuffer::new(nodes, 0);
+        for i in [0, 1, 3, 4, 5] {
+            buf.node(i, COMPONENT_BOX, if i == 0 { -1 } else { 0 });
+        }
+
+        let mut ctx = LayoutContext::new();
+        ctx.ensure_capacity(nodes);
+        ctx.rebuild_hierarchy(&buf, nodes);
+        assert_eq!(ctx.children[0], vec![1, 3, 4, 5]);
+
+        // Items sit at the anchor's slot, in list order, before the footer
+        buf.set_slot(5, Some(2), 0);
+        buf.set_slot(4, Some(2), 1);
+        ctx.rebuild_hierarchy(&buf, nodes);
+        assert_eq!(ctx.children[0], vec![1, 5, 4, 3]);
+        assert_eq!(ctx.roots, vec![0]);
+    }
+
+    /// Text pool bytes in test buffers
+    const TEST_POOL: usize = 256;
+
+    /// Zeroed buffer for `nodes` nodes.
+    fn test_buffer(nodes: usize) -> TestBuffer {
+        TestBuffer::new(nodes, TEST_POOL)
+    }
+
+    /// Visible flex box with the given size (NaN = auto), everything else auto.
+    fn test_box(buf: &TestBuffer, i: usize, parent: i32, width: f32, height: f32) {
+        use crate::shared_buffer::{
+            N_ASPECT_RATIO, N_DISPLAY, N_FLEX_BASIS, N_HEIGHT, N_MAX_HEIGHT, N_MAX_WIDTH, N_MIN_HEIGHT, N_MIN_WIDTH,
+            N_WIDTH,
+        };
+
+        buf.node(i, COMPONENT_BOX, parent);
+        buf.put_u8(i, N_DISPLAY, DISPLAY_FLEX);
+        let dims = [
+            (N_WIDTH, width),
+            (N_HEIGHT, height),
+            (N_MIN_WIDTH, f32::NAN),
+            (N_MIN_HEIGHT, f32::NAN),
+            (N_MAX_WIDTH, f32::NAN),
+            (N_MAX_HEIGHT, f32::NAN),
+            (N_ASPECT_RATIO, f32::NAN),
+            (N_FLEX_BASIS, f32::NAN),
+        ];
+        for (offset, value) in dims {
+            buf.put_f32(i, offset, value);
+        }
+    }
+
+    /// Flag node `i` as changed, as the TS setters do: layout is incremental
+    fn touch(buf: &TestBuffer, i: usize) {
+        buf.mark_dirty(i, DIRTY_LAYOUT);
+    }
+
+    #[test]
+    fn test_input_controls_measure_as_drawn() {
+        use crate::framebuffer::compute_framebuffer;
+        use crate::shared_buffer::{ControlChars, COMPONENT_INPUT, H_CONTROL_CHARS, N_COMPONENT_TYPE};
+
+        // Row [ input (auto width) ], controls in caret form
+        let buf = test_buffer(2);
+        test_box(&buf, 0, -1, 20.0, 1.0);
+        test_box(&buf, 1, 0, f32::NAN, 1.0);
+        buf.put_u8(1, N_COMPONENT_TYPE, COMPONENT_INPUT);
+        buf.put_header_u8(H_CONTROL_CHARS, ControlChars::Caret as u8);
+        assert!(buf.set_text(1, "a\x1b\nb"));
+        buf.set_terminal_size(20, 1);
+
+        compute_layout(&buf);
+        let (frame, _) = compute_framebuffer(&buf, 20, 1);
+        let drawn: String = (0..20).filter_map(|x| char::from_u32(frame.get(x, 0)?.char)).collect();
+        assert_eq!(drawn.trim_end(), "a␛␊b");
+        assert_eq!(buf.computed_width(1), 4.0);
+    }
+
+    #[test]
+    fn test_layout_change_events() {
+        use crate::shared_buffer::H_LAYOUT_EVENTS;
+
+        let buf = test_buffer(1);
+        buf.put_header_u8(H_LAYOUT_EVENTS, 1);
+        // width: 100%, height: 3
+        test_box(&buf, 0, -1, -100.0, 3.0);
+        buf.set_terminal_size(20, 10);
+
+        compute_layout(&buf);
+        assert_eq!(buf.event_write_count(), 1);
+        // Same layout again: nothing moved, no event
+        compute_layout(&buf);
+        assert_eq!(buf.event_write_count(), 1);
+        buf.set_terminal_size(30, 10);
+        compute_layout(&buf);
+        assert_eq!(buf.event_write_count(), 2);
+    }
+
+    #[test]
+    fn test_inline_regions_stack_in_order() {
+        use crate::shared_buffer::{H_RENDER_MODE, N_REGION};
+
+        // 0: prompt (region 1), 1: footer (region 2), 2: log (region 0)
+        let buf = test_buffer(3);
+        test_box(&buf, 0, -1, 10.0, 2.0);
+        test_box(&buf, 1, -1, 10.0, 1.0);
+        test_box(&buf, 2, -1, 10.0, 3.0);
+        buf.put_u8(0, N_REGION, 1);
+        buf.put_u8(1, N_REGION, 2);
+        buf.put_header_u8(H_RENDER_MODE, RenderMode::Inline as u8);
+        buf.set_terminal_size(20, 10);
+
+        compute_layout(&buf);
+        assert_eq!([buf.computed_y(2), buf.computed_y(0), buf.computed_y(1)], [0.0, 3.0, 5.0]);
+
+        // Fullscreen: every root at the top, as before
+        buf.put_header_u8(H_RENDER_MODE, RenderMode::Diff as u8);
+        compute_layout(&buf);
+        assert_eq!([buf.computed_y(2), buf.computed_y(0), buf.computed_y(1)], [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_percentages_of_viewport_and_named_ancestor() {
+        use crate::shared_buffer::{N_RELATIVE_TO, N_WIDTH};
+
+        // 0: the whole terminal; 1: a 10-wide column; 2: inside it, half of 0 wide and a quarter of the terminal high
+        let buf = test_buffer(3);
+        test_box(&buf, 0, -1, -100.0, -100.0);
+        test_box(&buf, 1, 0, 10.0, 10.0);
+        test_box(&buf, 2, 1, -50.0, -25.0);
+        buf.put_u8(2, N_WIDTH_BASIS, PercentBasis::Named as u8);
+        buf.put_u8(2, N_HEIGHT_BASIS, PercentBasis::ViewportHeight as u8);
+        buf.put_i32(2, N_RELATIVE_TO, 0);
+        buf.set_terminal_size(40, 20);
+
+        // The ancestor is sized in the same layout: the second pass sees it
+        compute_layout(&buf);
+        assert_eq!((buf.computed_width(2), buf.computed_height(2)), (20.0, 5.0));
+
+        // Follows the ancestor and the viewport as they resize
+        buf.set_terminal_size(60, 40);
+        compute_layout(&buf);
+        assert_eq!((buf.computed_width(2), buf.computed_height(2)), (30.0, 10.0));
+
+        // Not an ancestor: a plain percentage of the parent
+        buf.put_i32(2, N_RELATIVE_TO, 2);
+        touch(&buf, 2);
+        compute_layout(&buf);
+        assert_eq!(buf.computed_width(2), 5.0);
+
+        // A node that starts sizing against an ancestor later is picked up
+        buf.put_f32(1, N_WIDTH, -50.0);
+        buf.put_u8(1, N_WIDTH_BASIS, PercentBasis::Named as u8);
+        buf.put_i32(1, N_RELATIVE_TO, 0);
+        touch(&buf, 1);
+        compute_layout(&buf);
+        assert_eq!((buf.computed_width(1), buf.computed_width(2)), (30.0, 15.0));
+    }
+
+    #[test]
+    fn test_aspect_cells_corrects_for_the_cell_shape() {
+        use crate::shared_buffer::{N_ASPECT_CELLS, N_ASPECT_RATIO};
+
+        // A "square" 4 rows high, width from the aspect ratio
+        let buf = test_buffer(1);
+        test_box(&buf, 0, -1, f32::NAN, 4.0);
+        buf.put_f32(0, N_ASPECT_RATIO, 1.0);
+        buf.set_terminal_size(40, 20);
+
+        // Plain aspect ratio: square in cells
+        compute_layout(&buf);
+        assert_eq!(buf.computed_width(0), 4.0);
+
+        // Corrected: the default 2:1 cell until the terminal says otherwise
+        buf.put_u8(0, N_ASPECT_CELLS, 1);
+        touch(&buf, 0);
+        compute_layout(&buf);
+        assert_eq!(buf.computed_width(0), 8.0);
+        buf.set_cell_pixel_size(8, 20);
+        compute_layout(&buf);
+        assert_eq!(buf.computed_width(0), 10.0);
+
+        // A configured aspect wins over the measured one
+        buf.set_cell_aspect(1.5);
+        compute_layout(&buf);
+        assert_eq!(buf.computed_width(0), 6.0);
+    }
+
+    #[test]
+    fn test_baseline_aligns_text_on_its_first_line() {
+        use crate::shared_buffer::{AlignItems, N_ALIGN_ITEMS, N_COMPONENT_TYPE, N_PADDING_TOP};
+
+        // 0: a baseline row; 1: text padded down 2 rows; 2: two lines of text; 3: a box holding a text
+        let buf = test_buffer(5);
+        test_box(&buf, 0, -1, 30.0, 10.0);
+        for (i, parent) in [(1, 0), (2, 0), (3, 0), (4, 3)] {
+            test_box(&buf, i, parent, f32::NAN, f32::NAN);
+            if i != 3 {
+                buf.put_u8(i, N_COMPONENT_TYPE, COMPONENT_TEXT);
+            }
+        }
+        buf.put_u8(0, N_ALIGN_ITEMS, AlignItems::Baseline as u8);
+        buf.put_f32(1, N_PADDING_TOP, 2.0);
+        buf.set_text(1, "padded");
+        buf.set_text(2, "one\ntwo");
+        buf.set_text(4, "nested");
+        buf.set_terminal_size(40, 20);
+
+        // Every first line on row 2, with the padded text's
+        compute_layout(&buf);
+        assert_eq!(buf.computed_y(1), 0.0);
+        assert_eq!(buf.computed_y(2), 2.0);
+        assert_eq!(buf.computed_y(3) + buf.computed_y(4), 2.0);
+
+        // Start-aligned: all at the top
+        buf.put_u8(0, N_ALIGN_ITEMS, AlignItems::Start as u8);
+        touch(&buf, 0);
+        compute_layout(&buf);
+        assert_eq!([buf.computed_y(1), buf.computed_y(2), buf.computed_y(3)], [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_incremental_layout_follows_flagged_changes() {
+        use crate::shared_buffer::{FlexDirection, N_FLEX_DIRECTION, N_HEIGHT, N_VISIBLE};
+
+        // 0: an auto-height column; 1, 2: rows of 2; 3: inside 2, 1 high
+        let buf = test_buffer(4);
+        test_box(&buf, 0, -1, 10.0, f32::NAN);
+        buf.put_u8(0, N_FLEX_DIRECTION, FlexDirection::Column as u8);
+        test_box(&buf, 1, 0, 10.0, 2.0);
+        test_box(&buf, 2, 0, 10.0, f32::NAN);
+        test_box(&buf, 3, 2, 10.0, 1.0);
+        buf.set_terminal_size(20, 20);
+        compute_layout(&buf);
+        assert_eq!((buf.computed_y(2), buf.computed_height(0)), (2.0, 3.0));
+
+        // A deep leaf grows: its ancestors grow around it
+        buf.put_f32(3, N_HEIGHT, 4.0);
+        touch(&buf, 3);
+        compute_layout(&buf);
+        assert_eq!((buf.computed_height(2), buf.computed_height(0)), (4.0, 6.0));
+
+        // Hidden: out of the tree, and its sibling moves up
+        buf.put_u8(1, N_VISIBLE, 0);
+        touch(&buf, 1);
+        compute_layout(&buf);
+        assert_eq!((buf.computed_y(2), buf.computed_height(0)), (0.0, 4.0));
+
+        // Nothing flagged: nothing moves
+        compute_layout(&buf);
+        assert_eq!((buf.computed_y(2), buf.computed_height(0)), (0.0, 4.0));
+    }
+
+    #[test]
+    fn test_inline_max_height_bounds_percent_roots() {
+        use crate::shared_buffer::H_RENDER_MODE;
+
+        // 0: a root filling the height, 1: a root of fixed height
+        let buf = test_buffer(2);
+        test_box(&buf, 0, -1, 10.0, -100.0);
+        test_box(&buf, 1, -1, 10.0, 30.0);
+        buf.put_header_u8(H_RENDER_MODE, RenderMode::Inline as u8);
+        buf.set_terminal_size(20, 40);
+
+        // Unbounded: nothing for % to resolve against, so content (none) decides
+        compute_layout(&buf);
+        assert_eq!(buf.computed_height(0), 0.0);
+
+        // With a max, % fills it; fixed heights are left alone
+        buf.set_inline_height(0, 8);
+        compute_layout(&buf);
+        assert_eq!(buf.computed_height(0), 8.0);
+        assert_eq!(buf.computed_height(1), 30.0);
+    }
+
+    #[test]
+    fn test_scroll_anchors_to_the_first_visible_child() {
+        use crate::shared_buffer::{
+            FlexDirection, Overflow, N_FLEX_DIRECTION, N_HEIGHT, N_OVERFLOW,
+            N_SCROLL_FLAGS, SCROLL_NO_ANCHOR,
+        };
+
+        // A 5-row scroll column of three 5-row children
+        let buf = test_buffer(4);
+        test_box(&buf, 0, -1, 10.0, 5.0);
+        buf.put_u8(0, N_FLEX_DIRECTION, FlexDirection::Column as u8);
+        buf.put_u8(0, N_OVERFLOW, Overflow::Scroll as u8);
+        for child in 1..4 {
+            test_box(&buf, child, 0, 10.0, 5.0);
+        }
+        let set_height = |i: usize, height: f32| {
+            buf.put_f32(i, N_HEIGHT, height);
+            touch(&buf, i);
+        };
+        buf.set_terminal_size(20, 10);
+
+        // At the top, growth above pushes content down as usual
+        compute_layout(&buf);
+        set_height(1, 6.0);
+        compute_layout(&buf);
+        assert_eq!(buf.scroll_y(0), 0);
+
+        // Reading the second child: the first growing keeps it in view
+        buf.set_scroll(0, 0, 6);
+        set_height(1, 9.0);
+        compute_layout(&buf);
+        assert_eq!(buf.scroll_y(0), 9);
+        assert_eq!(buf.computed_y(2) - buf.scroll_y(0) as f32, 0.0);
+
+        // Shrinking pulls the offset back just as far
+        set_height(1, 7.0);
+        compute_layout(&buf);
+        assert_eq!(buf.scroll_y(0), 7);
+
+        // Opted out: the offset stays and the content shifts
+        buf.put_u8(0, N_SCROLL_FLAGS, SCROLL_NO_ANCHOR);
+        set_height(1, 10.0);
+        compute_layout(&buf);
+        assert_eq!(buf.scroll_y(0), 7);
+    }
+
+    #[test]
+    fn test_anchored_scroll_at_the_bottom_clamps_once() {
+        use crate::shared_buffer::{FlexDirection, Overflow, N_FLEX_DIRECTION, N_HEIGHT, N_OVERFLOW};
+
+        // A 5-row scroll column of three children, 7 + 5 + 5 rows
+        let buf = test_buffer(4);
+        test_box(&buf, 0, -1, 10.0, 5.0);
+        buf.put_u8(0, N_FLEX_DIRECTION, FlexDirection::Column as u8);
+        buf.put_u8(0, N_OVERFLOW, Overflow::Scroll as u8);
+        test_box(&buf, 1, 0, 10.0, 7.0);
+        for child in 2..4 {
+            test_box(&buf, child, 0, 10.0, 5.0);
+        }
+        buf.set_terminal_size(20, 10);
+        compute_layout(&buf);
+
+        // At the bottom, the offset follows the last child rather than
+        // clamping to the smaller range first and then moving again
+        buf.set_scroll(0, 0, 12);
+        buf.put_f32(1, N_HEIGHT, 4.0);
+        touch(&buf, 1);
+        compute_layout(&buf);
+        assert_eq!((buf.max_scroll_y(0), buf.scroll_y(0)), (9.0, 9));
+    }
+
+    #[test]
+    fn test_reach_end_reports_short_and_shrinking_content() {
+        use crate::shared_buffer::{
+            EventType, FlexDirection, Overflow, N_FLEX_DIRECTION, N_HEIGHT, N_OVERFLOW,
+            N_REACH_END_THRESHOLD, N_SCROLL_FLAGS,
+        };
+
+        // A 5-row scroll column over one child, reporting within 2 rows of the end
+        let buf = test_buffer(2);
+        test_box(&buf, 0, -1, 10.0, 5.0);
+        buf.put_u8(0, N_FLEX_DIRECTION, FlexDirection::Column as u8);
+        buf.put_u8(0, N_OVERFLOW, Overflow::Scroll as u8);
+        buf.put_u8(0, N_SCROLL_FLAGS, SCROLL_REACH_END);
+        buf.put_u8(0, N_REACH_END_THRESHOLD, 2);
+        test_box(&buf, 1, 0, 10.0, 3.0);
+        let set_height = |height: f32| {
+            buf.put_f32(1, N_HEIGHT, height);
+            touch(&buf, 1);
+        };
+        buf.set_terminal_size(20, 10);
+        let reached = |buf: &SharedBuffer| {
+            compute_layout(buf);
+            std::iter::from_fn(|| buf.pop_event()).filter(|event| event[0] == EventType::ReachEnd as u8).count()
+        };
+
+        // Too short to scroll: at the end from the start, and again after
+        // each load that still leaves it short
+        assert_eq!(reached(&buf), 1);
+        set_height(4.0);
+        assert_eq!(reached(&buf), 1);
+        assert_eq!(reached(&buf), 0, "nothing changed");
+
+        // Long enough: not near the end until it shrinks back under the offset
+        set_height(20.0);
+        assert_eq!(reached(&buf), 0);
+        buf.set_scroll(0, 0, 10);
+        set_height(14.0);
+        assert_eq!(reached(&buf), 1);
+        assert_eq!(buf.scroll_y(0), 9);
+    }
+
+    #[test]
+    fn test_framed_border_insets_content() {
+        use crate::shared_buffer::{
+            AlignItems, BorderStyle, N_ALIGN_ITEMS, N_BORDER_INNER_STYLE, N_BORDER_WIDTH_BOTTOM,
+            N_BORDER_WIDTH_LEFT, N_BORDER_WIDTH_RIGHT, N_BORDER_WIDTH_TOP,
+        };
+
+        let buf = test_buffer(2);
+        test_box(&buf, 0, -1, 10.0, 6.0);
+        buf.put_u8(0, N_ALIGN_ITEMS, AlignItems::Stretch as u8);
+        test_box(&buf, 1, 0, f32::NAN, f32::NAN);
+        for side in [N_BORDER_WIDTH_TOP, N_BORDER_WIDTH_RIGHT, N_BORDER_WIDTH_BOTTOM, N_BORDER_WIDTH_LEFT] {
+            buf.put_u8(0, side, 1);
+        }
+        buf.set_terminal_size(20, 10);
+
+        compute_layout(&buf);
+        assert_eq!((buf.computed_x(1), buf.computed_y(1)), (1.0, 1.0));
+        assert_eq!(buf.computed_height(1), 4.0);
+
+        // Framed: two cells per side
+        buf.put_u8(0, N_BORDER_INNER_STYLE, BorderStyle::Single as u8);
+        touch(&buf, 0);
+        compute_layout(&buf);
+        assert_eq!((buf.computed_x(1), buf.computed_y(1)), (2.0, 2.0));
+        assert_eq!(buf.computed_height(1), 2.0);
+    }
+
+    #[test]
+    fn test_wrapped_rows_align_content() {
+        use crate::shared_buffer::{AlignContent as Align, N_ALIGN_CONTENT, N_ALIGN_ITEMS, N_FLEX_WRAP};
+
+        // 10x10 wrapping row of four 4-wide items: two per line, two lines
+        let buf = test_buffer(5);
+        test_box(&buf, 0, -1, 10.0, 10.0);
+        buf.put_u8(0, N_FLEX_WRAP, 1);
+        buf.put_u8(0, N_ALIGN_ITEMS, crate::shared_buffer::AlignItems::Stretch as u8);
+        for i in 1..5 {
+            // Item 1 is 3 tall; the rest are auto height and stretch to their line
+            test_box(&buf, i, 0, 4.0, if i == 1 { 3.0 } else { f32::NAN });
+        }
+        buf.set_terminal_size(40, 20);
+        let set_align = |align: Align| {
+            buf.put_u8(0, N_ALIGN_CONTENT, align as u8);
+            touch(&buf, 0);
+        };
+
+        set_align(Align::SpaceBetween);
+        compute_layout(&buf);
+        // Auto-height items stretch to their line: the first line is 3 tall
+        // (item 1), the second has nothing definite and sits at the bottom
+        assert_eq!(buf.computed_height(2), 3.0);
+        assert_eq!((buf.computed_y(3), buf.computed_height(3)), (10.0, 0.0));
+
+        set_align(Align::Stretch);
+        compute_layout(&buf);
+        // The 7 free rows are shared: lines of 6.5 and 3.5, rounded
+        assert_eq!(buf.computed_height(2), 7.0);
+        assert_eq!((buf.computed_y(3), buf.computed_height(3)), (7.0, 3.0));
+
+        set_align(Align::Center);
+        compute_layout(&buf);
+        assert_eq!((buf.computed_y(1), buf.computed_y(3)), (4.0, 7.0));
+        // Scroll extent spans the lines, not the container
+        assert_eq!(buf.content_height(0), 7.0);
+    }
+
+    #[test]
+    fn test_rows_follow_the_inherited_direction() {
+        use crate::shared_buffer::{TextDirection, N_DIRECTION, N_FLEX_DIRECTION};
+
+        // rtl root column [ row [a b], ltr row [c d] ], each item 2 wide
+        let buf = test_buffer(7);
+        test_box(&buf, 0, -1, 10.0, 2.0);
+        buf.put_u8(0, N_FLEX_DIRECTION, 1);
+        buf.put_u8(0, N_DIRECTION, TextDirection::Rtl as u8);
+        test_box(&buf, 1, 0, 10.0, 1.0);
+        test_box(&buf, 2, 1, 2.0, 1.0);
+        test_box(&buf, 3, 1, 2.0, 1.0);
+        test_box(&buf, 4, 0, 10.0, 1.0);
+        buf.put_u8(4, N_DIRECTION, TextDirection::Ltr as u8);
+        test_box(&buf, 5, 4, 2.0, 1.0);
+        test_box(&buf, 6, 4, 2.0, 1.0);
+        buf.set_terminal_size(10, 2);
+
+        compute_layout(&buf);
+        // The inheriting row starts at the right edge
+        assert_eq!((buf.computed_x(2), buf.computed_x(3)), (8.0, 6.0));
+        assert_eq!((buf.computed_x(5), buf.computed_x(6)), (0.0, 2.0));
+
+        // Flipping the root flips its inheriting descendants only
+        buf.put_u8(0, N_DIRECTION, TextDirection::Ltr as u8);
+        touch(&buf, 0);
+        compute_layout(&buf);
+        assert_eq!((buf.computed_x(2), buf.computed_x(3)), (0.0, 2.0));
+    }
 }
@@ -10,4 +10,5 @@ pub mod layout_tree;
 pub mod text_measure;
 
 pub use layout_tree::compute_layout;
+pub(crate) use layout_tree::LayoutEnv;
 pub use text_measure::*;
@@ -0,0 +1,420 @@
+//! Bidirectional text (UAX #9) for display.
+//!
+//! Text is stored in logical order: what was typed first comes first. A
+//! terminal draws cells left to right, so a line holding Hebrew or Arabic
+//! has to be put in visual order before it is drawn. This implements the
+//! parts of the Unicode Bidirectional Algorithm a terminal line needs:
+//!
+//! - Paragraph direction from the first strong character (P2, P3)
+//! - Weak and neutral type resolution (W1-W7, N1, N2) and levels (I1, I2)
+//! - Trailing whitespace at the paragraph level (L1)
+//! - Reversal by grapheme cluster, so combining marks and emoji sequences
+//!   stay whole (L2), and mirrored brackets in right-to-left runs (L4)
+//!
+//! Explicit embeddings and isolates (U+202A-U+202E, U+2066-U+2069) are
+//! ignored like other format characters, and each wrapped line is resolved
+//! on its own. Classes come from compact range tables covering the scripts
+//! and punctuation terminals show; anything else is left-to-right.
+
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Bidi_Class values the algorithm distinguishes.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    /// Left-to-right letter
+    L,
+    /// Right-to-left letter (Hebrew and others)
+    R,
+    /// Arabic letter
+    AL,
+    /// European number
+    EN,
+    /// European separator (+ -)
+    ES,
+    /// European terminator (# $ % and currency)
+    ET,
+    /// Arabic number
+    AN,
+    /// Common separator (, . / :)
+    CS,
+    /// Combining mark
+    NSM,
+    /// Format character, ignored
+    BN,
+    /// Paragraph separator
+    B,
+    /// Segment separator (tab)
+    S,
+    /// Whitespace
+    WS,
+    /// Other neutral
+    ON,
+}
+
+use Class::*;
+
+fn class(c: char) -> Class {
+    let cp = c as u32;
+    match cp {
+        0x09 | 0x0B | 0x1F => S,
+        0x0A | 0x0D | 0x1C..=0x1E | 0x85 | 0x2029 => B,
+        0x0C | 0x20 => WS,
+        0x00..=0x08 | 0x0E..=0x1B | 0x7F..=0x84 | 0x86..=0x9F | 0xAD => BN,
+        0x30..=0x39 | 0xB2 | 0xB3 | 0xB9 => EN,
+        0x2B | 0x2D => ES,
+        0x23..=0x25 | 0xA2..=0xA5 | 0xB0 | 0xB1 => ET,
+        0x2C | 0x2E | 0x2F | 0x3A | 0xA0 => CS,
+        0x41..=0x5A | 0x61..=0x7A | 0xAA | 0xB5 | 0xBA => L,
+        0x21..=0x7E | 0xA1..=0xBF | 0xD7 | 0xF7 => ON,
+        0x0300..=0x036F | 0x0483..=0x0489 => NSM,
+
+        // Hebrew
+        0x0591..=0x05BD | 0x05BF | 0x05C1 | 0x05C2 | 0x05C4 | 0x05C5 | 0x05C7 => NSM,
+        0x0590..=0x05FF => R,
+
+        // Arabic
+        0x0600..=0x0605 | 0x0660..=0x0669 | 0x066B | 0x066C | 0x06DD | 0x08E2 => AN,
+        0x0606 | 0x0607 | 0x060E | 0x060F | 0x06DE | 0x06E9 => ON,
+        0x0609 | 0x060A | 0x066A => ET,
+        0x060C => CS,
+        0x06F0..=0x06F9 => EN,
+        0x0610..=0x061A
+        | 0x064B..=0x065F
+        | 0x0670
+        | 0x06D6..=0x06DC
+        | 0x06DF..=0x06E4
+        | 0x06E7
+        | 0x06E8
+        | 0x06EA..=0x06ED
+        | 0x0711
+        | 0x0730..=0x074A
+        | 0x07A6..=0x07B0
+        | 0x07EB..=0x07F3
+        | 0x07FD
+        | 0x0816..=0x082D
+        | 0x0859..=0x085B
+        | 0x0898..=0x089F
+        | 0x08CA..=0x08FF => NSM,
+        0x0600..=0x07BF | 0x0860..=0x08FF => AL,
+        // N'Ko, Samaritan, Mandaic
+        0x07C0..=0x085F => R,
+
+        // Spaces, format characters and general punctuation
+        0x1680 | 0x2000..=0x200A | 0x2028 | 0x205F | 0x3000 => WS,
+        0x200B..=0x200D | 0x202A..=0x202E | 0x2060..=0x206F | 0xFEFF => BN,
+        0x200E => L,
+        0x200F => R,
+        0x202F | 0x2044 => CS,
+        0x2030..=0x2034 | 0x20A0..=0x20CF | 0x2213 => ET,
+        0x2070 | 0x2074..=0x2079 | 0x2080..=0x2089 | 0x2488..=0x249B => EN,
+        0x207A | 0x207B | 0x208A | 0x208B | 0x2212 => ES,
+        0x2010..=0x2027 | 0x2035..=0x205E | 0x207C..=0x207E | 0x208C..=0x208E => ON,
+        0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF => NSM,
+        0x2100..=0x214F | 0x2190..=0x2487 | 0x249C..=0x249F | 0x2500..=0x2BFF | 0x2E00..=0x2E7F => ON,
+        0x3001..=0x3004 | 0x3008..=0x3020 | 0x3030 => ON,
+
+        // Presentation forms
+        0xFB1E | 0xFE00..=0xFE0F | 0xFE20..=0xFE2F => NSM,
+        0xFB29 | 0xFE62 | 0xFE63 | 0xFF0B | 0xFF0D => ES,
+        0xFB1D..=0xFB4F => R,
+        0xFD3E | 0xFD3F => ON,
+        0xFB50..=0xFDFF | 0xFE70..=0xFEFE => AL,
+        0xFE50 | 0xFE52 | 0xFE55 | 0xFF0C | 0xFF0E | 0xFF0F | 0xFF1A => CS,
+        0xFE5F | 0xFE69 | 0xFE6A | 0xFF03..=0xFF05 | 0xFFE0 | 0xFFE1 | 0xFFE5 | 0xFFE6 => ET,
+        0xFF10..=0xFF19 => EN,
+        0xFF21..=0xFF3A | 0xFF41..=0xFF5A | 0xFF66..=0xFFDC => L,
+        0xFE10..=0xFE19 | 0xFE30..=0xFE6F | 0xFF01..=0xFF65 | 0xFFE2..=0xFFE4 | 0xFFE8..=0xFFEE | 0xFFF9..=0xFFFD => ON,
+
+        // Historic right-to-left scripts, Arabic mathematical letters
+        0x10D30..=0x10D39 | 0x10E60..=0x10E7E => AN,
+        0x10D00..=0x10D3F | 0x10F30..=0x10F6F | 0x1EC70..=0x1ECBF | 0x1ED00..=0x1ED4F | 0x1EE00..=0x1EEEF => AL,
+        0x10800..=0x10FFF | 0x1E800..=0x1EFFF => R,
+
+        // Symbols and emoji
+        0x1F100..=0x1F10A => EN,
+        0x1F000..=0x1F0FF | 0x1F10B..=0x1F10F | 0x1F16A..=0x1F16F | 0x1F300..=0x1FAFF => ON,
+        0xE0001..=0xE007F => BN,
+        0xE0100..=0xE01EF => NSM,
+        _ => L,
+    }
+}
+
+/// The mirrored glyph drawn for `c` in a right-to-left run (L4).
+fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '<' => '>',
+        '>' => '<',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        '⁅' => '⁆',
+        '⁆' => '⁅',
+        '⁽' => '⁾',
+        '⁾' => '⁽',
+        '₍' => '₎',
+        '₎' => '₍',
+        '≤' => '≥',
+        '≥' => '≤',
+        '〈' => '〉',
+        '〉' => '〈',
+        '《' => '》',
+        '》' => '《',
+        '「' => '」',
+        '」' => '「',
+        '『' => '』',
+        '』' => '『',
+        '【' => '】',
+        '】' => '【',
+        _ => c,
+    }
+}
+
+/// Whether a paragraph reads right to left: its first strong character is
+/// Hebrew, Arabic or another right-to-left script. Text without a strong
+/// character (digits, punctuation) is left-to-right.
+pub fn paragraph_is_rtl(text: &str) -> bool {
+    text.chars()
+        .map(class)
+        .find(|c| matches!(c, L | R | AL))
+        .is_some_and(|c| c != L)
+}
+
+/// Resolved embedding level of each char of a line in a paragraph of the
+/// given direction.
+fn levels(chars: &[char], rtl: bool) -> Vec<u8> {
+    let base: u8 = if rtl { 1 } else { 0 };
+    let sos = if rtl { R } else { L };
+    let original: Vec<Class> = chars.iter().map(|&c| class(c)).collect();
+
+    // Format characters take no part in resolution (X9); they get a level
+    // from their neighbours at the end
+    let kept: Vec<usize> = (0..chars.len()).filter(|&i| original[i] != BN).collect();
+    let mut t: Vec<Class> = kept.iter().map(|&i| original[i]).collect();
+    let n = t.len();
+
+    // W1: combining marks take the type before them
+    let mut prev = sos;
+    for ty in t.iter_mut() {
+        if *ty == NSM {
+            *ty = prev;
+        }
+        prev = *ty;
+    }
+
+    // W2: European numbers after Arabic letters are Arabic numbers; W3
+    let mut strong = sos;
+    for ty in t.iter_mut() {
+        match *ty {
+            L | R | AL => strong = *ty,
+            EN if strong == AL => *ty = AN,
+            _ => {}
+        }
+    }
+    for ty in t.iter_mut() {
+        if *ty == AL {
+            *ty = R;
+        }
+    }
+
+    // W4: a single separator between two numbers of the same kind
+    for i in 1..n.saturating_sub(1) {
+        let (before, after) = (t[i - 1], t[i + 1]);
+        t[i] = match t[i] {
+            ES if before == EN && after == EN => EN,
+            CS if before == after && matches!(before, EN | AN) => before,
+            ty => ty,
+        };
+    }
+
+    // W5: terminators next to European numbers
+    let mut i = 0;
+    while i < n {
+        if t[i] != ET {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < n && t[i] == ET {
+            i += 1;
+        }
+        let touches_en = (start > 0 && t[start - 1] == EN) || (i < n && t[i] == EN);
+        if touches_en {
+            t[start..i].fill(EN);
+        }
+    }
+
+    // W6: remaining separators and terminators are neutral
+    for ty in t.iter_mut() {
+        if matches!(*ty, ES | ET | CS) {
+            *ty = ON;
+        }
+    }
+
+    // W7: European numbers in left-to-right context
+    let mut strong = sos;
+    for ty in t.iter_mut() {
+        match *ty {
+            L | R => strong = *ty,
+            EN if strong == L => *ty = L,
+            _ => {}
+        }
+    }
+
+    // N1, N2: neutrals between two strong types of the same direction take
+    // it, others the paragraph's. Numbers count as right-to-left.
+    let strong_dir = |ty: Class| match ty {
+        L => Some(L),
+        R | EN | AN => Some(R),
+        _ => None,
+    };
+    let mut i = 0;
+    while i < n {
+        if strong_dir(t[i]).is_some() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < n && strong_dir(t[i]).is_none() {
+            i += 1;
+        }
+        let before = if start == 0 { sos } else { strong_dir(t[start - 1]).unwrap_or(sos) };
+        let after = if i == n { sos } else { strong_dir(t[i]).unwrap_or(sos) };
+        let resolved = if before == after { before } else { sos };
+        t[start..i].fill(resolved);
+    }
+
+    // I1, I2
+    let mut out = vec![base; chars.len()];
+    for (k, &i) in kept.iter().enumerate() {
+        out[i] = match (t[k], rtl) {
+            (R, false) => base + 1,
+            (AN | EN, false) => base + 2,
+            (L | EN | AN, true) => base + 1,
+            _ => base,
+        };
+    }
+    for i in 0..chars.len() {
+        if original[i] == BN {
+            out[i] = if i > 0 { out[i - 1] } else { base };
+        }
+    }
+
+    // L1: separators, and whitespace before them or at the end of the line,
+    // go back to the paragraph level
+    let mut trailing = true;
+    for i in (0..chars.len()).rev() {
+        match original[i] {
+            S | B => {
+                out[i] = base;
+                trailing = true;
+            }
+            WS | BN if trailing => out[i] = base,
+            _ => trailing = false,
+        }
+    }
+    out
+}
+
+/// `line` in visual (left-to-right drawing) order, for a paragraph that is
+/// right-to-left when `rtl`. Borrowed unchanged when nothing moves.
+pub fn reorder_line(line: &str, rtl: bool) -> Cow<'_, str> {
+    if !rtl && !line.chars().any(|c| matches!(class(c), R | AL)) {
+        return Cow::Borrowed(line);
+    }
+    let chars: Vec<char> = line.chars().collect();
+    let levels = levels(&chars, rtl);
+
+    // L2 on clusters: a cluster keeps its order inside and takes the level
+    // of its first char
+    let mut clusters: Vec<(u8, &str)> = Vec::new();
+    let mut pos = 0;
+    for grapheme in line.graphemes(true) {
+        clusters.push((levels[pos], grapheme));
+        pos += grapheme.chars().count();
+    }
+    let highest = clusters.iter().map(|&(level, _)| level).max().unwrap_or(0);
+    let lowest_odd = clusters
+        .iter()
+        .map(|&(level, _)| level)
+        .filter(|level| level % 2 == 1)
+        .min()
+        .unwrap_or(highest + 1);
+    let mut level = highest;
+    while level >= lowest_odd {
+        let mut i = 0;
+        while i < clusters.len() {
+            if clusters[i].0 < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < clusters.len() && clusters[i].0 >= level {
+                i += 1;
+            }
+            clusters[start..i].reverse();
+        }
+        level -= 1;
+    }
+
+    let mut out = String::with_capacity(line.len());
+    for (level, grapheme) in clusters {
+        if level % 2 == 1 {
+            out.extend(grapheme.chars().map(mirror));
+        } else {
+            out.push_str(grapheme);
+        }
+    }
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paragraph_direction() {
+        assert!(!paragraph_is_rtl("hello שלום"));
+        assert!(paragraph_is_rtl("123 שלום hello"));
+        assert!(paragraph_is_rtl("مرحبا"));
+        assert!(!paragraph_is_rtl("123 !?"));
+        assert!(!paragraph_is_rtl(""));
+    }
+
+    #[test]
+    fn test_ltr_text_is_borrowed() {
+        assert!(matches!(reorder_line("plain (text) 42", false), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_rtl_run_in_ltr_paragraph() {
+        assert_eq!(reorder_line("abc אבג def", false), "abc גבא def");
+        // Numbers keep their order inside a reversed run
+        assert_eq!(reorder_line("a אב 12 גד", false), "a דג 12 בא");
+    }
+
+    #[test]
+    fn test_rtl_paragraph() {
+        assert_eq!(reorder_line("אבג abc", true), "abc גבא");
+        // Brackets mirror; trailing whitespace stays at the logical end
+        assert_eq!(reorder_line("א(ב) ", true), " (ב)א");
+        // Arabic digits after Arabic letters stay in reading order
+        assert_eq!(reorder_line("عدد ١٢٣", true), "١٢٣ ددع");
+    }
+
+    #[test]
+    fn test_clusters_stay_whole() {
+        // Hebrew letter with a point, and a flag, keep their char order
+        let line = "ש\u{5C1}ל \u{1F1EE}\u{1F1F1}";
+        assert_eq!(reorder_line(line, true), "\u{1F1EE}\u{1F1F1} לש\u{5C1}");
+    }
+}
@@ -0,0 +1,183 @@
+//! Memoized text measurement for layout.
+//!
+//! Wrapping a text to count its lines is the bulk of measuring it, and most
+//! texts don't change between layouts. The cache keeps each text's width and
+//! its heights at the widths it was measured for, found by a hash of the
+//! content, so an unchanged text is wrapped once per width it's laid out at.
+//! Entries keep their text: two texts whose hashes collide replace each
+//! other instead of sharing a size.
+//!
+//! - Least recently used texts are evicted once the cache is full
+//! - A node whose text changed (`DIRTY_TEXT`) drops the entry of its old text
+//!   straight away instead of leaving it to age out
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use super::width::string_width;
+use super::wrap::measure_text_height;
+
+/// Texts kept before the least recently used are evicted
+const CAPACITY: usize = 4096;
+
+/// Share of the cache evicted at once when it's full (1 / EVICT_DIVISOR)
+const EVICT_DIVISOR: usize = 8;
+
+/// Widths remembered per text; a text is rarely laid out at more than a few
+const WIDTHS_PER_TEXT: usize = 4;
+
+/// One text's measurements
+struct Entry {
+    /// The text measured
+    text: Box<str>,
+    /// Widest line, in cells
+    width: usize,
+    /// Line counts by wrap width, most recent last
+    heights: Vec<(usize, usize)>,
+    /// Tick of the last lookup, for eviction
+    last_used: u64,
+}
+
+/// Text measurements by content and wrap width.
+#[derive(Default)]
+pub struct MeasureCache {
+    entries: HashMap<u64, Entry>,
+    /// Content hash each node was last measured with
+    node_texts: Vec<Option<u64>>,
+    tick: u64,
+}
+
+impl MeasureCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Width and line count of `text` wrapped at `max_width` (cells).
+    pub fn measure(&mut self, node: usize, text: &str, max_width: usize) -> (usize, usize) {
+        let hash = content_hash(text);
+        if node >= self.node_texts.len() {
+            self.node_texts.resize(node + 1, None);
+        }
+        self.node_texts[node] = Some(hash);
+        self.tick += 1;
+
+        if !self.entries.contains_key(&hash) && self.entries.len() >= CAPACITY {
+            self.evict();
+        }
+        let fresh = || Entry {
+            text: text.into(),
+            width: string_width(text),
+            heights: Vec::with_capacity(WIDTHS_PER_TEXT),
+            last_used: 0,
+        };
+        let entry = self.entries.entry(hash).or_insert_with(fresh);
+        if *entry.text != *text {
+            // Another text with the same hash
+            *entry = fresh();
+        }
+        entry.last_used = self.tick;
+
+        let height = match entry.heights.iter().find(|&&(w, _)| w == max_width) {
+            Some(&(_, height)) => height,
+            None => {
+                let height = measure_text_height(text, max_width);
+                if entry.heights.len() == WIDTHS_PER_TEXT {
+                    entry.heights.remove(0);
+                }
+                entry.heights.push((max_width, height));
+                height
+            }
+        };
+        (entry.width, height)
+    }
+
+    /// The node's text changed: forget what its old text measured.
+    pub fn invalidate_node(&mut self, node: usize) {
+        if let Some(hash) = self.node_texts.get_mut(node).and_then(Option::take) {
+            self.entries.remove(&hash);
+        }
+    }
+
+    /// Texts cached
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop the least recently used share of the entries
+    fn evict(&mut self) {
+        let mut ticks: Vec<u64> = self.entries.values().map(|entry| entry.last_used).collect();
+        let cutoff = CAPACITY / EVICT_DIVISOR;
+        let (_, &mut oldest_kept, _) = ticks.select_nth_unstable(cutoff);
+        self.entries.retain(|_, entry| entry.last_used >= oldest_kept);
+    }
+}
+
+fn content_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measures_once_per_width() {
+        let mut cache = MeasureCache::new();
+        assert_eq!(cache.measure(0, "abcdef", 4), (6, 2));
+        assert_eq!(cache.measure(0, "abcdef", 4), (6, 2));
+        assert_eq!(cache.measure(0, "abcdef", 3), (6, 2));
+        assert_eq!(cache.measure(0, "abcdef", 2), (6, 3));
+        // Same text in another node shares the entry
+        assert_eq!(cache.measure(1, "abcdef", 6), (6, 1));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_colliding_hash_is_not_shared() {
+        let mut cache = MeasureCache::new();
+        cache.measure(0, "abcdef", 4);
+        // Plant another text under this one's hash
+        let hash = content_hash("abcdef");
+        let entry = cache.entries.remove(&hash).unwrap();
+        cache.entries.insert(content_hash("xy"), entry);
+        assert_eq!(cache.measure(1, "xy", 4), (2, 1));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_invalidate_node_drops_its_old_text() {
+        let mut cache = MeasureCache::new();
+        cache.measure(0, "old", 10);
+        cache.measure(1, "other", 10);
+        cache.invalidate_node(0);
+        assert_eq!(cache.len(), 1);
+        // Nothing measured for it since: nothing to drop
+        cache.invalidate_node(0);
+        cache.invalidate_node(7);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = MeasureCache::new();
+        for i in 0..CAPACITY {
+            cache.measure(0, &i.to_string(), 10);
+        }
+        // Touch the first text, so the next ones are the oldest
+        cache.measure(0, "0", 10);
+        cache.measure(0, "new", 10);
+        assert!(cache.len() < CAPACITY);
+        let before = cache.len();
+        cache.measure(0, "0", 10);
+        assert_eq!(cache.len(), before, "recently used text was kept");
+        cache.measure(0, "1", 10);
+        assert_eq!(cache.len(), before + 1, "oldest text was evicted");
+    }
+}
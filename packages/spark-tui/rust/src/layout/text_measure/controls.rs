@@ -0,0 +1,102 @@
+//! Control-character visualization.
+//!
+//! Text from logs, subprocesses or users can carry C0/C1 controls and raw
+//! escape sequences. Left alone they measure as zero width while their
+//! payload (`[31m`) still draws, and a control that reaches a cell would be
+//! written straight to the terminal. Instead every control becomes visible:
+//!
+//! | Input          | `Pictures` | `Caret` |
+//! |----------------|------------|---------|
+//! | `\0`..`\x1F`   | `␀`..`␟`   | `^@`..`^_` |
+//! | `\x7F` (DEL)   | `␡`        | `^?`    |
+//! | `\x80`..`\x9F` | `�`        | `^[@`..`^[_` (the 7-bit escape form) |
+//!
+//! Newlines are left alone — they are line breaks for wrapping. The same
+//! visualized string feeds layout measurement and drawing, so the two agree.
+//!
+//! Inputs keep one cell per char so cursor and selection columns line up:
+//! their value shows every control, `\n` too, as its picture in either mode
+//! (`input_pictures`).
+
+use std::borrow::Cow;
+
+use crate::shared_buffer::ControlChars;
+use crate::utils::{control_picture, is_control};
+
+/// Replace every control except `\n` with its visible form.
+/// Borrows when there is nothing to replace.
+pub fn visualize_controls(text: &str, mode: ControlChars) -> Cow<'_, str> {
+    if !text.chars().any(|ch| ch != '\n' && is_control(ch)) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len() + 8);
+    for ch in text.chars() {
+        if ch == '\n' || !is_control(ch) {
+            out.push(ch);
+            continue;
+        }
+        match mode {
+            ControlChars::Pictures => out.push(control_picture(ch)),
+            ControlChars::Caret => {
+                let cp = ch as u32;
+                match cp {
+                    0x7F => out.push_str("^?"),
+                    0x80..=0x9F => {
+                        out.push_str("^[");
+                        out.push(char::from_u32(cp - 0x40).unwrap_or('?'));
+                    }
+                    _ => {
+                        out.push('^');
+                        out.push(char::from_u32(cp + 0x40).unwrap_or('?'));
+                    }
+                }
+            }
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// An input's value as it's drawn and measured: each control, `\n`
+/// included, as its one-cell picture. Borrows when there is none.
+pub fn input_pictures(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_control) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.chars().map(control_picture).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_is_borrowed() {
+        assert!(matches!(visualize_controls("line\nnext", ControlChars::Pictures), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn pictures_mode() {
+        assert_eq!(visualize_controls("\x1b[31mred\x07", ControlChars::Pictures), "␛[31mred␇");
+        assert_eq!(visualize_controls("a\tb\x7f\u{9b}", ControlChars::Pictures), "a␉b␡\u{FFFD}");
+    }
+
+    #[test]
+    fn caret_mode() {
+        assert_eq!(visualize_controls("\0\x1b\x7f", ControlChars::Caret), "^@^[^?");
+        assert_eq!(visualize_controls("\u{9b}1m\nok", ControlChars::Caret), "^[[1m\nok");
+    }
+
+    #[test]
+    fn input_pictures_are_one_char_per_control() {
+        assert!(matches!(input_pictures("plain"), Cow::Borrowed(_)));
+        assert_eq!(input_pictures("a\x1b\nb"), "a␛␊b");
+    }
+
+    #[test]
+    fn control_picture_leaves_text_alone() {
+        assert_eq!(control_picture('a'), 'a');
+        assert_eq!(control_picture('\0'), '␀');
+        assert_eq!(control_picture('\r'), '␍');
+    }
+}
@@ -0,0 +1,96 @@
+//! Grapheme cluster positions for text editing.
+//!
+//! Editing positions are char indices (the cursor and selection stored in
+//! the SharedBuffer), but what the user moves over and deletes is a
+//! grapheme cluster: an emoji ZWJ sequence, a flag or a letter with
+//! combining marks is one cluster of several chars. These helpers keep
+//! positions on cluster boundaries.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::width::char_width;
+
+/// Char index of every grapheme cluster boundary in `text`, from 0 through
+/// its char count.
+pub fn grapheme_boundaries(text: &str) -> Vec<usize> {
+    let mut bounds = vec![0];
+    let mut pos = 0;
+    for grapheme in text.graphemes(true) {
+        pos += grapheme.chars().count();
+        bounds.push(pos);
+    }
+    bounds
+}
+
+/// Number of grapheme clusters in `text` (what `maxLength` counts).
+pub fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Start of the cluster before char index `pos` (Left, Backspace).
+pub fn prev_grapheme_boundary(text: &str, pos: usize) -> usize {
+    grapheme_boundaries(text)
+        .into_iter()
+        .rev()
+        .find(|&b| b < pos)
+        .unwrap_or(0)
+}
+
+/// End of the cluster at char index `pos` (Right, Delete).
+pub fn next_grapheme_boundary(text: &str, pos: usize) -> usize {
+    let bounds = grapheme_boundaries(text);
+    let last = bounds.last().copied().unwrap_or(0);
+    bounds.into_iter().find(|&b| b > pos).unwrap_or(last)
+}
+
+/// Char index of the cluster drawn at display column `col` (cells counted
+/// as the framebuffer draws them); past the end, the char count.
+pub fn column_to_char(text: &str, col: usize) -> usize {
+    let mut pos = 0;
+    let mut x = 0;
+    for grapheme in text.graphemes(true) {
+        let width: usize = grapheme.chars().map(char_width).sum();
+        if col < x + width {
+            return pos;
+        }
+        x += width;
+        pos += grapheme.chars().count();
+    }
+    pos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 👩‍👩‍👧 is 5 chars (3 people + 2 ZWJ); é here is e + U+0301
+    const FAMILY: &str = "a\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}e\u{301}b";
+
+    #[test]
+    fn test_boundaries_skip_inside_clusters() {
+        assert_eq!(grapheme_boundaries(FAMILY), vec![0, 1, 6, 8, 9]);
+        assert_eq!(grapheme_count(FAMILY), 4);
+        assert_eq!(grapheme_boundaries(""), vec![0]);
+    }
+
+    #[test]
+    fn test_prev_next() {
+        assert_eq!(next_grapheme_boundary(FAMILY, 1), 6);
+        assert_eq!(next_grapheme_boundary(FAMILY, 6), 8);
+        assert_eq!(next_grapheme_boundary(FAMILY, 9), 9);
+        assert_eq!(prev_grapheme_boundary(FAMILY, 8), 6);
+        assert_eq!(prev_grapheme_boundary(FAMILY, 6), 1);
+        assert_eq!(prev_grapheme_boundary(FAMILY, 0), 0);
+    }
+
+    #[test]
+    fn test_column_to_char() {
+        // Each emoji draws 2 cells: columns 1-6 are the family
+        assert_eq!(column_to_char(FAMILY, 0), 0);
+        assert_eq!(column_to_char(FAMILY, 1), 1);
+        assert_eq!(column_to_char(FAMILY, 6), 1);
+        assert_eq!(column_to_char(FAMILY, 7), 6);
+        assert_eq!(column_to_char(FAMILY, 9), 9);
+        assert_eq!(column_to_char("漢字", 3), 1);
+    }
+}
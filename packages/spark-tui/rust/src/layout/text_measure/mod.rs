@@ -7,10 +7,14 @@
 //!
 //! - **Width calculation**: Correct terminal cell width for any Unicode text
 //! - **ANSI stripping**: Properly skips CSI, OSC, and ESC escape sequences
+//! - **Control visualization**: C0/C1 controls shown as `␛` or `^[` instead of vanishing
 //! - **Grapheme awareness**: Never breaks in the middle of a grapheme cluster
 //! - **Emoji sequences**: ZWJ families, skin tones, flags measured as width 2
-//! - **Text wrapping**: Character-break and word-break modes
+//! - **Text wrapping**: Character-break, word-break and hyphenated modes, justification
+//! - **Measurement cache**: Widths and line counts memoized across layouts
 //! - **Text truncation**: Grapheme-safe truncation with configurable suffix
+//! - **Editing positions**: Cursor motion and deletion by grapheme cluster
+//! - **Bidirectional text**: Right-to-left runs put in visual order (UAX #9)
 //!
 //! # Implementation
 //!
@@ -19,11 +23,21 @@
 //! foundation, with custom handling for ANSI escapes and emoji sequences.
 
 mod ansi;
+mod bidi;
+mod cache;
+mod controls;
+mod grapheme;
 mod truncate;
 mod width;
 mod wrap;
 
 pub use ansi::strip_ansi;
+pub use bidi::{paragraph_is_rtl, reorder_line};
+pub use cache::MeasureCache;
+pub use controls::{input_pictures, visualize_controls};
+pub use grapheme::{
+    column_to_char, grapheme_boundaries, grapheme_count, next_grapheme_boundary, prev_grapheme_boundary,
+};
 pub use truncate::truncate_text;
 pub use width::{char_width, grapheme_width, string_width};
-pub use wrap::{measure_text_height, wrap_text, wrap_text_word};
+pub use wrap::{justify_line, measure_text_height, wrap_text, wrap_text_hyphenated, wrap_text_word};
@@ -4,6 +4,10 @@
 //! - **Character-break** (`wrap_text`): breaks at any grapheme boundary
 //! - **Word-break** (`wrap_text_word`): breaks at word boundaries, falls
 //!   back to grapheme-break for words wider than the line
+//! - **Hyphenated** (`wrap_text_hyphenated`): word-break that also breaks
+//!   words at soft hyphens (U+00AD), drawing a `-` where it does
+//!
+//! `justify_line` widens a wrapped line to fill its width.
 //!
 //! Both modes correctly handle:
 //! - Explicit newlines (`\n`) as hard line breaks
@@ -13,7 +17,10 @@
 
 use unicode_segmentation::UnicodeSegmentation;
 
-use super::width::grapheme_width;
+use super::width::{grapheme_width, string_width};
+
+/// Soft hyphen: an invisible break opportunity inside a word
+const SOFT_HYPHEN: char = '\u{AD}';
 
 /// Wrap text by breaking at any grapheme boundary.
 ///
@@ -62,28 +69,48 @@ pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
 ///
 /// Returns an empty `Vec` for empty input.
 pub fn wrap_text_word(text: &str, max_width: usize) -> Vec<String> {
+    wrap_words(text, max_width, false)
+}
+
+/// Wrap text by breaking at word boundaries and at soft hyphens.
+///
+/// Like `wrap_text_word`, but a word that doesn't fit is broken at the last
+/// soft hyphen (U+00AD) that leaves room for a `-` on the line. Soft
+/// hyphens that aren't broken at are removed.
+///
+/// Returns an empty `Vec` for empty input.
+pub fn wrap_text_hyphenated(text: &str, max_width: usize) -> Vec<String> {
+    wrap_words(text, max_width, true)
+}
+
+fn wrap_words(text: &str, max_width: usize, hyphenate: bool) -> Vec<String> {
     if text.is_empty() {
         return vec![];
     }
     if max_width == 0 {
-        return vec![text.to_string()];
+        return vec![if hyphenate { strip_soft_hyphens(text) } else { text.to_string() }];
     }
 
     let mut lines: Vec<String> = Vec::new();
 
     for raw_line in text.split('\n') {
-        wrap_line_word(raw_line, max_width, &mut lines);
+        wrap_line_word(raw_line, max_width, hyphenate, &mut lines);
     }
 
     lines
 }
 
 /// Wrap a single line by word boundaries.
-fn wrap_line_word(line: &str, max_width: usize, lines: &mut Vec<String>) {
+fn wrap_line_word(line: &str, max_width: usize, hyphenate: bool, lines: &mut Vec<String>) {
     let mut current = String::new();
     let mut current_width: usize = 0;
 
     for segment in line.split_word_bounds() {
+        if hyphenate && segment.contains(SOFT_HYPHEN) {
+            place_hyphenated(segment, max_width, lines, &mut current, &mut current_width);
+            continue;
+        }
+
         let seg_width: usize = segment.graphemes(true).map(grapheme_width).sum();
 
         if current_width + seg_width > max_width {
@@ -112,6 +139,84 @@ fn wrap_line_word(line: &str, max_width: usize, lines: &mut Vec<String>) {
     lines.push(current);
 }
 
+/// Place a word containing soft hyphens, breaking it at them as needed.
+fn place_hyphenated(
+    word: &str,
+    max_width: usize,
+    lines: &mut Vec<String>,
+    current: &mut String,
+    current_width: &mut usize,
+) {
+    let mut rest = word;
+    loop {
+        let rest_width = string_width(&strip_soft_hyphens(rest));
+        if *current_width + rest_width <= max_width {
+            current.push_str(&strip_soft_hyphens(rest));
+            *current_width += rest_width;
+            return;
+        }
+
+        match hyphen_break(rest, max_width - *current_width) {
+            Some((head, tail)) => {
+                current.push_str(&strip_soft_hyphens(head));
+                current.push('-');
+                lines.push(std::mem::take(current));
+                *current_width = 0;
+                rest = tail;
+            }
+            None if *current_width > 0 => {
+                lines.push(current.trim_end().to_string());
+                current.clear();
+                *current_width = 0;
+            }
+            None => {
+                force_break_graphemes(&strip_soft_hyphens(rest), max_width, lines, current, current_width);
+                return;
+            }
+        }
+    }
+}
+
+/// Split `word` at its last soft hyphen whose head, plus a `-`, fits in
+/// `available` cells.
+fn hyphen_break(word: &str, available: usize) -> Option<(&str, &str)> {
+    word.match_indices(SOFT_HYPHEN)
+        .map(|(at, _)| at)
+        .take_while(|&at| string_width(&strip_soft_hyphens(&word[..at])) < available)
+        .last()
+        .map(|at| (&word[..at], &word[at + SOFT_HYPHEN.len_utf8()..]))
+}
+
+fn strip_soft_hyphens(s: &str) -> String {
+    s.replace(SOFT_HYPHEN, "")
+}
+
+/// Widen `line` to `width` cells by spreading the spaces between its words
+/// evenly, leftmost gaps taking any remainder. Leading indentation is kept;
+/// a line without gaps, or already as wide, comes back unchanged.
+pub fn justify_line(line: &str, width: usize) -> String {
+    let body = line.trim_start_matches(' ');
+    let indent = &line[..line.len() - body.len()];
+    let words: Vec<&str> = body.split(' ').filter(|w| !w.is_empty()).collect();
+    let used: usize = string_width(indent) + words.iter().map(|w| string_width(w)).sum::<usize>();
+    let gaps = words.len().saturating_sub(1);
+    if gaps == 0 || used + gaps >= width {
+        return line.to_string();
+    }
+
+    let spaces = width - used;
+    let mut out = String::with_capacity(line.len() + spaces);
+    out.push_str(indent);
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            let n = spaces / gaps + usize::from(i <= spaces % gaps);
+            out.extend(std::iter::repeat_n(' ', n));
+        }
+        out.push_str(word);
+    }
+    out
+}
+
 /// Force-break a segment that is wider than `max_width` by grapheme boundaries.
 fn force_break_graphemes(
     segment: &str,
@@ -321,4 +426,34 @@ mod tests {
     fn height_empty_line() {
         assert_eq!(measure_text_height("a\n\nb", 10), 3);
     }
+
+    // ── wrap_text_hyphenated ──
+
+    #[test]
+    fn hyphenate_at_soft_hyphen() {
+        let text = "a hy\u{AD}phen\u{AD}ation";
+        assert_eq!(wrap_text_hyphenated(text, 10), vec!["a hyphen-", "ation"]);
+        assert_eq!(wrap_text_hyphenated(text, 5), vec!["a hy-", "phen-", "ation"]);
+        // Fits whole: the soft hyphens vanish
+        assert_eq!(wrap_text_hyphenated(text, 20), vec!["a hyphenation"]);
+        // Word mode leaves soft hyphens alone
+        assert_eq!(wrap_text_word(text, 20), vec![text]);
+    }
+
+    #[test]
+    fn hyphenate_no_room_moves_word() {
+        // No soft hyphen leaves room for "-" after "ab ", so the word moves
+        assert_eq!(wrap_text_hyphenated("ab cd\u{AD}ef", 3), vec!["ab", "cd-", "ef"]);
+    }
+
+    // ── justify_line ──
+
+    #[test]
+    fn justify_spreads_spaces() {
+        assert_eq!(justify_line("a b c", 9), "a   b   c");
+        assert_eq!(justify_line("a b c", 8), "a   b  c");
+        assert_eq!(justify_line("  ab cd", 9), "  ab   cd");
+        assert_eq!(justify_line("word", 9), "word");
+        assert_eq!(justify_line("a b", 2), "a b");
+    }
 }
@@ -4,6 +4,9 @@
 //! reads layout properties, runs Taffy layout computation, builds the framebuffer,
 //! diff-renders to the terminal, and handles all input.
 //!
+//! Without a JS host, the component tree is built through the C ABI below
+//! (see `host`) or, from Rust, with a `runtime::Runtime`.
+//!
 //! # Architecture
 //!
 //! ```text
@@ -38,6 +41,7 @@
 // MODULES
 // =============================================================================
 
+pub mod app;
 pub mod shared_buffer;
 pub mod utils;
 pub mod layout;
@@ -45,44 +49,77 @@ pub mod renderer;
 pub mod framebuffer;
 pub mod input;
 pub mod pipeline;
+pub mod devtools;
+pub mod task;
+pub mod state;
+pub mod host;
+pub mod runtime;
 
+use app::App;
+use host::NodeRegistry;
+use pipeline::terminal::osc;
 use shared_buffer::{SharedBuffer, DEFAULT_BUFFER_SIZE, calculate_buffer_size};
-use std::sync::{OnceLock, Mutex, Condvar};
+use std::collections::HashMap;
+use std::sync::{OnceLock, Mutex};
 
 // =============================================================================
-// GLOBAL STATE
+// DEFAULT INSTANCE
 // =============================================================================
+//
+// Everything an instance owns lives in its `App` (see app.rs). The FFI keeps
+// one app per process terminal plus the session apps, and each export is a
+// thin wrapper over one of them.
+
+/// The app on the process terminal, started by `spark_init`.
+static APP: OnceLock<App> = OnceLock::new();
+
+/// Session apps started over FFI, by session id.
+static SESSIONS: Mutex<Option<HashMap<u32, SessionApp>>> = Mutex::new(None);
 
-/// The shared buffer (1024 bytes/node), initialized once via FFI.
-static BUFFER: OnceLock<SharedBuffer> = OnceLock::new();
+/// Last session id handed out (ids start at 1; 0 means failure).
+static LAST_SESSION_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
 
-fn get_buffer() -> &'static SharedBuffer {
-    BUFFER.get().expect("SharedBuffer not initialized - call spark_init() first")
+/// A session app with the SharedBuffer view it draws from, freed with it.
+struct SessionApp {
+    app: Option<App>,
+    buf: *mut SharedBuffer,
 }
 
-/// Global engine handle.
-static ENGINE: OnceLock<pipeline::Engine> = OnceLock::new();
+// The view is only read through the app, and freed once it has stopped
+unsafe impl Send for SessionApp {}
 
-/// Condvar for Rust→TS event notification.
-/// TS calls spark_wait_for_events() which blocks on this.
-/// Rust calls notify_ts_events() when events are written to ring buffer.
-static TS_EVENT_SIGNAL: OnceLock<(Mutex<bool>, Condvar)> = OnceLock::new();
+impl SessionApp {
+    #[cfg(unix)]
+    fn start(buf: SharedBuffer, io: pipeline::SessionIo) -> std::io::Result<Self> {
+        let buf = Box::into_raw(Box::new(buf));
+        match App::start_session(unsafe { &*buf }, io) {
+            Ok(app) => Ok(Self { app: Some(app), buf }),
+            Err(e) => {
+                // Nothing reads it once start has failed
+                drop(unsafe { Box::from_raw(buf) });
+                Err(e)
+            }
+        }
+    }
 
-fn init_ts_event_signal() {
-    let _ = TS_EVENT_SIGNAL.set((Mutex::new(false), Condvar::new()));
+    fn app(&self) -> &App {
+        self.app.as_ref().expect("session app is only taken on drop")
+    }
 }
 
-/// Signal TS that events are ready in the ring buffer.
-/// Called internally by Rust when writing events.
-pub fn notify_ts_events() {
-    if let Some((lock, cvar)) = TS_EVENT_SIGNAL.get() {
-        if let Ok(mut ready) = lock.lock() {
-            *ready = true;
-            cvar.notify_one();
-        }
+impl Drop for SessionApp {
+    fn drop(&mut self) {
+        // Stops and joins the engine: after this nothing reads the view
+        drop(self.app.take());
+        drop(unsafe { Box::from_raw(self.buf) });
     }
 }
 
+fn with_session<R>(id: u32, f: impl FnOnce(&App) -> R) -> Option<R> {
+    let sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    sessions.as_ref()?.get(&id).map(|session| f(session.app()))
+}
+
 // =============================================================================
 // FFI EXPORTS
 // =============================================================================
@@ -102,37 +139,35 @@ pub fn notify_ts_events() {
 /// ```
 ///
 /// Returns: 0 = success, 1 = already initialized, 2 = engine start failed
+///
+/// # Safety
+///
+/// `ptr` must point to a `len`-byte SharedBuffer that stays valid, and isn't
+/// moved, for the rest of the process.
 #[unsafe(no_mangle)]
-pub extern "C" fn spark_init(ptr: *mut u8, len: u32) -> u32 {
-    let buf = unsafe { SharedBuffer::from_raw(ptr, len as usize) };
+pub unsafe extern "C" fn spark_init(ptr: *mut u8, len: u32) -> u32 {
+    if APP.get().is_some() {
+        eprintln!("[spark-engine] Already initialized!");
+        return 1;
+    }
 
-    // Initialize TS event signal (condvar for Rust→TS notification)
-    init_ts_event_signal();
-
-    match BUFFER.set(buf) {
-        Ok(_) => {
-            let buf = get_buffer();
-            eprintln!(
-                "[spark-engine] Initialized with {}MB buffer ({} max nodes, 1024 bytes/node)",
-                len / (1024 * 1024),
-                buf.max_nodes()
-            );
-
-            // Start the reactive engine
-            match pipeline::Engine::start(buf) {
-                Ok(engine) => {
-                    let _ = ENGINE.set(engine);
-                    0 // success
-                }
-                Err(e) => {
-                    eprintln!("[spark-engine] Failed to start engine: {}", e);
-                    2 // engine start failed
-                }
-            }
-        }
-        Err(_) => {
-            eprintln!("[spark-engine] Already initialized!");
-            1 // already init
+    // Lives as long as the process, like the app drawing it
+    let buf: &'static SharedBuffer = Box::leak(Box::new(unsafe { SharedBuffer::from_raw(ptr, len as usize) }));
+    eprintln!(
+        "[spark-engine] Initialized with {}MB buffer ({} max nodes, 1024 bytes/node)",
+        len / (1024 * 1024),
+        buf.max_nodes()
+    );
+
+    // Start the reactive engine
+    match App::start(buf) {
+        Ok(app) => match APP.set(app) {
+            Ok(()) => 0, // success
+            Err(_) => 1, // lost a race with another spark_init
+        },
+        Err(e) => {
+            eprintln!("[spark-engine] Failed to start engine: {}", e);
+            2 // engine start failed
         }
     }
 }
@@ -169,10 +204,9 @@ pub extern "C" fn spark_buffer_size_custom(max_nodes: u32, text_pool_size: u32)
 /// with wake calls during construction being harmless no-ops.
 #[unsafe(no_mangle)]
 pub extern "C" fn spark_wake() {
-    if let Some(buf) = BUFFER.get() {
-        buf.set_wake_flag();
+    if let Some(app) = APP.get() {
+        app.wake();
     }
-    pipeline::wake::unpark_wake_thread();
 }
 
 /// Stop the engine and clean up.
@@ -180,11 +214,9 @@ pub extern "C" fn spark_wake() {
 /// Call this before program exit to restore terminal state.
 #[unsafe(no_mangle)]
 pub extern "C" fn spark_cleanup() {
-    // Wake TS event loop so it can exit
-    notify_ts_events();
-
-    if let Some(engine) = ENGINE.get() {
-        engine.stop();
+    // Also wakes the TS event loop so it can exit
+    if let Some(app) = APP.get() {
+        app.stop();
     }
 }
 
@@ -198,16 +230,548 @@ pub extern "C" fn spark_cleanup() {
 /// - No polling, no fixed FPS
 #[unsafe(no_mangle)]
 pub extern "C" fn spark_wait_for_events() {
-    if let Some((lock, cvar)) = TS_EVENT_SIGNAL.get() {
-        if let Ok(mut ready) = lock.lock() {
-            while !*ready {
-                ready = cvar.wait(ready).unwrap();
+    if let Some(app) = APP.get() {
+        app.wait_for_events();
+    }
+}
+
+/// Record the terminal output to an asciinema cast at the UTF-8 path
+/// `path_ptr[..path_len]` (replacing a recording already running), or stop
+/// recording when `path_len` is 0.
+///
+/// Returns: 0 = success, 1 = not initialized, 2 = failed (bad path, I/O error)
+///
+/// # Safety
+///
+/// `path_ptr` must point to `path_len` readable bytes (or be anything when `path_len` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_record_cast(path_ptr: *const u8, path_len: u32) -> u32 {
+    let Some(app) = APP.get() else { return 1 };
+    unsafe { record_cast(app, path_ptr, path_len) }
+}
+
+/// Shared by `spark_record_cast` and `spark_session_record_cast`.
+///
+/// # Safety
+///
+/// `path_ptr` must point to `path_len` readable bytes (or be anything when `path_len` is 0).
+unsafe fn record_cast(app: &App, path_ptr: *const u8, path_len: u32) -> u32 {
+    let path = match path_len {
+        0 => None,
+        len => {
+            let bytes = unsafe { std::slice::from_raw_parts(path_ptr, len as usize) };
+            match std::str::from_utf8(bytes) {
+                Ok(path) => Some(std::path::PathBuf::from(path)),
+                Err(_) => return 2,
+            }
+        }
+    };
+    match app.record_cast(path.as_deref()) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("[spark-engine] Cast recording failed: {}", e);
+            2
+        }
+    }
+}
+
+/// Set the window title to the UTF-8 string `ptr[..len]`. The terminal's
+/// own title comes back when the engine stops.
+///
+/// Returns: 0 = success, 1 = not initialized, 2 = not UTF-8
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes (or be anything when `len` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_set_title(ptr: *const u8, len: u32) -> u32 {
+    let Some(app) = APP.get() else { return 1 };
+    let Some(title) = (unsafe { ffi_str(ptr, len) }) else { return 2 };
+    app.terminal(osc::Command::Title(title));
+    0
+}
+
+/// Ring the terminal bell.
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_bell() {
+    if let Some(app) = APP.get() {
+        app.terminal(osc::Command::Bell);
+    }
+}
+
+/// Post a desktop notification. `protocol` is a `NotifyProtocol`
+/// (0 = OSC 9, 1 = OSC 777); title and body are UTF-8, the title may be
+/// empty.
+///
+/// Returns: 0 = success, 1 = not initialized, 2 = not UTF-8
+///
+/// # Safety
+///
+/// `title_ptr` and `body_ptr` must point to `title_len` and `body_len`
+/// readable bytes (or be anything when their length is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_notify(
+    protocol: u32,
+    title_ptr: *const u8,
+    title_len: u32,
+    body_ptr: *const u8,
+    body_len: u32,
+) -> u32 {
+    let Some(app) = APP.get() else { return 1 };
+    let (Some(title), Some(body)) = (unsafe { ffi_str(title_ptr, title_len) }, unsafe { ffi_str(body_ptr, body_len) }) else {
+        return 2;
+    };
+    let protocol = osc::NotifyProtocol::from(protocol as u8);
+    app.terminal(osc::Command::Notify { protocol, title, body });
+    0
+}
+
+/// Freeze one styled line into history above the live region (append
+/// mode; other modes drop it). `ptr[..len]` is the line as TS encodes it:
+/// per span, fg and bg as packed ARGB u32 (0 = terminal default), attrs u8,
+/// then the UTF-8 text's length as u32 and the text (see `StyledLine::decode`).
+///
+/// Returns: 0 = success, 1 = not initialized, 2 = malformed
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes (or be anything when `len` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_println(ptr: *const u8, len: u32) -> u32 {
+    let Some(app) = APP.get() else { return 1 };
+    unsafe { println(app, ptr, len) }
+}
+
+/// Shared by `spark_println` and `spark_session_println`.
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes (or be anything when `len` is 0).
+unsafe fn println(app: &App, ptr: *const u8, len: u32) -> u32 {
+    let bytes: &[u8] = if len == 0 { &[] } else { unsafe { std::slice::from_raw_parts(ptr, len as usize) } };
+    match renderer::StyledLine::decode(bytes) {
+        Some(line) => {
+            app.println(vec![line]);
+            0
+        }
+        None => 2,
+    }
+}
+
+/// Copy a UTF-8 string passed from TS. `None` if it isn't UTF-8.
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes (or be anything when `len` is 0).
+unsafe fn ffi_str(ptr: *const u8, len: u32) -> Option<String> {
+    if len == 0 {
+        return Some(String::new());
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+    std::str::from_utf8(bytes).ok().map(str::to_owned)
+}
+
+// =============================================================================
+// FFI EXPORTS: Sessions
+// =============================================================================
+//
+// One engine per remote terminal (see pipeline::session), for serving an
+// app over SSH/telnet. The host hands over a SharedBuffer and the
+// connection's file descriptors (a socket, a pty master, or the ends of
+// pipes bridged to its channel) and gets a session id back.
+
+/// Start a session engine on its own SharedBuffer, reading input from
+/// `input_fd` and writing frames to `output_fd` (they may be the same fd).
+/// Once it has started the session owns both, and the caller must not use
+/// or close them again; if it fails to start they stay the caller's.
+///
+/// Returns: the session id, or 0 if it couldn't start.
+///
+/// # Safety
+///
+/// `ptr` must point to a `len`-byte SharedBuffer that stays valid until
+/// `spark_session_stop`, and both fds must be open.
+#[cfg(unix)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_session_start(ptr: *mut u8, len: u32, input_fd: i32, output_fd: i32, width: u32, height: u32) -> u32 {
+    use std::fs::File;
+    use std::os::unix::io::{BorrowedFd, FromRawFd, OwnedFd};
+
+    // Run on duplicates until the session is up, so a failure closes none
+    // of the caller's fds
+    let dup = |fd| unsafe { BorrowedFd::borrow_raw(fd) }.try_clone_to_owned().map(File::from);
+    let (Ok(input), Ok(output)) = (dup(input_fd), dup(output_fd)) else {
+        return 0;
+    };
+    let Ok((input, close_input)) = pipeline::session::fd_input(input) else {
+        return 0;
+    };
+    let io = pipeline::SessionIo {
+        input,
+        close_input: Some(close_input),
+        output: Box::new(output),
+        width: width as u16,
+        height: height as u16,
+    };
+
+    // Freed when the session is stopped
+    let buf = unsafe { SharedBuffer::from_raw(ptr, len as usize) };
+    match SessionApp::start(buf, io) {
+        Ok(session) => {
+            // Started: the fds are the session's now, and it has its own copies
+            drop(unsafe { OwnedFd::from_raw_fd(input_fd) });
+            if output_fd != input_fd {
+                drop(unsafe { OwnedFd::from_raw_fd(output_fd) });
+            }
+            let id = LAST_SESSION_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            let mut sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+            sessions.get_or_insert_with(HashMap::new).insert(id, session);
+            id
+        }
+        Err(e) => {
+            eprintln!("[spark-engine] Failed to start session: {}", e);
+            0
+        }
+    }
+}
+
+/// Wake a session engine (its `spark_wake`). No-op for unknown ids.
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_session_wake(id: u32) {
+    with_session(id, |session| session.wake());
+}
+
+/// Wait for events from a session engine (its `spark_wait_for_events`).
+/// Returns at once for unknown ids.
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_session_wait_for_events(id: u32) {
+    // Wait outside the session table's lock
+    let events = with_session(id, |session| session.events());
+    if let Some(events) = events {
+        events.wait();
+    }
+}
+
+/// Tell a session its client's terminal changed size.
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_session_resize(id: u32, width: u32, height: u32) {
+    with_session(id, |session| session.resize(width as u16, height as u16));
+}
+
+/// Record a session's output to an asciinema cast (its
+/// `spark_record_cast`). Returns 1 for unknown ids.
+///
+/// # Safety
+///
+/// `path_ptr` must point to `path_len` readable bytes (or be anything when `path_len` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_session_record_cast(id: u32, path_ptr: *const u8, path_len: u32) -> u32 {
+    with_session(id, |session| unsafe { record_cast(session, path_ptr, path_len) }).unwrap_or(1)
+}
+
+/// Freeze a line into a session's history (its `spark_println`). Returns
+/// 1 for unknown ids.
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes (or be anything when `len` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_session_println(id: u32, ptr: *const u8, len: u32) -> u32 {
+    with_session(id, |session| unsafe { println(session, ptr, len) }).unwrap_or(1)
+}
+
+/// Stop a session, restoring its client's terminal, and forget it.
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_session_stop(id: u32) {
+    let mut sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(session) = sessions.as_mut().and_then(|sessions| sessions.remove(&id)) {
+        // Wake its TS event loop, then restore the client's terminal unlocked
+        session.app().stop();
+        drop(sessions);
+        drop(session);
+    }
+}
+
+// =============================================================================
+// FFI EXPORTS: Browser (wasm)
+// =============================================================================
+//
+// The default app in a browser tab, drawing into a JS terminal emulator
+// (see pipeline::browser). The page provides `spark_host_write` as an
+// import; everything else (`spark_wake`, `spark_wait_for_events`, …) is the
+// same as on a terminal.
+
+#[cfg(target_family = "wasm")]
+unsafe extern "C" {
+    /// Provided by the page: `len` bytes of ANSI at `ptr` for the emulator.
+    fn spark_host_write(ptr: *const u8, len: u32);
+}
+
+/// Input for the browser app, fed by `spark_browser_input`.
+#[cfg(target_family = "wasm")]
+static BROWSER_INPUT: OnceLock<pipeline::browser::InputFeed> = OnceLock::new();
+
+/// Allocate `len` zeroed bytes of wasm memory for the SharedBuffer (or
+/// input staging), aligned for the header's atomics. JS can't place one
+/// there itself. Free it with `spark_browser_free`; the engine's buffer
+/// lives as long as the page.
+#[cfg(target_family = "wasm")]
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_browser_alloc(len: u32) -> *mut u8 {
+    vec![0u64; (len as usize).div_ceil(8)].leak().as_mut_ptr().cast()
+}
+
+/// Free memory from `spark_browser_alloc`, with the `len` it was asked for.
+///
+/// # Safety
+///
+/// `ptr` must be null or come from `spark_browser_alloc(len)`, and not be
+/// used or freed again.
+#[cfg(target_family = "wasm")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_browser_free(ptr: *mut u8, len: u32) {
+    if ptr.is_null() {
+        return;
+    }
+    let words = (len as usize).div_ceil(8);
+    drop(unsafe { Vec::from_raw_parts(ptr.cast::<u64>(), words, words) });
+}
+
+/// Initialize the engine in the browser: like `spark_init`, but frames go
+/// to `spark_host_write` and input comes from `spark_browser_input`, for a
+/// `width`×`height` emulator.
+///
+/// Returns: 0 = success, 1 = already initialized, 2 = engine start failed
+///
+/// # Safety
+///
+/// `ptr` must point to a `len`-byte SharedBuffer that stays valid for the
+/// life of the page (from `spark_browser_alloc`).
+#[cfg(target_family = "wasm")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_browser_init(ptr: *mut u8, len: u32, width: u32, height: u32) -> u32 {
+    use pipeline::browser::{browser_io, HostOutput};
+
+    if APP.get().is_some() {
+        return 1;
+    }
+
+    // Lives as long as the page, like the app drawing it
+    let buf: &'static SharedBuffer = Box::leak(Box::new(unsafe { SharedBuffer::from_raw(ptr, len as usize) }));
+    let output = HostOutput::new(|bytes| unsafe { spark_host_write(bytes.as_ptr(), bytes.len() as u32) });
+    let (io, input) = browser_io(width as u16, height as u16, output);
+
+    match App::start_session(buf, io) {
+        Ok(app) => match APP.set(app) {
+            Ok(()) => {
+                let _ = BROWSER_INPUT.set(input);
+                0
             }
-            *ready = false;
+            Err(_) => 1, // lost a race with another init
+        },
+        Err(e) => {
+            eprintln!("[spark-engine] Failed to start engine: {}", e);
+            2
         }
     }
 }
 
+/// Hand the engine input from the emulator (xterm.js `onData`, UTF-8).
+///
+/// Returns: 0 = success, 1 = not initialized
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes (or be anything when `len` is 0).
+#[cfg(target_family = "wasm")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_browser_input(ptr: *const u8, len: u32) -> u32 {
+    let Some(input) = BROWSER_INPUT.get() else { return 1 };
+    if len == 0 {
+        return 0;
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(ptr, len as usize) };
+    if input.push(bytes) { 0 } else { 1 }
+}
+
+/// The emulator was resized (xterm.js `onResize`), in cells.
+#[cfg(target_family = "wasm")]
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_browser_resize(width: u32, height: u32) {
+    if let Some(app) = APP.get() {
+        app.resize(width as u16, height as u16);
+    }
+}
+
+// =============================================================================
+// FFI EXPORTS: Component tree (non-TS hosts)
+// =============================================================================
+//
+// For hosts other than the TS API (see host.rs): set up the buffer, create
+// and destroy nodes, write props and read events, all in terms of the same
+// SharedBuffer layout TS writes. Writes don't wake the engine; call
+// `spark_wake` once after a batch.
+
+/// The buffer a non-TS host set up with `spark_buffer_init`, and its nodes.
+struct Host {
+    buf: &'static SharedBuffer,
+    nodes: NodeRegistry,
+}
+
+static HOST: Mutex<Option<Host>> = Mutex::new(None);
+
+fn with_host<R>(f: impl FnOnce(&mut Host) -> R) -> Option<R> {
+    let mut host = HOST.lock().unwrap_or_else(|e| e.into_inner());
+    host.as_mut().map(f)
+}
+
+/// Write a fresh header and node defaults into a host-allocated buffer of
+/// `calculate_buffer_size(max_nodes, text_pool_size)` bytes (what TS's
+/// `createSharedBuffer` does). Then start the engine on it with `spark_init`.
+///
+/// Returns: 0 = success, 1 = already initialized, 2 = buffer too small
+///
+/// # Safety
+///
+/// `ptr` must point to `len` writable bytes that stay valid, and aren't
+/// moved, for the rest of the process.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_buffer_init(ptr: *mut u8, len: u32, max_nodes: u32, text_pool_size: u32) -> u32 {
+    let mut host = HOST.lock().unwrap_or_else(|e| e.into_inner());
+    if host.is_some() {
+        return 1;
+    }
+    let Some(buf) = (unsafe { SharedBuffer::initialize(ptr, len as usize, max_nodes as usize, text_pool_size as usize) })
+    else {
+        return 2;
+    };
+    // Lives as long as the process, like the engine reading it
+    let buf: &'static SharedBuffer = Box::leak(Box::new(buf));
+    *host = Some(Host { buf, nodes: NodeRegistry::new() });
+    0
+}
+
+/// Create a node of `component_type` (COMPONENT_*) under `parent`
+/// (-1 = a root).
+///
+/// Returns: the node index, or -1 (not initialized, buffer full, unknown
+/// parent)
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_create_node(component_type: u32, parent: i32) -> i32 {
+    let parent = usize::try_from(parent).ok();
+    with_host(|host| host.nodes.create(host.buf, component_type as u8, parent))
+        .flatten()
+        .map_or(-1, |index| index as i32)
+}
+
+/// Generation of a node index, bumped each time it's destroyed. Hosts
+/// holding on to an index keep it with its generation and pass both to
+/// `spark_destroy_node`.
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_node_generation(index: u32) -> u32 {
+    with_host(|host| {
+        let index = index as usize;
+        if index < host.buf.max_nodes() { host.buf.node_generation(index) } else { 0 }
+    })
+    .unwrap_or(0)
+}
+
+/// Destroy a node and everything under it, if it's still the one created
+/// at `generation`.
+///
+/// Returns: 0 = success, 1 = not initialized, 2 = stale or unknown node
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_destroy_node(index: u32, generation: u32) -> u32 {
+    with_host(|host| {
+        let node = shared_buffer::ComponentRef { index: index as usize, generation };
+        if node.index < host.buf.max_nodes() && host.buf.is_live(node) && host.nodes.destroy(host.buf, node.index) {
+            0
+        } else {
+            2
+        }
+    })
+    .unwrap_or(1)
+}
+
+/// Write `field` of a live node and mark it dirty the way TS would.
+fn set_prop(index: u32, field: u32, write: impl FnOnce(&SharedBuffer, usize, usize) -> bool) -> u32 {
+    with_host(|host| {
+        let (index, field) = (index as usize, field as usize);
+        if !host.nodes.is_live(host.buf, index) || !write(host.buf, index, field) {
+            return 2;
+        }
+        host.buf.mark_dirty(index, shared_buffer::field_dirty(field));
+        0
+    })
+    .unwrap_or(1)
+}
+
+/// Set an f32 prop (sizes, flex factors, …) at node offset `field` (N_*).
+///
+/// Returns: 0 = success, 1 = not initialized, 2 = unknown node or field
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_set_prop_f32(index: u32, field: u32, value: f32) -> u32 {
+    set_prop(index, field, |buf, i, field| buf.write_field_f32(i, field, value))
+}
+
+/// Set a u8 prop (enums, flags) at node offset `field` (N_*).
+///
+/// Returns: 0 = success, 1 = not initialized, 2 = unknown node or field
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_set_prop_u8(index: u32, field: u32, value: u32) -> u32 {
+    set_prop(index, field, |buf, i, field| buf.write_field_u8(i, field, value as u8))
+}
+
+/// Set a color prop at node offset `field` (N_*), packed ARGB.
+///
+/// Returns: 0 = success, 1 = not initialized, 2 = unknown node or field
+#[unsafe(no_mangle)]
+pub extern "C" fn spark_set_prop_color(index: u32, field: u32, argb: u32) -> u32 {
+    set_prop(index, field, |buf, i, field| buf.write_field_u32(i, field, argb))
+}
+
+/// Set a node's text content (UTF-8).
+///
+/// Returns: 0 = success, 1 = not initialized, 2 = unknown node or not
+/// UTF-8, 3 = text pool full
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes (or be anything when `len` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_set_text(index: u32, ptr: *const u8, len: u32) -> u32 {
+    let Some(text) = (unsafe { ffi_str(ptr, len) }) else { return 2 };
+    with_host(|host| {
+        let index = index as usize;
+        if !host.nodes.is_live(host.buf, index) {
+            return 2;
+        }
+        if !host.buf.set_text(index, &text) {
+            return 3;
+        }
+        host.buf.mark_dirty(index, shared_buffer::DIRTY_TEXT | shared_buffer::DIRTY_LAYOUT);
+        0
+    })
+    .unwrap_or(1)
+}
+
+/// Take the oldest unread event, copying its `EVENT_SLOT_SIZE` bytes to
+/// `out` (type, generation tag, u16 component index, 16 data bytes).
+/// Events for since-destroyed nodes are dropped. Block in
+/// `spark_wait_for_events` until there's something to take.
+///
+/// Returns: 1 = event copied, 0 = none (or not initialized)
+///
+/// # Safety
+///
+/// `out` must point to `EVENT_SLOT_SIZE` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn spark_poll_event(out: *mut u8) -> u32 {
+    let Some(Some(event)) = with_host(|host| host.buf.pop_event()) else { return 0 };
+    unsafe { std::ptr::copy_nonoverlapping(event.as_ptr(), out, event.len()) };
+    1
+}
+
 // =============================================================================
 // RE-EXPORTS: Wake mechanism test functions
 // =============================================================================
@@ -0,0 +1,148 @@
+//! Browser backend — the engine behind a JS terminal emulator (xterm.js).
+//!
+//! In a browser there is no stdin and no TTY to put in raw mode. The engine
+//! runs as a session (see `session`) whose streams are the page:
+//!
+//! ```text
+//! xterm.onData ──→ spark_browser_input ──→ InputFeed ──→ HostInput ──→ session reader
+//!                                                                          │
+//! xterm.write  ←── spark_host_write (JS import) ←── HostOutput ←── session engine
+//! xterm.onResize ─→ spark_browser_resize ──────────────────────────────────┘
+//! ```
+//!
+//! The same TS app code drives it: `spark_browser_init` starts the default
+//! app, so `spark_wake` and `spark_wait_for_events` work as on a terminal.
+//! Built for a wasm target with threads (`wasm32-wasip1-threads`), where the
+//! engine and reader threads are web workers on shared memory.
+
+use std::io::{self, Read, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use super::session::SessionIo;
+
+/// Sending end of a browser session's input: bytes typed into the
+/// emulator (its `onData`), as UTF-8 or raw escape sequences.
+#[derive(Clone)]
+pub struct InputFeed(Sender<Vec<u8>>);
+
+impl InputFeed {
+    /// Queue input for the session. False once the session is gone.
+    pub fn push(&self, bytes: &[u8]) -> bool {
+        bytes.is_empty() || self.0.send(bytes.to_vec()).is_ok()
+    }
+}
+
+/// Input stream over an `InputFeed`: blocks until bytes are pushed, ends
+/// when every feed is dropped.
+pub struct HostInput {
+    rx: Receiver<Vec<u8>>,
+    /// Part of the last chunk that didn't fit the reader's buffer
+    pending: Vec<u8>,
+}
+
+impl Read for HostInput {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            match self.rx.recv() {
+                Ok(chunk) => self.pending = chunk,
+                Err(_) => return Ok(0),
+            }
+        }
+        let len = self.pending.len().min(out.len());
+        out[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.drain(..len);
+        Ok(len)
+    }
+}
+
+/// Host callback receiving ANSI bytes.
+type WriteFn = Box<dyn FnMut(&[u8]) + Send>;
+
+/// Output stream handing each frame's ANSI to a host callback (the
+/// emulator's `write`).
+pub struct HostOutput(WriteFn);
+
+impl HostOutput {
+    pub fn new(write: impl FnMut(&[u8]) + Send + 'static) -> Self {
+        Self(Box::new(write))
+    }
+}
+
+impl Write for HostOutput {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        (self.0)(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Streams for a browser session of `width`×`height` cells writing to
+/// `output`, and the feed its input arrives on.
+pub fn browser_io(width: u16, height: u16, output: HostOutput) -> (SessionIo, InputFeed) {
+    let (tx, rx) = mpsc::channel();
+    let io = SessionIo {
+        input: Box::new(HostInput { rx, pending: Vec::new() }),
+        // Ends with the page, or once the feeds are dropped
+        close_input: None,
+        output: Box::new(output),
+        width,
+        height,
+    };
+    (io, InputFeed(tx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::App;
+    use crate::shared_buffer::{calculate_buffer_size, SharedBuffer, EventType};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_host_input_splits_chunks() {
+        let (io, feed) = browser_io(10, 2, HostOutput::new(|_| {}));
+        let mut input = io.input;
+        assert!(feed.push(b"abcdef"));
+
+        let mut out = [0u8; 4];
+        assert_eq!(input.read(&mut out).unwrap(), 4);
+        assert_eq!(&out, b"abcd");
+        assert_eq!(input.read(&mut out).unwrap(), 2);
+        assert_eq!(&out[..2], b"ef");
+
+        drop(feed);
+        assert_eq!(input.read(&mut out).unwrap(), 0, "closed once the feed is gone");
+    }
+
+    #[test]
+    fn test_browser_session_round_trip() {
+        let len = calculate_buffer_size(4, 256);
+        let data: &'static mut [u8] = vec![0u8; len].leak();
+        let buf = unsafe { SharedBuffer::initialize(data.as_mut_ptr(), len, 4, 256) }.unwrap();
+        let buf: &'static SharedBuffer = Box::leak(Box::new(buf));
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let sink = written.clone();
+        let (io, feed) = browser_io(20, 5, HostOutput::new(move |bytes| sink.lock().unwrap().extend_from_slice(bytes)));
+        let mut app = App::start_session(buf, io).unwrap();
+        // Set up for the emulator's size
+        assert_eq!(buf.terminal_width(), 20);
+
+        // Keys typed into the emulator come back as events
+        assert!(feed.push(b"x"));
+        let key = std::iter::repeat_with(|| {
+            app.wait_for_events();
+            std::iter::from_fn(|| buf.pop_event()).find(|slot| slot[0] == EventType::Key as u8)
+        })
+        .find_map(|key| key);
+        assert!(key.is_some());
+
+        // Frames reach the callback (all of them once the app is joined)
+        app.stop();
+        app.join();
+        assert!(written.lock().unwrap().windows(8).any(|w| w == b"\x1b[?1049h"));
+    }
+}
@@ -0,0 +1,336 @@
+//! Terminal capabilities — what the terminal can show, published for TS.
+//!
+//! | Fact         | Source                                              |
+//! |--------------|-----------------------------------------------------|
+//! | color depth  | `NO_COLOR`, `COLORTERM`, `TERM`, `TERM_PROGRAM`     |
+//! | unicode      | locale (`LC_ALL` → `LC_CTYPE` → `LANG`), `TERM`     |
+//! | mouse        | fullscreen + `MOUSE_ENABLED` + a terminal that reports it |
+//! | cell pixels  | `CSI 16t` reply, else `CSI 14t` ÷ terminal size     |
+//! | pixel mouse  | DECRPM reply to `CSI ? 1016 $ p`, plus a cell size  |
+//! | palette      | OSC 10/11 replies (default foreground, background)  |
+//!
+//! Everything lands in the `H_COLOR_DEPTH`.. header slots; every change bumps
+//! `H_CAPABILITIES_SEQ` and pushes a `Capabilities` event so TS signals
+//! update. The engine re-evaluates on resize and focus-in — what a tmux
+//! reattach from another terminal produces — re-sending the pixel queries.
+//! Palette colors don't fit the header: each one that changes is pushed as a
+//! `TerminalColor` event instead, for TS themes built from the real colors.
+//! Terminals with color scheme notifications (mode 2031) say when they switch
+//! between light and dark; the palette is asked for again then.
+//!
+//! Rust acts on two facts itself: at `UnicodeLevel::Ascii` borders are drawn
+//! with `+-|`, and a terminal that knows SGR-pixels mouse reporting (1016)
+//! gets it switched on once the cell size is known, so the parser can turn
+//! pixels back into cells and keep the raw pixels for TS.
+
+use std::io::Write;
+
+use crate::input::parser::TerminalColor;
+use crate::shared_buffer::{
+    ColorDepth, ConfigFlags, SharedBuffer, TERMINAL_COLOR_BACKGROUND, TERMINAL_COLOR_FOREGROUND,
+    TERMINAL_COLOR_SLOTS, UnicodeLevel,
+};
+
+/// Ask for the cell size (`CSI 16t`) and, for terminals without it, the
+/// text-area size in pixels (`CSI 14t`).
+pub const PIXEL_QUERY: &str = "\x1b[16t\x1b[14t";
+
+/// Ask for the default foreground (OSC 10) and background (OSC 11); each
+/// reply is `OSC Ps ; rgb:R/G/B ST`. The ANSI entries need no asking: themes
+/// name them by index and the terminal draws its own.
+pub const PALETTE_QUERY: &str = "\x1b]10;?\x1b\\\x1b]11;?\x1b\\";
+
+/// DECRQM for SGR-pixels mouse mode; the reply is `CSI ? 1016 ; Ps $ y`.
+pub const PIXEL_MOUSE_QUERY: &str = "\x1b[?1016$p";
+
+/// SGR-pixels mouse reporting (private mode 1016).
+pub const PIXEL_MOUSE_MODE: u16 = 1016;
+const PIXEL_MOUSE_ON: &str = "\x1b[?1016h";
+const PIXEL_MOUSE_OFF: &str = "\x1b[?1016l";
+
+/// A snapshot of the terminal's capabilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities {
+    pub color_depth: ColorDepth,
+    pub unicode: UnicodeLevel,
+    pub mouse: bool,
+    /// Cell size in pixels, if the terminal reported it.
+    pub cell_pixels: Option<(u16, u16)>,
+}
+
+impl Capabilities {
+    /// Detect from environment variables. `mouse_tracking`: whether the
+    /// engine enabled mouse reporting at all.
+    pub fn from_env(var: impl Fn(&str) -> Option<String>, mouse_tracking: bool) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        Self {
+            color_depth: color_depth(&var),
+            unicode: unicode_level(&var),
+            mouse: mouse_tracking && !matches!(term.as_str(), "dumb" | "linux"),
+            cell_pixels: None,
+        }
+    }
+
+    /// Read what is currently published.
+    pub fn read(buf: &SharedBuffer) -> Self {
+        Self {
+            color_depth: buf.color_depth(),
+            unicode: buf.unicode_level(),
+            mouse: buf.mouse_available(),
+            cell_pixels: buf.cell_pixel_size(),
+        }
+    }
+
+    fn write(&self, buf: &SharedBuffer) {
+        buf.set_color_depth(self.color_depth);
+        buf.set_unicode_level(self.unicode);
+        buf.set_mouse_available(self.mouse);
+        let (w, h) = self.cell_pixels.unwrap_or((0, 0));
+        buf.set_cell_pixel_size(w, h);
+    }
+}
+
+/// Color depth from `NO_COLOR` / `COLORTERM` / `TERM` / `TERM_PROGRAM`.
+pub fn color_depth(var: impl Fn(&str) -> Option<String>) -> ColorDepth {
+    if var("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return ColorDepth::Mono;
+    }
+    let term = var("TERM").unwrap_or_default();
+    if term == "dumb" {
+        return ColorDepth::Mono;
+    }
+    let colorterm = var("COLORTERM").unwrap_or_default();
+    if matches!(colorterm.as_str(), "truecolor" | "24bit")
+        || term.ends_with("-direct")
+        || matches!(
+            var("TERM_PROGRAM").as_deref(),
+            Some("iTerm.app" | "WezTerm" | "vscode" | "ghostty")
+        )
+    {
+        return ColorDepth::TrueColor;
+    }
+    if term.contains("256color") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/// Unicode level from the locale and `TERM`. A locale that names another
+/// charset (`C`, `POSIX`, `ISO-8859-1`) means ASCII; no locale at all is
+/// common in containers and over ssh, and modern terminals are UTF-8.
+pub fn unicode_level(var: impl Fn(&str) -> Option<String>) -> UnicodeLevel {
+    // First non-empty of LC_ALL, LC_CTYPE, LANG decides (POSIX precedence)
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|name| var(name).filter(|v| !v.is_empty()))
+        .map(|v| v.to_ascii_lowercase());
+    if locale.is_some_and(|l| !(l.contains("utf-8") || l.contains("utf8"))) {
+        return UnicodeLevel::Ascii;
+    }
+    // The Linux console font has box drawing but no wide glyphs
+    if var("TERM").as_deref() == Some("linux") {
+        UnicodeLevel::Basic
+    } else {
+        UnicodeLevel::Full
+    }
+}
+
+/// Keeps the published capabilities current.
+#[derive(Debug, Default)]
+pub struct CapabilityMonitor {
+    current: Capabilities,
+    /// The terminal answered `CSI 16t`; `CSI 14t` derivations are ignored.
+    cell_reported: bool,
+    /// Mouse tracking is on and the terminal recognizes mode 1016.
+    pixel_mouse_supported: bool,
+    /// Mode 1016 is switched on.
+    pixel_mouse_on: bool,
+    /// Reported colors by `TerminalColor` event slot.
+    palette: [Option<[u8; 3]>; TERMINAL_COLOR_SLOTS],
+}
+
+impl CapabilityMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Re-detect from the environment and config, and re-send the pixel and
+    /// palette queries (replies arrive as input). Returns whether anything changed.
+    pub fn refresh(&mut self, buf: &SharedBuffer, fullscreen: bool, out: &mut impl Write) -> bool {
+        let mouse_tracking = fullscreen && buf.config_flags().contains(ConfigFlags::MOUSE_ENABLED);
+        let detected = Capabilities {
+            cell_pixels: self.current.cell_pixels,
+            ..Capabilities::from_env(|name| std::env::var(name).ok(), mouse_tracking)
+        };
+        self.cell_reported = false;
+        // Inline, the app shares the terminal with the shell and can exit
+        // before the reply, which the shell would then read as typed input
+        if fullscreen {
+            let _ = out.write_all(PIXEL_QUERY.as_bytes()).and_then(|_| out.flush());
+        }
+        self.query_palette(out);
+        if mouse_tracking {
+            let _ = out.write_all(PIXEL_MOUSE_QUERY.as_bytes()).and_then(|_| out.flush());
+        } else {
+            self.pixel_mouse_supported = false;
+        }
+        self.publish(buf, detected)
+    }
+
+    /// DECRPM reply: note whether the terminal knows SGR-pixels.
+    pub fn mode_report(&mut self, mode: u16, state: u8) {
+        if mode == PIXEL_MOUSE_MODE {
+            self.pixel_mouse_supported = state != 0;
+        }
+    }
+
+    /// Switch mode 1016 on or off to match what's known, and return the cell
+    /// size mouse reports are in pixels for (`None`: reports are in cells).
+    pub fn pixel_mouse(&mut self, out: &mut impl Write) -> Option<(u16, u16)> {
+        let cell = self.current.cell_pixels.filter(|_| self.pixel_mouse_supported);
+        if cell.is_some() != self.pixel_mouse_on {
+            self.pixel_mouse_on = cell.is_some();
+            let mode = if self.pixel_mouse_on { PIXEL_MOUSE_ON } else { PIXEL_MOUSE_OFF };
+            let _ = out.write_all(mode.as_bytes()).and_then(|_| out.flush());
+        }
+        cell
+    }
+
+    /// `CSI 6 ; h ; w t` reply.
+    pub fn cell_pixels(&mut self, buf: &SharedBuffer, width: u16, height: u16) -> bool {
+        self.cell_reported = true;
+        self.set_cell_pixels(buf, width, height)
+    }
+
+    /// `CSI 4 ; h ; w t` reply: derive the cell size unless `CSI 16t` answered.
+    pub fn window_pixels(&mut self, buf: &SharedBuffer, width: u16, height: u16) -> bool {
+        let (cols, rows) = (buf.terminal_width(), buf.terminal_height());
+        if self.cell_reported || cols == 0 || rows == 0 {
+            return false;
+        }
+        self.set_cell_pixels(buf, (width as u32 / cols) as u16, (height as u32 / rows) as u16)
+    }
+
+    /// Ask for the palette again (the terminal switched color schemes).
+    pub fn query_palette(&mut self, out: &mut impl Write) {
+        let _ = out.write_all(PALETTE_QUERY.as_bytes()).and_then(|_| out.flush());
+    }
+
+    /// OSC 10/11 reply: push a `TerminalColor` event if the color is new.
+    pub fn terminal_color(&mut self, buf: &SharedBuffer, color: TerminalColor, rgb: [u8; 3]) -> bool {
+        let slot = match color {
+            TerminalColor::Foreground => TERMINAL_COLOR_FOREGROUND,
+            TerminalColor::Background => TERMINAL_COLOR_BACKGROUND,
+        };
+        let known = &mut self.palette[slot as usize];
+        if *known == Some(rgb) {
+            return false;
+        }
+        *known = Some(rgb);
+        buf.push_terminal_color_event(slot, rgb);
+        true
+    }
+
+    fn set_cell_pixels(&mut self, buf: &SharedBuffer, width: u16, height: u16) -> bool {
+        let cell_pixels = (width > 0 && height > 0).then_some((width, height));
+        self.publish(buf, Capabilities { cell_pixels, ..self.current })
+    }
+
+    fn publish(&mut self, buf: &SharedBuffer, caps: Capabilities) -> bool {
+        if caps == self.current && Capabilities::read(buf) == caps {
+            return false;
+        }
+        self.current = caps;
+        caps.write(buf);
+        buf.bump_capabilities_seq();
+        buf.push_capabilities_event();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(pairs: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| pairs.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn test_color_depth_from_env() {
+        assert_eq!(color_depth(env(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")])), ColorDepth::TrueColor);
+        assert_eq!(color_depth(env(&[("TERM", "screen-256color")])), ColorDepth::Ansi256);
+        assert_eq!(color_depth(env(&[("TERM", "xterm")])), ColorDepth::Ansi16);
+        assert_eq!(color_depth(env(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")])), ColorDepth::Mono);
+        assert_eq!(color_depth(env(&[("TERM", "dumb")])), ColorDepth::Mono);
+    }
+
+    #[test]
+    fn test_unicode_level_from_locale() {
+        assert_eq!(unicode_level(env(&[("LANG", "en_US.UTF-8")])), UnicodeLevel::Full);
+        // LC_ALL wins over LANG
+        assert_eq!(unicode_level(env(&[("LC_ALL", "C"), ("LANG", "en_US.UTF-8")])), UnicodeLevel::Ascii);
+        assert_eq!(unicode_level(env(&[("LC_CTYPE", "de_DE.utf8"), ("TERM", "linux")])), UnicodeLevel::Basic);
+        // No locale at all: assume UTF-8
+        assert_eq!(unicode_level(env(&[])), UnicodeLevel::Full);
+        assert_eq!(unicode_level(env(&[("LANG", "")])), UnicodeLevel::Full);
+    }
+
+    #[test]
+    fn test_pixel_mouse_needs_support_and_a_cell_size() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        let buf = TestBuffer::new(0, 0);
+        let mut monitor = CapabilityMonitor::new();
+        let mut out = Vec::new();
+
+        monitor.mode_report(PIXEL_MOUSE_MODE, 2);
+        assert_eq!(monitor.pixel_mouse(&mut out), None);
+        monitor.cell_pixels(&buf, 9, 18);
+        assert_eq!(monitor.pixel_mouse(&mut out), Some((9, 18)));
+        assert_eq!(out, PIXEL_MOUSE_ON.as_bytes());
+
+        // Already on: nothing more written
+        out.clear();
+        assert_eq!(monitor.pixel_mouse(&mut out), Some((9, 18)));
+        assert!(out.is_empty());
+
+        monitor.mode_report(PIXEL_MOUSE_MODE, 0);
+        assert_eq!(monitor.pixel_mouse(&mut out), None);
+        assert_eq!(out, PIXEL_MOUSE_OFF.as_bytes());
+    }
+
+    #[test]
+    fn test_terminal_colors_publish_changes_only() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        let buf = TestBuffer::new(0, 0);
+        let mut monitor = CapabilityMonitor::new();
+
+        assert!(monitor.terminal_color(&buf, TerminalColor::Background, [0x1e, 0x1e, 0x2e]));
+        assert!(!monitor.terminal_color(&buf, TerminalColor::Background, [0x1e, 0x1e, 0x2e]));
+        assert!(monitor.terminal_color(&buf, TerminalColor::Background, [0xff, 0xff, 0xff]));
+        assert!(monitor.terminal_color(&buf, TerminalColor::Foreground, [0x1e, 0x1e, 0x2e]));
+    }
+
+    #[test]
+    fn test_mouse_needs_tracking_and_a_capable_terminal() {
+        assert!(Capabilities::from_env(env(&[("TERM", "xterm")]), true).mouse);
+        assert!(!Capabilities::from_env(env(&[("TERM", "xterm")]), false).mouse);
+        assert!(!Capabilities::from_env(env(&[("TERM", "linux")]), true).mouse);
+    }
+
+    #[test]
+    fn test_pixel_query_only_in_fullscreen() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        let buf = TestBuffer::new(0, 0);
+        let mut monitor = CapabilityMonitor::new();
+        let sent = |out: &[u8]| out.windows(PIXEL_QUERY.len()).any(|w| w == PIXEL_QUERY.as_bytes());
+
+        let mut out = Vec::new();
+        monitor.refresh(&buf, false, &mut out);
+        assert!(!sent(&out));
+        out.clear();
+        monitor.refresh(&buf, true, &mut out);
+        assert!(sent(&out));
+    }
+}
@@ -0,0 +1,157 @@
+//! Idle work — non-urgent computation deferred until the engine is idle.
+//!
+//! Some work is worth doing ahead of time but must never delay a frame:
+//! pre-wrapping off-screen text, pre-rendering the next carousel page,
+//! warming caches. Queue it with [`idle_compute`] from anywhere on the
+//! engine thread (deriveds, effects, input handlers):
+//!
+//! ```text
+//! message ─→ propagate ─→ render ─→ channel empty?
+//!                                     ├─ no:  handle the next message
+//!                                     └─ yes: run idle jobs, highest priority first,
+//!                                             checking the channel between jobs ─→ block
+//! ```
+//!
+//! The engine is *idle* when a message has been fully propagated and no
+//! other message is waiting. Jobs run one at a time; before each one the
+//! engine checks the channel and hands control back the moment input or a
+//! wake arrives. A job that is already running finishes — keep jobs small.
+//!
+//! Nothing here wakes the engine: with no messages and no jobs it blocks
+//! on the channel as before.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+/// How soon a queued job should run, relative to other idle work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IdlePriority {
+    /// Speculative work that may never be needed (cache warming).
+    Low = 0,
+    /// Work likely needed soon (text about to scroll into view).
+    Normal = 1,
+    /// Work needed on the next interaction (the next carousel page).
+    High = 2,
+}
+
+const PRIORITY_COUNT: usize = 3;
+
+type IdleJob = Box<dyn FnOnce()>;
+
+/// Pending idle jobs, one FIFO per priority.
+#[derive(Default)]
+pub struct IdleQueue {
+    queues: [VecDeque<IdleJob>; PRIORITY_COUNT],
+}
+
+impl IdleQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a job. Jobs of equal priority run in the order queued.
+    pub fn push(&mut self, priority: IdlePriority, job: impl FnOnce() + 'static) {
+        self.queues[priority as usize].push_back(Box::new(job));
+    }
+
+    /// Take the next job: highest priority first.
+    pub fn pop(&mut self) -> Option<IdleJob> {
+        self.queues.iter_mut().rev().find_map(|q| q.pop_front())
+    }
+
+    pub fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.iter().all(VecDeque::is_empty)
+    }
+}
+
+thread_local! {
+    /// The engine thread's idle queue.
+    static IDLE_QUEUE: RefCell<IdleQueue> = RefCell::new(IdleQueue::new());
+}
+
+/// Queue `job` to run on this thread the next time the engine is idle.
+pub fn idle_compute(priority: IdlePriority, job: impl FnOnce() + 'static) {
+    IDLE_QUEUE.with(|q| q.borrow_mut().push(priority, job));
+}
+
+/// Number of jobs waiting on this thread.
+pub fn pending_idle_jobs() -> usize {
+    IDLE_QUEUE.with(|q| q.borrow().len())
+}
+
+/// Run queued jobs until the queue is empty or `should_yield` returns true.
+/// `should_yield` is checked before every job. Jobs may queue more jobs.
+/// Returns the number of jobs run.
+pub fn run_idle(mut should_yield: impl FnMut() -> bool) -> usize {
+    let mut ran = 0;
+    loop {
+        if IDLE_QUEUE.with(|q| q.borrow().is_empty()) || should_yield() {
+            return ran;
+        }
+        // Release the borrow before running: jobs may call idle_compute
+        let Some(job) = IDLE_QUEUE.with(|q| q.borrow_mut().pop()) else {
+            return ran;
+        };
+        job();
+        ran += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_queue_orders_by_priority_then_fifo() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut queue = IdleQueue::new();
+        for (priority, tag) in [
+            (IdlePriority::Low, "low"),
+            (IdlePriority::High, "high-1"),
+            (IdlePriority::Normal, "normal"),
+            (IdlePriority::High, "high-2"),
+        ] {
+            let log = log.clone();
+            queue.push(priority, move || log.borrow_mut().push(tag));
+        }
+        assert_eq!(queue.len(), 4);
+
+        while let Some(job) = queue.pop() {
+            job();
+        }
+        assert_eq!(*log.borrow(), ["high-1", "high-2", "normal", "low"]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_run_idle_yields_and_resumes() {
+        let count = Rc::new(RefCell::new(0));
+        for _ in 0..3 {
+            let count = count.clone();
+            idle_compute(IdlePriority::Normal, move || *count.borrow_mut() += 1);
+        }
+
+        // Input "arrives" after the first job
+        let mut checks = 0;
+        let ran = run_idle(|| {
+            checks += 1;
+            checks > 1
+        });
+        assert_eq!(ran, 1);
+        assert_eq!(pending_idle_jobs(), 2);
+
+        // Jobs can queue follow-up work
+        let follow = count.clone();
+        idle_compute(IdlePriority::Low, move || {
+            idle_compute(IdlePriority::Low, move || *follow.borrow_mut() += 10);
+        });
+        assert_eq!(run_idle(|| false), 4);
+        assert_eq!(*count.borrow(), 13);
+        assert_eq!(pending_idle_jobs(), 0);
+    }
+}
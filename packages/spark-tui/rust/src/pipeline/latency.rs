@@ -0,0 +1,142 @@
+//! Input-to-paint latency — how long a keystroke takes to reach the screen.
+//!
+//! ```text
+//! stdin read ─→ parse/dispatch ─→ (TS handler ─→ wake) ─→ layout ─→ framebuffer ─→ paint
+//!  read_at                                                                    painted
+//! ```
+//!
+//! The stdin thread stamps every read. The first frame after it that
+//! actually changes the screen closes the sample, so input handled in TS is
+//! measured through its round trip back to the engine. Until then further
+//! input is folded into the oldest pending one.
+//!
+//! Only deliberate input counts (keys, paste, clicks, wheel): bare motion
+//! rarely changes the screen. Input that never leads to a change expires
+//! after `PENDING_TIMEOUT` instead of being charged to an unrelated later
+//! frame.
+//!
+//! Percentiles over the last `LATENCY_WINDOW` samples are published to the
+//! header after each sample (`H_INPUT_LATENCY_*`).
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::shared_buffer::SharedBuffer;
+
+/// Samples kept for the percentiles.
+pub const LATENCY_WINDOW: usize = 256;
+
+/// Pending input older than this produced no visible change; drop it.
+const PENDING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Latency percentiles in microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatencyStats {
+    pub p50: u32,
+    pub p95: u32,
+    pub p99: u32,
+    pub last: u32,
+}
+
+/// Rolling window of input-to-paint samples.
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    samples: VecDeque<u32>,
+    pending: Option<Instant>,
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Input read at `read_at` is waiting for a paint.
+    pub fn input(&mut self, read_at: Instant) {
+        if let Some(pending) = self.pending
+            && read_at.saturating_duration_since(pending) > PENDING_TIMEOUT
+        {
+            self.pending = None;
+        }
+        self.pending.get_or_insert(read_at);
+    }
+
+    /// A frame that changed the screen finished at `now`. Returns the
+    /// latency recorded, if an input was waiting for it.
+    pub fn painted(&mut self, now: Instant) -> Option<u32> {
+        let elapsed = now.saturating_duration_since(self.pending.take()?);
+        if elapsed > PENDING_TIMEOUT {
+            return None;
+        }
+        let us = elapsed.as_micros().min(u32::MAX as u128) as u32;
+        if self.samples.len() == LATENCY_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(us);
+        Some(us)
+    }
+
+    /// Percentiles over the window (nearest rank); zeros when empty.
+    pub fn stats(&self) -> LatencyStats {
+        let Some(&last) = self.samples.back() else {
+            return LatencyStats::default();
+        };
+        let mut sorted: Vec<u32> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = |p: usize| sorted[(sorted.len() * p).div_ceil(100).max(1) - 1];
+        LatencyStats { p50: rank(50), p95: rank(95), p99: rank(99), last }
+    }
+
+    /// Write the current percentiles to the header.
+    pub fn publish(&self, buf: &SharedBuffer) {
+        let stats = self.stats();
+        buf.set_input_latency(stats.p50, stats.p95, stats.p99, stats.last);
+        buf.increment_input_latency_count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_percentiles_nearest_rank() {
+        let start = Instant::now();
+        let mut tracker = LatencyTracker::new();
+        for i in 1..=100 {
+            tracker.input(start);
+            tracker.painted(start + Duration::from_micros(i * 10));
+        }
+        assert_eq!(
+            tracker.stats(),
+            LatencyStats { p50: 500, p95: 950, p99: 990, last: 1000 }
+        );
+    }
+
+    #[test]
+    fn test_pending_folds_and_expires() {
+        let start = Instant::now();
+        let mut tracker = LatencyTracker::new();
+
+        // No input → a paint records nothing
+        assert_eq!(tracker.painted(start), None);
+
+        // Two inputs before one paint: measured from the first
+        tracker.input(start);
+        tracker.input(start + ms(5));
+        assert_eq!(tracker.painted(start + ms(8)), Some(8_000));
+
+        // Input that changed nothing is dropped once a later input arrives past the timeout
+        tracker.input(start + ms(10));
+        tracker.input(start + ms(10) + PENDING_TIMEOUT + ms(20));
+        assert_eq!(tracker.painted(start + ms(30) + PENDING_TIMEOUT + ms(2)), Some(2_000));
+
+        // ... or when the eventual paint comes too late
+        tracker.input(start);
+        assert_eq!(tracker.painted(start + PENDING_TIMEOUT + ms(1)), None);
+        assert_eq!(tracker.stats().last, 2_000);
+    }
+}
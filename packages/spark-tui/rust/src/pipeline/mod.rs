@@ -12,10 +12,28 @@
 //!
 //! Rust stdin input → updates state in SharedBuffer → same propagation → terminal
 //! Rust writes events to ring buffer → wakes TS → TS dispatches callbacks
+//!
+//! Changes can be coalesced into at most one frame per batch window (`pacing`).
+//! Non-urgent work queued with `idle_compute` runs in the gaps between messages.
+//! Input-to-paint latency is sampled along the way (`latency`); what the
+//! terminal can show is published for TS (`capabilities`).
+//!
+//! Besides the process engine on stdin/stdout, more engines can serve remote
+//! terminals over arbitrary streams (`session`), including a JS terminal
+//! emulator in the browser (`browser`).
 
+pub mod browser;
+pub mod capabilities;
+pub mod idle;
+pub mod latency;
+pub mod pacing;
+pub mod session;
 pub mod setup;
 pub mod terminal;
 pub mod wake;
+pub mod watchdog;
 
+pub use idle::{idle_compute, IdlePriority};
+pub use session::{Session, SessionIo};
 pub use setup::Engine;
 pub use terminal::TerminalSetup;
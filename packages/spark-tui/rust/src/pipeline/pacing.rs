@@ -0,0 +1,101 @@
+//! Frame pacing — coalesce high-frequency changes into one frame per window.
+//!
+//! By default every change starts a frame at once. A signal set 10,000 times
+//! a second (tick data, a sensor, a log tail) then re-runs layout,
+//! framebuffer and render for every set. With a batch window, frames start
+//! at most once per window:
+//!
+//! ```text
+//! change ─→ pacer.request()
+//!             ├─ window since the last frame elapsed → frame now
+//!             └─ otherwise → frame pending, due when the window closes
+//!                             (changes until then land in the same frame)
+//! ```
+//!
+//! The first change after a quiet spell still renders immediately; only
+//! changes that follow a frame more closely than the window are held back.
+//! Nothing is scheduled while idle: the engine waits for a due frame only
+//! while one is pending, the same way it waits out a watchdog storm.
+//!
+//! The window is `H_BATCH_WINDOW_US` in the header — 0 (off) by default, set
+//! from TS with the `batchWindow` mount option and from Rust with
+//! `SharedBuffer::set_batch_window`.
+
+use std::time::{Duration, Instant};
+
+/// Decides when the frames changes ask for may start.
+#[derive(Debug, Default)]
+pub struct FramePacer {
+    /// When the last paced frame started.
+    last_frame: Option<Instant>,
+    /// A change is waiting for the window to close.
+    pending: bool,
+}
+
+impl FramePacer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A change at `now` wants a frame. Returns whether to start it now;
+    /// otherwise a frame is pending until `due`.
+    pub fn request(&mut self, window: Duration, now: Instant) -> bool {
+        let open = self.last_frame.is_none_or(|last| now.duration_since(last) >= window);
+        if open {
+            self.start(now);
+        } else {
+            self.pending = true;
+        }
+        open
+    }
+
+    /// When the pending frame may start, if there is one.
+    pub fn due(&self, window: Duration) -> Option<Instant> {
+        match (self.pending, self.last_frame) {
+            (true, Some(last)) => Some(last + window),
+            _ => None,
+        }
+    }
+
+    /// The pending frame is starting at `now`.
+    pub fn start(&mut self, now: Instant) {
+        self.last_frame = Some(now);
+        self.pending = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(16);
+
+    #[test]
+    fn test_no_window_renders_every_change() {
+        let mut pacer = FramePacer::new();
+        let now = Instant::now();
+        assert!(pacer.request(Duration::ZERO, now));
+        assert!(pacer.request(Duration::ZERO, now));
+        assert_eq!(pacer.due(Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_changes_within_the_window_share_one_frame() {
+        let mut pacer = FramePacer::new();
+        let start = Instant::now();
+        // First change after a quiet spell renders at once
+        assert!(pacer.request(WINDOW, start));
+        assert_eq!(pacer.due(WINDOW), None);
+
+        for micros in [10, 500, 9000] {
+            assert!(!pacer.request(WINDOW, start + Duration::from_micros(micros)));
+        }
+        assert_eq!(pacer.due(WINDOW), Some(start + WINDOW));
+
+        pacer.start(start + WINDOW);
+        assert_eq!(pacer.due(WINDOW), None);
+        // Idle until the next change, which again waits out the window
+        assert!(!pacer.request(WINDOW, start + WINDOW + Duration::from_millis(1)));
+        assert!(pacer.request(WINDOW, start + WINDOW * 3));
+    }
+}
@@ -0,0 +1,305 @@
+//! Sessions — more engines in the same process, one per remote terminal.
+//!
+//! Serving an app over SSH or telnet means one UI per connection. A session
+//! is a full engine (own thread, SharedBuffer, reactive graph, renderer,
+//! input parser, timers) bound to a pair of streams and a terminal size
+//! instead of stdin/stdout and the controlling TTY:
+//!
+//! ```text
+//! connection ──input──→ session reader thread ─┐
+//!                                              ├─→ session engine thread ──output──→ connection
+//! host: wake() / resize() / stop() ────────────┘     (own SharedBuffer)
+//! ```
+//!
+//! ```ignore
+//! let (input, close_input) = fd_input(socket.try_clone()?)?;
+//! let session = Session::start(buf, SessionIo {
+//!     input,
+//!     close_input: Some(close_input),
+//!     output: Box::new(socket),
+//!     width: 80,
+//!     height: 24,
+//! })?;
+//! // on the client's window-change request:
+//! session.resize(cols, rows);
+//! ```
+//!
+//! The streams are plain blocking `Read`/`Write`, like stdin/stdout; an
+//! async server bridges its channel to them (a pipe or socket pair, or a
+//! blocking adapter). Each gets a thread of its own: a slow client holds up
+//! its writer (frames queue behind it), never the engine, and stopping the
+//! session ends the pending read (`close_input`; `fd_input` makes one for
+//! file descriptors) so both threads are joined.
+//!
+//! The client's pty is already raw, so the session only sends escape
+//! sequences — the process terminal, its raw mode and its crash restore
+//! hooks belong to the process engine alone. There is no SIGWINCH for a
+//! connection: the host forwards size changes with `resize`.
+//!
+//! Each session needs its own SharedBuffer (and the app instance writing
+//! it). Hosts usually hold one through an `App` (`App::start_session`),
+//! which also gives it its own events signal. Over FFI, sessions are started
+//! on file descriptors (`spark_session_start`).
+
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::input::reader::StdinMessage;
+use crate::renderer::StyledLine;
+use crate::shared_buffer::SharedBuffer;
+use super::setup::{self, EngineTerminal};
+use super::terminal::osc;
+use super::wake::Waker;
+
+/// Ends a `read` of a session's input blocked on another thread.
+pub type CloseInput = Box<dyn FnOnce() + Send>;
+
+/// A session's connection: where its input comes from and its frames go.
+pub struct SessionIo {
+    pub input: Box<dyn Read + Send>,
+    /// Ends a pending read of `input` (with EOF or an error) when the
+    /// session stops, so its reader thread is joined. Without it the reader
+    /// is left to finish when the input closes.
+    pub close_input: Option<CloseInput>,
+    pub output: Box<dyn Write + Send>,
+    /// Initial terminal size in cells
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Input from a file descriptor (a socket, a pty master, a pipe) that the
+/// returned `CloseInput` can end from another thread.
+#[cfg(unix)]
+pub fn fd_input<R>(input: R) -> io::Result<(Box<dyn Read + Send>, CloseInput)>
+where
+    R: Read + std::os::unix::io::AsRawFd + Send + 'static,
+{
+    let (stop, close) = io::pipe()?;
+    Ok((Box::new(FdInput { input, stop }), Box::new(move || drop(close))))
+}
+
+/// Reads wait for the input or for the stop pipe to close, whichever first.
+#[cfg(unix)]
+struct FdInput<R> {
+    input: R,
+    stop: io::PipeReader,
+}
+
+#[cfg(unix)]
+impl<R: Read + std::os::unix::io::AsRawFd> Read for FdInput<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        use std::os::unix::io::AsRawFd;
+
+        let mut fds = [
+            libc::pollfd { fd: self.input.as_raw_fd(), events: libc::POLLIN, revents: 0 },
+            libc::pollfd { fd: self.stop.as_raw_fd(), events: libc::POLLIN, revents: 0 },
+        ];
+        if unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if fds[1].revents != 0 {
+            return Ok(0); // the session stopped
+        }
+        self.input.read(out)
+    }
+}
+
+/// A session's end of its writer thread: frames are queued at each flush
+/// and written to the connection there.
+struct SessionOutput {
+    pending: Vec<u8>,
+    tx: Sender<Vec<u8>>,
+}
+
+impl Write for SessionOutput {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        self.tx
+            .send(std::mem::take(&mut self.pending))
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+}
+
+impl Drop for SessionOutput {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Write what the engine queues to `output` on a thread of its own, until
+/// the engine lets go of its end or the connection fails.
+fn spawn_writer(mut output: Box<dyn Write + Send>) -> io::Result<(SessionOutput, JoinHandle<()>)> {
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+    let handle = thread::Builder::new().name("spark-session-output".to_string()).spawn(move || {
+        for frame in rx {
+            if output.write_all(&frame).and_then(|()| output.flush()).is_err() {
+                break;
+            }
+        }
+    })?;
+    Ok((SessionOutput { pending: Vec::new(), tx }, handle))
+}
+
+/// Handle to a running session engine. Dropping it stops the session and
+/// waits for the client's terminal to be put back.
+pub struct Session {
+    buf: &'static SharedBuffer,
+    running: Arc<AtomicBool>,
+    tx: Sender<StdinMessage>,
+    waker: Waker,
+    handle: Option<JoinHandle<()>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl Session {
+    /// Start an engine for one connection, drawing from `buf`.
+    ///
+    /// Returns once the terminal is set up and the engine is listening, or
+    /// with the error that stopped it.
+    pub fn start(buf: &'static SharedBuffer, mut io: SessionIo) -> io::Result<Self> {
+        let (output, writer) = spawn_writer(io.output)?;
+        io.output = Box::new(output);
+        let spawned = match setup::spawn_engine("spark-session", buf, EngineTerminal::Session(io)) {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                // The engine thread drops its end as it finishes
+                let _ = writer.join();
+                return Err(e);
+            }
+        };
+        Ok(Self {
+            buf,
+            running: spawned.running,
+            tx: spawned.tx,
+            waker: spawned.waker,
+            handle: Some(spawned.handle),
+            writer: Some(writer),
+        })
+    }
+
+    /// Tell the engine its SharedBuffer changed (the session's `spark_wake`).
+    pub fn wake(&self) {
+        self.buf.set_wake_flag();
+        self.waker.wake();
+    }
+
+    /// The client's terminal changed size (SSH window-change, telnet NAWS).
+    pub fn resize(&self, width: u16, height: u16) {
+        let _ = self.tx.send(StdinMessage::Resize(width, height));
+    }
+
+    /// Record what the client sees to an asciinema cast at `path`, or stop
+    /// recording with `None`.
+    pub fn record_cast(&self, path: Option<&Path>) -> io::Result<()> {
+        setup::request_cast(&self.tx, path)
+    }
+
+    /// Set the client's window title, ring its bell or notify it.
+    pub fn terminal(&self, command: osc::Command) {
+        let _ = self.tx.send(StdinMessage::Terminal(command));
+    }
+
+    /// Freeze `lines` into the client's history (append mode only).
+    pub fn println(&self, lines: Vec<StyledLine>) {
+        let _ = self.tx.send(StdinMessage::History(lines));
+    }
+
+    /// Stop the engine and put the client's terminal back.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        let _ = self.tx.send(StdinMessage::Closed);
+    }
+
+    /// Wait for the engine thread to finish and the client's terminal to be
+    /// put back (after `stop`, or once the input closes): the engine, its
+    /// reader and its writer are joined. After this nothing reads the
+    /// SharedBuffer.
+    pub fn join(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        // Drains the frames still queued for the client
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+
+    /// False once stopped, or once the connection's input closed.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.stop();
+        self.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::shared_buffer::{calculate_buffer_size, H_MAX_NODES, H_TEXT_POOL_SIZE};
+
+    /// Output stream the test can read back.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_session_draws_to_its_own_stream() {
+        const NODES: usize = 4;
+        const TEXT_POOL: usize = 1024;
+        let data: &'static mut [u8] = vec![0u8; calculate_buffer_size(NODES, TEXT_POOL)].leak();
+        data[H_MAX_NODES..H_MAX_NODES + 4].copy_from_slice(&(NODES as u32).to_le_bytes());
+        data[H_TEXT_POOL_SIZE..H_TEXT_POOL_SIZE + 4].copy_from_slice(&(TEXT_POOL as u32).to_le_bytes());
+        let buf: &'static SharedBuffer = Box::leak(Box::new(unsafe { SharedBuffer::from_raw(data.as_mut_ptr(), data.len()) }));
+
+        // The client keeps its end open: stopping has to end the read
+        let (input, _client_keys) = io::pipe().unwrap();
+        let (input, close_input) = fd_input(input).unwrap();
+        let output = Captured::default();
+        let session = Session::start(buf, SessionIo {
+            input,
+            close_input: Some(close_input),
+            output: Box::new(output.clone()),
+            width: 20,
+            height: 5,
+        })
+        .unwrap();
+        assert!(session.is_running());
+        // Sized by the host rather than the TTY
+        assert_eq!(buf.terminal_width(), 20);
+
+        // Joins the engine, its reader and its writer, which drained the
+        // set-up and the teardown to the stream
+        drop(session);
+        let written = output.0.lock().unwrap();
+        let at = |seq: &[u8]| written.windows(seq.len()).position(|w| w == seq);
+        assert!(at(b"\x1b[?1049h") < at(b"\x1b[?1049l"));
+        assert!(at(b"\x1b[?1049h").is_some());
+    }
+}
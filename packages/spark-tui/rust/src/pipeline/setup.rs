@@ -4,6 +4,8 @@
 //!
 //! ```text
 //! generation signal (incremented on TS wake or stdin input)
+//!   │   each increment is a frame: on_frame callbacks (state::frame) run
+//!   │   first, in the same batch, so their updates render together
 //!   │
 //!   ├─→ layout_derived (spark-signals derived)
 //!   │     reads generation → reads SharedBuffer dirty flags
@@ -30,35 +32,118 @@
 //! - **engine thread**: blocks on channel.recv(), processes both immediately
 //!
 //! No polling. No fixed timeout. Pure event-driven reactive propagation.
+//!
+//! Panics in input dispatch, timer and task callbacks and layout are caught
+//! where they happen and reported to TS as `Error` events (TS error
+//! boundaries swap the offending subtree for an error box); the engine
+//! carries on. Anything that still unwinds out of the engine thread drops
+//! `TerminalSetup`, which restores the terminal, and asks TS to exit.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::io;
+use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::thread;
+use std::thread::{self, JoinHandle};
 use std::sync::mpsc;
-use std::time::Instant;
-use spark_signals::{signal, derived, effect, Signal};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use spark_signals::{batch, signal, derived, effect, Derived, Signal};
 
-use crate::shared_buffer::{SharedBuffer, RenderMode, DIRTY_LAYOUT, DIRTY_TEXT, DIRTY_HIERARCHY};
-use crate::layout;
+use crate::shared_buffer::{SharedBuffer, RenderMode, ErrorSource, DIRTY_LAYOUT, DIRTY_TEXT, DIRTY_HIERARCHY, SCREENSHOT_COPY_PATH, SCREENSHOT_IMAGE, SCREENSHOT_COPY_IMAGE};
+use crate::layout::{self, LayoutEnv};
 use crate::framebuffer::{self, HitRegion};
-use crate::renderer::{FrameBuffer, DiffRenderer, InlineRenderer};
-use crate::input::parser::{InputParser, ParsedEvent};
+use crate::renderer::{ansi, passthrough, AppendRenderer, FrameBuffer, DiffRenderer, InlineRenderer, RegionBand, StyledLine, TerminalOut};
+use crate::renderer::cast;
+use crate::renderer::output::{self, set_terminal_output};
+use crate::input::parser::{InputParser, MouseKind, ParsedEvent};
 use crate::input::focus::FocusManager;
 use crate::input::keyboard;
+use crate::input::keymap::NavChord;
 use crate::input::mouse::MouseManager;
 use crate::input::scroll::ScrollManager;
 use crate::input::text_edit::TextEditor;
 use crate::input::reader::{StdinReader, StdinMessage, ResizeWatcher, get_terminal_size};
-use super::terminal::TerminalSetup;
-use super::wake::WakeWatcher;
+use super::capabilities::CapabilityMonitor;
+use super::idle;
+use super::latency::LatencyTracker;
+use super::pacing::FramePacer;
+use super::terminal::{self, osc, TerminalSetup};
+use super::session::SessionIo;
+use super::wake::{self, Waker, WakeWatcher};
+use super::watchdog::{self, Watchdog, WakeVerdict};
+use crate::devtools::{colorblind, hud, layout_debug};
+use crate::devtools::inspector::Inspector;
+use crate::state::{frame, keymap, timers};
+use crate::task::{self, TaskBridge};
+use crate::devtools::screenshot::{self, Capture};
 
 // =============================================================================
 // Types
 // =============================================================================
 
+/// Exit code sent to TS when the engine thread itself panicked.
+pub(super) const ENGINE_PANIC_EXIT_CODE: u8 = 101;
+
+/// The terminal an engine drives.
+pub(super) enum EngineTerminal {
+    /// The process's: stdin, stdout and the controlling TTY.
+    Process,
+    /// A session's connection.
+    Session(SessionIo),
+}
+
+/// A started engine thread, as handed back to its owner.
+pub(super) struct Spawned {
+    pub running: Arc<AtomicBool>,
+    pub tx: mpsc::Sender<StdinMessage>,
+    pub waker: Waker,
+    pub handle: JoinHandle<()>,
+}
+
+/// Spawn an engine thread named `name` for `target`.
+///
+/// Returns once the terminal is set up and the engine is listening, or with
+/// the error that stopped it.
+pub(super) fn spawn_engine(name: &'static str, buf: &'static SharedBuffer, target: EngineTerminal) -> io::Result<Spawned> {
+    let running = Arc::new(AtomicBool::new(true));
+    let running_clone = running.clone();
+    let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+
+    let handle = thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            match panic::catch_unwind(AssertUnwindSafe(|| run_engine(buf, running_clone.clone(), target, ready_tx))) {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => eprintln!("[{}] Error: {}", name, e),
+                Err(_) => {
+                    // The panic hook already restored the terminal; let TS shut down
+                    running_clone.store(false, Ordering::SeqCst);
+                    buf.push_exit_event(ENGINE_PANIC_EXIT_CODE);
+                }
+            }
+        })?;
+
+    // The sender is dropped unsent if setup fails; the thread is then
+    // finishing, and once it has nothing reads `buf`
+    let Ok((tx, waker)) = ready_rx.recv() else {
+        let _ = handle.join();
+        return Err(io::Error::other(format!("{} failed to start", name)));
+    };
+
+    Ok(Spawned { running, tx, waker, handle })
+}
+
+/// Ask the engine behind `tx` to start or stop a cast recording, and wait
+/// for the outcome.
+pub(super) fn request_cast(tx: &mpsc::Sender<StdinMessage>, path: Option<&Path>) -> io::Result<()> {
+    let (reply_tx, reply_rx) = mpsc::channel();
+    tx.send(StdinMessage::RecordCast(path.map(Path::to_path_buf), reply_tx))
+        .map_err(|_| io::Error::other("engine stopped"))?;
+    reply_rx.recv().map_err(|_| io::Error::other("engine stopped"))?
+}
+
 /// Result of the framebuffer derived computation.
 /// Must be Clone + PartialEq for spark-signals derived.
 #[derive(Debug, Clone, PartialEq)]
@@ -66,6 +151,8 @@ struct FrameBufferResult {
     buffer: FrameBuffer,
     hit_regions: Vec<HitRegion>,
     terminal_size: (u16, u16),
+    /// Inline/append: rows of each region (empty in fullscreen)
+    regions: Vec<RegionBand>,
 }
 
 // =============================================================================
@@ -76,7 +163,11 @@ struct FrameBufferResult {
 ///
 /// Owns all Rust-side state and runs the reactive pipeline.
 pub struct Engine {
+    buf: &'static SharedBuffer,
     running: Arc<AtomicBool>,
+    tx: mpsc::Sender<StdinMessage>,
+    waker: Waker,
+    handle: Option<JoinHandle<()>>,
 }
 
 impl Engine {
@@ -88,25 +179,55 @@ impl Engine {
     /// 3. Creates the reactive graph (generation → layout → framebuffer → render)
     /// 4. Blocks on channel events — increments generation on input or wake
     ///
-    /// Returns an Engine handle for shutdown.
+    /// Returns an Engine handle once the engine is listening.
     pub fn start(buf: &'static SharedBuffer) -> io::Result<Self> {
-        let running = Arc::new(AtomicBool::new(true));
-        let running_clone = running.clone();
-
-        thread::Builder::new()
-            .name("spark-engine".to_string())
-            .spawn(move || {
-                if let Err(e) = run_engine(buf, running_clone) {
-                    eprintln!("[spark-engine] Error: {}", e);
-                }
-            })?;
+        let spawned = spawn_engine("spark-engine", buf, EngineTerminal::Process)?;
+        Ok(Self {
+            buf,
+            running: spawned.running,
+            tx: spawned.tx,
+            waker: spawned.waker,
+            handle: Some(spawned.handle),
+        })
+    }
+
+    /// Tell the engine its SharedBuffer changed (`spark_wake`).
+    pub fn wake(&self) {
+        self.buf.set_wake_flag();
+        self.waker.wake();
+    }
+
+    /// Record the terminal output to an asciinema cast at `path`, or stop
+    /// recording with `None`.
+    pub fn record_cast(&self, path: Option<&Path>) -> io::Result<()> {
+        request_cast(&self.tx, path)
+    }
+
+    /// Queue a title change, bell or notification (see `terminal::osc`).
+    pub fn terminal(&self, command: osc::Command) {
+        let _ = self.tx.send(StdinMessage::Terminal(command));
+    }
 
-        Ok(Self { running })
+    /// Freeze `lines` into history above the live region, with the next
+    /// frame. Append mode only; other modes drop them.
+    pub fn println(&self, lines: Vec<StyledLine>) {
+        let _ = self.tx.send(StdinMessage::History(lines));
     }
 
     /// Stop the engine gracefully.
     pub fn stop(&self) {
         self.running.store(false, Ordering::SeqCst);
+        // Wakes the engine thread if it's waiting for input
+        let _ = self.tx.send(StdinMessage::Closed);
+    }
+
+    /// Wait for the engine thread to finish and the terminal to be
+    /// restored (after `stop`, or once stdin closes). After this nothing
+    /// reads the SharedBuffer.
+    pub fn join(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
     }
 
     /// Check if the engine is running.
@@ -126,12 +247,29 @@ impl Drop for Engine {
 // =============================================================================
 
 /// Main engine function. Runs on the engine thread.
-fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Result<()> {
+pub(super) fn run_engine(
+    buf: &'static SharedBuffer,
+    running: Arc<AtomicBool>,
+    target: EngineTerminal,
+    ready: mpsc::SyncSender<(mpsc::Sender<StdinMessage>, Waker)>,
+) -> io::Result<()> {
     // 1. Setup terminal based on render mode
     let render_mode = buf.render_mode();
-    let mut terminal = TerminalSetup::new();
     let is_fullscreen = render_mode == RenderMode::Diff;
 
+    // A session's frames go to its connection; its input is read from there
+    let (mut terminal, session) = match target {
+        EngineTerminal::Process => {
+            // Under tmux/screen: sync markers only where the multiplexer honours them
+            ansi::set_sync_output(passthrough::multiplexer().sync_output());
+            (TerminalSetup::new(), None)
+        }
+        EngineTerminal::Session(io) => {
+            set_terminal_output(Some(io.output));
+            (TerminalSetup::remote(), Some((io.input, io.close_input, (io.width, io.height))))
+        }
+    };
+
     if is_fullscreen {
         terminal.enter_fullscreen()?;
     } else {
@@ -142,22 +280,43 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
     let (tx, rx) = mpsc::channel();
 
     // 3. Start stdin reader (sends Data/Closed messages)
-    let stdin_reader = StdinReader::spawn(tx.clone())?;
-
-    // 4. Start wake watcher (sends Wake messages when TS writes to SharedBuffer)
-    let _wake_watcher = WakeWatcher::spawn(buf, tx.clone(), running.clone());
-
-    // 5. Start resize watcher (sends Resize messages on SIGWINCH)
-    let _resize_watcher = ResizeWatcher::spawn(tx, running.clone());
+    let (stdin_reader, session_size) = match session {
+        Some((input, close, size)) => (StdinReader::spawn_from(input, close, tx.clone())?, Some(size)),
+        None => (StdinReader::spawn(tx.clone())?, None),
+    };
+
+    // 4. Start wake watcher (sends Wake messages when TS writes to SharedBuffer).
+    // Timers on this thread re-arm it; the owner's handle wakes it for TS.
+    let wake_watcher = WakeWatcher::spawn(buf, tx.clone(), running.clone());
+    wake::install(wake_watcher.waker());
+
+    // task::spawn on this thread reports results through the same channel (TaskDone)
+    let _task_bridge = TaskBridge::install(tx.clone());
+
+    // 5. Start resize watcher (sends Resize messages on SIGWINCH). A session's
+    // host sends them instead.
+    let handoff = (tx.clone(), wake_watcher.waker());
+    let _resize_watcher = match session_size {
+        Some(_) => None,
+        None => Some(ResizeWatcher::spawn(tx, running.clone())),
+    };
 
     // 6. Initialize input system state
     let mut parser = InputParser::new();
     let mut focus = FocusManager::new();
     let mut editor = TextEditor::new();
     let mut scroll = ScrollManager::new();
+    let mut chord = NavChord::default();
+    let mut watchdog = Watchdog::new();
+    let mut pacer = FramePacer::new();
+
+    // Publish terminal capabilities and ask for the cell size (replies arrive as input)
+    let mut capabilities = CapabilityMonitor::new();
+    capabilities.refresh(buf, is_fullscreen, &mut TerminalOut);
 
     // Get initial terminal size (prefer ioctl over SharedBuffer for accuracy)
-    let (init_tw, init_th) = get_terminal_size()
+    let (init_tw, init_th) = session_size
+        .or_else(get_terminal_size)
         .unwrap_or((buf.terminal_width() as u16, buf.terminal_height() as u16));
 
     // Cursor blink is handled by TS pulse() signal - no Rust-side timer needed
@@ -184,6 +343,7 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
     let gen_for_layout = generation.clone();
     let tw_for_layout = terminal_width.clone();
     let th_for_layout = terminal_height.clone();
+    let last_env = Cell::new(LayoutEnv::read(buf));
     let layout_derived = derived(move || {
         let layout_start = Instant::now();
 
@@ -212,17 +372,25 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
         // - Any node has dirty flags
         let mut needs_layout = generation_value <= 1 || terminal_resized;
 
+        // Header settings layout reads: the cell aspect (cell size report,
+        // user setting), the inline max rows % roots fill, …
+        if layout_env_changed(buf, &last_env) {
+            needs_layout = true;
+        }
+
+        // Layout flags are left for compute_layout: they say what to lay out again
         for i in 0..node_count {
             let flags = buf.dirty_flags(i);
             if flags & (DIRTY_LAYOUT | DIRTY_TEXT | DIRTY_HIERARCHY) != 0 {
                 needs_layout = true;
+            } else if flags != 0 {
+                buf.clear_dirty(i);
             }
-            buf.clear_dirty(i);
         }
 
         // Layout computation
         if needs_layout && node_count > 0 {
-            layout::compute_layout(buf);
+            contain(buf, ErrorSource::Layout, None, || layout::compute_layout(buf));
         }
 
         // Record layout timing
@@ -239,8 +407,18 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
         generation_value
     });
 
+    // Last screenshot taken, shown in the toast while H_SCREENSHOT_TOAST is set
+    let last_capture: Rc<RefCell<Option<Capture>>> = Rc::new(RefCell::new(None));
+
     // Framebuffer derived: depends on layout, builds 2D cell grid.
     let layout_d = layout_derived.clone();
+    let capture_for_fb = last_capture.clone();
+    // Snapshots of `cache` boxes, reused until their subtree changes
+    let render_cache = RefCell::new(framebuffer::RenderCache::new());
+    let perf_hud = RefCell::new(hud::PerfHud::new());
+    // Component inspector selection, moved by input on the loop side
+    let inspector: Rc<RefCell<Inspector>> = Rc::new(RefCell::new(Inspector::new()));
+    let inspector_for_fb = inspector.clone();
     let fb_derived = derived(move || {
         let fb_start = Instant::now();
 
@@ -255,19 +433,55 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
         //   would overflow the terminal if content > viewport, causing character
         //   wrapping that corrupts all row positions in the diff renderer.
         //
-        // INLINE/APPEND: framebuffer = root's computed size.
-        //   Content determines height; no viewport constraint.
+        // INLINE/APPEND: framebuffer = the roots' computed size, their
+        //   regions stacked. Content determines height; no viewport constraint,
+        //   but inline keeps to its configured min/max rows (content past the
+        //   max is clipped — a root sized in % fills the max and can scroll).
+        let regions = match buf.render_mode() {
+            RenderMode::Diff => Vec::new(),
+            _ => framebuffer::region_bands(buf),
+        };
         let (tw, th) = match buf.render_mode() {
             RenderMode::Diff => {
                 (buf.terminal_width().max(1) as u16, buf.terminal_height().max(1) as u16)
             }
-            _ => {
-                (buf.computed_width(0).max(1.0) as u16, buf.computed_height(0).max(1.0) as u16)
+            RenderMode::Inline => {
+                let width = regions.iter().map(|band| band.width).max().unwrap_or(0);
+                let content = regions.last().map_or(0, |band| band.y + band.height);
+                let height = InlineRenderer::fit_height(content, buf.inline_min_height(), buf.inline_max_height());
+                (width.max(1), height)
+            }
+            RenderMode::Append => {
+                let width = regions.iter().map(|band| band.width).max().unwrap_or(0);
+                let height = regions.last().map_or(0, |band| band.y + band.height);
+                (width.max(1), height.max(1))
             }
         };
 
         // Build framebuffer from SharedBuffer
-        let (buffer, hit_regions) = framebuffer::compute_framebuffer(buf, tw, th);
+        let (mut buffer, hit_regions) = framebuffer::compute_framebuffer_cached(buf, tw, th, &mut render_cache.borrow_mut());
+
+        // Dev overlay: outlines, padding and gaps, under everything else drawn here
+        layout_debug::draw(&mut buffer, buf);
+
+        // Warning bar while the watchdog is holding back an update storm
+        watchdog::draw_overlay(&mut buffer, buf);
+
+        // Dev overlay: show the frame as seen with a color-vision deficiency
+        colorblind::apply(&mut buffer, buf.color_vision());
+
+        // Dev overlay: component tree, selected node highlighted
+        inspector_for_fb.borrow_mut().draw(&mut buffer, buf, &hit_regions);
+
+        // Dev overlay: frame stats in the top-right corner
+        hud::draw(&mut buffer, buf, &mut perf_hud.borrow_mut(), fb_start);
+
+        // Screenshot confirmation toast
+        if buf.screenshot_toast()
+            && let Some(capture) = capture_for_fb.borrow().as_ref()
+        {
+            screenshot::draw_toast(&mut buffer, capture);
+        }
 
         // Record framebuffer timing
         let fb_us = fb_start.elapsed().as_micros() as u32;
@@ -277,15 +491,34 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
             buffer,
             hit_regions,
             terminal_size: (tw, th),
+            regions,
         }
     });
 
+    // Input-to-paint latency: stamped on read, closed by the first frame that changes the screen
+    let latency: Rc<RefCell<LatencyTracker>> = Rc::new(RefCell::new(LatencyTracker::new()));
+    let latency_for_effect = latency.clone();
+
     // ONE render effect: fires when framebuffer derived changes.
+    let fb_for_capture = fb_derived.clone();
     let running_for_effect = running.clone();
     let mouse_for_effect = mouse_mgr.clone();
     let frame_start_for_effect = frame_start.clone();
+    // Bumped to make the next render a full repaint (a cast recording started)
+    let repaint: Signal<u32> = signal(0);
+    let repaint_for_effect = repaint.clone();
+    let mut last_repaint = 0;
+    // Lines waiting to go into append-mode history, announced by a bump
+    let history_lines: Rc<RefCell<Vec<StyledLine>>> = Rc::new(RefCell::new(Vec::new()));
+    let history_for_effect = history_lines.clone();
+    let history: Signal<u32> = signal(0);
+    let history_signal_for_effect = history.clone();
     let mut diff_renderer = DiffRenderer::new();
-    let mut inline_renderer = InlineRenderer::new();
+    // Shared so cleanup can move the cursor out of the last frame
+    let inline_renderer = Rc::new(RefCell::new(InlineRenderer::new()));
+    let append_renderer = Rc::new(RefCell::new(AppendRenderer::new()));
+    let inline_for_effect = inline_renderer.clone();
+    let append_for_effect = append_renderer.clone();
     let _stop_effect = effect(move || {
         let render_start = Instant::now();
 
@@ -295,20 +528,64 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
 
         // Read framebuffer (creates reactive dependency)
         let result = fb_derived.get();
+        let mut inline_renderer = inline_for_effect.borrow_mut();
+        let mut append_renderer = append_for_effect.borrow_mut();
+
+        let repaint_seq = repaint_for_effect.get();
+        if repaint_seq != last_repaint {
+            last_repaint = repaint_seq;
+            diff_renderer.invalidate();
+            inline_renderer.invalidate();
+        }
+        history_signal_for_effect.get();
+        let history_lines = std::mem::take(&mut *history_for_effect.borrow_mut());
 
         // Update hit grid (side effect)
         let (tw, th) = result.terminal_size;
         let mut mouse = mouse_for_effect.borrow_mut();
         mouse.hit_grid.resize(tw, th);
         for hr in &result.hit_regions {
-            mouse.hit_grid.fill_rect(hr.x, hr.y, hr.width, hr.height, hr.component_index);
+            mouse.hit_grid.fill_region(hr, buf);
         }
-
-        // Render based on mode
-        match buf.render_mode() {
-            RenderMode::Inline => { let _ = inline_renderer.render(&result.buffer); }
-            RenderMode::Append => { /* TODO: append_renderer */ }
-            RenderMode::Diff => { let _ = diff_renderer.render(&result.buffer); }
+        mouse.refresh_hover_payload(buf);
+
+        // Render based on mode. Append counts every cell of the frame.
+        let bytes_before = output::bytes_written();
+        let all_cells = tw as u32 * th as u32;
+        // Queued title/bell/notifications go out in the frame's write
+        if osc::has_pending() {
+            match buf.render_mode() {
+                RenderMode::Inline => osc::drain_into(inline_renderer.output_mut()),
+                RenderMode::Append => osc::drain_into(append_renderer.output_mut()),
+                RenderMode::Diff => osc::drain_into(diff_renderer.output_mut()),
+            }
+        }
+        let (painted, cells) = match buf.render_mode() {
+            RenderMode::Inline => {
+                inline_renderer.resize(buf.terminal_width() as u16, buf.terminal_height() as u16);
+                let painted = inline_renderer.render_regions(&result.buffer, &result.regions);
+                (painted.is_ok(), inline_renderer.cells_written())
+            }
+            RenderMode::Append => {
+                // Rows written at the old width may have been rewrapped since
+                append_renderer.resize(buf.terminal_width() as u16, buf.terminal_height() as u16);
+                let painted = append_renderer.render_regions(&history_lines, &result.buffer, &result.regions);
+                (painted.is_ok(), all_cells)
+            }
+            RenderMode::Diff => {
+                let painted = diff_renderer.render(&result.buffer).unwrap_or(false);
+                (painted, diff_renderer.cells_written())
+            }
+        };
+        let bytes = output::bytes_written() - bytes_before;
+        buf.set_frame_output(cells, bytes.min(u32::MAX as u64) as u32);
+
+        // Close the pending input-to-paint sample
+        if painted {
+            let mut latency = latency_for_effect.borrow_mut();
+            if latency.painted(Instant::now()).is_some() {
+                latency.publish(buf);
+            }
         }
 
         // Record render timing
@@ -328,6 +605,10 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
         buf.increment_render_count();
     });
 
+    // Running: the first frame is out and the engine is sized. Hand the
+    // owner its channel and waker.
+    let _ = ready.send(handoff);
+
     // Clone signals for event loop
     let tw_for_loop = terminal_width.clone();
     let th_for_loop = terminal_height.clone();
@@ -346,41 +627,139 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
     //
     // The engine thread blocks on channel.recv(). It wakes IMMEDIATELY when
     // either stdin data arrives OR the wake watcher detects TS wrote props.
-    // No polling, no timers. Cursor blink is driven by TS pulse() signal.
+    // No polling. Cursor blink is driven by TS pulse() signal; component
+    // timers (state::timers) arrive as messages from the wake thread.
+    // Queued idle work (idle_compute) runs only in the gap before blocking.
 
     while running.load(Ordering::SeqCst) {
-        // Block indefinitely until input or wake
-        let msg = rx.recv();
+        // Nothing waiting → run idle jobs, handing back as soon as a message arrives
+        let mut arrived = None;
+        idle::run_idle(|| {
+            arrived = rx.try_recv().ok();
+            arrived.is_some()
+        });
+
+        // Block indefinitely until input or wake. While the watchdog is
+        // holding back a storm, wait at most STORM_GAP for wakes to stop;
+        // while the batch window holds back a frame, until it's due.
+        let msg = if let Some(msg) = arrived {
+            Ok(msg)
+        } else if watchdog.is_tripped() {
+            match rx.recv_timeout(watchdog::STORM_GAP) {
+                Ok(msg) => Ok(msg),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    // Storm settled → render the final state once
+                    watchdog.settle(buf);
+                    next_frame(buf, &generation);
+                    continue;
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        } else if let Some(due) = pacer.due(buf.batch_window()) {
+            // A paced frame is pending: wait for more changes until it's due
+            match rx.recv_timeout(due.saturating_duration_since(Instant::now())) {
+                Ok(msg) => Ok(msg),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    pacer.start(Instant::now());
+                    next_frame(buf, &generation);
+                    continue;
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            rx.recv().map_err(|_| ())
+        };
+
+        // Set when the screenshot binding is pressed; handled once input settles
+        let mut screenshot_requested = false;
+
+        // Strings read from the text pool stay put until the message is handled
+        let _pool = buf.lock_text_pool();
 
         match msg {
-            Ok(StdinMessage::Data(data)) => {
+            Ok(StdinMessage::Data(data, read_at)) => {
                 // Parse and dispatch input
                 let parsed = parser.parse(&data);
                 for event in parsed {
+                    // Only deliberate input is sampled — bare motion rarely repaints
+                    let deliberate = match &event {
+                        ParsedEvent::Key(_) | ParsedEvent::Paste(_) => true,
+                        ParsedEvent::Mouse(mouse) => mouse.kind != MouseKind::Move,
+                        _ => false,
+                    };
+                    if deliberate {
+                        latency.borrow_mut().input(read_at);
+                    }
                     match event {
+                        ParsedEvent::Key(key) if keyboard::is_screenshot_key(buf, &key) => {
+                            screenshot_requested = true;
+                        }
+                        ParsedEvent::Key(key) if keyboard::is_perf_hud_key(buf, &key) => {
+                            buf.set_perf_hud(!buf.perf_hud());
+                        }
+                        ParsedEvent::Key(key) if keyboard::is_inspector_key(buf, &key) => {
+                            buf.set_inspector(!buf.inspector());
+                        }
+                        // The open inspector keeps keys and mouse to itself
+                        ParsedEvent::Key(key) if buf.inspector() => {
+                            inspector.borrow_mut().key(buf, &key);
+                        }
+                        ParsedEvent::Mouse(mouse) if buf.inspector() => {
+                            if let MouseKind::Press(_) = mouse.kind {
+                                let hit = mouse_mgr.borrow().hit_grid.hit_test(mouse.x, mouse.y);
+                                inspector.borrow_mut().click(mouse.x, mouse.y, hit);
+                            }
+                        }
                         ParsedEvent::Key(key) => {
-                            keyboard::dispatch_key(
-                                buf, &mut focus,
-                                &mut editor, &mut scroll, &key,
-                            );
+                            let focused = focus.focused();
+                            contain(buf, ErrorSource::Key, focused, || {
+                                keyboard::dispatch_key(
+                                    buf, &mut focus,
+                                    &mut editor, &mut scroll, &mut chord, &key,
+                                )
+                            });
                         }
                         ParsedEvent::Mouse(mouse) => {
-                            mouse_mgr.borrow_mut().dispatch(
-                                buf, &mut focus,
-                                &mut scroll, &mouse,
-                            );
+                            contain(buf, ErrorSource::Mouse, None, || {
+                                mouse_mgr.borrow_mut().dispatch(
+                                    buf, &mut focus,
+                                    &mut scroll, &mouse,
+                                )
+                            });
                         }
                         ParsedEvent::Resize(w, h) => {
                             // Escape-sequence based resize (some terminals)
                             mouse_mgr.borrow_mut().resize(w, h);
                             tw_for_loop.set(w);
                             th_for_loop.set(h);
+                            cast::resize(w, h);
                             // Push resize event to TS
                             buf.push_resize_event(w, h);
                         }
+                        ParsedEvent::FocusGained => {
+                            // Possibly a different terminal now (tmux reattach)
+                            capabilities.refresh(buf, is_fullscreen, &mut TerminalOut);
+                        }
+                        ParsedEvent::CellPixels(w, h) => {
+                            capabilities.cell_pixels(buf, w, h);
+                        }
+                        ParsedEvent::WindowPixels(w, h) => {
+                            capabilities.window_pixels(buf, w, h);
+                        }
+                        ParsedEvent::ModeReport(mode, state) => {
+                            capabilities.mode_report(mode, state);
+                        }
+                        ParsedEvent::ColorReport(color, rgb) => {
+                            capabilities.terminal_color(buf, color, rgb);
+                        }
+                        ParsedEvent::ColorSchemeChanged => {
+                            capabilities.query_palette(&mut TerminalOut);
+                        }
                         _ => {}
                     }
                 }
+                // SGR-pixels on/off follows support and the cell size
+                parser.set_pixel_mouse(capabilities.pixel_mouse(&mut TerminalOut));
 
                 // Check for exit event (Ctrl+C)
                 if buf.exit_requested() {
@@ -388,7 +767,7 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
                 }
 
                 // Input changed state → increment generation → reactive propagation
-                generation.set(generation.get() + 1);
+                request_frame(buf, &generation, &mut pacer);
             }
             Ok(StdinMessage::Resize(w, h)) => {
                 // SIGWINCH detected by ResizeWatcher
@@ -396,17 +775,67 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
                 mouse_mgr.borrow_mut().resize(w, h);
                 tw_for_loop.set(w);
                 th_for_loop.set(h);
+                cast::resize(w, h);
                 // Push resize event to TS (optional - user callback)
                 buf.push_resize_event(w, h);
+                // Font zoom or a reattach changes what the terminal reports
+                capabilities.refresh(buf, is_fullscreen, &mut TerminalOut);
                 // Signal change auto-triggers reactive graph, but increment generation too
-                generation.set(generation.get() + 1);
+                request_frame(buf, &generation, &mut pacer);
             }
             Ok(StdinMessage::Wake) => {
+                // Timers of components TS just released never fire
+                timers::sweep(buf);
+
+                // Runaway update cycle → drop the wake instead of spinning
+                if watchdog.on_wake(buf) == WakeVerdict::Suppress {
+                    continue;
+                }
+
+                // An overlay TS just opened (or closed) takes (or returns) focus
+                focus.track_traps(buf);
+                focus.enforce_trap(buf);
+
                 // Capture frame start for timing measurement
                 *frame_start.borrow_mut() = Some(Instant::now());
 
-                // TS wrote props to SharedBuffer → increment generation → reactive propagation
-                generation.set(generation.get() + 1);
+                // TS wrote props to SharedBuffer → increment generation → reactive propagation.
+                // A wake the pacer folds into a later frame isn't a propagation of its own.
+                if request_frame(buf, &generation, &mut pacer) {
+                    watchdog.after_propagation(buf);
+                }
+            }
+            Ok(StdinMessage::TaskDone(id, value)) => {
+                // Apply the result (sets signals) → propagate like any other change.
+                // A panicking apply may have set some signals already.
+                if contain(buf, ErrorSource::Task, None, || task::complete(id, value)).unwrap_or(true) {
+                    request_frame(buf, &generation, &mut pacer);
+                }
+            }
+            Ok(StdinMessage::Timers) => {
+                // Run every due callback, then propagate once for the batch
+                if timers::fire(buf, Instant::now()) {
+                    request_frame(buf, &generation, &mut pacer);
+                }
+            }
+            Ok(StdinMessage::RecordCast(path, reply)) => {
+                let result = match &path {
+                    Some(path) => cast::record_cast(path, tw_for_loop.get(), th_for_loop.get()),
+                    None => cast::stop_cast(),
+                };
+                // Open the recording on the whole screen, not a diff
+                if path.is_some() && result.is_ok() {
+                    repaint.set(repaint.get() + 1);
+                }
+                let _ = reply.send(result);
+            }
+            Ok(StdinMessage::Terminal(command)) => command.run(),
+            Ok(StdinMessage::History(lines)) => {
+                // Written by the render effect, ahead of its next frame
+                if buf.render_mode() == RenderMode::Append {
+                    history_lines.borrow_mut().extend(lines);
+                    history.set(history.get() + 1);
+                }
             }
             Ok(StdinMessage::Closed) => break,
             Err(_) => break, // Channel disconnected
@@ -417,10 +846,29 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
             let pending = parser.flush_pending();
             for event in pending {
                 if let ParsedEvent::Key(key) = event {
-                    keyboard::dispatch_key(
-                        buf, &mut focus,
-                        &mut editor, &mut scroll, &key,
-                    );
+                    if keyboard::is_screenshot_key(buf, &key) {
+                        screenshot_requested = true;
+                        continue;
+                    }
+                    if keyboard::is_perf_hud_key(buf, &key) {
+                        buf.set_perf_hud(!buf.perf_hud());
+                        continue;
+                    }
+                    if keyboard::is_inspector_key(buf, &key) {
+                        buf.set_inspector(!buf.inspector());
+                        continue;
+                    }
+                    if buf.inspector() {
+                        inspector.borrow_mut().key(buf, &key);
+                        continue;
+                    }
+                    let focused = focus.focused();
+                    contain(buf, ErrorSource::Key, focused, || {
+                        keyboard::dispatch_key(
+                            buf, &mut focus,
+                            &mut editor, &mut scroll, &mut chord, &key,
+                        )
+                    });
                 }
             }
 
@@ -429,17 +877,148 @@ fn run_engine(buf: &'static SharedBuffer, running: Arc<AtomicBool>) -> io::Resul
                 running.store(false, Ordering::SeqCst);
             }
 
-            generation.set(generation.get() + 1);
+            request_frame(buf, &generation, &mut pacer);
+        }
+
+        if screenshot_requested {
+            take_screenshot(buf, &generation, &fb_for_capture, &last_capture);
         }
+
+        // No frame went out to carry them
+        let _ = osc::flush();
     }
 
     // Cleanup
+    running.store(false, Ordering::SeqCst);
+    timers::clear_all();
+    frame::clear_all();
+    keymap::clear_all();
     drop(stdin_reader);
+    osc::reset()?;
     if is_fullscreen {
         terminal.exit_fullscreen()?;
     } else {
+        // What the shell prints next goes below the frame, not over it
+        match buf.render_mode() {
+            RenderMode::Append => append_renderer.borrow_mut().leave()?,
+            _ => inline_renderer.borrow_mut().leave()?,
+        }
         terminal.exit_inline()?;
     }
+    // After the terminal teardown, so playback ends on the restored screen
+    cast::stop_cast()?;
+    // A session's writer drains what's queued and finishes
+    set_terminal_output(None);
 
     Ok(())
 }
+
+/// Whether the header settings every node's layout reads changed since
+/// `last`, which is updated.
+fn layout_env_changed(buf: &SharedBuffer, last: &Cell<LayoutEnv>) -> bool {
+    let env = LayoutEnv::read(buf);
+    env != last.replace(env)
+}
+
+/// Start a frame: run the `on_frame` callbacks, then propagate. One batch,
+/// so everything they set reaches the render effect in the same frame.
+fn next_frame(buf: &SharedBuffer, generation: &Signal<u64>) {
+    let now = Instant::now();
+    // TS can't move pool strings while layout and the framebuffer read them
+    let _pool = buf.lock_text_pool();
+    batch(|| {
+        frame::run(buf, now);
+        generation.set(generation.get() + 1);
+    });
+}
+
+/// Start a frame for a change, unless the batch window holds it back for
+/// a pending one. Returns whether the frame ran.
+fn request_frame(buf: &SharedBuffer, generation: &Signal<u64>, pacer: &mut FramePacer) -> bool {
+    let now = pacer.request(buf.batch_window(), Instant::now());
+    if now {
+        next_frame(buf, generation);
+    }
+    now
+}
+
+/// Run `f`, turning a panic into an `Error` event so one bad callback
+/// doesn't take the engine down with it.
+fn contain<R>(
+    buf: &SharedBuffer,
+    source: ErrorSource,
+    component: Option<usize>,
+    f: impl FnOnce() -> R,
+) -> Option<R> {
+    match terminal::contained(f) {
+        Ok(value) => Some(value),
+        Err(_) => {
+            buf.push_error_event(component, source);
+            None
+        }
+    }
+}
+
+/// Save the frame on screen, then raise the toast and tell TS.
+fn take_screenshot(
+    buf: &SharedBuffer,
+    generation: &Signal<u64>,
+    frame: &Derived<FrameBufferResult>,
+    last_capture: &RefCell<Option<Capture>>,
+) {
+    // Drop a toast still showing from the previous shot so it isn't captured
+    if buf.screenshot_toast() {
+        buf.set_screenshot_toast(false);
+        next_frame(buf, generation);
+    }
+
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let dir = std::env::current_dir().unwrap_or_else(|_| ".".into());
+    let flags = buf.screenshot_flags();
+    let image = flags & (SCREENSHOT_IMAGE | SCREENSHOT_COPY_IMAGE) != 0;
+    let capture = screenshot::capture(&frame.get().buffer, &dir, timestamp_ms, image);
+
+    if let Capture::Saved { path, image } = &capture {
+        let png_path = path.with_extension("png");
+        let copy_image = *image && flags & SCREENSHOT_COPY_IMAGE != 0;
+        let mut out = TerminalOut;
+        if copy_image
+            && screenshot::supports_image_clipboard()
+            && let Ok(png) = std::fs::read(&png_path)
+        {
+            let _ = screenshot::copy_image_to_clipboard(&mut out, &png);
+        } else if copy_image {
+            let _ = screenshot::copy_to_clipboard(&mut out, &png_path.to_string_lossy());
+        } else if flags & SCREENSHOT_COPY_PATH != 0 {
+            let _ = screenshot::copy_to_clipboard(&mut out, &path.to_string_lossy());
+        }
+    }
+
+    buf.push_screenshot_event(timestamp_ms, matches!(capture, Capture::Saved { .. }));
+    *last_capture.borrow_mut() = Some(capture);
+    buf.set_screenshot_toast(true);
+    next_frame(buf, generation);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_buffer::test_support::TestBuffer;
+
+    #[test]
+    fn test_layout_settings_changes_lay_out_again() {
+        let buf = TestBuffer::new(1, 64);
+        let last = Cell::new(LayoutEnv::read(&buf));
+        assert!(!layout_env_changed(&buf, &last));
+
+        buf.set_inline_height(0, 10);
+        assert!(layout_env_changed(&buf, &last));
+        assert!(!layout_env_changed(&buf, &last));
+
+        buf.set_cell_aspect(2.5);
+        assert!(layout_env_changed(&buf, &last));
+    }
+}
@@ -4,11 +4,39 @@
 //! Kitty keyboard protocol, and other terminal configuration.
 //!
 //! All terminal protocol writes are done via ANSI escape sequences.
+//!
+//! The terminal is restored however the process goes down. Besides the
+//! normal exit path and `Drop`, entering a mode installs (once):
+//!
+//! - a panic hook — an uncontained panic on any thread restores first, then
+//!   prints its message on the normal screen. Panics the engine recovers
+//!   from (run through [`contained`]) leave the terminal alone;
+//! - SIGTERM / SIGHUP handlers — restore, then hand the signal to whatever
+//!   handled it before (the host runtime), or die of it as usual.
+//!
+//! Both paths write a fixed restore sequence and reset termios with plain
+//! `write`/`tcsetattr`, which are safe inside a signal handler.
+//!
+//! Window title, bell and notifications are queued in [`osc`].
+//!
+//! On Windows, raw mode is a pair of console modes: VT processing on output,
+//! VT input (keys and mouse arrive as the same escape sequences as on unix)
+//! plus window events on input. Closing the console window, logoff and
+//! shutdown restore through a console control handler instead of signals.
 
+use std::cell::Cell;
 use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU8, Ordering};
+#[cfg(windows)]
+use std::sync::atomic::{AtomicBool, AtomicU32};
+use std::sync::Once;
+use std::thread;
 use crate::renderer::ansi;
 use crate::renderer::OutputBuffer;
 
+pub mod osc;
+
 /// Terminal setup/teardown handle.
 pub struct TerminalSetup {
     is_fullscreen: bool,
@@ -17,6 +45,9 @@ pub struct TerminalSetup {
     kitty_keyboard: bool,
     bracketed_paste: bool,
     focus_reporting: bool,
+    color_scheme_reports: bool,
+    /// A session's terminal at the far end of its output stream
+    remote: bool,
 }
 
 impl TerminalSetup {
@@ -28,9 +59,19 @@ impl TerminalSetup {
             kitty_keyboard: false,
             bracketed_paste: false,
             focus_reporting: false,
+            color_scheme_reports: false,
+            remote: false,
         }
     }
 
+    /// Setup for a session's terminal (see `pipeline::session`). Only the
+    /// escape sequences go out, through the session's stream: the client's
+    /// pty is already raw, and the process terminal and its restore hooks
+    /// are left alone.
+    pub fn remote() -> Self {
+        Self { remote: true, ..Self::new() }
+    }
+
     /// Enter fullscreen mode with all terminal features enabled.
     pub fn enter_fullscreen(&mut self) -> io::Result<()> {
         let mut out = OutputBuffer::new();
@@ -47,11 +88,15 @@ impl TerminalSetup {
         // Clear screen
         ansi::clear_screen(&mut out)?;
 
-        // Enable SGR mouse tracking
+        // Enable SGR mouse tracking. Terminals use the last encoding they
+        // know: SGR, else urxvt, else X10. SGR-pixels (1016) is negotiated
+        // later, once the cell size is known (see capabilities).
         out.write_str("\x1b[?1000h"); // Enable mouse clicks
         out.write_str("\x1b[?1002h"); // Enable mouse motion
         out.write_str("\x1b[?1003h"); // Enable all mouse tracking
+        out.write_str("\x1b[?1015h"); // urxvt mouse protocol (fallback)
         out.write_str("\x1b[?1006h"); // SGR mouse protocol
+        out.write_str("\x1b[?1007h"); // Alternate scroll: wheel → arrows if tracking is lost
         self.mouse_enabled = true;
 
         // Enable Kitty keyboard protocol (progressive enhancement level 1)
@@ -66,11 +111,16 @@ impl TerminalSetup {
         out.write_str("\x1b[?1004h");
         self.focus_reporting = true;
 
+        // Enable color scheme change notifications (light/dark switches)
+        out.write_str("\x1b[?2031h");
+        self.color_scheme_reports = true;
+
         // Synchronized output start
-        out.write_str("\x1b[?2026h");
+        ansi::begin_sync(&mut out)?;
 
         out.flush_stdout()?;
         self.is_fullscreen = true;
+        self.set_active_mode(MODE_FULLSCREEN);
         Ok(())
     }
 
@@ -96,15 +146,27 @@ impl TerminalSetup {
         out.write_str("\x1b[?2004h");
         self.bracketed_paste = true;
 
+        // Enable color scheme change notifications (light/dark switches)
+        out.write_str("\x1b[?2031h");
+        self.color_scheme_reports = true;
+
         out.flush_stdout()?;
         // Note: is_fullscreen stays false for inline mode
+        self.set_active_mode(MODE_INLINE);
         Ok(())
     }
 
     /// Exit inline mode and restore terminal.
     pub fn exit_inline(&mut self) -> io::Result<()> {
+        self.set_active_mode(MODE_NONE);
         let mut out = OutputBuffer::new();
 
+        // Disable color scheme notifications
+        if self.color_scheme_reports {
+            out.write_str("\x1b[?2031l");
+            self.color_scheme_reports = false;
+        }
+
         // Disable bracketed paste
         if self.bracketed_paste {
             out.write_str("\x1b[?2004l");
@@ -120,7 +182,8 @@ impl TerminalSetup {
         // Reset terminal state
         ansi::reset(&mut out)?;
 
-        // Show cursor
+        // Show cursor, in the user's shape (a native cursor may have set one)
+        ansi::cursor_shape_default(&mut out)?;
         ansi::cursor_show(&mut out)?;
 
         out.flush_stdout()?;
@@ -132,6 +195,7 @@ impl TerminalSetup {
 
     /// Exit fullscreen mode and restore terminal.
     pub fn exit_fullscreen(&mut self) -> io::Result<()> {
+        self.set_active_mode(MODE_NONE);
         let mut out = OutputBuffer::new();
 
         // Disable focus reporting
@@ -140,6 +204,12 @@ impl TerminalSetup {
             self.focus_reporting = false;
         }
 
+        // Disable color scheme notifications
+        if self.color_scheme_reports {
+            out.write_str("\x1b[?2031l");
+            self.color_scheme_reports = false;
+        }
+
         // Disable bracketed paste
         if self.bracketed_paste {
             out.write_str("\x1b[?2004l");
@@ -154,7 +224,10 @@ impl TerminalSetup {
 
         // Disable mouse tracking
         if self.mouse_enabled {
+            out.write_str("\x1b[?1007l");
+            out.write_str("\x1b[?1016l");
             out.write_str("\x1b[?1006l");
+            out.write_str("\x1b[?1015l");
             out.write_str("\x1b[?1003l");
             out.write_str("\x1b[?1002l");
             out.write_str("\x1b[?1000l");
@@ -162,12 +235,13 @@ impl TerminalSetup {
         }
 
         // End synchronized output
-        out.write_str("\x1b[?2026l");
+        ansi::end_sync(&mut out)?;
 
         // Reset terminal state
         ansi::reset(&mut out)?;
 
-        // Show cursor
+        // Show cursor, in the user's shape (a native cursor may have set one)
+        ansi::cursor_shape_default(&mut out)?;
         ansi::cursor_show(&mut out)?;
 
         // Exit alternate screen
@@ -181,8 +255,23 @@ impl TerminalSetup {
         Ok(())
     }
 
+    /// Record what the process terminal needs restoring to on a crash, and
+    /// hook the crash paths up. Sessions don't own it.
+    fn set_active_mode(&self, mode: u8) {
+        if self.remote {
+            return;
+        }
+        ACTIVE_MODE.store(mode, Ordering::SeqCst);
+        if mode != MODE_NONE {
+            install_restore_hooks();
+        }
+    }
+
     /// Enable raw mode (platform-specific).
     fn enable_raw_mode(&mut self) -> io::Result<()> {
+        if self.remote {
+            return Ok(());
+        }
         #[cfg(unix)]
         {
             use std::os::unix::io::AsRawFd;
@@ -220,11 +309,48 @@ impl TerminalSetup {
             }
             self.is_raw = true;
         }
+        #[cfg(windows)]
+        {
+            use windows_sys::Win32::System::Console::*;
+
+            unsafe {
+                let input = GetStdHandle(STD_INPUT_HANDLE);
+                let output = GetStdHandle(STD_OUTPUT_HANDLE);
+                let (mut in_mode, mut out_mode) = (0, 0);
+                // Not a console (redirected) — render anyway, like a non-TTY on unix
+                if GetConsoleMode(input, &mut in_mode) == 0 || GetConsoleMode(output, &mut out_mode) == 0 {
+                    return Ok(());
+                }
+                ORIGINAL_INPUT_MODE.store(in_mode, Ordering::SeqCst);
+                ORIGINAL_OUTPUT_MODE.store(out_mode, Ordering::SeqCst);
+
+                // Consoles without VT processing (before Windows 10) can't host us
+                let vt_out = out_mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING | DISABLE_NEWLINE_AUTO_RETURN;
+                if SetConsoleMode(output, vt_out) == 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                // No line editing, echo or Ctrl+C processing; quick-edit off so
+                // mouse reports reach us instead of selecting text
+                let vt_in = (in_mode & !(ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT | ENABLE_PROCESSED_INPUT | ENABLE_QUICK_EDIT_MODE))
+                    | ENABLE_VIRTUAL_TERMINAL_INPUT | ENABLE_WINDOW_INPUT | ENABLE_EXTENDED_FLAGS;
+                if SetConsoleMode(input, vt_in) == 0 {
+                    SetConsoleMode(output, out_mode);
+                    return Err(io::Error::last_os_error());
+                }
+                ORIGINAL_OUTPUT_CP.store(GetConsoleOutputCP(), Ordering::SeqCst);
+                SetConsoleOutputCP(CP_UTF8);
+            }
+            CONSOLE_MODES_SAVED.store(true, Ordering::SeqCst);
+            self.is_raw = true;
+        }
         Ok(())
     }
 
     /// Disable raw mode.
     fn disable_raw_mode(&mut self) -> io::Result<()> {
+        if self.remote {
+            return Ok(());
+        }
         #[cfg(unix)]
         {
             use std::os::unix::io::AsRawFd;
@@ -238,6 +364,11 @@ impl TerminalSetup {
             }
             self.is_raw = false;
         }
+        #[cfg(windows)]
+        {
+            restore_console_modes();
+            self.is_raw = false;
+        }
         Ok(())
     }
 }
@@ -248,10 +379,15 @@ impl Default for TerminalSetup {
     }
 }
 
+/// Restores the terminal however the engine ends — including a panic
+/// unwinding out of the engine thread.
 impl Drop for TerminalSetup {
     fn drop(&mut self) {
         if self.is_fullscreen {
             let _ = self.exit_fullscreen();
+        } else if self.is_raw || (self.remote && self.bracketed_paste) {
+            // A session never goes raw; bracketed paste marks it entered
+            let _ = self.exit_inline();
         }
     }
 }
@@ -259,3 +395,223 @@ impl Drop for TerminalSetup {
 /// Saved original terminal settings for restore.
 #[cfg(unix)]
 static mut ORIGINAL_TERMIOS: Option<libc::termios> = None;
+
+// Saved original console modes and output code page (Windows), as atomics
+// so the console control handler thread can read them.
+#[cfg(windows)]
+static CONSOLE_MODES_SAVED: AtomicBool = AtomicBool::new(false);
+#[cfg(windows)]
+static ORIGINAL_INPUT_MODE: AtomicU32 = AtomicU32::new(0);
+#[cfg(windows)]
+static ORIGINAL_OUTPUT_MODE: AtomicU32 = AtomicU32::new(0);
+#[cfg(windows)]
+static ORIGINAL_OUTPUT_CP: AtomicU32 = AtomicU32::new(0);
+
+#[cfg(windows)]
+const CP_UTF8: u32 = 65001;
+
+/// Put back the console modes and code page `enable_raw_mode` replaced.
+#[cfg(windows)]
+fn restore_console_modes() {
+    use windows_sys::Win32::System::Console::*;
+
+    if !CONSOLE_MODES_SAVED.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    unsafe {
+        SetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), ORIGINAL_INPUT_MODE.load(Ordering::SeqCst));
+        SetConsoleMode(GetStdHandle(STD_OUTPUT_HANDLE), ORIGINAL_OUTPUT_MODE.load(Ordering::SeqCst));
+        SetConsoleOutputCP(ORIGINAL_OUTPUT_CP.load(Ordering::SeqCst));
+    }
+}
+
+// =============================================================================
+// Emergency restore (panic hook, SIGTERM / SIGHUP)
+// =============================================================================
+
+const MODE_NONE: u8 = 0;
+const MODE_INLINE: u8 = 1;
+const MODE_FULLSCREEN: u8 = 2;
+
+/// The mode the terminal is in right now, for the emergency restore.
+static ACTIVE_MODE: AtomicU8 = AtomicU8::new(MODE_NONE);
+
+/// Undoes everything `enter_fullscreen` turns on: focus reporting, color
+/// scheme notifications, bracketed paste, Kitty keyboard, mouse tracking, synchronized output, attributes,
+/// hidden cursor, alternate screen.
+const FULLSCREEN_RESTORE: &[u8] =
+    b"\x1b[?1004l\x1b[?2031l\x1b[?2004l\x1b[<u\x1b[?1007l\x1b[?1016l\x1b[?1006l\x1b[?1015l\x1b[?1003l\x1b[?1002l\x1b[?1000l\x1b[?2026l\x1b[0m\x1b[?25h\x1b[?1049l";
+
+/// Undoes everything `enter_inline` turns on.
+const INLINE_RESTORE: &[u8] = b"\x1b[?2031l\x1b[?2004l\x1b[<u\x1b[0m\x1b[?25h";
+
+/// Signals that restore the terminal before the process goes.
+#[cfg(unix)]
+const RESTORE_SIGNALS: [libc::c_int; 2] = [libc::SIGTERM, libc::SIGHUP];
+
+thread_local! {
+    /// Depth of `contained` calls on this thread.
+    static CONTAINED: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Sequence that restores the terminal from `mode`.
+fn restore_sequence(mode: u8) -> &'static [u8] {
+    match mode {
+        MODE_FULLSCREEN => FULLSCREEN_RESTORE,
+        MODE_INLINE => INLINE_RESTORE,
+        _ => b"",
+    }
+}
+
+/// Run `f`, catching a panic the caller will recover from. The panic hook
+/// leaves the terminal (and the screen) alone for these.
+pub fn contained<R>(f: impl FnOnce() -> R) -> thread::Result<R> {
+    CONTAINED.with(|depth| depth.set(depth.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CONTAINED.with(|depth| depth.set(depth.get() - 1));
+    result
+}
+
+/// Put the terminal back the way it was, from wherever we are. Only
+/// async-signal-safe calls: one `write`, one `tcsetattr`.
+fn emergency_restore() {
+    let mode = ACTIVE_MODE.swap(MODE_NONE, Ordering::SeqCst);
+    if mode == MODE_NONE {
+        return;
+    }
+    #[cfg(unix)]
+    unsafe {
+        let seq = restore_sequence(mode);
+        let _ = libc::write(libc::STDOUT_FILENO, seq.as_ptr().cast(), seq.len());
+        if let Some(ref original) = *std::ptr::addr_of!(ORIGINAL_TERMIOS) {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, original);
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        use std::io::Write;
+        let _ = io::stdout().write_all(restore_sequence(mode));
+        let _ = io::stdout().flush();
+    }
+    #[cfg(windows)]
+    restore_console_modes();
+}
+
+/// Install the panic hook and signal handlers, once per process.
+fn install_restore_hooks() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CONTAINED.with(Cell::get) > 0 {
+                // Recovered and reported as an Error event; printing now
+                // would scribble over the UI
+                if ACTIVE_MODE.load(Ordering::SeqCst) == MODE_NONE {
+                    previous(info);
+                }
+                return;
+            }
+            emergency_restore();
+            previous(info);
+        }));
+
+        #[cfg(unix)]
+        install_signal_handlers();
+        #[cfg(windows)]
+        unsafe {
+            windows_sys::Win32::System::Console::SetConsoleCtrlHandler(Some(restore_ctrl_handler), 1);
+        }
+    });
+}
+
+/// Console control handler: restore on close, logoff and shutdown, then let
+/// the next handler (ultimately the default: exit) run. Ctrl+C isn't seen
+/// here in raw mode — it arrives as input.
+#[cfg(windows)]
+unsafe extern "system" fn restore_ctrl_handler(ctrl_type: u32) -> windows_sys::core::BOOL {
+    use windows_sys::Win32::System::Console::{CTRL_BREAK_EVENT, CTRL_CLOSE_EVENT, CTRL_LOGOFF_EVENT, CTRL_SHUTDOWN_EVENT};
+
+    if matches!(ctrl_type, CTRL_BREAK_EVENT | CTRL_CLOSE_EVENT | CTRL_LOGOFF_EVENT | CTRL_SHUTDOWN_EVENT) {
+        emergency_restore();
+    }
+    0
+}
+
+/// Handlers that were installed before ours, by signal number.
+#[cfg(unix)]
+static mut PREVIOUS_HANDLERS: [Option<libc::sigaction>; 32] = [None; 32];
+
+#[cfg(unix)]
+fn install_signal_handlers() {
+    for signal in RESTORE_SIGNALS {
+        unsafe {
+            let mut previous: libc::sigaction = std::mem::zeroed();
+            if libc::sigaction(signal, std::ptr::null(), &mut previous) != 0 {
+                continue;
+            }
+            // Deliberately ignored → the process won't die of it; nothing to restore
+            if previous.sa_sigaction == libc::SIG_IGN {
+                continue;
+            }
+            (*std::ptr::addr_of_mut!(PREVIOUS_HANDLERS))[signal as usize] = Some(previous);
+
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = restore_signal_handler as *const () as usize;
+            action.sa_flags = libc::SA_SIGINFO;
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(signal, &action, std::ptr::null_mut());
+        }
+    }
+}
+
+/// Restore the terminal, then let the previous handler (or the default
+/// action) deal with the signal.
+#[cfg(unix)]
+extern "C" fn restore_signal_handler(signal: libc::c_int, info: *mut libc::siginfo_t, context: *mut libc::c_void) {
+    emergency_restore();
+    unsafe {
+        let previous = (*std::ptr::addr_of!(PREVIOUS_HANDLERS)).get(signal as usize).copied().flatten();
+        match previous {
+            Some(action) if action.sa_sigaction != libc::SIG_DFL => {
+                if action.sa_flags & libc::SA_SIGINFO != 0 {
+                    let handler: extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void) =
+                        std::mem::transmute(action.sa_sigaction);
+                    handler(signal, info, context);
+                } else {
+                    let handler: extern "C" fn(libc::c_int) = std::mem::transmute(action.sa_sigaction);
+                    handler(signal);
+                }
+            }
+            _ => {
+                // Die of the signal as if we had never caught it
+                libc::signal(signal, libc::SIG_DFL);
+                libc::raise(signal);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_sequences_undo_setup() {
+        let fullscreen = std::str::from_utf8(restore_sequence(MODE_FULLSCREEN)).unwrap();
+        for off in ["\x1b[?1049l", "\x1b[?25h", "\x1b[?1000l", "\x1b[?1003l", "\x1b[?1006l", "\x1b[?1015l", "\x1b[?1016l", "\x1b[?1007l", "\x1b[<u", "\x1b[?2004l"] {
+            assert!(fullscreen.contains(off), "missing {off:?}");
+        }
+        // Inline never entered the alternate screen: leaving it would move the cursor
+        let inline = std::str::from_utf8(restore_sequence(MODE_INLINE)).unwrap();
+        assert!(!inline.contains("\x1b[?1049l"));
+        assert!(inline.contains("\x1b[?25h"));
+        assert!(restore_sequence(MODE_NONE).is_empty());
+    }
+
+    #[test]
+    fn test_contained_catches_and_unwinds_depth() {
+        assert!(contained(|| panic!("contained")).is_err());
+        assert_eq!(contained(|| 7).unwrap(), 7);
+        assert_eq!(CONTAINED.with(Cell::get), 0);
+    }
+}
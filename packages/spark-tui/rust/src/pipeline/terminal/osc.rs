@@ -0,0 +1,173 @@
+//! Window title, bell and desktop notifications.
+//!
+//! These go out as OSC sequences (and a BEL) beside the frames. They're
+//! queued on the engine thread and the render effect hands the queue to the
+//! renderer's output buffer, so they reach the terminal in the same write as
+//! the frame being drawn. Whatever no frame picked up is flushed once the
+//! engine has handled the message that queued it.
+//!
+//! ```text
+//! set_title("build ✓")      → ESC[22;0t (once: save the title)  ESC]2;build ✓ ESC\
+//! bell()                    → BEL
+//! notify(Osc9, "", "done")  → ESC]9;done ESC\
+//! notify(Osc777, "CI", "…") → ESC]777;notify;CI;… ESC\
+//! ```
+//!
+//! The first `set_title` saves the terminal's title on its title stack
+//! (XTWINOPS); `reset` at cleanup puts it back.
+
+use std::cell::{Cell, RefCell};
+use std::io::{self, Write};
+
+use crate::renderer::{OutputBuffer, TerminalOut};
+
+/// Save the window and icon title on the terminal's title stack.
+const PUSH_TITLE: &str = "\x1b[22;0t";
+/// Restore the title saved by `PUSH_TITLE`.
+const POP_TITLE: &str = "\x1b[23;0t";
+
+/// Desktop notification protocol. Terminals pick one (or both).
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NotifyProtocol {
+    /// OSC 9: message only (iTerm2, WezTerm, Ghostty, Windows Terminal, kitty)
+    #[default]
+    Osc9 = 0,
+    /// OSC 777: title and body (urxvt, foot, Ghostty, WezTerm, VTE)
+    Osc777 = 1,
+}
+
+impl From<u8> for NotifyProtocol {
+    fn from(v: u8) -> Self {
+        match v {
+            1 => Self::Osc777,
+            _ => Self::Osc9,
+        }
+    }
+}
+
+/// A request from TS, sent to the engine thread (see `StdinMessage::Terminal`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Title(String),
+    Bell,
+    Notify { protocol: NotifyProtocol, title: String, body: String },
+}
+
+impl Command {
+    /// Queue the command's sequence.
+    pub fn run(self) {
+        match self {
+            Self::Title(title) => set_title(&title),
+            Self::Bell => bell(),
+            Self::Notify { protocol, title, body } => notify(protocol, &title, &body),
+        }
+    }
+}
+
+thread_local! {
+    /// Sequences waiting for this engine thread's next write.
+    static PENDING: RefCell<String> = const { RefCell::new(String::new()) };
+    /// The terminal's own title is saved on its title stack.
+    static TITLE_SAVED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set the window title.
+pub fn set_title(title: &str) {
+    queue(|pending| {
+        if !TITLE_SAVED.with(|saved| saved.replace(true)) {
+            pending.push_str(PUSH_TITLE);
+        }
+        pending.push_str(&format!("\x1b]2;{}\x1b\\", sanitize(title)));
+    });
+}
+
+/// Ring the terminal bell (audible or visual, as the user configured it).
+pub fn bell() {
+    queue(|pending| pending.push('\x07'));
+}
+
+/// Post a desktop notification. OSC 9 has no title field, so a title is
+/// joined to the body there.
+pub fn notify(protocol: NotifyProtocol, title: &str, body: &str) {
+    let sequence = match protocol {
+        NotifyProtocol::Osc9 if title.is_empty() => format!("\x1b]9;{}\x1b\\", sanitize(body)),
+        NotifyProtocol::Osc9 => format!("\x1b]9;{}: {}\x1b\\", sanitize(title), sanitize(body)),
+        // The title can't contain the field separator
+        NotifyProtocol::Osc777 => format!("\x1b]777;notify;{};{}\x1b\\", sanitize(title).replace(';', ","), sanitize(body)),
+    };
+    queue(|pending| pending.push_str(&sequence));
+}
+
+/// Whether anything is waiting to be written.
+pub fn has_pending() -> bool {
+    PENDING.with(|pending| !pending.borrow().is_empty())
+}
+
+/// Move the queue into `out`, ahead of the frame about to be written there.
+pub fn drain_into(out: &mut OutputBuffer) {
+    PENDING.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        out.write_str(&pending);
+        pending.clear();
+    });
+}
+
+/// Write the queue to the terminal now.
+pub fn flush() -> io::Result<()> {
+    if !has_pending() {
+        return Ok(());
+    }
+    let mut out = OutputBuffer::new();
+    drain_into(&mut out);
+    out.flush_stdout()
+}
+
+/// Drop what's queued and restore the title the terminal had before the
+/// first `set_title`.
+pub fn reset() -> io::Result<()> {
+    PENDING.with(|pending| pending.borrow_mut().clear());
+    if TITLE_SAVED.with(|saved| saved.replace(false)) {
+        let mut out = TerminalOut;
+        out.write_all(POP_TITLE.as_bytes())?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+fn queue(push: impl FnOnce(&mut String)) {
+    PENDING.with(|pending| push(&mut pending.borrow_mut()));
+}
+
+/// Controls would end the sequence early (ESC, BEL) or garble it; drop them.
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drained() -> String {
+        let mut out = OutputBuffer::new();
+        drain_into(&mut out);
+        out.as_str().into_owned()
+    }
+
+    #[test]
+    fn test_sequences_queue_until_drained() {
+        set_title("one\x1b]");
+        set_title("two");
+        bell();
+        notify(NotifyProtocol::Osc9, "", "done");
+        notify(NotifyProtocol::Osc777, "a;b", "c;d");
+        assert!(has_pending());
+
+        // The title is saved once, before the first change
+        assert_eq!(
+            drained(),
+            "\x1b[22;0t\x1b]2;one]\x1b\\\x1b]2;two\x1b\\\x07\x1b]9;done\x1b\\\x1b]777;notify;a,b;c;d\x1b\\"
+        );
+        assert!(!has_pending());
+    }
+}
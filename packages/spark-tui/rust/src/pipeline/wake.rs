@@ -8,27 +8,70 @@
 //!
 //! This replaces the adaptive spin-wait approach which burned 10%+ CPU.
 //! FFI call overhead is ~5ns, unpark latency is ~1-2μs.
+//!
+//! The same thread keeps the timer deadline (`state::timers`): with one
+//! pending it parks only until then and sends `Timers` to the engine.
+//!
+//! Every engine (the process's, and each session's) has its own watcher.
+//! The engine thread installs its `Waker` so timers re-arm the right one,
+//! and hands a clone to its owner (`Engine`/`Session`) for TS wakes.
 
+use std::cell::RefCell;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Sender;
-use std::sync::{Arc, OnceLock};
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle, Thread};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use crate::input::reader::StdinMessage;
 use crate::shared_buffer::SharedBuffer;
 
 // =============================================================================
-// GLOBAL THREAD HANDLE
+// THREAD HANDLES
 // =============================================================================
 
-/// The wake thread's handle, stored for FFI to unpark.
-static WAKE_THREAD: OnceLock<Thread> = OnceLock::new();
+thread_local! {
+    /// The waker of the engine running on this thread.
+    static ENGINE_WAKER: RefCell<Option<Waker>> = const { RefCell::new(None) };
+}
+
+/// Replace the timer deadline this engine's wake thread sleeps toward
+/// (`None` = no timers). No-op off an engine thread.
+pub fn set_timer_deadline(deadline: Option<Instant>) {
+    ENGINE_WAKER.with(|w| {
+        if let Some(waker) = w.borrow().as_ref() {
+            waker.set_deadline(deadline);
+        }
+    });
+}
+
+/// Make `waker` this engine thread's (see `set_timer_deadline`).
+pub fn install(waker: Waker) {
+    ENGINE_WAKER.with(|w| *w.borrow_mut() = Some(waker));
+}
+
+/// Handle to one wake watcher: unparks it and hands it the timer deadline.
+#[derive(Debug, Clone)]
+pub struct Waker {
+    thread: Thread,
+    /// Earliest timer deadline, set by the engine thread.
+    deadline: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Waker {
+    /// Unpark the watcher so it checks the wake flag.
+    pub fn wake(&self) {
+        self.thread.unpark();
+    }
 
-/// Unpark the wake thread. Called by FFI `spark_wake()`.
-pub fn unpark_wake_thread() {
-    if let Some(thread) = WAKE_THREAD.get() {
-        thread.unpark();
+    fn set_deadline(&self, deadline: Option<Instant>) {
+        let mut current = self.deadline.lock().unwrap_or_else(|e| e.into_inner());
+        if *current != deadline {
+            *current = deadline;
+            drop(current);
+            // Re-park with the new deadline
+            self.wake();
+        }
     }
 }
 
@@ -42,6 +85,7 @@ pub fn unpark_wake_thread() {
 /// FFI `spark_wake()` calls `unpark()`.
 pub struct WakeWatcher {
     handle: Option<JoinHandle<()>>,
+    waker: Waker,
 }
 
 impl WakeWatcher {
@@ -55,25 +99,31 @@ impl WakeWatcher {
         tx: Sender<StdinMessage>,
         running: Arc<AtomicBool>,
     ) -> Self {
+        let deadline = Arc::new(Mutex::new(None));
+        let deadline_for_thread = deadline.clone();
         let handle = thread::Builder::new()
             .name("spark-wake".to_string())
             .spawn(move || {
-                // Store this thread's handle so FFI can unpark us
-                let _ = WAKE_THREAD.set(thread::current());
-
-                Self::watch_loop(buf, tx, running);
+                Self::watch_loop(buf, tx, running, deadline_for_thread);
             })
             .expect("Failed to spawn wake watcher thread");
+        let waker = Waker { thread: handle.thread().clone(), deadline };
 
         Self {
             handle: Some(handle),
+            waker,
         }
     }
 
+    pub fn waker(&self) -> Waker {
+        self.waker.clone()
+    }
+
     fn watch_loop(
         buf: &'static SharedBuffer,
         tx: Sender<StdinMessage>,
         running: Arc<AtomicBool>,
+        timer_deadline: Arc<Mutex<Option<Instant>>>,
     ) {
         while running.load(Ordering::Relaxed) {
             // Check for wake flag (may have been set before we parked)
@@ -103,8 +153,27 @@ impl WakeWatcher {
                 continue;
             }
 
-            // No wake pending — park until FFI unparks us (0% CPU, instant wake)
-            thread::park();
+            // No wake pending — park until FFI unparks us or a timer is due
+            let deadline = *timer_deadline.lock().unwrap_or_else(|e| e.into_inner());
+            match deadline {
+                Some(due) => {
+                    let now = Instant::now();
+                    if due > now {
+                        thread::park_timeout(due - now);
+                        continue;
+                    }
+                    // Fired once; the engine re-arms with the next deadline
+                    let mut current = timer_deadline.lock().unwrap_or_else(|e| e.into_inner());
+                    if *current == Some(due) {
+                        *current = None;
+                    }
+                    drop(current);
+                    if tx.send(StdinMessage::Timers).is_err() {
+                        break;
+                    }
+                }
+                None => thread::park(),
+            }
         }
     }
 }
@@ -112,9 +181,7 @@ impl WakeWatcher {
 impl Drop for WakeWatcher {
     fn drop(&mut self) {
         // Unpark to ensure the thread can exit if it's parked
-        if let Some(thread) = WAKE_THREAD.get() {
-            thread.unpark();
-        }
+        self.waker.wake();
         if let Some(handle) = self.handle.take() {
             let _ = handle.join();
         }
@@ -0,0 +1,286 @@
+//! Watchdog — breaks runaway update storms instead of freezing the terminal.
+//!
+//! A getter that writes to a signal it depends on makes TS wake Rust again
+//! from inside the very propagation Rust just finished. Each wake re-runs
+//! layout, framebuffer and render, which wakes TS, which wakes Rust...
+//! The pipeline spins at 100% CPU and input starves.
+//!
+//! The watchdog sits where wakes enter the engine thread:
+//!
+//! ```text
+//! Wake ─→ watchdog.on_wake()
+//!           │
+//!           ├─ Propagate: increment generation (normal path)
+//!           │               └─ watchdog.after_propagation(): which nodes are
+//!           │                  dirty again the moment the frame is done?
+//!           └─ Suppress:  storm detected → drop the wake, keep the last frame
+//! ```
+//!
+//! A frame clears the dirty flags of every node it lays out and paints. A
+//! node that is dirty again right after its frame was written while the
+//! frame ran. Real UIs (typing, animation pulses, a 10kHz tick stream) write
+//! between frames, so now and then — a feedback cycle writes continuously,
+//! and the same nodes come out of every frame dirty. Once that happens for
+//! `MAX_STORM_UPDATES` consecutive propagations, the watchdog trips:
+//!
+//! - The nodes that keep coming out dirty are written to the SharedBuffer
+//!   header (`H_WATCHDOG_NODES`) so TS can log which components are cycling.
+//! - `H_WATCHDOG_ACTIVE` is set and a warning bar is drawn over the frame.
+//! - Wakes that find those nodes dirty are dropped. A wake that finds them
+//!   clean, or no wake for `STORM_GAP`, ends the storm: the engine calls
+//!   `settle()`, clears the warning, and renders the final state once.
+//!
+//! How far apart wakes are never matters, and wakes the frame pacer
+//! coalesces into a later frame are not propagations — they don't count.
+//!
+//! Input from stdin never goes through the watchdog — the user can always
+//! reach Ctrl+C.
+
+use std::time::Duration;
+
+use crate::renderer::FrameBuffer;
+use crate::shared_buffer::{SharedBuffer, DIRTY_HIERARCHY, DIRTY_LAYOUT, DIRTY_TEXT, DIRTY_VISUAL, WATCHDOG_NODE_SLOTS};
+use crate::utils::{Attr, Rgba};
+
+/// A suppressed storm with no wake for this long is over.
+pub const STORM_GAP: Duration = Duration::from_millis(2);
+
+/// Consecutive propagations the same nodes may come out of dirty before the
+/// watchdog trips.
+pub const MAX_STORM_UPDATES: u32 = 1000;
+
+/// What the engine should do with a wake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeVerdict {
+    /// Increment generation and let the graph propagate.
+    Propagate,
+    /// Drop the wake — the pipeline is caught in an update storm.
+    Suppress,
+}
+
+/// Detects and breaks update storms on the engine thread.
+pub struct Watchdog {
+    /// Nodes still dirty after the last propagation.
+    leftover: Vec<usize>,
+    /// Consecutive propagations that left some of the same nodes dirty.
+    chain: u32,
+    /// Whether wakes are currently being suppressed.
+    tripped: bool,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        Self {
+            leftover: Vec::new(),
+            chain: 0,
+            tripped: false,
+        }
+    }
+
+    /// Whether a storm is currently being suppressed.
+    pub fn is_tripped(&self) -> bool {
+        self.tripped
+    }
+
+    /// Judge an incoming TS wake.
+    pub fn on_wake(&mut self, buf: &SharedBuffer) -> WakeVerdict {
+        if self.tripped {
+            if overlaps(&dirty_nodes(buf, WATCHDOG_NODE_SLOTS), &self.leftover) {
+                return WakeVerdict::Suppress;
+            }
+            // The cycling nodes went clean — the storm is over
+            self.settle(buf);
+            return WakeVerdict::Propagate;
+        }
+
+        if self.chain >= MAX_STORM_UPDATES {
+            self.tripped = true;
+            buf.record_watchdog_trip(&self.leftover);
+            // Let this one wake through so the warning bar gets painted
+        }
+
+        WakeVerdict::Propagate
+    }
+
+    /// A wake's frame just ran. Not called for wakes the pacer held back.
+    pub fn after_propagation(&mut self, buf: &SharedBuffer) {
+        let leftover = dirty_nodes(buf, WATCHDOG_NODE_SLOTS);
+        self.chain = if leftover.is_empty() {
+            0
+        } else if overlaps(&leftover, &self.leftover) {
+            self.chain.saturating_add(1)
+        } else {
+            1
+        };
+        self.leftover = leftover;
+    }
+}
+
+impl Watchdog {
+    /// End a suppressed storm. The caller should propagate once so the
+    /// final state (without the warning bar) reaches the terminal.
+    pub fn settle(&mut self, buf: &SharedBuffer) {
+        self.tripped = false;
+        self.chain = 0;
+        self.leftover.clear();
+        buf.clear_watchdog_active();
+    }
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collect up to `limit` node indices that still carry dirty flags.
+fn dirty_nodes(buf: &SharedBuffer, limit: usize) -> Vec<usize> {
+    let any_dirty = DIRTY_LAYOUT | DIRTY_VISUAL | DIRTY_TEXT | DIRTY_HIERARCHY;
+    (0..buf.node_count())
+        .filter(|&i| buf.dirty_flags(i) & any_dirty != 0)
+        .take(limit)
+        .collect()
+}
+
+/// Whether two ascending node lists share a node.
+fn overlaps(a: &[usize], b: &[usize]) -> bool {
+    a.iter().any(|i| b.binary_search(i).is_ok())
+}
+
+// =============================================================================
+// Overlay
+// =============================================================================
+
+/// Draw the watchdog warning bar on the top row while a storm is suppressed.
+pub fn draw_overlay(frame: &mut FrameBuffer, buf: &SharedBuffer) {
+    if !buf.watchdog_active() || frame.width() == 0 || frame.height() == 0 {
+        return;
+    }
+
+    let nodes = buf.watchdog_nodes();
+    let mut message = String::from(" ⚠ update loop detected — rendering paused");
+    if !nodes.is_empty() {
+        let list: Vec<String> = nodes.iter().map(|i| i.to_string()).collect();
+        message.push_str(" (nodes ");
+        message.push_str(&list.join(", "));
+        message.push(')');
+    }
+    message.push(' ');
+
+    let bg = Rgba::rgb(160, 30, 30);
+    frame.fill_rect(0, 0, frame.width(), 1, bg, None);
+    frame.draw_text(0, 0, &message, Rgba::WHITE, Some(bg), Attr::BOLD, None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_buffer::test_support::TestBuffer;
+
+    const MAX_NODES: usize = 8;
+    const TEXT_POOL: usize = 1024;
+
+    /// Buffer with `node_count` live nodes; `dirty` nodes carry DIRTY_VISUAL.
+    fn create_buffer(node_count: usize, dirty: &[usize]) -> TestBuffer {
+        let buf = TestBuffer::new(MAX_NODES, TEXT_POOL);
+        buf.set_node_count(node_count);
+        for &i in dirty {
+            buf.mark_dirty(i, DIRTY_VISUAL);
+        }
+        buf
+    }
+
+    /// One wake and, unless the watchdog drops it, its frame — which
+    /// cleans every node except `rewritten`, dirtied again while it ran.
+    fn wake_and_render(dog: &mut Watchdog, buf: &TestBuffer, rewritten: &[usize]) -> WakeVerdict {
+        let verdict = dog.on_wake(buf);
+        if verdict == WakeVerdict::Propagate {
+            for i in 0..buf.node_count() {
+                buf.clear_dirty(i);
+            }
+            for &i in rewritten {
+                buf.mark_dirty(i, DIRTY_VISUAL);
+            }
+            dog.after_propagation(buf);
+        }
+        verdict
+    }
+
+    #[test]
+    fn test_high_rate_stream_never_trips() {
+        // A 10kHz tick stream: every wake finds node 1 freshly written, and
+        // every frame leaves it clean until the next tick
+        let buf = create_buffer(4, &[]);
+        let mut dog = Watchdog::new();
+        for _ in 0..(MAX_STORM_UPDATES * 10) {
+            buf.mark_dirty(1, DIRTY_TEXT);
+            assert_eq!(wake_and_render(&mut dog, &buf, &[]), WakeVerdict::Propagate);
+        }
+        assert!(!dog.is_tripped());
+        assert_eq!(buf.watchdog_trip_count(), 0);
+    }
+
+    #[test]
+    fn test_changing_leftovers_never_trip() {
+        // Writes that happen to land mid-frame, but never on the same node twice in a row
+        let buf = create_buffer(4, &[]);
+        let mut dog = Watchdog::new();
+        for n in 0..(MAX_STORM_UPDATES * 2) as usize {
+            assert_eq!(wake_and_render(&mut dog, &buf, &[n % 2]), WakeVerdict::Propagate);
+        }
+        assert!(!dog.is_tripped());
+    }
+
+    #[test]
+    fn test_storm_trips_and_recovers() {
+        // Node 2 keeps getting dirtied by the cycle while every frame runs
+        let buf = create_buffer(4, &[2]);
+
+        let mut dog = Watchdog::new();
+        for _ in 0..=MAX_STORM_UPDATES {
+            assert_eq!(wake_and_render(&mut dog, &buf, &[2]), WakeVerdict::Propagate);
+        }
+        assert!(dog.is_tripped());
+        assert!(buf.watchdog_active());
+        assert_eq!(buf.watchdog_trip_count(), 1);
+        assert_eq!(buf.watchdog_nodes(), vec![2]);
+
+        assert_eq!(dog.on_wake(&buf), WakeVerdict::Suppress);
+
+        // The cycle stops writing node 2 — the next wake ends the storm
+        buf.clear_dirty(2);
+        buf.mark_dirty(3, DIRTY_VISUAL);
+        assert_eq!(dog.on_wake(&buf), WakeVerdict::Propagate);
+        assert!(!dog.is_tripped());
+        assert!(!buf.watchdog_active());
+        assert_eq!(buf.watchdog_trip_count(), 1);
+    }
+
+    #[test]
+    fn test_settle_ends_a_quiet_storm() {
+        let buf = create_buffer(4, &[2]);
+        let mut dog = Watchdog::new();
+        for _ in 0..=MAX_STORM_UPDATES {
+            wake_and_render(&mut dog, &buf, &[2]);
+        }
+        assert!(dog.is_tripped());
+
+        dog.settle(&buf);
+        assert!(!buf.watchdog_active());
+        // A fresh chain has to build up again before the next trip
+        assert_eq!(wake_and_render(&mut dog, &buf, &[2]), WakeVerdict::Propagate);
+        assert!(!dog.is_tripped());
+    }
+
+    #[test]
+    fn test_overlay_only_when_active() {
+        let buf = create_buffer(1, &[]);
+        let mut frame = FrameBuffer::new(60, 3);
+        draw_overlay(&mut frame, &buf);
+        assert_eq!(frame.get(1, 0).unwrap().char, ' ' as u32);
+
+        buf.record_watchdog_trip(&[7]);
+        draw_overlay(&mut frame, &buf);
+        assert_eq!(frame.get(1, 0).unwrap().char, '⚠' as u32);
+    }
+}
@@ -9,6 +9,7 @@
 //! - Synchronized output for flicker-free rendering
 
 use crate::utils::{Attr, Rgba};
+use std::cell::Cell;
 use std::io::Write;
 
 // =============================================================================
@@ -144,6 +145,12 @@ pub fn cursor_shape<W: Write>(w: &mut W, shape: CursorShape, blinking: bool) ->
     write!(w, "\x1b[{} q", n)
 }
 
+/// Restore the terminal's default cursor shape.
+#[inline]
+pub fn cursor_shape_default<W: Write>(w: &mut W) -> std::io::Result<()> {
+    write!(w, "\x1b[0 q")
+}
+
 // =============================================================================
 // Screen Control
 // =============================================================================
@@ -234,15 +241,33 @@ pub fn scroll_down<W: Write>(w: &mut W, n: u16) -> std::io::Result<()> {
 // Synchronized Output (Flicker Prevention)
 // =============================================================================
 
+thread_local! {
+    /// Whether this engine thread writes sync markers (off under GNU
+    /// screen, see `passthrough`). Per thread: a session's terminal isn't
+    /// the process's.
+    static SYNC_OUTPUT: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Enable or disable synchronized output for this engine thread's terminal.
+pub fn set_sync_output(enabled: bool) {
+    SYNC_OUTPUT.with(|sync| sync.set(enabled));
+}
+
 /// Begin synchronized output (terminal buffers until end_sync).
 #[inline]
 pub fn begin_sync<W: Write>(w: &mut W) -> std::io::Result<()> {
+    if !SYNC_OUTPUT.with(Cell::get) {
+        return Ok(());
+    }
     write!(w, "\x1b[?2026h")
 }
 
 /// End synchronized output (terminal flushes buffer).
 #[inline]
 pub fn end_sync<W: Write>(w: &mut W) -> std::io::Result<()> {
+    if !SYNC_OUTPUT.with(Cell::get) {
+        return Ok(());
+    }
     write!(w, "\x1b[?2026l")
 }
 
@@ -460,6 +485,33 @@ pub fn link<W: Write>(w: &mut W, text: &str, url: &str) -> std::io::Result<()> {
     write!(w, "\x1b]8;;{}\x07{}\x1b]8;;\x07", url, text)
 }
 
+/// Start a hyperlink (OSC 8): text printed until `link_close` links to
+/// `url`. The `id` comes from the url, so the runs of one link (wrapped
+/// lines, a redrawn half) highlight together on hover. Bytes outside
+/// printable ASCII are percent-encoded; an escape can't end the sequence.
+pub fn link_open<W: Write>(w: &mut W, url: &str) -> std::io::Result<()> {
+    let mut encoded = String::with_capacity(url.len());
+    for &byte in url.as_bytes() {
+        if byte.is_ascii_graphic() {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    write!(w, "\x1b]8;id={:x};{}\x1b\\", link_id(url), encoded)
+}
+
+/// End the current hyperlink (OSC 8).
+#[inline]
+pub fn link_close<W: Write>(w: &mut W) -> std::io::Result<()> {
+    w.write_all(b"\x1b]8;;\x1b\\")
+}
+
+/// FNV-1a of the url: stable across frames and runs.
+fn link_id(url: &str) -> u32 {
+    url.bytes().fold(0x811c_9dc5, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
 // =============================================================================
 // Testing Helpers
 // =============================================================================
@@ -21,18 +21,157 @@
 //! │ Progress: ████░░░░ 50%  │
 //! └─────────────────────────┘
 //! ```
+//!
+//! # History
+//!
+//! [`AppendRenderer::println`] freezes a [`StyledLine`] into history: the
+//! active region is erased, the line written where it was, and the last
+//! active frame drawn again below it, all in one synchronized write. The
+//! engine queues lines sent from TS (`spark_println`) and writes them with
+//! the next frame ([`AppendRenderer::render_with_history`]).
+//!
+//! Only rows still on screen can be erased. An active region taller than
+//! the terminal would push its top into scrollback, where the next frame
+//! can't reach it and would leave a stale copy behind, so only its bottom
+//! `height - 1` rows are drawn (the last row holds the cursor).
+//!
+//! # Regions
+//!
+//! The active region can hold several independent trees, one per region
+//! (`N_REGION`): say an interactive prompt above a persistent status footer.
+//! Layout stacks them and [`AppendRenderer::render_regions`] (or, inline,
+//! [`InlineRenderer::render_regions`](super::InlineRenderer::render_regions)) diffs them
+//! separately. While the frame keeps its shape, a region that changed is
+//! rewritten in place and the ones around it aren't touched; one whose
+//! height changed is redrawn along with everything below it.
+//!
+//! # Resize
+//!
+//! Most terminals rewrap what is on screen when they get narrower, so the
+//! active region can occupy more rows than were written. Rows are written
+//! without their trailing blanks (only real content wraps), and their widths
+//! are kept: after [`AppendRenderer::resize`] the erase climbs exactly the
+//! rows they now take, and the history above is never touched.
 
-use std::io::{self, Write};
+use std::io;
+use std::ops::Range;
 
 use super::ansi;
-use super::buffer::FrameBuffer;
-use super::output::{OutputBuffer, StatefulCellRenderer};
+use super::buffer::{string_width, FrameBuffer};
+use super::output::{NativeCursor, OutputBuffer, StatefulCellRenderer};
+use crate::utils::{Attr, Cell, Rgba};
+
+/// A run of text in one style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledSpan {
+    pub text: String,
+    pub fg: Rgba,
+    pub bg: Rgba,
+    pub attrs: Attr,
+}
+
+/// One line of history: styled spans, left to right.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StyledLine {
+    pub spans: Vec<StyledSpan>,
+}
+
+impl StyledLine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A line in the terminal's own colors.
+    pub fn plain(text: &str) -> Self {
+        Self::new().span(text, Rgba::TERMINAL_DEFAULT, Rgba::TERMINAL_DEFAULT, Attr::NONE)
+    }
+
+    /// Add a span. Control characters (a newline included) are dropped: a
+    /// line is one row, however wide it wraps.
+    pub fn span(mut self, text: &str, fg: Rgba, bg: Rgba, attrs: Attr) -> Self {
+        let text = text.chars().filter(|c| !c.is_control()).collect();
+        self.spans.push(StyledSpan { text, fg, bg, attrs });
+        self
+    }
+
+    /// Width in cells.
+    pub fn width(&self) -> u16 {
+        self.spans.iter().map(|span| string_width(&span.text)).sum::<usize>().min(u16::MAX as usize) as u16
+    }
+
+    /// Decode the wire format TS sends (`encodeStyledLine`): per span, fg
+    /// and bg as packed ARGB u32 (0 = terminal default), attrs u8, UTF-8
+    /// length u32 and the text. None if it's malformed.
+    pub fn decode(mut bytes: &[u8]) -> Option<Self> {
+        fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+            let (head, rest) = bytes.split_at_checked(len)?;
+            *bytes = rest;
+            Some(head)
+        }
+        fn take_u32(bytes: &mut &[u8]) -> Option<u32> {
+            take(bytes, 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        }
+        fn color(packed: u32) -> Rgba {
+            if packed == 0 { Rgba::TERMINAL_DEFAULT } else { Rgba::from_u32(packed) }
+        }
+
+        let mut line = Self::new();
+        while !bytes.is_empty() {
+            let fg = color(take_u32(&mut bytes)?);
+            let bg = color(take_u32(&mut bytes)?);
+            let attrs = Attr::from_bits_truncate(take(&mut bytes, 1)?[0]);
+            let len = take_u32(&mut bytes)? as usize;
+            let text = std::str::from_utf8(take(&mut bytes, len)?).ok()?;
+            line = line.span(text, fg, bg, attrs);
+        }
+        Some(line)
+    }
+
+    /// The line as a one-row frame.
+    fn to_buffer(&self) -> FrameBuffer {
+        let mut buffer = FrameBuffer::new(self.width().max(1), 1);
+        let mut x = 0;
+        for span in &self.spans {
+            let bg = (!span.bg.is_terminal_default()).then_some(span.bg);
+            x += buffer.draw_text(x, 0, &span.text, span.fg, bg, span.attrs, None);
+        }
+        buffer
+    }
+}
+
+/// Rows one region covers in a frame (see `framebuffer::region_bands`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionBand {
+    /// N_REGION of the roots drawn here
+    pub region: u8,
+    /// First row
+    pub y: u16,
+    pub height: u16,
+    /// Right edge of its widest root
+    pub width: u16,
+}
 
 /// Append-mode renderer with history and active regions.
 pub struct AppendRenderer {
     output: OutputBuffer,
     cell_renderer: StatefulCellRenderer,
-    previous_active_height: u16,
+    /// Written width of each active row, for finding their top after a reflow
+    active_rows: Vec<u16>,
+    /// Terminal width now (0 = unknown: assume nothing rewrapped)
+    terminal_width: u16,
+    /// Terminal height now (0 = unknown: draw every active row)
+    terminal_height: u16,
+    /// Last frame drawn in the active region, redrawn under new history
+    active: Option<FrameBuffer>,
+    /// Regions of that frame
+    regions: Vec<RegionBand>,
+    /// First of its rows drawn (the ones above didn't fit on screen)
+    first_row: u16,
+    /// Terminal width it was drawn at
+    drawn_width: u16,
+    native_cursor: NativeCursor,
+    /// Active row the native cursor was left on (None = below the region)
+    cursor_row: Option<usize>,
 }
 
 impl AppendRenderer {
@@ -41,100 +180,305 @@ impl AppendRenderer {
         Self {
             output: OutputBuffer::new(),
             cell_renderer: StatefulCellRenderer::new(),
-            previous_active_height: 0,
+            active_rows: Vec::new(),
+            terminal_width: 0,
+            terminal_height: 0,
+            active: None,
+            regions: Vec::new(),
+            first_row: 0,
+            drawn_width: 0,
+            native_cursor: NativeCursor::new(),
+            cursor_row: None,
         }
     }
 
+    /// The buffer the next render writes into, for sequences that should
+    /// reach the terminal with the frame.
+    pub fn output_mut(&mut self) -> &mut OutputBuffer {
+        &mut self.output
+    }
+
     /// Render the active region (updates in place).
     ///
     /// This erases the previous active content and renders new content.
     /// History above remains untouched.
     pub fn render_active(&mut self, buffer: &FrameBuffer) -> io::Result<()> {
-        // Begin synchronized output
-        ansi::begin_sync(&mut self.output)?;
+        self.render_with_history(&[], buffer)
+    }
 
-        // Erase previous active region
-        self.erase_active_internal()?;
+    /// Freeze `line` into history, above the active region.
+    ///
+    /// The active region is redrawn below it as it was.
+    pub fn println(&mut self, line: &StyledLine) -> io::Result<()> {
+        self.println_all(std::slice::from_ref(line))
+    }
 
-        // Reset renderer state
-        self.cell_renderer.reset();
+    /// Freeze several lines into history at once.
+    pub fn println_all(&mut self, lines: &[StyledLine]) -> io::Result<()> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        match self.active.take() {
+            Some(active) => {
+                let regions = std::mem::take(&mut self.regions);
+                self.render_regions(lines, &active, &regions)
+            }
+            None => {
+                self.begin_frame()?;
+                self.write_lines(lines)?;
+                self.end_frame()
+            }
+        }
+    }
+
+    /// Freeze `history` above the active region, then draw `buffer` as the
+    /// new active region, in one write.
+    pub fn render_with_history(&mut self, history: &[StyledLine], buffer: &FrameBuffer) -> io::Result<()> {
+        self.render_regions(history, buffer, &[])
+    }
 
-        // Render buffer content
-        let width = buffer.width();
-        let height = buffer.height();
+    /// [`render_with_history`](Self::render_with_history) for a frame made
+    /// of `regions`, rewriting only the regions that changed when it can.
+    pub fn render_regions(
+        &mut self,
+        history: &[StyledLine],
+        buffer: &FrameBuffer,
+        regions: &[RegionBand],
+    ) -> io::Result<()> {
+        let rows: Vec<u16> = (0..buffer.height()).map(|y| written_width(buffer, y, buffer.width())).collect();
+        let first = first_visible_row(&rows, self.terminal_width, self.terminal_height);
+        let dirty = if history.is_empty() { self.dirty_bands(buffer, regions, &rows, first) } else { None };
 
-        for y in 0..height {
-            for x in 0..width {
-                if let Some(cell) = buffer.get(x, y) {
-                    self.cell_renderer.render_cell_inline(&mut self.output, cell);
+        match dirty {
+            Some(dirty) => {
+                ansi::begin_sync(&mut self.output)?;
+                self.native_cursor.begin(&mut self.output)?;
+                self.leave_cursor_row()?;
+                self.cell_renderer.reset();
+                for range in dirty {
+                    self.redraw_rows(buffer, range, &rows, first)?;
                 }
+                self.finish_rows(buffer, first)?;
+            }
+            None => {
+                self.begin_frame()?;
+                self.write_lines(history)?;
+                self.draw_active(buffer, &rows, first)?;
             }
-            // Newline after each row
-            self.output.write_char('\n');
         }
+        self.active = Some(buffer.clone());
+        self.regions = regions.to_vec();
+        self.first_row = first;
+        self.drawn_width = self.terminal_width;
+        self.end_frame()
+    }
 
-        // Reset attributes
-        ansi::reset(&mut self.output)?;
+    /// Write a line to history (above active region).
+    ///
+    /// The line is written as is (it may carry its own escapes) and the
+    /// active region redrawn below it.
+    pub fn write_history(&mut self, line: &str) -> io::Result<()> {
+        self.write_history_lines(&[line])
+    }
 
-        // End synchronized output
+    /// Write multiple lines to history.
+    pub fn write_history_lines(&mut self, lines: &[&str]) -> io::Result<()> {
+        self.begin_frame()?;
+        for line in lines {
+            self.output.write_str(line);
+            self.output.write_str("\r\n");
+        }
+        if let Some(active) = self.active.take() {
+            let rows: Vec<u16> = (0..active.height()).map(|y| written_width(&active, y, active.width())).collect();
+            let first = first_visible_row(&rows, self.terminal_width, self.terminal_height);
+            self.draw_active(&active, &rows, first)?;
+            self.first_row = first;
+            self.drawn_width = self.terminal_width;
+            self.active = Some(active);
+        }
+        self.end_frame()
+    }
+
+    /// Open a synchronized write and erase the active region.
+    fn begin_frame(&mut self) -> io::Result<()> {
+        ansi::begin_sync(&mut self.output)?;
+        self.native_cursor.begin(&mut self.output)?;
+        self.erase_active_internal()?;
+        self.active_rows.clear();
+        self.cell_renderer.reset();
+        Ok(())
+    }
+
+    fn end_frame(&mut self) -> io::Result<()> {
         ansi::end_sync(&mut self.output)?;
+        self.output.flush_stdout()
+    }
 
-        // Flush
-        self.output.flush_stdout()?;
+    /// Write `lines` where the cursor is, each ending the row.
+    fn write_lines(&mut self, lines: &[StyledLine]) -> io::Result<()> {
+        for line in lines {
+            let row = line.to_buffer();
+            self.write_row(&row, 0, line.width());
+        }
+        self.reset_style()
+    }
 
-        // Track height for next erase
-        self.previous_active_height = height;
+    /// Rows of `buffer` to rewrite over the last frame, band by band; None
+    /// when the frame changed shape and must be drawn whole.
+    fn dirty_bands(&self, buffer: &FrameBuffer, regions: &[RegionBand], rows: &[u16], first: u16) -> Option<Vec<Range<u16>>> {
+        let previous = self.active.as_ref()?;
+        let same_bands = regions.len() == self.regions.len()
+            && regions.iter().zip(&self.regions).all(|(a, b)| (a.y, a.height) == (b.y, b.height));
+        let covered = regions.first().is_some_and(|band| band.y == 0)
+            && regions.last().is_some_and(|band| band.y + band.height == buffer.height());
+        if !same_bands
+            || !covered
+            || (previous.width(), previous.height()) != (buffer.width(), buffer.height())
+            || self.first_row != first
+            || self.drawn_width != self.terminal_width
+        {
+            return None;
+        }
 
-        Ok(())
+        // In place only while no row wraps, before or after (a wrapped row
+        // spans more than the one line erased ahead of it)
+        let unwrapped = |y: u16| {
+            let widest = rows[y as usize].max(self.active_rows[(y - first) as usize]);
+            self.terminal_width == 0 || widest < self.terminal_width
+        };
+        let mut dirty = Vec::new();
+        for band in regions {
+            let range = band.y.max(first)..band.y + band.height;
+            if range.clone().all(|y| rows_equal(previous, buffer, y)) {
+                continue;
+            }
+            if !range.clone().all(unwrapped) {
+                dirty.push(range.start..buffer.height());
+                break;
+            }
+            dirty.push(range);
+        }
+        Some(dirty)
     }
 
-    /// Write a line to history (above active region).
-    ///
-    /// The line is written and the active region redrawn below it.
-    pub fn write_history(&mut self, line: &str) -> io::Result<()> {
-        // Erase active region first
-        self.erase_active()?;
+    /// Rewrite rows `range` of the active region, from below it. Rows to
+    /// the bottom are erased and written afresh; others line by line, in
+    /// place, before returning below the region.
+    fn redraw_rows(&mut self, buffer: &FrameBuffer, range: Range<u16>, rows: &[u16], first: u16) -> io::Result<()> {
+        let start = (range.start - first) as usize;
+        ansi::cursor_up(&mut self.output, wrapped_height(&self.active_rows[start..], self.terminal_width))?;
+        ansi::cursor_column_zero(&mut self.output)?;
 
-        // Write history line
-        let mut stdout = io::stdout().lock();
-        writeln!(stdout, "{}", line)?;
-        stdout.flush()?;
+        if range.end == buffer.height() {
+            self.reset_style()?;
+            ansi::erase_down(&mut self.output)?;
+            self.active_rows.truncate(start);
+            for y in range {
+                self.write_row(buffer, y, rows[y as usize]);
+                self.active_rows.push(rows[y as usize]);
+            }
+            return Ok(());
+        }
 
-        Ok(())
+        for y in range.clone() {
+            // Erase on the terminal's own background, not the last cell's
+            self.reset_style()?;
+            ansi::erase_line(&mut self.output)?;
+            self.write_row(buffer, y, rows[y as usize]);
+            self.active_rows[(y - first) as usize] = rows[y as usize];
+        }
+        let below = &self.active_rows[(range.end - first) as usize..];
+        ansi::cursor_down(&mut self.output, wrapped_height(below, self.terminal_width))
     }
 
-    /// Write multiple lines to history.
-    pub fn write_history_lines(&mut self, lines: &[&str]) -> io::Result<()> {
-        // Erase active region first
-        self.erase_active()?;
+    /// Draw `buffer`'s rows from `first` as the active region.
+    fn draw_active(&mut self, buffer: &FrameBuffer, rows: &[u16], first: u16) -> io::Result<()> {
+        for y in first..buffer.height() {
+            self.write_row(buffer, y, rows[y as usize]);
+            self.active_rows.push(rows[y as usize]);
+        }
+        self.finish_rows(buffer, first)
+    }
 
-        // Write all history lines
-        let mut stdout = io::stdout().lock();
-        for line in lines {
-            writeln!(stdout, "{}", line)?;
+    /// After the rows: reset attributes and climb from below the region to
+    /// the native cursor's cell.
+    fn finish_rows(&mut self, buffer: &FrameBuffer, first: u16) -> io::Result<()> {
+        self.reset_style()?;
+
+        if let Some(cursor) = buffer.cursor().filter(|cursor| cursor.y >= first) {
+            let row = (cursor.y - first) as usize;
+            ansi::cursor_up(&mut self.output, wrapped_height(&self.active_rows[row..], self.terminal_width))?;
+            ansi::cursor_column_zero(&mut self.output)?;
+            ansi::cursor_forward(&mut self.output, cursor.x)?;
+            self.native_cursor.show(&mut self.output, &cursor)?;
+            self.cursor_row = Some(row);
         }
-        stdout.flush()?;
+        Ok(())
+    }
 
+    /// Back to the terminal's default style, with no link open.
+    fn reset_style(&mut self) -> io::Result<()> {
+        self.cell_renderer.set_link(&mut self.output, None);
+        ansi::reset(&mut self.output)?;
+        self.cell_renderer.reset();
         Ok(())
     }
 
+    /// Write the first `row_width` cells of row `y`, then end the row.
+    fn write_row(&mut self, buffer: &FrameBuffer, y: u16, row_width: u16) {
+        for x in 0..row_width {
+            if let Some(cell) = buffer.get(x, y) {
+                self.cell_renderer.set_link(&mut self.output, buffer.link_url(cell.link));
+                self.cell_renderer.render_cell_inline(&mut self.output, cell);
+            }
+        }
+        // Newline after each row (raw mode: no implicit carriage return)
+        self.output.write_str("\r\n");
+    }
+
     /// Erase the active region without rendering new content.
     pub fn erase_active(&mut self) -> io::Result<()> {
-        if self.previous_active_height > 0 {
+        if !self.active_rows.is_empty() {
             ansi::begin_sync(&mut self.output)?;
+            self.native_cursor.begin(&mut self.output)?;
             self.erase_active_internal()?;
             ansi::end_sync(&mut self.output)?;
             self.output.flush_stdout()?;
-            self.previous_active_height = 0;
+            self.active_rows.clear();
         }
         Ok(())
     }
 
+    /// Tell the renderer the terminal is now `width`×`height` cells.
+    ///
+    /// Call on every resize, before the next render or erase.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.terminal_width = width;
+        self.terminal_height = height;
+    }
+
+    /// Back below the region from the native cursor.
+    fn leave_cursor_row(&mut self) -> io::Result<()> {
+        if let Some(row) = self.cursor_row.take() {
+            ansi::cursor_down(&mut self.output, wrapped_height(&self.active_rows[row..], self.terminal_width))?;
+        }
+        Ok(())
+    }
+
+    /// Move the cursor back below the active region (a native cursor leaves
+    /// it inside), for whatever the terminal prints after unmount.
+    pub fn leave(&mut self) -> io::Result<()> {
+        self.leave_cursor_row()?;
+        self.output.flush_stdout()
+    }
+
     /// Internal erase without sync block (for use within render).
     fn erase_active_internal(&mut self) -> io::Result<()> {
-        if self.previous_active_height > 0 {
-            // Move cursor up by previous height
-            ansi::cursor_up(&mut self.output, self.previous_active_height)?;
+        self.leave_cursor_row()?;
+        let height = self.active_height();
+        if height > 0 {
+            // Move cursor up to the first active row, however it was rewrapped
+            ansi::cursor_up(&mut self.output, height)?;
             ansi::cursor_column_zero(&mut self.output)?;
             // Erase from cursor down
             ansi::erase_down(&mut self.output)?;
@@ -142,15 +486,17 @@ impl AppendRenderer {
         Ok(())
     }
 
-    /// Get the height of the current active region.
+    /// Get the height of the current active region, in terminal rows.
     pub fn active_height(&self) -> u16 {
-        self.previous_active_height
+        wrapped_height(&self.active_rows, self.terminal_width)
     }
 
     /// Clear everything and reset state.
     pub fn reset(&mut self) -> io::Result<()> {
         self.erase_active()?;
-        self.previous_active_height = 0;
+        self.active_rows.clear();
+        self.active = None;
+        self.regions.clear();
         self.cell_renderer.reset();
         Ok(())
     }
@@ -173,6 +519,52 @@ impl Default for AppendRenderer {
     }
 }
 
+/// Width of row `y` up to its last visible cell. A space on the terminal's
+/// own background without attributes shows nothing and needn't be written.
+fn written_width(buffer: &FrameBuffer, y: u16, width: u16) -> u16 {
+    let visible = |cell: &Cell| cell.char != ' ' as u32 || !cell.bg.is_terminal_default() || !cell.attrs.is_empty();
+    (0..width)
+        .rev()
+        .find(|&x| buffer.get(x, y).is_some_and(visible))
+        .map_or(0, |x| x + 1)
+}
+
+/// Whether row `y` is the same in both frames, links included.
+pub(super) fn rows_equal(a: &FrameBuffer, b: &FrameBuffer, y: u16) -> bool {
+    (0..a.width()).all(|x| match (a.get(x, y), b.get(x, y)) {
+        (Some(ca), Some(cb)) => ca == cb && (ca.link == 0 || a.link_url(ca.link) == b.link_url(cb.link)),
+        (ca, cb) => ca.is_none() && cb.is_none(),
+    })
+}
+
+/// First row of a frame with rows of the given widths to draw, so the drawn
+/// rows plus the cursor's row below them fit `height` terminal rows.
+fn first_visible_row(rows: &[u16], width: u16, height: u16) -> u16 {
+    if height == 0 {
+        return 0;
+    }
+    let room = height - 1;
+    let mut used = 0;
+    let mut first = rows.len();
+    while first > 0 {
+        let row = wrapped_height(&rows[first - 1..first], width);
+        if used + row > room {
+            break;
+        }
+        used += row;
+        first -= 1;
+    }
+    first as u16
+}
+
+/// Terminal rows taken by rows of the given widths once wrapped at `width`.
+fn wrapped_height(rows: &[u16], width: u16) -> u16 {
+    if width == 0 {
+        return rows.len() as u16;
+    }
+    rows.iter().map(|&row| row.div_ceil(width).max(1)).sum()
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -186,4 +578,124 @@ mod tests {
         let renderer = AppendRenderer::new();
         assert_eq!(renderer.active_height(), 0);
     }
+
+    #[test]
+    fn test_wrapped_height_after_narrowing() {
+        // Rows written 40, 12 and 0 wide
+        let rows = [40, 12, 0];
+        assert_eq!(wrapped_height(&rows, 0), 3);
+        assert_eq!(wrapped_height(&rows, 80), 3);
+        // Narrowed to 20: the 40-wide row rewraps onto two
+        assert_eq!(wrapped_height(&rows, 20), 4);
+        assert_eq!(wrapped_height(&rows, 10), 7);
+    }
+
+    #[test]
+    fn test_rows_written_without_trailing_blanks() {
+        let mut buffer = FrameBuffer::new(10, 2);
+        buffer.draw_text(0, 0, "abc", crate::utils::Rgba::TERMINAL_DEFAULT, None, crate::utils::Attr::NONE, None);
+        assert_eq!(written_width(&buffer, 0, 10), 3);
+        assert_eq!(written_width(&buffer, 1, 10), 0);
+    }
+
+    #[test]
+    fn test_styled_line_decode() {
+        let span = |fg: u32, bg: u32, attrs: u8, text: &str| {
+            let mut bytes = Vec::new();
+            bytes.extend_from_slice(&fg.to_le_bytes());
+            bytes.extend_from_slice(&bg.to_le_bytes());
+            bytes.push(attrs);
+            bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(text.as_bytes());
+            bytes
+        };
+        let mut bytes = span(0xFF00FF00, 0, Attr::BOLD.bits(), "[PASS] ");
+        bytes.extend(span(0, 0, 0, "test_foo\n"));
+
+        let line = StyledLine::decode(&bytes).unwrap();
+        assert_eq!(line.spans.len(), 2);
+        assert_eq!(line.spans[0].fg, Rgba::from_u32(0xFF00FF00));
+        assert_eq!(line.spans[0].attrs, Attr::BOLD);
+        assert!(line.spans[1].fg.is_terminal_default());
+        assert_eq!(line.spans[1].text, "test_foo", "control characters dropped");
+        assert_eq!(line.width(), 15);
+        assert_eq!(StyledLine::decode(&[]), Some(StyledLine::new()));
+
+        // Cut short, or text that isn't UTF-8
+        assert_eq!(StyledLine::decode(&bytes[..bytes.len() - 1]), None);
+        let mut bad = span(0, 0, 0, "ab");
+        *bad.last_mut().unwrap() = 0xFF;
+        assert_eq!(StyledLine::decode(&bad), None);
+    }
+
+    #[test]
+    fn test_regions_redraw_independently() {
+        use crate::renderer::output::set_terminal_output;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for Capture {
+            fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(bytes);
+                Ok(bytes.len())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let capture = Capture::default();
+        set_terminal_output(Some(Box::new(capture.clone())));
+        let take = || String::from_utf8(std::mem::take(&mut *capture.0.lock().unwrap())).unwrap();
+
+        // Prompt (rows 0-1) above a status footer (row 2)
+        let regions = [
+            RegionBand { region: 0, y: 0, height: 2, width: 20 },
+            RegionBand { region: 1, y: 2, height: 1, width: 20 },
+        ];
+        let frame = |prompt: &str, status: &str| {
+            let mut buffer = FrameBuffer::new(20, 3);
+            buffer.draw_text(0, 0, "Name?", Rgba::TERMINAL_DEFAULT, None, Attr::NONE, None);
+            buffer.draw_text(0, 1, prompt, Rgba::TERMINAL_DEFAULT, None, Attr::NONE, None);
+            buffer.draw_text(0, 2, status, Rgba::TERMINAL_DEFAULT, None, Attr::NONE, None);
+            buffer
+        };
+        let mut renderer = AppendRenderer::new();
+        renderer.resize(40, 10);
+        renderer.render_regions(&[], &frame("> ", "idle"), &regions).unwrap();
+        assert!(take().contains("Name?"));
+
+        // Only the footer changed: the prompt isn't rewritten
+        renderer.render_regions(&[], &frame("> ", "busy"), &regions).unwrap();
+        let out = take();
+        assert!(out.contains("busy") && !out.contains("Name?"), "{out:?}");
+
+        // Only the prompt changed: rewritten in place, the footer left alone
+        renderer.render_regions(&[], &frame("> ab", "busy"), &regions).unwrap();
+        let out = take();
+        assert!(out.contains("ab") && out.contains("\x1b[2K") && !out.contains("busy"), "{out:?}");
+        assert!(!out.contains("\x1b[J"), "nothing below erased: {out:?}");
+
+        // Regions changed shape: everything drawn again
+        let grown = [regions[0], RegionBand { height: 2, ..regions[1] }];
+        let mut tall = FrameBuffer::new(20, 4);
+        tall.draw_text(0, 0, "Name?", Rgba::TERMINAL_DEFAULT, None, Attr::NONE, None);
+        renderer.render_regions(&[], &tall, &grown).unwrap();
+        assert!(take().contains("Name?"));
+
+        set_terminal_output(None);
+    }
+
+    #[test]
+    fn test_active_region_keeps_bottom_rows_on_screen() {
+        let rows = [5, 5, 5, 5, 5];
+        // Unknown height, or room for all rows plus the cursor's
+        assert_eq!(first_visible_row(&rows, 0, 0), 0);
+        assert_eq!(first_visible_row(&rows, 80, 6), 0);
+        // 4 rows tall: the bottom 3 drawn, the top ones dropped
+        assert_eq!(first_visible_row(&rows, 80, 4), 2);
+        // A row wrapping onto two counts twice
+        assert_eq!(first_visible_row(&[5, 5, 30, 5], 20, 4), 2);
+        assert_eq!(first_visible_row(&rows, 80, 1), 5);
+    }
 }
@@ -9,9 +9,14 @@
 //! - **Clipping**: All drawing functions accept an optional `ClipRect` for overflow:hidden.
 //! - **Alpha blending**: Transparent backgrounds blend with existing cells.
 //! - **Wide characters**: Emoji and CJK characters use continuation markers.
+//! - **Hyperlinks**: Cells carry an id into the frame's link table. Drawing
+//!   stamps the current link (`set_link`) on every cell it touches.
+//! - **Native cursor**: A frame can ask for the terminal's own cursor at a
+//!   cell (`set_cursor`) instead of painting one; renderers place it.
 
+use super::ansi::CursorShape;
 use crate::shared_buffer::BorderStyle;
-use crate::utils::{Attr, Cell, ClipRect, Rgba};
+use crate::utils::{control_picture, is_control, Attr, Cell, ClipRect, Rgba};
 
 // =============================================================================
 // FrameBuffer
@@ -25,6 +30,21 @@ pub struct FrameBuffer {
     width: u16,
     height: u16,
     cells: Vec<Cell>,
+    /// Link targets; cell link `n` is `links[n - 1]`.
+    links: Vec<String>,
+    /// Link stamped on drawn cells, 0 = none.
+    link: u16,
+    /// Where the terminal's cursor shows this frame, None = hidden.
+    cursor: Option<TerminalCursor>,
+}
+
+/// The terminal's own cursor, as a frame places it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalCursor {
+    pub x: u16,
+    pub y: u16,
+    pub shape: CursorShape,
+    pub blinking: bool,
 }
 
 impl FrameBuffer {
@@ -35,6 +55,9 @@ impl FrameBuffer {
             width,
             height,
             cells: vec![Cell::default(); size],
+            links: Vec::new(),
+            link: 0,
+            cursor: None,
         }
     }
 
@@ -46,11 +69,15 @@ impl FrameBuffer {
             fg: Rgba::TERMINAL_DEFAULT,
             bg,
             attrs: Attr::NONE,
+            link: 0,
         };
         Self {
             width,
             height,
             cells: vec![cell; size],
+            links: Vec::new(),
+            link: 0,
+            cursor: None,
         }
     }
 
@@ -131,6 +158,20 @@ impl FrameBuffer {
         for cell in &mut self.cells {
             *cell = Cell::default();
         }
+        self.links.clear();
+        self.link = 0;
+        self.cursor = None;
+    }
+
+    /// Clear rows `y..y + height` to default cells, along with the link
+    /// table and the cursor. Cells of the other rows are left as they are.
+    pub fn clear_rows(&mut self, y: u16, height: u16) {
+        let start = self.index(0, y.min(self.height));
+        let end = self.index(0, y.saturating_add(height).min(self.height));
+        self.cells[start..end].fill(Cell::default());
+        self.links.clear();
+        self.link = 0;
+        self.cursor = None;
     }
 
     /// Clear with a specific background color.
@@ -140,7 +181,11 @@ impl FrameBuffer {
             cell.fg = Rgba::TERMINAL_DEFAULT;
             cell.bg = bg;
             cell.attrs = Attr::NONE;
+            cell.link = 0;
         }
+        self.links.clear();
+        self.link = 0;
+        self.cursor = None;
     }
 
     /// Resize the buffer (clears content).
@@ -152,6 +197,86 @@ impl FrameBuffer {
         self.clear();
     }
 
+    /// Copy rows `y..y + height` from a buffer of the same size, linking the
+    /// copied cells to the same targets in this buffer's link table.
+    pub fn copy_rows(&mut self, source: &FrameBuffer, y: u16, height: u16) {
+        debug_assert_eq!((self.width, self.height), (source.width, source.height));
+        let ids: Vec<u16> = source.links.iter().map(|url| self.intern_link(url)).collect();
+        let start = self.index(0, y.min(self.height));
+        let end = self.index(0, y.saturating_add(height).min(self.height));
+        for (target, cell) in self.cells[start..end].iter_mut().zip(&source.cells[start..end]) {
+            let link = (cell.link as usize).checked_sub(1).map_or(0, |i| ids[i]);
+            *target = Cell { link, ..*cell };
+        }
+    }
+
+    // =========================================================================
+    // Native cursor
+    // =========================================================================
+
+    /// Where the terminal's cursor should show, None to keep it hidden.
+    #[inline]
+    pub fn cursor(&self) -> Option<TerminalCursor> {
+        self.cursor
+    }
+
+    /// Show the terminal's cursor at a cell this frame (ignored off the
+    /// buffer), or hide it with None.
+    pub fn set_cursor(&mut self, cursor: Option<TerminalCursor>) {
+        self.cursor = cursor.filter(|c| c.x < self.width && c.y < self.height);
+    }
+
+    // =========================================================================
+    // Hyperlinks
+    // =========================================================================
+
+    /// Id for `url` in this frame's link table, adding it if new. Returns
+    /// 0 (no link) for an empty url or once the table is full.
+    pub fn intern_link(&mut self, url: &str) -> u16 {
+        if url.is_empty() {
+            return 0;
+        }
+        if let Some(pos) = self.links.iter().position(|link| link == url) {
+            return pos as u16 + 1;
+        }
+        if self.links.len() >= u16::MAX as usize {
+            return 0;
+        }
+        self.links.push(url.to_string());
+        self.links.len() as u16
+    }
+
+    /// Target of link `id`, None for 0 or an unknown id.
+    #[inline]
+    pub fn link_url(&self, id: u16) -> Option<&str> {
+        (id as usize).checked_sub(1).and_then(|i| self.links.get(i)).map(String::as_str)
+    }
+
+    /// The link drawing currently stamps on cells.
+    #[inline]
+    pub fn link(&self) -> u16 {
+        self.link
+    }
+
+    /// Stamp link `id` (0 = none) on the cells drawn from now on.
+    #[inline]
+    pub fn set_link(&mut self, id: u16) {
+        self.link = id;
+    }
+
+    /// Link every cell of a rectangle to `id`, whether or not anything is
+    /// drawn there (clipped to the buffer).
+    pub fn link_rect(&mut self, x: u16, y: u16, width: u16, height: u16, id: u16) {
+        let x2 = x.saturating_add(width).min(self.width);
+        let y2 = y.saturating_add(height).min(self.height);
+        for row in y..y2 {
+            for col in x..x2 {
+                let idx = self.index(col, row);
+                self.cells[idx].link = id;
+            }
+        }
+    }
+
     // =========================================================================
     // Drawing Primitives
     // =========================================================================
@@ -191,10 +316,15 @@ impl FrameBuffer {
             Rgba::blend(bg, cell.bg)
         };
 
-        cell.char = char;
+        // A raw control would be written straight to the terminal; 0 stays the continuation marker
+        cell.char = match char::from_u32(char) {
+            Some(ch) if char != 0 && is_control(ch) => control_picture(ch) as u32,
+            _ => char,
+        };
         cell.fg = fg;
         cell.bg = blended_bg;
         cell.attrs = attrs;
+        cell.link = self.link;
 
         true
     }
@@ -231,6 +361,7 @@ impl FrameBuffer {
 
         // Fast path for opaque fill
         let is_opaque = bg.is_opaque() || bg.is_terminal_default() || bg.is_ansi();
+        let link = self.link;
 
         for row in y1..y2 {
             let row_start = self.index(x1, row);
@@ -243,6 +374,7 @@ impl FrameBuffer {
                 }
                 cell.char = b' ' as u32;
                 cell.attrs = Attr::NONE;
+                cell.link = link;
             }
         }
     }
@@ -294,6 +426,7 @@ impl FrameBuffer {
                 // Handle wide characters (emoji, CJK)
                 if char_width == 2 && col + 1 < self.width {
                     // Mark next cell as continuation (char = 0)
+                    let link = self.link;
                     if let Some(next) = self.get_mut(col + 1, y) {
                         if clip.map_or(true, |c| c.contains(col + 1, y)) {
                             next.char = 0; // Continuation marker
@@ -302,6 +435,7 @@ impl FrameBuffer {
                                 next.bg = Rgba::blend(bg, next.bg);
                             }
                             next.attrs = attrs;
+                            next.link = link;
                         }
                     }
                 }
@@ -780,6 +914,17 @@ mod tests {
         assert_eq!(cell.attrs, Attr::BOLD);
     }
 
+    #[test]
+    fn test_set_cell_never_stores_controls() {
+        let mut buffer = FrameBuffer::new(3, 1);
+        buffer.set_cell(0, 0, 0x1B, Rgba::RED, Rgba::BLACK, Attr::NONE, None);
+        buffer.set_cell(1, 0, 0x9B, Rgba::RED, Rgba::BLACK, Attr::NONE, None);
+        buffer.set_cell(2, 0, 0, Rgba::RED, Rgba::BLACK, Attr::NONE, None);
+        assert_eq!(buffer.get(0, 0).unwrap().char, '␛' as u32);
+        assert_eq!(buffer.get(1, 0).unwrap().char, '\u{FFFD}' as u32);
+        assert_eq!(buffer.get(2, 0).unwrap().char, 0);
+    }
+
     #[test]
     fn test_framebuffer_fill_rect() {
         let mut buffer = FrameBuffer::new(20, 20);
@@ -804,6 +949,23 @@ mod tests {
         assert_eq!(buffer.get(4, 0).unwrap().char, 'o' as u32);
     }
 
+    #[test]
+    fn test_copy_rows_reinterns_links() {
+        let mut target = FrameBuffer::new(4, 3);
+        target.intern_link("https://a.example");
+        let mut source = FrameBuffer::new(4, 3);
+        let link = source.intern_link("https://b.example");
+        source.set_link(link);
+        source.draw_text(0, 0, "top", Rgba::WHITE, None, Attr::NONE, None);
+        source.draw_text(0, 1, "mid", Rgba::WHITE, None, Attr::NONE, None);
+
+        target.copy_rows(&source, 1, 1);
+        assert_eq!(target.get(0, 0).unwrap().char, ' ' as u32);
+        let copied = target.get(0, 1).unwrap();
+        assert_eq!(copied.char, 'm' as u32);
+        assert_eq!(target.link_url(copied.link), Some("https://b.example"));
+    }
+
     #[test]
     fn test_char_width() {
         assert_eq!(char_width('a'), 1);
@@ -0,0 +1,168 @@
+//! Asciinema recording — tee terminal output into a `.cast` file.
+//!
+//! While a recording is running, everything `TerminalOut` writes on this
+//! engine thread is also appended to an asciinema v2 cast with its time
+//! since the recording started, and terminal resizes become resize events:
+//!
+//! ```text
+//! {"version": 2, "width": 80, "height": 24, "timestamp": 1760000000}
+//! [0.000000, "o", "\u001b[?1049h\u001b[H..."]
+//! [0.016802, "o", "\u001b[3;5HHello"]
+//! [1.204113, "r", "100x30"]
+//! ```
+//!
+//! The file plays back with `asciinema play`, or in the web player. The
+//! first frame after starting is a full repaint, so the recording opens on
+//! the whole screen rather than on a diff against a screen it never saw.
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+thread_local! {
+    /// This engine thread's recording, if one is running.
+    static RECORDING: RefCell<Option<CastRecorder<BufWriter<File>>>> = const { RefCell::new(None) };
+}
+
+/// Start recording this thread's terminal output to `path` (replacing any
+/// recording already running). `width`×`height` is the terminal size now.
+pub fn record_cast(path: &Path, width: u16, height: u16) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let recorder = CastRecorder::new(file, width, height, timestamp, Instant::now())?;
+    stop_cast()?;
+    RECORDING.with(|r| *r.borrow_mut() = Some(recorder));
+    Ok(())
+}
+
+/// Finish this thread's recording, if any, flushing it to disk.
+pub fn stop_cast() -> io::Result<()> {
+    match RECORDING.with(|r| r.borrow_mut().take()) {
+        Some(recorder) => recorder.finish().map(drop),
+        None => Ok(()),
+    }
+}
+
+/// Append terminal output to the recording, if one is running. A write
+/// error ends the recording rather than the frame.
+pub(super) fn tee(bytes: &[u8]) {
+    record(|recorder| recorder.output(bytes, Instant::now()));
+}
+
+/// Record a terminal resize, if a recording is running.
+pub fn resize(width: u16, height: u16) {
+    record(|recorder| recorder.resize(width, height, Instant::now()));
+}
+
+fn record(event: impl FnOnce(&mut CastRecorder<BufWriter<File>>) -> io::Result<()>) {
+    RECORDING.with(|r| {
+        let mut recording = r.borrow_mut();
+        if let Some(recorder) = recording.as_mut()
+            && let Err(e) = event(recorder)
+        {
+            eprintln!("[spark-engine] Cast recording stopped: {}", e);
+            *recording = None;
+        }
+    });
+}
+
+/// Writes asciinema v2 events to `out`.
+#[derive(Debug)]
+pub struct CastRecorder<W: Write> {
+    out: W,
+    start: Instant,
+    /// Tail of a UTF-8 character split across writes.
+    partial: Vec<u8>,
+}
+
+impl<W: Write> CastRecorder<W> {
+    /// Write the header; events are timed from `start`.
+    pub fn new(mut out: W, width: u16, height: u16, timestamp: u64, start: Instant) -> io::Result<Self> {
+        writeln!(out, "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}}}", width, height, timestamp)?;
+        Ok(Self { out, start, partial: Vec::new() })
+    }
+
+    /// Output event. Bytes of a character cut off at the end wait for the
+    /// next write, so every event holds whole characters.
+    pub fn output(&mut self, bytes: &[u8], now: Instant) -> io::Result<()> {
+        self.partial.extend_from_slice(bytes);
+        let complete = match std::str::from_utf8(&self.partial) {
+            Ok(_) => self.partial.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            // Invalid bytes: let from_utf8_lossy replace them
+            Err(_) => self.partial.len(),
+        };
+        if complete == 0 {
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&self.partial[..complete]).into_owned();
+        self.partial.drain(..complete);
+        self.event(now, "o", &text)
+    }
+
+    /// Resize event.
+    pub fn resize(&mut self, width: u16, height: u16, now: Instant) -> io::Result<()> {
+        self.event(now, "r", &format!("{}x{}", width, height))
+    }
+
+    /// Flush and hand back the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn event(&mut self, now: Instant, code: &str, data: &str) -> io::Result<()> {
+        let time = now.saturating_duration_since(self.start).as_secs_f64();
+        writeln!(self.out, "[{:.6}, \"{}\", \"{}\"]", time, code, json_escape(data))
+    }
+}
+
+/// Escape `s` for a JSON string literal.
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{7f}' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cast_events() {
+        let start = Instant::now();
+        let mut cast = CastRecorder::new(Vec::new(), 80, 24, 1_760_000_000, start).unwrap();
+        cast.output(b"\x1b[1;1H\"hi\"\n", start).unwrap();
+        cast.resize(100, 30, start + Duration::from_millis(1500)).unwrap();
+        let out = String::from_utf8(cast.finish().unwrap()).unwrap();
+
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "{\"version\": 2, \"width\": 80, \"height\": 24, \"timestamp\": 1760000000}");
+        assert_eq!(lines[1], "[0.000000, \"o\", \"\\u001b[1;1H\\\"hi\\\"\\n\"]");
+        assert_eq!(lines[2], "[1.500000, \"r\", \"100x30\"]");
+    }
+
+    #[test]
+    fn test_split_characters_wait_for_the_rest() {
+        let start = Instant::now();
+        let mut cast = CastRecorder::new(Vec::new(), 10, 1, 0, start).unwrap();
+        let bytes = "é".as_bytes();
+        cast.output(&bytes[..1], start).unwrap();
+        cast.output(&bytes[1..], start).unwrap();
+        let out = String::from_utf8(cast.finish().unwrap()).unwrap();
+        assert_eq!(out.lines().skip(1).collect::<Vec<_>>(), vec!["[0.000000, \"o\", \"é\"]"]);
+    }
+}
@@ -9,15 +9,17 @@
 //! 1. Wrap output in synchronized block (beginSync/endSync)
 //! 2. For each cell in the new frame:
 //!    - If previous frame exists and cell is unchanged: skip
-//!    - Otherwise: render cell with StatefulCellRenderer
-//! 3. Flush output buffer (single syscall)
-//! 4. Store current frame as previous for next comparison
+//!    - Otherwise: render cell with StatefulCellRenderer, inside its
+//!      hyperlink if it has one
+//! 3. Move the terminal's cursor to the frame's native cursor, if it has one
+//! 4. Flush output buffer (single syscall)
+//! 5. Store current frame as previous for next comparison
 
 use std::io;
 
 use super::ansi;
 use super::buffer::FrameBuffer;
-use super::output::{OutputBuffer, StatefulCellRenderer};
+use super::output::{NativeCursor, OutputBuffer, StatefulCellRenderer};
 use crate::utils::{Cell, Rgba};
 
 /// Differential renderer for fullscreen mode.
@@ -28,6 +30,9 @@ pub struct DiffRenderer {
     output: OutputBuffer,
     cell_renderer: StatefulCellRenderer,
     previous: Option<FrameBuffer>,
+    native_cursor: NativeCursor,
+    /// Cells the last `render` wrote.
+    cells_written: u32,
 }
 
 impl DiffRenderer {
@@ -37,17 +42,32 @@ impl DiffRenderer {
             output: OutputBuffer::new(),
             cell_renderer: StatefulCellRenderer::new(),
             previous: None,
+            native_cursor: NativeCursor::new(),
+            cells_written: 0,
         }
     }
 
+    /// The buffer the next render writes into, for sequences that should
+    /// reach the terminal with the frame.
+    pub fn output_mut(&mut self) -> &mut OutputBuffer {
+        &mut self.output
+    }
+
+    /// Cells the last `render` wrote to the terminal.
+    pub fn cells_written(&self) -> u32 {
+        self.cells_written
+    }
+
     /// Render a frame, outputting only changed cells.
     ///
     /// Returns true if any cells were changed.
     pub fn render(&mut self, buffer: &FrameBuffer) -> io::Result<bool> {
         let mut has_changes = false;
+        self.cells_written = 0;
 
         // Begin synchronized output
         ansi::begin_sync(&mut self.output)?;
+        self.native_cursor.begin(&mut self.output)?;
 
         // Reset renderer state for new frame
         self.cell_renderer.reset();
@@ -73,7 +93,9 @@ impl DiffRenderer {
                 let changed = match &self.previous {
                     Some(prev) if prev.width() == width && prev.height() == height => {
                         match prev.get(x, y) {
-                            Some(prev_cell) => !cells_equal(cell, prev_cell),
+                            Some(prev_cell) => {
+                                !cells_equal(cell, prev_cell) || buffer.link_url(cell.link) != prev.link_url(prev_cell.link)
+                            }
                             None => true,
                         }
                     }
@@ -82,6 +104,8 @@ impl DiffRenderer {
 
                 if changed {
                     has_changes = true;
+                    self.cells_written += 1;
+                    self.cell_renderer.set_link(&mut self.output, buffer.link_url(cell.link));
                     self.cell_renderer.render_cell(&mut self.output, x, y, cell);
                 }
             }
@@ -91,8 +115,16 @@ impl DiffRenderer {
         // This ensures the terminal starts next frame in a known state (no attributes).
         // Without this, attributes from the last rendered cell leak into the next frame
         // because we skip unchanged cells and don't re-emit resets.
+        // An open hyperlink would leak the same way.
+        self.cell_renderer.set_link(&mut self.output, None);
         ansi::reset(&mut self.output)?;
 
+        // A moved cursor is a change even when no cell is
+        if self.previous.as_ref().is_some_and(|prev| prev.cursor() != buffer.cursor()) {
+            has_changes = true;
+        }
+        self.place_cursor(buffer)?;
+
         // End synchronized output
         ansi::end_sync(&mut self.output)?;
 
@@ -111,6 +143,7 @@ impl DiffRenderer {
     pub fn render_full(&mut self, buffer: &FrameBuffer) -> io::Result<()> {
         // Begin synchronized output
         ansi::begin_sync(&mut self.output)?;
+        self.native_cursor.begin(&mut self.output)?;
 
         // Move to home position
         ansi::cursor_to(&mut self.output, 0, 0)?;
@@ -125,13 +158,16 @@ impl DiffRenderer {
         for y in 0..height {
             for x in 0..width {
                 if let Some(cell) = buffer.get(x, y) {
+                    self.cell_renderer.set_link(&mut self.output, buffer.link_url(cell.link));
                     self.cell_renderer.render_cell(&mut self.output, x, y, cell);
                 }
             }
         }
 
         // Reset terminal state at end of frame
+        self.cell_renderer.set_link(&mut self.output, None);
         ansi::reset(&mut self.output)?;
+        self.place_cursor(buffer)?;
 
         // End synchronized output
         ansi::end_sync(&mut self.output)?;
@@ -145,6 +181,15 @@ impl DiffRenderer {
         Ok(())
     }
 
+    /// Show the terminal's cursor at the frame's native cursor, if any.
+    fn place_cursor(&mut self, buffer: &FrameBuffer) -> io::Result<()> {
+        if let Some(cursor) = buffer.cursor() {
+            ansi::cursor_to(&mut self.output, cursor.x, cursor.y)?;
+            self.native_cursor.show(&mut self.output, &cursor)?;
+        }
+        Ok(())
+    }
+
     /// Invalidate the previous frame.
     ///
     /// Next render will be a full redraw.
@@ -246,6 +291,7 @@ mod tests {
             fg: Rgba::WHITE,
             bg: Rgba::BLACK,
             attrs: Attr::BOLD,
+            link: 0,
         };
         let b = a;
         assert!(cells_equal(&a, &b));
@@ -297,6 +343,7 @@ mod tests {
             fg: Rgba::from_u32(0xFFFFFFFF),
             bg: Rgba::from_u32(0xFFFFFFFF),
             attrs: Attr::NONE,
+            link: 0,
         };
 
         // These should be considered equal (same semantic meaning)
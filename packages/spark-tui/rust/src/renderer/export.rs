@@ -0,0 +1,361 @@
+//! Frame exporters — serialize a FrameBuffer outside the terminal.
+//!
+//! - [`FrameBuffer::to_text`]: characters only, trailing blanks trimmed.
+//! - [`FrameBuffer::to_html`]: a standalone page with colors and attributes.
+//! - [`FrameBuffer::to_svg`]: a vector image, crisp at any size in docs.
+//! - [`FrameBuffer::to_png`]: a bitmap image, see [`super::raster`].
+//!
+//! Terminal-default colors become `DEFAULT_FG` on `DEFAULT_BG` (left to the
+//! page in HTML); ANSI palette colors resolve through the xterm palette.
+
+use std::fmt::Write as _;
+
+use super::buffer::FrameBuffer;
+use super::raster::{self, Canvas, Rgb};
+use crate::utils::{Attr, Cell, Rgba};
+
+/// Page colors standing in for the terminal's default foreground/background.
+const DEFAULT_FG: Rgb = (0xd0, 0xd0, 0xd0);
+const DEFAULT_BG: Rgb = (0x10, 0x10, 0x10);
+
+/// Dimmed text is drawn at this weight over its background.
+const DIM_WEIGHT: f32 = 0.6;
+
+/// SVG cell size in user units; text runs are stretched to fit exactly.
+const SVG_CELL_W: f32 = 9.6;
+const SVG_CELL_H: f32 = 20.0;
+/// Text baseline and font size within an SVG cell.
+const SVG_BASELINE: f32 = 15.0;
+const SVG_FONT_SIZE: f32 = 16.0;
+
+/// xterm's 16 base colors.
+const ANSI_BASE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Channel levels of the 6×6×6 color cube (indices 16-231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// RGB for an ANSI palette index.
+fn ansi_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_BASE[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            (CUBE_LEVELS[(i / 36) as usize], CUBE_LEVELS[(i / 6 % 6) as usize], CUBE_LEVELS[(i % 6) as usize])
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// RGB for a cell color; `None` for terminal default and transparent.
+fn resolve_rgb(color: Rgba) -> Option<Rgb> {
+    if color.is_terminal_default() || color.is_transparent() {
+        return None;
+    }
+    Some(if color.is_ansi() {
+        ansi_rgb(color.ansi_index())
+    } else {
+        (color.r as u8, color.g as u8, color.b as u8)
+    })
+}
+
+fn hex((r, g, b): Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// CSS color for a cell color; `None` for terminal default and transparent.
+fn css_color(color: Rgba) -> Option<String> {
+    resolve_rgb(color).map(hex)
+}
+
+/// Final pixel colors of a cell, defaults filled in and inverse/dim applied.
+fn cell_rgb(cell: &Cell) -> (Rgb, Rgb) {
+    let mut fg = resolve_rgb(cell.fg).unwrap_or(DEFAULT_FG);
+    let mut bg = resolve_rgb(cell.bg).unwrap_or(DEFAULT_BG);
+    if cell.attrs.contains(Attr::INVERSE) {
+        (fg, bg) = (bg, fg);
+    }
+    if cell.attrs.contains(Attr::DIM) {
+        fg = raster::blend(fg, bg, DIM_WEIGHT);
+    }
+    (fg, bg)
+}
+
+/// Inline style for a run of cells sharing fg/bg/attrs.
+fn css_style(cell: &Cell) -> String {
+    let (mut fg, mut bg) = (css_color(cell.fg), css_color(cell.bg));
+    if cell.attrs.contains(Attr::INVERSE) {
+        (fg, bg) = (
+            Some(bg.unwrap_or_else(|| hex(DEFAULT_BG))),
+            Some(fg.unwrap_or_else(|| hex(DEFAULT_FG))),
+        );
+    }
+
+    let mut style = String::new();
+    if cell.attrs.contains(Attr::HIDDEN) {
+        style.push_str("color:transparent;");
+    } else if let Some(fg) = fg {
+        let _ = write!(style, "color:{fg};");
+    }
+    if let Some(bg) = bg {
+        let _ = write!(style, "background:{bg};");
+    }
+    if cell.attrs.contains(Attr::BOLD) {
+        style.push_str("font-weight:bold;");
+    }
+    if cell.attrs.contains(Attr::DIM) {
+        style.push_str("opacity:0.6;");
+    }
+    if cell.attrs.contains(Attr::ITALIC) {
+        style.push_str("font-style:italic;");
+    }
+    match (cell.attrs.contains(Attr::UNDERLINE), cell.attrs.contains(Attr::STRIKETHROUGH)) {
+        (true, true) => style.push_str("text-decoration:underline line-through;"),
+        (true, false) => style.push_str("text-decoration:underline;"),
+        (false, true) => style.push_str("text-decoration:line-through;"),
+        (false, false) => {}
+    }
+    style
+}
+
+fn push_escaped(out: &mut String, ch: char) {
+    match ch {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        _ => out.push(ch),
+    }
+}
+
+/// `cells` SVG cells of `size` each, rounded so coordinates print cleanly.
+fn svg_units(cells: usize, size: f32) -> f32 {
+    (cells as f32 * size * 100.0).round() / 100.0
+}
+
+/// SVG text attributes for a run of cells sharing fg/attrs.
+fn svg_text_attrs(cell: &Cell, fg: Rgb) -> String {
+    let mut attrs = format!("fill=\"{}\"", hex(fg));
+    if cell.attrs.contains(Attr::BOLD) {
+        attrs.push_str(" font-weight=\"bold\"");
+    }
+    if cell.attrs.contains(Attr::ITALIC) {
+        attrs.push_str(" font-style=\"italic\"");
+    }
+    match (cell.attrs.contains(Attr::UNDERLINE), cell.attrs.contains(Attr::STRIKETHROUGH)) {
+        (true, true) => attrs.push_str(" text-decoration=\"underline line-through\""),
+        (true, false) => attrs.push_str(" text-decoration=\"underline\""),
+        (false, true) => attrs.push_str(" text-decoration=\"line-through\""),
+        (false, false) => {}
+    }
+    attrs
+}
+
+/// Printable character of a cell; `None` for wide-character continuations.
+fn cell_char(cell: &Cell) -> Option<char> {
+    if cell.char == 0 {
+        return None;
+    }
+    Some(char::from_u32(cell.char).unwrap_or(' '))
+}
+
+impl FrameBuffer {
+    /// Plain text of the frame, one line per row, trailing spaces trimmed.
+    pub fn to_text(&self) -> String {
+        let width = self.width() as usize;
+        let mut out = String::with_capacity(self.cells().len() + self.height() as usize);
+        for row in self.cells().chunks(width.max(1)) {
+            let line: String = row.iter().filter_map(cell_char).collect();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Standalone HTML page reproducing the frame's colors and attributes.
+    pub fn to_html(&self) -> String {
+        let width = self.width() as usize;
+        let (fg, bg) = (hex(DEFAULT_FG), hex(DEFAULT_BG));
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>spark-tui</title>\n\
+             <style>pre {{ color: {fg}; background: {bg}; \
+             font-family: ui-monospace, monospace; line-height: 1.2; padding: 1em; }}</style>\n\
+             </head>\n<body>\n<pre>"
+        );
+
+        for row in self.cells().chunks(width.max(1)) {
+            let mut run: Option<&Cell> = None;
+            for cell in row {
+                let Some(ch) = cell_char(cell) else { continue };
+                let same_style = run.is_some_and(|r| r.fg == cell.fg && r.bg == cell.bg && r.attrs == cell.attrs);
+                if !same_style {
+                    if run.is_some() {
+                        out.push_str("</span>");
+                    }
+                    let _ = write!(out, "<span style=\"{}\">", css_style(cell));
+                    run = Some(cell);
+                }
+                push_escaped(&mut out, ch);
+            }
+            if run.is_some() {
+                out.push_str("</span>");
+            }
+            out.push('\n');
+        }
+
+        out.push_str("</pre>\n</body>\n</html>\n");
+        out
+    }
+
+    /// SVG image of the frame: one rect per background run, one text
+    /// element per styled run, each stretched over its exact cells.
+    pub fn to_svg(&self) -> String {
+        let width = self.width() as usize;
+        let (px_w, px_h) = (svg_units(width, SVG_CELL_W), svg_units(self.height() as usize, SVG_CELL_H));
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{px_w}\" height=\"{px_h}\" \
+             viewBox=\"0 0 {px_w} {px_h}\" font-family=\"ui-monospace, monospace\" font-size=\"{SVG_FONT_SIZE}\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+            hex(DEFAULT_BG)
+        );
+
+        for (y, row) in self.cells().chunks(width.max(1)).enumerate() {
+            let top = svg_units(y, SVG_CELL_H);
+            let colors: Vec<(Rgb, Rgb)> = row.iter().map(cell_rgb).collect();
+
+            // Backgrounds, merged across runs of one color
+            let mut x = 0;
+            while x < row.len() {
+                let bg = colors[x].1;
+                let end = (x..row.len()).find(|&i| colors[i].1 != bg).unwrap_or(row.len());
+                if bg != DEFAULT_BG {
+                    let _ = writeln!(
+                        out,
+                        "<rect x=\"{}\" y=\"{top}\" width=\"{}\" height=\"{SVG_CELL_H}\" fill=\"{}\"/>",
+                        svg_units(x, SVG_CELL_W),
+                        svg_units(end - x, SVG_CELL_W),
+                        hex(bg)
+                    );
+                }
+                x = end;
+            }
+
+            // Text, one element per run of matching fg and attributes;
+            // continuations belong to the run of their wide character
+            let same_run = |a: usize, b: usize| row[b].char == 0 || (colors[a].0 == colors[b].0 && row[a].attrs == row[b].attrs);
+            let mut x = 0;
+            while x < row.len() {
+                let end = (x + 1..row.len()).find(|&i| !same_run(x, i)).unwrap_or(row.len());
+                let text: String = row[x..end].iter().filter_map(cell_char).collect();
+                if !text.trim().is_empty() && !row[x].attrs.contains(Attr::HIDDEN) {
+                    let _ = write!(
+                        out,
+                        "<text x=\"{}\" y=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" xml:space=\"preserve\" {}>",
+                        svg_units(x, SVG_CELL_W),
+                        top + SVG_BASELINE,
+                        svg_units(end - x, SVG_CELL_W),
+                        svg_text_attrs(&row[x], colors[x].0)
+                    );
+                    for ch in text.chars() {
+                        push_escaped(&mut out, ch);
+                    }
+                    out.push_str("</text>\n");
+                }
+                x = end;
+            }
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// PNG image of the frame, `raster::CELL_W`×`raster::CELL_H` pixels per cell.
+    pub fn to_png(&self) -> Vec<u8> {
+        let width = self.width() as usize;
+        let mut canvas = Canvas::for_cells(width, self.height() as usize, DEFAULT_BG);
+        for (i, cell) in self.cells().iter().enumerate() {
+            // Continuations were covered by their wide character
+            let Some(ch) = cell_char(cell) else { continue };
+            let (fg, bg) = cell_rgb(cell);
+            canvas.draw_cell(i % width, i / width, ch, cell.attrs, fg, bg);
+        }
+        raster::encode_png(&canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_text_trims_and_skips_continuations() {
+        let mut frame = FrameBuffer::new(8, 2);
+        frame.draw_text(0, 0, "日x", Rgba::RED, None, Attr::NONE, None);
+        frame.draw_text(1, 1, "ok", Rgba::RED, None, Attr::NONE, None);
+        assert_eq!(frame.to_text(), "日x\n ok\n");
+    }
+
+    #[test]
+    fn test_to_html_styles_runs_and_escapes() {
+        let mut frame = FrameBuffer::new(4, 1);
+        frame.draw_text(0, 0, "<a", Rgba::rgb(255, 0, 0), Some(Rgba::ansi(4)), Attr::BOLD, None);
+        let html = frame.to_html();
+        assert!(html.contains("<span style=\"color:#ff0000;background:#0000ee;font-weight:bold;\">&lt;a</span>"));
+        // Default-colored tail is its own unstyled run
+        assert!(html.contains("<span style=\"\">  </span>"));
+    }
+
+    #[test]
+    fn test_to_svg_runs() {
+        let mut frame = FrameBuffer::new(6, 1);
+        frame.draw_text(0, 0, "a<", Rgba::rgb(255, 0, 0), Some(Rgba::rgb(0, 0, 255)), Attr::BOLD, None);
+        frame.draw_text(3, 0, "日", Rgba::rgb(255, 0, 0), None, Attr::NONE, None);
+        let svg = frame.to_svg();
+
+        // One background run over the two blue cells
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"19.2\" height=\"20\" fill=\"#0000ff\"/>"));
+        assert!(svg.contains("textLength=\"19.2\" lengthAdjust=\"spacingAndGlyphs\" xml:space=\"preserve\" fill=\"#ff0000\" font-weight=\"bold\">a&lt;</text>"));
+        // The wide character spans its continuation cell
+        assert!(svg.contains("x=\"28.8\" y=\"15\" textLength=\"19.2\""));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_cell_rgb_applies_inverse_and_dim() {
+        let cell = Cell { char: 'x' as u32, fg: Rgba::rgb(200, 0, 0), bg: Rgba::TERMINAL_DEFAULT, attrs: Attr::INVERSE, link: 0 };
+        assert_eq!(cell_rgb(&cell), (DEFAULT_BG, (200, 0, 0)));
+
+        let dim = Cell { attrs: Attr::DIM, ..cell };
+        assert_eq!(cell_rgb(&dim).0, raster::blend((200, 0, 0), DEFAULT_BG, DIM_WEIGHT));
+    }
+
+    #[test]
+    fn test_ansi_palette() {
+        assert_eq!(ansi_rgb(9), (255, 0, 0));
+        assert_eq!(ansi_rgb(16), (0, 0, 0));
+        assert_eq!(ansi_rgb(231), (255, 255, 255));
+        assert_eq!(ansi_rgb(232), (8, 8, 8));
+    }
+}
@@ -3,25 +3,41 @@
 //! Unlike DiffRenderer which uses the alternate screen buffer,
 //! InlineRenderer writes to the normal terminal buffer. It:
 //!
-//! - Clears and rewrites the entire content each frame
+//! - Clears and rewrites the entire content when the frame changes shape
 //! - Respects terminal scrollback
 //! - Suitable for CLI tools that want to show updating content
 //!   without taking over the full screen
+//!
+//! A frame made of several regions (`N_REGION`, stacked by layout) that
+//! keeps its shape only rewrites the rows of the regions that changed, as
+//! [`AppendRenderer`](super::AppendRenderer) does: a busy status footer
+//! doesn't repaint the prompt above it.
 
 use std::io;
+use std::ops::Range;
 
 use super::ansi;
+use super::append::{rows_equal, RegionBand};
 use super::buffer::FrameBuffer;
-use super::output::{OutputBuffer, StatefulCellRenderer};
+use super::output::{NativeCursor, OutputBuffer, StatefulCellRenderer};
 
 /// Inline renderer for normal terminal mode.
 ///
 /// Renders content inline (not fullscreen). Each render clears
-/// the previous content and writes new content.
+/// the previous content and writes new content, or only the regions
+/// that changed.
 pub struct InlineRenderer {
     output: OutputBuffer,
     cell_renderer: StatefulCellRenderer,
+    native_cursor: NativeCursor,
     previous_height: u16,
+    /// Last frame drawn, to diff regions against (None = draw whole)
+    previous: Option<FrameBuffer>,
+    /// Its regions
+    regions: Vec<RegionBand>,
+    terminal_width: u16,
+    terminal_height: u16,
+    cells_written: u32,
 }
 
 impl InlineRenderer {
@@ -30,62 +46,154 @@ impl InlineRenderer {
         Self {
             output: OutputBuffer::new(),
             cell_renderer: StatefulCellRenderer::new(),
+            native_cursor: NativeCursor::new(),
             previous_height: 0,
+            previous: None,
+            regions: Vec::new(),
+            terminal_width: 0,
+            terminal_height: 0,
+            cells_written: 0,
         }
     }
 
+    /// The buffer the next render writes into, for sequences that should
+    /// reach the terminal with the frame.
+    pub fn output_mut(&mut self) -> &mut OutputBuffer {
+        &mut self.output
+    }
+
+    /// Cells the last render wrote to the terminal.
+    pub fn cells_written(&self) -> u32 {
+        self.cells_written
+    }
+
+    /// Set the terminal size. A new size draws the next frame whole.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        if (width, height) != (self.terminal_width, self.terminal_height) {
+            self.terminal_width = width;
+            self.terminal_height = height;
+            self.previous = None;
+        }
+    }
+
+    /// Make the next render draw the whole frame.
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
     /// Render a frame inline.
     ///
     /// Uses sequential output with newlines, letting the terminal scroll
     /// naturally when content exceeds viewport height.
     pub fn render(&mut self, buffer: &FrameBuffer) -> io::Result<()> {
+        self.render_regions(buffer, &[])
+    }
+
+    /// [`render`](Self::render) for a frame made of `regions`, rewriting
+    /// only the regions that changed when it can.
+    pub fn render_regions(&mut self, buffer: &FrameBuffer, regions: &[RegionBand]) -> io::Result<()> {
         // Begin synchronized output
         ansi::begin_sync(&mut self.output)?;
-
-        let height = buffer.height();
-        let width = buffer.width();
-
-        // Clear viewport + scrollback, cursor home
-        self.output.write_str("\x1b[2J");  // Clear viewport
-        self.output.write_str("\x1b[3J");  // Clear scrollback
-        self.output.write_str("\x1b[H");   // Cursor home (0,0)
+        self.native_cursor.begin(&mut self.output)?;
 
         // Reset renderer state
         self.cell_renderer.reset();
 
-        // Render rows sequentially - let terminal scroll naturally
-        for y in 0..height {
-            // Position cursor at start of row (handles sparse content)
-            ansi::cursor_to(&mut self.output, 0, y)?;
-
-            for x in 0..width {
-                if let Some(cell) = buffer.get(x, y) {
-                    self.cell_renderer.render_cell_inline(&mut self.output, cell);
+        match self.dirty_bands(buffer, regions) {
+            Some(dirty) => {
+                self.cells_written = 0;
+                for y in dirty.into_iter().flatten() {
+                    ansi::cursor_to(&mut self.output, 0, y)?;
+                    // Erase on the terminal's own background, not the last cell's
+                    self.cell_renderer.set_link(&mut self.output, None);
+                    ansi::reset(&mut self.output)?;
+                    self.cell_renderer.reset();
+                    ansi::erase_line(&mut self.output)?;
+                    self.write_row(buffer, y);
+                    self.cells_written += buffer.width() as u32;
                 }
             }
+            None => {
+                // Clear viewport + scrollback, cursor home
+                self.output.write_str("\x1b[2J");  // Clear viewport
+                self.output.write_str("\x1b[3J");  // Clear scrollback
+                self.output.write_str("\x1b[H");   // Cursor home (0,0)
 
-            // Newline after each row EXCEPT the last
-            // This lets content scroll into scrollback naturally
-            if y < height - 1 {
-                self.output.write_str("\r\n");
+                // Render rows sequentially - let terminal scroll naturally
+                let height = buffer.height();
+                for y in 0..height {
+                    // Position cursor at start of row (handles sparse content)
+                    ansi::cursor_to(&mut self.output, 0, y)?;
+                    self.write_row(buffer, y);
+
+                    // Newline after each row EXCEPT the last
+                    // This lets content scroll into scrollback naturally
+                    if y < height - 1 {
+                        self.output.write_str("\r\n");
+                    }
+                }
+                self.cells_written = buffer.width() as u32 * height as u32;
             }
         }
 
         // Reset attributes at end
+        self.cell_renderer.set_link(&mut self.output, None);
         ansi::reset(&mut self.output)?;
 
+        // The frame starts at the screen's top, so its cells are screen cells
+        if let Some(cursor) = buffer.cursor() {
+            ansi::cursor_to(&mut self.output, cursor.x, cursor.y)?;
+            self.native_cursor.show(&mut self.output, &cursor)?;
+        }
+
         // End synchronized output
         ansi::end_sync(&mut self.output)?;
 
         // Flush to terminal
         self.output.flush_stdout()?;
 
-        // Track height for next erase
-        self.previous_height = height;
+        // Track height for next erase, and the frame for the next diff
+        self.previous_height = buffer.height();
+        self.previous = Some(buffer.clone());
+        self.regions = regions.to_vec();
 
         Ok(())
     }
 
+    /// Write row `y` of `buffer` where the cursor is.
+    fn write_row(&mut self, buffer: &FrameBuffer, y: u16) {
+        for x in 0..buffer.width() {
+            if let Some(cell) = buffer.get(x, y) {
+                self.cell_renderer.set_link(&mut self.output, buffer.link_url(cell.link));
+                self.cell_renderer.render_cell_inline(&mut self.output, cell);
+            }
+        }
+    }
+
+    /// Rows of `buffer` to rewrite over the last frame, region by region;
+    /// None when the frame changed shape and must be drawn whole.
+    ///
+    /// Rows are addressed from the screen's top, so only a frame that fits
+    /// the terminal (nothing scrolled off, no row wrapped) is diffed.
+    fn dirty_bands(&self, buffer: &FrameBuffer, regions: &[RegionBand]) -> Option<Vec<Range<u16>>> {
+        let previous = self.previous.as_ref()?;
+        let same_bands = regions.len() == self.regions.len()
+            && regions.iter().zip(&self.regions).all(|(a, b)| (a.y, a.height) == (b.y, b.height));
+        let covered = regions.first().is_some_and(|band| band.y == 0)
+            && regions.last().is_some_and(|band| band.y + band.height == buffer.height());
+        let fits = buffer.width() <= self.terminal_width && buffer.height() <= self.terminal_height;
+        if !same_bands || !covered || !fits || (previous.width(), previous.height()) != (buffer.width(), buffer.height()) {
+            return None;
+        }
+        Some(
+            regions
+                .iter()
+                .map(|band| band.y..band.y + band.height)
+                .filter(|range| !range.clone().all(|y| rows_equal(previous, buffer, y)))
+                .collect(),
+        )
+    }
+
     /// Clear any rendered content and reset state.
     pub fn clear(&mut self) -> io::Result<()> {
         if self.previous_height > 0 {
@@ -95,6 +203,18 @@ impl InlineRenderer {
             self.output.flush_stdout()?;
             self.previous_height = 0;
         }
+        self.previous = None;
+        Ok(())
+    }
+
+    /// Move the cursor to the line below the last frame (a native cursor
+    /// leaves it inside), for whatever the terminal prints after unmount.
+    pub fn leave(&mut self) -> io::Result<()> {
+        if self.previous_height > 0 {
+            ansi::cursor_to(&mut self.output, 0, self.previous_height - 1)?;
+            self.output.write_str("\r\n");
+            self.output.flush_stdout()?;
+        }
         Ok(())
     }
 
@@ -106,8 +226,16 @@ impl InlineRenderer {
     /// Reset the renderer state.
     pub fn reset(&mut self) {
         self.previous_height = 0;
+        self.previous = None;
         self.cell_renderer.reset();
     }
+
+    /// Rows a frame takes for `content` rows of UI, within the configured
+    /// bounds (0 = unset). As in CSS, the minimum wins over the maximum.
+    pub fn fit_height(content: u16, min: u16, max: u16) -> u16 {
+        let capped = if max > 0 { content.min(max) } else { content };
+        capped.max(min).max(1)
+    }
 }
 
 impl Default for InlineRenderer {
@@ -130,6 +258,86 @@ mod tests {
         assert_eq!(renderer.previous_height(), 0);
     }
 
+    #[test]
+    fn test_fit_height_clamps_content() {
+        // Unbounded: the content's own height (never under one row)
+        assert_eq!(InlineRenderer::fit_height(12, 0, 0), 12);
+        assert_eq!(InlineRenderer::fit_height(0, 0, 0), 1);
+        // Grows to the maximum, then stops
+        assert_eq!(InlineRenderer::fit_height(5, 0, 8), 5);
+        assert_eq!(InlineRenderer::fit_height(30, 0, 8), 8);
+        // Short content still takes the minimum
+        assert_eq!(InlineRenderer::fit_height(2, 4, 8), 4);
+        // Conflicting bounds: the minimum wins
+        assert_eq!(InlineRenderer::fit_height(30, 10, 6), 10);
+    }
+
+    #[test]
+    fn test_regions_redraw_independently() {
+        use crate::renderer::output::set_terminal_output;
+        use crate::utils::{Attr, Rgba};
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for Capture {
+            fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(bytes);
+                Ok(bytes.len())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let capture = Capture::default();
+        set_terminal_output(Some(Box::new(capture.clone())));
+        let take = || String::from_utf8(std::mem::take(&mut *capture.0.lock().unwrap())).unwrap();
+
+        // Prompt (rows 0-1) above a status footer (row 2)
+        let regions = [
+            RegionBand { region: 0, y: 0, height: 2, width: 20 },
+            RegionBand { region: 1, y: 2, height: 1, width: 20 },
+        ];
+        let frame = |prompt: &str, status: &str| {
+            let mut buffer = FrameBuffer::new(20, 3);
+            buffer.draw_text(0, 0, "Name?", Rgba::TERMINAL_DEFAULT, None, Attr::NONE, None);
+            buffer.draw_text(0, 1, prompt, Rgba::TERMINAL_DEFAULT, None, Attr::NONE, None);
+            buffer.draw_text(0, 2, status, Rgba::TERMINAL_DEFAULT, None, Attr::NONE, None);
+            buffer
+        };
+        let mut renderer = InlineRenderer::new();
+        renderer.resize(40, 10);
+        renderer.render_regions(&frame("> ", "idle"), &regions).unwrap();
+        assert!(take().contains("Name?"));
+        assert_eq!(renderer.cells_written(), 60);
+
+        // Only the footer changed: its row rewritten where it is, nothing else
+        renderer.render_regions(&frame("> ", "busy"), &regions).unwrap();
+        let out = take();
+        assert!(out.contains("\x1b[3;1H") && out.contains("busy") && !out.contains("Name?"), "{out:?}");
+        assert!(!out.contains("\x1b[2J"), "not cleared: {out:?}");
+        assert_eq!(renderer.cells_written(), 20);
+
+        // Taller than the terminal: rows scrolled off can't be addressed, drawn whole
+        renderer.resize(40, 2);
+        renderer.render_regions(&frame("> ", "done"), &regions).unwrap();
+        assert!(take().contains("Name?"));
+        renderer.render_regions(&frame("> a", "done"), &regions).unwrap();
+        assert!(take().contains("Name?"));
+
+        // Regions changed shape: everything drawn again
+        renderer.resize(40, 10);
+        renderer.render_regions(&frame("> ", "idle"), &regions).unwrap();
+        take();
+        let grown = [regions[0], RegionBand { height: 2, ..regions[1] }];
+        let mut tall = FrameBuffer::new(20, 4);
+        tall.draw_text(0, 0, "Name?", Rgba::TERMINAL_DEFAULT, None, Attr::NONE, None);
+        renderer.render_regions(&tall, &grown).unwrap();
+        assert!(take().contains("Name?"));
+
+        set_terminal_output(None);
+    }
+
     #[test]
     fn test_inline_renderer_reset() {
         let mut renderer = InlineRenderer::new();
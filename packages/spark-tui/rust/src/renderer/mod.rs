@@ -14,18 +14,25 @@
 //!
 //! - **Append** ([`AppendRenderer`]): Two regions - frozen history
 //!   above, active updating region below
+//!
+//! Whatever the mode, output can be recorded as an asciinema cast
+//! ([`cast::record_cast`]).
 
 pub mod ansi;
 pub mod append;
 pub mod buffer;
+pub mod cast;
 pub mod diff;
+pub mod export;
 pub mod inline;
 pub mod output;
+pub mod passthrough;
+pub mod raster;
 
 // Re-exports for convenience
-pub use append::AppendRenderer;
-pub use buffer::{char_width, string_width, BorderColors, BorderSides, FrameBuffer};
+pub use append::{AppendRenderer, RegionBand, StyledLine, StyledSpan};
+pub use buffer::{char_width, string_width, BorderColors, BorderSides, FrameBuffer, TerminalCursor};
 pub use crate::utils::ClipRect;
 pub use diff::DiffRenderer;
 pub use inline::InlineRenderer;
-pub use output::{OutputBuffer, StatefulCellRenderer};
+pub use output::{NativeCursor, OutputBuffer, StatefulCellRenderer, TerminalOut};
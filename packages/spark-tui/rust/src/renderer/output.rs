@@ -4,11 +4,79 @@
 //! - Batching writes into a single syscall
 //! - Tracking terminal state to avoid redundant escape codes
 //! - Only emitting changes (colors, attributes, cursor position)
+//!
+//! Everything bound for the terminal goes through `TerminalOut`: stdout, or
+//! the session stream installed on the current engine thread (see
+//! `pipeline::session`), and counts the bytes it carries (`bytes_written`).
+//! A running cast recording gets a copy (see `cast`).
 
 use crate::utils::{Attr, Cell, Rgba};
+use std::cell::{Cell as StdCell, RefCell};
 use std::io::{self, Write};
 
-use super::ansi;
+use super::ansi::CursorShape;
+use super::buffer::TerminalCursor;
+use super::{ansi, cast};
+
+// =============================================================================
+// Terminal output
+// =============================================================================
+
+thread_local! {
+    /// This engine thread's terminal, when it isn't the process's stdout.
+    static TERMINAL_OUTPUT: RefCell<Option<Box<dyn Write>>> = const { RefCell::new(None) };
+    /// Bytes this thread has written to its terminal.
+    static BYTES_WRITTEN: StdCell<u64> = const { StdCell::new(0) };
+}
+
+/// Bytes written to this thread's terminal so far. Frame stats take the
+/// difference around a render.
+pub fn bytes_written() -> u64 {
+    BYTES_WRITTEN.with(|b| b.get())
+}
+
+fn count_written(bytes: usize) {
+    BYTES_WRITTEN.with(|b| b.set(b.get() + bytes as u64));
+}
+
+/// Send this thread's terminal output to `output` instead of stdout
+/// (`None` restores stdout).
+pub fn set_terminal_output(output: Option<Box<dyn Write>>) {
+    TERMINAL_OUTPUT.with(|o| *o.borrow_mut() = output);
+}
+
+/// The current thread's terminal: the installed session stream, or stdout.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerminalOut;
+
+impl Write for TerminalOut {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = TERMINAL_OUTPUT.with(|o| match o.borrow_mut().as_mut() {
+            Some(output) => output.write(buf),
+            None => io::stdout().lock().write(buf),
+        })?;
+        count_written(written);
+        cast::tee(&buf[..written]);
+        Ok(written)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        TERMINAL_OUTPUT.with(|o| match o.borrow_mut().as_mut() {
+            Some(output) => output.write_all(buf),
+            None => io::stdout().lock().write_all(buf),
+        })?;
+        count_written(buf.len());
+        cast::tee(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        TERMINAL_OUTPUT.with(|o| match o.borrow_mut().as_mut() {
+            Some(output) => output.flush(),
+            None => io::stdout().flush(),
+        })
+    }
+}
 
 // =============================================================================
 // OutputBuffer
@@ -82,14 +150,15 @@ impl OutputBuffer {
         }
     }
 
-    /// Flush buffer to stdout (blocking).
+    /// Flush buffer to the terminal (blocking): stdout, or this engine
+    /// thread's session stream.
     pub fn flush_stdout(&mut self) -> io::Result<()> {
         if self.data.is_empty() {
             return Ok(());
         }
-        let mut stdout = io::stdout().lock();
-        stdout.write_all(&self.data)?;
-        stdout.flush()?;
+        let mut out = TerminalOut;
+        out.write_all(&self.data)?;
+        out.flush()?;
         self.data.clear();
         Ok(())
     }
@@ -137,6 +206,7 @@ impl Write for OutputBuffer {
 /// - Last foreground color
 /// - Last background color
 /// - Last text attributes
+/// - The open hyperlink
 ///
 /// When rendering a cell, it only emits escape codes for changed state.
 #[derive(Debug)]
@@ -146,6 +216,7 @@ pub struct StatefulCellRenderer {
     last_fg: Option<Rgba>,
     last_bg: Option<Rgba>,
     last_attrs: Attr,
+    open_link: Option<String>,
 }
 
 impl StatefulCellRenderer {
@@ -157,6 +228,7 @@ impl StatefulCellRenderer {
             last_fg: None,
             last_bg: None,
             last_attrs: Attr::NONE,
+            open_link: None,
         }
     }
 
@@ -169,6 +241,22 @@ impl StatefulCellRenderer {
         self.last_fg = None;
         self.last_bg = None;
         self.last_attrs = Attr::NONE;
+        self.open_link = None;
+    }
+
+    /// Make `url` the open hyperlink (OSC 8) for the cells rendered next,
+    /// closing the previous one. `None` closes it; do that before the frame
+    /// ends so the link doesn't run on into whatever is printed later.
+    pub fn set_link(&mut self, output: &mut OutputBuffer, url: Option<&str>) {
+        if self.open_link.as_deref() == url {
+            return;
+        }
+        match url {
+            // Opening a link ends the previous one
+            Some(url) => ansi::link_open(output, url).ok(),
+            None => ansi::link_close(output).ok(),
+        };
+        self.open_link = url.map(str::to_string);
     }
 
     /// Render a single cell to the output buffer.
@@ -289,6 +377,50 @@ impl Default for StatefulCellRenderer {
     }
 }
 
+// =============================================================================
+// Native cursor
+// =============================================================================
+
+/// Shows the terminal's cursor where a frame places it (see
+/// `FrameBuffer::set_cursor`).
+///
+/// The cursor is hidden while a frame draws, so it never flickers through
+/// the cells being written, and shown again once the renderer has moved it
+/// to its cell. The shape is only sent when it changes: terminals restart
+/// the blink on every DECSCUSR.
+#[derive(Debug, Default)]
+pub struct NativeCursor {
+    visible: bool,
+    shape: Option<(CursorShape, bool)>,
+}
+
+impl NativeCursor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hide the cursor before drawing.
+    pub fn begin(&mut self, output: &mut OutputBuffer) -> io::Result<()> {
+        if self.visible {
+            ansi::cursor_hide(output)?;
+            self.visible = false;
+        }
+        Ok(())
+    }
+
+    /// Show the cursor, already moved to `cursor`'s cell.
+    pub fn show(&mut self, output: &mut OutputBuffer, cursor: &TerminalCursor) -> io::Result<()> {
+        let shape = (cursor.shape, cursor.blinking);
+        if self.shape != Some(shape) {
+            ansi::cursor_shape(output, cursor.shape, cursor.blinking)?;
+            self.shape = Some(shape);
+        }
+        ansi::cursor_show(output)?;
+        self.visible = true;
+        Ok(())
+    }
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -325,6 +457,7 @@ mod tests {
             fg: Rgba::WHITE,
             bg: Rgba::BLACK,
             attrs: Attr::NONE,
+            link: 0,
         };
 
         // First cell at (0, 0) - needs cursor move
@@ -350,6 +483,7 @@ mod tests {
             fg: Rgba::rgb(255, 0, 0),
             bg: Rgba::rgb(0, 0, 255),
             attrs: Attr::NONE,
+            link: 0,
         };
 
         // First cell
@@ -375,6 +509,7 @@ mod tests {
             fg: Rgba::WHITE,
             bg: Rgba::BLACK,
             attrs: Attr::NONE,
+            link: 0,
         };
 
         renderer.render_cell(&mut output, 0, 0, &continuation);
@@ -396,6 +531,7 @@ mod tests {
             fg: Rgba::WHITE,
             bg: Rgba::BLACK,
             attrs: Attr::NONE,
+            link: 0,
         };
         renderer.render_cell(&mut output, 0, 0, &wide);
         output.clear();
@@ -408,6 +544,7 @@ mod tests {
             fg: Rgba::WHITE,
             bg: Rgba::BLACK,
             attrs: Attr::NONE,
+            link: 0,
         };
         renderer.render_cell(&mut output, 1, 0, &continuation);
 
@@ -426,9 +563,47 @@ mod tests {
             fg: Rgba::WHITE,
             bg: Rgba::BLACK,
             attrs: Attr::NONE,
+            link: 0,
         };
         renderer.render_cell_inline(&mut output, &continuation);
 
         assert!(output.is_empty(), "Inline continuation should produce no output");
     }
+
+    #[test]
+    fn test_links_open_once_and_close() {
+        let mut renderer = StatefulCellRenderer::new();
+        let mut output = OutputBuffer::new();
+
+        renderer.set_link(&mut output, Some("https://example.com/a b"));
+        renderer.set_link(&mut output, Some("https://example.com/a b"));
+        renderer.set_link(&mut output, None);
+        renderer.set_link(&mut output, None);
+
+        let out = output.as_str();
+        assert_eq!(out.matches("\x1b]8;id=").count(), 1, "Same link shouldn't reopen");
+        assert!(out.contains(";https://example.com/a%20b\x1b\\"), "Unsafe bytes are percent-encoded");
+        assert!(out.ends_with("\x1b]8;;\x1b\\"));
+        assert_eq!(out.matches("\x1b]8;;").count(), 1);
+    }
+
+    #[test]
+    fn test_native_cursor_hides_while_drawing() {
+        let mut native = NativeCursor::new();
+        let mut output = OutputBuffer::new();
+        let cursor = TerminalCursor { x: 3, y: 1, shape: CursorShape::Bar, blinking: true };
+
+        // Nothing to hide before the first show
+        native.begin(&mut output).unwrap();
+        assert!(output.is_empty());
+
+        native.show(&mut output, &cursor).unwrap();
+        assert_eq!(output.as_str().as_ref(), "\x1b[5 q\x1b[?25h");
+        output.clear();
+
+        // Same shape again: only hide and show
+        native.begin(&mut output).unwrap();
+        native.show(&mut output, &cursor).unwrap();
+        assert_eq!(output.as_str().as_ref(), "\x1b[?25l\x1b[?25h");
+    }
 }
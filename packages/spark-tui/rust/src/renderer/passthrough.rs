@@ -0,0 +1,135 @@
+//! Terminal multiplexers — tmux and GNU screen between us and the terminal.
+//!
+//! A multiplexer parses our output into its own grid and redraws that on the
+//! real terminal, so sequences meant for the outer terminal (clipboard,
+//! graphics) are dropped unless wrapped in a DCS passthrough:
+//!
+//! ```text
+//! tmux:   ESC P tmux; <sequence, every ESC doubled> ESC \
+//! screen: ESC P <sequence> ESC \          (in ≤ SCREEN_CHUNK byte pieces)
+//! ```
+//!
+//! | Sequence              | tmux                                   | screen       |
+//! |-----------------------|----------------------------------------|--------------|
+//! | OSC 52 clipboard      | passthrough if allowed, else raw (tmux's own `set-clipboard`) | passthrough |
+//! | OSC 5522 image clip.  | passthrough if allowed, else unsupported | unsupported |
+//! | sync output (2026)    | raw — tmux ≥ 3.4 syncs its own redraw  | dropped      |
+//!
+//! Synchronized output is never wrapped: the outer terminal sees tmux's
+//! redraw, not our frame, so only the multiplexer itself can honour it.
+//! tmux only forwards passthrough with `allow-passthrough` on (3.3+), which
+//! is asked once via `tmux show-options`.
+
+use std::borrow::Cow;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// GNU screen truncates DCS strings longer than this.
+pub const SCREEN_CHUNK: usize = 768;
+
+const DCS: &str = "\x1bP";
+const ST: &str = "\x1b\\";
+
+/// The multiplexer we run under, if any.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexer {
+    None,
+    /// `passthrough`: `allow-passthrough` is on.
+    Tmux { passthrough: bool },
+    Screen,
+}
+
+impl Multiplexer {
+    /// Detect from `TMUX` / `STY`; `tmux_passthrough` is only asked under tmux.
+    pub fn detect_with(var: impl Fn(&str) -> Option<String>, tmux_passthrough: impl FnOnce() -> bool) -> Self {
+        if var("TMUX").is_some_and(|v| !v.is_empty()) {
+            Self::Tmux { passthrough: tmux_passthrough() }
+        } else if var("STY").is_some_and(|v| !v.is_empty()) {
+            Self::Screen
+        } else {
+            Self::None
+        }
+    }
+
+    /// Whether synchronized output should be emitted.
+    pub fn sync_output(self) -> bool {
+        self != Self::Screen
+    }
+
+    /// Whether outer-terminal graphics (image clipboard) can get through.
+    pub fn graphics(self) -> bool {
+        matches!(self, Self::None | Self::Tmux { passthrough: true })
+    }
+
+    /// Wrap a sequence meant for the outer terminal.
+    pub fn wrap(self, seq: &str) -> Cow<'_, str> {
+        match self {
+            Self::None | Self::Tmux { passthrough: false } => Cow::Borrowed(seq),
+            Self::Tmux { passthrough: true } => {
+                Cow::Owned(format!("{DCS}tmux;{}{ST}", seq.replace('\x1b', "\x1b\x1b")))
+            }
+            Self::Screen => {
+                let mut out = String::with_capacity(seq.len() + seq.len() / SCREEN_CHUNK * 4 + 4);
+                // Sequences here are ASCII (base64 payloads), so byte chunks are char-safe
+                for chunk in seq.as_bytes().chunks(SCREEN_CHUNK) {
+                    out.push_str(DCS);
+                    out.push_str(&String::from_utf8_lossy(chunk));
+                    out.push_str(ST);
+                }
+                Cow::Owned(out)
+            }
+        }
+    }
+}
+
+/// The multiplexer of this process, detected once.
+pub fn multiplexer() -> Multiplexer {
+    static DETECTED: OnceLock<Multiplexer> = OnceLock::new();
+    *DETECTED.get_or_init(|| Multiplexer::detect_with(|name| std::env::var(name).ok(), tmux_allows_passthrough))
+}
+
+/// Wrap `seq` for the outer terminal under the detected multiplexer.
+pub fn wrap(seq: &str) -> Cow<'_, str> {
+    multiplexer().wrap(seq)
+}
+
+fn tmux_allows_passthrough() -> bool {
+    Command::new("tmux")
+        .args(["show-options", "-gqv", "allow-passthrough"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .is_some_and(|out| matches!(String::from_utf8_lossy(&out.stdout).trim(), "on" | "all"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let tmux = |name: &str| (name == "TMUX").then(|| "/tmp/tmux-1000/default,1,0".to_string());
+        assert_eq!(Multiplexer::detect_with(tmux, || true), Multiplexer::Tmux { passthrough: true });
+        let screen = |name: &str| (name == "STY").then(|| "1234.pts-0.host".to_string());
+        assert_eq!(Multiplexer::detect_with(screen, || unreachable!()), Multiplexer::Screen);
+        assert_eq!(Multiplexer::detect_with(|_| None, || unreachable!()), Multiplexer::None);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let osc = "\x1b]52;c;aGk=\x07";
+        assert_eq!(Multiplexer::None.wrap(osc), osc);
+        assert_eq!(Multiplexer::Tmux { passthrough: false }.wrap(osc), osc);
+        assert_eq!(
+            Multiplexer::Tmux { passthrough: true }.wrap(osc),
+            "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+        assert_eq!(Multiplexer::Screen.wrap(osc), "\x1bP\x1b]52;c;aGk=\x07\x1b\\");
+
+        // screen: long sequences split into several DCS strings
+        let long = format!("\x1b]52;c;{}\x07", "A".repeat(SCREEN_CHUNK * 2));
+        let wrapped = Multiplexer::Screen.wrap(&long);
+        assert_eq!(wrapped.matches(DCS).count(), 3);
+        assert_eq!(wrapped.replace(DCS, "").replace(ST, ""), long);
+    }
+}
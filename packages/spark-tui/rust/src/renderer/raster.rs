@@ -0,0 +1,425 @@
+//! Cell-grid rasterizer — FrameBuffer pixels and PNG encoding.
+//!
+//! Each cell becomes a `CELL_W`×`CELL_H` block: the background fill, then
+//! the glyph in the foreground color. Printable ASCII comes from a bundled
+//! 5×7 bitmap font; box-drawing, block and braille characters are drawn
+//! procedurally so borders and bars connect across cells. Anything else is
+//! an outlined box, one cell per column it occupies.
+//!
+//! The PNG is RGB8 with stored (uncompressed) deflate blocks: larger files,
+//! but no compression dependency and nothing to tune.
+
+use crate::utils::Attr;
+
+/// Pixel size of one cell.
+pub const CELL_W: usize = 6;
+pub const CELL_H: usize = 10;
+
+/// Rows above the 5×7 glyphs (the rest below holds descender space and the underline).
+const GLYPH_TOP: usize = 1;
+const GLYPH_ROWS: usize = 7;
+const GLYPH_COLS: usize = 5;
+
+const UNDERLINE_ROW: usize = CELL_H - 1;
+const STRIKE_ROW: usize = GLYPH_TOP + GLYPH_ROWS / 2;
+
+/// Largest payload of one stored deflate block.
+const MAX_STORED_BLOCK: usize = 0xFFFF;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+pub type Rgb = (u8, u8, u8);
+
+/// 5×7 glyphs for ASCII 0x20..=0x7E; bit 4 is the leftmost pixel.
+const FONT_5X7: [[u8; GLYPH_ROWS]; 95] = [
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000], // ' '
+    [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100], // '!'
+    [0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000], // '"'
+    [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010], // '#'
+    [0b00100, 0b01111, 0b10100, 0b01110, 0b00101, 0b11110, 0b00100], // '$'
+    [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011], // '%'
+    [0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101], // '&'
+    [0b01100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000], // '\''
+    [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010], // '('
+    [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000], // ')'
+    [0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000], // '*'
+    [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000], // '+'
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000], // ','
+    [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000], // '-'
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100], // '.'
+    [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000], // '/'
+    [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110], // '0'
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110], // '1'
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111], // '2'
+    [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110], // '3'
+    [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010], // '4'
+    [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110], // '5'
+    [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110], // '6'
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000], // '7'
+    [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110], // '8'
+    [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100], // '9'
+    [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000], // ':'
+    [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b00100, 0b01000], // ';'
+    [0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010], // '<'
+    [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000], // '='
+    [0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000], // '>'
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100], // '?'
+    [0b01110, 0b10001, 0b00001, 0b01101, 0b10101, 0b10101, 0b01110], // '@'
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001], // 'A'
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110], // 'B'
+    [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110], // 'C'
+    [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100], // 'D'
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111], // 'E'
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000], // 'F'
+    [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111], // 'G'
+    [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001], // 'H'
+    [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110], // 'I'
+    [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100], // 'J'
+    [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001], // 'K'
+    [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111], // 'L'
+    [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001], // 'M'
+    [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001], // 'N'
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110], // 'O'
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000], // 'P'
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101], // 'Q'
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001], // 'R'
+    [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110], // 'S'
+    [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100], // 'T'
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110], // 'U'
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100], // 'V'
+    [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010], // 'W'
+    [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001], // 'X'
+    [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100], // 'Y'
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111], // 'Z'
+    [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110], // '['
+    [0b00000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00000], // '\\'
+    [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110], // ']'
+    [0b00100, 0b01010, 0b10001, 0b00000, 0b00000, 0b00000, 0b00000], // '^'
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111], // '_'
+    [0b01000, 0b00100, 0b00010, 0b00000, 0b00000, 0b00000, 0b00000], // '`'
+    [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111], // 'a'
+    [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110], // 'b'
+    [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110], // 'c'
+    [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111], // 'd'
+    [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110], // 'e'
+    [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000], // 'f'
+    [0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110], // 'g'
+    [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001], // 'h'
+    [0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110], // 'i'
+    [0b00010, 0b00000, 0b00110, 0b00010, 0b00010, 0b10010, 0b01100], // 'j'
+    [0b10000, 0b10000, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010], // 'k'
+    [0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110], // 'l'
+    [0b00000, 0b00000, 0b11010, 0b10101, 0b10101, 0b10001, 0b10001], // 'm'
+    [0b00000, 0b00000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001], // 'n'
+    [0b00000, 0b00000, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110], // 'o'
+    [0b00000, 0b00000, 0b11110, 0b10001, 0b11110, 0b10000, 0b10000], // 'p'
+    [0b00000, 0b00000, 0b01101, 0b10011, 0b01111, 0b00001, 0b00001], // 'q'
+    [0b00000, 0b00000, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000], // 'r'
+    [0b00000, 0b00000, 0b01110, 0b10000, 0b01110, 0b00001, 0b11110], // 's'
+    [0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b01001, 0b00110], // 't'
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b10011, 0b01101], // 'u'
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100], // 'v'
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10101, 0b10101, 0b01010], // 'w'
+    [0b00000, 0b00000, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001], // 'x'
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110], // 'y'
+    [0b00000, 0b00000, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111], // 'z'
+    [0b00010, 0b00100, 0b00100, 0b01000, 0b00100, 0b00100, 0b00010], // '{'
+    [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100], // '|'
+    [0b01000, 0b00100, 0b00100, 0b00010, 0b00100, 0b00100, 0b01000], // '}'
+    [0b00000, 0b00000, 0b01000, 0b10101, 0b00010, 0b00000, 0b00000], // '~'
+];
+
+/// `a` weighted by `t` over `b`.
+pub fn blend(a: Rgb, b: Rgb, t: f32) -> Rgb {
+    let mix = |x: u8, y: u8| (x as f32 * t + y as f32 * (1.0 - t)).round() as u8;
+    (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+/// An RGB8 pixel buffer.
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    /// A canvas for a `cols`×`rows` cell grid, filled with `bg`.
+    pub fn for_cells(cols: usize, rows: usize, bg: Rgb) -> Self {
+        let (width, height) = (cols * CELL_W, rows * CELL_H);
+        let pixels = [bg.0, bg.1, bg.2].repeat(width * height);
+        Self { width, height, pixels }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        let i = (y * self.width + x) * 3;
+        (self.pixels[i], self.pixels[i + 1], self.pixels[i + 2])
+    }
+
+    fn fill(&mut self, x: usize, y: usize, w: usize, h: usize, color: Rgb) {
+        for py in y..(y + h).min(self.height) {
+            for px in x..(x + w).min(self.width) {
+                let i = (py * self.width + px) * 3;
+                self.pixels[i..i + 3].copy_from_slice(&[color.0, color.1, color.2]);
+            }
+        }
+    }
+
+    /// Paint one cell at grid position (`col`, `row`). Wide characters
+    /// cover the following cell too; `fg`/`bg` are already resolved
+    /// (inverse and dim applied).
+    pub fn draw_cell(&mut self, col: usize, row: usize, ch: char, attrs: Attr, fg: Rgb, bg: Rgb) {
+        let span = crate::renderer::char_width(ch).max(1);
+        let (x, y) = (col * CELL_W, row * CELL_H);
+        self.fill(x, y, span * CELL_W, CELL_H, bg);
+        if attrs.contains(Attr::HIDDEN) {
+            return;
+        }
+
+        if !self.draw_procedural(x, y, ch, fg, bg) && ch != ' ' {
+            match ascii_glyph(ch) {
+                Some(glyph) => {
+                    self.draw_glyph(x, y, glyph, fg);
+                    if attrs.contains(Attr::BOLD) {
+                        self.draw_glyph(x + 1, y, glyph, fg);
+                    }
+                }
+                None => self.draw_tofu(x, y, span, fg),
+            }
+        }
+
+        if attrs.contains(Attr::UNDERLINE) {
+            self.fill(x, y + UNDERLINE_ROW, span * CELL_W, 1, fg);
+        }
+        if attrs.contains(Attr::STRIKETHROUGH) {
+            self.fill(x, y + STRIKE_ROW, span * CELL_W, 1, fg);
+        }
+    }
+
+    fn draw_glyph(&mut self, x: usize, y: usize, glyph: &[u8; GLYPH_ROWS], fg: Rgb) {
+        for (gy, bits) in glyph.iter().enumerate() {
+            for gx in 0..GLYPH_COLS {
+                if bits >> (GLYPH_COLS - 1 - gx) & 1 != 0 {
+                    self.fill(x + gx, y + GLYPH_TOP + gy, 1, 1, fg);
+                }
+            }
+        }
+    }
+
+    /// Outlined box for characters the font lacks.
+    fn draw_tofu(&mut self, x: usize, y: usize, span: usize, fg: Rgb) {
+        let (w, h) = (span * CELL_W - 2, GLYPH_ROWS);
+        let top = y + GLYPH_TOP;
+        self.fill(x + 1, top, w, 1, fg);
+        self.fill(x + 1, top + h - 1, w, 1, fg);
+        self.fill(x + 1, top, 1, h, fg);
+        self.fill(x + w, top, 1, h, fg);
+    }
+
+    /// Box-drawing, block and braille characters. False if `ch` is none of them.
+    fn draw_procedural(&mut self, x: usize, y: usize, ch: char, fg: Rgb, bg: Rgb) -> bool {
+        let code = ch as u32;
+        let (mid_x, mid_y) = (x + CELL_W / 2, y + CELL_H / 2);
+
+        if let Some([up, down, left, right]) = box_arms(code) {
+            if up {
+                self.fill(mid_x, y, 1, CELL_H / 2 + 1, fg);
+            }
+            if down {
+                self.fill(mid_x, mid_y, 1, CELL_H - CELL_H / 2, fg);
+            }
+            if left {
+                self.fill(x, mid_y, CELL_W / 2 + 1, 1, fg);
+            }
+            if right {
+                self.fill(mid_x, mid_y, CELL_W - CELL_W / 2, 1, fg);
+            }
+            return true;
+        }
+
+        match code {
+            // ▀ upper half
+            0x2580 => self.fill(x, y, CELL_W, CELL_H / 2, fg),
+            // ▁..█ lower eighths
+            0x2581..=0x2588 => {
+                let h = CELL_H * (code - 0x2580) as usize / 8;
+                self.fill(x, y + CELL_H - h, CELL_W, h, fg);
+            }
+            // ▉..▏ left eighths
+            0x2589..=0x258F => self.fill(x, y, CELL_W * (0x2590 - code) as usize / 8, CELL_H, fg),
+            // ▐ right half
+            0x2590 => self.fill(mid_x, y, CELL_W - CELL_W / 2, CELL_H, fg),
+            // ░ ▒ ▓ shades
+            0x2591..=0x2593 => {
+                let t = (code - 0x2590) as f32 / 4.0;
+                self.fill(x, y, CELL_W, CELL_H, blend(fg, bg, t));
+            }
+            // Braille: dots 1-3 and 7 down the left column, 4-6 and 8 down the right
+            0x2800..=0x28FF => {
+                const DOTS: [(usize, usize); 8] = [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2), (0, 3), (1, 3)];
+                let bits = code - 0x2800;
+                for (i, (dx, dy)) in DOTS.iter().enumerate() {
+                    if bits >> i & 1 != 0 {
+                        self.fill(x + 1 + dx * 3, y + 1 + dy * 2, 2, 2, fg);
+                    }
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+fn ascii_glyph(ch: char) -> Option<&'static [u8; GLYPH_ROWS]> {
+    let code = ch as usize;
+    (0x20..=0x7E).contains(&code).then(|| &FONT_5X7[code - 0x20])
+}
+
+/// Which arms (up, down, left, right) a box-drawing character has.
+/// Light, heavy, double, dashed and rounded variants all draw as one line.
+fn box_arms(code: u32) -> Option<[bool; 4]> {
+    const U: u8 = 1;
+    const D: u8 = 2;
+    const L: u8 = 4;
+    const R: u8 = 8;
+    let arms = match code {
+        0x2500 | 0x2501 | 0x2504 | 0x2505 | 0x2508 | 0x2509 | 0x254C | 0x254D | 0x2550 => L | R,
+        0x2502 | 0x2503 | 0x2506 | 0x2507 | 0x250A | 0x250B | 0x254E | 0x254F | 0x2551 => U | D,
+        0x250C..=0x250F | 0x2552..=0x2554 | 0x256D => D | R,
+        0x2510..=0x2513 | 0x2555..=0x2557 | 0x256E => D | L,
+        0x2514..=0x2517 | 0x2558..=0x255A | 0x2570 => U | R,
+        0x2518..=0x251B | 0x255B..=0x255D | 0x256F => U | L,
+        0x251C..=0x2523 | 0x255E..=0x2560 => U | D | R,
+        0x2524..=0x252B | 0x2561..=0x2563 => U | D | L,
+        0x252C..=0x2533 | 0x2564..=0x2566 => D | L | R,
+        0x2534..=0x253B | 0x2567..=0x2569 => U | L | R,
+        0x253C..=0x254B | 0x256A..=0x256C => U | D | L | R,
+        0x2574 | 0x2578 => L,
+        0x2575 | 0x2579 => U,
+        0x2576 | 0x257A => R,
+        0x2577 | 0x257B => D,
+        _ => return None,
+    };
+    Some([arms & U != 0, arms & D != 0, arms & L != 0, arms & R != 0])
+}
+
+// =============================================================================
+// PNG
+// =============================================================================
+
+/// Encode the canvas as a PNG file.
+pub fn encode_png(canvas: &Canvas) -> Vec<u8> {
+    let stride = canvas.width * 3;
+    let mut raw = Vec::with_capacity((stride + 1) * canvas.height);
+    for row in canvas.pixels.chunks(stride.max(1)) {
+        raw.push(0); // filter: none
+        raw.extend_from_slice(row);
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&(canvas.width as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(canvas.height as u32).to_be_bytes());
+    ihdr.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit, RGB, deflate, no filter set, no interlace
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    write_chunk(&mut out, b"IHDR", &ihdr);
+    write_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// zlib stream of stored deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / MAX_STORED_BLOCK * 5 + 11);
+    out.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(blocks.peek().is_none() as u8); // BFINAL, BTYPE = stored
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % MOD;
+        b = (b + a) % MOD;
+    }
+    b << 16 | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FG: Rgb = (255, 255, 255);
+    const BG: Rgb = (0, 0, 0);
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn test_png_header() {
+        let png = encode_png(&Canvas::for_cells(3, 2, BG));
+        assert_eq!(png[..8], PNG_SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 3 * CELL_W as u32);
+        assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 2 * CELL_H as u32);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    }
+
+    #[test]
+    fn test_draw_cell_glyphs_and_lines() {
+        let mut canvas = Canvas::for_cells(3, 1, BG);
+        canvas.draw_cell(0, 0, '!', Attr::UNDERLINE, FG, BG);
+        // '!' is a centered stem with a gap above the dot
+        assert_eq!(canvas.pixel(2, GLYPH_TOP), FG);
+        assert_eq!(canvas.pixel(2, GLYPH_TOP + 5), BG);
+        assert_eq!(canvas.pixel(0, UNDERLINE_ROW), FG);
+
+        // Horizontal line reaches both cell edges so borders connect
+        canvas.draw_cell(1, 0, '─', Attr::NONE, FG, (1, 2, 3));
+        assert_eq!(canvas.pixel(CELL_W, CELL_H / 2), FG);
+        assert_eq!(canvas.pixel(2 * CELL_W - 1, CELL_H / 2), FG);
+        assert_eq!(canvas.pixel(CELL_W, 0), (1, 2, 3));
+    }
+}
@@ -0,0 +1,399 @@
+//! Runtime — the engine as a Rust library, no JS host.
+//!
+//! A `Runtime` allocates its own SharedBuffer, starts an [`App`] on it and
+//! takes the TS side's two jobs: building the component tree (through
+//! [`NodeRegistry`]) and reading events, which arrive on a channel.
+//!
+//! ```text
+//! Runtime ─┬─ SharedBuffer ──── Node setters write props, as TS would
+//!          ├─ NodeRegistry ──── index allocation + tree links
+//!          ├─ App ───────────── engine thread (layout → framebuffer → render)
+//!          └─ event thread ──── EventSignal → pop_event → Sender<Event>
+//! ```
+//!
+//! ```ignore
+//! let mut rt = Runtime::new()?;
+//! let root = rt.add(COMPONENT_BOX, None).expect("buffer full")
+//!     .width(40.0)
+//!     .border(BorderStyle::Rounded)
+//!     .id();
+//! rt.add(COMPONENT_TEXT, Some(root)).and_then(|label| label.text("hello")).expect("buffer full");
+//! rt.wake();
+//! for event in rt.events() {
+//!     if event.kind == EventType::Exit { break }
+//! }
+//! ```
+//!
+//! Setters don't wake the engine; call `wake` once after a batch of writes.
+//!
+//! The runtime owns its buffer: dropping it stops the engine, waits for its
+//! threads and frees the memory.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::app::{App, EventSignal};
+use crate::host::NodeRegistry;
+use crate::pipeline::SessionIo;
+use crate::shared_buffer::{
+    calculate_buffer_size, BorderStyle, ComponentRef, EventType, FlexDirection, Rgba, SharedBuffer,
+    DEFAULT_MAX_NODES, DEFAULT_TEXT_POOL_SIZE, DIRTY_LAYOUT, DIRTY_TEXT, DIRTY_VISUAL, EVENT_SLOT_SIZE,
+    N_BG_COLOR, N_BORDER_COLOR, N_BORDER_STYLE, N_BORDER_WIDTH_BOTTOM, N_BORDER_WIDTH_LEFT,
+    N_BORDER_WIDTH_RIGHT, N_BORDER_WIDTH_TOP, N_FG_COLOR, N_FLEX_DIRECTION, N_FLEX_GROW, N_GAP, N_HEIGHT,
+    N_PADDING_BOTTOM, N_PADDING_LEFT, N_PADDING_RIGHT, N_PADDING_TOP, N_WIDTH,
+};
+
+/// One event from the ring buffer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Event {
+    pub kind: EventType,
+    /// The component it's for, None for app-wide events (resize, exit, …)
+    pub target: Option<ComponentRef>,
+    /// Payload, laid out per event type as in ts/engine/events.ts
+    pub data: [u8; 16],
+}
+
+impl Event {
+    fn from_slot(buf: &SharedBuffer, slot: &[u8; EVENT_SLOT_SIZE]) -> Self {
+        let kind = EventType::from(slot[0]);
+        let index = u16::from_le_bytes([slot[2], slot[3]]) as usize;
+        let target = (kind.targets_component() && index < buf.max_nodes()).then(|| buf.component_ref(index));
+        let mut data = [0u8; 16];
+        data.copy_from_slice(&slot[4..]);
+        Self { kind, target, data }
+    }
+}
+
+/// A standalone engine instance driven from Rust.
+pub struct Runtime {
+    app: App,
+    nodes: NodeRegistry,
+    events: Receiver<Event>,
+    stopped: Arc<AtomicBool>,
+    forwarder: Option<JoinHandle<()>>,
+    /// Last: freed after everything reading it
+    memory: OwnedBuffer,
+}
+
+impl Runtime {
+    /// Start on the process terminal with the default capacity
+    /// (`DEFAULT_MAX_NODES` nodes, `DEFAULT_TEXT_POOL_SIZE` of text).
+    pub fn new() -> io::Result<Self> {
+        Self::with_capacity(DEFAULT_MAX_NODES, DEFAULT_TEXT_POOL_SIZE)
+    }
+
+    /// Start on the process terminal with room for `max_nodes` nodes and
+    /// `text_pool_size` bytes of text.
+    pub fn with_capacity(max_nodes: usize, text_pool_size: usize) -> io::Result<Self> {
+        let memory = OwnedBuffer::allocate(max_nodes, text_pool_size)?;
+        let app = App::start(unsafe { memory.shared() })?;
+        Ok(Self::attach(app, memory))
+    }
+
+    /// Start on a remote terminal's streams (see `pipeline::session`).
+    pub fn session(max_nodes: usize, text_pool_size: usize, io: SessionIo) -> io::Result<Self> {
+        let memory = OwnedBuffer::allocate(max_nodes, text_pool_size)?;
+        let app = App::start_session(unsafe { memory.shared() }, io)?;
+        Ok(Self::attach(app, memory))
+    }
+
+    fn attach(app: App, memory: OwnedBuffer) -> Self {
+        let (tx, events) = mpsc::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let forwarder = {
+            let (buf, signal, stopped) = (app.buffer(), app.events(), stopped.clone());
+            std::thread::Builder::new()
+                .name("spark-events".into())
+                .spawn(move || forward_events(buf, &signal, &stopped, &tx))
+                .ok()
+        };
+        Self { app, nodes: NodeRegistry::new(), events, stopped, forwarder, memory }
+    }
+
+    /// The SharedBuffer, for reading computed layout and other output.
+    pub fn buffer(&self) -> &SharedBuffer {
+        self.memory.get()
+    }
+
+    /// Events from the engine, oldest first. Ends when the runtime stops.
+    pub fn events(&self) -> &Receiver<Event> {
+        &self.events
+    }
+
+    /// Create a node of `component_type` (COMPONENT_*) under `parent`
+    /// (None = a root). None if the buffer is full or `parent` is gone.
+    pub fn add(&mut self, component_type: u8, parent: Option<ComponentRef>) -> Option<Node<'_>> {
+        let buf = self.memory.get();
+        let parent = match parent {
+            Some(parent) => Some(buf.resolve(parent)?),
+            None => None,
+        };
+        let index = self.nodes.create(buf, component_type, parent)?;
+        Some(Node { buf, index })
+    }
+
+    /// The node `node` refers to, for changing its props. None once it's
+    /// been removed.
+    pub fn node(&self, node: ComponentRef) -> Option<Node<'_>> {
+        let buf = self.memory.get();
+        let index = buf.resolve(node).filter(|&index| self.nodes.is_live(buf, index))?;
+        Some(Node { buf, index })
+    }
+
+    /// Remove a node and everything under it. False if it was already gone.
+    pub fn remove(&mut self, node: ComponentRef) -> bool {
+        let buf = self.memory.get();
+        buf.resolve(node).is_some_and(|index| self.nodes.destroy(buf, index))
+    }
+
+    /// Tell the engine the tree changed.
+    pub fn wake(&self) {
+        self.app.wake();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.app.is_running()
+    }
+}
+
+impl Drop for Runtime {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Raises the event signal, letting the forwarder see `stopped`
+        self.app.stop();
+        self.app.join();
+        if let Some(forwarder) = self.forwarder.take() {
+            let _ = forwarder.join();
+        }
+        // Nothing reads the buffer now; `memory` frees it as it drops
+    }
+}
+
+/// A live node, with chainable prop setters. Each marks the node dirty for
+/// the stage that reads the prop.
+#[derive(Clone, Copy)]
+pub struct Node<'a> {
+    buf: &'a SharedBuffer,
+    index: usize,
+}
+
+impl Node<'_> {
+    /// Reference to this node, valid until it's removed
+    pub fn id(&self) -> ComponentRef {
+        self.buf.component_ref(self.index)
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Write an f32 prop at node offset `field` (N_*)
+    pub(crate) fn f32(self, field: usize, value: f32) -> Self {
+        if self.buf.write_field_f32(self.index, field, value) {
+            self.buf.mark_dirty(self.index, DIRTY_LAYOUT);
+        }
+        self
+    }
+
+    /// Write a u8 prop at node offset `field` (N_*)
+    pub(crate) fn u8(self, field: usize, value: u8) -> Self {
+        if self.buf.write_field_u8(self.index, field, value) {
+            self.buf.mark_dirty(self.index, DIRTY_LAYOUT | DIRTY_VISUAL);
+        }
+        self
+    }
+
+    /// Write a color prop at node offset `field` (N_*)
+    pub(crate) fn color(self, field: usize, color: Rgba) -> Self {
+        if self.buf.write_field_u32(self.index, field, color.to_packed()) {
+            self.buf.mark_dirty(self.index, DIRTY_VISUAL);
+        }
+        self
+    }
+
+    pub fn width(self, cells: f32) -> Self {
+        self.f32(N_WIDTH, cells)
+    }
+
+    pub fn height(self, cells: f32) -> Self {
+        self.f32(N_HEIGHT, cells)
+    }
+
+    pub fn flex_direction(self, direction: FlexDirection) -> Self {
+        self.u8(N_FLEX_DIRECTION, direction as u8)
+    }
+
+    pub fn flex_grow(self, grow: f32) -> Self {
+        self.f32(N_FLEX_GROW, grow)
+    }
+
+    pub fn gap(self, cells: f32) -> Self {
+        self.f32(N_GAP, cells)
+    }
+
+    /// Same padding on all four sides
+    pub fn padding(self, cells: f32) -> Self {
+        self.f32(N_PADDING_TOP, cells)
+            .f32(N_PADDING_RIGHT, cells)
+            .f32(N_PADDING_BOTTOM, cells)
+            .f32(N_PADDING_LEFT, cells)
+    }
+
+    /// Border on all four sides (`BorderStyle::None` removes it), taking a
+    /// cell each side like TS `border`
+    pub fn border(self, style: BorderStyle) -> Self {
+        let width = u8::from(style != BorderStyle::None);
+        self.u8(N_BORDER_STYLE, style as u8)
+            .u8(N_BORDER_WIDTH_TOP, width)
+            .u8(N_BORDER_WIDTH_RIGHT, width)
+            .u8(N_BORDER_WIDTH_BOTTOM, width)
+            .u8(N_BORDER_WIDTH_LEFT, width)
+    }
+
+    pub fn fg(self, color: Rgba) -> Self {
+        self.color(N_FG_COLOR, color)
+    }
+
+    pub fn bg(self, color: Rgba) -> Self {
+        self.color(N_BG_COLOR, color)
+    }
+
+    pub fn border_color(self, color: Rgba) -> Self {
+        self.color(N_BORDER_COLOR, color)
+    }
+
+    /// Set the text content. None if the text pool is full.
+    pub fn text(self, text: &str) -> Option<Self> {
+        if !self.buf.set_text(self.index, text) {
+            return None;
+        }
+        self.buf.mark_dirty(self.index, DIRTY_TEXT | DIRTY_LAYOUT);
+        Some(self)
+    }
+}
+
+/// A zeroed buffer with a fresh header and node defaults, owned by a
+/// runtime and freed with it.
+struct OwnedBuffer {
+    buf: *mut SharedBuffer,
+    data: *mut [u8],
+}
+
+// SAFETY: owns both allocations, like a Box; SharedBuffer is Send + Sync
+unsafe impl Send for OwnedBuffer {}
+unsafe impl Sync for OwnedBuffer {}
+
+impl OwnedBuffer {
+    fn allocate(max_nodes: usize, text_pool_size: usize) -> io::Result<Self> {
+        let len = calculate_buffer_size(max_nodes, text_pool_size);
+        let data = Box::into_raw(vec![0u8; len].into_boxed_slice());
+        match unsafe { SharedBuffer::initialize(data.cast::<u8>(), len, max_nodes, text_pool_size) } {
+            Some(buf) => Ok(Self { buf: Box::into_raw(Box::new(buf)), data }),
+            None => {
+                drop(unsafe { Box::from_raw(data) });
+                Err(io::Error::new(io::ErrorKind::InvalidInput, "buffer capacity out of range"))
+            }
+        }
+    }
+
+    fn get(&self) -> &SharedBuffer {
+        unsafe { &*self.buf }
+    }
+
+    /// The buffer, for the engine threads.
+    ///
+    /// # Safety
+    /// Every thread given it must be joined before this drops.
+    unsafe fn shared(&self) -> &'static SharedBuffer {
+        unsafe { &*self.buf }
+    }
+}
+
+impl Drop for OwnedBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            drop(Box::from_raw(self.buf));
+            drop(Box::from_raw(self.data));
+        }
+    }
+}
+
+/// Move events from the ring buffer to the channel each time the engine
+/// raises the signal, until the runtime stops or the receiver is dropped.
+fn forward_events(buf: &SharedBuffer, signal: &EventSignal, stopped: &AtomicBool, tx: &Sender<Event>) {
+    while !stopped.load(Ordering::SeqCst) {
+        signal.wait();
+        while let Some(slot) = buf.pop_event() {
+            if tx.send(Event::from_slot(buf, &slot)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_buffer::{COMPONENT_BOX, COMPONENT_TEXT};
+    use std::time::Duration;
+
+    /// A session runtime, and the client end that keeps its input open.
+    fn start() -> (Runtime, io::PipeWriter) {
+        let (input, client_keys) = io::pipe().unwrap();
+        let rt = Runtime::session(8, 1024, SessionIo {
+            input: Box::new(input),
+            close_input: None,
+            output: Box::new(io::sink()),
+            width: 20,
+            height: 5,
+        })
+        .unwrap();
+        (rt, client_keys)
+    }
+
+    #[test]
+    fn test_node_builder_writes_props() {
+        let (mut rt, _keys) = start();
+        let root = rt.add(COMPONENT_BOX, None).unwrap()
+            .width(12.0)
+            .padding(1.0)
+            .border(BorderStyle::Rounded)
+            .fg(Rgba::from_packed(0xFF112233))
+            .id();
+        let label = rt.add(COMPONENT_TEXT, Some(root)).unwrap().text("hello").unwrap().id();
+
+        let buf = rt.buffer();
+        assert_eq!(buf.width(root.index), 12.0);
+        assert_eq!(buf.border_width_top(root.index), 1);
+        assert_eq!(buf.fg_color(root.index), 0xFF112233);
+        assert_eq!(buf.parent_index(label.index), Some(root.index));
+        assert_eq!(buf.text(label.index), "hello");
+
+        assert!(rt.remove(root));
+        assert!(rt.node(label).is_none());
+        assert!(!rt.remove(label));
+        assert!(rt.add(COMPONENT_TEXT, Some(root)).is_none());
+    }
+
+    /// The next event of `kind`, skipping the engine's own (capabilities, …)
+    fn next(rt: &Runtime, kind: EventType) -> Event {
+        std::iter::repeat_with(|| rt.events().recv_timeout(Duration::from_secs(5)).unwrap())
+            .find(|event| event.kind == kind)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_events_arrive_on_channel() {
+        let (mut rt, _keys) = start();
+        let button = rt.add(COMPONENT_BOX, None).unwrap().id();
+
+        rt.buffer().push_event(EventType::Click, button.index as u16, &[0; 16]);
+        assert_eq!(next(&rt, EventType::Click).target, Some(button));
+
+        rt.buffer().push_event(EventType::Resize, 0, &[0; 16]);
+        assert_eq!(next(&rt, EventType::Resize).target, None);
+    }
+}
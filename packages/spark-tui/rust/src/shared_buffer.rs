@@ -12,11 +12,16 @@
 //! @version 3.0
 //! @date 2026-01-31
 
+use std::cell::RefCell;
 use std::ptr;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use bitflags::bitflags;
 
+use crate::app::EventSignal;
+
 // =============================================================================
 // CONSTANTS
 // =============================================================================
@@ -33,6 +38,16 @@ pub const DEFAULT_MAX_NODES: usize = 10_000;
 /// Default text pool size (10 MB)
 pub const DEFAULT_TEXT_POOL_SIZE: usize = 10 * 1024 * 1024;
 
+/// Text slots are allocated in multiples of this many bytes, so text that
+/// grows a little (counters, clocks) can be rewritten in place.
+pub const TEXT_SLOT_ALIGN: usize = 8;
+
+/// An allocation that carries the text pool write pointer past this
+/// percentage of the pool compacts first. Compaction brings the pointer
+/// back under the mark, so it runs once per crossing rather than on every
+/// write; past it, the pool is only compacted again when full.
+pub const TEXT_POOL_HIGH_WATER_PERCENT: usize = 75;
+
 /// Event ring header size
 pub const EVENT_RING_HEADER_SIZE: usize = 12;
 
@@ -81,12 +96,36 @@ pub const H_TERMINAL_HEIGHT: usize = 16;
 pub const H_GENERATION: usize = 20;
 pub const H_TEXT_POOL_SIZE: usize = 24;
 pub const H_TEXT_POOL_WRITE_PTR: usize = 28;
-// 32-63: reserved
+// Input-to-paint latency over the last LATENCY_WINDOW samples (Rust writes, TS reads; u32 μs)
+pub const H_INPUT_LATENCY_P50_US: usize = 32;
+pub const H_INPUT_LATENCY_P95_US: usize = 36;
+pub const H_INPUT_LATENCY_P99_US: usize = 40;
+pub const H_INPUT_LATENCY_LAST_US: usize = 44;
+pub const H_INPUT_LATENCY_COUNT: usize = 48;      // Samples recorded since start (u32)
+// Terminal capabilities (Rust writes, TS reads) — re-evaluated on resize / focus-in
+pub const H_CAPABILITIES_SEQ: usize = 52;         // Bumped on every capability change (u32)
+pub const H_CELL_PIXEL_WIDTH: usize = 56;         // Cell size in pixels, 0 = unknown (u16)
+pub const H_CELL_PIXEL_HEIGHT: usize = 58;        // (u16)
+pub const H_COLOR_DEPTH: usize = 60;              // (u8, ColorDepth)
+pub const H_UNICODE_LEVEL: usize = 61;            // (u8, UnicodeLevel)
+pub const H_MOUSE_AVAILABLE: usize = 62;          // 1 when mouse events are reported (u8)
+pub const H_TEXT_POOL_LOCK: usize = 63;           // 1 while TS writes the text pool or Rust uses it (u8, CAS 0 → 1)
 
 // --- Bytes 64-95: Wake & Sync (4-byte aligned for Atomics) ---
 pub const H_WAKE_RUST: usize = 64;
 pub const H_WAKE_TS: usize = 68;
-// 72-95: reserved
+pub const H_BATCH_WINDOW_US: usize = 72;          // Min time between frames, 0 = off (u32 μs, TS writes)
+// Last frame's terminal output (Rust writes, TS reads)
+pub const H_FRAME_CELLS: usize = 76;              // Cells written (u32)
+pub const H_FRAME_BYTES: usize = 80;              // Bytes written (u32)
+// Performance HUD (devtools::hud)
+pub const H_PERF_HUD_KEY: usize = 84;             // Toggle key, key-event keycode encoding (u32, 0 = off)
+pub const H_PERF_HUD_MODIFIERS: usize = 88;       // Modifiers that must be held, parser bits (u8)
+pub const H_PERF_HUD: usize = 89;                 // 1 while the HUD shows (u8, TS or the toggle key sets)
+// Component inspector (devtools::inspector)
+pub const H_INSPECTOR: usize = 90;                // 1 while the inspector is open (u8, TS or the toggle key sets)
+pub const H_INSPECTOR_MODIFIERS: usize = 91;      // Modifiers that must be held, parser bits (u8)
+pub const H_INSPECTOR_KEY: usize = 92;            // Toggle key, key-event keycode encoding (u32, 0 = off)
 
 // --- Bytes 96-127: State (Rust writes, TS reads) ---
 pub const H_FOCUSED_INDEX: usize = 96;
@@ -94,20 +133,63 @@ pub const H_HOVERED_INDEX: usize = 100;
 pub const H_PRESSED_INDEX: usize = 104;
 pub const H_MOUSE_X: usize = 108;
 pub const H_MOUSE_Y: usize = 110;
-// 112-127: reserved
+pub const H_WATCHDOG_TRIP_COUNT: usize = 112;     // Update storms broken by the watchdog (u32)
+pub const H_WATCHDOG_NODES: usize = 116;          // Offending node indices (4 × u16, 0xFFFF = empty)
+pub const H_WATCHDOG_ACTIVE: usize = 124;         // 1 while a storm is being suppressed (u8)
+// 125-127: reserved
+
+/// Number of offending node slots in the watchdog table
+pub const WATCHDOG_NODE_SLOTS: usize = 4;
+
+/// Empty slot marker in the watchdog node table
+pub const WATCHDOG_NODE_NONE: u16 = u16::MAX;
 
 // --- Bytes 128-159: Config (TS writes, Rust reads) ---
 pub const H_CONFIG_FLAGS: usize = 128;
 pub const H_RENDER_MODE: usize = 132;
 pub const H_CURSOR_CONFIG: usize = 136;
 pub const H_SCROLL_SPEED: usize = 140;
-// 144-159: reserved
+pub const H_COLOR_VISION: usize = 144;            // Color-vision simulation overlay (u8, ColorVision)
+pub const H_CONTROL_CHARS: usize = 145;           // How control characters in text are shown (u8, ControlChars)
+pub const H_KEYMAP_PRESET: usize = 146;           // Framework navigation/editing keys (u8, KeymapPreset)
+pub const H_LAYOUT_EVENTS: usize = 147;           // 1 = push LayoutChange after layouts that moved nodes (u8, TS observers)
+pub const H_SCREENSHOT_KEY: usize = 148;          // Screenshot key, key-event keycode encoding (u32, 0 = off)
+pub const H_SCREENSHOT_MODIFIERS: usize = 152;    // Modifiers that must be held, parser bits (u8)
+pub const H_SCREENSHOT_FLAGS: usize = 153;        // SCREENSHOT_* option bits (u8)
+pub const H_SCREENSHOT_TOAST: usize = 154;        // 1 while the confirmation toast shows (u8, Rust sets, TS clears)
+pub const H_DEBUG_LAYOUT: usize = 155;            // 1 = outline boxes, hatch padding, tint gaps (u8, devtools::layout_debug)
+pub const H_KEYMAP_OVERRIDES: usize = 156;        // NavAction bits the TS keymap handles instead (u16)
+pub const H_TEXT_POOL_COMPACTIONS: usize = 158;   // Bumped by whichever side compacts the text pool (u16, wraps)
+
+/// Screenshot option: copy the saved path to the clipboard (OSC 52)
+pub const SCREENSHOT_COPY_PATH: u8 = 1 << 0;
+/// Screenshot option: also save a `.png` image
+pub const SCREENSHOT_IMAGE: u8 = 1 << 1;
+/// Screenshot option: put the PNG on the clipboard where the terminal supports it (implies `SCREENSHOT_IMAGE`)
+pub const SCREENSHOT_COPY_IMAGE: u8 = 1 << 2;
 
 // --- Bytes 160-191: Events ---
 pub const H_EVENT_WRITE_IDX: usize = 160;
 pub const H_EVENT_READ_IDX: usize = 164;
 pub const H_EXIT_REQUESTED: usize = 168;
-// 169-191: reserved
+// Inline frame height bounds (TS writes, Rust reads) — the content height, clamped
+pub const H_INLINE_MIN_HEIGHT: usize = 169;       // Rows the frame takes at least, 0 = none (u8)
+pub const H_INLINE_MAX_HEIGHT: usize = 170;       // Rows the frame takes at most, 0 = unbounded (u16)
+pub const H_CELL_ASPECT: usize = 171;             // Cell height ÷ width for N_ASPECT_CELLS, × CELL_ASPECT_SCALE (u8, TS writes; 0 = measured)
+
+/// Fixed-point scale of H_CELL_ASPECT (2.0 is stored as 128)
+pub const CELL_ASPECT_SCALE: f32 = 64.0;
+
+/// Cell height ÷ width assumed until the terminal reports its cell size
+pub const DEFAULT_CELL_ASPECT: f32 = 2.0;
+// Hover payload table (Rust writes, TS reads) — nearest hovered node with a hover tag
+pub const H_HOVER_SEQ: usize = 172;               // Bumped on every payload change (u32)
+pub const H_HOVER_PAYLOAD_INDEX: usize = 176;     // Tagged node under the mouse, -1 = none (i32)
+pub const H_HOVER_TAG: usize = 180;               // That node's N_HOVER_TAG (u32)
+pub const H_HOVER_REGION_X: usize = 184;          // Its hit region on screen (u16 × 4)
+pub const H_HOVER_REGION_Y: usize = 186;
+pub const H_HOVER_REGION_W: usize = 188;
+pub const H_HOVER_REGION_H: usize = 190;
 
 // --- Bytes 192-255: Stats & Debug ---
 pub const H_RENDER_COUNT: usize = 192;
@@ -153,8 +235,18 @@ pub const N_OVERFLOW: usize = 31;
 pub const N_VISIBLE: usize = 32;
 pub const N_BOX_SIZING: usize = 33;
 pub const N_DIRTY_FLAGS: usize = 34;
-// 35: reserved
-// 36-63: reserved
+pub const N_OVERFLOW_X: usize = 35;  // Overflow along x, N_OVERFLOW when 0 (u8, Overflow + 1)
+pub const N_OVERFLOW_Y: usize = 36;  // Overflow along y, N_OVERFLOW when 0 (u8, Overflow + 1)
+// What each % dimension is a percentage of (u8, PercentBasis; TS writes with the dimension)
+pub const N_WIDTH_BASIS: usize = 37;
+pub const N_HEIGHT_BASIS: usize = 38;
+pub const N_MIN_WIDTH_BASIS: usize = 39;
+pub const N_MIN_HEIGHT_BASIS: usize = 40;
+pub const N_MAX_WIDTH_BASIS: usize = 41;
+pub const N_MAX_HEIGHT_BASIS: usize = 42;
+pub const N_ASPECT_CELLS: usize = 43; // 1 = N_ASPECT_RATIO is of the shape on screen, not of cells (u8)
+pub const N_RELATIVE_TO: usize = 44; // Ancestor PercentBasis::Named sizes against (i32, -1 = none)
+// 48-63: reserved
 
 // --- Cache Line 2 (64-127): Flexbox Properties ---
 pub const N_FLEX_DIRECTION: usize = 64;
@@ -212,7 +304,12 @@ pub const N_JUSTIFY_SELF: usize = 216;
 pub const N_FIRST_CHILD: usize = 220;
 pub const N_PREV_SIBLING: usize = 224;
 pub const N_NEXT_SIBLING: usize = 228;
-// 232-255: reserved
+pub const N_SLOT_ANCHOR: usize = 232;             // Keyed lists: sort among siblings at this index + 1, 0 = own index (i32)
+pub const N_SLOT_POSITION: usize = 236;           // Order within that slot (i32)
+pub const N_CREATION_SEQ: usize = 240;            // Allocation sequence, survives index reuse (u32)
+pub const N_GENERATION: usize = 244;              // Bumped each time TS releases the index, survives reuse (u32)
+pub const N_REGION: usize = 248;                  // Inline/append region a root draws into, stacked in ascending order (u8)
+// 249-255: reserved
 
 // --- Cache Lines 5-7 (256-447): Grid Column Tracks ---
 // 32 tracks × 6 bytes each = 192 bytes
@@ -251,7 +348,12 @@ pub const N_BORDER_CHAR_BL: usize = 726;
 pub const N_BORDER_CHAR_BR: usize = 728;
 pub const N_FOCUS_INDICATOR_CHAR: usize = 730;
 pub const N_FOCUS_INDICATOR_ENABLED: usize = 731;
-// 732-767: reserved
+pub const N_BG_FILL: usize = 732;                 // BackgroundFill kind (u8)
+pub const N_BG_FILL_SIZE: usize = 733;            // Pattern cell size in cells, 0 = 1 (u8)
+pub const N_BG_FILL_ANGLE: usize = 734;           // Gradient direction in degrees, 0 = left→right (u16)
+pub const N_BORDER_INNER_STYLE: usize = 736;      // Framed border inner line, 0 = single-line border (u8)
+pub const N_RENDER_CACHE: usize = 737;            // Reuse the subtree's rendered cells while unchanged, 0 = off (u8)
+// 738-767: reserved
 
 // --- Cache Line 13 (768-831): Colors ---
 pub const N_FG_COLOR: usize = 768;
@@ -265,7 +367,10 @@ pub const N_FOCUS_RING_COLOR: usize = 796;
 pub const N_CURSOR_FG_COLOR: usize = 800;
 pub const N_CURSOR_BG_COLOR: usize = 804;
 pub const N_SELECTION_COLOR: usize = 808;
-// 812-831: reserved
+pub const N_BG_COLOR_2: usize = 812;              // Second background fill color (u32)
+pub const N_BORDER_INNER_COLOR: usize = 816;      // Framed border inner line color, 0 = border color (u32)
+pub const N_BORDER_FRAME_FILL: usize = 820;       // Background of the framed border band, 0 = none (u32)
+// 824-831: reserved
 
 // --- Cache Line 14 (832-895): Text Properties ---
 pub const N_TEXT_OFFSET: usize = 832;
@@ -281,7 +386,15 @@ pub const N_TEXT_DECORATION_COLOR: usize = 848;
 pub const N_LINE_HEIGHT: usize = 852;
 pub const N_LETTER_SPACING: usize = 853;
 pub const N_MAX_LINES: usize = 854;
-// 855-895: reserved
+// 855: reserved (alignment)
+pub const N_TEXT_CAPACITY: usize = 856;           // Bytes allocated for the text slot, ≥ length (u32)
+pub const N_HREF_OFFSET: usize = 860;             // Hyperlink target in text pool (u32)
+pub const N_HREF_LENGTH: usize = 864;             // Target byte length, 0 = not a link (u32)
+pub const N_A11Y_LABEL_OFFSET: usize = 868;       // Accessible name in text pool (u32)
+pub const N_A11Y_LABEL_LENGTH: usize = 872;       // Name byte length, 0 = none (u32)
+pub const N_A11Y_VALUE_OFFSET: usize = 876;       // Accessible value in text pool (u32)
+pub const N_A11Y_VALUE_LENGTH: usize = 880;       // Value byte length, 0 = none (u32)
+// 884-895: reserved
 
 // --- Cache Line 15 (896-959): Interaction State ---
 pub const N_SCROLL_X: usize = 896;
@@ -297,10 +410,48 @@ pub const N_CURSOR_STYLE: usize = 926;
 pub const N_CURSOR_BLINK_RATE: usize = 927;
 pub const N_MAX_LENGTH: usize = 928;
 pub const N_INPUT_TYPE: usize = 929;
-// 930-959: reserved
-
-// --- Cache Line 16 (960-1023): Reserved (Animation, Effects, Transforms) ---
-// Reserved for future animation/effects/physics
+// 930-931: reserved (alignment)
+pub const N_HOVER_TAG: usize = 932;               // User tag exported on hover, 0 = none (u32)
+pub const N_GHOST_OFFSET: usize = 936;            // Input completion suggestion in text pool (u32)
+pub const N_GHOST_LENGTH: usize = 940;            // Suggestion byte length, 0 = none (u32)
+pub const N_WORD_BOUNDARY: usize = 944;           // WordBoundary rule for word motion/selection (u8)
+pub const N_A11Y_ROLE: usize = 945;               // What the node is to assistive tools (u8, A11yRole)
+pub const N_A11Y_LIVE: usize = 946;               // Announce changes to the node (u8, LiveRegion)
+pub const N_INPUT_FLAGS: usize = 947;             // INPUT_* bits for masked input editing (u8)
+pub const N_INPUT_MASK_OFFSET: usize = 948;       // Pattern/date mask in text pool (u32)
+pub const N_INPUT_MASK_LENGTH: usize = 952;       // Mask byte length, 0 = none (u32)
+pub const N_DIRECTION: usize = 956;               // Text direction, inherited when 0 (u8, TextDirection)
+pub const N_POINTER_EVENTS: usize = 957;          // Whether the mouse can hit the node, inherited when 0 (u8, PointerEvents)
+pub const N_STICKY: usize = 958;                   // Edge of a scrolling parent's viewport the node pins to (u8, Sticky)
+// 959: reserved
+
+// --- Cache Line 16 (960-1023): Animation ---
+// Smooth scroll: Rust records where a scroll is headed, TS springs the offset there
+pub const N_SCROLL_TARGET_X: usize = 960;          // Spring target for N_SCROLL_X (i32)
+pub const N_SCROLL_TARGET_Y: usize = 964;          // Spring target for N_SCROLL_Y (i32)
+pub const N_SCROLL_VELOCITY_X: usize = 968;        // Spring velocity, cells/s (f32, TS writes)
+pub const N_SCROLL_VELOCITY_Y: usize = 972;        // Spring velocity, cells/s (f32, TS writes)
+pub const N_SMOOTH_SCROLL: usize = 976;            // 1 = scrolls ease to their target (u8, TS writes)
+pub const N_SCROLL_ANIMATING: usize = 977;         // 1 while a target is pending (u8, Rust sets, TS clears)
+pub const N_SCROLL_FLAGS: usize = 978;             // SCROLL_* bits (u8, TS writes)
+pub const N_REACH_END_THRESHOLD: usize = 979;      // Rows short of the bottom that count as its end (u8, TS writes)
+// IME composition: uncommitted text drawn underlined at an input's cursor
+pub const N_PREEDIT_OFFSET: usize = 980;           // Preedit text in text pool (u32)
+pub const N_PREEDIT_LENGTH: usize = 984;           // Preedit byte length, 0 = not composing (u32)
+// 988-1023: reserved (effects, transforms)
+
+/// Every (offset, length, capacity) field set that points into the text
+/// pool. Compaction moves exactly these; a new pool string needs an entry
+/// here and in TS's `TEXT_POOL_SLOTS`.
+pub const TEXT_POOL_SLOTS: [(usize, usize, Option<usize>); 7] = [
+    (N_TEXT_OFFSET, N_TEXT_LENGTH, Some(N_TEXT_CAPACITY)),
+    (N_GHOST_OFFSET, N_GHOST_LENGTH, None),
+    (N_HREF_OFFSET, N_HREF_LENGTH, None),
+    (N_A11Y_LABEL_OFFSET, N_A11Y_LABEL_LENGTH, None),
+    (N_A11Y_VALUE_OFFSET, N_A11Y_VALUE_LENGTH, None),
+    (N_INPUT_MASK_OFFSET, N_INPUT_MASK_LENGTH, None),
+    (N_PREEDIT_OFFSET, N_PREEDIT_LENGTH, None),
+];
 
 // =============================================================================
 // LEGACY OFFSET ALIASES (for layout_tree.rs compatibility)
@@ -430,6 +581,11 @@ bitflags! {
         const FOCUS_ON_CLICK = 1 << 6;
         const MOUSE_ENABLED = 1 << 7;
         const KITTY_KEYBOARD = 1 << 8;
+        /// Arrow keys move focus to the nearest focusable in that direction (opt-in)
+        const SPATIAL_NAVIGATION = 1 << 9;
+        /// The focused input shows the terminal's own cursor instead of a
+        /// drawn one (opt-in)
+        const NATIVE_CURSOR = 1 << 10;
     }
 }
 
@@ -456,6 +612,30 @@ pub const DIRTY_VISUAL: u8 = 1 << 1;
 pub const DIRTY_TEXT: u8 = 1 << 2;
 pub const DIRTY_HIERARCHY: u8 = 1 << 3;
 
+/// Node fields layout reads. TS's setters mark writes to them DIRTY_LAYOUT.
+const LAYOUT_FIELDS: [std::ops::Range<usize>; 10] = [
+    N_WIDTH..N_COMPONENT_TYPE,                                               // Sizes, aspect ratio
+    N_DISPLAY..N_DIRTY_FLAGS,                                                // Display, position, overflow, visible, box sizing
+    N_OVERFLOW_X..N_RELATIVE_TO + 4,                                         // Per-axis overflow, percent bases
+    N_FLEX_DIRECTION..N_COLUMN_GAP + 4,                                      // Flex container and item
+    N_PADDING_TOP..N_PARENT_INDEX,                                           // Padding, margin, inset, border widths
+    N_GRID_AUTO_FLOW..N_JUSTIFY_SELF + 1,                                    // Grid container and item
+    N_GRID_COLUMN_TRACKS..N_GRID_ROW_TRACKS + MAX_GRID_TRACKS * GRID_TRACK_SIZE, // Grid tracks
+    N_BORDER_INNER_STYLE..N_BORDER_INNER_STYLE + 1,
+    N_TEXT_WRAP..N_TEXT_WRAP + 1,
+    N_DIRECTION..N_DIRECTION + 1,
+];
+
+/// The DIRTY_* flag a write to node field `field` raises, as TS's setters
+/// classify it: the tree's shape, what layout reads, or paint only.
+pub fn field_dirty(field: usize) -> u8 {
+    match field {
+        N_COMPONENT_TYPE | N_PARENT_INDEX | N_SLOT_ANCHOR | N_SLOT_POSITION => DIRTY_HIERARCHY,
+        _ if LAYOUT_FIELDS.iter().any(|fields| fields.contains(&field)) => DIRTY_LAYOUT,
+        _ => DIRTY_VISUAL,
+    }
+}
+
 // =============================================================================
 // INTERACTION FLAGS
 // =============================================================================
@@ -465,6 +645,36 @@ pub const FLAG_FOCUSED: u8 = 1 << 1;
 pub const FLAG_HOVERED: u8 = 1 << 2;
 pub const FLAG_PRESSED: u8 = 1 << 3;
 pub const FLAG_DISABLED: u8 = 1 << 4;
+/// Container traps focus: Tab and spatial navigation stay inside it
+pub const FLAG_FOCUS_TRAP: u8 = 1 << 5;
+/// Tab and Shift+Tab within this box go to its onKey handlers instead of moving focus
+pub const FLAG_CAPTURE_TAB: u8 = 1 << 6;
+
+// =============================================================================
+// INPUT FLAGS
+// =============================================================================
+
+/// Number input accepts a leading minus sign
+pub const INPUT_SIGNED: u8 = 1 << 0;
+/// Number input accepts one decimal point
+pub const INPUT_DECIMAL: u8 = 1 << 1;
+/// The text slot holds the placeholder, not the value (the value is empty)
+pub const INPUT_PLACEHOLDER: u8 = 1 << 2;
+/// TS edits this input itself; keys go to its onKey handlers instead
+pub const INPUT_SCRIPTED: u8 = 1 << 3;
+
+// =============================================================================
+// SCROLL FLAGS
+// =============================================================================
+
+/// Scrolled to the bottom, the node stays there as its content grows
+pub const SCROLL_STICK_BOTTOM: u8 = 1 << 0;
+/// Push a ScrollChange event whenever Rust moves the node's scroll offset
+pub const SCROLL_EVENTS: u8 = 1 << 1;
+/// Let content changes above the viewport move what's shown (no scroll anchoring)
+pub const SCROLL_NO_ANCHOR: u8 = 1 << 2;
+/// Push a ReachEnd event when the offset comes within N_REACH_END_THRESHOLD of the bottom
+pub const SCROLL_REACH_END: u8 = 1 << 3;
 
 // =============================================================================
 // TEXT ATTRIBUTES
@@ -487,6 +697,29 @@ pub const COMPONENT_NONE: u8 = 0;
 pub const COMPONENT_BOX: u8 = 1;
 pub const COMPONENT_TEXT: u8 = 2;
 pub const COMPONENT_INPUT: u8 = 3;
+pub const COMPONENT_SELECT: u8 = 4;
+pub const COMPONENT_PROGRESS: u8 = 5;
+
+/// A node index plus the generation it was allocated in. TS recycles
+/// released indices, so an index kept across frames can name a different
+/// component later; the ref stops resolving instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentRef {
+    pub index: usize,
+    pub generation: u32,
+}
+
+/// Event byte 1 for events that aren't tied to a component's generation
+pub const EVENT_UNTAGGED: u8 = 0;
+
+/// Event byte 1 for a component event: its target's generation, never
+/// `EVENT_UNTAGGED`. TS drops component events whose tag no longer
+/// matches, so a released component's late events don't reach the next
+/// one at its index.
+#[inline]
+pub const fn generation_tag(generation: u32) -> u8 {
+    (generation % 255) as u8 + 1
+}
 
 // =============================================================================
 // BORDER STYLES
@@ -524,6 +757,16 @@ pub enum EventType {
     Cancel = 13,
     Exit = 14,
     Resize = 15,
+    Screenshot = 16,
+    ScrollAnimate = 17,
+    Capabilities = 18,
+    LayoutChange = 19,
+    Error = 20,
+    ContextMenu = 21,
+    TerminalColor = 22,
+    ScrollChange = 23,
+    ReachEnd = 24,
+    Hover = 25,
 }
 
 impl From<u8> for EventType {
@@ -544,11 +787,45 @@ impl From<u8> for EventType {
             13 => Self::Cancel,
             14 => Self::Exit,
             15 => Self::Resize,
+            16 => Self::Screenshot,
+            17 => Self::ScrollAnimate,
+            18 => Self::Capabilities,
+            19 => Self::LayoutChange,
+            20 => Self::Error,
+            21 => Self::ContextMenu,
+            22 => Self::TerminalColor,
+            23 => Self::ScrollChange,
+            24 => Self::ReachEnd,
+            25 => Self::Hover,
             _ => Self::None,
         }
     }
 }
 
+impl EventType {
+    /// Whether the event is about its component (and so tagged with its
+    /// generation) rather than the app, with a placeholder index.
+    pub fn targets_component(self) -> bool {
+        !matches!(
+            self,
+            Self::None
+                | Self::Exit
+                | Self::Resize
+                | Self::Screenshot
+                | Self::Capabilities
+                | Self::LayoutChange
+                | Self::Error
+                | Self::TerminalColor
+        )
+    }
+}
+
+/// `TerminalColor` event slots
+pub const TERMINAL_COLOR_FOREGROUND: u8 = 0;
+pub const TERMINAL_COLOR_BACKGROUND: u8 = 1;
+/// Foreground, background
+pub const TERMINAL_COLOR_SLOTS: usize = 2;
+
 // =============================================================================
 // ENUMS
 // =============================================================================
@@ -651,6 +928,8 @@ pub enum AlignContent {
     SpaceBetween = 3,
     SpaceAround = 4,
     SpaceEvenly = 5,
+    /// Lines grow to share the free cross space
+    Stretch = 6,
 }
 
 impl From<u8> for AlignContent {
@@ -661,6 +940,7 @@ impl From<u8> for AlignContent {
             3 => Self::SpaceBetween,
             4 => Self::SpaceAround,
             5 => Self::SpaceEvenly,
+            6 => Self::Stretch,
             _ => Self::Start,
         }
     }
@@ -727,6 +1007,32 @@ impl From<u8> for Overflow {
     }
 }
 
+/// What a percentage dimension is a percentage of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum PercentBasis {
+    /// The parent, as Taffy resolves it
+    #[default]
+    Parent = 0,
+    /// The terminal's width (`vw`)
+    ViewportWidth = 1,
+    /// The terminal's height (`vh`)
+    ViewportHeight = 2,
+    /// The same axis of the ancestor in `N_RELATIVE_TO`
+    Named = 3,
+}
+
+impl From<u8> for PercentBasis {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::ViewportWidth,
+            2 => Self::ViewportHeight,
+            3 => Self::Named,
+            _ => Self::Parent,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[repr(u8)]
 pub enum Display {
@@ -753,6 +1059,12 @@ pub enum TextAlign {
     Left = 0,
     Center = 1,
     Right = 2,
+    /// Left in a left-to-right paragraph, right in a right-to-left one
+    Start = 3,
+    /// Right in a left-to-right paragraph, left in a right-to-left one
+    End = 4,
+    /// Wrapped lines fill the width; a paragraph's last line aligns to Start
+    Justify = 5,
 }
 
 impl From<u8> for TextAlign {
@@ -760,11 +1072,88 @@ impl From<u8> for TextAlign {
         match value {
             1 => Self::Center,
             2 => Self::Right,
+            3 => Self::Start,
+            4 => Self::End,
+            5 => Self::Justify,
             _ => Self::Left,
         }
     }
 }
 
+/// Whether the mouse can hit a node (CSS `pointer-events`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum PointerEvents {
+    /// The parent's (Auto at the root)
+    #[default]
+    Inherit = 0,
+    Auto = 1,
+    /// Clicks, hover and scroll go to whatever is drawn beneath
+    None = 2,
+    /// The whole box takes the mouse, background or not — a backdrop that
+    /// catches clicks outside what it holds (children inherit Auto)
+    All = 3,
+}
+
+impl From<u8> for PointerEvents {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Auto,
+            2 => Self::None,
+            3 => Self::All,
+            _ => Self::Inherit,
+        }
+    }
+}
+
+/// Viewport edge a child of a scroll container stays pinned to (CSS
+/// `position: sticky`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum Sticky {
+    /// Scrolls with the rest of the content
+    #[default]
+    None = 0,
+    /// Stops at the top edge instead of scrolling past it (table headers)
+    Top = 1,
+    /// Stops at the bottom edge until scrolled into place (footers)
+    Bottom = 2,
+}
+
+impl From<u8> for Sticky {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Top,
+            2 => Self::Bottom,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Base direction of text (and of a box's rows)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum TextDirection {
+    /// The parent's direction (Auto at the root)
+    #[default]
+    Inherit = 0,
+    Ltr = 1,
+    Rtl = 2,
+    /// Each paragraph takes the direction of its first strong character
+    Auto = 3,
+}
+
+impl From<u8> for TextDirection {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Ltr,
+            2 => Self::Rtl,
+            3 => Self::Auto,
+            _ => Self::Inherit,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[repr(u8)]
 pub enum TextWrap {
@@ -772,6 +1161,8 @@ pub enum TextWrap {
     NoWrap = 0,
     Wrap = 1,
     Truncate = 2,
+    /// Wrap, also breaking words at soft hyphens (U+00AD)
+    Hyphenate = 3,
 }
 
 impl From<u8> for TextWrap {
@@ -779,6 +1170,7 @@ impl From<u8> for TextWrap {
         match value {
             1 => Self::Wrap,
             2 => Self::Truncate,
+            3 => Self::Hyphenate,
             _ => Self::NoWrap,
         }
     }
@@ -822,6 +1214,353 @@ impl From<u8> for CursorStyle {
     }
 }
 
+/// What an input accepts. Number, Date and Pattern reject keystrokes that
+/// don't fit (see `input::mask`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum InputType {
+    #[default]
+    Text = 0,
+    Password = 1,
+    /// Digits, with a sign and decimal point per INPUT_SIGNED / INPUT_DECIMAL
+    Number = 2,
+    Email = 3,
+    /// Fixed-layout date; the mask is its format, e.g. `DD/MM/YYYY`
+    Date = 4,
+    /// Fixed-layout mask: `9` digit, `a` letter, `*` either, anything else literal
+    Pattern = 5,
+}
+
+impl From<u8> for InputType {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Password,
+            2 => Self::Number,
+            3 => Self::Email,
+            4 => Self::Date,
+            5 => Self::Pattern,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// What counts as a word for Ctrl+arrow motion, word deletion and
+/// double-click selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum WordBoundary {
+    /// Unicode word boundaries (UAX #29)
+    #[default]
+    Unicode = 0,
+    /// Runs of alphanumeric characters
+    Alphanumeric = 1,
+    /// Runs of alphanumerics and underscores
+    Identifier = 2,
+    /// Runs of anything but whitespace
+    Whitespace = 3,
+}
+
+impl From<u8> for WordBoundary {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Alphanumeric,
+            2 => Self::Identifier,
+            3 => Self::Whitespace,
+            _ => Self::Unicode,
+        }
+    }
+}
+
+/// What a node is to assistive tools. Announcements read it after the
+/// node's label ("Save, button").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum A11yRole {
+    /// Not announced as anything in particular
+    #[default]
+    None = 0,
+    Button = 1,
+    Checkbox = 2,
+    Radio = 3,
+    Switch = 4,
+    TextBox = 5,
+    Link = 6,
+    List = 7,
+    ListItem = 8,
+    Menu = 9,
+    MenuItem = 10,
+    Tab = 11,
+    TabPanel = 12,
+    Dialog = 13,
+    Alert = 14,
+    Status = 15,
+    Heading = 16,
+    ProgressBar = 17,
+    Slider = 18,
+    Table = 19,
+    Group = 20,
+}
+
+impl From<u8> for A11yRole {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Button,
+            2 => Self::Checkbox,
+            3 => Self::Radio,
+            4 => Self::Switch,
+            5 => Self::TextBox,
+            6 => Self::Link,
+            7 => Self::List,
+            8 => Self::ListItem,
+            9 => Self::Menu,
+            10 => Self::MenuItem,
+            11 => Self::Tab,
+            12 => Self::TabPanel,
+            13 => Self::Dialog,
+            14 => Self::Alert,
+            15 => Self::Status,
+            16 => Self::Heading,
+            17 => Self::ProgressBar,
+            18 => Self::Slider,
+            19 => Self::Table,
+            20 => Self::Group,
+            _ => Self::None,
+        }
+    }
+}
+
+impl A11yRole {
+    /// Spoken name of the role, empty for `None`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Button => "button",
+            Self::Checkbox => "checkbox",
+            Self::Radio => "radio button",
+            Self::Switch => "switch",
+            Self::TextBox => "text box",
+            Self::Link => "link",
+            Self::List => "list",
+            Self::ListItem => "list item",
+            Self::Menu => "menu",
+            Self::MenuItem => "menu item",
+            Self::Tab => "tab",
+            Self::TabPanel => "tab panel",
+            Self::Dialog => "dialog",
+            Self::Alert => "alert",
+            Self::Status => "status",
+            Self::Heading => "heading",
+            Self::ProgressBar => "progress bar",
+            Self::Slider => "slider",
+            Self::Table => "table",
+            Self::Group => "group",
+        }
+    }
+}
+
+/// Whether changes to a node are announced, and how urgently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum LiveRegion {
+    #[default]
+    Off = 0,
+    /// Announced once the user is idle
+    Polite = 1,
+    /// Announced at once, interrupting
+    Assertive = 2,
+}
+
+impl From<u8> for LiveRegion {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Polite,
+            2 => Self::Assertive,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// How a box paints its background. Non-solid fills blend from the bg
+/// color to the second bg color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum BackgroundFill {
+    #[default]
+    Solid = 0,
+    /// Linear gradient along `N_BG_FILL_ANGLE`
+    LinearGradient = 1,
+    /// Alternating squares of `N_BG_FILL_SIZE` cells
+    Checker = 2,
+    /// Horizontal bands
+    StripesHorizontal = 3,
+    /// Vertical bands
+    StripesVertical = 4,
+    /// Diagonal bands
+    StripesDiagonal = 5,
+}
+
+impl From<u8> for BackgroundFill {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::LinearGradient,
+            2 => Self::Checker,
+            3 => Self::StripesHorizontal,
+            4 => Self::StripesVertical,
+            5 => Self::StripesDiagonal,
+            _ => Self::Solid,
+        }
+    }
+}
+
+/// Color-vision deficiency simulated by the dev overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum ColorVision {
+    /// Overlay off
+    #[default]
+    None = 0,
+    /// Missing L cones (red-blind)
+    Protanopia = 1,
+    /// Missing M cones (green-blind)
+    Deuteranopia = 2,
+    /// Missing S cones (blue-blind)
+    Tritanopia = 3,
+}
+
+impl From<u8> for ColorVision {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Protanopia,
+            2 => Self::Deuteranopia,
+            3 => Self::Tritanopia,
+            _ => Self::None,
+        }
+    }
+}
+
+/// How control characters in text content are made visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum ControlChars {
+    /// Control Pictures glyphs: `␀`, `␛`, `␡`
+    #[default]
+    Pictures = 0,
+    /// Caret notation: `^@`, `^[`, `^?`
+    Caret = 1,
+}
+
+impl From<u8> for ControlChars {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Caret,
+            _ => Self::Pictures,
+        }
+    }
+}
+
+/// Key set for framework navigation and input editing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum KeymapPreset {
+    /// Arrows, Page Up/Down, Home/End
+    #[default]
+    Standard = 0,
+    /// Standard + hjkl, Ctrl+D/U, Ctrl+F/B, g/G
+    Vim = 1,
+    /// Standard + Ctrl+N/P/F/B, Ctrl+V/Alt+V, Alt+</>, readline editing
+    Emacs = 2,
+}
+
+impl From<u8> for KeymapPreset {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Vim,
+            2 => Self::Emacs,
+            _ => Self::Standard,
+        }
+    }
+}
+
+/// Where a panic was caught (Error event payload).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum ErrorSource {
+    /// Keyboard dispatch
+    #[default]
+    Key = 0,
+    /// Mouse dispatch
+    Mouse = 1,
+    /// A component timer callback
+    Timer = 2,
+    /// A task's apply callback
+    Task = 3,
+    /// Layout computation
+    Layout = 4,
+    /// A frame callback (state::frame)
+    Frame = 5,
+}
+
+impl From<u8> for ErrorSource {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Mouse,
+            2 => Self::Timer,
+            3 => Self::Task,
+            4 => Self::Layout,
+            5 => Self::Frame,
+            _ => Self::Key,
+        }
+    }
+}
+
+/// Colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[repr(u8)]
+pub enum ColorDepth {
+    /// No color (dumb terminal, or `NO_COLOR`)
+    Mono = 0,
+    /// The 16 ANSI colors
+    #[default]
+    Ansi16 = 1,
+    Ansi256 = 2,
+    /// 24-bit RGB
+    TrueColor = 3,
+}
+
+impl From<u8> for ColorDepth {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Mono,
+            2 => Self::Ansi256,
+            3 => Self::TrueColor,
+            _ => Self::Ansi16,
+        }
+    }
+}
+
+/// How much of Unicode the terminal can draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum UnicodeLevel {
+    /// Everything, including wide CJK and emoji
+    #[default]
+    Full = 0,
+    /// Single-width symbols: box drawing, blocks, arrows
+    Basic = 1,
+    /// ASCII only — borders fall back to `+-|`
+    Ascii = 2,
+}
+
+impl From<u8> for UnicodeLevel {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Basic,
+            2 => Self::Ascii,
+            _ => Self::Full,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[repr(u8)]
 pub enum RenderMode {
@@ -1175,6 +1914,8 @@ pub struct SharedBuffer {
     text_pool_size: usize,
     text_pool_offset: usize,
     event_ring_offset: usize,
+    /// Raised whenever events are written for TS (see `App`).
+    events: OnceLock<Arc<EventSignal>>,
 }
 
 // SAFETY: The buffer is shared with JS via SharedArrayBuffer.
@@ -1182,6 +1923,29 @@ pub struct SharedBuffer {
 unsafe impl Send for SharedBuffer {}
 unsafe impl Sync for SharedBuffer {}
 
+thread_local! {
+    /// Text pool locks this thread holds, by address: a write inside a held
+    /// span (an input edit during a message) must not wait on itself.
+    static HELD_TEXT_POOLS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Spins on the text pool lock before yielding the thread between tries.
+const TEXT_POOL_SPINS: u32 = 1000;
+
+/// Held text pool lock (`H_TEXT_POOL_LOCK`), released on drop. Empty when
+/// this thread already held it: only the outermost hold releases.
+pub struct TextPoolLock<'a>(Option<&'a AtomicU8>);
+
+impl Drop for TextPoolLock<'_> {
+    fn drop(&mut self) {
+        if let Some(lock) = self.0 {
+            let address = lock as *const AtomicU8 as usize;
+            HELD_TEXT_POOLS.with(|held| held.borrow_mut().retain(|&a| a != address));
+            lock.store(0, Ordering::Release);
+        }
+    }
+}
+
 impl SharedBuffer {
     /// Create from raw pointer (from FFI).
     ///
@@ -1203,7 +1967,46 @@ impl SharedBuffer {
             text_pool_size,
             text_pool_offset,
             event_ring_offset,
+            events: OnceLock::new(),
+        }
+    }
+
+    /// Lay out a fresh buffer in `ptr[..len]` — header defaults and every
+    /// node reset — as TS's `createSharedBuffer` does, for hosts without the
+    /// TS side. None if `len` is too small for the configuration.
+    ///
+    /// # Safety
+    /// Same as `from_raw`; nothing else may be using the memory yet.
+    pub unsafe fn initialize(ptr: *mut u8, len: usize, max_nodes: usize, text_pool_size: usize) -> Option<Self> {
+        if len < calculate_buffer_size(max_nodes, text_pool_size) {
+            return None;
+        }
+        // SAFETY: caller guarantees ptr is valid for len bytes
+        unsafe {
+            ptr::write_bytes(ptr, 0, len);
+            ptr::write_unaligned(ptr.add(H_VERSION) as *mut u32, 3);
+            ptr::write_unaligned(ptr.add(H_MAX_NODES) as *mut u32, max_nodes as u32);
+            ptr::write_unaligned(ptr.add(H_TEXT_POOL_SIZE) as *mut u32, text_pool_size as u32);
         }
+        let buf = unsafe { Self::from_raw(ptr, len) };
+
+        buf.write_header_i32(H_FOCUSED_INDEX, -1);
+        buf.write_header_i32(H_HOVERED_INDEX, -1);
+        buf.write_header_i32(H_PRESSED_INDEX, -1);
+        buf.write_header_i32(H_HOVER_PAYLOAD_INDEX, -1);
+        buf.write_header_u32(H_CONFIG_FLAGS, ConfigFlags::default().bits());
+        buf.write_header_u32(H_RENDER_MODE, RenderMode::Diff as u32);
+        buf.write_header_u32(H_SCROLL_SPEED, 3);
+        for i in 0..max_nodes {
+            buf.init_node(i);
+        }
+        Some(buf)
+    }
+
+    /// Raise `events` from now on when events are written for TS. A buffer
+    /// belongs to one `App`; later calls are ignored.
+    pub fn attach_event_signal(&self, events: Arc<EventSignal>) {
+        let _ = self.events.set(events);
     }
 
     /// Get raw pointer
@@ -1334,59 +2137,292 @@ impl SharedBuffer {
         self.write_header_u32(H_TERMINAL_HEIGHT, height);
     }
 
-    /// Get generation counter
+    // Terminal capabilities
+
     #[inline]
-    pub fn generation(&self) -> u32 {
-        self.read_header_u32(H_GENERATION)
+    pub fn capabilities_seq(&self) -> u32 {
+        self.read_header_u32(H_CAPABILITIES_SEQ)
     }
 
-    /// Get config flags
     #[inline]
-    pub fn config_flags(&self) -> ConfigFlags {
-        ConfigFlags::from_bits_truncate(self.read_header_u32(H_CONFIG_FLAGS))
+    pub fn bump_capabilities_seq(&self) {
+        let seq = self.read_header_u32(H_CAPABILITIES_SEQ);
+        self.write_header_u32(H_CAPABILITIES_SEQ, seq.wrapping_add(1));
     }
 
-    /// Get render mode
+    /// Cell size in pixels, `None` until the terminal reports it
     #[inline]
-    pub fn render_mode(&self) -> RenderMode {
-        RenderMode::from(self.read_header_u32(H_RENDER_MODE) as u8)
+    pub fn cell_pixel_size(&self) -> Option<(u16, u16)> {
+        let w = self.read_header_u16(H_CELL_PIXEL_WIDTH);
+        let h = self.read_header_u16(H_CELL_PIXEL_HEIGHT);
+        (w > 0 && h > 0).then_some((w, h))
     }
 
-    /// Get scroll speed
     #[inline]
-    pub fn scroll_speed(&self) -> u32 {
-        self.read_header_u32(H_SCROLL_SPEED)
+    pub fn set_cell_pixel_size(&self, width: u16, height: u16) {
+        self.write_header_u16(H_CELL_PIXEL_WIDTH, width);
+        self.write_header_u16(H_CELL_PIXEL_HEIGHT, height);
     }
 
-    // =========================================================================
-    // STATE (Rust writes, TS reads)
-    // =========================================================================
+    #[inline]
+    pub fn color_depth(&self) -> ColorDepth {
+        ColorDepth::from(self.read_header_u8(H_COLOR_DEPTH))
+    }
 
-    /// Get focused component index (-1 = none)
     #[inline]
-    pub fn focused_index(&self) -> i32 {
-        self.read_header_i32(H_FOCUSED_INDEX)
+    pub fn set_color_depth(&self, depth: ColorDepth) {
+        self.write_header_u8(H_COLOR_DEPTH, depth as u8);
     }
 
-    /// Set focused component index
     #[inline]
-    pub fn set_focused_index(&self, idx: i32) {
-        self.write_header_i32(H_FOCUSED_INDEX, idx)
+    pub fn unicode_level(&self) -> UnicodeLevel {
+        UnicodeLevel::from(self.read_header_u8(H_UNICODE_LEVEL))
     }
 
-    /// Get hovered component index (-1 = none)
     #[inline]
-    pub fn hovered_index(&self) -> i32 {
-        self.read_header_i32(H_HOVERED_INDEX)
+    pub fn set_unicode_level(&self, level: UnicodeLevel) {
+        self.write_header_u8(H_UNICODE_LEVEL, level as u8);
     }
 
-    /// Set hovered component index
     #[inline]
-    pub fn set_hovered_index(&self, idx: i32) {
-        self.write_header_i32(H_HOVERED_INDEX, idx)
+    pub fn mouse_available(&self) -> bool {
+        self.read_header_u8(H_MOUSE_AVAILABLE) != 0
     }
 
-    /// Get pressed component index (-1 = none)
+    #[inline]
+    pub fn set_mouse_available(&self, available: bool) {
+        self.write_header_u8(H_MOUSE_AVAILABLE, available as u8);
+    }
+
+    /// Get generation counter
+    #[inline]
+    pub fn generation(&self) -> u32 {
+        self.read_header_u32(H_GENERATION)
+    }
+
+    /// Get config flags
+    #[inline]
+    pub fn config_flags(&self) -> ConfigFlags {
+        ConfigFlags::from_bits_truncate(self.read_header_u32(H_CONFIG_FLAGS))
+    }
+
+    /// Get render mode
+    #[inline]
+    pub fn render_mode(&self) -> RenderMode {
+        RenderMode::from(self.read_header_u32(H_RENDER_MODE) as u8)
+    }
+
+    /// Get scroll speed
+    #[inline]
+    pub fn scroll_speed(&self) -> u32 {
+        self.read_header_u32(H_SCROLL_SPEED)
+    }
+
+    /// Get the color-vision simulation overlay mode
+    #[inline]
+    pub fn color_vision(&self) -> ColorVision {
+        ColorVision::from(self.read_header_u8(H_COLOR_VISION))
+    }
+
+    /// How control characters in text content are shown
+    #[inline]
+    pub fn control_chars(&self) -> ControlChars {
+        ControlChars::from(self.read_header_u8(H_CONTROL_CHARS))
+    }
+
+    /// Whether the layout debug overlay is on
+    #[inline]
+    pub fn debug_layout(&self) -> bool {
+        self.read_header_u8(H_DEBUG_LAYOUT) != 0
+    }
+
+    /// Turn the layout debug overlay (outlines, padding, gaps) on or off
+    #[inline]
+    pub fn set_debug_layout(&self, on: bool) {
+        self.write_header_u8(H_DEBUG_LAYOUT, on as u8);
+    }
+
+    /// Cell height ÷ width: configured, else from the reported cell size, else 2
+    pub fn cell_aspect(&self) -> f32 {
+        let configured = self.read_header_u8(H_CELL_ASPECT);
+        if configured > 0 {
+            return configured as f32 / CELL_ASPECT_SCALE;
+        }
+        match self.cell_pixel_size() {
+            Some((w, h)) => h as f32 / w as f32,
+            None => DEFAULT_CELL_ASPECT,
+        }
+    }
+
+    /// Fix the cell aspect instead of measuring it (0.0 = measure; TS writes this; exposed for tests)
+    #[inline]
+    pub fn set_cell_aspect(&self, aspect: f32) {
+        self.write_header_u8(H_CELL_ASPECT, (aspect * CELL_ASPECT_SCALE).round().clamp(0.0, 255.0) as u8);
+    }
+
+    /// Fewest rows an inline frame takes (0 = no minimum)
+    #[inline]
+    pub fn inline_min_height(&self) -> u16 {
+        self.read_header_u8(H_INLINE_MIN_HEIGHT) as u16
+    }
+
+    /// Most rows an inline frame takes (0 = as tall as its content)
+    #[inline]
+    pub fn inline_max_height(&self) -> u16 {
+        self.read_header_u16(H_INLINE_MAX_HEIGHT)
+    }
+
+    /// Bound the inline frame's height (TS writes this at mount; exposed for tests)
+    #[inline]
+    pub fn set_inline_height(&self, min: u8, max: u16) {
+        self.write_header_u8(H_INLINE_MIN_HEIGHT, min);
+        self.write_header_u16(H_INLINE_MAX_HEIGHT, max);
+    }
+
+    /// Whether TS wants a LayoutChange event after layouts that moved nodes
+    #[inline]
+    pub fn layout_events(&self) -> bool {
+        self.read_header_u8(H_LAYOUT_EVENTS) != 0
+    }
+
+    /// Key set for framework navigation and input editing
+    #[inline]
+    pub fn keymap_preset(&self) -> KeymapPreset {
+        KeymapPreset::from(self.read_header_u8(H_KEYMAP_PRESET))
+    }
+
+    /// Select the key preset (TS writes this at mount; exposed for tests)
+    #[inline]
+    pub fn set_keymap_preset(&self, preset: KeymapPreset) {
+        self.write_header_u8(H_KEYMAP_PRESET, preset as u8);
+    }
+
+    /// `NavAction` bits taken over by the TS keymap — Rust leaves those keys alone
+    #[inline]
+    pub fn keymap_overrides(&self) -> u16 {
+        self.read_header_u16(H_KEYMAP_OVERRIDES)
+    }
+
+    /// Screenshot key in key-event keycode encoding (0 = no binding)
+    #[inline]
+    pub fn screenshot_key(&self) -> u32 {
+        self.read_header_u32(H_SCREENSHOT_KEY)
+    }
+
+    /// Modifier bits that must accompany the screenshot key
+    #[inline]
+    pub fn screenshot_modifiers(&self) -> u8 {
+        self.read_header_u8(H_SCREENSHOT_MODIFIERS)
+    }
+
+    /// SCREENSHOT_* option bits
+    #[inline]
+    pub fn screenshot_flags(&self) -> u8 {
+        self.read_header_u8(H_SCREENSHOT_FLAGS)
+    }
+
+    /// Whether the screenshot confirmation toast is showing
+    #[inline]
+    pub fn screenshot_toast(&self) -> bool {
+        self.read_header_u8(H_SCREENSHOT_TOAST) != 0
+    }
+
+    /// Show or hide the screenshot confirmation toast
+    #[inline]
+    pub fn set_screenshot_toast(&self, visible: bool) {
+        self.write_header_u8(H_SCREENSHOT_TOAST, visible as u8);
+    }
+
+    /// Whether the performance HUD is showing
+    #[inline]
+    pub fn perf_hud(&self) -> bool {
+        self.read_header_u8(H_PERF_HUD) != 0
+    }
+
+    /// Show or hide the performance HUD
+    #[inline]
+    pub fn set_perf_hud(&self, visible: bool) {
+        self.write_header_u8(H_PERF_HUD, visible as u8);
+    }
+
+    /// Performance HUD toggle key in key-event keycode encoding (0 = no binding)
+    #[inline]
+    pub fn perf_hud_key(&self) -> u32 {
+        self.read_header_u32(H_PERF_HUD_KEY)
+    }
+
+    /// Modifier bits that must accompany the HUD toggle key
+    #[inline]
+    pub fn perf_hud_modifiers(&self) -> u8 {
+        self.read_header_u8(H_PERF_HUD_MODIFIERS)
+    }
+
+    /// Whether the component inspector is open
+    #[inline]
+    pub fn inspector(&self) -> bool {
+        self.read_header_u8(H_INSPECTOR) != 0
+    }
+
+    /// Open or close the component inspector
+    #[inline]
+    pub fn set_inspector(&self, open: bool) {
+        self.write_header_u8(H_INSPECTOR, open as u8);
+    }
+
+    /// Inspector toggle key in key-event keycode encoding (0 = no binding)
+    #[inline]
+    pub fn inspector_key(&self) -> u32 {
+        self.read_header_u32(H_INSPECTOR_KEY)
+    }
+
+    /// Modifier bits that must accompany the inspector toggle key
+    #[inline]
+    pub fn inspector_modifiers(&self) -> u8 {
+        self.read_header_u8(H_INSPECTOR_MODIFIERS)
+    }
+
+    /// Frames start at most once per this window (zero = every change renders
+    /// at once). See `pipeline::pacing`.
+    #[inline]
+    pub fn batch_window(&self) -> Duration {
+        Duration::from_micros(self.read_header_u32(H_BATCH_WINDOW_US) as u64)
+    }
+
+    /// Coalesce changes into one frame per `window` (zero turns pacing off)
+    #[inline]
+    pub fn set_batch_window(&self, window: Duration) {
+        self.write_header_u32(H_BATCH_WINDOW_US, window.as_micros().min(u32::MAX as u128) as u32);
+    }
+
+    // =========================================================================
+    // STATE (Rust writes, TS reads)
+    // =========================================================================
+
+    /// Get focused component index (-1 = none)
+    #[inline]
+    pub fn focused_index(&self) -> i32 {
+        self.read_header_i32(H_FOCUSED_INDEX)
+    }
+
+    /// Set focused component index
+    #[inline]
+    pub fn set_focused_index(&self, idx: i32) {
+        self.write_header_i32(H_FOCUSED_INDEX, idx)
+    }
+
+    /// Get hovered component index (-1 = none)
+    #[inline]
+    pub fn hovered_index(&self) -> i32 {
+        self.read_header_i32(H_HOVERED_INDEX)
+    }
+
+    /// Set hovered component index
+    #[inline]
+    pub fn set_hovered_index(&self, idx: i32) {
+        self.write_header_i32(H_HOVERED_INDEX, idx)
+    }
+
+    /// Get pressed component index (-1 = none)
     #[inline]
     pub fn pressed_index(&self) -> i32 {
         self.read_header_i32(H_PRESSED_INDEX)
@@ -1432,6 +2468,25 @@ impl SharedBuffer {
         self.write_header_u32(H_LAYOUT_COUNT, count.wrapping_add(1));
     }
 
+    /// Record what the last frame wrote to the terminal
+    #[inline]
+    pub fn set_frame_output(&self, cells: u32, bytes: u32) {
+        self.write_header_u32(H_FRAME_CELLS, cells);
+        self.write_header_u32(H_FRAME_BYTES, bytes);
+    }
+
+    /// Cells the last frame wrote to the terminal
+    #[inline]
+    pub fn frame_cells(&self) -> u32 {
+        self.read_header_u32(H_FRAME_CELLS)
+    }
+
+    /// Bytes the last frame wrote to the terminal
+    #[inline]
+    pub fn frame_bytes(&self) -> u32 {
+        self.read_header_u32(H_FRAME_BYTES)
+    }
+
     // =========================================================================
     // TIMING STATS (for benchmarking)
     // =========================================================================
@@ -1519,6 +2574,32 @@ impl SharedBuffer {
         self.read_header_u32(H_WAKE_LATENCY_US)
     }
 
+    /// Set input-to-paint latency percentiles and the latest sample (microseconds)
+    #[inline]
+    pub fn set_input_latency(&self, p50: u32, p95: u32, p99: u32, last: u32) {
+        self.write_header_u32(H_INPUT_LATENCY_P50_US, p50);
+        self.write_header_u32(H_INPUT_LATENCY_P95_US, p95);
+        self.write_header_u32(H_INPUT_LATENCY_P99_US, p99);
+        self.write_header_u32(H_INPUT_LATENCY_LAST_US, last);
+    }
+
+    /// Increment the input-to-paint sample count
+    #[inline]
+    pub fn increment_input_latency_count(&self) {
+        let count = self.read_header_u32(H_INPUT_LATENCY_COUNT);
+        self.write_header_u32(H_INPUT_LATENCY_COUNT, count.wrapping_add(1));
+    }
+
+    /// Get input-to-paint latency percentiles (p50, p95, p99) in microseconds
+    #[inline]
+    pub fn input_latency_us(&self) -> (u32, u32, u32) {
+        (
+            self.read_header_u32(H_INPUT_LATENCY_P50_US),
+            self.read_header_u32(H_INPUT_LATENCY_P95_US),
+            self.read_header_u32(H_INPUT_LATENCY_P99_US),
+        )
+    }
+
     /// Increment event write count (called when Rust writes event to ring)
     #[inline]
     pub fn increment_event_write_count(&self) {
@@ -1550,6 +2631,97 @@ impl SharedBuffer {
         self.write_header_u8(H_EXIT_REQUESTED, if value { 1 } else { 0 });
     }
 
+    // =========================================================================
+    // HOVER PAYLOAD (Rust writes, TS reads)
+    // =========================================================================
+
+    /// Sequence number of the hover payload table
+    #[inline]
+    pub fn hover_seq(&self) -> u32 {
+        self.read_header_u32(H_HOVER_SEQ)
+    }
+
+    /// Tagged node currently under the mouse (-1 = none)
+    #[inline]
+    pub fn hover_payload_index(&self) -> i32 {
+        self.read_header_i32(H_HOVER_PAYLOAD_INDEX)
+    }
+
+    /// Tag of the node in the hover payload table
+    #[inline]
+    pub fn hover_payload_tag(&self) -> u32 {
+        self.read_header_u32(H_HOVER_TAG)
+    }
+
+    /// Screen region of the hover payload (x, y, width, height)
+    pub fn hover_region(&self) -> (u16, u16, u16, u16) {
+        (
+            self.read_header_u16(H_HOVER_REGION_X),
+            self.read_header_u16(H_HOVER_REGION_Y),
+            self.read_header_u16(H_HOVER_REGION_W),
+            self.read_header_u16(H_HOVER_REGION_H),
+        )
+    }
+
+    /// Publish a hover payload (or clear it with index -1), bump the sequence
+    /// and push a Hover event so TS hears about it
+    pub fn set_hover_payload(&self, index: i32, tag: u32, region: (u16, u16, u16, u16)) {
+        self.write_header_i32(H_HOVER_PAYLOAD_INDEX, index);
+        self.write_header_u32(H_HOVER_TAG, tag);
+        self.write_header_u16(H_HOVER_REGION_X, region.0);
+        self.write_header_u16(H_HOVER_REGION_Y, region.1);
+        self.write_header_u16(H_HOVER_REGION_W, region.2);
+        self.write_header_u16(H_HOVER_REGION_H, region.3);
+        let seq = self.read_header_u32(H_HOVER_SEQ);
+        self.write_header_u32(H_HOVER_SEQ, seq.wrapping_add(1));
+        self.push_hover_event(index, tag, region);
+    }
+
+    // =========================================================================
+    // WATCHDOG (Rust writes, TS reads)
+    // =========================================================================
+
+    /// Number of update storms the watchdog has broken
+    #[inline]
+    pub fn watchdog_trip_count(&self) -> u32 {
+        self.read_header_u32(H_WATCHDOG_TRIP_COUNT)
+    }
+
+    /// Whether the watchdog is currently suppressing an update storm
+    #[inline]
+    pub fn watchdog_active(&self) -> bool {
+        self.read_header_u8(H_WATCHDOG_ACTIVE) != 0
+    }
+
+    /// Offending node indices recorded at the last trip
+    pub fn watchdog_nodes(&self) -> Vec<usize> {
+        (0..WATCHDOG_NODE_SLOTS)
+            .map(|slot| self.read_header_u16(H_WATCHDOG_NODES + slot * 2))
+            .filter(|&idx| idx != WATCHDOG_NODE_NONE)
+            .map(|idx| idx as usize)
+            .collect()
+    }
+
+    /// Record a watchdog trip: bump the counter, store offenders, mark active
+    pub fn record_watchdog_trip(&self, nodes: &[usize]) {
+        let count = self.read_header_u32(H_WATCHDOG_TRIP_COUNT);
+        self.write_header_u32(H_WATCHDOG_TRIP_COUNT, count.wrapping_add(1));
+        for slot in 0..WATCHDOG_NODE_SLOTS {
+            let value = nodes
+                .get(slot)
+                .map(|&idx| idx.min(WATCHDOG_NODE_NONE as usize - 1) as u16)
+                .unwrap_or(WATCHDOG_NODE_NONE);
+            self.write_header_u16(H_WATCHDOG_NODES + slot * 2, value);
+        }
+        self.write_header_u8(H_WATCHDOG_ACTIVE, 1);
+    }
+
+    /// Clear the active flag once the storm has settled
+    #[inline]
+    pub fn clear_watchdog_active(&self) {
+        self.write_header_u8(H_WATCHDOG_ACTIVE, 0);
+    }
+
     // =========================================================================
     // WAKE MECHANISM
     // =========================================================================
@@ -1583,7 +2755,9 @@ impl SharedBuffer {
             (*wake_ptr).store(1, Ordering::SeqCst);
         }
         // Signal the condvar that TS is blocking on (the actual wake mechanism)
-        crate::notify_ts_events();
+        if let Some(events) = self.events.get() {
+            events.notify();
+        }
     }
 
     // =========================================================================
@@ -1597,6 +2771,12 @@ impl SharedBuffer {
         unsafe { self.ptr.add(HEADER_SIZE + index * NODE_STRIDE) }
     }
 
+    /// A node's whole record, for comparing snapshots of it
+    #[inline]
+    pub fn node_record(&self, index: usize) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.node_ptr(index), NODE_STRIDE) }
+    }
+
     /// Get mutable pointer to node data (with bounds check)
     #[inline]
     fn node_ptr_mut(&self, index: usize) -> *mut u8 {
@@ -1654,6 +2834,87 @@ impl SharedBuffer {
         unsafe { ptr::write_unaligned(self.node_ptr_mut(index).add(field) as *mut i32, value) }
     }
 
+    // =========================================================================
+    // HOST WRITES
+    // =========================================================================
+    //
+    // What the TS side normally writes, for hosts driving the buffer without
+    // it (see `host`). Fields are N_* offsets; writes that would run past
+    // the node record are refused.
+
+    /// Set the number of node slots in use (highest index + 1)
+    #[inline]
+    pub fn set_node_count(&self, count: usize) {
+        self.write_header_u32(H_NODE_COUNT, count as u32)
+    }
+
+    /// Add DIRTY_* flags to a node
+    #[inline]
+    pub fn mark_dirty(&self, i: usize, flags: u8) {
+        self.write_node_u8(i, N_DIRTY_FLAGS, self.dirty_flags(i) | flags)
+    }
+
+    /// Write an f32 field. False if `field` isn't inside the record.
+    pub fn write_field_f32(&self, i: usize, field: usize, value: f32) -> bool {
+        let fits = i < self.max_nodes && field + 4 <= NODE_STRIDE;
+        if fits {
+            self.write_node_f32(i, field, value);
+        }
+        fits
+    }
+
+    /// Write a u8 field. False if `field` isn't inside the record.
+    pub fn write_field_u8(&self, i: usize, field: usize, value: u8) -> bool {
+        let fits = i < self.max_nodes && field < NODE_STRIDE;
+        if fits {
+            self.write_node_u8(i, field, value);
+        }
+        fits
+    }
+
+    /// Write a u32 field (colors, indices). False if `field` isn't inside
+    /// the record.
+    pub fn write_field_u32(&self, i: usize, field: usize, value: u32) -> bool {
+        let fits = i < self.max_nodes && field + 4 <= NODE_STRIDE;
+        if fits {
+            self.write_node_u32(i, field, value);
+        }
+        fits
+    }
+
+    /// Reset node `i` to its defaults: every field zero except those whose
+    /// default isn't (auto sizes, no parent, visible, …). Mirrors TS
+    /// `initializeNode`, which a test replays against this; the generation
+    /// survives, as it does there.
+    pub fn init_node(&self, i: usize) {
+        let generation = self.node_generation(i);
+        unsafe { ptr::write_bytes(self.node_ptr_mut(i), 0, NODE_STRIDE) };
+        self.write_node_u32(i, N_GENERATION, generation);
+
+        for field in [N_WIDTH, N_HEIGHT, N_MIN_WIDTH, N_MIN_HEIGHT, N_MAX_WIDTH, N_MAX_HEIGHT, N_ASPECT_RATIO, N_FLEX_BASIS] {
+            self.write_node_f32(i, field, f32::NAN);
+        }
+        for field in [N_INSET_TOP, N_INSET_RIGHT, N_INSET_BOTTOM, N_INSET_LEFT] {
+            self.write_node_f32(i, field, f32::NAN);
+        }
+        for field in [
+            N_PARENT_INDEX, N_FIRST_CHILD, N_PREV_SIBLING, N_NEXT_SIBLING, N_RELATIVE_TO, N_SELECTION_START,
+            N_SELECTION_END,
+        ] {
+            self.write_node_i32(i, field, -1);
+        }
+        self.write_node_u8(i, N_DISPLAY, Display::Flex as u8);
+        self.write_node_u8(i, N_VISIBLE, 1);
+        self.write_node_u8(i, N_ALIGN_ITEMS, AlignItems::Stretch as u8);
+        self.write_node_f32(i, N_FLEX_SHRINK, 1.0);
+        self.write_node_u8(i, N_GRID_AUTO_COLUMNS_TYPE, TrackType::Auto as u8);
+        self.write_node_u8(i, N_GRID_AUTO_ROWS_TYPE, TrackType::Auto as u8);
+        self.write_node_f32(i, N_OPACITY, 1.0);
+        self.write_node_u8(i, N_FOCUS_INDICATOR_CHAR, b'*');
+        self.write_node_u8(i, N_FOCUS_INDICATOR_ENABLED, 1);
+        self.write_node_u8(i, N_TEXT_ALIGN, TextAlign::Start as u8);
+    }
+
     // =========================================================================
     // LAYOUT PROPERTIES (Cache Lines 1-4)
     // =========================================================================
@@ -1666,6 +2927,17 @@ impl SharedBuffer {
     #[inline] pub fn max_width(&self, i: usize) -> f32 { self.read_node_f32(i, N_MAX_WIDTH) }
     #[inline] pub fn max_height(&self, i: usize) -> f32 { self.read_node_f32(i, N_MAX_HEIGHT) }
     #[inline] pub fn aspect_ratio(&self, i: usize) -> f32 { self.read_node_f32(i, N_ASPECT_RATIO) }
+    /// What the % dimension at `field` (N_WIDTH_BASIS…N_MAX_HEIGHT_BASIS) is of
+    #[inline] pub fn percent_basis(&self, i: usize, field: usize) -> PercentBasis {
+        PercentBasis::from(self.read_node_u8(i, field))
+    }
+    /// Whether the node's aspect ratio is of its shape on screen (corrected for the cell's shape)
+    #[inline] pub fn aspect_cells(&self, i: usize) -> bool { self.read_node_u8(i, N_ASPECT_CELLS) != 0 }
+    /// Node that `PercentBasis::Named` dimensions size against
+    #[inline] pub fn relative_to(&self, i: usize) -> Option<usize> {
+        let idx = self.read_node_i32(i, N_RELATIVE_TO);
+        if idx < 0 { None } else { Some(idx as usize) }
+    }
 
     // Flex properties
     #[inline] pub fn flex_basis(&self, i: usize) -> f32 { self.read_node_f32(i, N_FLEX_BASIS) }
@@ -1693,11 +2965,19 @@ impl SharedBuffer {
     #[inline] pub fn flex_direction(&self, i: usize) -> u8 { self.read_node_u8(i, N_FLEX_DIRECTION) }
     #[inline] pub fn flex_wrap(&self, i: usize) -> u8 { self.read_node_u8(i, N_FLEX_WRAP) }
     #[inline] pub fn justify_content(&self, i: usize) -> u8 { self.read_node_u8(i, N_JUSTIFY_CONTENT) }
-    #[inline] pub fn align_items(&self, i: usize) -> u8 { self.read_node_u8(i, N_ALIGN_ITEMS) }
-    #[inline] pub fn align_content(&self, i: usize) -> u8 { self.read_node_u8(i, N_ALIGN_CONTENT) }
-    #[inline] pub fn align_self(&self, i: usize) -> u8 { self.read_node_u8(i, N_ALIGN_SELF) }
+    #[inline] pub fn align_items(&self, i: usize) -> AlignItems { AlignItems::from(self.read_node_u8(i, N_ALIGN_ITEMS)) }
+    #[inline] pub fn align_content(&self, i: usize) -> AlignContent { AlignContent::from(self.read_node_u8(i, N_ALIGN_CONTENT)) }
+    #[inline] pub fn align_self(&self, i: usize) -> AlignSelf { AlignSelf::from(self.read_node_u8(i, N_ALIGN_SELF)) }
     #[inline] pub fn position(&self, i: usize) -> u8 { self.read_node_u8(i, N_POSITION) }
     #[inline] pub fn overflow(&self, i: usize) -> u8 { self.read_node_u8(i, N_OVERFLOW) }
+    /// Overflow along x: N_OVERFLOW_X when set, else N_OVERFLOW.
+    #[inline] pub fn overflow_x(&self, i: usize) -> u8 {
+        match self.read_node_u8(i, N_OVERFLOW_X) { 0 => self.overflow(i), axis => axis - 1 }
+    }
+    /// Overflow along y: N_OVERFLOW_Y when set, else N_OVERFLOW.
+    #[inline] pub fn overflow_y(&self, i: usize) -> u8 {
+        match self.read_node_u8(i, N_OVERFLOW_Y) { 0 => self.overflow(i), axis => axis - 1 }
+    }
     #[inline] pub fn display(&self, i: usize) -> u8 { self.read_node_u8(i, N_DISPLAY) }
     #[inline] pub fn box_sizing(&self, i: usize) -> u8 { self.read_node_u8(i, N_BOX_SIZING) }
 
@@ -1707,6 +2987,24 @@ impl SharedBuffer {
     #[inline] pub fn border_width_bottom(&self, i: usize) -> u8 { self.read_node_u8(i, N_BORDER_WIDTH_BOTTOM) }
     #[inline] pub fn border_width_left(&self, i: usize) -> u8 { self.read_node_u8(i, N_BORDER_WIDTH_LEFT) }
 
+    /// Inner line of a framed border; `None` for an ordinary one-cell border
+    #[inline] pub fn border_inner_style(&self, i: usize) -> BorderStyle { BorderStyle::from(self.read_node_u8(i, N_BORDER_INNER_STYLE)) }
+    #[inline] pub fn render_cache(&self, i: usize) -> bool { self.read_node_u8(i, N_RENDER_CACHE) != 0 }
+    #[inline] pub fn border_inner_color(&self, i: usize) -> u32 { self.read_node_u32(i, N_BORDER_INNER_COLOR) }
+    #[inline] pub fn border_frame_fill(&self, i: usize) -> u32 { self.read_node_u32(i, N_BORDER_FRAME_FILL) }
+
+    /// Cells each bordered side takes: 2 for a framed border, 1 otherwise
+    #[inline]
+    pub fn border_thickness(&self, i: usize) -> u8 {
+        if self.border_inner_style(i) == BorderStyle::None { 1 } else { 2 }
+    }
+
+    // Cells taken by the border on each side, for layout and content insets
+    #[inline] pub fn border_inset_top(&self, i: usize) -> u8 { if self.border_top(i) > 0 { self.border_thickness(i) } else { 0 } }
+    #[inline] pub fn border_inset_right(&self, i: usize) -> u8 { if self.border_right(i) > 0 { self.border_thickness(i) } else { 0 } }
+    #[inline] pub fn border_inset_bottom(&self, i: usize) -> u8 { if self.border_bottom(i) > 0 { self.border_thickness(i) } else { 0 } }
+    #[inline] pub fn border_inset_left(&self, i: usize) -> u8 { if self.border_left(i) > 0 { self.border_thickness(i) } else { 0 } }
+
     // Border width aliases (for layout compatibility)
     #[inline] pub fn border_top(&self, i: usize) -> u8 { self.border_width_top(i) }
     #[inline] pub fn border_right(&self, i: usize) -> u8 { self.border_width_right(i) }
@@ -1732,8 +3030,113 @@ impl SharedBuffer {
     #[inline] pub fn next_sibling(&self, i: usize) -> i32 { self.read_node_i32(i, N_NEXT_SIBLING) }
 
     #[inline] pub fn set_first_child(&self, i: usize, v: i32) { self.write_node_i32(i, N_FIRST_CHILD, v) }
+
+    /// Sort key among siblings. Nodes of a keyed list sort at their list's
+    /// anchor index, by position; everything else at its own index.
+    #[inline]
+    pub fn sibling_order(&self, i: usize) -> (usize, i32) {
+        match self.read_node_i32(i, N_SLOT_ANCHOR) {
+            anchor if anchor > 0 => ((anchor - 1) as usize, self.read_node_i32(i, N_SLOT_POSITION)),
+            _ => (i, 0),
+        }
+    }
+
+    /// When TS allocated the node — increasing, unlike recycled indices
+    #[inline] pub fn creation_seq(&self, i: usize) -> u32 { self.read_node_u32(i, N_CREATION_SEQ) }
+
+    /// Times the index has been released (see `ComponentRef`)
+    #[inline] pub fn node_generation(&self, i: usize) -> u32 { self.read_node_u32(i, N_GENERATION) }
+
+    /// Region a root's tree draws into in inline and append mode (0 = the first)
+    #[inline] pub fn region(&self, i: usize) -> u8 { self.read_node_u8(i, N_REGION) }
+
+    /// A ref to whatever component holds index `i` now
+    #[inline]
+    pub fn component_ref(&self, i: usize) -> ComponentRef {
+        ComponentRef { index: i, generation: self.node_generation(i) }
+    }
+
+    /// Whether `r`'s component still exists (its index wasn't released since)
+    pub fn is_live(&self, r: ComponentRef) -> bool {
+        r.index < self.node_count()
+            && self.component_type(r.index) != COMPONENT_NONE
+            && self.node_generation(r.index) == r.generation
+    }
+
+    /// `r`'s index while its component exists
+    #[inline]
+    pub fn resolve(&self, r: ComponentRef) -> Option<usize> {
+        self.is_live(r).then_some(r.index)
+    }
+
+    /// `r`'s index, for callers that own the component and so know it
+    /// exists. Debug builds assert it; release builds trust the caller.
+    #[inline]
+    pub fn live_index(&self, r: ComponentRef) -> usize {
+        debug_assert!(self.is_live(r), "stale component ref: index {} generation {}", r.index, r.generation);
+        r.index
+    }
+
+    /// Paint order among siblings: z-index, then tree order, then creation.
+    ///
+    /// Tree order is declaration order — a keyed-list node sorts at its
+    /// anchor, by position — read from creation sequences rather than
+    /// indices, so a node recreated into a recycled index still paints
+    /// over the siblings that were there before it.
+    #[inline]
+    pub fn paint_order(&self, i: usize) -> (i32, u32, i32, u32) {
+        let seq = self.creation_seq(i);
+        let (declared, position) = match self.read_node_i32(i, N_SLOT_ANCHOR) {
+            anchor if anchor > 0 => (self.creation_seq((anchor - 1) as usize), self.read_node_i32(i, N_SLOT_POSITION)),
+            _ => (seq, 0),
+        };
+        (self.z_index(i), declared, position, seq)
+    }
+
+    /// Whether the node sorts at a keyed-list slot instead of its own index
+    #[inline] pub fn has_slot(&self, i: usize) -> bool { self.read_node_i32(i, N_SLOT_ANCHOR) > 0 }
+
+    /// Place a node at `anchor`'s slot, `position`-th (TS writes this; exposed for tests)
+    #[inline]
+    pub fn set_slot(&self, i: usize, anchor: Option<usize>, position: i32) {
+        self.write_node_i32(i, N_SLOT_ANCHOR, anchor.map_or(0, |a| a as i32 + 1));
+        self.write_node_i32(i, N_SLOT_POSITION, position);
+    }
     #[inline] pub fn set_prev_sibling(&self, i: usize, v: i32) { self.write_node_i32(i, N_PREV_SIBLING, v) }
     #[inline] pub fn set_next_sibling(&self, i: usize, v: i32) { self.write_node_i32(i, N_NEXT_SIBLING, v) }
+    #[inline] pub fn set_parent_index(&self, i: usize, v: i32) { self.write_node_i32(i, N_PARENT_INDEX, v) }
+
+    /// Link `child` under `parent` (prepended to its sibling list). O(1).
+    /// Mirrors TS `linkChild`.
+    pub fn link_child(&self, child: usize, parent: usize) {
+        self.set_parent_index(child, parent as i32);
+        let old_first = self.first_child(parent);
+        self.set_next_sibling(child, old_first);
+        self.set_prev_sibling(child, -1);
+        if old_first >= 0 {
+            self.set_prev_sibling(old_first as usize, child as i32);
+        }
+        self.set_first_child(parent, child as i32);
+    }
+
+    /// Unlink `child` from its parent's sibling list and clear its links.
+    /// O(1). Mirrors TS `unlinkChild`.
+    pub fn unlink_child(&self, child: usize) {
+        let Some(parent) = self.parent_index(child) else { return };
+        let prev = self.prev_sibling(child);
+        let next = self.next_sibling(child);
+        if prev >= 0 {
+            self.set_next_sibling(prev as usize, next);
+        } else {
+            self.set_first_child(parent, next);
+        }
+        if next >= 0 {
+            self.set_prev_sibling(next as usize, prev);
+        }
+        self.set_prev_sibling(child, -1);
+        self.set_next_sibling(child, -1);
+        self.set_parent_index(child, -1);
+    }
 
     /// Iterate over children of a node. O(children) instead of O(N).
     pub fn iter_children(&self, parent: usize) -> ChildIter<'_> {
@@ -1883,7 +3286,11 @@ impl SharedBuffer {
 
     /// Get all border characters for a node, handling both predefined and custom styles.
     pub fn border_chars(&self, i: usize) -> (char, char, char, char, char, char) {
-        let style = self.border_style(i);
+        self.border_chars_for(i, self.border_style(i))
+    }
+
+    /// Characters for `style` on node `i` — `Custom` reads the node's own.
+    pub fn border_chars_for(&self, i: usize, style: BorderStyle) -> (char, char, char, char, char, char) {
         if style == BorderStyle::Custom {
             let h  = char::from_u32(self.border_char_h(i) as u32).unwrap_or('─');
             let v  = char::from_u32(self.border_char_v(i) as u32).unwrap_or('│');
@@ -1909,6 +3316,16 @@ impl SharedBuffer {
         self.read_node_u8(i, N_FOCUS_INDICATOR_ENABLED) != 0
     }
 
+    // Background fill
+    #[inline] pub fn bg_fill(&self, i: usize) -> BackgroundFill { BackgroundFill::from(self.read_node_u8(i, N_BG_FILL)) }
+    #[inline] pub fn bg_fill_angle(&self, i: usize) -> u16 { self.read_node_u16(i, N_BG_FILL_ANGLE) }
+
+    /// Pattern cell size in cells (minimum 1)
+    #[inline]
+    pub fn bg_fill_size(&self, i: usize) -> u16 {
+        self.read_node_u8(i, N_BG_FILL_SIZE).max(1) as u16
+    }
+
     // =========================================================================
     // COLORS (Cache Line 13)
     // =========================================================================
@@ -1920,6 +3337,7 @@ impl SharedBuffer {
     #[inline] pub fn cursor_fg_color(&self, i: usize) -> u32 { self.read_node_u32(i, N_CURSOR_FG_COLOR) }
     #[inline] pub fn cursor_bg_color(&self, i: usize) -> u32 { self.read_node_u32(i, N_CURSOR_BG_COLOR) }
     #[inline] pub fn selection_color(&self, i: usize) -> u32 { self.read_node_u32(i, N_SELECTION_COLOR) }
+    #[inline] pub fn bg_color_2(&self, i: usize) -> u32 { self.read_node_u32(i, N_BG_COLOR_2) }
 
     /// Get border top color (falls back to border_color if 0)
     #[inline]
@@ -1970,9 +3388,13 @@ impl SharedBuffer {
 
     /// Read text content from text pool
     pub fn text(&self, i: usize) -> &str {
-        let offset = self.text_offset(i) as usize;
-        let length = self.text_length(i) as usize;
+        self.pool_str(self.text_offset(i) as usize, self.text_length(i) as usize)
+    }
 
+    /// Read a string slice out of the text pool. TS moves and rewrites
+    /// pool bytes whenever it isn't locked out: hold `lock_text_pool` for as
+    /// long as the string is in use (the engine does, per message and frame).
+    fn pool_str(&self, offset: usize, length: usize) -> &str {
         if length == 0 {
             return "";
         }
@@ -2001,25 +3423,114 @@ impl SharedBuffer {
         self.write_header_u32(H_TEXT_POOL_WRITE_PTR, ptr)
     }
 
-    /// Write text content to text pool (bump allocation).
-    /// Allocates new space in the text pool and updates the node's offset/length.
+    /// Take the text pool lock, waiting while TS holds it. Reentrant on
+    /// the holding thread.
+    ///
+    /// Both sides bump-allocate and compact the pool; a compaction moves
+    /// every string and an in-place rewrite changes its bytes. Writers hold
+    /// it for one write or compaction. Readers hold it while they use what
+    /// they read: the engine thread for each message it handles and each
+    /// frame, so TS waits out at most one of those.
+    pub fn lock_text_pool(&self) -> TextPoolLock<'_> {
+        let lock = unsafe { &*(self.ptr.add(H_TEXT_POOL_LOCK) as *const AtomicU8) };
+        let address = lock as *const AtomicU8 as usize;
+        if HELD_TEXT_POOLS.with(|held| held.borrow().contains(&address)) {
+            return TextPoolLock(None);
+        }
+
+        let mut spins = 0;
+        while lock.compare_exchange_weak(0, 1, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            if spins < TEXT_POOL_SPINS {
+                spins += 1;
+                std::hint::spin_loop();
+            } else {
+                std::thread::yield_now();
+            }
+        }
+        HELD_TEXT_POOLS.with(|held| held.borrow_mut().push(address));
+        TextPoolLock(Some(lock))
+    }
+
+    /// Write text content to the text pool.
+    /// Rewrites in place when the text fits the node's existing slot;
+    /// otherwise bump-allocates a new slot with some headroom so small
+    /// growth (9 → 10) stays in place next time.
     /// Returns true if successful, false if pool is full.
     pub fn set_text(&self, i: usize, text: &str) -> bool {
-        let bytes = text.as_bytes();
-        let len = bytes.len();
+        let _pool = self.lock_text_pool();
+        let len = text.len();
+        let capacity = self.text_capacity(i) as usize;
+
+        if len > 0 && len <= capacity {
+            let offset = self.read_node_u32(i, N_TEXT_OFFSET) as usize;
+            unsafe {
+                let ptr = self.ptr.add(self.text_pool_offset + offset);
+                ptr::copy_nonoverlapping(text.as_bytes().as_ptr(), ptr, len);
+            }
+            self.write_node_u32(i, N_TEXT_LENGTH, len as u32);
+            return true;
+        }
+
+        match self.write_pool_str(i, N_TEXT_OFFSET, N_TEXT_LENGTH, text, len.next_multiple_of(TEXT_SLOT_ALIGN)) {
+            Some(reserved) => {
+                if len > 0 {
+                    self.write_node_u32(i, N_TEXT_CAPACITY, reserved as u32);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Bytes reserved for the node's text slot (at least its current length).
+    #[inline]
+    pub fn text_capacity(&self, i: usize) -> u32 {
+        self.read_node_u32(i, N_TEXT_CAPACITY).max(self.text_length(i))
+    }
+
+    /// Bump-allocate `reserve` bytes (≥ `text.len()`) in the pool, write
+    /// `text` at the start and point the node's offset/length fields at it.
+    /// Compacts first when the write would cross the high-water mark or
+    /// overflow the pool. Headroom is dropped when the pool is nearly full.
+    /// Returns the bytes actually reserved, or None if the pool is full.
+    /// The caller holds the text pool lock.
+    fn write_pool_str(&self, i: usize, offset_field: usize, length_field: usize, text: &str, reserve: usize) -> Option<usize> {
+        let len = text.len();
 
         if len == 0 {
             // Empty text - just set length to 0
-            self.write_node_u32(i, N_TEXT_LENGTH, 0);
-            return true;
+            self.write_node_u32(i, length_field, 0);
+            return Some(0);
+        }
+
+        if self.text_pool_needs_compaction(len) {
+            // `text` may itself live in the pool, which is about to move
+            let owned = text.to_owned();
+            self.compact_text_pool_locked();
+            return self.bump_pool_str(i, offset_field, length_field, &owned, reserve);
         }
 
+        self.bump_pool_str(i, offset_field, length_field, text, reserve)
+    }
+
+    /// Whether writing `len` more bytes should compact the pool first.
+    fn text_pool_needs_compaction(&self, len: usize) -> bool {
         let write_ptr = self.text_pool_write_ptr() as usize;
-        let text_end = write_ptr + len;
+        let high_water = self.text_pool_size / 100 * TEXT_POOL_HIGH_WATER_PERCENT;
+        write_ptr + len > self.text_pool_size || (write_ptr <= high_water && write_ptr + len > high_water)
+    }
 
-        // Check if we have space in the text pool
+    fn bump_pool_str(&self, i: usize, offset_field: usize, length_field: usize, text: &str, reserve: usize) -> Option<usize> {
+        let bytes = text.as_bytes();
+        let len = bytes.len();
+        let write_ptr = self.text_pool_write_ptr() as usize;
+        let reserve = reserve.max(len);
+
+        // Check if we have space in the text pool (headroom is optional)
+        let reserve = if write_ptr + reserve <= self.text_pool_size { reserve } else { len };
+        let text_end = write_ptr + reserve;
         if text_end > self.text_pool_size {
-            return false; // Pool is full
+            return None; // Pool is full
         }
 
         // Write bytes to text pool
@@ -2028,14 +3539,172 @@ impl SharedBuffer {
             ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, len);
         }
 
-        // Update node's text offset and length
-        self.write_node_u32(i, N_TEXT_OFFSET, write_ptr as u32);
-        self.write_node_u32(i, N_TEXT_LENGTH, len as u32);
+        // Update node's offset and length
+        self.write_node_u32(i, offset_field, write_ptr as u32);
+        self.write_node_u32(i, length_field, len as u32);
 
         // Advance write pointer
         self.set_text_pool_write_ptr(text_end as u32);
 
-        true
+        Some(reserve)
+    }
+
+    /// Move every live string to the front of the text pool, in pool order,
+    /// and reset the write pointer to the end of them. Strings of released
+    /// nodes are dropped and text slots lose their headroom. Bumps
+    /// `H_TEXT_POOL_COMPACTIONS`. Returns the bytes reclaimed.
+    ///
+    /// Offsets change: callers must not hold a string read from the pool
+    /// across this, even while holding the lock.
+    pub fn compact_text_pool(&self) -> usize {
+        let _pool = self.lock_text_pool();
+        self.compact_text_pool_locked()
+    }
+
+    fn compact_text_pool_locked(&self) -> usize {
+        let old_write_ptr = self.text_pool_write_ptr() as usize;
+
+        // (node, offset field, capacity field, offset, length)
+        let mut live = Vec::new();
+        for i in 0..self.node_count() {
+            let released = self.component_type(i) == COMPONENT_NONE;
+            for &(offset_field, length_field, capacity_field) in &TEXT_POOL_SLOTS {
+                let length = self.read_node_u32(i, length_field) as usize;
+                if length > 0 && !released {
+                    let offset = self.read_node_u32(i, offset_field) as usize;
+                    live.push((i, offset_field, capacity_field, offset, length));
+                    continue;
+                }
+                // Its old bytes are about to be reused by someone else
+                if released {
+                    self.write_node_u32(i, length_field, 0);
+                }
+                if let Some(field) = capacity_field {
+                    self.write_node_u32(i, field, 0);
+                }
+            }
+        }
+        live.sort_by_key(|&(_, _, _, offset, _)| offset);
+
+        // Sorted by offset, each string moves down (or stays): copy in place
+        let mut write_ptr = 0;
+        for &(i, offset_field, capacity_field, offset, length) in &live {
+            unsafe {
+                let base = self.ptr.add(self.text_pool_offset);
+                ptr::copy(base.add(offset), base.add(write_ptr), length);
+            }
+            self.write_node_u32(i, offset_field, write_ptr as u32);
+            if let Some(field) = capacity_field {
+                self.write_node_u32(i, field, length as u32);
+            }
+            write_ptr += length;
+        }
+
+        self.set_text_pool_write_ptr(write_ptr as u32);
+        self.write_header_u16(H_TEXT_POOL_COMPACTIONS, self.text_pool_compactions().wrapping_add(1));
+        old_write_ptr.saturating_sub(write_ptr)
+    }
+
+    /// Compactions so far (wraps); a change means pool offsets moved.
+    #[inline]
+    pub fn text_pool_compactions(&self) -> u16 {
+        self.read_header_u16(H_TEXT_POOL_COMPACTIONS)
+    }
+
+    /// Input completion suggestion (ghost text), empty if none
+    pub fn ghost_text(&self, i: usize) -> &str {
+        self.pool_str(
+            self.read_node_u32(i, N_GHOST_OFFSET) as usize,
+            self.read_node_u32(i, N_GHOST_LENGTH) as usize,
+        )
+    }
+
+    /// Write ghost text to the text pool. Returns false if the pool is full.
+    pub fn set_ghost_text(&self, i: usize, text: &str) -> bool {
+        let _pool = self.lock_text_pool();
+        self.write_pool_str(i, N_GHOST_OFFSET, N_GHOST_LENGTH, text, text.len()).is_some()
+    }
+
+    /// Dismiss the ghost text
+    #[inline]
+    pub fn clear_ghost_text(&self, i: usize) {
+        self.write_node_u32(i, N_GHOST_LENGTH, 0);
+    }
+
+    /// Input composition text not yet committed (IME preedit), empty if none
+    pub fn preedit(&self, i: usize) -> &str {
+        self.pool_str(
+            self.read_node_u32(i, N_PREEDIT_OFFSET) as usize,
+            self.read_node_u32(i, N_PREEDIT_LENGTH) as usize,
+        )
+    }
+
+    /// Write preedit text to the text pool. Returns false if the pool is full.
+    pub fn set_preedit(&self, i: usize, text: &str) -> bool {
+        let _pool = self.lock_text_pool();
+        self.write_pool_str(i, N_PREEDIT_OFFSET, N_PREEDIT_LENGTH, text, text.len()).is_some()
+    }
+
+    /// End the composition
+    #[inline]
+    pub fn clear_preedit(&self, i: usize) {
+        self.write_node_u32(i, N_PREEDIT_LENGTH, 0);
+    }
+
+    /// Hyperlink target (OSC 8) for the node's cells, empty if none
+    pub fn href(&self, i: usize) -> &str {
+        self.pool_str(
+            self.read_node_u32(i, N_HREF_OFFSET) as usize,
+            self.read_node_u32(i, N_HREF_LENGTH) as usize,
+        )
+    }
+
+    /// Write the hyperlink target to the text pool. Returns false if the pool is full.
+    pub fn set_href(&self, i: usize, url: &str) -> bool {
+        let _pool = self.lock_text_pool();
+        self.write_pool_str(i, N_HREF_OFFSET, N_HREF_LENGTH, url, url.len()).is_some()
+    }
+
+    /// Accessible name (from TS's `label` prop), empty if unset.
+    pub fn a11y_label(&self, i: usize) -> &str {
+        self.pool_str(
+            self.read_node_u32(i, N_A11Y_LABEL_OFFSET) as usize,
+            self.read_node_u32(i, N_A11Y_LABEL_LENGTH) as usize,
+        )
+    }
+
+    /// Write the accessible name to the text pool. Returns false if the pool is full.
+    pub fn set_a11y_label(&self, i: usize, label: &str) -> bool {
+        let _pool = self.lock_text_pool();
+        self.write_pool_str(i, N_A11Y_LABEL_OFFSET, N_A11Y_LABEL_LENGTH, label, label.len()).is_some()
+    }
+
+    /// Accessible value ("3 of 10", "checked"), empty if unset.
+    pub fn a11y_value(&self, i: usize) -> &str {
+        self.pool_str(
+            self.read_node_u32(i, N_A11Y_VALUE_OFFSET) as usize,
+            self.read_node_u32(i, N_A11Y_VALUE_LENGTH) as usize,
+        )
+    }
+
+    /// Write the accessible value to the text pool. Returns false if the pool is full.
+    pub fn set_a11y_value(&self, i: usize, value: &str) -> bool {
+        let _pool = self.lock_text_pool();
+        self.write_pool_str(i, N_A11Y_VALUE_OFFSET, N_A11Y_VALUE_LENGTH, value, value.len()).is_some()
+    }
+
+    /// Pattern or date mask of an input, empty if none
+    pub fn input_mask(&self, i: usize) -> &str {
+        self.pool_str(
+            self.read_node_u32(i, N_INPUT_MASK_OFFSET) as usize,
+            self.read_node_u32(i, N_INPUT_MASK_LENGTH) as usize,
+        )
+    }
+
+    /// Write an input's mask to the text pool. Returns false if the pool is full.
+    pub fn set_input_mask(&self, i: usize, mask: &str) -> bool {
+        let _pool = self.lock_text_pool();
+        self.write_pool_str(i, N_INPUT_MASK_OFFSET, N_INPUT_MASK_LENGTH, mask, mask.len()).is_some()
     }
 
     // =========================================================================
@@ -2052,12 +3721,54 @@ impl SharedBuffer {
     #[inline] pub fn cursor_style(&self, i: usize) -> CursorStyle { CursorStyle::from(self.read_node_u8(i, N_CURSOR_STYLE)) }
     #[inline] pub fn cursor_blink_rate(&self, i: usize) -> u8 { self.read_node_u8(i, N_CURSOR_BLINK_RATE) }
     #[inline] pub fn max_length(&self, i: usize) -> u8 { self.read_node_u8(i, N_MAX_LENGTH) }
+    #[inline] pub fn hover_tag(&self, i: usize) -> u32 { self.read_node_u32(i, N_HOVER_TAG) }
+    #[inline] pub fn word_boundary(&self, i: usize) -> WordBoundary { WordBoundary::from(self.read_node_u8(i, N_WORD_BOUNDARY)) }
+    #[inline] pub fn input_type(&self, i: usize) -> InputType { InputType::from(self.read_node_u8(i, N_INPUT_TYPE)) }
+    #[inline] pub fn input_flags(&self, i: usize) -> u8 { self.read_node_u8(i, N_INPUT_FLAGS) }
+    #[inline] pub fn set_input_flags(&self, i: usize, flags: u8) { self.write_node_u8(i, N_INPUT_FLAGS, flags) }
+    #[inline] pub fn a11y_role(&self, i: usize) -> A11yRole { A11yRole::from(self.read_node_u8(i, N_A11Y_ROLE)) }
+    #[inline] pub fn a11y_live(&self, i: usize) -> LiveRegion { LiveRegion::from(self.read_node_u8(i, N_A11Y_LIVE)) }
+    #[inline] pub fn direction(&self, i: usize) -> TextDirection { TextDirection::from(self.read_node_u8(i, N_DIRECTION)) }
+    #[inline] pub fn pointer_events(&self, i: usize) -> PointerEvents { PointerEvents::from(self.read_node_u8(i, N_POINTER_EVENTS)) }
+    #[inline] pub fn sticky(&self, i: usize) -> Sticky { Sticky::from(self.read_node_u8(i, N_STICKY)) }
+
+    /// The node's direction with Inherit resolved through its ancestors
+    pub fn resolved_direction(&self, i: usize) -> TextDirection {
+        let mut node = Some(i);
+        while let Some(n) = node {
+            match self.direction(n) {
+                TextDirection::Inherit => node = self.parent_index(n),
+                direction => return direction,
+            }
+        }
+        TextDirection::Auto
+    }
 
     #[inline] pub fn set_scroll(&self, i: usize, x: i32, y: i32) {
         self.write_node_i32(i, N_SCROLL_X, x);
         self.write_node_i32(i, N_SCROLL_Y, y);
     }
 
+    // Smooth scroll (cache line 16)
+    #[inline] pub fn smooth_scroll(&self, i: usize) -> bool { self.read_node_u8(i, N_SMOOTH_SCROLL) != 0 }
+    #[inline] pub fn scroll_animating(&self, i: usize) -> bool { self.read_node_u8(i, N_SCROLL_ANIMATING) != 0 }
+    #[inline] pub fn scroll_target(&self, i: usize) -> (i32, i32) {
+        (self.read_node_i32(i, N_SCROLL_TARGET_X), self.read_node_i32(i, N_SCROLL_TARGET_Y))
+    }
+    #[inline] pub fn scroll_velocity(&self, i: usize) -> (f32, f32) {
+        (self.read_node_f32(i, N_SCROLL_VELOCITY_X), self.read_node_f32(i, N_SCROLL_VELOCITY_Y))
+    }
+
+    #[inline] pub fn scroll_flags(&self, i: usize) -> u8 { self.read_node_u8(i, N_SCROLL_FLAGS) }
+    #[inline] pub fn reach_end_threshold(&self, i: usize) -> u8 { self.read_node_u8(i, N_REACH_END_THRESHOLD) }
+
+    /// Record a smooth-scroll target and mark the node animating.
+    #[inline] pub fn set_scroll_target(&self, i: usize, x: i32, y: i32) {
+        self.write_node_i32(i, N_SCROLL_TARGET_X, x);
+        self.write_node_i32(i, N_SCROLL_TARGET_Y, y);
+        self.write_node_u8(i, N_SCROLL_ANIMATING, 1);
+    }
+
     #[inline] pub fn set_cursor_position(&self, i: usize, pos: i32) {
         self.write_node_i32(i, N_CURSOR_POSITION, pos);
     }
@@ -2078,6 +3789,8 @@ impl SharedBuffer {
     #[inline] pub fn is_focused(&self, i: usize) -> bool { (self.interaction_flags(i) & FLAG_FOCUSED) != 0 }
     #[inline] pub fn is_hovered(&self, i: usize) -> bool { (self.interaction_flags(i) & FLAG_HOVERED) != 0 }
     #[inline] pub fn is_pressed(&self, i: usize) -> bool { (self.interaction_flags(i) & FLAG_PRESSED) != 0 }
+    #[inline] pub fn traps_focus(&self, i: usize) -> bool { (self.interaction_flags(i) & FLAG_FOCUS_TRAP) != 0 }
+    #[inline] pub fn captures_tab(&self, i: usize) -> bool { (self.interaction_flags(i) & FLAG_CAPTURE_TAB) != 0 }
     #[inline] pub fn is_disabled(&self, i: usize) -> bool { (self.interaction_flags(i) & FLAG_DISABLED) != 0 }
 
     #[inline]
@@ -2136,16 +3849,48 @@ impl SharedBuffer {
         self.read_header_u32(H_EVENT_READ_IDX)
     }
 
+    /// Take the oldest unread event slot (type, generation tag, u16
+    /// component index, 16 data bytes), the way TS's `readEvents` does:
+    /// component events whose target was released since are skipped.
+    /// For hosts reading events without the TS side.
+    pub fn pop_event(&self) -> Option<[u8; EVENT_SLOT_SIZE]> {
+        let write_idx = self.event_write_idx();
+        let mut read_idx = self.event_read_idx();
+        let mut event = None;
+        while read_idx < write_idx && event.is_none() {
+            let offset = self.event_ring_offset + EVENT_RING_HEADER_SIZE + (read_idx as usize % MAX_EVENTS) * EVENT_SLOT_SIZE;
+            let mut slot = [0u8; EVENT_SLOT_SIZE];
+            unsafe { ptr::copy_nonoverlapping(self.ptr.add(offset), slot.as_mut_ptr(), EVENT_SLOT_SIZE) };
+            read_idx += 1;
+
+            let index = u16::from_le_bytes([slot[2], slot[3]]) as usize;
+            let stale = slot[1] != EVENT_UNTAGGED
+                && (index >= self.max_nodes || slot[1] != generation_tag(self.node_generation(index)));
+            if slot[0] != EventType::None as u8 && !stale {
+                event = Some(slot);
+            }
+        }
+        self.write_header_u32(H_EVENT_READ_IDX, read_idx);
+        event
+    }
+
     /// Push an event to the ring buffer
     pub fn push_event(&self, event_type: EventType, component_index: u16, data: &[u8; 16]) {
         let write_idx = self.event_write_idx() as usize;
         let slot = write_idx % MAX_EVENTS;
         let offset = self.event_ring_offset + EVENT_RING_HEADER_SIZE + slot * EVENT_SLOT_SIZE;
 
+        let index = component_index as usize;
+        let tag = if event_type.targets_component() && index < self.node_count() {
+            generation_tag(self.node_generation(index))
+        } else {
+            EVENT_UNTAGGED
+        };
+
         unsafe {
             let ptr = self.ptr.add(offset);
             *ptr = event_type as u8;
-            // ptr[1] is padding
+            *ptr.add(1) = tag;
             ptr::write_unaligned(ptr.add(2) as *mut u16, component_index);
             ptr::copy_nonoverlapping(data.as_ptr(), ptr.add(4), 16);
         }
@@ -2184,6 +3929,78 @@ impl SharedBuffer {
         data[0] = exit_code;
         self.push_event(EventType::Exit, 0xFFFF, &data);
     }
+
+    /// Push a scroll-animate event: a smooth-scroll target was set on `component_index`
+    pub fn push_scroll_animate_event(&self, component_index: u16) {
+        self.push_event(EventType::ScrollAnimate, component_index, &[0; 16]);
+    }
+
+    /// Push a scroll-change event: `component_index` now scrolls to (x, y)
+    pub fn push_scroll_change_event(&self, component_index: u16, x: i32, y: i32) {
+        let mut data = [0u8; 16];
+        data[0..4].copy_from_slice(&x.to_le_bytes());
+        data[4..8].copy_from_slice(&y.to_le_bytes());
+        self.push_event(EventType::ScrollChange, component_index, &data);
+    }
+
+    /// Push a reach-end event: `component_index` scrolled (or shrank) to
+    /// within its threshold of the bottom, `remaining` rows short of it
+    pub fn push_reach_end_event(&self, component_index: u16, remaining: i32) {
+        let mut data = [0u8; 16];
+        data[0..4].copy_from_slice(&remaining.to_le_bytes());
+        self.push_event(EventType::ReachEnd, component_index, &data);
+    }
+
+    /// Push a hover event: the tagged node under the mouse (-1 = none), its
+    /// tag and its screen region
+    pub fn push_hover_event(&self, index: i32, tag: u32, region: (u16, u16, u16, u16)) {
+        let mut data = [0u8; 16];
+        data[0..4].copy_from_slice(&tag.to_le_bytes());
+        data[4..6].copy_from_slice(&region.0.to_le_bytes());
+        data[6..8].copy_from_slice(&region.1.to_le_bytes());
+        data[8..10].copy_from_slice(&region.2.to_le_bytes());
+        data[10..12].copy_from_slice(&region.3.to_le_bytes());
+        let target = if index < 0 { 0xFFFF } else { index as u16 };
+        self.push_event(EventType::Hover, target, &data);
+    }
+
+    /// Push a capabilities event: the H_COLOR_DEPTH.. header slots changed
+    pub fn push_capabilities_event(&self) {
+        self.push_event(EventType::Capabilities, 0xFFFF, &[0; 16]);
+    }
+
+    /// Push a terminal color event: the terminal reported `slot`
+    /// (`TERMINAL_COLOR_FOREGROUND` or `TERMINAL_COLOR_BACKGROUND`) as `rgb`
+    pub fn push_terminal_color_event(&self, slot: u8, rgb: [u8; 3]) {
+        let mut data = [0u8; 16];
+        data[0] = slot;
+        data[1..4].copy_from_slice(&rgb);
+        self.push_event(EventType::TerminalColor, 0xFFFF, &data);
+    }
+
+    /// Push an error event: a panic was caught; `component` is the node
+    /// whose callback (or focused input) it came from, if any
+    pub fn push_error_event(&self, component: Option<usize>, source: ErrorSource) {
+        let mut data = [0u8; 16];
+        data[0] = source as u8;
+        let target = component.map_or(0xFFFF, |index| index as u16);
+        self.push_event(EventType::Error, target, &data);
+    }
+
+    /// Push a layout change event: how many nodes got a new computed rect
+    pub fn push_layout_change_event(&self, changed: u32) {
+        let mut data = [0u8; 16];
+        data[0..4].copy_from_slice(&changed.to_le_bytes());
+        self.push_event(EventType::LayoutChange, 0xFFFF, &data);
+    }
+
+    /// Push a screenshot event: the capture's timestamp (file stem) and whether it was saved
+    pub fn push_screenshot_event(&self, timestamp_ms: u64, saved: bool) {
+        let mut data = [0u8; 16];
+        data[0..8].copy_from_slice(&timestamp_ms.to_le_bytes());
+        data[8] = saved as u8;
+        self.push_event(EventType::Screenshot, 0xFFFF, &data);
+    }
 }
 
 // =============================================================================
@@ -2211,6 +4028,81 @@ impl Iterator for ChildIter<'_> {
     }
 }
 
+// =============================================================================
+// TEST SUPPORT
+// =============================================================================
+
+/// Buffers for unit tests, built without TS.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+
+    /// A zeroed buffer that owns its bytes. Derefs to the `SharedBuffer`
+    /// over them.
+    pub(crate) struct TestBuffer {
+        _data: Vec<u8>,
+        buf: SharedBuffer,
+    }
+
+    impl TestBuffer {
+        /// Room for `nodes` nodes and a `pool`-byte text pool. No node is
+        /// live until `node()` creates it.
+        pub(crate) fn new(nodes: usize, pool: usize) -> Self {
+            let mut data = vec![0u8; calculate_buffer_size(nodes, pool)];
+            for (field, value) in [(H_VERSION, 3), (H_MAX_NODES, nodes as u32), (H_TEXT_POOL_SIZE, pool as u32)] {
+                data[field..field + 4].copy_from_slice(&value.to_le_bytes());
+            }
+            let buf = unsafe { SharedBuffer::from_raw(data.as_mut_ptr(), data.len()) };
+            Self { _data: data, buf }
+        }
+
+        /// Visible, fully opaque node of `component` type under `parent`
+        /// (-1 for a root). Counts it live, as TS allocation does.
+        pub(crate) fn node(&self, i: usize, component: u8, parent: i32) {
+            if i >= self.buf.node_count() {
+                self.buf.write_header_u32(H_NODE_COUNT, i as u32 + 1);
+            }
+            self.buf.write_node_u8(i, N_COMPONENT_TYPE, component);
+            self.buf.write_node_u8(i, N_VISIBLE, 1);
+            self.buf.write_node_i32(i, N_PARENT_INDEX, parent);
+            self.buf.write_node_f32(i, N_OPACITY, 1.0);
+        }
+
+        /// Raw writes for node fields without a setter of their own
+        pub(crate) fn put_u8(&self, i: usize, field: usize, value: u8) {
+            self.buf.write_node_u8(i, field, value);
+        }
+
+        pub(crate) fn put_u32(&self, i: usize, field: usize, value: u32) {
+            self.buf.write_node_u32(i, field, value);
+        }
+
+        pub(crate) fn put_i32(&self, i: usize, field: usize, value: i32) {
+            self.buf.write_node_i32(i, field, value);
+        }
+
+        pub(crate) fn put_f32(&self, i: usize, field: usize, value: f32) {
+            self.buf.write_node_f32(i, field, value);
+        }
+
+        pub(crate) fn put_header_u8(&self, field: usize, value: u8) {
+            self.buf.write_header_u8(field, value);
+        }
+
+        pub(crate) fn put_header_u32(&self, field: usize, value: u32) {
+            self.buf.write_header_u32(field, value);
+        }
+    }
+
+    impl std::ops::Deref for TestBuffer {
+        type Target = SharedBuffer;
+
+        fn deref(&self) -> &SharedBuffer {
+            &self.buf
+        }
+    }
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -2218,6 +4110,8 @@ impl Iterator for ChildIter<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::test_support::TestBuffer;
+    use std::collections::HashMap;
 
     fn create_test_buffer(max_nodes: usize, text_pool_size: usize) -> (Vec<u8>, SharedBuffer) {
         let text_pool_offset = HEADER_SIZE + max_nodes * NODE_STRIDE;
@@ -2238,6 +4132,122 @@ mod tests {
         (data, buf)
     }
 
+    /// What TS `initializeNode` writes, replayed on zeroed bytes from its
+    /// source: its `v.setX(base + N_*, value)` calls, with N_* offsets, enum
+    /// members and constants resolved from the same file.
+    fn ts_node_defaults() -> (Vec<u8>, HashMap<usize, String>) {
+        const TS: &str = include_str!("../../ts/bridge/shared-buffer.ts");
+        let number = |text: &str| -> Option<f64> {
+            match text {
+                "NaN" => Some(f64::NAN),
+                _ => match text.strip_prefix("0x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().map(f64::from),
+                    None => text.parse().ok(),
+                },
+            }
+        };
+
+        let mut names: HashMap<String, f64> = HashMap::new();
+        let mut in_enum: Option<&str> = None;
+        for line in TS.lines() {
+            // Without a trailing comment
+            let line = line.split("//").next().unwrap_or_default().trim();
+            if let Some(name) = line.strip_prefix("export const enum ") {
+                in_enum = Some(name.trim_end_matches(" {"));
+            } else if line.starts_with('}') {
+                in_enum = None;
+            } else if let Some((member, value)) = line.trim_end_matches(',').split_once(" = ") {
+                let (name, value) = match in_enum {
+                    Some(enum_name) => (format!("{enum_name}.{member}"), value),
+                    None => match member.strip_prefix("export const ") {
+                        Some(name) => (name.to_string(), value.split(';').next().unwrap_or_default().trim()),
+                        None => continue,
+                    },
+                };
+                if let Some(value) = number(value) {
+                    names.insert(name, value);
+                }
+            }
+        }
+
+        let body = TS.split("function initializeNode(").nth(1).expect("initializeNode in shared-buffer.ts");
+        let body = &body[..body.find("\n}").expect("end of initializeNode")];
+        let mut bytes = vec![0u8; NODE_STRIDE];
+        let mut fields = HashMap::new();
+        for call in body.lines().filter_map(|line| line.trim().strip_prefix("v.set")) {
+            let call = call.split("//").next().unwrap_or_default().trim();
+            let (setter, args) = call.split_once('(').unwrap();
+            let mut args = args.trim_end_matches(");").split(", ");
+            let field = args.next().unwrap().trim_start_matches("base + ");
+            let value = args.next().unwrap();
+            let offset = names[field] as usize;
+            let value = number(value).or_else(|| names.get(value).copied()).unwrap_or_else(|| panic!("{field}: {value}"));
+            let encoded = match setter {
+                "Float32" => (value as f32).to_le_bytes().to_vec(),
+                "Int32" => (value as i32).to_le_bytes().to_vec(),
+                "Uint32" => (value as u32).to_le_bytes().to_vec(),
+                "Int16" => (value as i16).to_le_bytes().to_vec(),
+                "Uint16" => (value as u16).to_le_bytes().to_vec(),
+                "Uint8" => vec![value as u8],
+                other => panic!("setter {other}"),
+            };
+            bytes[offset..offset + encoded.len()].copy_from_slice(&encoded);
+            fields.insert(offset, field.to_string());
+        }
+        (bytes, fields)
+    }
+
+    #[test]
+    fn test_field_dirty_matches_ts_marks() {
+        const TS_LAYOUT: &str = include_str!("../../ts/bridge/shared-buffer.ts");
+        const TS_ARRAYS: &str = include_str!("../../ts/bridge/reactive-arrays.ts");
+
+        let offsets: HashMap<&str, usize> = TS_LAYOUT
+            .lines()
+            .filter_map(|line| {
+                let (name, value) = line.strip_prefix("export const N_")?.split_once(" = ")?;
+                Some((name, value.split(';').next()?.trim().parse().ok()?))
+            })
+            .collect();
+
+        // `width: f32(N_WIDTH, DIRTY_LAYOUT),`: each reactive array's field
+        // and what its writes mark
+        let mut checked = 0;
+        for line in TS_ARRAYS.lines() {
+            let Some((_, args)) = line.split_once("(N_") else { continue };
+            let args = args.split(')').next().unwrap_or_default();
+            let (name, mark) = args.split_once(", ").unwrap_or((args, ""));
+            let Some(&field) = offsets.get(name) else { continue };
+            let expected = match mark {
+                "DIRTY_LAYOUT" => DIRTY_LAYOUT,
+                "DIRTY_HIERARCHY" => DIRTY_HIERARCHY,
+                _ => DIRTY_VISUAL,
+            };
+            assert_eq!(field_dirty(field), expected, "N_{name}");
+            checked += 1;
+        }
+        assert!(checked > 100, "only {checked} reactive array fields found");
+    }
+
+    #[test]
+    fn test_init_node_matches_ts_defaults() {
+        let (expected, fields) = ts_node_defaults();
+        let buf = TestBuffer::new(1, 0);
+        unsafe { ptr::write_bytes(buf.node_ptr_mut(0), 0xAA, NODE_STRIDE) };
+        buf.write_node_u32(0, N_GENERATION, 0);
+        buf.init_node(0);
+        let actual = unsafe { std::slice::from_raw_parts(buf.node_ptr(0), NODE_STRIDE) };
+
+        let differing: Vec<String> = (0..NODE_STRIDE)
+            .filter(|&at| actual[at] != expected[at])
+            .map(|at| {
+                let field = (0..=at).rev().find_map(|start| fields.get(&start)).map_or("?", String::as_str);
+                format!("byte {at} ({field}): rust {:#04x}, ts {:#04x}", actual[at], expected[at])
+            })
+            .collect();
+        assert!(differing.is_empty(), "init_node and initializeNode disagree:\n{}", differing.join("\n"));
+    }
+
     #[test]
     fn test_constants_alignment() {
         // Verify cache line boundaries (64-byte aligned)
@@ -2286,6 +4296,215 @@ mod tests {
         assert_eq!(buf.mouse_position(), (100, 50));
     }
 
+    #[test]
+    fn test_hover_payload() {
+        let (_data, buf) = create_test_buffer(100, 1024);
+        assert_eq!(buf.hover_seq(), 0);
+
+        buf.set_hover_payload(7, 0xBEEF, (4, 2, 10, 3));
+        assert_eq!(buf.hover_seq(), 1);
+        assert_eq!(buf.hover_payload_index(), 7);
+        assert_eq!(buf.hover_payload_tag(), 0xBEEF);
+        assert_eq!(buf.hover_region(), (4, 2, 10, 3));
+
+        buf.set_hover_payload(-1, 0, (0, 0, 0, 0));
+        assert_eq!(buf.hover_seq(), 2);
+        assert_eq!(buf.hover_payload_index(), -1);
+
+        // Every change reaches TS as a Hover event
+        let event = buf.pop_event().unwrap();
+        assert_eq!(EventType::from(event[0]), EventType::Hover);
+        assert_eq!(u16::from_le_bytes([event[2], event[3]]), 7);
+        assert_eq!(u32::from_le_bytes(event[4..8].try_into().unwrap()), 0xBEEF);
+        assert_eq!(u16::from_le_bytes([event[12], event[13]]), 10);
+        let event = buf.pop_event().unwrap();
+        assert_eq!(EventType::from(event[0]), EventType::Hover);
+        assert_eq!(u16::from_le_bytes([event[2], event[3]]), 0xFFFF);
+        assert!(buf.pop_event().is_none());
+    }
+
+    #[test]
+    fn test_component_refs_and_event_tags() {
+        let (_data, buf) = create_test_buffer(4, 64);
+        unsafe { ptr::write_unaligned(buf.ptr.add(H_NODE_COUNT) as *mut u32, 2) };
+        buf.write_node_u8(1, N_COMPONENT_TYPE, COMPONENT_BOX);
+
+        let old = buf.component_ref(1);
+        assert_eq!(buf.resolve(old), Some(1));
+        assert_eq!(buf.live_index(old), 1);
+
+        // TS releases index 1, then reuses it for another component
+        buf.write_node_u32(1, N_GENERATION, 1);
+        assert!(!buf.is_live(old));
+        assert_eq!(buf.resolve(buf.component_ref(1)), Some(1));
+        assert_eq!(buf.resolve(ComponentRef { index: 3, generation: 0 }), None);
+
+        // Component events carry their target's generation; app events don't
+        let slot = |n: usize| buf.event_ring_offset + EVENT_RING_HEADER_SIZE + n * EVENT_SLOT_SIZE;
+        buf.push_focus_event(1);
+        buf.push_event(EventType::Resize, 1, &[0; 16]);
+        buf.push_focus_event(3);
+        unsafe {
+            assert_eq!(*buf.ptr.add(slot(0) + 1), generation_tag(1));
+            assert_eq!(*buf.ptr.add(slot(1) + 1), EVENT_UNTAGGED);
+            assert_eq!(*buf.ptr.add(slot(2) + 1), EVENT_UNTAGGED);
+        }
+        assert_ne!(generation_tag(254), generation_tag(255));
+        assert_ne!(generation_tag(u32::MAX), EVENT_UNTAGGED);
+    }
+
+    #[test]
+    fn test_text_rewritten_in_place() {
+        let (_data, buf) = create_test_buffer(4, 1024);
+
+        assert!(buf.set_text(0, "9"));
+        let offset = buf.read_node_u32(0, N_TEXT_OFFSET);
+        assert_eq!(buf.text_capacity(0), TEXT_SLOT_ALIGN as u32);
+        let write_ptr = buf.text_pool_write_ptr();
+
+        // Growth within the slot reuses it
+        assert!(buf.set_text(0, "10"));
+        assert!(buf.set_text(0, "1000000"));
+        assert_eq!(buf.text(0), "1000000");
+        assert_eq!(buf.read_node_u32(0, N_TEXT_OFFSET), offset);
+        assert_eq!(buf.text_pool_write_ptr(), write_ptr);
+
+        // Outgrowing it allocates a new slot
+        assert!(buf.set_text(0, "100000000"));
+        assert_eq!(buf.text(0), "100000000");
+        assert_ne!(buf.read_node_u32(0, N_TEXT_OFFSET), offset);
+        assert_eq!(buf.text_capacity(0), 2 * TEXT_SLOT_ALIGN as u32);
+    }
+
+    #[test]
+    fn test_text_headroom_dropped_when_pool_nearly_full() {
+        let (_data, buf) = create_test_buffer(4, 10);
+
+        assert!(buf.set_text(0, "abc"));
+        assert!(buf.set_text(1, "de"));
+        assert_eq!(buf.text_capacity(1), 2);
+        assert_eq!(buf.text_pool_write_ptr(), 10);
+
+        // Full: compaction trims "abc"'s headroom, which is still too little
+        unsafe { ptr::write_unaligned(buf.ptr.add(H_NODE_COUNT) as *mut u32, 3) };
+        for i in 0..3 {
+            buf.write_node_u8(i, N_COMPONENT_TYPE, COMPONENT_TEXT);
+        }
+        assert!(!buf.set_text(2, "xxxxxx"));
+        assert!(buf.set_text(2, "x"));
+        assert_eq!(buf.text(0), "abc");
+        assert_eq!(buf.text(1), "de");
+        assert_eq!(buf.text_pool_write_ptr(), 6);
+    }
+
+    #[test]
+    fn test_text_pool_compaction() {
+        let (_data, buf) = create_test_buffer(4, 100);
+        unsafe { ptr::write_unaligned(buf.ptr.add(H_NODE_COUNT) as *mut u32, 3) };
+        for i in 0..3 {
+            buf.write_node_u8(i, N_COMPONENT_TYPE, COMPONENT_TEXT);
+        }
+
+        assert!(buf.set_text(0, "first"));
+        assert!(buf.set_href(0, "https://a"));
+        assert!(buf.set_text(1, "second"));
+        assert!(buf.set_text(2, "released"));
+        buf.write_node_u8(2, N_COMPONENT_TYPE, COMPONENT_NONE);
+        // Outgrown slots leave dead bytes behind
+        assert!(buf.set_text(0, "first, longer"));
+        let before = buf.text_pool_write_ptr() as usize;
+
+        let reclaimed = buf.compact_text_pool();
+        assert_eq!(buf.text_pool_write_ptr() as usize, 9 + 6 + 13);
+        assert_eq!(reclaimed, before - 28);
+        assert_eq!(buf.text_pool_compactions(), 1);
+        assert_eq!(buf.href(0), "https://a");
+        assert_eq!(buf.text(1), "second");
+        assert_eq!(buf.text(0), "first, longer");
+        assert_eq!(buf.text_capacity(0), 13);
+        assert_eq!(buf.text_length(2), 0);
+    }
+
+    #[test]
+    fn test_text_pool_writers_on_two_threads() {
+        // Rust and TS both write and compact the pool: stand in for TS with
+        // a second thread, each side rewriting its own texts at growing lengths
+        // so both keep allocating and compacting
+        let buf = TestBuffer::new(8, 512);
+        for i in 0..8 {
+            buf.node(i, COMPONENT_TEXT, -1);
+        }
+        let texts = |i: usize, round: usize| char::from(b'a' + i as u8).to_string().repeat(1 + (round * 7 + i) % 40);
+        let write = |nodes: std::ops::Range<usize>| {
+            for round in 0..2000 {
+                for i in nodes.clone() {
+                    assert!(buf.set_text(i, &texts(i, round)));
+                    if i % 4 == 0 {
+                        assert!(buf.set_href(i, &texts(i, round + 1)));
+                    }
+                }
+            }
+        };
+        std::thread::scope(|scope| {
+            scope.spawn(|| write(0..4));
+            write(4..8);
+        });
+
+        assert!(buf.text_pool_compactions() > 0);
+        for i in 0..8 {
+            assert_eq!(buf.text(i), texts(i, 1999));
+        }
+        assert_eq!(buf.href(0), texts(0, 2000));
+        assert_eq!(buf.href(4), texts(4, 2000));
+        assert_eq!(buf.read_header_u8(H_TEXT_POOL_LOCK), 0);
+    }
+
+    #[test]
+    fn test_held_text_pool_keeps_out_the_other_side() {
+        let (_data, buf) = create_test_buffer(4, 256);
+        unsafe { ptr::write_unaligned(buf.ptr.add(H_NODE_COUNT) as *mut u32, 2) };
+        buf.write_node_u8(0, N_COMPONENT_TYPE, COMPONENT_TEXT);
+        buf.write_node_u8(1, N_COMPONENT_TYPE, COMPONENT_TEXT);
+        assert!(buf.set_text(0, "read"));
+
+        let pool = buf.lock_text_pool();
+        let text = buf.text(0);
+        std::thread::scope(|scope| {
+            // Stands in for TS: it may not compact or rewrite while the span is held
+            let other = scope.spawn(|| {
+                assert!(buf.set_text(0, "moved"));
+                buf.compact_text_pool();
+            });
+            // Writes inside the held span go straight through
+            assert!(buf.set_text(1, "nested"));
+            assert_eq!(buf.read_header_u8(H_TEXT_POOL_LOCK), 1);
+            std::thread::sleep(Duration::from_millis(20));
+            assert!(!other.is_finished());
+            assert_eq!(text, "read");
+            drop(pool);
+        });
+
+        assert_eq!(buf.text(0), "moved");
+        assert_eq!(buf.text(1), "nested");
+        assert_eq!(buf.read_header_u8(H_TEXT_POOL_LOCK), 0);
+    }
+
+    #[test]
+    fn test_text_pool_compacts_at_high_water() {
+        let (_data, buf) = create_test_buffer(4, 100);
+        unsafe { ptr::write_unaligned(buf.ptr.add(H_NODE_COUNT) as *mut u32, 1) };
+        buf.write_node_u8(0, N_COMPONENT_TYPE, COMPONENT_TEXT);
+
+        // A clock that keeps outgrowing its slot
+        for len in 1..=6 {
+            assert!(buf.set_text(0, &"9".repeat(len * TEXT_SLOT_ALIGN)));
+        }
+        // 8+16+24 fit under the mark; 32, 40 and 48 each compacted first
+        assert_eq!(buf.text_pool_compactions(), 3);
+        assert_eq!(buf.text(0), "9".repeat(48));
+        assert_eq!(buf.text_pool_write_ptr(), 88);
+    }
+
     #[test]
     fn test_node_layout_fields() {
         let (mut data, buf) = create_test_buffer(100, 1024);
@@ -2410,7 +4629,15 @@ mod tests {
         assert_eq!(BorderStyle::from(200), BorderStyle::None); // Unknown -> None
 
         assert_eq!(EventType::from(9), EventType::Focus);
+        assert_eq!(EventType::from(16), EventType::Screenshot);
+        assert_eq!(EventType::from(17), EventType::ScrollAnimate);
         assert_eq!(EventType::from(255), EventType::None);
+
+        assert_eq!(WordBoundary::from(2), WordBoundary::Identifier);
+        assert_eq!(WordBoundary::from(255), WordBoundary::Unicode);
+        assert_eq!(BackgroundFill::from(1), BackgroundFill::LinearGradient);
+        assert_eq!(BackgroundFill::from(5), BackgroundFill::StripesDiagonal);
+        assert_eq!(BackgroundFill::from(9), BackgroundFill::Solid);
     }
 
     #[test]
@@ -0,0 +1,185 @@
+//! Frame callbacks — run once per frame, ahead of the render pipeline.
+//!
+//! ```ignore
+//! let progress = signal(0.0f32);
+//! let p = progress.clone();
+//! let _unsubscribe = frame::on_frame(move |now| p.set(eased(start, now)));
+//! ```
+//!
+//! Every propagation the engine starts (TS wake, input, resize, timers, task
+//! results) is a frame. Before bumping `generation`, the engine runs every
+//! frame callback with the frame's timestamp, in the same batch as the bump:
+//!
+//! ```text
+//! frame begins (timestamp)
+//!   │  batch {
+//!   ├─→ on_frame callbacks (subscription order) → set signals / SharedBuffer
+//!   └─→ generation += 1
+//!   │  }
+//!   ▼
+//! layout derived → framebuffer derived → render effect   (once)
+//! ```
+//!
+//! So a progress bar, a chart and a status line driven from callbacks all
+//! move in the same render, never one frame apart. Callbacks don't cause
+//! frames — nothing renders just because one is subscribed.
+//!
+//! A callback that panics is unsubscribed and reported to TS as an `Error`
+//! event; the rest of the frame carries on.
+
+use std::cell::RefCell;
+use std::time::Instant;
+
+use crate::pipeline::terminal;
+use crate::shared_buffer::{ErrorSource, SharedBuffer};
+
+/// Identifies a frame callback.
+pub type FrameCallbackId = u64;
+
+/// Taken out while running, so the callback may (un)subscribe.
+type Slot = Option<Box<dyn FnMut(Instant)>>;
+
+struct Callbacks {
+    /// Subscription order.
+    entries: Vec<(FrameCallbackId, Slot)>,
+    next_id: FrameCallbackId,
+}
+
+thread_local! {
+    static CALLBACKS: RefCell<Callbacks> = const {
+        RefCell::new(Callbacks { entries: Vec::new(), next_id: 1 })
+    };
+}
+
+/// Handle returned by `on_frame`. Dropping it leaves the callback subscribed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsubscribe {
+    id: FrameCallbackId,
+}
+
+impl Unsubscribe {
+    pub fn id(&self) -> FrameCallbackId {
+        self.id
+    }
+
+    /// Whether the callback is still subscribed.
+    pub fn is_active(&self) -> bool {
+        CALLBACKS.with(|c| c.borrow().entries.iter().any(|(id, _)| *id == self.id))
+    }
+
+    pub fn unsubscribe(&self) {
+        CALLBACKS.with(|c| c.borrow_mut().entries.retain(|(id, _)| *id != self.id));
+    }
+}
+
+/// Run `callback` at the start of every frame with the frame timestamp,
+/// before layout and the framebuffer are computed.
+pub fn on_frame(callback: impl FnMut(Instant) + 'static) -> Unsubscribe {
+    CALLBACKS.with(|c| {
+        let mut c = c.borrow_mut();
+        let id = c.next_id;
+        c.next_id += 1;
+        c.entries.push((id, Some(Box::new(callback))));
+        Unsubscribe { id }
+    })
+}
+
+/// Unsubscribe everything (engine shutdown).
+pub fn clear_all() {
+    CALLBACKS.with(|c| c.borrow_mut().entries.clear());
+}
+
+/// Run every frame callback for the frame starting at `now`. Called by the
+/// engine inside the batch that bumps `generation`.
+pub fn run(buf: &SharedBuffer, now: Instant) {
+    run_with(now, || buf.push_error_event(None, ErrorSource::Frame));
+}
+
+fn run_with(now: Instant, mut panicked: impl FnMut()) {
+    // Callbacks subscribed during this frame wait for the next one
+    let ids: Vec<FrameCallbackId> = CALLBACKS.with(|c| c.borrow().entries.iter().map(|(id, _)| *id).collect());
+
+    for id in ids {
+        // Release the borrow while running: callbacks may (un)subscribe
+        let Some(mut callback) = CALLBACKS.with(|c| {
+            c.borrow_mut().entries.iter_mut().find(|(entry, _)| *entry == id).and_then(|(_, cb)| cb.take())
+        }) else {
+            continue; // Unsubscribed by an earlier callback
+        };
+        if terminal::contained(|| callback(now)).is_err() {
+            CALLBACKS.with(|c| c.borrow_mut().entries.retain(|(entry, _)| *entry != id));
+            panicked();
+            continue;
+        }
+
+        CALLBACKS.with(|c| {
+            // Unsubscribed by its own callback → stays unsubscribed
+            if let Some((_, slot)) = c.borrow_mut().entries.iter_mut().find(|(entry, _)| *entry == id) {
+                *slot = Some(callback);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_callbacks_run_in_order_with_frame_time() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (a, b) = (seen.clone(), seen.clone());
+        let first = on_frame(move |now| a.borrow_mut().push(("first", now)));
+        let second = on_frame(move |now| b.borrow_mut().push(("second", now)));
+
+        let now = Instant::now();
+        run_with(now, || {});
+        assert_eq!(*seen.borrow(), vec![("first", now), ("second", now)]);
+
+        first.unsubscribe();
+        assert!(!first.is_active() && second.is_active());
+        run_with(now, || {});
+        assert_eq!(seen.borrow().len(), 3);
+        clear_all();
+    }
+
+    #[test]
+    fn test_callbacks_can_unsubscribe_during_a_frame() {
+        let count = Rc::new(Cell::new(0));
+        let handle = Rc::new(Cell::new(None::<Unsubscribe>));
+        let own = handle.clone();
+        let c = count.clone();
+        handle.set(Some(on_frame(move |_| {
+            c.set(c.get() + 1);
+            own.get().unwrap().unsubscribe();
+            // Starts with the next frame
+            let late = c.clone();
+            on_frame(move |_| late.set(late.get() + 10));
+        })));
+
+        run_with(Instant::now(), || {});
+        assert_eq!(count.get(), 1);
+        assert!(!handle.get().unwrap().is_active());
+        run_with(Instant::now(), || {});
+        assert_eq!(count.get(), 11);
+        clear_all();
+    }
+
+    #[test]
+    fn test_panicking_callback_is_unsubscribed() {
+        let count = Rc::new(Cell::new(0));
+        let broken = on_frame(|_| panic!("frame callback"));
+        let c = count.clone();
+        let healthy = on_frame(move |_| c.set(c.get() + 1));
+
+        let failures = Rc::new(Cell::new(0));
+        let report = failures.clone();
+        run_with(Instant::now(), move || report.set(report.get() + 1));
+        assert_eq!(failures.get(), 1);
+        assert_eq!(count.get(), 1);
+        assert!(!broken.is_active() && healthy.is_active());
+        clear_all();
+    }
+}
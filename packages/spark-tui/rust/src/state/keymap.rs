@@ -0,0 +1,656 @@
+//! App keymap — named actions bound to key sequences, on the engine thread.
+//!
+//! ```ignore
+//! keymap::define_keymap(KeymapContext::Global, &[
+//!     ("save", "Ctrl+S"),
+//!     ("quit", "q"),
+//!     ("quit", "Ctrl+Q"),
+//!     ("go-top", "g g"),
+//!     ("save-all", "Ctrl+K Ctrl+S"),
+//! ])?;
+//! keymap::define_keymap(KeymapContext::Zone(buf.component_ref(sidebar)), &[("new-file", "a")])?;
+//!
+//! let _save = keymap::on_action("save", move || { save(); true });
+//! keymap::rebind("quit", &["Ctrl+X"], None)?;
+//! ```
+//!
+//! The Rust counterpart of ts/state/keymap.ts, for app-level shortcuts of a
+//! Rust host instead of ad-hoc key handling. Same key syntax (`'+'` joins
+//! modifiers, spaces separate the strokes of a chord, letters ignore case so
+//! Shift is spelled out: `Shift+G`) and the same rules:
+//!
+//! - Per-context maps, most specific wins: a focus zone (focus inside that
+//!   node, innermost first) → the focused component type → global
+//! - Runtime rebinding, and conflict detection within a context
+//!   (`find_conflicts`: the same sequence twice, or one starting another)
+//!
+//! `keyboard::dispatch_key` offers every key press here after input editing
+//! and before TS and framework navigation. A key that completes a binding
+//! runs the action's handlers (subscription order) until one returns true;
+//! if none does, the key carries on as if unbound. A key that starts a chord
+//! is held; if a sequence is both complete and the start of a longer one
+//! (`g` and `g g`), the shorter fires after `CHORD_TIMEOUT` unless the next
+//! key continues it.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::time::Duration;
+
+use crate::input::parser::{KeyCode, KeyEvent, Modifier};
+use crate::shared_buffer::{ComponentRef, SharedBuffer};
+use super::timers::{self, TimerHandle};
+
+/// How long a half-typed chord waits for its next key.
+pub const CHORD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Modifiers in the order strokes are written in.
+const MODIFIER_ORDER: [(Modifier, &str); 4] = [
+    (Modifier::CTRL, "ctrl"),
+    (Modifier::ALT, "alt"),
+    (Modifier::SHIFT, "shift"),
+    (Modifier::SUPER, "meta"),
+];
+
+// =============================================================================
+// Strokes
+// =============================================================================
+
+/// One key with its modifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stroke {
+    pub code: KeyCode,
+    pub modifiers: Modifier,
+}
+
+impl Stroke {
+    /// Parse one stroke: `"q"`, `"Ctrl+S"`, `"Shift+Tab"`, `"Ctrl++"`.
+    pub fn parse(stroke: &str) -> Option<Self> {
+        let stroke = stroke.trim();
+        let (mods, key) = match stroke.rsplit_once('+') {
+            // "Ctrl++" / "+": the key is '+'
+            Some((mods, "")) => (mods.strip_suffix('+').unwrap_or(mods), "+"),
+            Some((mods, key)) => (mods, key),
+            None => ("", stroke),
+        };
+
+        let mut modifiers = Modifier::empty();
+        for name in mods.split('+').map(str::trim).filter(|m| !m.is_empty()) {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => Modifier::CTRL,
+                "alt" | "option" => Modifier::ALT,
+                "shift" => Modifier::SHIFT,
+                "meta" | "super" | "cmd" => Modifier::SUPER,
+                _ => return None,
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(ch), None) => KeyCode::Char(ch.to_ascii_lowercase()),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "escape" | "esc" => KeyCode::Escape,
+                "delete" | "del" => KeyCode::Delete,
+                "up" | "arrowup" => KeyCode::Up,
+                "down" | "arrowdown" => KeyCode::Down,
+                "left" | "arrowleft" => KeyCode::Left,
+                "right" | "arrowright" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "insert" => KeyCode::Insert,
+                "space" => KeyCode::Char(' '),
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok().filter(|n| (1..=35).contains(n))?),
+            },
+        };
+        Some(Self { code, modifiers })
+    }
+
+    /// The stroke a key press is, letters folded to lowercase + Shift.
+    pub fn of(key: &KeyEvent) -> Self {
+        match key.code {
+            KeyCode::Char(ch) if ch.is_ascii_uppercase() => {
+                Self { code: KeyCode::Char(ch.to_ascii_lowercase()), modifiers: key.modifiers | Modifier::SHIFT }
+            }
+            ref code => Self { code: code.clone(), modifiers: key.modifiers },
+        }
+    }
+}
+
+impl fmt::Display for Stroke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in MODIFIER_ORDER {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(ch) => write!(f, "{ch}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            ref code => f.write_str(&format!("{code:?}").to_ascii_lowercase()),
+        }
+    }
+}
+
+/// Parse a key sequence: strokes separated by spaces (`"g g"`, `"Ctrl+K Ctrl+S"`).
+pub fn parse_keys(keys: &str) -> Option<Vec<Stroke>> {
+    let strokes: Option<Vec<Stroke>> = keys.split_whitespace().map(Stroke::parse).collect();
+    strokes.filter(|s| !s.is_empty())
+}
+
+/// `"ctrl+k ctrl+s"`
+fn format_keys(strokes: &[Stroke]) -> String {
+    strokes.iter().map(Stroke::to_string).collect::<Vec<_>>().join(" ")
+}
+
+/// A key sequence that doesn't parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidKeys(pub String);
+
+impl fmt::Display for InvalidKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid key sequence '{}'", self.0)
+    }
+}
+
+impl std::error::Error for InvalidKeys {}
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+/// Where a map applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeymapContext {
+    /// Everywhere.
+    Global,
+    /// The focused component is of this type (`COMPONENT_INPUT`, ...).
+    Component(u8),
+    /// Focus is inside this node (the node itself or a descendant).
+    Zone(ComponentRef),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapConflict {
+    pub context: KeymapContext,
+    pub kind: ConflictKind,
+    /// Shorter sequence's action first.
+    pub actions: [String; 2],
+    pub keys: [String; 2],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// The same sequence.
+    Duplicate,
+    /// One sequence starts the other: the shorter waits for the timeout.
+    Prefix,
+}
+
+/// Identifies a `define_keymap` call or an `on_action` handler.
+pub type KeymapId = u64;
+
+struct Binding {
+    /// The `define_keymap` call it came from (0: `rebind`)
+    map: KeymapId,
+    context: KeymapContext,
+    action: String,
+    strokes: Vec<Stroke>,
+}
+
+/// Taken out while running, so the handler may change the keymap.
+type Slot = Option<Box<dyn FnMut() -> bool>>;
+
+struct Keymap {
+    /// Definition order.
+    bindings: Vec<Binding>,
+    /// Subscription order.
+    handlers: Vec<(KeymapId, String, Slot)>,
+    next_id: KeymapId,
+    /// Strokes of the chord typed so far.
+    pending: Vec<Stroke>,
+    /// Fires the pending chord's complete binding, if it has one.
+    pending_timer: Option<TimerHandle>,
+}
+
+thread_local! {
+    static KEYMAP: RefCell<Keymap> = const {
+        RefCell::new(Keymap {
+            bindings: Vec::new(),
+            handlers: Vec::new(),
+            next_id: 1,
+            pending: Vec::new(),
+            pending_timer: None,
+        })
+    };
+}
+
+impl Keymap {
+    fn next_id(&mut self) -> KeymapId {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn clear_pending(&mut self) {
+        self.pending.clear();
+        if let Some(timer) = self.pending_timer.take() {
+            timer.cancel();
+        }
+    }
+}
+
+/// Handle returned by `define_keymap`. Dropping it keeps the bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeymapHandle {
+    id: KeymapId,
+}
+
+impl KeymapHandle {
+    /// Remove the map's bindings (those `rebind` hasn't replaced).
+    pub fn remove(&self) {
+        KEYMAP.with(|k| {
+            let mut k = k.borrow_mut();
+            k.bindings.retain(|b| b.map != self.id);
+            k.clear_pending();
+        });
+    }
+}
+
+/// Handle returned by `on_action`. Dropping it leaves the handler subscribed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionHandle {
+    id: KeymapId,
+}
+
+impl ActionHandle {
+    pub fn unsubscribe(&self) {
+        KEYMAP.with(|k| k.borrow_mut().handlers.retain(|(id, ..)| *id != self.id));
+    }
+}
+
+/// Bind `(action, keys)` pairs in `context`; an action may appear more than
+/// once for alternative keys. Nothing is bound if a sequence doesn't parse.
+/// Bindings colliding with others are kept — check `find_conflicts`.
+pub fn define_keymap(context: KeymapContext, bindings: &[(&str, &str)]) -> Result<KeymapHandle, InvalidKeys> {
+    let parsed = bindings
+        .iter()
+        .map(|&(action, keys)| Ok((action, parse_keys(keys).ok_or_else(|| InvalidKeys(keys.to_string()))?)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    KEYMAP.with(|k| {
+        let mut k = k.borrow_mut();
+        let id = k.next_id();
+        for (action, strokes) in parsed {
+            k.bindings.push(Binding { map: id, context, action: action.to_string(), strokes });
+        }
+        Ok(KeymapHandle { id })
+    })
+}
+
+/// Run `handler` when `action` is triggered. Returning false lets the key
+/// fall through to the action's next handler, then on as if unbound.
+pub fn on_action(action: &str, handler: impl FnMut() -> bool + 'static) -> ActionHandle {
+    KEYMAP.with(|k| {
+        let mut k = k.borrow_mut();
+        let id = k.next_id();
+        k.handlers.push((id, action.to_string(), Some(Box::new(handler))));
+        ActionHandle { id }
+    })
+}
+
+/// Replace the keys of `action` in `context` (None: every context it's
+/// bound in, or global if unbound). Returns the conflicts this creates.
+pub fn rebind(action: &str, keys: &[&str], context: Option<KeymapContext>) -> Result<Vec<KeymapConflict>, InvalidKeys> {
+    let sequences = keys
+        .iter()
+        .map(|&keys| parse_keys(keys).ok_or_else(|| InvalidKeys(keys.to_string())))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let contexts = KEYMAP.with(|k| {
+        let mut k = k.borrow_mut();
+        let mut contexts = Vec::new();
+        match context {
+            Some(context) => contexts.push(context),
+            None => {
+                for b in k.bindings.iter().filter(|b| b.action == action) {
+                    if !contexts.contains(&b.context) {
+                        contexts.push(b.context);
+                    }
+                }
+            }
+        }
+        if contexts.is_empty() {
+            contexts.push(KeymapContext::Global);
+        }
+
+        k.bindings.retain(|b| b.action != action || !contexts.contains(&b.context));
+        for &context in &contexts {
+            for strokes in &sequences {
+                k.bindings.push(Binding { map: 0, context, action: action.to_string(), strokes: strokes.clone() });
+            }
+        }
+        k.clear_pending();
+        contexts
+    });
+
+    Ok(contexts
+        .into_iter()
+        .flat_map(|context| find_conflicts(Some(context)))
+        .filter(|c| c.actions.iter().any(|a| a == action))
+        .collect())
+}
+
+/// Keys bound to `action`, by context.
+pub fn bindings_of(action: &str) -> Vec<(KeymapContext, String)> {
+    KEYMAP.with(|k| {
+        k.borrow().bindings.iter().filter(|b| b.action == action).map(|b| (b.context, format_keys(&b.strokes))).collect()
+    })
+}
+
+/// Sequences within a context (None: every context) that collide: the same
+/// sequence bound to different actions, or one sequence starting another.
+pub fn find_conflicts(context: Option<KeymapContext>) -> Vec<KeymapConflict> {
+    KEYMAP.with(|k| {
+        let k = k.borrow();
+        let scoped: Vec<&Binding> = k.bindings.iter().filter(|b| context.is_none_or(|c| b.context == c)).collect();
+        let mut conflicts = Vec::new();
+        for (i, a) in scoped.iter().enumerate() {
+            for b in &scoped[i + 1..] {
+                if a.context != b.context || a.action == b.action {
+                    continue;
+                }
+                let (short, long) = if a.strokes.len() <= b.strokes.len() { (a, b) } else { (b, a) };
+                if !long.strokes.starts_with(&short.strokes) {
+                    continue;
+                }
+                conflicts.push(KeymapConflict {
+                    context: a.context,
+                    kind: if short.strokes.len() == long.strokes.len() { ConflictKind::Duplicate } else { ConflictKind::Prefix },
+                    actions: [short.action.clone(), long.action.clone()],
+                    keys: [format_keys(&short.strokes), format_keys(&long.strokes)],
+                });
+            }
+        }
+        conflicts
+    })
+}
+
+/// The chord typed so far (`"ctrl+k"`), empty when none is.
+pub fn pending_keys() -> String {
+    KEYMAP.with(|k| format_keys(&k.borrow().pending))
+}
+
+/// Remove every binding and handler (engine shutdown).
+pub fn clear_all() {
+    KEYMAP.with(|k| {
+        let mut k = k.borrow_mut();
+        k.clear_pending();
+        k.bindings.clear();
+        k.handlers.clear();
+    });
+}
+
+// =============================================================================
+// Dispatch
+// =============================================================================
+
+/// Contexts that apply with `focused` focused, most specific first.
+fn active_contexts(k: &Keymap, buf: &SharedBuffer, focused: Option<usize>) -> Vec<KeymapContext> {
+    let Some(focused) = focused else {
+        return vec![KeymapContext::Global];
+    };
+    let mut contexts = Vec::new();
+    let mut zones: Vec<KeymapContext> = Vec::new();
+    for b in k.bindings.iter().filter(|b| matches!(b.context, KeymapContext::Zone(_))) {
+        if !zones.contains(&b.context) {
+            zones.push(b.context);
+        }
+    }
+    // Innermost zone first
+    let mut node = Some(focused);
+    while let Some(n) = node
+        && !zones.is_empty()
+    {
+        zones.retain(|&zone| match zone {
+            KeymapContext::Zone(r) if buf.resolve(r) == Some(n) => {
+                contexts.push(zone);
+                false
+            }
+            _ => true,
+        });
+        node = buf.parent_index(n);
+    }
+    contexts.push(KeymapContext::Component(buf.component_type(focused)));
+    contexts.push(KeymapContext::Global);
+    contexts
+}
+
+/// Bindings `sequence` starts, in the most specific context that has any.
+fn candidates<'a>(k: &'a Keymap, contexts: &[KeymapContext], sequence: &[Stroke]) -> Vec<&'a Binding> {
+    contexts
+        .iter()
+        .map(|&context| {
+            k.bindings.iter().filter(|b| b.context == context && b.strokes.starts_with(sequence)).collect::<Vec<_>>()
+        })
+        .find(|matches| !matches.is_empty())
+        .unwrap_or_default()
+}
+
+/// Run `action`'s handlers until one handles it.
+fn fire(action: &str) -> bool {
+    let ids: Vec<KeymapId> =
+        KEYMAP.with(|k| k.borrow().handlers.iter().filter(|(_, a, _)| a == action).map(|(id, ..)| *id).collect());
+
+    for id in ids {
+        // Release the borrow while running: handlers may change the keymap
+        let Some(mut handler) = KEYMAP.with(|k| {
+            k.borrow_mut().handlers.iter_mut().find(|(entry, ..)| *entry == id).and_then(|(.., h)| h.take())
+        }) else {
+            continue; // Unsubscribed by an earlier handler
+        };
+        let handled = handler();
+        KEYMAP.with(|k| {
+            // Unsubscribed by its own handler → stays unsubscribed
+            if let Some((.., slot)) = k.borrow_mut().handlers.iter_mut().find(|(entry, ..)| *entry == id) {
+                *slot = Some(handler);
+            }
+        });
+        if handled {
+            return true;
+        }
+    }
+    false
+}
+
+/// Offer a key press to the keymap, with `focused` focused. True if it was
+/// taken: it ran a handled action or is held as part of a chord.
+pub fn dispatch(buf: &SharedBuffer, focused: Option<usize>, key: &KeyEvent) -> bool {
+    let stroke = Stroke::of(key);
+    enum Outcome {
+        Unbound,
+        Fire(String),
+        Wait(Option<String>),
+    }
+
+    let outcome = KEYMAP.with(|k| {
+        let mut k = k.borrow_mut();
+        if k.bindings.is_empty() {
+            return Outcome::Unbound;
+        }
+        let contexts = active_contexts(&k, buf, focused);
+
+        let mut sequence = k.pending.clone();
+        sequence.push(stroke.clone());
+        let mut found = candidates(&k, &contexts, &sequence);
+        if found.is_empty() && !k.pending.is_empty() {
+            // Broken chord: start over from this key
+            sequence = vec![stroke];
+            found = candidates(&k, &contexts, &sequence);
+        }
+        if found.is_empty() {
+            k.clear_pending();
+            return Outcome::Unbound;
+        }
+
+        let complete = found.iter().find(|b| b.strokes.len() == sequence.len()).map(|b| b.action.clone());
+        let longer = found.iter().any(|b| b.strokes.len() > sequence.len());
+        k.clear_pending();
+        match complete {
+            Some(action) if !longer => Outcome::Fire(action),
+            complete => {
+                k.pending = sequence;
+                Outcome::Wait(complete)
+            }
+        }
+    });
+
+    match outcome {
+        Outcome::Unbound => false,
+        Outcome::Fire(action) => fire(&action),
+        Outcome::Wait(complete) => {
+            // Wait for the next key; a complete shorter binding fires if none comes
+            let timer = timers::set_timeout(buf.component_ref(0), CHORD_TIMEOUT, move || {
+                KEYMAP.with(|k| {
+                    let mut k = k.borrow_mut();
+                    k.pending.clear();
+                    k.pending_timer = None;
+                });
+                if let Some(action) = complete {
+                    fire(&action);
+                }
+            });
+            KEYMAP.with(|k| k.borrow_mut().pending_timer = Some(timer));
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::parser::KeyState;
+    use crate::shared_buffer::test_support::TestBuffer;
+    use crate::shared_buffer::{COMPONENT_BOX, COMPONENT_INPUT};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn press(keys: &str) -> KeyEvent {
+        let stroke = Stroke::parse(keys).expect("valid stroke");
+        KeyEvent { code: stroke.code, modifiers: stroke.modifiers, state: KeyState::Press }
+    }
+
+    fn counter(action: &str) -> Rc<Cell<u32>> {
+        let count = Rc::new(Cell::new(0));
+        let c = count.clone();
+        on_action(action, move || {
+            c.set(c.get() + 1);
+            true
+        });
+        count
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        assert_eq!(parse_keys("Ctrl+K  Ctrl+S").map(|s| format_keys(&s)).as_deref(), Some("ctrl+k ctrl+s"));
+        assert_eq!(Stroke::parse("Shift+Ctrl+G").map(|s| s.to_string()).as_deref(), Some("ctrl+shift+g"));
+        assert_eq!(Stroke::parse("Ctrl++").map(|s| (s.code, s.modifiers)), Some((KeyCode::Char('+'), Modifier::CTRL)));
+        assert_eq!(Stroke::parse("PageDown").map(|s| s.code), Some(KeyCode::PageDown));
+        assert_eq!(Stroke::parse("F12").map(|s| s.to_string()).as_deref(), Some("f12"));
+        assert_eq!(Stroke::parse("Hyper+a"), None);
+        assert_eq!(parse_keys("  "), None);
+
+        // An uppercase letter is typed with Shift
+        let shifted = KeyEvent { code: KeyCode::Char('G'), modifiers: Modifier::empty(), state: KeyState::Press };
+        assert_eq!(Stroke::of(&shifted), Stroke::parse("Shift+g").unwrap());
+    }
+
+    #[test]
+    fn test_chords_and_timeout() {
+        clear_all();
+        let buf = TestBuffer::new(1, 64);
+        buf.node(0, COMPONENT_BOX, -1);
+        define_keymap(KeymapContext::Global, &[("top", "g g"), ("go", "g"), ("save-all", "Ctrl+K Ctrl+S")]).unwrap();
+        let (top, go, save_all) = (counter("top"), counter("go"), counter("save-all"));
+
+        // Complete and a prefix: held, then the chord completes
+        assert!(dispatch(&buf, None, &press("g")));
+        assert_eq!(pending_keys(), "g");
+        assert!(dispatch(&buf, None, &press("g")));
+        assert_eq!((top.get(), go.get(), pending_keys().as_str()), (1, 0, ""));
+
+        // Broken chord: starts over from the breaking key, which isn't bound
+        assert!(dispatch(&buf, None, &press("Ctrl+K")));
+        assert!(!dispatch(&buf, None, &press("x")));
+        assert!(dispatch(&buf, None, &press("Ctrl+K")));
+        assert!(dispatch(&buf, None, &press("Ctrl+S")));
+        assert_eq!(save_all.get(), 1);
+
+        // Nothing continues `g`: it fires on the timeout
+        assert!(dispatch(&buf, None, &press("g")));
+        let timer = KEYMAP.with(|k| k.borrow().pending_timer).expect("timeout scheduled");
+        assert!(timer.is_active());
+        clear_all();
+        assert!(!timer.is_active());
+        assert_eq!(go.get(), 0);
+    }
+
+    #[test]
+    fn test_context_precedence_and_fall_through() {
+        clear_all();
+        // Root (0) holding zone box 1, which holds input 2
+        let buf = TestBuffer::new(3, 64);
+        buf.node(0, COMPONENT_BOX, -1);
+        buf.node(1, COMPONENT_BOX, 0);
+        buf.node(2, COMPONENT_INPUT, 1);
+        define_keymap(KeymapContext::Global, &[("quit", "q"), ("help", "?")]).unwrap();
+        let zone = define_keymap(KeymapContext::Zone(buf.component_ref(1)), &[("close-panel", "q")]).unwrap();
+        define_keymap(KeymapContext::Component(COMPONENT_INPUT), &[("submit", "Enter")]).unwrap();
+        let (quit, close, submit) = (counter("quit"), counter("close-panel"), counter("submit"));
+        on_action("help", || false);
+
+        assert!(dispatch(&buf, Some(2), &press("q")));
+        assert_eq!((close.get(), quit.get()), (1, 0));
+        assert!(dispatch(&buf, Some(2), &press("Enter")));
+        assert!(!dispatch(&buf, Some(1), &press("Enter")));
+        assert_eq!(submit.get(), 1);
+        assert!(dispatch(&buf, Some(0), &press("q")));
+        assert_eq!(quit.get(), 1);
+
+        // No handler took it: the key carries on
+        assert!(!dispatch(&buf, None, &press("?")));
+
+        zone.remove();
+        assert!(dispatch(&buf, Some(2), &press("q")));
+        assert_eq!((close.get(), quit.get()), (1, 2));
+        clear_all();
+    }
+
+    #[test]
+    fn test_rebind_and_conflicts() {
+        clear_all();
+        let buf = TestBuffer::new(1, 64);
+        buf.node(0, COMPONENT_BOX, -1);
+        define_keymap(KeymapContext::Global, &[("quit", "q"), ("save", "Ctrl+S"), ("go-top", "g g")]).unwrap();
+        let quit = counter("quit");
+        assert!(find_conflicts(None).is_empty());
+
+        let conflicts = rebind("quit", &["Ctrl+S", "g"], None).unwrap();
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts.iter().any(|c| c.kind == ConflictKind::Duplicate && c.actions == ["save", "quit"]));
+        assert!(conflicts.iter().any(|c| c.kind == ConflictKind::Prefix && c.keys == ["g", "g g"]));
+
+        rebind("quit", &["Ctrl+X"], None).unwrap();
+        assert_eq!(bindings_of("quit"), vec![(KeymapContext::Global, "ctrl+x".to_string())]);
+        assert!(!dispatch(&buf, None, &press("q")));
+        assert!(dispatch(&buf, None, &press("Ctrl+X")));
+        assert_eq!(quit.get(), 1);
+
+        assert_eq!(define_keymap(KeymapContext::Global, &[("bad", "Hyper+a")]), Err(InvalidKeys("Hyper+a".into())));
+        clear_all();
+    }
+}
@@ -0,0 +1,164 @@
+//! Layout persistence — the saved layout a run leaves for the next.
+//!
+//! [`LayoutSnapshot`] is the structure ts/state/layoutState.ts writes with
+//! `saveLayout`: component ID → key → JSON value. With the `serde` feature
+//! it derives `Serialize`/`Deserialize`, so a Rust host reads and writes the
+//! same files TS does:
+//!
+//! ```json
+//! { "version": 1, "components": { "log": { "scroll": { "x": 0, "y": 120 } } } }
+//! ```
+//!
+//! Scroll offsets (`scroll`) are the one key the engine owns: a host names
+//! its nodes and [`LayoutSnapshot::save_scroll`] / [`LayoutSnapshot::restore_scroll`]
+//! move them in and out of the buffer. Anything else (a split ratio, a
+//! column width, the collapsed nodes of a tree) is the component's own
+//! state, set and read with [`LayoutSnapshot::set`] / [`LayoutSnapshot::get`].
+
+use std::collections::BTreeMap;
+
+use crate::shared_buffer::{SharedBuffer, SCROLL_EVENTS};
+
+/// Snapshot format version; snapshots of another are ignored when restored.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Key of the scroll offset in a component's state.
+pub const SCROLL_KEY: &str = "scroll";
+
+/// What survives a JSON round trip.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(untagged))]
+pub enum LayoutValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<LayoutValue>),
+    Object(BTreeMap<String, LayoutValue>),
+}
+
+impl LayoutValue {
+    /// Field `key` of an object.
+    pub fn field(&self, key: &str) -> Option<&LayoutValue> {
+        match self {
+            Self::Object(fields) => fields.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+/// One component's remembered state, by key.
+pub type ComponentLayoutState = BTreeMap<String, LayoutValue>;
+
+/// Remembered layout of every component, by component ID.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutSnapshot {
+    pub version: u32,
+    pub components: BTreeMap<String, ComponentLayoutState>,
+}
+
+impl LayoutSnapshot {
+    pub fn new() -> Self {
+        Self { version: SNAPSHOT_VERSION, components: BTreeMap::new() }
+    }
+
+    /// Whether this is a snapshot this version restores.
+    pub fn is_current(&self) -> bool {
+        self.version == SNAPSHOT_VERSION
+    }
+
+    /// State `key` of component `id`.
+    pub fn get(&self, id: &str, key: &str) -> Option<&LayoutValue> {
+        self.components.get(id)?.get(key)
+    }
+
+    /// Remember `value` as state `key` of component `id`.
+    pub fn set(&mut self, id: &str, key: &str, value: LayoutValue) {
+        self.components.entry(id.to_string()).or_default().insert(key.to_string(), value);
+    }
+
+    /// Remember node `index`'s scroll offset as component `id`'s. Nodes
+    /// with nothing to scroll are left out, as TS leaves them.
+    pub fn save_scroll(&mut self, buf: &SharedBuffer, id: &str, index: usize) {
+        if buf.max_scroll_x(index) <= 0.0 && buf.max_scroll_y(index) <= 0.0 {
+            return;
+        }
+        let offset = BTreeMap::from([
+            ("x".to_string(), LayoutValue::Number(buf.scroll_x(index) as f64)),
+            ("y".to_string(), LayoutValue::Number(buf.scroll_y(index) as f64)),
+        ]);
+        self.set(id, SCROLL_KEY, LayoutValue::Object(offset));
+    }
+
+    /// Scroll node `index` to component `id`'s saved offset, clamped to
+    /// what it can scroll now. False when there's none to restore, or the
+    /// node isn't laid out yet (nothing to scroll): try again after layout.
+    pub fn restore_scroll(&self, buf: &SharedBuffer, id: &str, index: usize) -> bool {
+        let Some(saved) = self.get(id, SCROLL_KEY).filter(|_| self.is_current()) else {
+            return false;
+        };
+        let (max_x, max_y) = (buf.max_scroll_x(index), buf.max_scroll_y(index));
+        if max_x <= 0.0 && max_y <= 0.0 {
+            return false;
+        }
+        let axis = |key: &str, max: f32| {
+            let value = saved.field(key).and_then(LayoutValue::as_f64).unwrap_or(0.0);
+            value.clamp(0.0, max.max(0.0) as f64).round() as i32
+        };
+        let (x, y) = (axis("x", max_x), axis("y", max_y));
+        buf.set_scroll(index, x, y);
+        if buf.scroll_flags(index) & SCROLL_EVENTS != 0 {
+            buf.push_scroll_change_event(index as u16, x, y);
+        }
+        true
+    }
+}
+
+impl Default for LayoutSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_buffer::test_support::TestBuffer;
+    use crate::shared_buffer::{COMPONENT_BOX, N_MAX_SCROLL_Y};
+
+    #[test]
+    fn test_scroll_round_trips_clamped() {
+        let buf = TestBuffer::new(2, 64);
+        buf.node(0, COMPONENT_BOX, -1);
+        buf.node(1, COMPONENT_BOX, -1);
+        buf.put_f32(0, N_MAX_SCROLL_Y, 40.0);
+        buf.set_scroll(0, 0, 25);
+
+        let mut snapshot = LayoutSnapshot::new();
+        snapshot.save_scroll(&buf, "log", 0);
+        // Nothing to scroll: left out
+        snapshot.save_scroll(&buf, "label", 1);
+        assert!(snapshot.get("label", SCROLL_KEY).is_none());
+        assert_eq!(snapshot.get("log", SCROLL_KEY).and_then(|s| s.field("y")), Some(&LayoutValue::Number(25.0)));
+
+        // Next run: not laid out yet, then content shorter than before
+        buf.set_scroll(0, 0, 0);
+        buf.put_f32(0, N_MAX_SCROLL_Y, 0.0);
+        assert!(!snapshot.restore_scroll(&buf, "log", 0));
+        buf.put_f32(0, N_MAX_SCROLL_Y, 10.0);
+        assert!(snapshot.restore_scroll(&buf, "log", 0));
+        assert_eq!((buf.scroll_x(0), buf.scroll_y(0)), (0, 10));
+
+        // Another version's snapshot isn't restored
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        assert!(!snapshot.restore_scroll(&buf, "log", 0));
+    }
+}
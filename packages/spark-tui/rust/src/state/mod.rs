@@ -0,0 +1,7 @@
+//! Engine-side state helpers for components — things that live on the
+//! engine thread next to the reactive graph.
+
+pub mod frame;
+pub mod keymap;
+pub mod layout;
+pub mod timers;
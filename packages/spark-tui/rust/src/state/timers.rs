@@ -0,0 +1,345 @@
+//! Component timers — intervals and timeouts on the engine thread.
+//!
+//! ```ignore
+//! let seconds = signal(0u32);
+//! let tick = seconds.clone();
+//! timers::set_interval(buf.component_ref(index), Duration::from_secs(1), move || tick.set(tick.get() + 1));
+//! ```
+//!
+//! No thread per timer. All timers share one schedule on the engine thread;
+//! only the earliest deadline is handed to the wake thread, which parks until
+//! then and sends `StdinMessage::Timers`. The engine runs every due callback
+//! and propagates once:
+//!
+//! ```text
+//! set_interval/set_timeout ──→ schedule (engine thread)
+//!                                 │ earliest deadline
+//!                                 ▼
+//!                     wake thread: park_timeout(deadline)
+//!                                 │ StdinMessage::Timers
+//!                                 ▼
+//!                 fire: run due callbacks → generation += 1
+//! ```
+//!
+//! Coalescing keeps that to one frame per tick: intervals are phase-aligned to
+//! a common epoch (same period → same instants, so 50 one-second tickers wake
+//! the engine once a second), and everything due within `COALESCE_WINDOW` of
+//! the earliest deadline fires with it.
+//!
+//! Timers belong to a component (a `ComponentRef`, so an index TS released
+//! and reused for another component doesn't inherit them) and are dropped
+//! once it's gone, checked on every TS wake and before firing.
+//! A callback that panics takes its component's timers with it and is
+//! reported to TS as an `Error` event; the other timers keep running.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::pipeline::{terminal, wake};
+use crate::shared_buffer::{ComponentRef, ErrorSource, SharedBuffer};
+
+/// Timers due this close after the earliest one fire in the same batch.
+pub const COALESCE_WINDOW: Duration = Duration::from_millis(4);
+
+/// Shortest interval period; shorter requests are raised to it.
+const MIN_PERIOD: Duration = Duration::from_millis(1);
+
+/// Identifies a timer.
+pub type TimerId = u64;
+
+struct Timer {
+    owner: ComponentRef,
+    due: Instant,
+    /// `None` for timeouts.
+    period: Option<Duration>,
+    /// Taken out while running, so the callback may touch the schedule.
+    callback: Option<Box<dyn FnMut()>>,
+}
+
+struct Schedule {
+    timers: HashMap<TimerId, Timer>,
+    next_id: TimerId,
+    /// Phase reference for intervals.
+    epoch: Instant,
+}
+
+thread_local! {
+    static SCHEDULE: RefCell<Schedule> = RefCell::new(Schedule {
+        timers: HashMap::new(),
+        next_id: 1,
+        epoch: Instant::now(),
+    });
+}
+
+/// Handle to a timer. Dropping it leaves the timer running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerHandle {
+    id: TimerId,
+}
+
+impl TimerHandle {
+    pub fn id(&self) -> TimerId {
+        self.id
+    }
+
+    /// Whether the timer is still scheduled (false once a timeout has fired).
+    pub fn is_active(&self) -> bool {
+        SCHEDULE.with(|s| s.borrow().timers.contains_key(&self.id))
+    }
+
+    pub fn cancel(&self) {
+        SCHEDULE.with(|s| s.borrow_mut().timers.remove(&self.id));
+        rearm();
+    }
+}
+
+/// Run `callback` every `every` while component `owner` exists.
+///
+/// The first tick lands on the next multiple of `every` since the schedule's
+/// epoch (at most `every` from now), keeping equal periods in step.
+pub fn set_interval(owner: ComponentRef, every: Duration, callback: impl FnMut() + 'static) -> TimerHandle {
+    let every = every.max(MIN_PERIOD);
+    insert(owner, Some(every), Box::new(callback), |epoch, now| next_tick(epoch, now, every))
+}
+
+/// Run `callback` once after `after`, unless component `owner` is gone by then.
+pub fn set_timeout(owner: ComponentRef, after: Duration, callback: impl FnOnce() + 'static) -> TimerHandle {
+    let mut callback = Some(callback);
+    let once = move || {
+        if let Some(callback) = callback.take() {
+            callback();
+        }
+    };
+    insert(owner, None, Box::new(once), |_, now| now + after)
+}
+
+/// Cancel every timer of component `owner`.
+pub fn clear_component(owner: ComponentRef) {
+    SCHEDULE.with(|s| s.borrow_mut().timers.retain(|_, t| t.owner != owner));
+    rearm();
+}
+
+/// Cancel everything (engine shutdown).
+pub fn clear_all() {
+    SCHEDULE.with(|s| s.borrow_mut().timers.clear());
+    wake::set_timer_deadline(None);
+}
+
+/// Drop timers whose component was released. Called on TS wakes.
+pub fn sweep(buf: &SharedBuffer) {
+    let removed = SCHEDULE.with(|s| {
+        let timers = &mut s.borrow_mut().timers;
+        let before = timers.len();
+        timers.retain(|_, t| buf.is_live(t.owner));
+        before != timers.len()
+    });
+    if removed {
+        rearm();
+    }
+}
+
+/// Run every timer due at `now` (within `COALESCE_WINDOW`). Called by the
+/// engine loop on `StdinMessage::Timers`; returns whether any callback ran.
+pub fn fire(buf: &SharedBuffer, now: Instant) -> bool {
+    fire_with(
+        now,
+        |owner| buf.is_live(owner),
+        |owner| buf.push_error_event(Some(owner.index), ErrorSource::Timer),
+    )
+}
+
+fn fire_with(now: Instant, alive: impl Fn(ComponentRef) -> bool, mut panicked: impl FnMut(ComponentRef)) -> bool {
+    let horizon = now + COALESCE_WINDOW;
+    let mut due: Vec<(Instant, TimerId)> = SCHEDULE.with(|s| {
+        let mut s = s.borrow_mut();
+        s.timers.retain(|_, t| alive(t.owner));
+        s.timers.iter().filter(|(_, t)| t.due <= horizon).map(|(&id, t)| (t.due, id)).collect()
+    });
+    // Deadline order, registration order on ties
+    due.sort_unstable();
+
+    let mut ran = false;
+    for (_, id) in due {
+        // Release the borrow while running: callbacks may add or cancel timers
+        let Some((owner, mut callback)) = SCHEDULE.with(|s| {
+            s.borrow_mut().timers.get_mut(&id).and_then(|t| Some((t.owner, t.callback.take()?)))
+        }) else {
+            continue; // Cancelled by an earlier callback
+        };
+        ran = true;
+        if terminal::contained(&mut callback).is_err() {
+            clear_component(owner);
+            panicked(owner);
+            continue;
+        }
+
+        SCHEDULE.with(|s| {
+            let mut s = s.borrow_mut();
+            let epoch = s.epoch;
+            // Removed by its own callback → stays removed
+            let Some(timer) = s.timers.get_mut(&id) else { return };
+            match timer.period {
+                Some(every) => {
+                    timer.callback = Some(callback);
+                    // Skip missed ticks rather than firing a burst
+                    timer.due = next_tick(epoch, timer.due.max(now), every);
+                }
+                None => {
+                    s.timers.remove(&id);
+                }
+            }
+        });
+    }
+
+    rearm();
+    ran
+}
+
+fn insert(
+    owner: ComponentRef,
+    period: Option<Duration>,
+    callback: Box<dyn FnMut()>,
+    due: impl FnOnce(Instant, Instant) -> Instant,
+) -> TimerHandle {
+    let id = SCHEDULE.with(|s| {
+        let mut s = s.borrow_mut();
+        let id = s.next_id;
+        s.next_id += 1;
+        let due = due(s.epoch, Instant::now());
+        s.timers.insert(id, Timer { owner, due, period, callback: Some(callback) });
+        id
+    });
+    rearm();
+    TimerHandle { id }
+}
+
+/// First multiple of `every` since `epoch` strictly after `after`.
+fn next_tick(epoch: Instant, after: Instant, every: Duration) -> Instant {
+    let elapsed = after.saturating_duration_since(epoch).as_nanos();
+    let period = every.as_nanos();
+    let ticks = elapsed / period + 1;
+    epoch + Duration::from_nanos((ticks * period).min(u64::MAX as u128) as u64)
+}
+
+/// Hand the earliest deadline to the wake thread.
+fn rearm() {
+    let earliest = SCHEDULE.with(|s| s.borrow().timers.values().map(|t| t.due).min());
+    wake::set_timer_deadline(earliest);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn owner(index: usize) -> ComponentRef {
+        ComponentRef { index, generation: 0 }
+    }
+
+    fn counter() -> (Rc<Cell<u32>>, impl FnMut() + 'static) {
+        let count = Rc::new(Cell::new(0));
+        let c = count.clone();
+        (count, move || c.set(c.get() + 1))
+    }
+
+    fn due_of(handle: TimerHandle) -> Instant {
+        SCHEDULE.with(|s| s.borrow().timers[&handle.id()].due)
+    }
+
+    #[test]
+    fn test_intervals_share_ticks() {
+        let (count, tick) = counter();
+        let a = set_interval(owner(1), Duration::from_secs(1), tick);
+        let (_, other) = counter();
+        let b = set_interval(owner(2), Duration::from_secs(1), other);
+        // Same period → same instants, wherever they were created
+        assert_eq!(due_of(a), due_of(b));
+
+        let first = due_of(a);
+        assert!(fire_with(first, |_| true, |_| {}));
+        assert_eq!(count.get(), 1);
+        assert_eq!(due_of(a), first + Duration::from_secs(1));
+
+        // A late batch skips missed ticks instead of bursting
+        let late = first + Duration::from_millis(3500);
+        assert!(fire_with(late, |_| true, |_| {}));
+        assert_eq!(count.get(), 2);
+        assert_eq!(due_of(a), first + Duration::from_secs(4));
+        clear_all();
+    }
+
+    #[test]
+    fn test_timeouts_fire_once_and_follow_component() {
+        let (count, tick) = counter();
+        let timeout = set_timeout(owner(3), Duration::ZERO, tick);
+        let (gone_count, gone) = counter();
+        let orphan = set_interval(owner(4), Duration::from_millis(10), gone);
+
+        let later = Instant::now() + Duration::from_millis(20);
+        assert!(fire_with(later, |owner| owner.index != 4, |_| {}));
+        assert_eq!(count.get(), 1);
+        assert!(!timeout.is_active());
+        // Component 4 was released → its interval is dropped without running
+        assert_eq!(gone_count.get(), 0);
+        assert!(!orphan.is_active());
+        assert!(!fire_with(later, |_| true, |_| {}));
+    }
+
+    #[test]
+    fn test_callbacks_can_reschedule_and_cancel() {
+        let (count, tick) = counter();
+        let interval = set_interval(owner(5), Duration::from_millis(10), tick);
+        let handle = Rc::new(Cell::new(None::<TimerHandle>));
+        let own = handle.clone();
+        handle.set(Some(set_interval(owner(6), Duration::from_millis(10), move || {
+            own.get().unwrap().cancel();
+            set_timeout(owner(6), Duration::ZERO, || {});
+        })));
+
+        assert!(fire_with(due_of(interval), |_| true, |_| {}));
+        assert_eq!(count.get(), 1);
+        assert!(!handle.get().unwrap().is_active());
+        assert!(interval.is_active());
+        clear_component(owner(5));
+        assert!(!interval.is_active());
+        clear_all();
+    }
+
+    #[test]
+    fn test_panicking_callback_drops_its_component() {
+        let (count, tick) = counter();
+        let healthy = set_interval(owner(7), Duration::from_millis(10), tick);
+        let broken = set_interval(owner(8), Duration::from_millis(10), || panic!("timer callback"));
+        let sibling = set_timeout(owner(8), Duration::from_secs(60), || {});
+
+        let failed = Rc::new(Cell::new(None));
+        let report = failed.clone();
+        assert!(fire_with(due_of(healthy), |_| true, move |owner| report.set(Some(owner.index))));
+        assert_eq!(failed.get(), Some(8));
+        assert_eq!(count.get(), 1);
+        assert!(healthy.is_active());
+        assert!(!broken.is_active() && !sibling.is_active());
+        clear_all();
+    }
+
+    #[test]
+    fn test_reused_index_does_not_inherit_timers() {
+        use crate::shared_buffer::test_support::TestBuffer;
+        use crate::shared_buffer::{COMPONENT_BOX, N_GENERATION};
+
+        let buf = TestBuffer::new(2, 64);
+        buf.node(0, COMPONENT_BOX, -1);
+        buf.node(1, COMPONENT_BOX, 0);
+        let (count, tick) = counter();
+        let interval = set_interval(buf.component_ref(1), Duration::from_millis(10), tick);
+
+        // TS released index 1 and gave it to another component before any wake
+        buf.put_u32(1, N_GENERATION, 1);
+        assert!(!fire(&buf, due_of(interval)));
+        assert_eq!(count.get(), 0);
+        assert!(!interval.is_active());
+        clear_all();
+    }
+}
@@ -0,0 +1,232 @@
+//! Async tasks — background work that resolves into signal updates.
+//!
+//! Signals live on the engine thread and are not `Send`, so a future can't
+//! touch them directly. [`spawn`] splits the job in two:
+//!
+//! ```text
+//! engine thread                     worker thread
+//! ─────────────                     ─────────────
+//! spawn(future, apply) ───────────→ block_on(future)
+//!   apply kept here                    │
+//!                                      ▼
+//! engine channel ←─── TaskDone(id, value)
+//!   → apply(value)       (sets signals)
+//!   → generation += 1    (reactive propagation, as for input)
+//! ```
+//!
+//! ```ignore
+//! let status = signal(String::from("loading…"));
+//! let target = status.clone();
+//! task::spawn(async move { fetch_status().await }, move |s| target.set(s));
+//! ```
+//!
+//! Each task gets its own worker thread that parks until the future's waker
+//! fires — nothing polls. Futures needing a specific runtime (tokio, …) must
+//! be driven by that runtime and hand their result over the same way.
+//!
+//! [`spawn`] must be called on the engine thread (from deriveds, effects or
+//! input handlers), where the pipeline installs the [`TaskBridge`].
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use crate::input::reader::StdinMessage;
+
+/// Identifies a spawned task.
+pub type TaskId = u64;
+
+type Apply = Box<dyn FnOnce(Box<dyn Any + Send>)>;
+
+thread_local! {
+    /// Channel into the engine loop, while a bridge is installed.
+    static SENDER: RefCell<Option<Sender<StdinMessage>>> = const { RefCell::new(None) };
+    /// Completion handlers of tasks still running.
+    static PENDING: RefCell<HashMap<TaskId, Apply>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<TaskId> = const { Cell::new(1) };
+}
+
+/// Installs the engine channel for [`spawn`] on this thread; uninstalls on drop.
+pub struct TaskBridge(());
+
+impl TaskBridge {
+    pub fn install(tx: Sender<StdinMessage>) -> Self {
+        SENDER.with(|s| *s.borrow_mut() = Some(tx));
+        Self(())
+    }
+}
+
+impl Drop for TaskBridge {
+    fn drop(&mut self) {
+        // Drop the sender so the channel can disconnect, and any handlers
+        SENDER.with(|s| s.borrow_mut().take());
+        PENDING.with(|p| p.borrow_mut().clear());
+    }
+}
+
+/// Handle to a spawned task. Dropping it detaches the task; it still applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskHandle {
+    id: TaskId,
+}
+
+impl TaskHandle {
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Whether the result has not been applied yet (false once cancelled).
+    pub fn is_pending(&self) -> bool {
+        PENDING.with(|p| p.borrow().contains_key(&self.id))
+    }
+
+    /// Discard the result when it arrives. The worker runs to completion.
+    pub fn cancel(&self) {
+        PENDING.with(|p| p.borrow_mut().remove(&self.id));
+    }
+}
+
+/// Run `future` on a worker thread, then `apply` its output on the engine
+/// thread and propagate.
+///
+/// # Panics
+///
+/// If called off the engine thread (no [`TaskBridge`] installed).
+pub fn spawn<F, T>(future: F, apply: impl FnOnce(T) + 'static) -> TaskHandle
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let tx = SENDER
+        .with(|s| s.borrow().clone())
+        .expect("task::spawn called outside the engine thread");
+    let id = NEXT_ID.with(|n| n.replace(n.get() + 1));
+
+    PENDING.with(|p| {
+        p.borrow_mut().insert(
+            id,
+            Box::new(move |value: Box<dyn Any + Send>| {
+                if let Ok(value) = value.downcast::<T>() {
+                    apply(*value);
+                }
+            }),
+        )
+    });
+
+    let spawned = thread::Builder::new()
+        .name(format!("spark-task-{id}"))
+        .spawn(move || {
+            let value = block_on(future);
+            // Engine gone → nobody to apply it
+            let _ = tx.send(StdinMessage::TaskDone(id, Box::new(value)));
+        });
+    if spawned.is_err() {
+        PENDING.with(|p| p.borrow_mut().remove(&id));
+    }
+
+    TaskHandle { id }
+}
+
+/// Apply a finished task's result. Called by the engine loop on `TaskDone`;
+/// returns false if the task was cancelled.
+pub fn complete(id: TaskId, value: Box<dyn Any + Send>) -> bool {
+    // Release the borrow first: apply may spawn more tasks
+    let Some(apply) = PENDING.with(|p| p.borrow_mut().remove(&id)) else {
+        return false;
+    };
+    apply(value);
+    true
+}
+
+/// Unparks the worker when the future can make progress.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Drive `future` to completion on the current thread, parked while pending.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(value) => return value,
+            // Spurious unparks just poll again
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use std::sync::mpsc;
+    use std::sync::Mutex;
+
+    /// Resolves once another thread delivers a value.
+    struct Handoff(Arc<Mutex<(Option<u32>, Option<Waker>)>>);
+
+    impl Future for Handoff {
+        type Output = u32;
+        fn poll(self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+            let mut slot = self.0.lock().unwrap();
+            match slot.0.take() {
+                Some(value) => Poll::Ready(value),
+                None => {
+                    slot.1 = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_block_on_wakes_from_another_thread() {
+        let slot: Arc<Mutex<(Option<u32>, Option<Waker>)>> = Arc::new(Mutex::new((None, None)));
+        let producer = slot.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(std::time::Duration::from_millis(10));
+            let mut slot = producer.lock().unwrap();
+            slot.0 = Some(7);
+            if let Some(waker) = slot.1.take() {
+                waker.wake();
+            }
+        });
+        assert_eq!(block_on(Handoff(slot)), 7);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_spawn_applies_on_engine_thread() {
+        let (tx, rx) = mpsc::channel();
+        let _bridge = TaskBridge::install(tx);
+        let result = Rc::new(Cell::new(0));
+
+        let target = result.clone();
+        let task = spawn(async { 20 + 22 }, move |v: i32| target.set(v));
+        let cancelled = spawn(async { 1 }, |_: i32| panic!("cancelled task applied"));
+        cancelled.cancel();
+
+        for _ in 0..2 {
+            let Ok(StdinMessage::TaskDone(id, value)) = rx.recv() else { panic!("expected TaskDone") };
+            assert_eq!(complete(id, value), id == task.id());
+        }
+        assert_eq!(result.get(), 42);
+        assert!(!task.is_pending());
+    }
+}
@@ -16,7 +16,7 @@
 /// Special markers:
 /// - r=-1 (or 255 after unpack): Terminal default
 /// - r=-2 (or 254 after unpack): ANSI palette, index in g
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct Rgba {
     pub r: i16,
     pub g: i16,
@@ -180,6 +180,23 @@ impl Rgba {
             a: self.a,
         }
     }
+
+    /// Linear interpolation from `a` (t = 0.0) to `b` (t = 1.0).
+    /// Special colors can't be mixed, so they switch over at the midpoint.
+    #[inline]
+    pub fn lerp(a: Self, b: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        if a.is_terminal_default() || a.is_ansi() || b.is_terminal_default() || b.is_ansi() {
+            return if t < 0.5 { a } else { b };
+        }
+        let mix = |x: i16, y: i16| (x as f32 + (y - x) as f32 * t).round() as i16;
+        Self {
+            r: mix(a.r, b.r),
+            g: mix(a.g, b.g),
+            b: mix(a.b, b.b),
+            a: mix(a.a, b.a),
+        }
+    }
 }
 
 // =============================================================================
@@ -222,6 +239,8 @@ pub struct Cell {
     pub bg: Rgba,
     /// Attribute flags (bold, italic, etc.).
     pub attrs: Attr,
+    /// Hyperlink id in the frame's link table, 0 = none.
+    pub link: u16,
 }
 
 impl Default for Cell {
@@ -231,10 +250,39 @@ impl Default for Cell {
             fg: Rgba::TERMINAL_DEFAULT,
             bg: Rgba::TERMINAL_DEFAULT,
             attrs: Attr::NONE,
+            link: 0,
         }
     }
 }
 
+// =============================================================================
+// Control characters - never reach a cell raw
+// =============================================================================
+
+/// Offset from a C0 control to its Control Pictures glyph (U+2400).
+const PICTURES_BASE: u32 = 0x2400;
+/// Control Pictures glyph for DEL.
+const PICTURE_DEL: char = '\u{2421}';
+/// C1 controls have no picture.
+const PICTURE_C1: char = '\u{FFFD}';
+
+/// Whether `ch` is a C0/C1 control or DEL.
+#[inline]
+pub fn is_control(ch: char) -> bool {
+    matches!(ch as u32, 0..=0x1F | 0x7F..=0x9F)
+}
+
+/// Single visible character standing in for a control (`Pictures` form).
+/// Non-controls are returned unchanged.
+pub fn control_picture(ch: char) -> char {
+    match ch as u32 {
+        cp @ 0..=0x1F => char::from_u32(PICTURES_BASE + cp).unwrap_or(PICTURE_C1),
+        0x7F => PICTURE_DEL,
+        0x80..=0x9F => PICTURE_C1,
+        _ => ch,
+    }
+}
+
 // =============================================================================
 // ClipRect - For overflow handling
 // =============================================================================
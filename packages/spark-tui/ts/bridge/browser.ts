@@ -0,0 +1,144 @@
+/**
+ * Browser bridge — the Rust engine compiled to wasm, drawing into xterm.js.
+ *
+ * The engine runs as in a terminal, with the page standing in for the TTY:
+ * frames arrive at `write` (feed them to `term.write`), keys and resizes go
+ * in through `attachTerminal`. The SharedBuffer lives in the module's
+ * shared memory, so the usual bridge and primitives work on it unchanged:
+ *
+ * ```ts
+ * const engine = await loadBrowserEngine(wasmBytes, { imports: wasiImports, write: (b) => term.write(b) })
+ * const size = calculateBufferSize()
+ * const byteOffset = engine.alloc(size)
+ * initBridge({ memory: { buffer: engine.memory.buffer as SharedArrayBuffer, byteOffset }, wakeFn: engine.wake })
+ * scoped(() => app())
+ * engine.init(byteOffset, size, term.cols, term.rows)
+ * attachTerminal(engine, term)
+ * ```
+ *
+ * Build the module with `bun run build:wasm` (wasm32-wasip1-threads: the
+ * engine, input and event threads are workers sharing one memory). The
+ * WASI and thread-spawn imports come from the host's WASI shim. `write`
+ * runs on the engine's thread; inside a worker, post the bytes to the page.
+ */
+
+/** Exports of the wasm engine module used here. */
+interface EngineExports {
+  memory: WebAssembly.Memory
+  spark_browser_alloc(len: number): number
+  spark_browser_free(ptr: number, len: number): void
+  spark_browser_init(ptr: number, len: number, width: number, height: number): number
+  spark_browser_input(ptr: number, len: number): number
+  spark_browser_resize(width: number, height: number): void
+  spark_wake(): void
+  spark_wait_for_events(): void
+}
+
+export interface BrowserEngine {
+  /** The module's shared memory, holding the SharedBuffer. */
+  memory: WebAssembly.Memory
+  /** Allocate zeroed engine memory for the SharedBuffer. Returns its offset. */
+  alloc(len: number): number
+  /** Free memory from `alloc` (`len` as allocated). Not the buffer of a started engine, which keeps using it. */
+  free(ptr: number, len: number): void
+  /** Start the engine on the buffer at `bufferPtr`. Returns 0 on success. */
+  init(bufferPtr: number, bufferLen: number, width: number, height: number): number
+  /** Hand the engine input typed into the emulator. */
+  input(data: string | Uint8Array): void
+  /** The emulator was resized, in cells. */
+  resize(width: number, height: number): void
+  /** Wake the engine (after writing props to the SharedBuffer). */
+  wake(): void
+  /** Block until the engine has events ready. Only in a worker. */
+  waitForEvents(): void
+}
+
+export interface BrowserEngineOptions {
+  /** Receives each frame's ANSI output (e.g. `term.write`). */
+  write: (bytes: Uint8Array) => void
+  /** Other imports the module needs: WASI, thread spawn, shared memory. */
+  imports?: WebAssembly.Imports
+}
+
+/** The part of an xterm.js `Terminal` the engine talks to. */
+export interface TerminalLike {
+  cols: number
+  rows: number
+  onData(listener: (data: string) => void): { dispose(): void }
+  onResize(listener: (size: { cols: number; rows: number }) => void): { dispose(): void }
+}
+
+const encoder = new TextEncoder()
+
+/** Initial input staging size: room for a paste of ordinary length. */
+const INPUT_STAGING_SIZE = 4096
+
+/** Instantiate the wasm engine. */
+export async function loadBrowserEngine(
+  source: BufferSource | WebAssembly.Module,
+  options: BrowserEngineOptions,
+): Promise<BrowserEngine> {
+  let exports: EngineExports | null = null
+  const imports: WebAssembly.Imports = {
+    ...options.imports,
+    env: {
+      ...options.imports?.env,
+      spark_host_write(ptr: number, len: number) {
+        // Copied out: the engine reuses its output buffer
+        options.write(new Uint8Array(exports!.memory.buffer, ptr, len).slice())
+      },
+    },
+  }
+  const instance = source instanceof WebAssembly.Module
+    ? await WebAssembly.instantiate(source, imports)
+    : (await WebAssembly.instantiate(source, imports)).instance
+  const lib = instance.exports as unknown as EngineExports
+  exports = lib
+
+  // Staging area for input, grown as needed (the engine copies out of it)
+  let staging = { ptr: 0, len: 0 }
+
+  return {
+    memory: lib.memory,
+    alloc(len) {
+      return lib.spark_browser_alloc(len)
+    },
+    free(ptr, len) {
+      lib.spark_browser_free(ptr, len)
+    },
+    init(bufferPtr, bufferLen, width, height) {
+      return lib.spark_browser_init(bufferPtr, bufferLen, width, height)
+    },
+    input(data) {
+      const bytes = typeof data === 'string' ? encoder.encode(data) : data
+      if (!bytes.length) return
+      if (bytes.length > staging.len) {
+        // The engine copied the last input out already: the old area can go
+        if (staging.len > 0) lib.spark_browser_free(staging.ptr, staging.len)
+        const len = Math.max(bytes.length, INPUT_STAGING_SIZE)
+        staging = { ptr: lib.spark_browser_alloc(len), len }
+      }
+      new Uint8Array(lib.memory.buffer, staging.ptr, bytes.length).set(bytes)
+      lib.spark_browser_input(staging.ptr, bytes.length)
+    },
+    resize(width, height) {
+      lib.spark_browser_resize(width, height)
+    },
+    wake() {
+      lib.spark_wake()
+    },
+    waitForEvents() {
+      lib.spark_wait_for_events()
+    },
+  }
+}
+
+/** Route an emulator's input and resizes to the engine. Returns a detach function. */
+export function attachTerminal(engine: BrowserEngine, term: TerminalLike): () => void {
+  const data = term.onData((input) => engine.input(input))
+  const resize = term.onResize(({ cols, rows }) => engine.resize(cols, rows))
+  return () => {
+    data.dispose()
+    resize.dispose()
+  }
+}
@@ -45,6 +45,54 @@ const symbols = {
     args: [] as const,
     returns: FFIType.void,
   },
+  spark_record_cast: {
+    args: [FFIType.ptr, FFIType.u32] as const,
+    returns: FFIType.u32,
+  },
+  spark_set_title: {
+    args: [FFIType.ptr, FFIType.u32] as const,
+    returns: FFIType.u32,
+  },
+  spark_bell: {
+    args: [] as const,
+    returns: FFIType.void,
+  },
+  spark_notify: {
+    args: [FFIType.u32, FFIType.ptr, FFIType.u32, FFIType.ptr, FFIType.u32] as const,
+    returns: FFIType.u32,
+  },
+  spark_println: {
+    args: [FFIType.ptr, FFIType.u32] as const,
+    returns: FFIType.u32,
+  },
+  spark_session_start: {
+    args: [FFIType.ptr, FFIType.u32, FFIType.i32, FFIType.i32, FFIType.u32, FFIType.u32] as const,
+    returns: FFIType.u32,
+  },
+  spark_session_wake: {
+    args: [FFIType.u32] as const,
+    returns: FFIType.void,
+  },
+  spark_session_wait_for_events: {
+    args: [FFIType.u32] as const,
+    returns: FFIType.void,
+  },
+  spark_session_resize: {
+    args: [FFIType.u32, FFIType.u32, FFIType.u32] as const,
+    returns: FFIType.void,
+  },
+  spark_session_stop: {
+    args: [FFIType.u32] as const,
+    returns: FFIType.void,
+  },
+  spark_session_record_cast: {
+    args: [FFIType.u32, FFIType.ptr, FFIType.u32] as const,
+    returns: FFIType.u32,
+  },
+  spark_session_println: {
+    args: [FFIType.u32, FFIType.ptr, FFIType.u32] as const,
+    returns: FFIType.u32,
+  },
 } as const
 
 export interface SparkEngine {
@@ -58,10 +106,59 @@ export interface SparkEngine {
   waitForEvents(): void
   /** Stop the engine and clean up terminal. */
   cleanup(): void
+  /**
+   * Record the terminal output to an asciinema v2 cast at `path`, or stop
+   * recording with null. Returns 0 on success.
+   */
+  recordCast(path: string | null): number
+  /** Set the window title (restored when the engine stops). Returns 0 on success. */
+  setTitle(title: string): number
+  /** Ring the terminal bell. */
+  bell(): void
+  /**
+   * Post a desktop notification (protocol 0 = OSC 9, 1 = OSC 777; the title
+   * may be empty). Returns 0 on success.
+   */
+  notify(protocol: number, title: string, body: string): number
+  /**
+   * Freeze a styled line, encoded by `encodeStyledLine`, into history above
+   * the live region (append mode only). Returns 0 on success.
+   */
+  println(line: Uint8Array): number
+  /**
+   * Start an engine for a remote terminal (SSH/telnet connection) on its own
+   * SharedArrayBuffer, reading input from `inputFd` and drawing to
+   * `outputFd` (unix only; the session takes ownership of both fds).
+   * Returns the session id, or 0 if it couldn't start.
+   */
+  startSession(bufferPtr: ReturnType<typeof ptr>, bufferLen: number, inputFd: number, outputFd: number, width: number, height: number): number
+  /** Wake a session's engine (its `wake()`). */
+  wakeSession(id: number): void
+  /** Block until a session's engine has events ready (its `waitForEvents()`). */
+  waitForSessionEvents(id: number): void
+  /** Forward the client's new terminal size to a session. */
+  resizeSession(id: number, width: number, height: number): void
+  /** Stop a session and restore its client's terminal. */
+  stopSession(id: number): void
+  /** Record a session's output to a cast (its `recordCast()`). */
+  recordSessionCast(id: number, path: string | null): number
+  /** Freeze a line into a session's history (its `println()`). */
+  printlnSession(id: number, line: Uint8Array): number
   /** Close the library. */
   close(): void
 }
 
+/** UTF-8 path for the cast FFI calls; null (stop) for null or empty. */
+function castPath(path: string | null): Uint8Array | null {
+  return path ? new TextEncoder().encode(path) : null
+}
+
+/** UTF-8 bytes and length for the string FFI calls (null for empty). */
+function utf8(text: string): [ReturnType<typeof ptr> | null, number] {
+  const bytes = new TextEncoder().encode(text)
+  return bytes.length ? [ptr(bytes), bytes.length] : [null, 0]
+}
+
 /**
  * Load the Rust engine library.
  *
@@ -87,6 +184,44 @@ export function loadEngine(libPath?: string): SparkEngine {
     cleanup() {
       lib.symbols.spark_cleanup()
     },
+    recordCast(path) {
+      const bytes = castPath(path)
+      return lib.symbols.spark_record_cast(bytes ? ptr(bytes) : null, bytes?.length ?? 0)
+    },
+    setTitle(title) {
+      return lib.symbols.spark_set_title(...utf8(title))
+    },
+    bell() {
+      lib.symbols.spark_bell()
+    },
+    notify(protocol, title, body) {
+      return lib.symbols.spark_notify(protocol, ...utf8(title), ...utf8(body))
+    },
+    println(line) {
+      return lib.symbols.spark_println(line.length ? ptr(line) : null, line.length)
+    },
+    startSession(bufferPtr, bufferLen, inputFd, outputFd, width, height) {
+      return lib.symbols.spark_session_start(bufferPtr, bufferLen, inputFd, outputFd, width, height)
+    },
+    wakeSession(id) {
+      lib.symbols.spark_session_wake(id)
+    },
+    waitForSessionEvents(id) {
+      lib.symbols.spark_session_wait_for_events(id)
+    },
+    resizeSession(id, width, height) {
+      lib.symbols.spark_session_resize(id, width, height)
+    },
+    stopSession(id) {
+      lib.symbols.spark_session_stop(id)
+    },
+    recordSessionCast(id, path) {
+      const bytes = castPath(path)
+      return lib.symbols.spark_session_record_cast(id, bytes ? ptr(bytes) : null, bytes?.length ?? 0)
+    },
+    printlnSession(id, line) {
+      return lib.symbols.spark_session_println(id, line.length ? ptr(line) : null, line.length)
+    },
     close() {
       lib.close()
     },
@@ -3,20 +3,38 @@
  *
  * Creates the SharedArrayBuffer, reactive arrays, and wake notifier.
  * Call initBridge() once at startup. Primitives import getBuffer()/getArrays().
+ *
+ * A `Bridge` is one UI instance's memory: the Rust side runs an engine per
+ * buffer (`spark_init` for the process terminal, `spark_session_start` for
+ * each remote one). initBridge() sets up the default instance; createBridge()
+ * makes an independent one. The accessors answer for the active instance:
+ * the one withBridge() is running code for, else the default.
+ *
+ * Code that runs later, outside any withBridge() (a timer, a resolved
+ * promise), sees the default instance, so capture getBridge() at setup
+ * when it must reach another one.
  */
 
-import { createSharedBuffer, type SharedBuffer, DEFAULT_MAX_NODES } from './shared-buffer'
+import { createSharedBuffer, type SharedBuffer, type SharedBufferConfig, DEFAULT_MAX_NODES } from './shared-buffer'
 import { createReactiveArrays, type ReactiveArrays } from './reactive-arrays'
 import { createNoopNotifier, createFFINotifier } from './notify'
 import type { Notifier } from '@rlabs-inc/signals'
 
 // =============================================================================
-// Singleton state
+// Default instance
 // =============================================================================
 
-let _buffer: SharedBuffer | null = null
-let _arrays: ReactiveArrays | null = null
-let _notifier: Notifier | null = null
+/** One instance's shared buffer, reactive arrays and wake notifier. */
+export interface Bridge {
+  buffer: SharedBuffer
+  arrays: ReactiveArrays
+  notifier: Notifier
+}
+
+let _default: Bridge | null = null
+
+/** Instance withBridge() is running code for */
+let _active: Bridge | null = null
 
 // =============================================================================
 // Init
@@ -29,79 +47,124 @@ export interface BridgeOptions {
   maxNodes?: number
   /** Text pool size in bytes (default: 10MB) */
   textPoolSize?: number
+  /** Existing memory to place the buffer in (a wasm engine's, see `browser`) */
+  memory?: SharedBufferConfig['memory']
   /** FFI wake function (engine.wake). Required when not using noopNotifier. */
   wakeFn?: () => void
 }
 
-/** Check if bridge is initialized. */
+/** Check if a bridge is active (withBridge) or the default is initialized. */
 export function isInitialized(): boolean {
-  return _buffer !== null
+  return (_active ?? _default) !== null
 }
 
 /**
- * Initialize the shared memory bridge.
- *
- * Creates SharedArrayBuffer + reactive slot buffers + notifier.
- * Safe to call multiple times — returns existing state after first init.
+ * Create an independent bridge — its own SharedArrayBuffer, reactive slot
+ * buffers and notifier. `wakeFn` should wake the engine drawing this buffer.
  */
-export function initBridge(opts?: BridgeOptions): {
-  buffer: SharedBuffer
-  arrays: ReactiveArrays
-  notifier: Notifier
-} {
-  if (_buffer) {
-    return { buffer: _buffer, arrays: _arrays!, notifier: _notifier! }
-  }
-
-  _buffer = createSharedBuffer({
+export function createBridge(opts?: BridgeOptions): Bridge {
+  const buffer = createSharedBuffer({
     maxNodes: opts?.maxNodes,
     textPoolSize: opts?.textPoolSize,
+    memory: opts?.memory,
   })
 
+  let notifier: Notifier
   if (opts?.noopNotifier) {
-    _notifier = createNoopNotifier()
+    notifier = createNoopNotifier()
   } else if (opts?.wakeFn) {
-    _notifier = createFFINotifier(_buffer, opts.wakeFn)
+    notifier = createFFINotifier(buffer, opts.wakeFn)
   } else {
     throw new Error('wakeFn is required when not using noopNotifier')
   }
 
-  _arrays = createReactiveArrays(_buffer, _notifier)
+  return { buffer, arrays: createReactiveArrays(buffer, notifier), notifier }
+}
 
-  return { buffer: _buffer, arrays: _arrays, notifier: _notifier }
+/**
+ * Initialize the default shared memory bridge.
+ *
+ * Creates SharedArrayBuffer + reactive slot buffers + notifier.
+ * Safe to call multiple times — returns existing state after first init.
+ */
+export function initBridge(opts?: BridgeOptions): Bridge {
+  if (!_default) {
+    _default = createBridge(opts)
+  }
+  return _default
 }
 
 // =============================================================================
 // Accessors
 // =============================================================================
 
+/** Get the active bridge. Throws if not initialized. */
+export function getBridge(): Bridge {
+  const bridge = _active ?? _default
+  if (!bridge) throw new Error('Bridge not initialized — call initBridge() first')
+  return bridge
+}
+
 /** Get the shared buffer. Throws if not initialized. */
 export function getBuffer(): SharedBuffer {
-  if (!_buffer) throw new Error('Bridge not initialized — call initBridge() first')
-  return _buffer
+  return getBridge().buffer
 }
 
 /** Get the reactive arrays. Throws if not initialized. */
 export function getArrays(): ReactiveArrays {
-  if (!_arrays) throw new Error('Bridge not initialized — call initBridge() first')
-  return _arrays
+  return getBridge().arrays
 }
 
 /** Get the notifier. Throws if not initialized. */
 export function getNotifier(): Notifier {
-  if (!_notifier) throw new Error('Bridge not initialized — call initBridge() first')
-  return _notifier
+  return getBridge().notifier
+}
+
+// =============================================================================
+// Instances
+// =============================================================================
+
+/**
+ * Run `fn` with `bridge` active: the accessors, and state made with
+ * bridgeState(), answer for it. Nests; the previous one is restored after.
+ */
+export function withBridge<T>(bridge: Bridge, fn: () => T): T {
+  const previous = _active
+  _active = bridge
+  try {
+    return fn()
+  } finally {
+    _active = previous
+  }
+}
+
+/**
+ * Module state kept per instance (a component registry, handler maps):
+ * the returned getter gives the active bridge's own, made by `create` on
+ * first use. Before any bridge exists it gives a detached one.
+ */
+export function bridgeState<T>(create: () => T): () => T {
+  const states = new WeakMap<Bridge, T>()
+  let detached: T | undefined
+  return () => {
+    const bridge = _active ?? _default
+    if (!bridge) return (detached ??= create())
+    let state = states.get(bridge)
+    if (state === undefined) {
+      state = create()
+      states.set(bridge, state)
+    }
+    return state
+  }
 }
 
 // =============================================================================
 // Reset (for testing)
 // =============================================================================
 
-/** Reset bridge state. For testing only. */
+/** Drop the default bridge. For testing only. */
 export function resetBridge(): void {
-  _buffer = null
-  _arrays = null
-  _notifier = null
+  _default = null
 }
 
 // =============================================================================
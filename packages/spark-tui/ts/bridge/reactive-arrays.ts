@@ -15,8 +15,10 @@ import { createSlotBuffer } from './slot-buffer'
 import {
   // === Cache Line 1 (0-63): Core Layout Dimensions ===
   N_WIDTH, N_HEIGHT, N_MIN_WIDTH, N_MIN_HEIGHT, N_MAX_WIDTH, N_MAX_HEIGHT,
-  N_ASPECT_RATIO, N_COMPONENT_TYPE, N_DISPLAY, N_POSITION, N_OVERFLOW,
+  N_ASPECT_RATIO, N_COMPONENT_TYPE, N_DISPLAY, N_POSITION, N_OVERFLOW, N_OVERFLOW_X, N_OVERFLOW_Y,
   N_VISIBLE, N_BOX_SIZING, N_DIRTY_FLAGS,
+  N_WIDTH_BASIS, N_HEIGHT_BASIS, N_MIN_WIDTH_BASIS, N_MIN_HEIGHT_BASIS, N_MAX_WIDTH_BASIS, N_MAX_HEIGHT_BASIS, N_ASPECT_CELLS,
+  N_RELATIVE_TO,
 
   // === Cache Line 2 (64-127): Flexbox Properties ===
   N_FLEX_DIRECTION, N_FLEX_WRAP, N_JUSTIFY_CONTENT, N_ALIGN_ITEMS,
@@ -36,7 +38,7 @@ import {
   N_GRID_AUTO_COLUMNS_VALUE, N_GRID_AUTO_ROWS_VALUE,
   N_GRID_COLUMN_START, N_GRID_COLUMN_END, N_GRID_ROW_START, N_GRID_ROW_END,
   N_JUSTIFY_SELF,
-  N_FIRST_CHILD, N_PREV_SIBLING, N_NEXT_SIBLING,
+  N_FIRST_CHILD, N_PREV_SIBLING, N_NEXT_SIBLING, N_SLOT_ANCHOR, N_SLOT_POSITION,
 
   // === Cache Lines 5-10: Grid Tracks ===
   // Handled via setGridColumnTracks()/setGridRowTracks() - not as slot buffers
@@ -53,11 +55,13 @@ import {
   N_BORDER_CHAR_H, N_BORDER_CHAR_V,
   N_BORDER_CHAR_TL, N_BORDER_CHAR_TR, N_BORDER_CHAR_BL, N_BORDER_CHAR_BR,
   N_FOCUS_INDICATOR_CHAR, N_FOCUS_INDICATOR_ENABLED,
+  N_BG_FILL, N_BG_FILL_SIZE, N_BG_FILL_ANGLE, N_BORDER_INNER_STYLE, N_RENDER_CACHE,
 
   // === Cache Line 13 (768-831): Colors ===
   N_FG_COLOR, N_BG_COLOR, N_BORDER_COLOR,
   N_BORDER_TOP_COLOR, N_BORDER_RIGHT_COLOR, N_BORDER_BOTTOM_COLOR, N_BORDER_LEFT_COLOR,
   N_FOCUS_RING_COLOR, N_CURSOR_FG_COLOR, N_CURSOR_BG_COLOR, N_SELECTION_COLOR,
+  N_BG_COLOR_2, N_BORDER_INNER_COLOR, N_BORDER_FRAME_FILL,
 
   // === Cache Line 14 (832-895): Text Properties ===
   N_TEXT_OFFSET, N_TEXT_LENGTH, N_TEXT_ALIGN, N_TEXT_WRAP, N_TEXT_OVERFLOW,
@@ -68,7 +72,11 @@ import {
   N_SCROLL_X, N_SCROLL_Y, N_CURSOR_POSITION, N_SELECTION_START, N_SELECTION_END,
   N_CURSOR_CHAR, N_CURSOR_ALT_CHAR,
   N_INTERACTION_FLAGS, N_CURSOR_FLAGS, N_CURSOR_STYLE, N_CURSOR_BLINK_RATE,
-  N_MAX_LENGTH, N_INPUT_TYPE,
+  N_MAX_LENGTH, N_INPUT_TYPE, N_HOVER_TAG, N_A11Y_ROLE, N_A11Y_LIVE, N_DIRECTION,
+  N_POINTER_EVENTS,
+  N_STICKY,
+  N_SMOOTH_SCROLL, N_SCROLL_FLAGS, N_REACH_END_THRESHOLD,
+  DIRTY_LAYOUT, DIRTY_HIERARCHY,
 } from './shared-buffer'
 
 // =============================================================================
@@ -88,9 +96,19 @@ export interface ReactiveArrays {
   display: SharedSlotBuffer            // u8 @ 29
   position: SharedSlotBuffer           // u8 @ 30
   overflow: SharedSlotBuffer           // u8 @ 31
+  overflowX: SharedSlotBuffer          // u8 @ 35
+  overflowY: SharedSlotBuffer          // u8 @ 36
   visible: SharedSlotBuffer            // u8 @ 32
   boxSizing: SharedSlotBuffer          // u8 @ 33
   dirtyFlags: SharedSlotBuffer         // u8 @ 34
+  widthBasis: SharedSlotBuffer         // u8 @ 37
+  heightBasis: SharedSlotBuffer        // u8 @ 38
+  minWidthBasis: SharedSlotBuffer      // u8 @ 39
+  minHeightBasis: SharedSlotBuffer     // u8 @ 40
+  maxWidthBasis: SharedSlotBuffer      // u8 @ 41
+  maxHeightBasis: SharedSlotBuffer     // u8 @ 42
+  aspectCells: SharedSlotBuffer        // u8 @ 43
+  relativeTo: SharedSlotBuffer         // i32 @ 44
 
   // === Cache Line 2: Flexbox Properties ===
   flexDirection: SharedSlotBuffer      // u8 @ 64
@@ -145,6 +163,8 @@ export interface ReactiveArrays {
   firstChild: SharedSlotBuffer         // i32 @ 220
   prevSibling: SharedSlotBuffer        // i32 @ 224
   nextSibling: SharedSlotBuffer        // i32 @ 228
+  slotAnchor: SharedSlotBuffer         // i32 @ 232
+  slotPosition: SharedSlotBuffer       // i32 @ 236
 
   // === Cache Line 11: Computed Output ===
   computedX: SharedSlotBuffer          // f32 @ 640
@@ -174,6 +194,11 @@ export interface ReactiveArrays {
   borderCharBR: SharedSlotBuffer       // u16 @ 728
   focusIndicatorChar: SharedSlotBuffer // u8 @ 730
   focusIndicatorEnabled: SharedSlotBuffer // u8 @ 731
+  bgFill: SharedSlotBuffer             // u8 @ 732
+  bgFillSize: SharedSlotBuffer         // u8 @ 733
+  bgFillAngle: SharedSlotBuffer        // u16 @ 734
+  borderInnerStyle: SharedSlotBuffer   // u8 @ 736
+  renderCache: SharedSlotBuffer        // u8 @ 737
 
   // === Cache Line 13: Colors ===
   fgColor: SharedSlotBuffer            // u32 @ 768
@@ -187,6 +212,9 @@ export interface ReactiveArrays {
   cursorFgColor: SharedSlotBuffer      // u32 @ 800
   cursorBgColor: SharedSlotBuffer      // u32 @ 804
   selectionColor: SharedSlotBuffer     // u32 @ 808
+  bgColor2: SharedSlotBuffer           // u32 @ 812
+  borderInnerColor: SharedSlotBuffer   // u32 @ 816
+  borderFrameFill: SharedSlotBuffer    // u32 @ 820
 
   // === Cache Line 14: Text Properties ===
   textOffset: SharedSlotBuffer         // u32 @ 832
@@ -216,6 +244,17 @@ export interface ReactiveArrays {
   cursorBlinkRate: SharedSlotBuffer    // u8 @ 927
   maxLength: SharedSlotBuffer          // u8 @ 928
   inputType: SharedSlotBuffer          // u8 @ 929
+  hoverTag: SharedSlotBuffer           // u32 @ 932
+  a11yRole: SharedSlotBuffer           // u8 @ 945
+  a11yLive: SharedSlotBuffer           // u8 @ 946
+  direction: SharedSlotBuffer          // u8 @ 956
+  pointerEvents: SharedSlotBuffer      // u8 @ 957
+  sticky: SharedSlotBuffer             // u8 @ 958
+
+  // === Cache Line 16: Animation ===
+  smoothScroll: SharedSlotBuffer       // u8 @ 976
+  scrollFlags: SharedSlotBuffer        // u8 @ 978
+  reachEndThreshold: SharedSlotBuffer  // u8 @ 979
 }
 
 // =============================================================================
@@ -228,84 +267,97 @@ export function createReactiveArrays(
 ): ReactiveArrays {
   const v = buf.view
 
-  // Type-specific slot buffer creators
-  const f32 = (offset: number) => createSlotBuffer(v, offset, 'f32', notifier)
-  const u32 = (offset: number) => createSlotBuffer(v, offset, 'u32', notifier)
-  const i32 = (offset: number) => createSlotBuffer(v, offset, 'i32', notifier)
-  const u16 = (offset: number) => createSlotBuffer(v, offset, 'u16', notifier)
-  const i16 = (offset: number) => createSlotBuffer(v, offset, 'i16', notifier)
-  const u8 = (offset: number) => createSlotBuffer(v, offset, 'u8', notifier)
+  // Type-specific slot buffer creators; `marks` flag the node on every write
+  // (fields layout reads: DIRTY_LAYOUT, or DIRTY_HIERARCHY for the tree's shape)
+  const f32 = (offset: number, marks = 0) => createSlotBuffer(v, offset, 'f32', notifier, 0, marks)
+  const u32 = (offset: number, marks = 0) => createSlotBuffer(v, offset, 'u32', notifier, 0, marks)
+  const i32 = (offset: number, marks = 0) => createSlotBuffer(v, offset, 'i32', notifier, 0, marks)
+  const u16 = (offset: number, marks = 0) => createSlotBuffer(v, offset, 'u16', notifier, 0, marks)
+  const i16 = (offset: number, marks = 0) => createSlotBuffer(v, offset, 'i16', notifier, 0, marks)
+  const u8 = (offset: number, marks = 0) => createSlotBuffer(v, offset, 'u8', notifier, 0, marks)
 
   return {
     // === Cache Line 1: Core Layout Dimensions ===
-    width: f32(N_WIDTH),
-    height: f32(N_HEIGHT),
-    minWidth: f32(N_MIN_WIDTH),
-    minHeight: f32(N_MIN_HEIGHT),
-    maxWidth: f32(N_MAX_WIDTH),
-    maxHeight: f32(N_MAX_HEIGHT),
-    aspectRatio: f32(N_ASPECT_RATIO),
-    componentType: u8(N_COMPONENT_TYPE),
-    display: u8(N_DISPLAY),
-    position: u8(N_POSITION),
-    overflow: u8(N_OVERFLOW),
-    visible: u8(N_VISIBLE),
-    boxSizing: u8(N_BOX_SIZING),
+    width: f32(N_WIDTH, DIRTY_LAYOUT),
+    height: f32(N_HEIGHT, DIRTY_LAYOUT),
+    minWidth: f32(N_MIN_WIDTH, DIRTY_LAYOUT),
+    minHeight: f32(N_MIN_HEIGHT, DIRTY_LAYOUT),
+    maxWidth: f32(N_MAX_WIDTH, DIRTY_LAYOUT),
+    maxHeight: f32(N_MAX_HEIGHT, DIRTY_LAYOUT),
+    aspectRatio: f32(N_ASPECT_RATIO, DIRTY_LAYOUT),
+    componentType: u8(N_COMPONENT_TYPE, DIRTY_HIERARCHY),
+    display: u8(N_DISPLAY, DIRTY_LAYOUT),
+    position: u8(N_POSITION, DIRTY_LAYOUT),
+    overflow: u8(N_OVERFLOW, DIRTY_LAYOUT),
+    overflowX: u8(N_OVERFLOW_X, DIRTY_LAYOUT),
+    overflowY: u8(N_OVERFLOW_Y, DIRTY_LAYOUT),
+    visible: u8(N_VISIBLE, DIRTY_LAYOUT),
+    boxSizing: u8(N_BOX_SIZING, DIRTY_LAYOUT),
     dirtyFlags: u8(N_DIRTY_FLAGS),
+    widthBasis: u8(N_WIDTH_BASIS, DIRTY_LAYOUT),
+    heightBasis: u8(N_HEIGHT_BASIS, DIRTY_LAYOUT),
+    minWidthBasis: u8(N_MIN_WIDTH_BASIS, DIRTY_LAYOUT),
+    minHeightBasis: u8(N_MIN_HEIGHT_BASIS, DIRTY_LAYOUT),
+    maxWidthBasis: u8(N_MAX_WIDTH_BASIS, DIRTY_LAYOUT),
+    maxHeightBasis: u8(N_MAX_HEIGHT_BASIS, DIRTY_LAYOUT),
+    aspectCells: u8(N_ASPECT_CELLS, DIRTY_LAYOUT),
+    relativeTo: i32(N_RELATIVE_TO, DIRTY_LAYOUT),
 
     // === Cache Line 2: Flexbox Properties ===
-    flexDirection: u8(N_FLEX_DIRECTION),
-    flexWrap: u8(N_FLEX_WRAP),
-    justifyContent: u8(N_JUSTIFY_CONTENT),
-    alignItems: u8(N_ALIGN_ITEMS),
-    alignContent: u8(N_ALIGN_CONTENT),
-    alignSelf: u8(N_ALIGN_SELF),
-    flexGrow: f32(N_FLEX_GROW),
-    flexShrink: f32(N_FLEX_SHRINK),
-    flexBasis: f32(N_FLEX_BASIS),
-    gap: f32(N_GAP),
-    rowGap: f32(N_ROW_GAP),
-    columnGap: f32(N_COLUMN_GAP),
+    flexDirection: u8(N_FLEX_DIRECTION, DIRTY_LAYOUT),
+    flexWrap: u8(N_FLEX_WRAP, DIRTY_LAYOUT),
+    justifyContent: u8(N_JUSTIFY_CONTENT, DIRTY_LAYOUT),
+    alignItems: u8(N_ALIGN_ITEMS, DIRTY_LAYOUT),
+    alignContent: u8(N_ALIGN_CONTENT, DIRTY_LAYOUT),
+    alignSelf: u8(N_ALIGN_SELF, DIRTY_LAYOUT),
+    flexGrow: f32(N_FLEX_GROW, DIRTY_LAYOUT),
+    flexShrink: f32(N_FLEX_SHRINK, DIRTY_LAYOUT),
+    flexBasis: f32(N_FLEX_BASIS, DIRTY_LAYOUT),
+    gap: f32(N_GAP, DIRTY_LAYOUT),
+    rowGap: f32(N_ROW_GAP, DIRTY_LAYOUT),
+    columnGap: f32(N_COLUMN_GAP, DIRTY_LAYOUT),
 
     // === Cache Line 3: Spacing Properties ===
-    paddingTop: f32(N_PADDING_TOP),
-    paddingRight: f32(N_PADDING_RIGHT),
-    paddingBottom: f32(N_PADDING_BOTTOM),
-    paddingLeft: f32(N_PADDING_LEFT),
-    marginTop: f32(N_MARGIN_TOP),
-    marginRight: f32(N_MARGIN_RIGHT),
-    marginBottom: f32(N_MARGIN_BOTTOM),
-    marginLeft: f32(N_MARGIN_LEFT),
-    insetTop: f32(N_INSET_TOP),
-    insetRight: f32(N_INSET_RIGHT),
-    insetBottom: f32(N_INSET_BOTTOM),
-    insetLeft: f32(N_INSET_LEFT),
-    borderWidthTop: u8(N_BORDER_WIDTH_TOP),
-    borderWidthRight: u8(N_BORDER_WIDTH_RIGHT),
-    borderWidthBottom: u8(N_BORDER_WIDTH_BOTTOM),
-    borderWidthLeft: u8(N_BORDER_WIDTH_LEFT),
-    parentIndex: i32(N_PARENT_INDEX),
+    paddingTop: f32(N_PADDING_TOP, DIRTY_LAYOUT),
+    paddingRight: f32(N_PADDING_RIGHT, DIRTY_LAYOUT),
+    paddingBottom: f32(N_PADDING_BOTTOM, DIRTY_LAYOUT),
+    paddingLeft: f32(N_PADDING_LEFT, DIRTY_LAYOUT),
+    marginTop: f32(N_MARGIN_TOP, DIRTY_LAYOUT),
+    marginRight: f32(N_MARGIN_RIGHT, DIRTY_LAYOUT),
+    marginBottom: f32(N_MARGIN_BOTTOM, DIRTY_LAYOUT),
+    marginLeft: f32(N_MARGIN_LEFT, DIRTY_LAYOUT),
+    insetTop: f32(N_INSET_TOP, DIRTY_LAYOUT),
+    insetRight: f32(N_INSET_RIGHT, DIRTY_LAYOUT),
+    insetBottom: f32(N_INSET_BOTTOM, DIRTY_LAYOUT),
+    insetLeft: f32(N_INSET_LEFT, DIRTY_LAYOUT),
+    borderWidthTop: u8(N_BORDER_WIDTH_TOP, DIRTY_LAYOUT),
+    borderWidthRight: u8(N_BORDER_WIDTH_RIGHT, DIRTY_LAYOUT),
+    borderWidthBottom: u8(N_BORDER_WIDTH_BOTTOM, DIRTY_LAYOUT),
+    borderWidthLeft: u8(N_BORDER_WIDTH_LEFT, DIRTY_LAYOUT),
+    parentIndex: i32(N_PARENT_INDEX, DIRTY_HIERARCHY),
     tabIndex: i32(N_TAB_INDEX),
 
     // === Cache Line 4: Grid Container Properties ===
-    gridAutoFlow: u8(N_GRID_AUTO_FLOW),
-    justifyItems: u8(N_JUSTIFY_ITEMS),
-    gridColumnCount: u8(N_GRID_COLUMN_COUNT),
-    gridRowCount: u8(N_GRID_ROW_COUNT),
-    gridAutoColumnsType: u8(N_GRID_AUTO_COLUMNS_TYPE),
-    gridAutoRowsType: u8(N_GRID_AUTO_ROWS_TYPE),
-    gridAutoColumnsValue: f32(N_GRID_AUTO_COLUMNS_VALUE),
-    gridAutoRowsValue: f32(N_GRID_AUTO_ROWS_VALUE),
-    gridColumnStart: i16(N_GRID_COLUMN_START),
-    gridColumnEnd: i16(N_GRID_COLUMN_END),
-    gridRowStart: i16(N_GRID_ROW_START),
-    gridRowEnd: i16(N_GRID_ROW_END),
-    justifySelf: u8(N_JUSTIFY_SELF),
+    gridAutoFlow: u8(N_GRID_AUTO_FLOW, DIRTY_LAYOUT),
+    justifyItems: u8(N_JUSTIFY_ITEMS, DIRTY_LAYOUT),
+    gridColumnCount: u8(N_GRID_COLUMN_COUNT, DIRTY_LAYOUT),
+    gridRowCount: u8(N_GRID_ROW_COUNT, DIRTY_LAYOUT),
+    gridAutoColumnsType: u8(N_GRID_AUTO_COLUMNS_TYPE, DIRTY_LAYOUT),
+    gridAutoRowsType: u8(N_GRID_AUTO_ROWS_TYPE, DIRTY_LAYOUT),
+    gridAutoColumnsValue: f32(N_GRID_AUTO_COLUMNS_VALUE, DIRTY_LAYOUT),
+    gridAutoRowsValue: f32(N_GRID_AUTO_ROWS_VALUE, DIRTY_LAYOUT),
+    gridColumnStart: i16(N_GRID_COLUMN_START, DIRTY_LAYOUT),
+    gridColumnEnd: i16(N_GRID_COLUMN_END, DIRTY_LAYOUT),
+    gridRowStart: i16(N_GRID_ROW_START, DIRTY_LAYOUT),
+    gridRowEnd: i16(N_GRID_ROW_END, DIRTY_LAYOUT),
+    justifySelf: u8(N_JUSTIFY_SELF, DIRTY_LAYOUT),
 
     // === Hierarchy Linked List ===
     firstChild: i32(N_FIRST_CHILD),
     prevSibling: i32(N_PREV_SIBLING),
     nextSibling: i32(N_NEXT_SIBLING),
+    slotAnchor: i32(N_SLOT_ANCHOR, DIRTY_HIERARCHY),
+    slotPosition: i32(N_SLOT_POSITION, DIRTY_HIERARCHY),
 
     // === Cache Line 11: Computed Output ===
     computedX: f32(N_COMPUTED_X),
@@ -335,6 +387,11 @@ export function createReactiveArrays(
     borderCharBR: u16(N_BORDER_CHAR_BR),
     focusIndicatorChar: u8(N_FOCUS_INDICATOR_CHAR),
     focusIndicatorEnabled: u8(N_FOCUS_INDICATOR_ENABLED),
+    bgFill: u8(N_BG_FILL),
+    bgFillSize: u8(N_BG_FILL_SIZE),
+    bgFillAngle: u16(N_BG_FILL_ANGLE),
+    borderInnerStyle: u8(N_BORDER_INNER_STYLE, DIRTY_LAYOUT),
+    renderCache: u8(N_RENDER_CACHE),
 
     // === Cache Line 13: Colors ===
     fgColor: u32(N_FG_COLOR),
@@ -348,12 +405,15 @@ export function createReactiveArrays(
     cursorFgColor: u32(N_CURSOR_FG_COLOR),
     cursorBgColor: u32(N_CURSOR_BG_COLOR),
     selectionColor: u32(N_SELECTION_COLOR),
+    bgColor2: u32(N_BG_COLOR_2),
+    borderInnerColor: u32(N_BORDER_INNER_COLOR),
+    borderFrameFill: u32(N_BORDER_FRAME_FILL),
 
     // === Cache Line 14: Text Properties ===
     textOffset: u32(N_TEXT_OFFSET),
     textLength: u32(N_TEXT_LENGTH),
     textAlign: u8(N_TEXT_ALIGN),
-    textWrap: u8(N_TEXT_WRAP),
+    textWrap: u8(N_TEXT_WRAP, DIRTY_LAYOUT),
     textOverflow: u8(N_TEXT_OVERFLOW),
     textAttrs: u8(N_TEXT_ATTRS),
     textDecoration: u8(N_TEXT_DECORATION),
@@ -377,5 +437,16 @@ export function createReactiveArrays(
     cursorBlinkRate: u8(N_CURSOR_BLINK_RATE),
     maxLength: u8(N_MAX_LENGTH),
     inputType: u8(N_INPUT_TYPE),
+    hoverTag: u32(N_HOVER_TAG),
+    a11yRole: u8(N_A11Y_ROLE),
+    a11yLive: u8(N_A11Y_LIVE),
+    direction: u8(N_DIRECTION, DIRTY_LAYOUT),
+    pointerEvents: u8(N_POINTER_EVENTS),
+    sticky: u8(N_STICKY),
+
+    // === Cache Line 16: Animation ===
+    smoothScroll: u8(N_SMOOTH_SCROLL),
+    scrollFlags: u8(N_SCROLL_FLAGS),
+    reachEndThreshold: u8(N_REACH_END_THRESHOLD),
   }
 }
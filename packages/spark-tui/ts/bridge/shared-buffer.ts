@@ -30,6 +30,16 @@ export const DEFAULT_MAX_NODES = 10_000;
 /** Default text pool size (10 MB) */
 export const DEFAULT_TEXT_POOL_SIZE = 10 * 1024 * 1024;
 
+/** Text slots are allocated in multiples of this, so small growth (9 → 10) stays in place */
+export const TEXT_SLOT_ALIGN = 8;
+
+/**
+ * A pool write that carries the write pointer past this percentage of the
+ * pool compacts first — once per crossing, since compaction brings the
+ * pointer back under it. Past the mark, the pool compacts only when full.
+ */
+export const TEXT_POOL_HIGH_WATER_PERCENT = 75;
+
 /** Event ring header size */
 export const EVENT_RING_HEADER_SIZE = 12;
 
@@ -64,12 +74,36 @@ export const H_TERMINAL_HEIGHT = 16;
 export const H_GENERATION = 20;
 export const H_TEXT_POOL_SIZE = 24;
 export const H_TEXT_POOL_WRITE_PTR = 28;
-// 32-63: reserved
+// Input-to-paint latency over the last LATENCY_WINDOW samples (Rust writes, TS reads; u32 μs)
+export const H_INPUT_LATENCY_P50_US = 32;
+export const H_INPUT_LATENCY_P95_US = 36;
+export const H_INPUT_LATENCY_P99_US = 40;
+export const H_INPUT_LATENCY_LAST_US = 44;
+export const H_INPUT_LATENCY_COUNT = 48;      // Samples recorded since start (u32)
+// Terminal capabilities (Rust writes, TS reads) — re-evaluated on resize / focus-in
+export const H_CAPABILITIES_SEQ = 52;         // Bumped on every capability change (u32)
+export const H_CELL_PIXEL_WIDTH = 56;         // Cell size in pixels, 0 = unknown (u16)
+export const H_CELL_PIXEL_HEIGHT = 58;        // (u16)
+export const H_COLOR_DEPTH = 60;              // (u8, ColorDepth)
+export const H_UNICODE_LEVEL = 61;            // (u8, UnicodeLevel)
+export const H_MOUSE_AVAILABLE = 62;          // 1 when mouse events are reported (u8)
+export const H_TEXT_POOL_LOCK = 63;           // 1 while TS writes the text pool or Rust uses it (u8, CAS 0 → 1)
 
 // --- Bytes 64-95: Wake & Sync (4-byte aligned for Atomics) ---
 export const H_WAKE_RUST = 64;
 export const H_WAKE_TS = 68;
-// 72-95: reserved
+export const H_BATCH_WINDOW_US = 72;          // Min time between frames, 0 = off (u32 μs, TS writes)
+// Last frame's terminal output (Rust writes, TS reads)
+export const H_FRAME_CELLS = 76;              // Cells written (u32)
+export const H_FRAME_BYTES = 80;              // Bytes written (u32)
+// Performance HUD
+export const H_PERF_HUD_KEY = 84;             // Toggle key, key-event keycode encoding (u32, 0 = off)
+export const H_PERF_HUD_MODIFIERS = 88;       // Modifiers that must be held, parser bits (u8)
+export const H_PERF_HUD = 89;                 // 1 while the HUD shows (u8, TS or the toggle key sets)
+// Component inspector
+export const H_INSPECTOR = 90;                // 1 while the inspector is open (u8, TS or the toggle key sets)
+export const H_INSPECTOR_MODIFIERS = 91;      // Modifiers that must be held, parser bits (u8)
+export const H_INSPECTOR_KEY = 92;            // Toggle key, key-event keycode encoding (u32, 0 = off)
 
 // --- Bytes 96-127: State (Rust writes, TS reads) ---
 export const H_FOCUSED_INDEX = 96;
@@ -77,20 +111,63 @@ export const H_HOVERED_INDEX = 100;
 export const H_PRESSED_INDEX = 104;
 export const H_MOUSE_X = 108;
 export const H_MOUSE_Y = 110;
-// 112-127: reserved
+export const H_WATCHDOG_TRIP_COUNT = 112;       // Update storms broken by the watchdog (u32)
+export const H_WATCHDOG_NODES = 116;            // Offending node indices (4 × u16, 0xFFFF = empty)
+export const H_WATCHDOG_ACTIVE = 124;           // 1 while a storm is being suppressed (u8)
+// 125-127: reserved
+
+/** Number of offending node slots in the watchdog table */
+export const WATCHDOG_NODE_SLOTS = 4;
+
+/** Empty slot marker in the watchdog node table */
+export const WATCHDOG_NODE_NONE = 0xffff;
 
 // --- Bytes 128-159: Config (TS writes, Rust reads) ---
 export const H_CONFIG_FLAGS = 128;
 export const H_RENDER_MODE = 132;
 export const H_CURSOR_CONFIG = 136;
 export const H_SCROLL_SPEED = 140;
-// 144-159: reserved
+export const H_COLOR_VISION = 144;            // Color-vision simulation overlay (u8, ColorVision)
+export const H_CONTROL_CHARS = 145;           // How control characters in text are shown (u8, ControlChars)
+export const H_KEYMAP_PRESET = 146;           // Framework navigation/editing keys (u8, KeymapPreset)
+export const H_LAYOUT_EVENTS = 147;           // 1 = push LayoutChange after layouts that moved nodes (u8, TS observers)
+export const H_SCREENSHOT_KEY = 148;          // Screenshot key, key-event keycode encoding (u32, 0 = off)
+export const H_SCREENSHOT_MODIFIERS = 152;    // Modifiers that must be held, parser bits (u8)
+export const H_SCREENSHOT_FLAGS = 153;        // SCREENSHOT_* option bits (u8)
+export const H_SCREENSHOT_TOAST = 154;        // 1 while the confirmation toast shows (u8, Rust sets, TS clears)
+export const H_DEBUG_LAYOUT = 155;            // 1 = outline boxes, hatch padding, tint gaps (u8)
+export const H_KEYMAP_OVERRIDES = 156;        // NavAction bits the TS keymap handles instead (u16)
+export const H_TEXT_POOL_COMPACTIONS = 158;   // Bumped by whichever side compacts the text pool (u16, wraps)
+
+/** Screenshot option: copy the saved path to the clipboard (OSC 52) */
+export const SCREENSHOT_COPY_PATH = 1 << 0;
+/** Screenshot option: also save a `.png` image */
+export const SCREENSHOT_IMAGE = 1 << 1;
+/** Screenshot option: put the PNG on the clipboard where the terminal supports it (implies SCREENSHOT_IMAGE) */
+export const SCREENSHOT_COPY_IMAGE = 1 << 2;
+
+/** Modifier bits as the Rust key parser reports them (H_SCREENSHOT_MODIFIERS) */
+export const PARSER_MOD_SHIFT = 1 << 0;
+export const PARSER_MOD_ALT = 1 << 1;
+export const PARSER_MOD_CTRL = 1 << 2;
 
 // --- Bytes 160-191: Events ---
 export const H_EVENT_WRITE_IDX = 160;
 export const H_EVENT_READ_IDX = 164;
 export const H_EXIT_REQUESTED = 168;
-// 169-191: reserved
+// Inline frame height bounds (TS writes, Rust reads) — the content height, clamped
+export const H_INLINE_MIN_HEIGHT = 169;         // Rows the frame takes at least, 0 = none (u8)
+export const H_INLINE_MAX_HEIGHT = 170;         // Rows the frame takes at most, 0 = unbounded (u16)
+// Cell shape (TS writes, Rust reads) — height ÷ width for N_ASPECT_CELLS, × CELL_ASPECT_SCALE
+export const H_CELL_ASPECT = 171;               // 0 = measured from the cell pixel size, else 2.0 (u8)
+// Hover payload table (Rust writes, TS reads) — nearest hovered node with a hover tag
+export const H_HOVER_SEQ = 172;                 // Bumped on every payload change (u32)
+export const H_HOVER_PAYLOAD_INDEX = 176;       // Tagged node under the mouse, -1 = none (i32)
+export const H_HOVER_TAG = 180;                 // That node's N_HOVER_TAG (u32)
+export const H_HOVER_REGION_X = 184;            // Its hit region on screen (u16 × 4)
+export const H_HOVER_REGION_Y = 186;
+export const H_HOVER_REGION_W = 188;
+export const H_HOVER_REGION_H = 190;
 
 // --- Bytes 192-255: Stats & Debug ---
 export const H_RENDER_COUNT = 192;
@@ -133,8 +210,18 @@ export const N_OVERFLOW = 31;
 export const N_VISIBLE = 32;
 export const N_BOX_SIZING = 33;
 export const N_DIRTY_FLAGS = 34;
-// 35: reserved
-// 36-63: reserved
+export const N_OVERFLOW_X = 35;  // Overflow + 1 per axis; 0 = N_OVERFLOW
+export const N_OVERFLOW_Y = 36;
+// What each % dimension is a percentage of (PercentBasis)
+export const N_WIDTH_BASIS = 37;
+export const N_HEIGHT_BASIS = 38;
+export const N_MIN_WIDTH_BASIS = 39;
+export const N_MIN_HEIGHT_BASIS = 40;
+export const N_MAX_WIDTH_BASIS = 41;
+export const N_MAX_HEIGHT_BASIS = 42;
+export const N_ASPECT_CELLS = 43; // aspect_ratio is in pixels, corrected for the cell shape (u8 bool)
+export const N_RELATIVE_TO = 44; // Ancestor PercentBasis.Named sizes against (i32, -1 = none)
+// 48-63: reserved
 
 // --- Cache Line 2 (64-127): Flexbox Properties ---
 export const N_FLEX_DIRECTION = 64;
@@ -192,7 +279,12 @@ export const N_JUSTIFY_SELF = 216;
 export const N_FIRST_CHILD = 220;
 export const N_PREV_SIBLING = 224;
 export const N_NEXT_SIBLING = 228;
-// 232-255: reserved
+export const N_SLOT_ANCHOR = 232;          // Keyed lists: sort among siblings at this index + 1, 0 = own index (i32)
+export const N_SLOT_POSITION = 236;        // Order within that slot (i32)
+export const N_CREATION_SEQ = 240;         // Allocation sequence, survives index reuse (u32)
+export const N_GENERATION = 244;           // Bumped each time the index is released, survives reuse (u32)
+export const N_REGION = 248;               // Inline/append region a root draws into, stacked in ascending order (u8)
+// 249-255: reserved
 
 // --- Cache Lines 5-7 (256-447): Grid Column Tracks ---
 // 32 tracks × 6 bytes each = 192 bytes
@@ -231,7 +323,12 @@ export const N_BORDER_CHAR_BL = 726;
 export const N_BORDER_CHAR_BR = 728;
 export const N_FOCUS_INDICATOR_CHAR = 730;
 export const N_FOCUS_INDICATOR_ENABLED = 731;
-// 732-767: reserved
+export const N_BG_FILL = 732;                 // BackgroundFill kind (u8)
+export const N_BG_FILL_SIZE = 733;            // Pattern cell size in cells, 0 = 1 (u8)
+export const N_BG_FILL_ANGLE = 734;           // Gradient direction in degrees, 0 = left→right (u16)
+export const N_BORDER_INNER_STYLE = 736;      // Framed border inner line, 0 = single-line border (u8)
+export const N_RENDER_CACHE = 737;            // Reuse the subtree's rendered cells while unchanged, 0 = off (u8)
+// 738-767: reserved
 
 // --- Cache Line 13 (768-831): Colors ---
 export const N_FG_COLOR = 768;
@@ -245,7 +342,10 @@ export const N_FOCUS_RING_COLOR = 796;
 export const N_CURSOR_FG_COLOR = 800;
 export const N_CURSOR_BG_COLOR = 804;
 export const N_SELECTION_COLOR = 808;
-// 812-831: reserved
+export const N_BG_COLOR_2 = 812;              // Second background fill color (u32)
+export const N_BORDER_INNER_COLOR = 816;      // Framed border inner line color, 0 = border color (u32)
+export const N_BORDER_FRAME_FILL = 820;       // Background of the framed border band, 0 = none (u32)
+// 824-831: reserved
 
 // --- Cache Line 14 (832-895): Text Properties ---
 export const N_TEXT_OFFSET = 832;
@@ -261,7 +361,15 @@ export const N_TEXT_DECORATION_COLOR = 848;
 export const N_LINE_HEIGHT = 852;
 export const N_LETTER_SPACING = 853;
 export const N_MAX_LINES = 854;
-// 855-895: reserved
+// 855: reserved (alignment)
+export const N_TEXT_CAPACITY = 856;           // Bytes allocated for the text slot, ≥ length (u32)
+export const N_HREF_OFFSET = 860;             // Hyperlink target in text pool (u32)
+export const N_HREF_LENGTH = 864;             // Target byte length, 0 = not a link (u32)
+export const N_A11Y_LABEL_OFFSET = 868;       // Accessible name in text pool (u32)
+export const N_A11Y_LABEL_LENGTH = 872;       // Name byte length, 0 = none (u32)
+export const N_A11Y_VALUE_OFFSET = 876;       // Accessible value in text pool (u32)
+export const N_A11Y_VALUE_LENGTH = 880;       // Value byte length, 0 = none (u32)
+// 884-895: reserved
 
 // --- Cache Line 15 (896-959): Interaction State ---
 export const N_SCROLL_X = 896;
@@ -277,10 +385,35 @@ export const N_CURSOR_STYLE = 926;
 export const N_CURSOR_BLINK_RATE = 927;
 export const N_MAX_LENGTH = 928;
 export const N_INPUT_TYPE = 929;
-// 930-959: reserved
-
-// --- Cache Line 16 (960-1023): Reserved (Animation, Effects, Transforms) ---
-// Reserved for future animation/effects/physics
+// 930-931: reserved (alignment)
+export const N_HOVER_TAG = 932;                 // User tag exported on hover, 0 = none (u32)
+export const N_GHOST_OFFSET = 936;              // Input completion suggestion in text pool (u32)
+export const N_GHOST_LENGTH = 940;              // Suggestion byte length, 0 = none (u32)
+export const N_WORD_BOUNDARY = 944;             // WordBoundary rule for word motion/selection (u8)
+export const N_A11Y_ROLE = 945;                 // What the node is to assistive tools (u8, A11yRole)
+export const N_A11Y_LIVE = 946;                 // Announce changes to the node (u8, LiveRegion)
+export const N_INPUT_FLAGS = 947;               // INPUT_* bits for masked input editing (u8)
+export const N_INPUT_MASK_OFFSET = 948;         // Pattern/date mask in text pool (u32)
+export const N_INPUT_MASK_LENGTH = 952;         // Mask byte length, 0 = none (u32)
+export const N_DIRECTION = 956;                 // Text direction, inherited when 0 (u8, TextDirection)
+export const N_POINTER_EVENTS = 957;            // Whether the mouse can hit the node, inherited when 0 (u8, PointerEvents)
+export const N_STICKY = 958;                    // Edge of a scrolling parent's viewport the node pins to (u8, Sticky)
+// 959: reserved
+
+// --- Cache Line 16 (960-1023): Animation ---
+// Smooth scroll: Rust records where a scroll is headed, TS springs the offset there
+export const N_SCROLL_TARGET_X = 960;           // Spring target for N_SCROLL_X (i32)
+export const N_SCROLL_TARGET_Y = 964;           // Spring target for N_SCROLL_Y (i32)
+export const N_SCROLL_VELOCITY_X = 968;         // Spring velocity, cells/s (f32, TS writes)
+export const N_SCROLL_VELOCITY_Y = 972;         // Spring velocity, cells/s (f32, TS writes)
+export const N_SMOOTH_SCROLL = 976;             // 1 = scrolls ease to their target (u8, TS writes)
+export const N_SCROLL_ANIMATING = 977;          // 1 while a target is pending (u8, Rust sets, TS clears)
+export const N_SCROLL_FLAGS = 978;              // SCROLL_* bits (u8, TS writes)
+export const N_REACH_END_THRESHOLD = 979;       // Rows short of the bottom that count as its end (u8, TS writes)
+// IME composition: uncommitted text drawn underlined at an input's cursor
+export const N_PREEDIT_OFFSET = 980;            // Preedit text in text pool (u32)
+export const N_PREEDIT_LENGTH = 984;            // Preedit byte length, 0 = not composing (u32)
+// 988-1023: reserved (effects, transforms)
 
 // =============================================================================
 // CONFIG FLAGS (bitfield at H_CONFIG_FLAGS)
@@ -295,6 +428,8 @@ export const CONFIG_WHEEL_SCROLL = 1 << 5;
 export const CONFIG_FOCUS_ON_CLICK = 1 << 6;
 export const CONFIG_MOUSE_ENABLED = 1 << 7;
 export const CONFIG_KITTY_KEYBOARD = 1 << 8;
+export const CONFIG_SPATIAL_NAVIGATION = 1 << 9;   // Arrow keys move focus by direction (opt-in)
+export const CONFIG_NATIVE_CURSOR = 1 << 10;       // Focused input shows the terminal's cursor (opt-in)
 
 /** Default config: bits 0-7 enabled */
 export const CONFIG_DEFAULT = 0x00ff;
@@ -317,6 +452,36 @@ export const FLAG_FOCUSED = 1 << 1;
 export const FLAG_HOVERED = 1 << 2;
 export const FLAG_PRESSED = 1 << 3;
 export const FLAG_DISABLED = 1 << 4;
+/** Container traps focus: Tab and spatial navigation stay inside it */
+export const FLAG_FOCUS_TRAP = 1 << 5;
+/** Tab and Shift+Tab within this box go to its onKey handlers instead of moving focus */
+export const FLAG_CAPTURE_TAB = 1 << 6;
+
+// =============================================================================
+// INPUT FLAGS (bitfield at N_INPUT_FLAGS)
+// =============================================================================
+
+/** Number input accepts a leading minus sign */
+export const INPUT_SIGNED = 1 << 0;
+/** Number input accepts one decimal point */
+export const INPUT_DECIMAL = 1 << 1;
+/** The text slot holds the placeholder, not the value (the value is empty) */
+export const INPUT_PLACEHOLDER = 1 << 2;
+/** TS edits this input itself; Rust passes its keys on instead */
+export const INPUT_SCRIPTED = 1 << 3;
+
+// =============================================================================
+// SCROLL FLAGS (bitfield at N_SCROLL_FLAGS)
+// =============================================================================
+
+/** Scrolled to the bottom, the node stays there as its content grows */
+export const SCROLL_STICK_BOTTOM = 1 << 0;
+/** Rust pushes a ScrollChange event whenever it moves the node's scroll offset */
+export const SCROLL_EVENTS = 1 << 1;
+/** Let content changes above the viewport move what's shown (no scroll anchoring) */
+export const SCROLL_NO_ANCHOR = 1 << 2;
+/** Rust pushes a ReachEnd event when the offset comes within N_REACH_END_THRESHOLD of the bottom */
+export const SCROLL_REACH_END = 1 << 3;
 
 // =============================================================================
 // TEXT ATTRIBUTES (bitfield at N_TEXT_ATTRS)
@@ -339,6 +504,8 @@ export const COMPONENT_NONE = 0;
 export const COMPONENT_BOX = 1;
 export const COMPONENT_TEXT = 2;
 export const COMPONENT_INPUT = 3;
+export const COMPONENT_SELECT = 4;
+export const COMPONENT_PROGRESS = 5;
 
 // =============================================================================
 // BORDER STYLES
@@ -422,6 +589,7 @@ export const enum AlignContent {
   SpaceBetween = 3,
   SpaceAround = 4,
   SpaceEvenly = 5,
+  Stretch = 6,
 }
 
 export const enum AlignSelf {
@@ -444,6 +612,14 @@ export const enum Overflow {
   Scroll = 2,
 }
 
+/** What a percentage dimension is a percentage of */
+export const enum PercentBasis {
+  Parent = 0,
+  ViewportWidth = 1,
+  ViewportHeight = 2,
+  Named = 3,
+}
+
 export const enum Display {
   None = 0,
   Flex = 1,
@@ -454,12 +630,39 @@ export const enum TextAlign {
   Left = 0,
   Center = 1,
   Right = 2,
+  Start = 3,  // Left in a left-to-right paragraph, right in a right-to-left one
+  End = 4,    // Right in a left-to-right paragraph, left in a right-to-left one
+  Justify = 5, // Wrapped lines fill the width; a paragraph's last line aligns to Start
+}
+
+/** Whether the mouse can hit a node, or passes through to what's beneath */
+export const enum PointerEvents {
+  Inherit = 0,  // The parent's (Auto at the root)
+  Auto = 1,
+  None = 2,
+  All = 3,      // The whole box takes the mouse, background or not (backdrops); children inherit Auto
+}
+
+/** Viewport edge a child of a scroll container stays pinned to */
+export const enum Sticky {
+  None = 0,
+  Top = 1,     // Stops at the top edge instead of scrolling past it
+  Bottom = 2,  // Stops at the bottom edge until scrolled into place
+}
+
+/** Base direction of text and of a box's rows */
+export const enum TextDirection {
+  Inherit = 0,  // The parent's (Auto at the root)
+  Ltr = 1,
+  Rtl = 2,
+  Auto = 3,     // Each paragraph takes the direction of its first strong character
 }
 
 export const enum TextWrap {
   NoWrap = 0,
   Wrap = 1,
   Truncate = 2,
+  Hyphenate = 3,  // Wrap, also breaking words at soft hyphens (U+00AD)
 }
 
 export const enum TextOverflow {
@@ -492,8 +695,106 @@ export const enum CursorStyle {
 export const enum InputType {
   Text = 0,
   Password = 1,
-  Number = 2,
+  Number = 2,   // Digits, sign and point per INPUT_SIGNED / INPUT_DECIMAL
   Email = 3,
+  Date = 4,     // Mask is the format, e.g. 'DD/MM/YYYY'
+  Pattern = 5,  // Mask: 9 digit, a letter, * either, anything else literal
+}
+
+/** What counts as a word for Ctrl+arrow motion, word deletion and double-click selection */
+export const enum WordBoundary {
+  Unicode = 0,       // UAX #29 word boundaries
+  Alphanumeric = 1,
+  Identifier = 2,    // alphanumerics + underscore
+  Whitespace = 3,    // anything but whitespace
+}
+
+/** What a node is to assistive tools (announced after its label: "Save, button") */
+export const enum A11yRole {
+  None = 0,
+  Button = 1,
+  Checkbox = 2,
+  Radio = 3,
+  Switch = 4,
+  TextBox = 5,
+  Link = 6,
+  List = 7,
+  ListItem = 8,
+  Menu = 9,
+  MenuItem = 10,
+  Tab = 11,
+  TabPanel = 12,
+  Dialog = 13,
+  Alert = 14,
+  Status = 15,
+  Heading = 16,
+  ProgressBar = 17,
+  Slider = 18,
+  Table = 19,
+  Group = 20,
+}
+
+/** Whether changes to a node are announced, and how urgently */
+export const enum LiveRegion {
+  Off = 0,
+  Polite = 1,      // once the user is idle
+  Assertive = 2,   // at once, interrupting
+}
+
+/** How a box paints its background (non-solid fills blend bg → bg color 2) */
+export const enum BackgroundFill {
+  Solid = 0,
+  LinearGradient = 1,
+  Checker = 2,
+  StripesHorizontal = 3,
+  StripesVertical = 4,
+  StripesDiagonal = 5,
+}
+
+/** Color-vision deficiency simulated by the dev overlay */
+export const enum ColorVision {
+  None = 0,
+  Protanopia = 1,    // red-blind
+  Deuteranopia = 2,  // green-blind
+  Tritanopia = 3,    // blue-blind
+}
+
+/** How control characters in text content are made visible */
+export const enum ControlChars {
+  Pictures = 0,  // ␀ ␛ ␡
+  Caret = 1,     // ^@ ^[ ^?
+}
+
+/** Key set for framework navigation and input editing */
+export const enum KeymapPreset {
+  Standard = 0,  // arrows, Page Up/Down, Home/End
+  Vim = 1,       // + hjkl, Ctrl+D/U, Ctrl+F/B, g/G
+  Emacs = 2,     // + Ctrl+N/P/F/B, Ctrl+V/Alt+V, Alt+</>, readline editing
+}
+
+/** Where Rust caught a panic (Error event payload) */
+export const enum ErrorSource {
+  Key = 0,       // keyboard dispatch
+  Mouse = 1,     // mouse dispatch
+  Timer = 2,     // a component timer callback
+  Task = 3,      // a task's apply callback
+  Layout = 4,    // layout computation
+  Frame = 5,     // a Rust on_frame callback
+}
+
+/** Colors the terminal can show */
+export const enum ColorDepth {
+  Mono = 0,       // dumb terminal or NO_COLOR
+  Ansi16 = 1,
+  Ansi256 = 2,
+  TrueColor = 3,
+}
+
+/** How much of Unicode the terminal can draw */
+export const enum UnicodeLevel {
+  Full = 0,   // wide CJK and emoji too
+  Basic = 1,  // single-width symbols: box drawing, blocks
+  Ascii = 2,  // borders fall back to +-|
 }
 
 export const enum RenderMode {
@@ -567,10 +868,14 @@ export interface GridTrack {
 export interface SharedBuffer {
   /** The underlying SharedArrayBuffer */
   raw: SharedArrayBuffer;
+  /** Where this buffer starts in `raw` (non-zero inside wasm memory) */
+  byteOffset: number;
   /** DataView for reading/writing */
   view: DataView;
   /** Int32Array view of header (for Atomics - must be Int32Array for wait/notify) */
   headerI32: Int32Array;
+  /** Uint8Array view of header (for byte-wide Atomics: the text pool lock) */
+  headerU8: Uint8Array;
   /** Configured maximum nodes */
   maxNodes: number;
   /** Configured text pool size */
@@ -586,6 +891,12 @@ export interface SharedBufferConfig {
   maxNodes?: number;
   /** Text pool size in bytes. Default: 10 MB */
   textPoolSize?: number;
+  /**
+   * Place the buffer in existing memory instead of a new SharedArrayBuffer:
+   * a wasm engine's shared memory, at the address it allocated
+   * (`spark_browser_alloc`). Default: a buffer of its own.
+   */
+  memory?: { buffer: SharedArrayBuffer; byteOffset: number };
 }
 
 // =============================================================================
@@ -611,14 +922,18 @@ export function createSharedBuffer(config: SharedBufferConfig = {}): SharedBuffe
   const eventRingOffset = textPoolOffset + textPoolSize;
   const totalSize = eventRingOffset + EVENT_RING_SIZE;
 
-  const raw = new SharedArrayBuffer(totalSize);
-  const view = new DataView(raw);
-  const headerI32 = new Int32Array(raw, 0, HEADER_SIZE / 4);
+  const raw = config.memory?.buffer ?? new SharedArrayBuffer(totalSize);
+  const byteOffset = config.memory?.byteOffset ?? 0;
+  const view = new DataView(raw, byteOffset, totalSize);
+  const headerI32 = new Int32Array(raw, byteOffset, HEADER_SIZE / 4);
+  const headerU8 = new Uint8Array(raw, byteOffset, HEADER_SIZE);
 
   const buffer: SharedBuffer = {
     raw,
+    byteOffset,
     view,
     headerI32,
+    headerU8,
     maxNodes,
     textPoolSize,
     textPoolOffset,
@@ -653,6 +968,7 @@ export function createSharedBuffer(config: SharedBufferConfig = {}): SharedBuffe
   view.setUint32(H_EVENT_WRITE_IDX, 0, true);
   view.setUint32(H_EVENT_READ_IDX, 0, true);
   view.setUint8(H_EXIT_REQUESTED, 0);
+  view.setInt32(H_HOVER_PAYLOAD_INDEX, -1, true);
 
   // Initialize all nodes with defaults
   for (let i = 0; i < maxNodes; i++) {
@@ -681,6 +997,16 @@ function initializeNode(buffer: SharedBuffer, nodeIndex: number): void {
   v.setUint8(base + N_DISPLAY, Display.Flex);
   v.setUint8(base + N_POSITION, Position.Relative);
   v.setUint8(base + N_OVERFLOW, Overflow.Visible);
+  v.setUint8(base + N_OVERFLOW_X, 0);
+  v.setUint8(base + N_OVERFLOW_Y, 0);
+  v.setUint8(base + N_WIDTH_BASIS, PercentBasis.Parent);
+  v.setUint8(base + N_HEIGHT_BASIS, PercentBasis.Parent);
+  v.setUint8(base + N_MIN_WIDTH_BASIS, PercentBasis.Parent);
+  v.setUint8(base + N_MIN_HEIGHT_BASIS, PercentBasis.Parent);
+  v.setUint8(base + N_MAX_WIDTH_BASIS, PercentBasis.Parent);
+  v.setUint8(base + N_MAX_HEIGHT_BASIS, PercentBasis.Parent);
+  v.setInt32(base + N_RELATIVE_TO, -1, true);
+  v.setUint8(base + N_ASPECT_CELLS, 0);
   v.setUint8(base + N_VISIBLE, 1);
   v.setUint8(base + N_BOX_SIZING, 0); // border-box
   v.setUint8(base + N_DIRTY_FLAGS, 0);
@@ -718,6 +1044,7 @@ function initializeNode(buffer: SharedBuffer, nodeIndex: number): void {
   v.setUint8(base + N_BORDER_WIDTH_LEFT, 0);
   v.setInt32(base + N_PARENT_INDEX, -1, true);
   v.setInt32(base + N_TAB_INDEX, 0, true);
+  v.setUint8(base + N_REGION, 0);
 
   // === Cache Line 4: Grid Container Properties ===
   v.setUint8(base + N_GRID_AUTO_FLOW, GridAutoFlow.Row);
@@ -733,6 +1060,9 @@ function initializeNode(buffer: SharedBuffer, nodeIndex: number): void {
   v.setInt16(base + N_GRID_ROW_START, 0, true);
   v.setInt16(base + N_GRID_ROW_END, 0, true);
   v.setUint8(base + N_JUSTIFY_SELF, JustifySelf.Auto);
+  v.setInt32(base + N_FIRST_CHILD, -1, true);
+  v.setInt32(base + N_PREV_SIBLING, -1, true);
+  v.setInt32(base + N_NEXT_SIBLING, -1, true);
 
   // === Cache Lines 5-10: Grid Tracks (zero-initialized by SharedArrayBuffer) ===
   // No explicit initialization needed - tracks start as TrackType.None
@@ -765,6 +1095,11 @@ function initializeNode(buffer: SharedBuffer, nodeIndex: number): void {
   v.setUint16(base + N_BORDER_CHAR_BR, 0, true);
   v.setUint8(base + N_FOCUS_INDICATOR_CHAR, 0x2a); // '*'
   v.setUint8(base + N_FOCUS_INDICATOR_ENABLED, 1);
+  v.setUint8(base + N_BG_FILL, BackgroundFill.Solid);
+  v.setUint8(base + N_BG_FILL_SIZE, 0);
+  v.setUint16(base + N_BG_FILL_ANGLE, 0, true);
+  v.setUint8(base + N_BORDER_INNER_STYLE, BORDER_NONE);
+  v.setUint8(base + N_RENDER_CACHE, 0);
 
   // === Cache Line 13: Colors ===
   v.setUint32(base + N_FG_COLOR, 0, true);
@@ -778,11 +1113,14 @@ function initializeNode(buffer: SharedBuffer, nodeIndex: number): void {
   v.setUint32(base + N_CURSOR_FG_COLOR, 0, true);
   v.setUint32(base + N_CURSOR_BG_COLOR, 0, true);
   v.setUint32(base + N_SELECTION_COLOR, 0, true);
+  v.setUint32(base + N_BG_COLOR_2, 0, true);
+  v.setUint32(base + N_BORDER_INNER_COLOR, 0, true);
+  v.setUint32(base + N_BORDER_FRAME_FILL, 0, true);
 
   // === Cache Line 14: Text Properties ===
   v.setUint32(base + N_TEXT_OFFSET, 0, true);
   v.setUint32(base + N_TEXT_LENGTH, 0, true);
-  v.setUint8(base + N_TEXT_ALIGN, TextAlign.Left);
+  v.setUint8(base + N_TEXT_ALIGN, TextAlign.Start);
   v.setUint8(base + N_TEXT_WRAP, TextWrap.NoWrap);
   v.setUint8(base + N_TEXT_OVERFLOW, TextOverflow.Clip);
   v.setUint8(base + N_TEXT_ATTRS, 0);
@@ -792,6 +1130,13 @@ function initializeNode(buffer: SharedBuffer, nodeIndex: number): void {
   v.setUint8(base + N_LINE_HEIGHT, 0);
   v.setUint8(base + N_LETTER_SPACING, 0);
   v.setUint8(base + N_MAX_LINES, 0);
+  v.setUint32(base + N_TEXT_CAPACITY, 0, true);
+  v.setUint32(base + N_HREF_OFFSET, 0, true);
+  v.setUint32(base + N_HREF_LENGTH, 0, true);
+  v.setUint32(base + N_A11Y_LABEL_OFFSET, 0, true);
+  v.setUint32(base + N_A11Y_LABEL_LENGTH, 0, true);
+  v.setUint32(base + N_A11Y_VALUE_OFFSET, 0, true);
+  v.setUint32(base + N_A11Y_VALUE_LENGTH, 0, true);
 
   // === Cache Line 15: Interaction State ===
   v.setInt32(base + N_SCROLL_X, 0, true);
@@ -807,6 +1152,30 @@ function initializeNode(buffer: SharedBuffer, nodeIndex: number): void {
   v.setUint8(base + N_CURSOR_BLINK_RATE, 0);
   v.setUint8(base + N_MAX_LENGTH, 0);
   v.setUint8(base + N_INPUT_TYPE, InputType.Text);
+  v.setUint32(base + N_HOVER_TAG, 0, true);
+  v.setUint32(base + N_GHOST_OFFSET, 0, true);
+  v.setUint32(base + N_GHOST_LENGTH, 0, true);
+  v.setUint8(base + N_WORD_BOUNDARY, WordBoundary.Unicode);
+  v.setUint8(base + N_A11Y_ROLE, A11yRole.None);
+  v.setUint8(base + N_A11Y_LIVE, LiveRegion.Off);
+  v.setUint8(base + N_INPUT_FLAGS, 0);
+  v.setUint32(base + N_INPUT_MASK_OFFSET, 0, true);
+  v.setUint32(base + N_INPUT_MASK_LENGTH, 0, true);
+  v.setUint8(base + N_DIRECTION, TextDirection.Inherit);
+  v.setUint8(base + N_POINTER_EVENTS, PointerEvents.Inherit);
+  v.setUint8(base + N_STICKY, Sticky.None);
+
+  // === Cache Line 16: Animation ===
+  v.setInt32(base + N_SCROLL_TARGET_X, 0, true);
+  v.setInt32(base + N_SCROLL_TARGET_Y, 0, true);
+  v.setFloat32(base + N_SCROLL_VELOCITY_X, 0, true);
+  v.setFloat32(base + N_SCROLL_VELOCITY_Y, 0, true);
+  v.setUint8(base + N_SMOOTH_SCROLL, 0);
+  v.setUint8(base + N_SCROLL_ANIMATING, 0);
+  v.setUint8(base + N_SCROLL_FLAGS, 0);
+  v.setUint8(base + N_REACH_END_THRESHOLD, 0);
+  v.setUint32(base + N_PREEDIT_OFFSET, 0, true);
+  v.setUint32(base + N_PREEDIT_LENGTH, 0, true);
 }
 
 // =============================================================================
@@ -953,6 +1322,119 @@ export function setScrollSpeed(buf: SharedBuffer, speed: number): void {
   buf.view.setUint32(H_SCROLL_SPEED, speed, true);
 }
 
+export function getColorVision(buf: SharedBuffer): ColorVision {
+  return buf.view.getUint8(H_COLOR_VISION);
+}
+
+export function setColorVision(buf: SharedBuffer, mode: ColorVision): void {
+  buf.view.setUint8(H_COLOR_VISION, mode);
+}
+
+export function getDebugLayout(buf: SharedBuffer): boolean {
+  return buf.view.getUint8(H_DEBUG_LAYOUT) !== 0;
+}
+
+/** Outline every node's border box, hatch its padding and tint gaps between children. */
+export function setDebugLayout(buf: SharedBuffer, on: boolean): void {
+  buf.view.setUint8(H_DEBUG_LAYOUT, on ? 1 : 0);
+}
+
+/** Bound the rows an inline frame takes (0 = no bound; min is capped at 255) */
+export function setInlineHeight(buf: SharedBuffer, min: number, max: number): void {
+  buf.view.setUint8(H_INLINE_MIN_HEIGHT, Math.max(0, Math.min(0xff, Math.round(min))));
+  buf.view.setUint16(H_INLINE_MAX_HEIGHT, Math.max(0, Math.min(0xffff, Math.round(max))), true);
+}
+
+export function getInlineMinHeight(buf: SharedBuffer): number {
+  return buf.view.getUint8(H_INLINE_MIN_HEIGHT);
+}
+
+export function getInlineMaxHeight(buf: SharedBuffer): number {
+  return buf.view.getUint16(H_INLINE_MAX_HEIGHT, true);
+}
+
+/** Fixed-point scale of H_CELL_ASPECT (2.0 is stored as 128) */
+export const CELL_ASPECT_SCALE = 64;
+
+/** Cell height ÷ width used by N_ASPECT_CELLS (0 = measure it from the cell pixel size) */
+export function setCellAspect(buf: SharedBuffer, aspect: number): void {
+  buf.view.setUint8(H_CELL_ASPECT, Math.max(0, Math.min(0xff, Math.round(aspect * CELL_ASPECT_SCALE))));
+}
+
+/** The configured cell aspect, 0 when measured */
+export function getCellAspect(buf: SharedBuffer): number {
+  return buf.view.getUint8(H_CELL_ASPECT) / CELL_ASPECT_SCALE;
+}
+
+export function getControlChars(buf: SharedBuffer): ControlChars {
+  return buf.view.getUint8(H_CONTROL_CHARS);
+}
+
+export function setControlChars(buf: SharedBuffer, mode: ControlChars): void {
+  buf.view.setUint8(H_CONTROL_CHARS, mode);
+}
+
+export function setKeymapPreset(buf: SharedBuffer, preset: KeymapPreset): void {
+  buf.view.setUint8(H_KEYMAP_PRESET, preset);
+}
+
+export function setLayoutEvents(buf: SharedBuffer, enabled: boolean): void {
+  buf.view.setUint8(H_LAYOUT_EVENTS, enabled ? 1 : 0);
+}
+
+export function getKeymapOverrides(buf: SharedBuffer): number {
+  return buf.view.getUint16(H_KEYMAP_OVERRIDES, true);
+}
+
+export function setKeymapOverrides(buf: SharedBuffer, bits: number): void {
+  buf.view.setUint16(H_KEYMAP_OVERRIDES, bits, true);
+}
+
+export function setScreenshotBinding(buf: SharedBuffer, keycode: number, modifiers: number, flags: number): void {
+  buf.view.setUint32(H_SCREENSHOT_KEY, keycode, true);
+  buf.view.setUint8(H_SCREENSHOT_MODIFIERS, modifiers);
+  buf.view.setUint8(H_SCREENSHOT_FLAGS, flags);
+}
+
+/** Coalesce changes into at most one frame per `micros` (0 = render every change at once). */
+export function setBatchWindow(buf: SharedBuffer, micros: number): void {
+  buf.view.setUint32(H_BATCH_WINDOW_US, micros, true);
+}
+
+export function getPerfHud(buf: SharedBuffer): boolean {
+  return buf.view.getUint8(H_PERF_HUD) !== 0;
+}
+
+export function setPerfHud(buf: SharedBuffer, visible: boolean): void {
+  buf.view.setUint8(H_PERF_HUD, visible ? 1 : 0);
+}
+
+export function setPerfHudBinding(buf: SharedBuffer, keycode: number, modifiers: number): void {
+  buf.view.setUint32(H_PERF_HUD_KEY, keycode, true);
+  buf.view.setUint8(H_PERF_HUD_MODIFIERS, modifiers);
+}
+
+export function getInspector(buf: SharedBuffer): boolean {
+  return buf.view.getUint8(H_INSPECTOR) !== 0;
+}
+
+export function setInspector(buf: SharedBuffer, open: boolean): void {
+  buf.view.setUint8(H_INSPECTOR, open ? 1 : 0);
+}
+
+export function setInspectorBinding(buf: SharedBuffer, keycode: number, modifiers: number): void {
+  buf.view.setUint32(H_INSPECTOR_KEY, keycode, true);
+  buf.view.setUint8(H_INSPECTOR_MODIFIERS, modifiers);
+}
+
+export function getScreenshotToast(buf: SharedBuffer): boolean {
+  return buf.view.getUint8(H_SCREENSHOT_TOAST) !== 0;
+}
+
+export function setScreenshotToast(buf: SharedBuffer, visible: boolean): void {
+  buf.view.setUint8(H_SCREENSHOT_TOAST, visible ? 1 : 0);
+}
+
 // --- State (Rust writes, TS reads) ---
 export function getFocusedIndex(buf: SharedBuffer): number {
   return buf.view.getInt32(H_FOCUSED_INDEX, true);
@@ -999,6 +1481,14 @@ export function getTotalFrameTimeUs(buf: SharedBuffer): number {
   return buf.view.getUint32(H_TOTAL_FRAME_TIME_US, true);
 }
 
+export function getFrameCells(buf: SharedBuffer): number {
+  return buf.view.getUint32(H_FRAME_CELLS, true);
+}
+
+export function getFrameBytes(buf: SharedBuffer): number {
+  return buf.view.getUint32(H_FRAME_BYTES, true);
+}
+
 // --- Timing Stats (TS side writes, TS/Rust reads) ---
 export function setTsSignalTimeNs(buf: SharedBuffer, ns: number): void {
   buf.view.setUint32(H_TS_SIGNAL_TIME_NS, ns >>> 0, true);
@@ -1067,6 +1557,52 @@ export function getEventWriteCount(buf: SharedBuffer): number {
   return buf.view.getUint32(H_EVENT_WRITE_COUNT, true);
 }
 
+// --- Input-to-paint latency (Rust writes, TS reads) ---
+export function getInputLatencyP50Us(buf: SharedBuffer): number {
+  return buf.view.getUint32(H_INPUT_LATENCY_P50_US, true);
+}
+
+export function getInputLatencyP95Us(buf: SharedBuffer): number {
+  return buf.view.getUint32(H_INPUT_LATENCY_P95_US, true);
+}
+
+export function getInputLatencyP99Us(buf: SharedBuffer): number {
+  return buf.view.getUint32(H_INPUT_LATENCY_P99_US, true);
+}
+
+export function getInputLatencyLastUs(buf: SharedBuffer): number {
+  return buf.view.getUint32(H_INPUT_LATENCY_LAST_US, true);
+}
+
+export function getInputLatencyCount(buf: SharedBuffer): number {
+  return buf.view.getUint32(H_INPUT_LATENCY_COUNT, true);
+}
+
+// --- Terminal capabilities (Rust writes, TS reads) ---
+export function getCapabilitiesSeq(buf: SharedBuffer): number {
+  return buf.view.getUint32(H_CAPABILITIES_SEQ, true);
+}
+
+export function getCellPixelWidth(buf: SharedBuffer): number {
+  return buf.view.getUint16(H_CELL_PIXEL_WIDTH, true);
+}
+
+export function getCellPixelHeight(buf: SharedBuffer): number {
+  return buf.view.getUint16(H_CELL_PIXEL_HEIGHT, true);
+}
+
+export function getColorDepth(buf: SharedBuffer): ColorDepth {
+  return buf.view.getUint8(H_COLOR_DEPTH);
+}
+
+export function getUnicodeLevel(buf: SharedBuffer): UnicodeLevel {
+  return buf.view.getUint8(H_UNICODE_LEVEL);
+}
+
+export function getMouseAvailable(buf: SharedBuffer): boolean {
+  return buf.view.getUint8(H_MOUSE_AVAILABLE) !== 0;
+}
+
 /**
  * Get all timing stats as an object.
  * Convenient for benchmarking.
@@ -1077,6 +1613,9 @@ export interface TimingStats {
   framebufferUs: number;
   renderUs: number;
   totalFrameUs: number;
+  // Last frame's terminal output
+  frameCells: number;
+  frameBytes: number;
   // TS side (nanoseconds)
   tsSignalNs: number;
   tsBufferWriteNs: number;
@@ -1087,6 +1626,12 @@ export interface TimingStats {
   wakeCount: number;
   wakeLatencyUs: number;
   eventWriteCount: number;
+  // Input-to-paint latency (microseconds)
+  inputLatencyP50Us: number;
+  inputLatencyP95Us: number;
+  inputLatencyP99Us: number;
+  inputLatencyLastUs: number;
+  inputLatencyCount: number;
 }
 
 export function getTimingStats(buf: SharedBuffer): TimingStats {
@@ -1095,6 +1640,8 @@ export function getTimingStats(buf: SharedBuffer): TimingStats {
     framebufferUs: getFramebufferTimeUs(buf),
     renderUs: getRenderTimeUs(buf),
     totalFrameUs: getTotalFrameTimeUs(buf),
+    frameCells: getFrameCells(buf),
+    frameBytes: getFrameBytes(buf),
     tsSignalNs: getTsSignalTimeNs(buf),
     tsBufferWriteNs: getTsBufferWriteTimeNs(buf),
     tsNotifyNs: getTsNotifyTimeNs(buf),
@@ -1103,6 +1650,11 @@ export function getTimingStats(buf: SharedBuffer): TimingStats {
     wakeCount: getWakeCount(buf),
     wakeLatencyUs: getWakeLatencyUs(buf),
     eventWriteCount: getEventWriteCount(buf),
+    inputLatencyP50Us: getInputLatencyP50Us(buf),
+    inputLatencyP95Us: getInputLatencyP95Us(buf),
+    inputLatencyP99Us: getInputLatencyP99Us(buf),
+    inputLatencyLastUs: getInputLatencyLastUs(buf),
+    inputLatencyCount: getInputLatencyCount(buf),
   };
 }
 
@@ -1236,6 +1788,15 @@ export function setDisabled(buf: SharedBuffer, nodeIndex: number, value: boolean
   setInteractionFlags(buf, nodeIndex, value ? flags | FLAG_DISABLED : flags & ~FLAG_DISABLED);
 }
 
+export function getFocusTrap(buf: SharedBuffer, nodeIndex: number): boolean {
+  return (getInteractionFlags(buf, nodeIndex) & FLAG_FOCUS_TRAP) !== 0;
+}
+
+export function setFocusTrap(buf: SharedBuffer, nodeIndex: number, value: boolean): void {
+  const flags = getInteractionFlags(buf, nodeIndex);
+  setInteractionFlags(buf, nodeIndex, value ? flags | FLAG_FOCUS_TRAP : flags & ~FLAG_FOCUS_TRAP);
+}
+
 // =============================================================================
 // HIERARCHY
 // =============================================================================
@@ -1283,6 +1844,29 @@ export function setNextSibling(buf: SharedBuffer, nodeIndex: number, siblingInde
   setI32(buf, nodeIndex, N_NEXT_SIBLING, siblingIndex);
 }
 
+/** Times the index has been released (N_GENERATION) */
+export function getNodeGeneration(buf: SharedBuffer, nodeIndex: number): number {
+  return getU32(buf, nodeIndex, N_GENERATION);
+}
+
+export function setNodeGeneration(buf: SharedBuffer, nodeIndex: number, generation: number): void {
+  setU32(buf, nodeIndex, N_GENERATION, generation);
+}
+
+/** Event byte 1 for events not tied to a component's generation */
+export const EVENT_UNTAGGED = 0;
+
+/**
+ * Event byte 1 for a component event: its target's generation when Rust
+ * pushed it, never EVENT_UNTAGGED. Must match Rust's generation_tag().
+ */
+export function generationTag(generation: number): number {
+  return (generation % 255) + 1;
+}
+
+/** Last creation sequence handed out (N_CREATION_SEQ) */
+let creationSeq = 0;
+
 /**
  * Initialize a node's hierarchy fields to -1 (no parent, no children, no siblings).
  * MUST be called when allocating a new node, before any linkChild() calls.
@@ -1293,6 +1877,11 @@ export function initNodeHierarchy(buf: SharedBuffer, nodeIndex: number): void {
   setI32(buf, nodeIndex, N_FIRST_CHILD, -1);
   setI32(buf, nodeIndex, N_PREV_SIBLING, -1);
   setI32(buf, nodeIndex, N_NEXT_SIBLING, -1);
+  // A recycled index must not keep a keyed-list slot
+  setI32(buf, nodeIndex, N_SLOT_ANCHOR, 0);
+  setI32(buf, nodeIndex, N_SLOT_POSITION, 0);
+  // Newest node so far: paints over equal-z siblings created before it
+  setU32(buf, nodeIndex, N_CREATION_SEQ, ++creationSeq);
 }
 
 /** Iterate children of a node. O(children) instead of O(N). */
@@ -1470,6 +2059,32 @@ export function setGridRowTracks(buf: SharedBuffer, nodeIndex: number, tracks: G
 const textEncoder = new TextEncoder();
 const textDecoder = new TextDecoder();
 
+/** Whether this runtime's TextDecoder reads shared memory in place (Bun
+ *  and Node do; browsers refuse it) */
+let decodesShared: boolean | null = null;
+
+function canDecodeShared(): boolean {
+  if (decodesShared === null) {
+    try {
+      textDecoder.decode(new Uint8Array(new SharedArrayBuffer(1)));
+      decodesShared = true;
+    } catch {
+      decodesShared = false;
+    }
+  }
+  return decodesShared;
+}
+
+/** Decode `length` bytes of the text pool at `offset`. The pool's place
+ *  comes from the header, as Rust reads it; a span outside it decodes to
+ *  nothing. Copied out first where shared memory can't be decoded. */
+function decodePool(buf: SharedBuffer, offset: number, length: number): string {
+  const poolOffset = HEADER_SIZE + buf.view.getUint32(H_MAX_NODES, true) * NODE_STRIDE;
+  if (offset + length > buf.view.getUint32(H_TEXT_POOL_SIZE, true)) return '';
+  const bytes = new Uint8Array(buf.raw, buf.byteOffset + poolOffset + offset, length);
+  return textDecoder.decode(canDecodeShared() ? bytes : bytes.slice());
+}
+
 /**
  * Get the current text pool write pointer.
  */
@@ -1484,15 +2099,47 @@ export function getTextPoolRemaining(buf: SharedBuffer): number {
   return buf.textPoolSize - getTextPoolWritePtr(buf);
 }
 
+/**
+ * Character class for layout-neutral checks: ASCII letters, digits and
+ * spaces each form a class; any other byte is only equal to itself.
+ * Word segmentation (and so wrapping) only looks at these classes.
+ */
+function layoutClass(byte: number): number {
+  if (byte === 0x20) return -1;
+  if (byte >= 0x30 && byte <= 0x39) return -2;
+  if ((byte >= 0x41 && byte <= 0x5a) || (byte >= 0x61 && byte <= 0x7a)) return -3;
+  return byte;
+}
+
+/**
+ * Whether replacing `old` with `next` leaves layout untouched: same byte
+ * length, printable ASCII only (one cell per byte), and the same character
+ * class at every position — so width and wrap points can't change.
+ * Clocks and counters hit this on every tick.
+ */
+function isLayoutNeutral(old: Uint8Array, next: Uint8Array): boolean {
+  if (old.length !== next.length) return false;
+  for (let i = 0; i < next.length; i++) {
+    const a = old[i]!;
+    const b = next[i]!;
+    if (a < 0x20 || a > 0x7e || b < 0x20 || b > 0x7e) return false;
+    if (layoutClass(a) !== layoutClass(b)) return false;
+  }
+  return true;
+}
+
 /**
  * Write text to a node, allocating from the text pool.
  *
  * Layered memory management:
- * 1. Slot reuse: If node already has text and new text fits, writes in place (zero allocation)
- * 2. New allocation: If text is longer, allocates from pool end
- * 3. Compaction: If pool full, compacts to reclaim dead space, then retries
+ * 1. Slot reuse: If the new text fits the node's slot (N_TEXT_CAPACITY), writes in place (zero allocation)
+ * 2. New allocation: Otherwise allocates a TEXT_SLOT_ALIGN-rounded slot from pool end
+ * 3. Compaction: If pool full or crossing the high-water mark, compacts to reclaim dead space, then retries
  * 4. Failure: Only fails if live text genuinely exceeds pool size
  *
+ * In-place rewrites that can't change width or wrapping (see isLayoutNeutral)
+ * mark DIRTY_VISUAL instead of DIRTY_TEXT, so Rust repaints without re-layout.
+ *
  * Returns { success: true } or { success: false, liveBytes, poolSize } for error reporting.
  */
 export function setText(
@@ -1501,32 +2148,48 @@ export function setText(
   text: string
 ): { success: true } | { success: false; liveBytes: number; poolSize: number; needed: number } {
   const encoded = textEncoder.encode(text);
+  return withTextPool(buf, () => writeText(buf, nodeIndex, encoded));
+}
+
+function writeText(
+  buf: SharedBuffer,
+  nodeIndex: number,
+  encoded: Uint8Array
+): { success: true } | { success: false; liveBytes: number; poolSize: number; needed: number } {
   const newLength = encoded.length;
 
   // Check if we can reuse the existing slot
   const existingOffset = getU32(buf, nodeIndex, N_TEXT_OFFSET);
   const existingLength = getU32(buf, nodeIndex, N_TEXT_LENGTH);
+  const capacity = Math.max(getU32(buf, nodeIndex, N_TEXT_CAPACITY), existingLength);
 
-  if (existingLength > 0 && newLength <= existingLength) {
+  if (newLength > 0 && newLength <= capacity) {
     // Reuse existing slot - write in place
-    const poolView = new Uint8Array(buf.raw, buf.textPoolOffset + existingOffset, newLength);
+    const poolView = new Uint8Array(buf.raw, buf.byteOffset + buf.textPoolOffset + existingOffset, Math.max(newLength, existingLength));
+    const neutral = isLayoutNeutral(poolView.subarray(0, existingLength), encoded);
     poolView.set(encoded);
 
     // Update length (offset stays the same)
     setU32(buf, nodeIndex, N_TEXT_LENGTH, newLength);
 
     // Mark dirty
-    markDirty(buf, nodeIndex, DIRTY_TEXT);
+    markDirty(buf, nodeIndex, neutral ? DIRTY_VISUAL : DIRTY_TEXT);
 
     return { success: true };
   }
 
+  if (newLength === 0) {
+    setU32(buf, nodeIndex, N_TEXT_LENGTH, 0);
+    markDirty(buf, nodeIndex, DIRTY_TEXT);
+    return { success: true };
+  }
+
   // Need new allocation
   let writePtr = getTextPoolWritePtr(buf);
 
-  if (writePtr + newLength > buf.textPoolSize) {
-    // Pool full - try compaction
-    const reclaimed = compactTextPool(buf);
+  if (textPoolNeedsCompaction(buf, newLength)) {
+    // Pool full or past the high-water mark - try compaction
+    const reclaimed = compactTextPoolLocked(buf);
 
     if (reclaimed > 0) {
       // Compaction helped - retry allocation
@@ -1544,16 +2207,21 @@ export function setText(
     }
   }
 
+  // Round the slot up for headroom, unless the pool is nearly full
+  const aligned = Math.ceil(newLength / TEXT_SLOT_ALIGN) * TEXT_SLOT_ALIGN;
+  const slot = writePtr + aligned <= buf.textPoolSize ? aligned : newLength;
+
   // Write text to pool
-  const poolView = new Uint8Array(buf.raw, buf.textPoolOffset + writePtr, newLength);
+  const poolView = new Uint8Array(buf.raw, buf.byteOffset + buf.textPoolOffset + writePtr, newLength);
   poolView.set(encoded);
 
-  // Update node's offset and length
+  // Update node's offset, length and slot size
   setU32(buf, nodeIndex, N_TEXT_OFFSET, writePtr);
   setU32(buf, nodeIndex, N_TEXT_LENGTH, newLength);
+  setU32(buf, nodeIndex, N_TEXT_CAPACITY, slot);
 
   // Update pool write pointer
-  buf.view.setUint32(H_TEXT_POOL_WRITE_PTR, writePtr + newLength, true);
+  buf.view.setUint32(H_TEXT_POOL_WRITE_PTR, writePtr + slot, true);
 
   // Mark dirty
   markDirty(buf, nodeIndex, DIRTY_TEXT);
@@ -1561,6 +2229,154 @@ export function setText(
   return { success: true };
 }
 
+/**
+ * Write an input's ghost text (inline completion) to the text pool.
+ * Rust renders it dimmed after the value and appends it on Right/Tab.
+ * Returns false if the pool is full even after compaction.
+ */
+export function setGhostText(buf: SharedBuffer, nodeIndex: number, text: string): boolean {
+  const encoded = textEncoder.encode(text);
+  const newLength = encoded.length;
+
+  if (newLength === 0) {
+    clearGhostText(buf, nodeIndex);
+    return true;
+  }
+
+  return withTextPool(buf, () => {
+    let writePtr = getTextPoolWritePtr(buf);
+    if (textPoolNeedsCompaction(buf, newLength)) {
+      compactTextPoolLocked(buf);
+      writePtr = getTextPoolWritePtr(buf);
+      if (writePtr + newLength > buf.textPoolSize) return false;
+    }
+
+    new Uint8Array(buf.raw, buf.byteOffset + buf.textPoolOffset + writePtr, newLength).set(encoded);
+    setU32(buf, nodeIndex, N_GHOST_OFFSET, writePtr);
+    setU32(buf, nodeIndex, N_GHOST_LENGTH, newLength);
+    buf.view.setUint32(H_TEXT_POOL_WRITE_PTR, writePtr + newLength, true);
+    markDirty(buf, nodeIndex, DIRTY_VISUAL);
+    return true;
+  });
+}
+
+export function clearGhostText(buf: SharedBuffer, nodeIndex: number): void {
+  setU32(buf, nodeIndex, N_GHOST_LENGTH, 0);
+  markDirty(buf, nodeIndex, DIRTY_VISUAL);
+}
+
+export function getGhostText(buf: SharedBuffer, nodeIndex: number): string {
+  const length = getU32(buf, nodeIndex, N_GHOST_LENGTH);
+  if (length === 0) return '';
+  const offset = getU32(buf, nodeIndex, N_GHOST_OFFSET);
+  return decodePool(buf, offset, length);
+}
+
+/**
+ * Link a node's cells to `url` (OSC 8 hyperlink); '' removes the link.
+ * Returns false if the pool is full even after compaction.
+ */
+export function setHref(buf: SharedBuffer, nodeIndex: number, url: string): boolean {
+  return setPoolString(buf, nodeIndex, N_HREF_OFFSET, N_HREF_LENGTH, url, DIRTY_VISUAL);
+}
+
+export function getHref(buf: SharedBuffer, nodeIndex: number): string {
+  return getPoolString(buf, nodeIndex, N_HREF_OFFSET, N_HREF_LENGTH);
+}
+
+/**
+ * Set a node's accessible name ('' = none). Nothing is drawn from it, so
+ * nothing is marked dirty. Returns false if the pool is full.
+ */
+export function setA11yLabel(buf: SharedBuffer, nodeIndex: number, label: string): boolean {
+  return setPoolString(buf, nodeIndex, N_A11Y_LABEL_OFFSET, N_A11Y_LABEL_LENGTH, label, 0);
+}
+
+export function getA11yLabel(buf: SharedBuffer, nodeIndex: number): string {
+  return getPoolString(buf, nodeIndex, N_A11Y_LABEL_OFFSET, N_A11Y_LABEL_LENGTH);
+}
+
+/** Set a node's accessible value ('' = none). Returns false if the pool is full. */
+export function setA11yValue(buf: SharedBuffer, nodeIndex: number, value: string): boolean {
+  return setPoolString(buf, nodeIndex, N_A11Y_VALUE_OFFSET, N_A11Y_VALUE_LENGTH, value, 0);
+}
+
+export function getA11yValue(buf: SharedBuffer, nodeIndex: number): string {
+  return getPoolString(buf, nodeIndex, N_A11Y_VALUE_OFFSET, N_A11Y_VALUE_LENGTH);
+}
+
+/**
+ * Set a number, date or pattern input's mask ('' = none). Rust reads it
+ * while editing; nothing is drawn from it. Returns false if the pool is full.
+ */
+export function setInputMask(buf: SharedBuffer, nodeIndex: number, mask: string): boolean {
+  return setPoolString(buf, nodeIndex, N_INPUT_MASK_OFFSET, N_INPUT_MASK_LENGTH, mask, 0);
+}
+
+export function getInputMask(buf: SharedBuffer, nodeIndex: number): string {
+  return getPoolString(buf, nodeIndex, N_INPUT_MASK_OFFSET, N_INPUT_MASK_LENGTH);
+}
+
+/**
+ * Set an input's composition (preedit) text ('' ends it). Rust draws it
+ * underlined at the cursor without it being part of the value. Returns
+ * false if the pool is full.
+ */
+export function setPreedit(buf: SharedBuffer, nodeIndex: number, text: string): boolean {
+  return setPoolString(buf, nodeIndex, N_PREEDIT_OFFSET, N_PREEDIT_LENGTH, text, DIRTY_VISUAL);
+}
+
+export function getPreedit(buf: SharedBuffer, nodeIndex: number): string {
+  return getPoolString(buf, nodeIndex, N_PREEDIT_OFFSET, N_PREEDIT_LENGTH);
+}
+
+/**
+ * Append `text` to the pool and point a node's offset/length fields at it
+ * ('' clears them), compacting first if it doesn't fit or crosses the
+ * high-water mark. Marks the node with
+ * `dirty` (0 = nothing). Returns false if the pool is full.
+ */
+function setPoolString(
+  buf: SharedBuffer,
+  nodeIndex: number,
+  offsetField: number,
+  lengthField: number,
+  text: string,
+  dirty: number,
+): boolean {
+  const encoded = textEncoder.encode(text);
+  const newLength = encoded.length;
+
+  if (newLength === 0) {
+    setU32(buf, nodeIndex, lengthField, 0);
+    if (dirty) markDirty(buf, nodeIndex, dirty);
+    return true;
+  }
+
+  return withTextPool(buf, () => {
+    let writePtr = getTextPoolWritePtr(buf);
+    if (textPoolNeedsCompaction(buf, newLength)) {
+      compactTextPoolLocked(buf);
+      writePtr = getTextPoolWritePtr(buf);
+      if (writePtr + newLength > buf.textPoolSize) return false;
+    }
+
+    new Uint8Array(buf.raw, buf.byteOffset + buf.textPoolOffset + writePtr, newLength).set(encoded);
+    setU32(buf, nodeIndex, offsetField, writePtr);
+    setU32(buf, nodeIndex, lengthField, newLength);
+    buf.view.setUint32(H_TEXT_POOL_WRITE_PTR, writePtr + newLength, true);
+    if (dirty) markDirty(buf, nodeIndex, dirty);
+    return true;
+  });
+}
+
+function getPoolString(buf: SharedBuffer, nodeIndex: number, offsetField: number, lengthField: number): string {
+  const length = getU32(buf, nodeIndex, lengthField);
+  if (length === 0) return '';
+  const offset = getU32(buf, nodeIndex, offsetField);
+  return decodePool(buf, offset, length);
+}
+
 /**
  * Get text content for a node.
  */
@@ -1572,8 +2388,7 @@ export function getText(buf: SharedBuffer, nodeIndex: number): string {
     return '';
   }
 
-  const poolView = new Uint8Array(buf.raw, buf.textPoolOffset + offset, length);
-  return textDecoder.decode(poolView);
+  return decodePool(buf, offset, length);
 }
 
 /**
@@ -1581,28 +2396,107 @@ export function getText(buf: SharedBuffer, nodeIndex: number): string {
  * WARNING: Invalidates all existing text references!
  */
 export function resetTextPool(buf: SharedBuffer): void {
-  buf.view.setUint32(H_TEXT_POOL_WRITE_PTR, 0, true);
+  withTextPool(buf, () => buf.view.setUint32(H_TEXT_POOL_WRITE_PTR, 0, true));
+}
+
+/** Tries at the text pool lock before sleeping between them */
+const TEXT_POOL_SPINS = 1000;
+/** How long to sleep between tries once spinning gave up (ms) */
+const TEXT_POOL_WAIT_MS = 0.1;
+
+/**
+ * Run `write` holding the text pool lock (H_TEXT_POOL_LOCK), waiting while
+ * Rust holds it. Both sides bump-allocate and compact the pool, and a
+ * compaction moves every string, so neither may write while the other is
+ * mid-write — or while the engine is reading. Rust holds it for one string
+ * write, or for one message and frame on the engine thread.
+ *
+ * A write usually gets it at once; past a short spin, it sleeps briefly
+ * between tries (Atomics.wait on the header word holding the lock, which
+ * returns at once if the word already changed).
+ */
+function withTextPool<T>(buf: SharedBuffer, write: () => T): T {
+  let spins = 0;
+  while (Atomics.compareExchange(buf.headerU8, H_TEXT_POOL_LOCK, 0, 1) !== 0) {
+    if (spins < TEXT_POOL_SPINS) {
+      spins++;
+      continue;
+    }
+    // Rust holds it for a frame: sleep instead of spinning the main thread
+    const word = H_TEXT_POOL_LOCK >> 2;
+    Atomics.wait(buf.headerI32, word, Atomics.load(buf.headerI32, word), TEXT_POOL_WAIT_MS);
+  }
+  try {
+    return write();
+  } finally {
+    Atomics.store(buf.headerU8, H_TEXT_POOL_LOCK, 0);
+  }
 }
 
 /**
- * Compact the text pool by removing dead space.
- * Only called when pool is full - not part of normal operation.
+ * Every (offset, length, capacity) field set that points into the text pool
+ * (-1 = no capacity field). Mirrors Rust's TEXT_POOL_SLOTS: both sides
+ * compact, so a new pool string needs an entry in each.
+ */
+const TEXT_POOL_SLOTS: ReadonlyArray<readonly [number, number, number]> = [
+  [N_TEXT_OFFSET, N_TEXT_LENGTH, N_TEXT_CAPACITY],
+  [N_GHOST_OFFSET, N_GHOST_LENGTH, -1],
+  [N_HREF_OFFSET, N_HREF_LENGTH, -1],
+  [N_A11Y_LABEL_OFFSET, N_A11Y_LABEL_LENGTH, -1],
+  [N_A11Y_VALUE_OFFSET, N_A11Y_VALUE_LENGTH, -1],
+  [N_INPUT_MASK_OFFSET, N_INPUT_MASK_LENGTH, -1],
+  [N_PREEDIT_OFFSET, N_PREEDIT_LENGTH, -1],
+];
+
+/** Whether writing `length` more bytes should compact the pool first */
+function textPoolNeedsCompaction(buf: SharedBuffer, length: number): boolean {
+  const writePtr = getTextPoolWritePtr(buf);
+  const highWater = Math.floor(buf.textPoolSize / 100) * TEXT_POOL_HIGH_WATER_PERCENT;
+  return writePtr + length > buf.textPoolSize || (writePtr <= highWater && writePtr + length > highWater);
+}
+
+/**
+ * Compactions so far, by either side (wraps at 2^16). A change means pool
+ * offsets moved: read N_TEXT_OFFSET and friends fresh, never cache them.
+ */
+export function getTextPoolCompactions(buf: SharedBuffer): number {
+  return buf.view.getUint16(H_TEXT_POOL_COMPACTIONS, true);
+}
+
+/**
+ * Compact the text pool by removing dead space: live strings move to the
+ * front in pool order, strings of released nodes (COMPONENT_NONE) are
+ * dropped and text slots lose their headroom. Runs when a write fills the
+ * pool or crosses the high-water mark — here, or in Rust when it writes an
+ * input's value. Bumps H_TEXT_POOL_COMPACTIONS. Takes the text pool lock,
+ * so it never runs alongside a Rust write or compaction.
  * Returns the amount of space reclaimed.
  */
 export function compactTextPool(buf: SharedBuffer): number {
+  return withTextPool(buf, () => compactTextPoolLocked(buf));
+}
+
+function compactTextPoolLocked(buf: SharedBuffer): number {
   const nodeCount = getNodeCount(buf);
   const oldWritePtr = getTextPoolWritePtr(buf);
 
-  // Collect all live text regions: [nodeIndex, offset, length]
-  const liveRegions: Array<{ nodeIndex: number; offset: number; length: number }> = [];
+  // Collect all live text regions: [nodeIndex, offsetField, offset, length]
+  const liveRegions: Array<{ nodeIndex: number; offsetField: number; capacityField: number; offset: number; length: number }> = [];
   let totalLiveBytes = 0;
 
   for (let i = 0; i < nodeCount; i++) {
-    const length = getU32(buf, i, N_TEXT_LENGTH);
-    if (length > 0) {
-      const offset = getU32(buf, i, N_TEXT_OFFSET);
-      liveRegions.push({ nodeIndex: i, offset, length });
-      totalLiveBytes += length;
+    const released = getU8(buf, i, N_COMPONENT_TYPE) === COMPONENT_NONE;
+    for (const [offsetField, lengthField, capacityField] of TEXT_POOL_SLOTS) {
+      const length = getU32(buf, i, lengthField);
+      if (length > 0 && !released) {
+        const offset = getU32(buf, i, offsetField);
+        liveRegions.push({ nodeIndex: i, offsetField, capacityField, offset, length });
+        totalLiveBytes += length;
+        continue;
+      }
+      // Old bytes are about to be reused by someone else
+      if (released) setU32(buf, i, lengthField, 0);
+      if (capacityField >= 0) setU32(buf, i, capacityField, 0);
     }
   }
 
@@ -1614,24 +2508,26 @@ export function compactTextPool(buf: SharedBuffer): number {
   let tempWritePtr = 0;
 
   for (const region of liveRegions) {
-    const src = new Uint8Array(buf.raw, buf.textPoolOffset + region.offset, region.length);
+    const src = new Uint8Array(buf.raw, buf.byteOffset + buf.textPoolOffset + region.offset, region.length);
     tempBuffer.set(src, tempWritePtr);
     tempWritePtr += region.length;
   }
 
   // Copy compacted data back to pool
-  const poolView = new Uint8Array(buf.raw, buf.textPoolOffset, totalLiveBytes);
+  const poolView = new Uint8Array(buf.raw, buf.byteOffset + buf.textPoolOffset, totalLiveBytes);
   poolView.set(tempBuffer);
 
-  // Update all node offsets to new positions
+  // Update all node offsets to new positions (slots shrink to their text)
   let newOffset = 0;
   for (const region of liveRegions) {
-    setU32(buf, region.nodeIndex, N_TEXT_OFFSET, newOffset);
+    setU32(buf, region.nodeIndex, region.offsetField, newOffset);
+    if (region.capacityField >= 0) setU32(buf, region.nodeIndex, region.capacityField, region.length);
     newOffset += region.length;
   }
 
   // Reset write pointer to end of live data
   buf.view.setUint32(H_TEXT_POOL_WRITE_PTR, totalLiveBytes, true);
+  buf.view.setUint16(H_TEXT_POOL_COMPACTIONS, (getTextPoolCompactions(buf) + 1) & 0xffff, true);
 
   return oldWritePtr - totalLiveBytes; // Space reclaimed
 }
@@ -1716,7 +2612,7 @@ function readEventAt(buf: SharedBuffer, offset: number): Event {
   return {
     type: v.getUint8(offset),
     componentIndex: v.getUint16(offset + 2, true),
-    data: new Uint8Array(buf.raw, offset + 4, 16),
+    data: new Uint8Array(buf.raw, buf.byteOffset + offset + 4, 16),
   };
 }
 
@@ -1944,6 +2840,66 @@ export function getScrollY(buf: SharedBuffer, nodeIndex: number): number {
   return getI32(buf, nodeIndex, N_SCROLL_Y);
 }
 
+// --- Smooth scroll ---
+
+export function getScrollTargetX(buf: SharedBuffer, nodeIndex: number): number {
+  return getI32(buf, nodeIndex, N_SCROLL_TARGET_X);
+}
+
+export function getScrollTargetY(buf: SharedBuffer, nodeIndex: number): number {
+  return getI32(buf, nodeIndex, N_SCROLL_TARGET_Y);
+}
+
+export function setScrollVelocity(buf: SharedBuffer, nodeIndex: number, vx: number, vy: number): void {
+  setF32(buf, nodeIndex, N_SCROLL_VELOCITY_X, vx);
+  setF32(buf, nodeIndex, N_SCROLL_VELOCITY_Y, vy);
+}
+
+export function isScrollAnimating(buf: SharedBuffer, nodeIndex: number): boolean {
+  return getU8(buf, nodeIndex, N_SCROLL_ANIMATING) !== 0;
+}
+
+/** Acknowledge the pending target — Rust re-raises the flag on the next scroll */
+export function clearScrollAnimating(buf: SharedBuffer, nodeIndex: number): void {
+  setU8(buf, nodeIndex, N_SCROLL_ANIMATING, 0);
+}
+
+// --- Hover payload ---
+
+/** Tag a node so Rust exports it (and its region) while the mouse is over it. 0 = untagged. */
+export function setHoverTag(buf: SharedBuffer, nodeIndex: number, tag: number): void {
+  setU32(buf, nodeIndex, N_HOVER_TAG, tag >>> 0);
+}
+
+export function getHoverTag(buf: SharedBuffer, nodeIndex: number): number {
+  return getU32(buf, nodeIndex, N_HOVER_TAG);
+}
+
+export interface HoverPayload {
+  /** Bumped by Rust on every change — compare to skip redundant reads */
+  seq: number;
+  /** Nearest tagged node under the mouse, -1 = none */
+  index: number;
+  tag: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export function getHoverPayload(buf: SharedBuffer): HoverPayload {
+  const v = buf.view;
+  return {
+    seq: v.getUint32(H_HOVER_SEQ, true),
+    index: v.getInt32(H_HOVER_PAYLOAD_INDEX, true),
+    tag: v.getUint32(H_HOVER_TAG, true),
+    x: v.getUint16(H_HOVER_REGION_X, true),
+    y: v.getUint16(H_HOVER_REGION_Y, true),
+    width: v.getUint16(H_HOVER_REGION_W, true),
+    height: v.getUint16(H_HOVER_REGION_H, true),
+  };
+}
+
 // --- Output (Rust writes, TS reads) ---
 
 export function getComputedX(buf: SharedBuffer, nodeIndex: number): number {
@@ -6,7 +6,7 @@
  */
 
 import type { Notifier, SharedSlotBuffer, Source } from '@rlabs-inc/signals'
-import { HEADER_SIZE, NODE_STRIDE, DEFAULT_MAX_NODES } from './shared-buffer'
+import { HEADER_SIZE, NODE_STRIDE, DEFAULT_MAX_NODES, N_DIRTY_FLAGS } from './shared-buffer'
 
 type DataType = 'f32' | 'u32' | 'i32' | 'u16' | 'i16' | 'u8' | 'i8'
 
@@ -14,13 +14,18 @@ type DataType = 'f32' | 'u32' | 'i32' | 'u16' | 'i16' | 'u8' | 'i8'
  * Create a slot buffer for a specific field.
  * Direct DataView access - no Proxy overhead.
  * Implements full SharedSlotBuffer interface for compatibility with repeat().
+ *
+ * `marks` are DIRTY_* flags set on the node with every write: Rust lays out
+ * again only the nodes flagged DIRTY_LAYOUT / DIRTY_HIERARCHY, so fields
+ * layout reads must say when they change.
  */
 export function createSlotBuffer(
   view: DataView,
   fieldOffset: number,
   dataType: DataType,
   notifier: Notifier,
-  defaultValue: number = 0
+  defaultValue: number = 0,
+  marks: number = 0
 ): SharedSlotBuffer {
   // Create getter/setter based on data type
   let getter: (index: number) => number
@@ -57,6 +62,15 @@ export function createSlotBuffer(
       break
   }
 
+  if (marks !== 0) {
+    const write = setter
+    setter = (index: number, value: number) => {
+      write(index, value)
+      const flags = HEADER_SIZE + index * NODE_STRIDE + N_DIRTY_FLAGS
+      view.setUint8(flags, view.getUint8(flags) | marks)
+    }
+  }
+
   // Create a dummy typed array for the `raw` property (required by interface)
   // In this layout, we don't have contiguous typed arrays per field, so this is a placeholder
   const dummyRaw = new Float32Array(0)
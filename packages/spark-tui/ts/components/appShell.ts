@@ -0,0 +1,111 @@
+/**
+ * SparkTUI Components - App shell
+ *
+ * The usual application chrome, filled in by slots:
+ *
+ * ```
+ * ┌──────────── header ────────────┐
+ * │ sidebar │ content              │
+ * │         │                      │
+ * └────────── statusBar ───────────┘
+ * ```
+ *
+ * Every slot but `content` is optional. The shell fills the terminal; the
+ * content area takes what the other slots leave and scrolls when it
+ * overflows.
+ *
+ * ```ts
+ * appShell({
+ *   header: () => text({ content: 'My App', bold }),
+ *   sidebar: () => fileTree(),
+ *   sidebarVisible: showSidebar,
+ *   content: () => editor(),
+ *   statusBar: () => statusBar({ left: () => file.value, right: () => `Ln ${line.value}` }),
+ * })
+ * ```
+ */
+
+import { box } from '../primitives/box'
+import type { BoxProps, Cleanup, Reactive } from '../primitives/types'
+import { BorderStyle } from '../types'
+import type { Dimension } from '../types'
+import { t } from '../state/theme'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export interface AppShellProps extends Omit<BoxProps, 'children'> {
+  /** Top row(s) */
+  header?: () => void
+  /** Side panel next to the content */
+  sidebar?: () => void
+  /** Main area */
+  content: () => void
+  /** Bottom row, e.g. a statusBar() */
+  statusBar?: () => void
+  /** Sidebar width (default: 24) */
+  sidebarWidth?: Reactive<Dimension>
+  /** Which side the sidebar is on (default: 'left') */
+  sidebarSide?: 'left' | 'right'
+  /** Show or hide the sidebar (default: true) */
+  sidebarVisible?: Reactive<boolean>
+  /** Rule between sidebar and content (default: true) */
+  sidebarBorder?: boolean
+}
+
+const DEFAULT_SIDEBAR_WIDTH = 24
+
+// =============================================================================
+// APP SHELL
+// =============================================================================
+
+export function appShell(props: AppShellProps): Cleanup {
+  const {
+    header,
+    sidebar,
+    content,
+    statusBar,
+    sidebarWidth = DEFAULT_SIDEBAR_WIDTH,
+    sidebarSide = 'left',
+    sidebarVisible,
+    sidebarBorder = true,
+    ...rest
+  } = props
+
+  const side = () => {
+    if (!sidebar) return
+    const rule = sidebarBorder ? BorderStyle.SINGLE : undefined
+    box({
+      width: sidebarWidth,
+      flexShrink: 0,
+      flexDirection: 'column',
+      overflow: 'hidden',
+      visible: sidebarVisible,
+      borderRight: sidebarSide === 'left' ? rule : undefined,
+      borderLeft: sidebarSide === 'right' ? rule : undefined,
+      borderColor: t.border,
+      children: sidebar,
+    })
+  }
+
+  return box({
+    width: '100%',
+    height: '100%',
+    flexDirection: 'column',
+    ...rest,
+    children: () => {
+      if (header) box({ flexShrink: 0, flexDirection: 'column', children: header })
+      box({
+        flexGrow: 1,
+        flexDirection: 'row',
+        children: () => {
+          if (sidebarSide === 'left') side()
+          box({ flexGrow: 1, flexDirection: 'column', overflow: 'auto', children: content })
+          if (sidebarSide === 'right') side()
+        },
+      })
+      if (statusBar) box({ flexShrink: 0, flexDirection: 'column', children: statusBar })
+    },
+  })
+}
@@ -0,0 +1,130 @@
+/**
+ * SparkTUI Components - Breadcrumbs
+ *
+ * A path of clickable segments, `Home › Projects › spark › src`:
+ *
+ * - Clicking a segment (or `onSelect`) jumps there; the last is the current
+ *   place, shown bright
+ * - When the path is wider than the row, the middle collapses into `…`,
+ *   keeping the first segment and as many of the last as fit; clicking `…`
+ *   lists the hidden ones in a menu
+ *
+ * ```ts
+ * breadcrumbs({
+ *   items: () => cwd.value.split('/').filter(Boolean),
+ *   onSelect: (i) => cd(parts.slice(0, i + 1)),
+ * })
+ * ```
+ */
+
+import { signal, derived } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { forEach } from '../primitives/each'
+import { scoped, onCleanup } from '../primitives/scope'
+import type { BoxProps, Cleanup, Reactive } from '../primitives/types'
+import { stringWidth } from '../types/color'
+import { getIndex } from '../engine/registry'
+import { observeComponents } from '../engine/observers'
+import type { ComponentObserver } from '../engine/observers'
+import { t } from '../state/theme'
+import { openContextMenu, screenPosition } from './contextMenu'
+import { unwrap } from '../primitives/utils'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export interface BreadcrumbsProps extends Omit<BoxProps, 'children'> {
+  /** Segment labels, outermost first */
+  items: Reactive<readonly string[]>
+  /** Called with the index of the segment chosen */
+  onSelect?(index: number): void
+  /** Between segments (default: ' › ') */
+  separator?: string
+}
+
+/** What shows: a segment, or the collapsed ones */
+type Crumb = { kind: 'item'; index: number } | { kind: 'more'; hidden: number[] }
+
+const ELLIPSIS = '…'
+
+let crumbsCount = 0
+
+// =============================================================================
+// BREADCRUMBS
+// =============================================================================
+
+export function breadcrumbs(props: BreadcrumbsProps): Cleanup {
+  const { items, onSelect, separator = ' › ', ...rest } = props
+
+  return scoped(() => {
+    const id = props.id ?? `breadcrumbs-${++crumbsCount}`
+    const width = signal(Infinity)
+    const observer: ComponentObserver = {
+      layoutChanged: (component, rect) => {
+        if (component.id === id) width.value = rect.width
+      },
+    }
+    onCleanup(observeComponents(observer))
+
+    const labels = derived(() => unwrap(items))
+    const crumbs = derived((): Crumb[] => {
+      const all = labels.value
+      const widthOf = (i: number) => stringWidth(all[i]!) + (i > 0 ? stringWidth(separator) : 0)
+      const total = all.reduce((sum, _, i) => sum + widthOf(i), 0)
+      const shown: Crumb[] = all.map((_, index) => ({ kind: 'item', index }))
+      if (total <= width.value || all.length <= 2) return shown
+
+      // First segment, `…`, then the most trailing segments that fit (at least the last)
+      let used = widthOf(0) + stringWidth(separator + ELLIPSIS)
+      let from = all.length - 1
+      used += widthOf(from)
+      while (from - 1 > 1 && used + widthOf(from - 1) <= width.value) used += widthOf(--from)
+      const hidden = Array.from({ length: from - 1 }, (_, i) => i + 1)
+      return [{ kind: 'item', index: 0 }, { kind: 'more', hidden }, ...shown.slice(from)]
+    })
+
+    box({
+      id,
+      flexDirection: 'row',
+      overflow: 'hidden',
+      ...rest,
+      children: () => {
+        forEach(crumbs, (crumb) => (crumb.kind === 'item' ? crumb.index : ELLIPSIS), (crumb, position) => {
+          const isLast = () => {
+            const c = crumb()
+            return c.kind === 'item' && c.index === labels.value.length - 1
+          }
+          return box({
+            flexDirection: 'row',
+            shrink: 0,
+            children: () => {
+              text({ content: () => (position() > 0 ? separator : ''), fg: t.textMuted })
+              text({
+                id: crumb().kind === 'more' ? `${id}-more` : undefined,
+                content: () => {
+                  const c = crumb()
+                  return c.kind === 'item' ? labels.value[c.index] ?? '' : ELLIPSIS
+                },
+                fg: () => (isLast() ? t.textBright.value : t.primary.value),
+                bold: isLast,
+                onClick: (event) => {
+                  const c = crumb()
+                  if (c.kind === 'item') return onSelect?.(c.index)
+                  const index = getIndex(`${id}-more`)
+                  const at = index !== undefined ? screenPosition(index) : { x: event.x, y: event.y }
+                  openContextMenu(
+                    at.x,
+                    at.y + 1,
+                    c.hidden.map((i) => ({ label: labels.value[i]!, onSelect: () => onSelect?.(i) }))
+                  )
+                },
+              })
+            },
+          })
+        })
+      },
+    })
+  })
+}
@@ -0,0 +1,379 @@
+/**
+ * SparkTUI Components - Calendar and date picker
+ *
+ * `calendar` is a month grid bound to a date signal:
+ *
+ * - ←→ move a day, ↑↓ a week, PgUp/PgDn a month, Shift+PgUp/PgDn a year,
+ *   Home/End to the start/end of the week, `t` to today
+ * - Enter or Space (or a click) selects the highlighted day; the wheel and
+ *   the ‹ › arrows change month
+ * - Today is underlined, the selected day reversed, days of the next and
+ *   previous months dimmed
+ * - Month and weekday names, and the first day of the week, follow the
+ *   locale; both can be set
+ * - `min`, `max` and `isDisabled` keep days from being chosen
+ *
+ * `datePicker` is the compact form: a field showing the date that opens the
+ * calendar in a popup beneath it, closed by a choice, Escape or a click
+ * outside.
+ *
+ * ```ts
+ * const due = signal<Date | null>(null)
+ * calendar({ value: due, firstDayOfWeek: 1 })
+ * datePicker({ value: due, min: new Date(), placeholder: 'Due date' })
+ * ```
+ */
+
+import { signal, derived, effect } from '@rlabs-inc/signals'
+import type { WritableSignal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { scoped, onCleanup } from '../primitives/scope'
+import type { BoxProps, Cleanup } from '../primitives/types'
+import { BorderStyle } from '../types'
+import { opaque } from '../types/color'
+import { getBuffer, isInitialized } from '../bridge'
+import { getTerminalSize } from '../bridge/shared-buffer'
+import type { MouseEvent } from '../engine/events'
+import { getIndex } from '../engine/registry'
+import { matchesKey, isPress } from '../state/keyboard'
+import { focus } from '../state/focus'
+import { t } from '../state/theme'
+import { screenPosition } from './contextMenu'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+interface DateOptions {
+  /** The selected date (time of day is ignored) */
+  value: WritableSignal<Date | null>
+  /** BCP 47 locale for names and the week (default: the system's) */
+  locale?: string
+  /** 0 = Sunday … 6 = Saturday (default: the locale's) */
+  firstDayOfWeek?: number
+  /** Earliest day that can be chosen */
+  min?: Date
+  /** Latest day that can be chosen */
+  max?: Date
+  /** Days that can't be chosen, e.g. weekends */
+  isDisabled?(date: Date): boolean
+  /** Called with each day chosen */
+  onSelect?(date: Date): void
+}
+
+export interface CalendarProps extends DateOptions, Omit<BoxProps, 'children' | 'onKey'> {}
+
+export interface DatePickerProps extends DateOptions, Omit<BoxProps, 'children' | 'onKey' | 'onClick'> {
+  /** Shown while no date is selected (default: 'Select a date') */
+  placeholder?: string
+  /** How the field shows the date (default: the locale's medium date) */
+  format?(date: Date): string
+}
+
+type WeekInfo = { firstDay: number }
+
+const DAYS_PER_WEEK = 7
+/** Enough weeks for any month, so the grid never changes height */
+const WEEKS = 6
+/** Two digits and a space before them */
+const CELL_WIDTH = 3
+const GRID_WIDTH = DAYS_PER_WEEK * CELL_WIDTH
+const MONTHS_PER_YEAR = 12
+/** Weeks start on Monday where the runtime can't say */
+const FALLBACK_FIRST_DAY = 1
+/** Above context menus, below dialogs */
+const POPUP_Z_INDEX = 1500
+/** Border, header, weekday names and the weeks */
+const POPUP_HEIGHT = 2 + 2 + WEEKS
+const POPUP_WIDTH = GRID_WIDTH + 4
+
+let calendarCount = 0
+
+// =============================================================================
+// DATES
+// =============================================================================
+
+const dayOf = (d: Date) => new Date(d.getFullYear(), d.getMonth(), d.getDate())
+const addDays = (d: Date, days: number) => new Date(d.getFullYear(), d.getMonth(), d.getDate() + days)
+const sameDay = (a: Date | null, b: Date | null) =>
+  !!a && !!b && a.getFullYear() === b.getFullYear() && a.getMonth() === b.getMonth() && a.getDate() === b.getDate()
+
+/** The same day `months` later, or the month's last day when it's shorter */
+function addMonths(d: Date, months: number): Date {
+  const first = new Date(d.getFullYear(), d.getMonth() + months, 1)
+  const length = new Date(first.getFullYear(), first.getMonth() + 1, 0).getDate()
+  return new Date(first.getFullYear(), first.getMonth(), Math.min(d.getDate(), length))
+}
+
+/** The locale's first day of the week, 0 = Sunday */
+function localeFirstDay(locale: string | undefined): number {
+  try {
+    const info = new Intl.Locale(locale ?? Intl.DateTimeFormat().resolvedOptions().locale) as Intl.Locale & {
+      getWeekInfo?(): WeekInfo
+      weekInfo?: WeekInfo
+    }
+    const week = info.getWeekInfo?.() ?? info.weekInfo
+    // Intl counts Monday = 1 … Sunday = 7
+    if (week) return week.firstDay % DAYS_PER_WEEK
+  } catch {
+    // Unknown locale: fall through
+  }
+  return FALLBACK_FIRST_DAY
+}
+
+// =============================================================================
+// CALENDAR
+// =============================================================================
+
+/** Month grid for `calendar` and the picker's popup */
+function monthGrid(options: DateOptions & { id: string; onChoose(date: Date): void; onEscape?(): void }, rest: Omit<BoxProps, 'children' | 'onKey'>): void {
+  const { value, locale, min, max, isDisabled, onChoose, onEscape, id } = options
+  const firstDay = options.firstDayOfWeek ?? localeFirstDay(locale)
+  const monthName = new Intl.DateTimeFormat(locale, { month: 'long', year: 'numeric' })
+  const weekdayName = new Intl.DateTimeFormat(locale, { weekday: 'short' })
+
+  const cursor = signal(dayOf(value.value ?? new Date()))
+  // The highlight follows a date set from outside
+  effect(() => {
+    if (value.value) cursor.value = dayOf(value.value)
+  })
+
+  const allowed = (d: Date) => (!min || d >= dayOf(min)) && (!max || d <= dayOf(max)) && !isDisabled?.(d)
+  const moveTo = (d: Date) => {
+    let next = d
+    if (min && next < dayOf(min)) next = dayOf(min)
+    if (max && next > dayOf(max)) next = dayOf(max)
+    cursor.value = next
+  }
+  const choose = (d: Date) => {
+    if (!allowed(d)) return
+    cursor.value = d
+    value.value = d
+    onChoose(d)
+  }
+
+  const cells = derived(() => {
+    const first = new Date(cursor.value.getFullYear(), cursor.value.getMonth(), 1)
+    const start = addDays(first, -((first.getDay() - firstDay + DAYS_PER_WEEK) % DAYS_PER_WEEK))
+    return Array.from({ length: WEEKS * DAYS_PER_WEEK }, (_, i) => addDays(start, i))
+  })
+  const today = derived(() => {
+    // Re-read with the month, so a grid left open overnight catches up
+    void cells.value
+    return dayOf(new Date())
+  })
+
+  box({
+    id,
+    flexDirection: 'column',
+    focusable: true,
+    width: GRID_WIDTH,
+    role: 'table',
+    label: () => monthName.format(cursor.value),
+    ...rest,
+    onKey: (event) => {
+      if (!isPress(event)) return
+      const at = cursor.value
+      const weekday = (at.getDay() - firstDay + DAYS_PER_WEEK) % DAYS_PER_WEEK
+      if (matchesKey(event, 'ArrowLeft')) moveTo(addDays(at, -1))
+      else if (matchesKey(event, 'ArrowRight')) moveTo(addDays(at, 1))
+      else if (matchesKey(event, 'ArrowUp')) moveTo(addDays(at, -DAYS_PER_WEEK))
+      else if (matchesKey(event, 'ArrowDown')) moveTo(addDays(at, DAYS_PER_WEEK))
+      else if (matchesKey(event, 'Shift+PageUp')) moveTo(addMonths(at, -MONTHS_PER_YEAR))
+      else if (matchesKey(event, 'Shift+PageDown')) moveTo(addMonths(at, MONTHS_PER_YEAR))
+      else if (matchesKey(event, 'PageUp')) moveTo(addMonths(at, -1))
+      else if (matchesKey(event, 'PageDown')) moveTo(addMonths(at, 1))
+      else if (matchesKey(event, 'Home')) moveTo(addDays(at, -weekday))
+      else if (matchesKey(event, 'End')) moveTo(addDays(at, DAYS_PER_WEEK - 1 - weekday))
+      else if (matchesKey(event, 't')) moveTo(dayOf(new Date()))
+      else if (matchesKey(event, 'Enter') || matchesKey(event, 'Space')) choose(at)
+      else if (matchesKey(event, 'Escape') && onEscape) onEscape()
+      else return
+      return true
+    },
+    onScroll: (event) => moveTo(addMonths(cursor.value, Math.sign(event.deltaY))),
+    children: () => {
+      box({
+        flexDirection: 'row',
+        children: () => {
+          text({ content: '‹', fg: t.primary, shrink: 0, onClick: () => moveTo(addMonths(cursor.value, -1)) })
+          text({ content: () => monthName.format(cursor.value), grow: 1, align: 'center', bold: true, fg: t.textBright })
+          text({ content: '›', fg: t.primary, shrink: 0, onClick: () => moveTo(addMonths(cursor.value, 1)) })
+        },
+      })
+
+      box({
+        flexDirection: 'row',
+        children: () => {
+          for (let day = 0; day < DAYS_PER_WEEK; day++) {
+            // Any week will do for the names; this one starts on a Sunday
+            const name = weekdayName.format(new Date(2023, 0, 1 + ((firstDay + day) % DAYS_PER_WEEK)))
+            text({ content: name.slice(0, CELL_WIDTH - 1).padStart(CELL_WIDTH), width: CELL_WIDTH, shrink: 0, fg: t.textMuted })
+          }
+        },
+      })
+
+      for (let week = 0; week < WEEKS; week++) {
+        box({
+          flexDirection: 'row',
+          children: () => {
+            for (let day = 0; day < DAYS_PER_WEEK; day++) {
+              const date = () => cells.value[week * DAYS_PER_WEEK + day]!
+              const isCursor = () => sameDay(date(), cursor.value)
+              const isSelected = () => sameDay(date(), value.value)
+              const inMonth = () => date().getMonth() === cursor.value.getMonth()
+              text({
+                content: () => String(date().getDate()).padStart(CELL_WIDTH),
+                width: CELL_WIDTH,
+                shrink: 0,
+                bg: () => (isCursor() ? t.primary.value : null),
+                fg: () =>
+                  isCursor() ? t.textBright.value
+                  : !allowed(date()) ? t.textDisabled.value
+                  : sameDay(date(), today.value) ? t.accent.value
+                  : inMonth() ? t.text.value
+                  : t.textMuted.value,
+                inverse: () => isSelected() && !isCursor(),
+                underline: () => sameDay(date(), today.value),
+                dim: () => !inMonth(),
+                onClick: () => choose(date()),
+              })
+            }
+          },
+        })
+      }
+    },
+  })
+}
+
+export function calendar(props: CalendarProps): Cleanup {
+  const { value, locale, firstDayOfWeek, min, max, isDisabled, onSelect, id, ...rest } = props
+  return scoped(() => {
+    monthGrid(
+      {
+        id: id ?? `calendar-${++calendarCount}`,
+        value,
+        locale,
+        firstDayOfWeek,
+        min,
+        max,
+        isDisabled,
+        onChoose: (date) => onSelect?.(date),
+      },
+      rest
+    )
+  })
+}
+
+// =============================================================================
+// DATE PICKER
+// =============================================================================
+
+export function datePicker(props: DatePickerProps): Cleanup {
+  const { value, locale, firstDayOfWeek, min, max, isDisabled, onSelect, placeholder = 'Select a date', format, id, ...rest } = props
+  const fieldId = id ?? `date-picker-${++calendarCount}`
+  const formatter = new Intl.DateTimeFormat(locale, { dateStyle: 'medium' })
+  const display = format ?? ((date: Date) => formatter.format(date))
+
+  let popup: Cleanup | null = null
+  const dismiss = () => {
+    popup?.()
+    popup = null
+  }
+  /** Close the popup and give the field its focus back */
+  const close = () => {
+    if (!popup) return
+    dismiss()
+    focus(fieldId)
+  }
+
+  const open = () => {
+    if (popup || !isInitialized()) return
+    const index = getIndex(fieldId)
+    if (index === undefined) return
+    const { x, y } = screenPosition(index)
+    const { width: screenW, height: screenH } = getTerminalSize(getBuffer())
+    // Beneath the field, or above it when that runs off screen
+    const below = y + 1
+    const top = below + POPUP_HEIGHT > screenH ? Math.max(0, y - POPUP_HEIGHT) : below
+    const left = Math.max(0, Math.min(x, screenW - POPUP_WIDTH))
+    const backdropId = `${fieldId}-popup`
+    const closeOutside = (event: MouseEvent) => {
+      if (event.componentIndex === getIndex(backdropId)) close()
+    }
+
+    popup = scoped(() => {
+      box({
+        id: backdropId,
+        width: '100%',
+        height: '100%',
+        alignItems: 'flex-start',
+        zIndex: POPUP_Z_INDEX,
+        // Transparent, but a click anywhere beside the popup closes it
+        pointerEvents: 'all',
+        trapFocus: true,
+        onClick: closeOutside,
+        children: () => {
+          box({
+            marginLeft: left,
+            marginTop: top,
+            border: BorderStyle.ROUNDED,
+            borderColor: t.primary,
+            bg: () => opaque(t.surface.value),
+            paddingLeft: 1,
+            paddingRight: 1,
+            children: () => {
+              monthGrid(
+                {
+                  id: `${fieldId}-calendar`,
+                  value,
+                  locale,
+                  firstDayOfWeek,
+                  min,
+                  max,
+                  isDisabled,
+                  onChoose: (date) => {
+                    close()
+                    onSelect?.(date)
+                  },
+                  onEscape: close,
+                },
+                {}
+              )
+            },
+          })
+        },
+      })
+      focus(`${fieldId}-calendar`)
+    })
+  }
+
+  return scoped(() => {
+    box({
+      id: fieldId,
+      flexDirection: 'row',
+      focusable: true,
+      gap: 1,
+      ...rest,
+      onKey: (event) => {
+        if (!isPress(event)) return
+        if (matchesKey(event, 'Enter') || matchesKey(event, 'Space') || matchesKey(event, 'ArrowDown')) open()
+        else return
+        return true
+      },
+      onClick: open,
+      children: () => {
+        text({
+          content: () => (value.value ? display(value.value) : placeholder),
+          fg: () => (value.value ? t.text.value : t.textMuted.value),
+          grow: 1,
+          wrap: 'truncate',
+        })
+        text({ content: '▾', fg: t.primary, shrink: 0 })
+      },
+    })
+    // The popup goes when the field does
+    onCleanup(dismiss)
+  })
+}
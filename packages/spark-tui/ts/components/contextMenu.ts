@@ -0,0 +1,451 @@
+/**
+ * SparkTUI Components - Context Menu
+ *
+ * Right-click menus, registered per component:
+ *
+ * - Items with a label, a shortcut hint, a callback, or a submenu; check and
+ *   radio items show and set a signal
+ * - Opens at the click position, moved or flipped to stay on screen
+ * - A focus-trapped overlay: keys go to the menu, a click outside closes it
+ * - ↑↓ move, → / Enter open a submenu, ← closes one, Esc closes the menu
+ * - Shift+F10 opens the focused component's menu from the keyboard
+ *
+ * ```ts
+ * box({ id: 'file-row', children: () => text({ content: 'notes.md' }) })
+ * contextMenu('file-row', [
+ *   { label: 'Open', shortcut: 'Enter', onSelect: open },
+ *   { label: 'Rename', shortcut: 'F2', onSelect: rename },
+ *   '-',
+ *   { label: 'Move to', submenu: folders.map((f) => ({ label: f, onSelect: () => move(f) })) },
+ * ])
+ * ```
+ *
+ * The innermost component with a menu wins: a right-click on a row with its
+ * own menu doesn't also open the list's.
+ */
+
+import { signal, derived } from '@rlabs-inc/signals'
+import type { WritableSignal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { each } from '../primitives/each'
+import { scoped } from '../primitives/scope'
+import type { Cleanup } from '../primitives/types'
+import { BorderStyle } from '../types'
+import { opaque, stringWidth } from '../types/color'
+import { isInitialized, getBuffer, bridgeState } from '../bridge'
+import {
+  getParentIndex,
+  getComputedX,
+  getComputedY,
+  getScrollX,
+  getScrollY,
+} from '../bridge/shared-buffer'
+import {
+  EventType,
+  registerMouseHandler,
+  hasShift,
+  terminalSize,
+  KEY_F10,
+  type KeyEvent,
+  type MouseEvent,
+} from '../engine/events'
+import { getIndex } from '../engine/registry'
+import { onDestroyOf } from '../engine/lifecycle'
+import { on, matchesKey, isPress } from '../state/keyboard'
+import { focusedIndex } from '../state/focus'
+import { t } from '../state/theme'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export interface MenuItem {
+  label: string
+  /** Shortcut hint shown on the right (display only — bind the key yourself) */
+  shortcut?: string
+  /** Called after the menu closes */
+  onSelect?(): void
+  /** Opens beside this item */
+  submenu?: MenuEntry[]
+  /** Shown dimmed, can't be chosen */
+  disabled?: boolean
+  /** Check item: ticked while true, toggled when chosen */
+  checked?: WritableSignal<boolean>
+  /** Radio item: marked while `selected` holds `value`, which choosing it sets */
+  radio?: RadioBinding
+}
+
+export interface RadioBinding<T = unknown> {
+  selected: WritableSignal<T>
+  value: T
+}
+
+export interface MenuOptions {
+  /**
+   * Called on ← in the top menu, or → on an item without a submenu — a menu
+   * bar opens the neighbouring menu. The menu stays open; open another to
+   * replace it.
+   */
+  onLeave?(direction: -1 | 1): void
+  /** Called once the menu has closed, however it closed */
+  onClose?(): void
+}
+
+/** An item, or '-' for a separator line */
+export type MenuEntry = MenuItem | '-'
+
+/** Items, or a function building them when the menu opens */
+export type MenuItems = MenuEntry[] | (() => MenuEntry[])
+
+/** One open panel: the root menu or a submenu */
+interface Level {
+  key: string
+  depth: number
+  entries: MenuEntry[]
+  x: number
+  y: number
+  /** Offset from the end of the previous panel — panels sit in a row */
+  marginLeft: number
+  width: number
+  /** Columns for check/radio marks, labels and shortcut hints */
+  markWidth: number
+  labelWidth: number
+  hintWidth: number
+}
+
+/** Stacks above the app */
+const MENU_Z_INDEX = 1000
+const SUBMENU_ARROW = '▸'
+const CHECK_MARK = '✓'
+const RADIO_MARK = '●'
+/** A mark and the space after it */
+const MARK_WIDTH = 2
+
+// =============================================================================
+// REGISTRATION
+// =============================================================================
+
+/** One instance's menus: indices are per bridge */
+interface Registration {
+  menus: Map<number, MenuItems>
+  /** The Shift+F10 handler, installed while any menu is registered */
+  unsubKeyboard: (() => void) | null
+}
+
+const registration = bridgeState<Registration>(() => ({ menus: new Map(), unsubKeyboard: null }))
+/** Context menu events an inner component's menu already took */
+const handled = new WeakSet<MouseEvent>()
+
+/**
+ * Give a component (index or id) a right-click menu.
+ * Returns a function that removes it; releasing the component removes it too.
+ */
+export function contextMenu(target: number | string, items: MenuItems): () => void {
+  const index = typeof target === 'number' ? target : getIndex(target)
+  if (index === undefined) {
+    throw new Error(`contextMenu(): no component with id '${target}'`)
+  }
+
+  const state = registration()
+  state.menus.set(index, items)
+  const unsubMouse = registerMouseHandler(index, EventType.ContextMenu, (event) => {
+    if (handled.has(event)) return
+    handled.add(event)
+    openContextMenu(event.x, event.y, items)
+  })
+  state.unsubKeyboard ??= on(openFromKeyboard)
+
+  let removed = false
+  const remove = () => {
+    if (removed) return
+    removed = true
+    unsubMouse()
+    if (state.menus.get(index) === items) state.menus.delete(index)
+    if (state.menus.size === 0) {
+      state.unsubKeyboard?.()
+      state.unsubKeyboard = null
+    }
+  }
+  onDestroyOf(index, remove)
+  return remove
+}
+
+/** Shift+F10: the menu of the focused component, or its nearest ancestor with one */
+function openFromKeyboard(event: KeyEvent): boolean | void {
+  if (!isPress(event) || event.keycode !== KEY_F10 || !hasShift(event)) return
+  if (!isInitialized() || current) return
+  const buf = getBuffer()
+  const { menus } = registration()
+  for (let i = focusedIndex.value; i >= 0; i = getParentIndex(buf, i)) {
+    const items = menus.get(i)
+    if (items) {
+      const { x, y } = screenPosition(i)
+      openContextMenu(x, y, items)
+      return true
+    }
+  }
+}
+
+/** Top-left corner of a component on screen */
+export function screenPosition(index: number): { x: number; y: number } {
+  const buf = getBuffer()
+  let x = 0
+  let y = 0
+  for (let i = index; i >= 0; i = getParentIndex(buf, i)) {
+    x += getComputedX(buf, i)
+    y += getComputedY(buf, i)
+    const parent = getParentIndex(buf, i)
+    if (parent >= 0) {
+      x -= getScrollX(buf, parent)
+      y -= getScrollY(buf, parent)
+    }
+  }
+  return { x, y }
+}
+
+// =============================================================================
+// OVERLAY
+// =============================================================================
+
+let current: Cleanup | null = null
+let currentOptions: MenuOptions = {}
+let menuCount = 0
+
+/** Open a menu at a screen position (closing any open one) */
+export function openContextMenu(x: number, y: number, items: MenuItems, options: MenuOptions = {}): void {
+  closeContextMenu()
+  const entries = typeof items === 'function' ? items() : items
+  if (!isInitialized() || firstSelectable(entries) < 0) return
+  currentOptions = options
+  current = scoped(() => renderMenu(x, y, entries, options))
+}
+
+/** Close the open menu, if any */
+export function closeContextMenu(): void {
+  const close = current
+  const { onClose } = currentOptions
+  current = null
+  currentOptions = {}
+  if (!close) return
+  close()
+  onClose?.()
+}
+
+/** Whether a menu is open */
+export function isContextMenuOpen(): boolean {
+  return current !== null
+}
+
+const isSelectable = (entry: MenuEntry | undefined): entry is MenuItem =>
+  entry !== undefined && entry !== '-' && !entry.disabled
+
+function firstSelectable(entries: MenuEntry[]): number {
+  return entries.findIndex(isSelectable)
+}
+
+function markOf(item: MenuItem): string {
+  if (item.checked) return item.checked.value ? CHECK_MARK : ''
+  if (item.radio) return item.radio.selected.value === item.radio.value ? RADIO_MARK : ''
+  return ''
+}
+
+function renderMenu(originX: number, originY: number, rootEntries: MenuEntry[], options: MenuOptions): void {
+  /** Highlighted row of each open panel; the last one has the keyboard */
+  const path = signal([firstSelectable(rootEntries)])
+
+  const levels = derived(() => {
+    const { width: screenW, height: screenH } = terminalSize()
+    const result: Level[] = []
+    let entries = rootEntries
+    let x = originX
+    let y = originY
+    let parentX = originX
+    let panelEnd = 0
+
+    const rows = path.value
+    for (let depth = 0; depth < rows.length; depth++) {
+      const row = rows[depth]!
+      const items = entries.filter((e): e is MenuItem => e !== '-')
+      const labelWidth = Math.max(0, ...items.map((item) => stringWidth(item.label)))
+      const hasHint = items.some((item) => item.shortcut || item.submenu)
+      const hintWidth = hasHint
+        ? Math.max(0, ...items.map((item) => stringWidth(hintOf(item))))
+        : 0
+      const markWidth = items.some((item) => item.checked || item.radio) ? MARK_WIDTH : 0
+      // Border + a space either side + mark, label, two spaces, hint
+      const width = markWidth + labelWidth + (hasHint ? hintWidth + 2 : 0) + 4
+      const height = entries.length + 2
+
+      // Submenus open to the right, or to the left when that runs off screen
+      if (depth > 0 && x + width > screenW) x = parentX - width
+      x = Math.max(0, Math.min(x, screenW - width))
+      y = Math.max(0, Math.min(y, screenH - height))
+
+      result.push({
+        key: rows.slice(0, depth).join('/'),
+        depth,
+        entries,
+        x,
+        y,
+        marginLeft: x - panelEnd,
+        width,
+        markWidth,
+        labelWidth,
+        hintWidth,
+      })
+
+      const item = entries[row]
+      if (depth + 1 === rows.length || !isSelectable(item) || !item.submenu) break
+      parentX = x
+      panelEnd = x + width
+      entries = item.submenu
+      x += width
+      y += row
+    }
+    return result
+  })
+
+  const deepest = () => levels.value[levels.value.length - 1]!
+
+  const move = (delta: number) => {
+    const { entries } = deepest()
+    const rows = path.value
+    let row = rows[rows.length - 1]!
+    for (let step = 0; step < entries.length; step++) {
+      row = (row + delta + entries.length) % entries.length
+      if (isSelectable(entries[row])) break
+    }
+    path.value = [...rows.slice(0, -1), row]
+  }
+
+  const openSubmenu = (depth: number, row: number): boolean => {
+    const item = levels.value[depth]?.entries[row]
+    if (!isSelectable(item) || !item.submenu) return false
+    const first = firstSelectable(item.submenu)
+    if (first < 0) return false
+    path.value = [...path.value.slice(0, depth), row, first]
+    return true
+  }
+
+  const choose = (depth: number, row: number) => {
+    const item = levels.value[depth]?.entries[row]
+    if (!isSelectable(item) || openSubmenu(depth, row)) return
+    closeContextMenu()
+    if (item.checked) item.checked.value = !item.checked.value
+    if (item.radio) item.radio.selected.value = item.radio.value
+    item.onSelect?.()
+  }
+
+  const hover = (depth: number, row: number) => {
+    if (!isSelectable(levels.value[depth]?.entries[row])) return
+    if (path.value[depth] === row && path.value.length > depth + 1) return
+    path.value = [...path.value.slice(0, depth), row]
+  }
+
+  const onKey = (event: KeyEvent): boolean => {
+    if (!isPress(event)) return true
+    const depth = path.value.length - 1
+    if (matchesKey(event, 'ArrowDown')) move(1)
+    else if (matchesKey(event, 'ArrowUp')) move(-1)
+    else if (matchesKey(event, 'ArrowRight')) {
+      if (!openSubmenu(depth, path.value[depth]!)) options.onLeave?.(1)
+    }
+    else if (matchesKey(event, 'Enter') || matchesKey(event, 'Space')) choose(depth, path.value[depth]!)
+    else if (matchesKey(event, 'Escape')) closeContextMenu()
+    else if (matchesKey(event, 'ArrowLeft')) {
+      if (depth > 0) path.value = path.value.slice(0, -1)
+      else options.onLeave?.(-1)
+    }
+    // Everything else stops here too: the menu has the keyboard
+    return true
+  }
+
+  // Full-screen backdrop: traps focus, and a click on it (outside the panels)
+  // closes. Transparent, so it takes the mouse with pointerEvents: 'all'
+  const backdropId = `context-menu-${++menuCount}`
+  const closeOutside = (event: MouseEvent) => {
+    if (event.componentIndex === getIndex(backdropId)) closeContextMenu()
+  }
+
+  box({
+    id: backdropId,
+    width: '100%',
+    height: '100%',
+    flexDirection: 'row',
+    alignItems: 'flex-start',
+    zIndex: MENU_Z_INDEX,
+    pointerEvents: 'all',
+    focusable: true,
+    trapFocus: true,
+    onKey,
+    onClick: closeOutside,
+    onContextMenu: closeOutside,
+    children: () => {
+      each(
+        () => levels.value,
+        (getLevel) => panel(getLevel, { path, choose, hover }),
+        { key: (level) => level.key }
+      )
+    },
+  })
+}
+
+function hintOf(item: MenuItem): string {
+  return item.submenu ? SUBMENU_ARROW : item.shortcut ?? ''
+}
+
+interface PanelActions {
+  path: { readonly value: number[] }
+  choose(depth: number, row: number): void
+  hover(depth: number, row: number): void
+}
+
+function panel(getLevel: () => Level, { path, choose, hover }: PanelActions): Cleanup {
+  return scoped(() => {
+    const { depth, entries, width, markWidth, labelWidth, hintWidth } = getLevel()
+    const inner = width - 2
+
+    box({
+      width,
+      shrink: 0,
+      marginLeft: () => getLevel().marginLeft,
+      marginTop: () => getLevel().y,
+      border: BorderStyle.ROUNDED,
+      borderColor: t.textMuted,
+      bg: () => opaque(t.surface.value),
+      flexDirection: 'column',
+      children: () => {
+        entries.forEach((entry, row) => {
+          if (entry === '-') {
+            text({ content: '─'.repeat(inner), fg: t.textMuted })
+            return
+          }
+          const highlighted = () => path.value[depth] === row
+          const fg = () => (entry.disabled ? t.textDisabled.value : highlighted() ? t.textBright.value : t.text.value)
+          const hint = hintOf(entry)
+          box({
+            flexDirection: 'row',
+            width: inner,
+            bg: () => (highlighted() ? t.primary.value : opaque(t.surface.value)),
+            onClick: () => choose(depth, row),
+            onMouseEnter: () => hover(depth, row),
+            children: () => {
+              if (markWidth > 0) {
+                text({ content: () => ' ' + pad(markOf(entry), markWidth), fg: () => (highlighted() ? fg() : t.primary.value) })
+              }
+              text({ content: (markWidth > 0 ? '' : ' ') + pad(entry.label, labelWidth) + ' ', fg })
+              if (hintWidth > 0) {
+                text({ content: ' ' + ' '.repeat(hintWidth - stringWidth(hint)) + hint + ' ', fg: () => (highlighted() ? fg() : t.textMuted.value) })
+              }
+            },
+          })
+        })
+      },
+    })
+  })
+}
+
+function pad(label: string, width: number): string {
+  return label + ' '.repeat(Math.max(0, width - stringWidth(label)))
+}
@@ -0,0 +1,374 @@
+/**
+ * SparkTUI Components - Dashboard grid
+ *
+ * Panels laid out on a grid of equal tracks that the user can rearrange:
+ *
+ * - Dragging a panel's title bar moves it, dragging its `◢` corner resizes
+ *   it; a ghost outline shows where it will land, snapped to the tracks,
+ *   and turns red where it would overlap another panel or leave the grid
+ * - With a panel focused, `m` starts arranging it from the keyboard:
+ *   arrows move the ghost, Shift+arrows resize it, Enter drops it there,
+ *   Esc leaves it where it was
+ * - The arrangement is a plain `DashboardLayout` — JSON in, JSON out — so
+ *   it can be saved on change and handed back on the next run; panels the
+ *   saved layout doesn't know about, or places that no longer fit (a grid
+ *   with fewer columns, an overlap), take their default place; it is also
+ *   part of `snapshotLayout()` under the grid's ID
+ *
+ * ```ts
+ * const layout = signal<DashboardLayout>(loadJson('layout.json') ?? { version: 1, panels: {} })
+ * dashboardGrid({
+ *   columns: 4,
+ *   rows: 3,
+ *   layout,
+ *   onLayoutChange: (next) => saveJson('layout.json', next),
+ *   panels: [
+ *     { id: 'cpu', title: 'CPU', place: { col: 0, row: 0, colSpan: 2 }, render: () => cpuChart() },
+ *     { id: 'logs', title: 'Logs', place: { col: 0, row: 1, colSpan: 4, rowSpan: 2 }, render: () => logView({ lines }) },
+ *   ],
+ * })
+ * ```
+ */
+
+import { signal, derived } from '@rlabs-inc/signals'
+import type { WritableSignal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { show } from '../primitives/show'
+import { scoped, onCleanup } from '../primitives/scope'
+import type { BoxProps, Cleanup } from '../primitives/types'
+import { BorderStyle } from '../types'
+import { EventType } from '../engine/events'
+import type { KeyEvent, MouseEvent } from '../engine/events'
+import { getIndex } from '../engine/registry'
+import { observeComponents } from '../engine/observers'
+import type { ComponentObserver } from '../engine/observers'
+import { onGlobalMouse, onGlobalMove, isLeftButton } from '../state/mouse'
+import { matchesKey, isPress, hasShift } from '../state/keyboard'
+import { t } from '../state/theme'
+import { persistLayout } from '../state/layoutState'
+import type { LayoutValue } from '../state/layoutState'
+import { screenPosition } from './contextMenu'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+/** Where a panel sits, in tracks counted from 0 */
+export interface PanelPlacement {
+  col: number
+  row: number
+  colSpan: number
+  rowSpan: number
+}
+
+/** A saved arrangement; safe to JSON.stringify and read back */
+export interface DashboardLayout {
+  version: 1
+  panels: Record<string, PanelPlacement>
+}
+
+export interface DashboardPanel {
+  /** Key of the panel in the layout */
+  id: string
+  title: string
+  /** Default place; spans default to 1 */
+  place: { col: number; row: number; colSpan?: number; rowSpan?: number }
+  render(): void
+  /** Smallest it can be resized to (default: 1 each) */
+  minColSpan?: number
+  minRowSpan?: number
+}
+
+export interface DashboardGridProps extends Omit<BoxProps, 'children' | 'display'> {
+  panels: DashboardPanel[]
+  /** Column tracks, all equal */
+  columns: number
+  /** Row tracks, all equal */
+  rows: number
+  /** Cells between tracks (default: 0) */
+  gap?: number
+  /** The arrangement, read and written; kept internally when not given */
+  layout?: WritableSignal<DashboardLayout>
+  /** Called with the new arrangement after each move or resize */
+  onLayoutChange?(layout: DashboardLayout): void
+}
+
+/** A move or resize in progress */
+interface Arranging {
+  id: string
+  mode: 'move' | 'resize'
+  /** Where the ghost is */
+  ghost: PanelPlacement
+  /** Dragged with the mouse, rather than stepped with keys */
+  byMouse: boolean
+  /** Track offset of the grab point from the panel's corner */
+  grabCol: number
+  grabRow: number
+}
+
+const LAYOUT_VERSION = 1
+const GHOST_Z_INDEX = 100
+
+let gridCount = 0
+
+// =============================================================================
+// DASHBOARD GRID
+// =============================================================================
+
+export function dashboardGrid(props: DashboardGridProps): Cleanup {
+  const { panels, columns, rows, gap = 0, layout, onLayoutChange, ...rest } = props
+
+  return scoped(() => {
+    const id = props.id ?? `dashboard-grid-${++gridCount}`
+    const state = layout ?? signal<DashboardLayout>({ version: LAYOUT_VERSION, panels: {} })
+    const arranging = signal<Arranging | null>(null)
+    const size = signal({ width: 0, height: 0 })
+
+    const observer: ComponentObserver = {
+      layoutChanged: (component, rect) => {
+        if (component.id === id) size.value = { width: rect.width, height: rect.height }
+      },
+    }
+    onCleanup(observeComponents(observer))
+    onCleanup(persistLayout(id, 'dashboard', {
+      save: () => state.value as unknown as LayoutValue,
+      restore: (value) => {
+        const saved = value as unknown as DashboardLayout
+        if (saved?.version === LAYOUT_VERSION && typeof saved.panels === 'object') state.value = saved
+      },
+    }))
+
+    /**
+     * Every panel's place: saved where it still fits the grid and clears the
+     * saved panels before it, else its default (a layout saved for another
+     * grid, or edited by hand, can't put panels outside or on top of others)
+     */
+    const placements = derived(() => {
+      const saved: Record<string, unknown> = state.value?.panels ?? {}
+      const result: Record<string, PanelPlacement> = {}
+      const placed: PanelPlacement[] = []
+      for (const panel of panels) {
+        const at = savedPlacement(saved[panel.id], panel, columns, rows)
+        if (at && !placed.some((other) => overlaps(at, other))) {
+          result[panel.id] = at
+          placed.push(at)
+        }
+      }
+      for (const panel of panels) result[panel.id] ??= defaultPlacement(panel)
+      return result
+    })
+
+    const isValid = (panelId: string, at: PanelPlacement) => {
+      if (at.col < 0 || at.row < 0 || at.col + at.colSpan > columns || at.row + at.rowSpan > rows) return false
+      return panels.every((other) => other.id === panelId || !overlaps(at, placements.value[other.id]!))
+    }
+
+    const begin = (panel: DashboardPanel, mode: Arranging['mode'], byMouse: boolean, grabCol = 0, grabRow = 0) => {
+      arranging.value = { id: panel.id, mode, ghost: { ...placements.value[panel.id]! }, byMouse, grabCol, grabRow }
+    }
+
+    /** Put the ghost at `to`, keeping it inside the grid and its panel's minimum size */
+    const moveGhost = (to: PanelPlacement) => {
+      const current = arranging.value
+      if (!current) return
+      const panel = panels.find((p) => p.id === current.id)!
+      const colSpan = clamp(to.colSpan, panel.minColSpan ?? 1, columns - to.col)
+      const rowSpan = clamp(to.rowSpan, panel.minRowSpan ?? 1, rows - to.row)
+      arranging.value = {
+        ...current,
+        ghost: {
+          col: clamp(to.col, 0, columns - colSpan),
+          row: clamp(to.row, 0, rows - rowSpan),
+          colSpan,
+          rowSpan,
+        },
+      }
+    }
+
+    const commit = () => {
+      const current = arranging.value
+      arranging.value = null
+      if (!current || !isValid(current.id, current.ghost)) return
+      const next: DashboardLayout = {
+        version: LAYOUT_VERSION,
+        panels: { ...placements.value, [current.id]: current.ghost },
+      }
+      state.value = next
+      onLayoutChange?.(next)
+    }
+
+    /** The track under a screen point, clamped to the grid */
+    const cellAt = (x: number, y: number) => {
+      const index = getIndex(id)
+      if (index === undefined) return { col: 0, row: 0 }
+      const origin = screenPosition(index)
+      const trackWidth = (size.value.width - gap * (columns - 1)) / columns
+      const trackHeight = (size.value.height - gap * (rows - 1)) / rows
+      return {
+        col: clamp(Math.floor((x - origin.x) / (trackWidth + gap)), 0, columns - 1),
+        row: clamp(Math.floor((y - origin.y) / (trackHeight + gap)), 0, rows - 1),
+      }
+    }
+
+    const startDrag = (panel: DashboardPanel, mode: Arranging['mode'], event: MouseEvent) => {
+      if (!isLeftButton(event)) return
+      const at = placements.value[panel.id]!
+      const cell = cellAt(event.x, event.y)
+      begin(panel, mode, true, cell.col - at.col, cell.row - at.row)
+      return true
+    }
+
+    onCleanup(onGlobalMove((x, y) => {
+      const current = arranging.value
+      if (!current?.byMouse) return
+      const cell = cellAt(x, y)
+      const { ghost } = current
+      if (current.mode === 'move') {
+        moveGhost({ ...ghost, col: cell.col - current.grabCol, row: cell.row - current.grabRow })
+      } else {
+        moveGhost({ ...ghost, colSpan: cell.col - ghost.col + 1, rowSpan: cell.row - ghost.row + 1 })
+      }
+    }))
+
+    onCleanup(onGlobalMouse((event) => {
+      if (event.type === EventType.MouseUp && arranging.value?.byMouse) commit()
+    }))
+
+    const onPanelKey = (panel: DashboardPanel, event: KeyEvent) => {
+      if (!isPress(event)) return
+      const current = arranging.value
+      if (!current || current.id !== panel.id) {
+        if (!matchesKey(event, 'm')) return
+        begin(panel, 'move', false)
+        return true
+      }
+      const { ghost } = current
+      const arrow = (key: string) => matchesKey(event, key) || matchesKey(event, `Shift+${key}`)
+      const step = (dCol: number, dRow: number) =>
+        hasShift(event)
+          ? moveGhost({ ...ghost, colSpan: ghost.colSpan + dCol, rowSpan: ghost.rowSpan + dRow })
+          : moveGhost({ ...ghost, col: ghost.col + dCol, row: ghost.row + dRow })
+      if (matchesKey(event, 'Enter')) commit()
+      else if (matchesKey(event, 'Escape')) arranging.value = null
+      else if (arrow('ArrowLeft')) step(-1, 0)
+      else if (arrow('ArrowRight')) step(1, 0)
+      else if (arrow('ArrowUp')) step(0, -1)
+      else if (arrow('ArrowDown')) step(0, 1)
+      // Everything else waits until the panel is dropped
+      return true
+    }
+
+    box({
+      id,
+      gap,
+      grow: 1,
+      ...rest,
+      display: 'grid',
+      gridTemplateColumns: Array.from({ length: columns }, () => '1fr' as const),
+      gridTemplateRows: Array.from({ length: rows }, () => '1fr' as const),
+      children: () => {
+        for (const panel of panels) {
+          const at = () => placements.value[panel.id]!
+          const isArranging = () => arranging.value?.id === panel.id
+          box({
+            id: `${id}-${panel.id}`,
+            gridColumnStart: () => at().col + 1,
+            colSpan: () => at().colSpan,
+            gridRowStart: () => at().row + 1,
+            rowSpan: () => at().rowSpan,
+            flexDirection: 'column',
+            overflow: 'hidden',
+            border: BorderStyle.ROUNDED,
+            borderColor: () => (isArranging() ? t.primary.value : t.textMuted.value),
+            focusable: true,
+            role: 'group',
+            label: panel.title,
+            onKey: (event) => onPanelKey(panel, event),
+            children: () => {
+              // Title bar: the handle for moving
+              box({
+                flexDirection: 'row',
+                shrink: 0,
+                onMouseDown: (event) => startDrag(panel, 'move', event),
+                children: () => {
+                  text({ content: panel.title, bold: true, fg: t.textBright, grow: 1, wrap: 'truncate' })
+                  text({
+                    content: () => (isArranging() ? '↔ arranging' : ''),
+                    fg: t.textMuted,
+                    shrink: 0,
+                  })
+                },
+              })
+              box({
+                flexDirection: 'column',
+                grow: 1,
+                overflow: 'hidden',
+                children: () => panel.render(),
+              })
+              // Corner handle for resizing
+              box({
+                flexDirection: 'row',
+                justifyContent: 'flex-end',
+                height: 1,
+                shrink: 0,
+                children: () => {
+                  text({
+                    content: '◢',
+                    fg: t.textMuted,
+                    onMouseDown: (event) => startDrag(panel, 'resize', event),
+                  })
+                },
+              })
+            },
+          })
+        }
+
+        // Ghost: the outline of where the panel being arranged will land
+        show(
+          () => arranging.value !== null,
+          () =>
+            box({
+              gridColumnStart: () => (arranging.value?.ghost.col ?? 0) + 1,
+              colSpan: () => arranging.value?.ghost.colSpan ?? 1,
+              gridRowStart: () => (arranging.value?.ghost.row ?? 0) + 1,
+              rowSpan: () => arranging.value?.ghost.rowSpan ?? 1,
+              zIndex: GHOST_Z_INDEX,
+              border: BorderStyle.DASHED,
+              borderColor: () => {
+                const current = arranging.value
+                return current && isValid(current.id, current.ghost) ? t.accent.value : t.error.value
+              },
+            })
+        )
+      },
+    })
+  })
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+function defaultPlacement(panel: DashboardPanel): PanelPlacement {
+  return { col: panel.place.col, row: panel.place.row, colSpan: panel.place.colSpan ?? 1, rowSpan: panel.place.rowSpan ?? 1 }
+}
+
+/** A saved place, if it's whole tracks inside the grid and no smaller than the panel allows */
+function savedPlacement(value: unknown, panel: DashboardPanel, columns: number, rows: number): PanelPlacement | undefined {
+  if (!value || typeof value !== 'object') return undefined
+  const { col, row, colSpan, rowSpan } = value as Record<string, unknown>
+  if (![col, row, colSpan, rowSpan].every(Number.isInteger)) return undefined
+  const at = { col, row, colSpan, rowSpan } as PanelPlacement
+  if (at.colSpan < (panel.minColSpan ?? 1) || at.rowSpan < (panel.minRowSpan ?? 1)) return undefined
+  if (at.col < 0 || at.row < 0 || at.col + at.colSpan > columns || at.row + at.rowSpan > rows) return undefined
+  return at
+}
+
+function overlaps(a: PanelPlacement, b: PanelPlacement): boolean {
+  return a.col < b.col + b.colSpan && b.col < a.col + a.colSpan && a.row < b.row + b.rowSpan && b.row < a.row + a.rowSpan
+}
+
+function clamp(value: number, min: number, max: number): number {
+  return Math.max(min, Math.min(value, max))
+}
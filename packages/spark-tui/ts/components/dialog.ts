@@ -0,0 +1,209 @@
+/**
+ * SparkTUI Components - Dialog
+ *
+ * Modal dialogs that hand back their answer as a promise:
+ *
+ * - `dialog.confirm` resolves true/false, `dialog.alert` once dismissed,
+ *   `dialog.prompt` with the text entered (null when cancelled)
+ * - A centered, themed panel over a full-screen backdrop that traps focus;
+ *   what was focused before gets focus back when it closes
+ * - Enter or a click on a button answers, Escape cancels
+ * - Everything it mounted is cleaned up before the promise settles
+ *
+ * ```ts
+ * if (await dialog.confirm('Delete file', `Delete ${name}? This can't be undone.`, { variant: 'error' })) {
+ *   remove(name)
+ * }
+ * const branch = await dialog.prompt('New branch', 'Name:', { initial: 'feature/' })
+ * ```
+ *
+ * Dialogs opened while another is showing stack on top of it.
+ */
+
+import { signal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { input } from '../primitives/input'
+import { scoped } from '../primitives/scope'
+import type { Cleanup } from '../primitives/types'
+import { BorderStyle } from '../types'
+import type { Dimension } from '../types'
+import { opaque } from '../types/color'
+import { isInitialized } from '../bridge'
+import { matchesKey, isPress } from '../state/keyboard'
+import { focus } from '../state/focus'
+import { t, getVariantStyle } from '../state/theme'
+import type { Variant } from '../state/theme'
+import { button } from './button'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+interface DialogCommon {
+  /** Panel width (default: 50) */
+  width?: Dimension
+  /** Colors of the answering button and the border (default: 'primary') */
+  variant?: Variant
+}
+
+export interface ConfirmOptions extends DialogCommon {
+  /** default: 'OK' */
+  confirmLabel?: string
+  /** default: 'Cancel' */
+  cancelLabel?: string
+}
+
+export interface AlertOptions extends DialogCommon {
+  /** default: 'OK' */
+  okLabel?: string
+}
+
+export interface PromptOptions extends ConfirmOptions {
+  /** Text the field starts with */
+  initial?: string
+  placeholder?: string
+  /** Error message while the text can't be accepted */
+  validate?(value: string): string | null | undefined
+}
+
+const DEFAULT_WIDTH = 50
+/** Above context menus, so a menu item can open a dialog */
+const DIALOG_Z_INDEX = 2000
+
+let dialogCount = 0
+
+// =============================================================================
+// DIALOGS
+// =============================================================================
+
+/** What a dialog's body needs from the frame around it */
+interface DialogFrame<T> {
+  /** Close the dialog, settling its promise with `value` */
+  close(value: T): void
+  /** Unique per dialog, for its components' ids */
+  id: string
+}
+
+/**
+ * Mount a modal panel and settle with whatever its body closes it with,
+ * or `cancelled` on Escape.
+ */
+function open<T>(title: string, options: DialogCommon, cancelled: T, body: (frame: DialogFrame<T>) => void): Promise<T> {
+  if (!isInitialized()) return Promise.resolve(cancelled)
+  const { width = DEFAULT_WIDTH, variant = 'primary' } = options
+  const id = `dialog-${++dialogCount}`
+
+  return new Promise<T>((resolve) => {
+    let cleanup: Cleanup | null = null
+    const close = (value: T) => {
+      if (!cleanup) return
+      cleanup()
+      cleanup = null
+      resolve(value)
+    }
+
+    cleanup = scoped(() => {
+      box({
+        id,
+        width: '100%',
+        height: '100%',
+        justifyContent: 'center',
+        alignItems: 'center',
+        zIndex: DIALOG_Z_INDEX + dialogCount,
+        // Modal: clicks beside the panel land on the backdrop, not behind it
+        pointerEvents: 'all',
+        trapFocus: true,
+        role: 'dialog',
+        label: title,
+        onKey: (event) => {
+          if (isPress(event) && matchesKey(event, 'Escape')) close(cancelled)
+          // Modal: nothing behind it hears the keyboard
+          return true
+        },
+        children: () => {
+          box({
+            width,
+            border: BorderStyle.ROUNDED,
+            borderColor: () => getVariantStyle(variant).border,
+            bg: () => opaque(t.surface.value),
+            padding: 1,
+            gap: 1,
+            flexDirection: 'column',
+            children: () => {
+              text({ content: title, bold: true, fg: t.textBright })
+              body({ close, id })
+            },
+          })
+        },
+      })
+    })
+  })
+}
+
+/** Right-aligned answer buttons; the first gets focus */
+function buttons(id: string, entries: { label: string; variant: Variant; onClick(): void }[]): void {
+  box({
+    flexDirection: 'row',
+    justifyContent: 'flex-end',
+    gap: 2,
+    children: () => {
+      entries.forEach((entry, i) => {
+        button({ id: `${id}-button-${i}`, label: entry.label, variant: entry.variant, onClick: entry.onClick })
+      })
+    },
+  })
+  focus(`${id}-button-0`)
+}
+
+function confirm(title: string, message: string, options: ConfirmOptions = {}): Promise<boolean> {
+  const { confirmLabel = 'OK', cancelLabel = 'Cancel', variant = 'primary' } = options
+  return open(title, options, false, ({ close, id }) => {
+    text({ content: message, fg: t.text })
+    buttons(id, [
+      { label: confirmLabel, variant, onClick: () => close(true) },
+      { label: cancelLabel, variant: 'secondary', onClick: () => close(false) },
+    ])
+  })
+}
+
+function alert(title: string, message: string, options: AlertOptions = {}): Promise<void> {
+  const { okLabel = 'OK', variant = 'primary' } = options
+  return open<void>(title, options, undefined, ({ close, id }) => {
+    text({ content: message, fg: t.text })
+    buttons(id, [{ label: okLabel, variant, onClick: () => close() }])
+  })
+}
+
+function prompt(title: string, message: string, options: PromptOptions = {}): Promise<string | null> {
+  const { confirmLabel = 'OK', cancelLabel = 'Cancel', variant = 'primary', initial = '', placeholder, validate } = options
+  return open<string | null>(title, options, null, ({ close, id }) => {
+    const value = signal(initial)
+    /** Whether OK was attempted — the error stays quiet until then */
+    const attempted = signal(false)
+    const error = () => validate?.(value.value) || null
+    const accept = () => {
+      attempted.value = true
+      if (!error()) close(value.value)
+    }
+
+    text({ content: message, fg: t.text })
+    input({
+      id: `${id}-input`,
+      value,
+      placeholder,
+      border: BorderStyle.SINGLE,
+      onSubmit: accept,
+      onCancel: () => close(null),
+    })
+    text({ content: () => (attempted.value && error()) || '', fg: t.error })
+    buttons(id, [
+      { label: confirmLabel, variant, onClick: accept },
+      { label: cancelLabel, variant: 'secondary', onClick: () => close(null) },
+    ])
+    focus(`${id}-input`)
+  })
+}
+
+/** Modal dialogs: `await dialog.confirm(...)`, `dialog.alert(...)`, `dialog.prompt(...)` */
+export const dialog = { confirm, alert, prompt }
@@ -0,0 +1,333 @@
+/**
+ * SparkTUI Components - File Picker
+ *
+ * A directory browser for choosing a file (or a directory):
+ *
+ * - Clickable breadcrumb path above the listing
+ * - Directories first, then files, sorted by name, size or modified time
+ * - ↑↓ PgUp PgDn Home End move, Enter / → open, ← / Backspace go up,
+ *   `.` shows hidden files, `s` cycles the sort and `r` reverses it,
+ *   `/` jumps to the filter field, Esc cancels
+ * - The filter narrows the listing by name as it's typed; Enter returns to
+ *   the list, Esc clears it
+ * - Directories are read asynchronously, so a huge one never stalls a frame,
+ *   and only the rows showing are rendered
+ *
+ * ```ts
+ * filePicker({
+ *   path: process.cwd(),
+ *   height: 20,
+ *   onSelect: (path) => openFile(path),
+ *   onCancel: () => (picking.value = false),
+ * })
+ * ```
+ */
+
+import { readdir, stat } from 'fs/promises'
+import { dirname, join, parse, resolve, sep } from 'path'
+import { signal, derived } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { input } from '../primitives/input'
+import { resource } from '../primitives/suspense'
+import { scoped } from '../primitives/scope'
+import type { BoxProps, Cleanup } from '../primitives/types'
+import { matchesKey, isPress } from '../state/keyboard'
+import { focus } from '../state/focus'
+import { t } from '../state/theme'
+import { breadcrumbs } from './breadcrumbs'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export type FileSortKey = 'name' | 'size' | 'modified'
+
+export interface FileEntry {
+  name: string
+  /** Absolute path */
+  path: string
+  isDirectory: boolean
+  /** Bytes (0 for directories and anything that couldn't be read) */
+  size: number
+  /** Last modified, ms since the epoch */
+  modified: number
+}
+
+export interface FilePickerProps extends Omit<BoxProps, 'children' | 'onKey' | 'height'> {
+  /** Directory to start in (default: the working directory) */
+  path?: string
+  /** What Enter on an entry chooses: files (default), directories, or either */
+  select?: 'file' | 'directory' | 'any'
+  /** Start with dotfiles showing (default: false) */
+  showHidden?: boolean
+  /** Initial sort (default: 'name') */
+  sort?: FileSortKey
+  /** Rows of the listing (default: 15) */
+  height?: number
+  /** Called with the absolute path of the chosen entry */
+  onSelect(path: string): void
+  /** Called on Escape from the listing */
+  onCancel?(): void
+  /** Called as the picker moves to another directory */
+  onNavigate?(directory: string): void
+}
+
+const DEFAULT_ROWS = 15
+const SORT_ORDER: FileSortKey[] = ['name', 'size', 'modified']
+const SIZE_WIDTH = 7
+const DATE_WIDTH = 10
+const SIZE_UNITS = ['B', 'K', 'M', 'G', 'T']
+const BYTES_PER_UNIT = 1024
+/** Most stats in flight at once, so a huge directory doesn't open thousands of files together */
+const STAT_CONCURRENCY = 32
+
+let pickerCount = 0
+
+// =============================================================================
+// FILE PICKER
+// =============================================================================
+
+export function filePicker(props: FilePickerProps): Cleanup {
+  const {
+    path = process.cwd(),
+    select = 'file',
+    sort = 'name',
+    height = DEFAULT_ROWS,
+    onSelect,
+    onCancel,
+    onNavigate,
+    ...rest
+  } = props
+
+  return scoped(() => {
+    const id = `file-picker-${++pickerCount}`
+    const directory = signal(resolve(path))
+    const showHidden = signal(props.showHidden ?? false)
+    const sortBy = signal<FileSortKey>(sort)
+    const descending = signal(false)
+    const filter = signal('')
+    /** Row asked for, and the window's first row; read through `highlighted`/`top`, which keep them on the list */
+    const cursor = signal(0)
+    const windowTop = signal(0)
+
+    const listing = resource(({ signal: abort }) => readDirectory(directory.value, abort))
+
+    const entries = derived((): FileEntry[] => {
+      const needle = filter.value.toLowerCase()
+      const shown = (listing.value ?? []).filter(
+        (entry) => (showHidden.value || !entry.name.startsWith('.')) && entry.name.toLowerCase().includes(needle)
+      )
+      const direction = descending.value ? -1 : 1
+      const key = sortBy.value
+      shown.sort((a, b) => {
+        if (a.isDirectory !== b.isDirectory) return a.isDirectory ? -1 : 1
+        const order = key === 'name' ? a.name.localeCompare(b.name) : key === 'size' ? a.size - b.size : a.modified - b.modified
+        return (order || a.name.localeCompare(b.name)) * direction
+      })
+      // Going up is always the first row, wherever the sort puts the rest
+      const parent = dirname(directory.value)
+      return parent === directory.value ? shown : [parentEntry(parent), ...shown]
+    })
+
+    // Clamped as they're read, so a list that shrinks (filtered, reloaded) never leaves them past its end
+    const highlighted = derived(() => Math.max(0, Math.min(cursor.value, entries.value.length - 1)))
+    const top = derived(() => {
+      const row = highlighted.value
+      const first = Math.max(0, Math.min(windowTop.value, entries.value.length - height))
+      // Keep the highlighted row inside the window
+      if (row < first) return row
+      if (row >= first + height) return row - height + 1
+      return first
+    })
+
+    const moveTo = (row: number) => {
+      cursor.value = Math.max(0, Math.min(row, entries.value.length - 1))
+      windowTop.value = top.value
+    }
+
+    const navigate = (to: string) => {
+      directory.value = to
+      filter.value = ''
+      cursor.value = 0
+      windowTop.value = 0
+      onNavigate?.(to)
+    }
+
+    const choose = (entry: FileEntry | undefined) => {
+      if (!entry) return
+      // Directories open unless they're what's being chosen (→ still opens them)
+      if (entry.isDirectory && (select === 'file' || entry.name === '..')) navigate(entry.path)
+      else if (entry.isDirectory || select !== 'directory') onSelect(entry.path)
+    }
+
+    const open = (entry: FileEntry | undefined) => {
+      if (entry?.isDirectory) navigate(entry.path)
+    }
+
+    const up = () => {
+      const parent = dirname(directory.value)
+      if (parent !== directory.value) navigate(parent)
+    }
+
+    const onKey = (event: Parameters<NonNullable<BoxProps['onKey']>>[0]) => {
+      if (!isPress(event)) return
+      const current = entries.value[highlighted.value]
+      if (matchesKey(event, 'ArrowDown')) moveTo(highlighted.value + 1)
+      else if (matchesKey(event, 'ArrowUp')) moveTo(highlighted.value - 1)
+      else if (matchesKey(event, 'PageDown')) moveTo(highlighted.value + height)
+      else if (matchesKey(event, 'PageUp')) moveTo(highlighted.value - height)
+      else if (matchesKey(event, 'Home')) moveTo(0)
+      else if (matchesKey(event, 'End')) moveTo(entries.value.length - 1)
+      else if (matchesKey(event, 'Enter')) choose(current)
+      else if (matchesKey(event, 'ArrowRight')) open(current)
+      else if (matchesKey(event, 'ArrowLeft') || matchesKey(event, 'Backspace')) up()
+      else if (matchesKey(event, '.')) showHidden.value = !showHidden.value
+      else if (matchesKey(event, 's')) sortBy.value = SORT_ORDER[(SORT_ORDER.indexOf(sortBy.value) + 1) % SORT_ORDER.length]!
+      else if (matchesKey(event, 'r')) descending.value = !descending.value
+      else if (matchesKey(event, '/')) focus(`${id}-filter`)
+      else if (matchesKey(event, 'Escape')) onCancel?.()
+      else return
+      return true
+    }
+
+    box({
+      id,
+      flexDirection: 'column',
+      focusable: true,
+      ...rest,
+      onKey,
+      onScroll: (event) => moveTo(highlighted.value + Math.sign(event.deltaY)),
+      children: () => {
+        pathCrumbs(() => directory.value, navigate)
+
+        input({
+          id: `${id}-filter`,
+          value: filter,
+          placeholder: '/ to filter',
+          onSubmit: () => focus(id),
+          onCancel: () => {
+            filter.value = ''
+            focus(id)
+          },
+        })
+
+        // Only the window of rows showing is rendered
+        for (let row = 0; row < height; row++) {
+          const entry = () => entries.value[top.value + row]
+          const isHighlighted = () => top.value + row === highlighted.value
+          box({
+            flexDirection: 'row',
+            gap: 1,
+            bg: () => (isHighlighted() ? t.primary.value : null),
+            onClick: () => {
+              const clicked = top.value + row
+              if (clicked === highlighted.value) choose(entry())
+              else moveTo(clicked)
+            },
+            children: () => {
+              text({
+                content: () => {
+                  const e = entry()
+                  return e ? (e.isDirectory ? `${e.name}/` : e.name) : ''
+                },
+                grow: 1,
+                wrap: 'truncate',
+                fg: () => (isHighlighted() ? t.textBright.value : entry()?.isDirectory ? t.primary.value : t.text.value),
+              })
+              text({
+                content: () => {
+                  const e = entry()
+                  return e && !e.isDirectory ? formatSize(e.size).padStart(SIZE_WIDTH) : ''
+                },
+                width: SIZE_WIDTH,
+                shrink: 0,
+                fg: t.textMuted,
+              })
+              text({
+                content: () => {
+                  const e = entry()
+                  return e && e.modified ? formatDate(e.modified) : ''
+                },
+                width: DATE_WIDTH,
+                shrink: 0,
+                fg: t.textMuted,
+              })
+            },
+          })
+        }
+
+        text({
+          content: () => {
+            if (listing.state === 'error') return `Can't read ${directory.value}: ${String(listing.error)}`
+            if (listing.loading) return 'Reading…'
+            const arrow = descending.value ? '↓' : '↑'
+            const hidden = showHidden.value ? 'shown' : 'hidden'
+            return `${entries.value.length} entries · sort ${sortBy.value} ${arrow} · dotfiles ${hidden}`
+          },
+          fg: () => (listing.state === 'error' ? t.error.value : t.textMuted.value),
+        })
+      },
+    })
+    focus(id)
+  })
+}
+
+// =============================================================================
+// PARTS
+// =============================================================================
+
+/** Path segments, each clickable to jump there */
+function pathCrumbs(directory: () => string, navigate: (to: string) => void): void {
+  const root = () => parse(directory()).root
+  const segments = () => directory().slice(root().length).split(sep).filter(Boolean)
+  breadcrumbs({
+    items: () => [root(), ...segments()],
+    onSelect: (i) => navigate(join(root(), ...segments().slice(0, i))),
+  })
+}
+
+function parentEntry(path: string): FileEntry {
+  return { name: '..', path, isDirectory: true, size: 0, modified: 0 }
+}
+
+/** Every entry of a directory with its size and time, read without blocking */
+async function readDirectory(directory: string, abort: AbortSignal): Promise<FileEntry[]> {
+  const dirents = await readdir(directory, { withFileTypes: true })
+  if (abort.aborted) return []
+
+  const entries: FileEntry[] = new Array(dirents.length)
+  let next = 0
+  const worker = async () => {
+    while (next < dirents.length && !abort.aborted) {
+      const i = next++
+      const dirent = dirents[i]!
+      const path = join(directory, dirent.name)
+      // stat follows symlinks, so a link to a directory opens like one
+      const stats = await stat(path).catch(() => null)
+      entries[i] = {
+        name: dirent.name,
+        path,
+        isDirectory: stats ? stats.isDirectory() : dirent.isDirectory(),
+        size: stats && !stats.isDirectory() ? stats.size : 0,
+        modified: stats?.mtimeMs ?? 0,
+      }
+    }
+  }
+  await Promise.all(Array.from({ length: Math.min(STAT_CONCURRENCY, dirents.length) }, worker))
+  return abort.aborted ? [] : entries
+}
+
+function formatSize(bytes: number): string {
+  let size = bytes
+  let unit = 0
+  while (size >= BYTES_PER_UNIT && unit < SIZE_UNITS.length - 1) {
+    size /= BYTES_PER_UNIT
+    unit++
+  }
+  return unit === 0 ? `${size}${SIZE_UNITS[0]}` : `${size.toFixed(size < 10 ? 1 : 0)}${SIZE_UNITS[unit]}`
+}
+
+function formatDate(ms: number): string {
+  return new Date(ms).toISOString().slice(0, DATE_WIDTH)
+}
@@ -0,0 +1,305 @@
+/**
+ * SparkTUI Components - Form
+ *
+ * Fields registered with a form, validated as they change and submitted
+ * together:
+ *
+ * - Field helpers: `input`, `checkbox`, `select`, each with a label and an
+ *   optional validator returning an error message (or nothing when valid)
+ * - Errors appear under a field, in the theme's error color, once it has
+ *   been left or a submit was attempted
+ * - Enter in an input submits; Tab moves through fields in the order they
+ *   are declared
+ * - `isValid`, `values` and `errors` are signals over every mounted field
+ *
+ * ```ts
+ * const signup = createForm({ onSubmit: (v) => register(v.email as string) })
+ *
+ * form({ form: signup, gap: 1, children: (f) => {
+ *   f.input('email', { label: 'Email', validate: (v) => (v.includes('@') ? null : 'Not an email') })
+ *   f.select('plan', { label: 'Plan', options: ['free', 'pro'] })
+ *   f.checkbox('terms', { label: 'I accept the terms', validate: (v) => (v ? null : 'Required') })
+ *   button({ label: 'Sign up', disabled: () => !f.isValid.value, onClick: f.submit })
+ * }})
+ * ```
+ *
+ * Values live in the form, not the fields: a field hidden with show() and
+ * shown again keeps what was entered, but only mounted fields validate.
+ */
+
+import { signal, derived, onScopeDispose } from '@rlabs-inc/signals'
+import type { ReadableSignal, WritableSignal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { input } from '../primitives/input'
+import type { BoxProps, Cleanup, InputProps } from '../primitives/types'
+import { matchesKey, isPress } from '../state/keyboard'
+import { focus } from '../state/focus'
+import { t } from '../state/theme'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export type FieldValue = string | boolean
+
+export type FormValues = Record<string, FieldValue>
+
+/** Error message for `value`, or nothing when it is valid */
+export type FieldValidator<T extends FieldValue> = (value: T, values: FormValues) => string | null | undefined | void
+
+interface FieldCommon<T extends FieldValue> {
+  /** Shown above the field (beside it for checkboxes) */
+  label?: string
+  /** Value the first time the field mounts, and after reset() */
+  initial?: T
+  validate?: FieldValidator<T>
+}
+
+export interface InputFieldProps extends FieldCommon<string>, Omit<InputProps, 'id' | 'value' | 'onSubmit' | 'onBlur'> {}
+
+export interface CheckboxFieldProps extends FieldCommon<boolean> {}
+
+export type SelectOption = string | { value: string; label: string }
+
+export interface SelectFieldProps extends FieldCommon<string> {
+  options: SelectOption[]
+}
+
+export interface FormOptions {
+  /** Called with every mounted field's value when the form submits valid */
+  onSubmit?(values: FormValues): void
+  /** Values for fields that don't set `initial` */
+  initial?: FormValues
+}
+
+export interface Form {
+  /** A text input field */
+  input(name: string, props?: InputFieldProps): Cleanup
+  /** A `[x] label` toggle: Space or click */
+  checkbox(name: string, props?: CheckboxFieldProps): Cleanup
+  /** One of `options`: ←/→ or Space to cycle, click for the next */
+  select(name: string, props: SelectFieldProps): Cleanup
+  /** The signal behind a field's value */
+  field<T extends FieldValue>(name: string, initial: T): WritableSignal<T>
+  /** Mounted fields' values, by name */
+  readonly values: ReadableSignal<FormValues>
+  /** Error messages of invalid mounted fields, by name */
+  readonly errors: ReadableSignal<Record<string, string>>
+  /** No mounted field has an error */
+  readonly isValid: ReadableSignal<boolean>
+  /** Submit if valid; otherwise show every error and focus the first invalid field */
+  submit(): void
+  /** Back to initial values, errors hidden */
+  reset(): void
+}
+
+export interface FormProps extends FormOptions, Omit<BoxProps, 'children'> {
+  /** A form from createForm(), to reach it from outside (its own options apply, not these) */
+  form?: Form
+  children: (form: Form) => void
+}
+
+interface FieldEntry {
+  value: WritableSignal<FieldValue>
+  initial: FieldValue
+  touched: WritableSignal<boolean>
+  validate?: FieldValidator<any>
+  /** Component id of the focusable control */
+  controlId: string
+}
+
+let formCounter = 0
+
+// =============================================================================
+// FORM STATE
+// =============================================================================
+
+export function createForm(options: FormOptions = {}): Form {
+  const prefix = `form${formCounter++}`
+  const values = new Map<string, WritableSignal<FieldValue>>()
+  /** Mounted fields, in declaration order */
+  const mounted = new Map<string, FieldEntry>()
+  const version = signal(0)
+  const attempted = signal(false)
+
+  const valueOf = (name: string, initial: FieldValue): WritableSignal<FieldValue> => {
+    let sig = values.get(name)
+    if (!sig) {
+      sig = signal(initial)
+      values.set(name, sig)
+    }
+    return sig
+  }
+
+  const field = <T extends FieldValue>(name: string, initial: T): WritableSignal<T> =>
+    valueOf(name, options.initial?.[name] ?? initial) as WritableSignal<T>
+
+  const valuesSignal = derived(() => {
+    void version.value
+    const result: FormValues = {}
+    for (const [name, entry] of mounted) result[name] = entry.value.value
+    return result
+  })
+
+  const errorsSignal = derived(() => {
+    const all = valuesSignal.value
+    const result: Record<string, string> = {}
+    for (const [name, entry] of mounted) {
+      const message = entry.validate?.(all[name], all)
+      if (message) result[name] = message
+    }
+    return result
+  })
+
+  const isValid = derived(() => Object.keys(errorsSignal.value).length === 0)
+
+  /** Mount a field: returns its entry, unregistered when its scope ends */
+  const register = (name: string, common: FieldCommon<any>, fallback: FieldValue): FieldEntry => {
+    const initial = common.initial ?? options.initial?.[name] ?? fallback
+    const entry: FieldEntry = {
+      value: valueOf(name, initial),
+      initial,
+      touched: signal(false),
+      validate: common.validate,
+      controlId: `${prefix}-${name}`,
+    }
+    mounted.set(name, entry)
+    version.value++
+    onScopeDispose(() => {
+      if (mounted.get(name) !== entry) return
+      mounted.delete(name)
+      version.value++
+    })
+    return entry
+  }
+
+  const shownError = (name: string, entry: FieldEntry) => () =>
+    (entry.touched.value || attempted.value) ? errorsSignal.value[name] ?? '' : ''
+
+  /** Label, control, error message */
+  const frame = (name: string, entry: FieldEntry, label: string | undefined, control: () => void) =>
+    box({
+      flexDirection: 'column',
+      children: () => {
+        if (label) text({ content: label, fg: t.textMuted })
+        control()
+        const error = shownError(name, entry)
+        text({ content: error, fg: t.error, visible: () => !!error() })
+      },
+    })
+
+  const form: Form = {
+    input(name, props = {}) {
+      const { label, initial, validate, ...rest } = props
+      const entry = register(name, { label, initial, validate }, '')
+      return frame(name, entry, label, () => {
+        input({
+          ...rest,
+          id: entry.controlId,
+          value: entry.value as WritableSignal<string>,
+          onSubmit: () => form.submit(),
+          onBlur: () => (entry.touched.value = true),
+        })
+      })
+    },
+
+    checkbox(name, props = {}) {
+      const entry = register(name, props, false)
+      const toggle = () => {
+        entry.value.value = !entry.value.value
+        entry.touched.value = true
+      }
+      return frame(name, entry, undefined, () => {
+        box({
+          id: entry.controlId,
+          focusable: true,
+          role: 'checkbox',
+          label: props.label,
+          valueText: () => (entry.value.value ? 'checked' : 'not checked'),
+          onClick: () => toggle(),
+          onKey: (event) => {
+            if (!isPress(event) || !matchesKey(event, 'Space')) return
+            toggle()
+            return true
+          },
+          children: () => {
+            text({ content: () => `${entry.value.value ? '[x]' : '[ ]'} ${props.label ?? ''}` })
+          },
+        })
+      })
+    },
+
+    select(name, props) {
+      const options = props.options.map((o) => (typeof o === 'string' ? { value: o, label: o } : o))
+      const entry = register(name, props, options[0]?.value ?? '')
+      const selected = () => Math.max(0, options.findIndex((o) => o.value === entry.value.value))
+      const step = (by: number) => {
+        if (options.length === 0) return
+        entry.value.value = options[(selected() + by + options.length) % options.length]!.value
+        entry.touched.value = true
+      }
+      return frame(name, entry, props.label, () => {
+        box({
+          id: entry.controlId,
+          focusable: true,
+          role: 'list',
+          label: props.label,
+          valueText: () => options[selected()]?.label ?? '',
+          onClick: () => step(1),
+          onKey: (event) => {
+            if (!isPress(event)) return
+            if (matchesKey(event, 'ArrowLeft')) step(-1)
+            else if (matchesKey(event, 'ArrowRight') || matchesKey(event, 'Space')) step(1)
+            else return
+            return true
+          },
+          children: () => {
+            text({ content: () => `‹ ${options[selected()]?.label ?? ''} ›` })
+          },
+        })
+      })
+    },
+
+    field,
+    values: valuesSignal,
+    errors: errorsSignal,
+    isValid,
+
+    submit() {
+      const errors = errorsSignal.value
+      const invalid = [...mounted.entries()].find(([name]) => errors[name])
+      if (invalid) {
+        attempted.value = true
+        focus(invalid[1].controlId)
+        return
+      }
+      attempted.value = false
+      options.onSubmit?.(valuesSignal.value)
+    },
+
+    reset() {
+      attempted.value = false
+      for (const entry of mounted.values()) {
+        entry.value.value = entry.initial
+        entry.touched.value = false
+      }
+    },
+  }
+
+  return form
+}
+
+// =============================================================================
+// FORM
+// =============================================================================
+
+export function form(props: FormProps): Cleanup {
+  const { form: given, onSubmit, initial, children, ...rest } = props
+  const f = given ?? createForm({ onSubmit, initial })
+  return box({
+    flexDirection: 'column',
+    ...rest,
+    children: () => children(f),
+  })
+}
@@ -0,0 +1,344 @@
+/**
+ * SparkTUI Components - Fuzzy list
+ *
+ * A prompt over a ranked, filtered list, the way fzf works — the building
+ * block for command palettes, file finders and other pickers:
+ *
+ * - The query's letters must appear in order; matches at word starts and in
+ *   runs rank higher, and the matched letters are highlighted
+ * - Smart case: an all-lowercase query ignores case, any capital makes it exact
+ * - ↑↓ / Ctrl+P Ctrl+N / PgUp PgDn move, Enter chooses, Esc cancels
+ * - `multi`: Tab marks the highlighted item and moves down (Shift+Tab up);
+ *   Enter then chooses every marked item
+ * - Items can be a list (or signal of one) or loaded per query by `load`,
+ *   which is aborted when the query changes before it resolves
+ * - Only the rows showing are rendered, whatever the number of items
+ *
+ * ```ts
+ * fuzzyList({
+ *   items: commands,
+ *   label: (command) => command.title,
+ *   height: 12,
+ *   onSelect: ([command]) => command?.run(),
+ *   onCancel: () => (paletteOpen.value = false),
+ * })
+ * ```
+ */
+
+import { signal, derived, effect } from '@rlabs-inc/signals'
+import type { WritableSignal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { input } from '../primitives/input'
+import { forEach } from '../primitives/each'
+import { resource } from '../primitives/suspense'
+import { scoped } from '../primitives/scope'
+import type { BoxProps, Cleanup, Reactive } from '../primitives/types'
+import { matchesKey, isPress } from '../state/keyboard'
+import { focus } from '../state/focus'
+import { t } from '../state/theme'
+import { unwrap } from '../primitives/utils'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export interface FuzzyMatch {
+  /** Higher is better */
+  score: number
+  /** Indices (in code points) of the matched characters */
+  positions: number[]
+}
+
+export interface FuzzyListProps<T> extends Omit<BoxProps, 'children' | 'onKey' | 'height'> {
+  /** Items to search */
+  items?: Reactive<readonly T[]>
+  /** Items for a query, fetched as it changes (ranked like `items`) */
+  load?: (query: string, signal: AbortSignal) => Promise<readonly T[]>
+  /** Text matched and shown for an item (default: `String(item)`) */
+  label?: (item: T) => string
+  /** Identity of an item for marking (default: its label) */
+  key?: (item: T) => unknown
+  /** Tab marks items and Enter chooses all of them */
+  multi?: boolean
+  /** Result rows (default: 10) */
+  height?: number
+  /** Shown before the query (default: '> ') */
+  prompt?: string
+  placeholder?: string
+  /** The query, when the caller wants to read or set it */
+  query?: WritableSignal<string>
+  /** Called with the marked items, or the highlighted one when none are */
+  onSelect(chosen: T[]): void
+  onCancel?(): void
+  /** Called as the highlight moves, e.g. to fill a preview */
+  onHighlight?(item: T | undefined): void
+}
+
+interface Ranked<T> {
+  item: T
+  label: string
+  positions: number[]
+}
+
+/** A stretch of a label, matched or not */
+interface Run {
+  text: string
+  matched: boolean
+}
+
+const DEFAULT_ROWS = 10
+
+// Scoring, in the spirit of fzf's: every matched character earns a base
+// score, plus bonuses where a person would start typing a word
+const SCORE_MATCH = 16
+const BONUS_BOUNDARY = 8
+const BONUS_CAMEL = 7
+const BONUS_CONSECUTIVE = 4
+const BONUS_FIRST_CHAR = 2
+const PENALTY_GAP_START = 3
+const PENALTY_GAP_EXTENSION = 1
+const WORD_SEPARATORS = new Set([' ', '/', '\\', '_', '-', '.', ':', ','])
+
+let listCount = 0
+
+// =============================================================================
+// MATCHING
+// =============================================================================
+
+/**
+ * Match `query` against `candidate` as a subsequence, or null when some
+ * query character is missing. Of the places the query fits, the shortest
+ * window ending at its first complete match is scored.
+ */
+export function fuzzyMatch(query: string, candidate: string): FuzzyMatch | null {
+  const needle = Array.from(query)
+  if (needle.length === 0) return { score: 0, positions: [] }
+
+  const chars = Array.from(candidate)
+  // Smart case: any capital in the query makes it case-sensitive
+  const exact = query !== query.toLowerCase()
+  const hay = exact ? chars : chars.map((c) => c.toLowerCase())
+  const want = exact ? needle : needle.map((c) => c.toLowerCase())
+
+  // Forward: where the first complete match ends
+  let end = -1
+  for (let i = 0, n = 0; i < hay.length; i++) {
+    if (hay[i] === want[n] && ++n === want.length) {
+      end = i
+      break
+    }
+  }
+  if (end < 0) return null
+
+  // Backward from there: the latest start, for the tightest window
+  let start = end
+  for (let i = end, n = want.length - 1; i >= 0; i--) {
+    if (hay[i] === want[n] && --n < 0) {
+      start = i
+      break
+    }
+  }
+
+  const positions: number[] = []
+  for (let i = start, n = 0; n < want.length; i++) {
+    if (hay[i] === want[n]) {
+      positions.push(i)
+      n++
+    }
+  }
+
+  let score = 0
+  let previous = -1
+  for (const p of positions) {
+    score += SCORE_MATCH
+    const before = chars[p - 1]
+    if (before === undefined || WORD_SEPARATORS.has(before)) score += BONUS_BOUNDARY
+    else if (before === before.toLowerCase() && chars[p] !== chars[p]!.toLowerCase()) score += BONUS_CAMEL
+    if (previous >= 0) {
+      const gap = p - previous - 1
+      score += gap === 0 ? BONUS_CONSECUTIVE : -(PENALTY_GAP_START + gap * PENALTY_GAP_EXTENSION)
+    }
+    previous = p
+  }
+  if (positions[0] === 0) score += BONUS_FIRST_CHAR
+
+  return { score, positions }
+}
+
+/** A label split into matched and unmatched stretches */
+function runsOf(label: string, positions: number[]): Run[] {
+  const matched = new Set(positions)
+  const runs: Run[] = []
+  Array.from(label).forEach((char, i) => {
+    const last = runs[runs.length - 1]
+    if (last && last.matched === matched.has(i)) last.text += char
+    else runs.push({ text: char, matched: matched.has(i) })
+  })
+  return runs
+}
+
+// =============================================================================
+// FUZZY LIST
+// =============================================================================
+
+export function fuzzyList<T>(props: FuzzyListProps<T>): Cleanup {
+  const {
+    items = [],
+    load,
+    label = String,
+    key = label,
+    multi = false,
+    height = DEFAULT_ROWS,
+    prompt = '> ',
+    placeholder,
+    onSelect,
+    onCancel,
+    onHighlight,
+    ...rest
+  } = props
+
+  return scoped(() => {
+    const id = `fuzzy-list-${++listCount}`
+    const query = props.query ?? signal('')
+    const highlighted = signal(0)
+    const top = signal(0)
+    const marked = signal(new Map<unknown, T>())
+
+    const loaded = load ? resource(({ signal: abort }) => load(query.value, abort)) : null
+    const source = derived(() => (loaded ? loaded.value ?? [] : unwrap(items)))
+
+    const ranked = derived((): Ranked<T>[] => {
+      const scored: (Ranked<T> & { score: number; order: number })[] = []
+      source.value.forEach((item, order) => {
+        const text = label(item)
+        const match = fuzzyMatch(query.value, text)
+        if (match) scored.push({ item, label: text, positions: match.positions, score: match.score, order })
+      })
+      // Best score, then the shorter label, then the source's order
+      return scored.sort((a, b) => b.score - a.score || a.label.length - b.label.length || a.order - b.order)
+    })
+
+    // Both stay in range as the results shrink under them
+    const cursor = derived(() => Math.max(0, Math.min(highlighted.value, ranked.value.length - 1)))
+    const windowStart = derived(() => Math.max(0, Math.min(top.value, ranked.value.length - height)))
+    const current = derived(() => ranked.value[cursor.value]?.item)
+
+    if (onHighlight) effect(() => onHighlight(current.value))
+
+    const moveTo = (row: number) => {
+      const next = Math.max(0, Math.min(row, ranked.value.length - 1))
+      highlighted.value = next
+      if (next < windowStart.value) top.value = next
+      else if (next >= windowStart.value + height) top.value = next - height + 1
+      else top.value = windowStart.value
+    }
+
+    const toggleMark = (step: number) => {
+      const item = current.value
+      if (item === undefined) return
+      const next = new Map(marked.value)
+      if (next.has(key(item))) next.delete(key(item))
+      else next.set(key(item), item)
+      marked.value = next
+      moveTo(cursor.value + step)
+    }
+
+    const choose = () => {
+      if (marked.value.size > 0) return onSelect([...marked.value.values()])
+      if (current.value !== undefined) onSelect([current.value])
+    }
+
+    box({
+      id,
+      flexDirection: 'column',
+      captureTab: multi,
+      ...rest,
+      onKey: (event) => {
+        if (!isPress(event)) return
+        if (matchesKey(event, 'ArrowDown') || matchesKey(event, 'Ctrl+N')) moveTo(cursor.value + 1)
+        else if (matchesKey(event, 'ArrowUp') || matchesKey(event, 'Ctrl+P')) moveTo(cursor.value - 1)
+        else if (matchesKey(event, 'PageDown')) moveTo(cursor.value + height)
+        else if (matchesKey(event, 'PageUp')) moveTo(cursor.value - height)
+        else if (multi && matchesKey(event, 'Tab')) toggleMark(1)
+        else if (multi && matchesKey(event, 'Shift+Tab')) toggleMark(-1)
+        else return
+        return true
+      },
+      onScroll: (event) => moveTo(cursor.value + Math.sign(event.deltaY)),
+      children: () => {
+        box({
+          flexDirection: 'row',
+          children: () => {
+            text({ content: prompt, fg: t.primary, bold: true, shrink: 0 })
+            input({
+              id: `${id}-query`,
+              value: query,
+              placeholder,
+              grow: 1,
+              onChange: () => {
+                highlighted.value = 0
+                top.value = 0
+              },
+              onSubmit: choose,
+              onCancel: () => onCancel?.(),
+            })
+          },
+        })
+
+        // Only the window of rows showing is rendered
+        for (let row = 0; row < height; row++) {
+          const entry = () => ranked.value[windowStart.value + row]
+          const isCursor = () => windowStart.value + row === cursor.value && entry() !== undefined
+          const runs = derived(() => {
+            const e = entry()
+            return e ? runsOf(e.label, e.positions) : []
+          })
+          box({
+            flexDirection: 'row',
+            overflow: 'hidden',
+            height: 1,
+            bg: () => (isCursor() ? t.surface.value : null),
+            onClick: () => {
+              const clicked = windowStart.value + row
+              if (clicked === cursor.value) choose()
+              else if (entry()) moveTo(clicked)
+            },
+            children: () => {
+              text({
+                content: () => {
+                  const e = entry()
+                  if (!e) return ''
+                  const mark = multi && marked.value.has(key(e.item)) ? '●' : ' '
+                  return `${isCursor() ? '▌' : ' '}${mark} `
+                },
+                fg: t.primary,
+                shrink: 0,
+              })
+              forEach(runs, (_, i) => i, (run) =>
+                text({
+                  content: () => run().text,
+                  wrap: 'nowrap',
+                  shrink: 0,
+                  bold: () => run().matched,
+                  fg: () => (run().matched ? t.accent.value : isCursor() ? t.textBright.value : t.text.value),
+                })
+              )
+            },
+          })
+        }
+
+        text({
+          content: () => {
+            if (loaded?.state === 'error') return `Failed: ${String(loaded.error)}`
+            const counts = `${ranked.value.length}/${source.value.length}`
+            const selection = marked.value.size > 0 ? ` (${marked.value.size} marked)` : ''
+            return `${counts}${selection}${loaded?.loading ? ' …' : ''}`
+          },
+          fg: () => (loaded?.state === 'error' ? t.error.value : t.textMuted.value),
+        })
+      },
+    })
+    focus(`${id}-query`)
+  })
+}
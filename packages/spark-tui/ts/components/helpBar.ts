@@ -0,0 +1,108 @@
+/**
+ * SparkTUI Components - Help bar
+ *
+ * A one-line key reference built from the keymap ("q Quit  / Search  Tab
+ * Next"), like Bubble Tea's help bubble. It lists what the keys do where
+ * focus is now, so it changes as focus moves between keymap contexts and
+ * as bindings are added or rebound.
+ *
+ * Entries come from `defineKeymap` bindings that have `help` text. Keys
+ * handled with plain `on()`/`onKey()` are opaque — list them in `extra`.
+ *
+ * ```ts
+ * defineKeymap('global', { quit: { keys: 'q', help: 'Quit' }, search: { keys: '/', help: 'Search' } })
+ * defineKeymap('#list', { open: { keys: 'Enter', help: 'Open' } })
+ *
+ * box({ flexDirection: 'column', children: () => {
+ *   main()
+ *   helpBar({ extra: [{ keys: 'Tab', help: 'Next' }] })
+ * }})
+ * ```
+ */
+
+import { derived } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { each } from '../primitives/each'
+import type { BoxProps, Cleanup } from '../primitives/types'
+import { Attr } from '../types'
+import { activeBindings, pendingKeys } from '../state/keymap'
+import { t } from '../state/theme'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export interface HelpEntry {
+  /** Key or keys as shown, e.g. 'Tab' or ['j', 'k'] */
+  keys: string | string[]
+  help: string
+}
+
+export interface HelpBarProps extends Omit<BoxProps, 'children'> {
+  /** Show only these actions, in this order — including ones without help text (shown by name) */
+  actions?: string[]
+  /** Entries for keys outside the keymap, shown after the bound ones */
+  extra?: HelpEntry[]
+  /** Between a key and its description (default: ' ') */
+  keySeparator?: string
+  /** Space between entries (default: 2) */
+  spacing?: number
+}
+
+interface Row {
+  id: string
+  keys: string
+  help: string
+}
+
+// =============================================================================
+// HELP BAR
+// =============================================================================
+
+export function helpBar(props: HelpBarProps = {}): Cleanup {
+  const { actions, extra = [], keySeparator = ' ', spacing = 2, ...rest } = props
+
+  const rows = derived((): Row[] => {
+    const bound = activeBindings()
+    const picked = actions
+      ? actions.flatMap((name) => bound.filter((b) => b.action === name))
+      : bound.filter((b) => b.help)
+    return [
+      ...picked.map((b) => ({ id: b.action, keys: b.keys.join('/'), help: b.help || b.action })),
+      ...extra.map((e, i) => ({
+        id: `extra-${i}`,
+        keys: Array.isArray(e.keys) ? e.keys.join('/') : e.keys,
+        help: e.help,
+      })),
+    ]
+  })
+
+  return box({
+    flexDirection: 'row',
+    flexWrap: 'wrap',
+    columnGap: spacing,
+    ...rest,
+    children: () => {
+      // Half-typed chord first: "ctrl+k …"
+      text({
+        content: () => (pendingKeys.value ? `${pendingKeys.value} …` : ''),
+        fg: t.warning,
+        attrs: Attr.BOLD,
+        visible: () => !!pendingKeys.value,
+      })
+      each(
+        () => rows.value,
+        (row) =>
+          box({
+            flexDirection: 'row',
+            children: () => {
+              text({ content: () => row().keys, fg: t.text, attrs: Attr.BOLD })
+              text({ content: () => `${keySeparator}${row().help}`, fg: t.textMuted })
+            },
+          }),
+        { key: (row) => row.id }
+      )
+    },
+  })
+}
@@ -0,0 +1,55 @@
+/**
+ * TUI Framework - Components
+ *
+ * Composite components assembled from the primitives.
+ */
+
+export { wizard } from './wizard'
+export { button } from './button'
+export { helpBar } from './helpBar'
+export { statusBar } from './statusBar'
+export { appShell } from './appShell'
+export { form, createForm } from './form'
+export { contextMenu, openContextMenu, closeContextMenu, isContextMenuOpen } from './contextMenu'
+export { logView } from './logView'
+export { createRouter, routerView } from './router'
+export { dialog } from './dialog'
+export { filePicker } from './filePicker'
+export { fuzzyList, fuzzyMatch } from './fuzzyList'
+export { pager } from './pager'
+export { calendar, datePicker } from './calendar'
+export { menuBar } from './menuBar'
+export { breadcrumbs } from './breadcrumbs'
+export { stepper } from './stepper'
+export { dashboardGrid } from './dashboardGrid'
+
+// Types
+export type { ButtonProps } from './button'
+export type { HelpBarProps, HelpEntry } from './helpBar'
+export type { StatusBarProps, StatusSegment } from './statusBar'
+export type { AppShellProps } from './appShell'
+export type {
+  Form,
+  FormProps,
+  FormOptions,
+  FormValues,
+  FieldValue,
+  FieldValidator,
+  InputFieldProps,
+  CheckboxFieldProps,
+  SelectFieldProps,
+  SelectOption,
+} from './form'
+export type { WizardProps, WizardStep, WizardStepContext, WizardValues } from './wizard'
+export type { MenuItem, MenuEntry, MenuItems, MenuOptions, RadioBinding } from './contextMenu'
+export type { LogViewProps, LogLineStyle } from './logView'
+export type { Router, RouterOptions, RouterViewProps, RouteRender, RouteParams, RouteLocation, RouteDirection } from './router'
+export type { ConfirmOptions, AlertOptions, PromptOptions } from './dialog'
+export type { FilePickerProps, FileEntry, FileSortKey } from './filePicker'
+export type { FuzzyListProps, FuzzyMatch } from './fuzzyList'
+export type { PagerProps } from './pager'
+export type { CalendarProps, DatePickerProps } from './calendar'
+export type { MenuBarProps, MenuBarMenu } from './menuBar'
+export type { BreadcrumbsProps } from './breadcrumbs'
+export type { StepperProps, StepperStep, StepperContext, StepValidation } from './stepper'
+export type { DashboardGridProps, DashboardPanel, DashboardLayout, PanelPlacement } from './dashboardGrid'
@@ -0,0 +1,155 @@
+/**
+ * SparkTUI Components - Log view
+ *
+ * A scrolling view over a long list of lines, one row each. Only the lines
+ * on screen (plus `overscan` above and below) have components, so a log of
+ * a million lines costs what a screenful does. Scrolling is the box's own:
+ * keys and wheel work as in any `overflow: 'scroll'` box, and Rust reports
+ * each new offset so the window of lines follows.
+ *
+ * ```ts
+ * const lines = signal<string[]>([])
+ * logView({
+ *   lines,
+ *   height: 20,
+ *   lineStyle: (line) =>
+ *     line.startsWith('ERROR') ? { fg: t.error, bold: true }
+ *     : line.startsWith('WARN') ? { fg: t.warning }
+ *     : undefined,
+ * })
+ * lines.value = [...lines.value, 'INFO started']
+ * ```
+ *
+ * Scrolled to the bottom, the view stays there as lines are added
+ * (`stickToBottom`, on by default); scrolling up holds the position until
+ * the user scrolls back down. Lines don't wrap — longer ones are truncated.
+ * With `smoothScroll`, the window covers everything between the offset and
+ * where it's easing to, so no blank rows pass by on the way.
+ */
+
+import { signal, derived } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { forEach } from '../primitives/each'
+import { onCleanup, scoped } from '../primitives/scope'
+import type { BoxProps, Cleanup, Reactive } from '../primitives/types'
+import type { ColorInput } from '../types'
+import { getBuffer, isInitialized } from '../bridge'
+import { getScrollTargetY, isScrollAnimating } from '../bridge/shared-buffer'
+import { getIndex } from '../engine/registry'
+import { registerScrollAnimateHandler, terminalSize } from '../engine/events'
+import { unwrap } from '../primitives/utils'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+/** How one line is drawn; unset fields inherit from the view */
+export interface LogLineStyle {
+  /** Color or theme color (`t.error`) */
+  fg?: Reactive<ColorInput>
+  bg?: Reactive<ColorInput>
+  bold?: boolean
+  dim?: boolean
+  italic?: boolean
+  underline?: boolean
+  strikethrough?: boolean
+}
+
+export interface LogViewProps extends Omit<BoxProps, 'children' | 'overflow'> {
+  /** The lines, oldest first */
+  lines: Reactive<readonly string[]>
+  /** Style for a line (severity colors); called again when the line at `index` changes */
+  lineStyle?: (line: string, index: number) => LogLineStyle | undefined
+  /** Lines kept rendered beyond each edge of the viewport (default: 10) */
+  overscan?: number
+}
+
+interface VisibleLine {
+  line: string
+  index: number
+}
+
+const DEFAULT_OVERSCAN = 10
+/** Viewport estimate before the terminal size is known */
+const FALLBACK_ROWS = 24
+
+let logViewCount = 0
+
+// =============================================================================
+// LOG VIEW
+// =============================================================================
+
+export function logView(props: LogViewProps): Cleanup {
+  const { lines, lineStyle, overscan = DEFAULT_OVERSCAN, stickToBottom = true, onScrollChange, ...rest } = props
+
+  return scoped(() => {
+    const id = rest.id ?? `log-view-${++logViewCount}`
+    const scrollY = signal(0)
+    /** Where a smooth scroll is easing to; the offset itself while none is */
+    const targetY = signal(0)
+    // No viewport is taller than the terminal, so its height bounds the window
+    const rows = derived(() => terminalSize().height || FALLBACK_ROWS)
+
+    const animating = () => {
+      const index = getIndex(id)
+      return index !== undefined && isInitialized() && isScrollAnimating(getBuffer(), index)
+    }
+    onCleanup(
+      registerScrollAnimateHandler((event) => {
+        if (event.componentIndex !== getIndex(id) || !isInitialized()) return
+        targetY.value = getScrollTargetY(getBuffer(), event.componentIndex)
+      })
+    )
+
+    const all = derived(() => unwrap(lines))
+    const visible = derived((): VisibleLine[] => {
+      const list = all.value
+      const top = Math.min(scrollY.value, targetY.value)
+      const bottom = Math.max(scrollY.value, targetY.value)
+      const start = Math.max(0, Math.min(top, list.length) - overscan)
+      const end = Math.min(list.length, bottom + rows.value + overscan)
+      return list.slice(start, end).map((line, i) => ({ line, index: start + i }))
+    })
+    const firstVisible = derived(() => visible.value[0]?.index ?? 0)
+
+    box({
+      ...rest,
+      id,
+      overflow: 'scroll',
+      stickToBottom,
+      onScrollChange: (x, y) => {
+        scrollY.value = y
+        if (!animating()) targetY.value = y
+        onScrollChange?.(x, y)
+      },
+      children: () => {
+        // Full height, so the box scrolls over every line; only the window is filled
+        box({
+          height: () => all.value.length,
+          shrink: 0,
+          flexDirection: 'column',
+          children: () => {
+            box({ height: () => firstVisible.value, shrink: 0 })
+            forEach(visible, (item) => item.index, (item) => {
+              const style = derived(() => lineStyle?.(item().line, item().index))
+              return text({
+                content: () => item().line,
+                wrap: 'truncate',
+                height: 1,
+                shrink: 0,
+                fg: () => unwrap(style.value?.fg ?? null),
+                bg: () => unwrap(style.value?.bg ?? null),
+                bold: () => style.value?.bold ?? false,
+                dim: () => style.value?.dim ?? false,
+                italic: () => style.value?.italic ?? false,
+                underline: () => style.value?.underline ?? false,
+                strikethrough: () => style.value?.strikethrough ?? false,
+              })
+            })
+          },
+        })
+      },
+    })
+  })
+}
@@ -0,0 +1,160 @@
+/**
+ * SparkTUI Components - Menu bar
+ *
+ * A File / Edit / View style bar across the top of an app:
+ *
+ * - Each title opens its menu beneath it — the context menu's overlay, so
+ *   submenus, separators, disabled, check and radio items all work the same
+ * - Alt+letter opens a menu by its mnemonic: the letter after `&` in its
+ *   title, else the first letter; F10 opens the first menu
+ * - With a menu open, ←/→ move to the neighbouring menu (→ opens a submenu
+ *   first where there is one), ↑↓ Enter Esc work as in any context menu
+ * - Clicking a title opens its menu
+ *
+ * ```ts
+ * const wordWrap = signal(true)
+ * const theme = signal('dark')
+ * menuBar({
+ *   menus: [
+ *     { title: '&File', items: [{ label: 'Open…', shortcut: 'Ctrl+O', onSelect: open }, '-', { label: 'Quit', onSelect: quit }] },
+ *     { title: '&View', items: [
+ *       { label: 'Word wrap', checked: wordWrap },
+ *       { label: 'Theme', submenu: ['dark', 'light'].map((name) => ({ label: name, radio: { selected: theme, value: name } })) },
+ *     ] },
+ *   ],
+ * })
+ * ```
+ */
+
+import { signal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { scoped, onCleanup } from '../primitives/scope'
+import type { BoxProps, Cleanup } from '../primitives/types'
+import { KEY_F10, type KeyEvent } from '../engine/events'
+import { getIndex } from '../engine/registry'
+import { on, matchesKey, isPress } from '../state/keyboard'
+import { t } from '../state/theme'
+import { openContextMenu, closeContextMenu, isContextMenuOpen, screenPosition } from './contextMenu'
+import type { MenuItems } from './contextMenu'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export interface MenuBarMenu {
+  /** Shown on the bar; `&` marks the mnemonic letter ('&File', 'E&xit') */
+  title: string
+  items: MenuItems
+  /** Shown dimmed, won't open */
+  disabled?: boolean
+}
+
+export interface MenuBarProps extends Omit<BoxProps, 'children'> {
+  menus: MenuBarMenu[]
+}
+
+/** A title split around its mnemonic letter */
+interface Title {
+  before: string
+  letter: string
+  after: string
+}
+
+let barCount = 0
+
+function parseTitle(title: string): Title {
+  const at = title.indexOf('&')
+  if (at < 0 || at === title.length - 1) return { before: '', letter: title.slice(0, 1), after: title.slice(1) }
+  return { before: title.slice(0, at), letter: title[at + 1]!, after: title.slice(at + 2) }
+}
+
+// =============================================================================
+// MENU BAR
+// =============================================================================
+
+export function menuBar(props: MenuBarProps): Cleanup {
+  const { menus, ...rest } = props
+
+  return scoped(() => {
+    const id = `menu-bar-${++barCount}`
+    const titles = menus.map((menu) => parseTitle(menu.title))
+    /** The menu showing, -1 when none is */
+    const active = signal(-1)
+
+    const open = (i: number) => {
+      const menu = menus[i]
+      const index = getIndex(`${id}-${i}`)
+      if (!menu || menu.disabled || index === undefined) return
+      const { x, y } = screenPosition(index)
+      openContextMenu(x, y + 1, menu.items, {
+        onLeave: (direction) => step(i, direction),
+        onClose: () => {
+          if (active.value === i) active.value = -1
+        },
+      })
+      if (isContextMenuOpen()) active.value = i
+    }
+
+    /** Open the next menu that can open, `direction` ways from `from` */
+    const step = (from: number, direction: -1 | 1) => {
+      for (let n = 1; n <= menus.length; n++) {
+        const i = (from + direction * n + menus.length * n) % menus.length
+        if (!menus[i]!.disabled) return open(i)
+      }
+    }
+
+    const toggle = (i: number) => {
+      if (active.value === i) closeContextMenu()
+      else open(i)
+    }
+
+    onCleanup(on((event: KeyEvent) => {
+      if (!isPress(event)) return
+      if (event.keycode === KEY_F10 && event.modifiers === 0 && !isContextMenuOpen()) {
+        step(-1, 1)
+        return true
+      }
+      const i = titles.findIndex((title, n) => !menus[n]!.disabled && matchesKey(event, `Alt+${title.letter.toLowerCase()}`))
+      if (i < 0) return
+      open(i)
+      return true
+    }))
+
+    // A menu still open when the bar goes would point at nothing
+    onCleanup(() => {
+      if (active.value >= 0) closeContextMenu()
+    })
+
+    box({
+      id,
+      flexDirection: 'row',
+      bg: t.surface,
+      role: 'menu',
+      ...rest,
+      children: () => {
+        menus.forEach((menu, i) => {
+          const title = titles[i]!
+          const isActive = () => active.value === i
+          const fg = () => (menu.disabled ? t.textDisabled.value : isActive() ? t.textBright.value : t.text.value)
+          box({
+            id: `${id}-${i}`,
+            flexDirection: 'row',
+            paddingLeft: 1,
+            paddingRight: 1,
+            shrink: 0,
+            bg: () => (isActive() ? t.primary.value : null),
+            role: 'menu item',
+            label: title.before + title.letter + title.after,
+            onClick: () => toggle(i),
+            children: () => {
+              if (title.before) text({ content: title.before, fg })
+              text({ content: title.letter, fg, underline: true })
+              if (title.after) text({ content: title.after, fg })
+            },
+          })
+        })
+      },
+    })
+  })
+}
@@ -0,0 +1,375 @@
+/**
+ * SparkTUI Components - Pager
+ *
+ * A less-style viewer for long text:
+ *
+ * - Lines wrap to the viewport's width; `w` turns wrapping off, and long
+ *   lines then scroll sideways (←/→, Shift+PgUp/PgDn)
+ * - `/` searches (smart case: a capital makes it exact), highlighting every
+ *   match; `n`/`N` jump to the next/previous one
+ * - `g`/`G` top and bottom, `j`/`k` a line, Space/`b` a page, `q` quits;
+ *   the arrows, PgUp/PgDn and Home/End scroll as in any scroll box
+ * - A status line shows the lines in view, how far through the text they
+ *   are, and where the current match is
+ * - Only the lines on screen have components, so a huge file costs what a
+ *   screenful does
+ *
+ * ```ts
+ * pager({
+ *   content: () => readme.value,
+ *   title: 'README.md',
+ *   grow: 1,
+ *   onQuit: () => router.back(),
+ * })
+ * ```
+ */
+
+import { signal, derived } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { input } from '../primitives/input'
+import { show } from '../primitives/show'
+import { forEach } from '../primitives/each'
+import { scoped, onCleanup } from '../primitives/scope'
+import type { BoxProps, Cleanup, Reactive } from '../primitives/types'
+import { getBuffer, getNotifier, isInitialized } from '../bridge'
+import { getScrollX, getTerminalSize, setScroll } from '../bridge/shared-buffer'
+import { getIndex } from '../engine/registry'
+import { observeComponents } from '../engine/observers'
+import type { ComponentObserver } from '../engine/observers'
+import { charWidth } from '../types/color'
+import { matchesKey, isPress } from '../state/keyboard'
+import { focus } from '../state/focus'
+import { t } from '../state/theme'
+import { unwrap } from '../primitives/utils'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export interface PagerProps extends Omit<BoxProps, 'children' | 'onKey'> {
+  /** The text; lines split on '\n' */
+  content: Reactive<string>
+  /** Shown at the start of the status line, e.g. a file name */
+  title?: Reactive<string>
+  /** Wrap long lines at first (default: true; `w` toggles) */
+  wrap?: boolean
+  /** Columns a tab advances to the next multiple of (default: 8) */
+  tabSize?: number
+  /** Called on `q` */
+  onQuit?(): void
+}
+
+/** One row on screen: part of a line, in UTF-16 offsets */
+interface Row {
+  line: number
+  start: number
+  end: number
+}
+
+interface Match {
+  line: number
+  start: number
+  end: number
+}
+
+/** A stretch of a row, matched or not */
+interface Run {
+  text: string
+  /** Not a match, a match, or the current match */
+  kind: 'plain' | 'match' | 'current'
+}
+
+const DEFAULT_TAB_SIZE = 8
+/** The vertical scrollbar takes a column from the text */
+const SCROLLBAR_WIDTH = 1
+/** Viewport estimate before the first layout */
+const FALLBACK_ROWS = 24
+
+let pagerCount = 0
+
+// =============================================================================
+// TEXT
+// =============================================================================
+
+function expandTabs(line: string, tabSize: number): string {
+  if (!line.includes('\t')) return line
+  let out = ''
+  let column = 0
+  for (const char of line) {
+    if (char === '\t') {
+      const spaces = tabSize - (column % tabSize)
+      out += ' '.repeat(spaces)
+      column += spaces
+    } else {
+      out += char
+      column += charWidth(char)
+    }
+  }
+  return out
+}
+
+/** A line's rows at `columns` wide; wide characters never straddle two */
+function wrapLine(line: string, index: number, columns: number): Row[] {
+  const rows: Row[] = []
+  let start = 0
+  let offset = 0
+  let width = 0
+  for (const char of line) {
+    const w = charWidth(char)
+    if (width + w > columns && offset > start) {
+      rows.push({ line: index, start, end: offset })
+      start = offset
+      width = 0
+    }
+    width += w
+    offset += char.length
+  }
+  rows.push({ line: index, start, end: line.length })
+  return rows
+}
+
+/** Every occurrence of `query`, smart case */
+function findMatches(lines: string[], query: string): Match[] {
+  if (!query) return []
+  const exact = query !== query.toLowerCase()
+  const needle = exact ? query : query.toLowerCase()
+  const matches: Match[] = []
+  lines.forEach((line, index) => {
+    const hay = exact ? line : line.toLowerCase()
+    for (let at = hay.indexOf(needle); at >= 0; at = hay.indexOf(needle, at + needle.length)) {
+      matches.push({ line: index, start: at, end: at + needle.length })
+    }
+  })
+  return matches
+}
+
+/** A row split where matches start and end */
+function runsOf(line: string, row: Row, matches: Match[], current: Match | undefined): Run[] {
+  const runs: Run[] = []
+  let at = row.start
+  for (const match of matches) {
+    if (match.end <= row.start || match.start >= row.end) continue
+    const start = Math.max(match.start, row.start)
+    const end = Math.min(match.end, row.end)
+    if (start > at) runs.push({ text: line.slice(at, start), kind: 'plain' })
+    runs.push({ text: line.slice(start, end), kind: match === current ? 'current' : 'match' })
+    at = end
+  }
+  if (at < row.end || runs.length === 0) runs.push({ text: line.slice(at, row.end), kind: 'plain' })
+  return runs
+}
+
+// =============================================================================
+// PAGER
+// =============================================================================
+
+export function pager(props: PagerProps): Cleanup {
+  const { content, title, wrap: initialWrap = true, tabSize = DEFAULT_TAB_SIZE, onQuit, ...rest } = props
+
+  return scoped(() => {
+    const id = `pager-${++pagerCount}`
+    const viewportId = `${id}-viewport`
+    const wrap = signal(initialWrap)
+    const scrollY = signal(0)
+    const terminal = isInitialized() ? getTerminalSize(getBuffer()) : null
+    const viewport = signal({ width: terminal?.width ?? 0, height: terminal?.height ?? FALLBACK_ROWS })
+
+    // The viewport's size decides the wrap width and the status line's position
+    const observer: ComponentObserver = {
+      layoutChanged: (component, rect) => {
+        if (component.id === viewportId) viewport.value = { width: rect.width, height: rect.height }
+      },
+    }
+    onCleanup(observeComponents(observer))
+
+    const lines = derived(() => unwrap(content).split('\n').map((line) => expandTabs(line, tabSize)))
+    const rows = derived((): Row[] => {
+      const columns = Math.max(1, viewport.value.width - SCROLLBAR_WIDTH)
+      return lines.value.flatMap((line, index) => (wrap.value ? wrapLine(line, index, columns) : [{ line: index, start: 0, end: line.length }]))
+    })
+
+    // Search
+    const searching = signal(false)
+    const draft = signal('')
+    const query = signal('')
+    const matches = derived(() => findMatches(lines.value, query.value))
+    const currentMatch = signal(-1)
+    const matchesByLine = derived(() => {
+      const byLine = new Map<number, Match[]>()
+      for (const match of matches.value) {
+        const list = byLine.get(match.line)
+        if (list) list.push(match)
+        else byLine.set(match.line, [match])
+      }
+      return byLine
+    })
+
+    /** First row showing `offset` of `line` */
+    const rowOf = (line: number, offset = 0) => {
+      const all = rows.value
+      const index = all.findIndex((row) => row.line === line && offset < Math.max(row.end, row.start + 1))
+      return index < 0 ? 0 : index
+    }
+
+    const scrollTo = (y: number) => {
+      const index = getIndex(viewportId)
+      if (index === undefined || !isInitialized()) return
+      const buf = getBuffer()
+      // From the rows rather than Rust's maximum, which trails a change of content until the next layout
+      const clamped = Math.max(0, Math.min(Math.round(y), rows.value.length - viewport.value.height))
+      setScroll(buf, index, getScrollX(buf, index), clamped)
+      scrollY.value = clamped
+      getNotifier().notify()
+    }
+
+    /** Show match `i`, wrapping around the ends, on the top row */
+    const showMatch = (i: number) => {
+      const count = matches.value.length
+      if (count === 0) return
+      const index = ((i % count) + count) % count
+      currentMatch.value = index
+      const match = matches.value[index]!
+      scrollTo(rowOf(match.line, match.start))
+    }
+
+    /** The first match at or after the top row, going `step` ways from there */
+    const nextMatch = (step: 1 | -1) => {
+      if (currentMatch.value >= 0) return showMatch(currentMatch.value + step)
+      const top = rows.value[scrollY.value]
+      const from = top ? matches.value.findIndex((m) => m.line > top.line || (m.line === top.line && m.end > top.start)) : 0
+      showMatch(from < 0 ? 0 : step > 0 ? from : from - 1)
+    }
+
+    const search = () => {
+      searching.value = false
+      query.value = draft.value
+      currentMatch.value = -1
+      focus(viewportId)
+      nextMatch(1)
+    }
+
+    const toggleWrap = () => {
+      // Keep the top line in view across the change
+      const top = rows.value[scrollY.value]
+      wrap.value = !wrap.value
+      if (top) scrollTo(rowOf(top.line, top.start))
+    }
+
+    const page = () => Math.max(1, viewport.value.height)
+
+    box({
+      id,
+      flexDirection: 'column',
+      ...rest,
+      children: () => {
+        box({
+          id: viewportId,
+          grow: 1,
+          overflowY: 'scroll',
+          overflowX: () => (wrap.value ? 'hidden' : 'scroll'),
+          onScrollChange: (_, y) => {
+            scrollY.value = y
+          },
+          onKey: (event) => {
+            if (!isPress(event)) return
+            if (matchesKey(event, '/')) {
+              draft.value = query.value
+              searching.value = true
+              focus(`${id}-search`)
+            }
+            else if (matchesKey(event, 'n')) nextMatch(1)
+            else if (matchesKey(event, 'Shift+N')) nextMatch(-1)
+            else if (matchesKey(event, 'g')) scrollTo(0)
+            else if (matchesKey(event, 'Shift+G')) scrollTo(rows.value.length)
+            else if (matchesKey(event, 'j')) scrollTo(scrollY.value + 1)
+            else if (matchesKey(event, 'k')) scrollTo(scrollY.value - 1)
+            else if (matchesKey(event, 'Space')) scrollTo(scrollY.value + page())
+            else if (matchesKey(event, 'b')) scrollTo(scrollY.value - page())
+            else if (matchesKey(event, 'w')) toggleWrap()
+            else if (matchesKey(event, 'q')) onQuit?.()
+            else return
+            return true
+          },
+          children: () => {
+            // Full height, so the box scrolls over every row; only the window is filled
+            const first = derived(() => Math.min(scrollY.value, rows.value.length))
+            const visible = derived(() =>
+              rows.value.slice(first.value, first.value + viewport.value.height).map((row, i) => ({ row, index: first.value + i }))
+            )
+            box({
+              height: () => rows.value.length,
+              shrink: 0,
+              flexDirection: 'column',
+              children: () => {
+                box({ height: () => first.value, shrink: 0 })
+                forEach(visible, (entry) => entry.index, (entry) => {
+                  const runs = derived(() => {
+                    const { row } = entry()
+                    const current = matches.value[currentMatch.value]
+                    return runsOf(lines.value[row.line] ?? '', row, matchesByLine.value.get(row.line) ?? [], current)
+                  })
+                  return box({
+                    flexDirection: 'row',
+                    height: 1,
+                    shrink: 0,
+                    children: () => {
+                      forEach(runs, (_, i) => i, (run) =>
+                        text({
+                          content: () => run().text,
+                          wrap: 'nowrap',
+                          shrink: 0,
+                          inverse: () => run().kind === 'match',
+                          bg: () => (run().kind === 'current' ? t.warning.value : null),
+                          fg: () => (run().kind === 'current' ? t.bg.value : null),
+                        })
+                      )
+                    },
+                  })
+                })
+              },
+            })
+          },
+        })
+
+        show(
+          () => searching.value,
+          () =>
+            box({
+              flexDirection: 'row',
+              children: () => {
+                text({ content: '/', shrink: 0 })
+                input({
+                  id: `${id}-search`,
+                  value: draft,
+                  grow: 1,
+                  onSubmit: search,
+                  onCancel: () => {
+                    searching.value = false
+                    focus(viewportId)
+                  },
+                })
+              },
+            }),
+          () =>
+            text({
+              content: () => {
+                const total = rows.value.length
+                const last = Math.min(total, scrollY.value + viewport.value.height)
+                const percent = total === 0 ? 100 : Math.round((last / total) * 100)
+                const name = title !== undefined ? `${unwrap(title)}  ` : ''
+                const position = `rows ${Math.min(total, scrollY.value + 1)}-${last}/${total} ${percent}%`
+                const count = matches.value.length
+                const found =
+                  !query.value ? '' : count === 0 ? `  Pattern not found: ${query.value}` : `  match ${currentMatch.value + 1}/${count}`
+                return `${name}${position}${found}`
+              },
+              wrap: 'truncate',
+              inverse: true,
+            })
+        )
+      },
+    })
+    focus(viewportId)
+  })
+}
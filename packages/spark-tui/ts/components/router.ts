@@ -0,0 +1,286 @@
+/**
+ * SparkTUI Components - Router
+ *
+ * Named screens for multi-screen apps, with a browser-style history:
+ *
+ * - Routes map a name to a render function that gets the route's params
+ * - `navigate` pushes a screen (dropping anything forward of it), `replace`
+ *   swaps the current one, `back`/`forward` move through the history
+ * - Each screen mounts fresh when it opens and is cleaned up when it's left,
+ *   with an optional enter transition that can differ by direction
+ * - The focused component is remembered per history entry, by its place in
+ *   the screen, and focused again when the entry is returned to
+ *
+ * ```ts
+ * const router = createRouter({
+ *   initial: 'inbox',
+ *   routes: {
+ *     inbox: (_, r) => mailList({ onOpen: (id) => r.navigate('message', { id }) }),
+ *     message: ({ id }) => messageView(String(id)),
+ *   },
+ *   transition: (direction) => ({ effect: direction === 'back' ? 'slide-right' : 'slide-left', duration: 150 }),
+ * })
+ *
+ * routerView({ router, grow: 1 })
+ * on((e) => (matchesKey(e, 'Escape') ? router.back() : false))
+ * ```
+ */
+
+import { signal, derived, batch, effect, effectScope, onScopeDispose } from '@rlabs-inc/signals'
+import type { ReadableSignal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { scoped } from '../primitives/scope'
+import { createSlot } from '../primitives/slot'
+import type { BoxProps, Cleanup, TransitionSpec } from '../primitives/types'
+import { getCurrentParentIndex } from '../engine/registry'
+import { getBuffer, isInitialized } from '../bridge'
+import { getParentIndex, iterChildren } from '../bridge/shared-buffer'
+import type { SharedBuffer } from '../bridge/shared-buffer'
+import { focus, focusedIndex } from '../state/focus'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export type RouteParams = Record<string, string | number | boolean>
+
+/** Render a screen; runs in its own scope, cleaned up when the screen is left */
+export type RouteRender = (params: RouteParams, router: Router) => void
+
+/** Which way the history moved to reach a screen */
+export type RouteDirection = 'forward' | 'back' | 'replace'
+
+export interface RouteLocation {
+  name: string
+  params: RouteParams
+}
+
+export interface RouterOptions {
+  routes: Record<string, RouteRender>
+  /** Route shown first */
+  initial: string
+  /** Params of the first route */
+  params?: RouteParams
+  /** Enter transition of each screen, or one chosen per direction */
+  transition?: TransitionSpec | ((direction: RouteDirection) => TransitionSpec | undefined)
+  /** Called after every change of screen */
+  onChange?(to: RouteLocation, from: RouteLocation): void
+  /** Most entries kept; the oldest are dropped (default: 50) */
+  maxHistory?: number
+}
+
+export interface Router {
+  /** The screen showing */
+  current: ReadableSignal<RouteLocation>
+  canGoBack: ReadableSignal<boolean>
+  canGoForward: ReadableSignal<boolean>
+  /** Open a screen after the current one; entries forward of it are dropped */
+  navigate(name: string, params?: RouteParams): void
+  /** Open a screen in place of the current one */
+  replace(name: string, params?: RouteParams): void
+  /** Return to the previous screen; false when there is none */
+  back(): boolean
+  /** Redo a `back`; false when there is nothing ahead */
+  forward(): boolean
+}
+
+export interface RouterViewProps extends Omit<BoxProps, 'children'> {
+  router: Router
+}
+
+/** One visit to a screen */
+interface HistoryEntry extends RouteLocation {
+  /** Remounts the screen when a new entry shows the same route */
+  key: number
+  /** Child positions from the screen's box down to what was focused when
+   * the entry was left; the remounted screen has the same shape */
+  focused: number[] | null
+}
+
+interface RouterState {
+  options: RouterOptions
+  entry: ReadableSignal<HistoryEntry>
+  /** How the current entry was reached */
+  direction(): RouteDirection
+  /** The box of the screen showing, -1 before one mounts */
+  screen: number
+}
+
+const DEFAULT_MAX_HISTORY = 50
+
+/** Views find their router's internals here; routers stay plain objects */
+const states = new WeakMap<Router, RouterState>()
+
+// =============================================================================
+// ROUTER
+// =============================================================================
+
+export function createRouter(options: RouterOptions): Router {
+  const { routes, maxHistory = DEFAULT_MAX_HISTORY } = options
+  const check = (name: string) => {
+    if (!Object.hasOwn(routes, name)) throw new Error(`Unknown route '${name}'`)
+  }
+  check(options.initial)
+
+  let nextKey = 0
+  const visit = (name: string, params: RouteParams = {}): HistoryEntry => ({ name, params, key: nextKey++, focused: null })
+
+  const entries = signal<HistoryEntry[]>([visit(options.initial, options.params)])
+  const position = signal(0)
+  let direction: RouteDirection = 'forward'
+  const entry = derived(() => entries.value[position.value]!)
+
+  const go = (next: HistoryEntry[], to: number, how: RouteDirection) => {
+    const from = entry.value
+    from.focused = isInitialized() ? pathTo(getBuffer(), state.screen, focusedIndex.value) : null
+    direction = how
+    batch(() => {
+      entries.value = next
+      position.value = to
+    })
+    options.onChange?.(locationOf(entry.value), locationOf(from))
+  }
+
+  const state: RouterState = { options, entry, direction: () => direction, screen: -1 }
+  const router: Router = {
+    current: derived(() => locationOf(entry.value)),
+    canGoBack: derived(() => position.value > 0),
+    canGoForward: derived(() => position.value < entries.value.length - 1),
+    navigate(name, params) {
+      check(name)
+      const kept = [...entries.value.slice(0, position.value + 1), visit(name, params)]
+      const next = kept.slice(Math.max(0, kept.length - maxHistory))
+      go(next, next.length - 1, 'forward')
+    },
+    replace(name, params) {
+      check(name)
+      const next = [...entries.value]
+      next[position.value] = visit(name, params)
+      go(next, position.value, 'replace')
+    },
+    back() {
+      if (position.value === 0) return false
+      go(entries.value, position.value - 1, 'back')
+      return true
+    },
+    forward() {
+      if (position.value >= entries.value.length - 1) return false
+      go(entries.value, position.value + 1, 'forward')
+      return true
+    },
+  }
+  states.set(router, state)
+  return router
+}
+
+function locationOf(entry: HistoryEntry): RouteLocation {
+  return { name: entry.name, params: entry.params }
+}
+
+/** Child positions from `root` down to `index`; null unless it's inside */
+function pathTo(buf: SharedBuffer, root: number, index: number): number[] | null {
+  if (root < 0 || index < 0) return null
+  const path: number[] = []
+  for (let node = index; node !== root; ) {
+    const parent = getParentIndex(buf, node)
+    if (parent < 0) return null
+    path.unshift([...iterChildren(buf, parent)].indexOf(node))
+    node = parent
+  }
+  return path
+}
+
+/** The node at `path` under `root`, -1 if the tree has no such place */
+function nodeAt(buf: SharedBuffer, root: number, path: number[]): number {
+  let node = root
+  for (const position of path) {
+    node = [...iterChildren(buf, node)][position] ?? -1
+    if (node < 0) return -1
+  }
+  return node
+}
+
+// =============================================================================
+// VIEW
+// =============================================================================
+
+/**
+ * Show the router's current screen, in a box that grows to fill its parent.
+ * The previous screen is cleaned up before the next one mounts.
+ */
+export function routerView(props: RouterViewProps): Cleanup {
+  const { router, ...rest } = props
+  const state = states.get(router)
+  if (!state) throw new Error('routerView() needs a router from createRouter()')
+
+  return box({
+    grow: 1,
+    flexDirection: 'column',
+    ...rest,
+    children: () => {
+      screens(router, state)
+    },
+  })
+}
+
+/** Mount the current entry's screen where this is declared (as `match` does) */
+function screens(router: Router, state: RouterState): Cleanup {
+  const { options, entry, direction } = state
+  let cleanup: Cleanup | null = null
+  let shown: number | null = null
+  const slot = createSlot(getCurrentParentIndex())
+  const scope = effectScope()
+
+  const update = (current: HistoryEntry) => {
+    if (current.key === shown) return
+    shown = current.key
+
+    if (cleanup) {
+      cleanup()
+      cleanup = null
+    }
+
+    const enter = typeof options.transition === 'function' ? options.transition(direction()) : options.transition
+    const mounted = slot.mount(() =>
+      box({
+        grow: 1,
+        flexDirection: 'column',
+        transition: enter ? { enter } : undefined,
+        children: () => {
+          scoped(() => options.routes[current.name]!(current.params, router))
+        },
+      })
+    )
+    cleanup = mounted.cleanup
+    slot.place([mounted.nodes])
+    state.screen = mounted.nodes[0] ?? -1
+    if (current.focused && isInitialized()) {
+      const node = nodeAt(getBuffer(), state.screen, current.focused)
+      if (node >= 0) focus(node)
+    }
+  }
+
+  scope.run(() => {
+    // Initial render
+    update(entry.value)
+
+    // Tracks the entry; the first run only subscribes
+    let initialized = false
+    effect(() => {
+      const current = entry.value
+      if (!initialized) {
+        initialized = true
+        return
+      }
+      update(current)
+    })
+
+    onScopeDispose(() => {
+      if (cleanup) cleanup()
+      slot.dispose()
+      state.screen = -1
+    })
+  })
+
+  return () => scope.stop()
+}
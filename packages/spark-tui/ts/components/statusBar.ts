@@ -0,0 +1,68 @@
+/**
+ * SparkTUI Components - Status bar
+ *
+ * A one-line bar with left, center and right segments. Each segment is a
+ * value, signal or getter — or `{ render }` for richer content:
+ *
+ * ```ts
+ * statusBar({
+ *   left: () => `${mode.value} │ ${file.value}`,
+ *   center: () => (dirty.value ? '● modified' : ''),
+ *   right: () => `Ln ${line.value}, Col ${col.value}`,
+ * })
+ * statusBar({ right: { render: () => text({ content: '● live', fg: t.success }) } })
+ * ```
+ *
+ * The center stays centered however long the sides grow; the sides
+ * truncate before it does.
+ */
+
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import type { BoxProps, Cleanup, Reactive } from '../primitives/types'
+import { t } from '../state/theme'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+/** Text of a segment, or `{ render }` drawing its own components */
+export type StatusSegment = Reactive<string | number> | { render: () => void }
+
+export interface StatusBarProps extends Omit<BoxProps, 'children'> {
+  left?: StatusSegment
+  center?: StatusSegment
+  right?: StatusSegment
+}
+
+// =============================================================================
+// STATUS BAR
+// =============================================================================
+
+export function statusBar(props: StatusBarProps = {}): Cleanup {
+  const { left, center, right, ...rest } = props
+
+  const segment = (content: StatusSegment | undefined, align: 'left' | 'center' | 'right') => {
+    if (content === undefined) return
+    if (typeof content === 'object' && content !== null && 'render' in content) content.render()
+    else text({ content, align, wrap: 'truncate' })
+  }
+
+  return box({
+    flexDirection: 'row',
+    width: '100%',
+    height: 1,
+    flexShrink: 0,
+    paddingLeft: 1,
+    paddingRight: 1,
+    columnGap: 2,
+    bg: t.surface,
+    fg: t.textMuted,
+    ...rest,
+    children: () => {
+      box({ flexGrow: 1, flexBasis: 0, flexDirection: 'row', justifyContent: 'flex-start', overflow: 'hidden', children: () => segment(left, 'left') })
+      box({ flexShrink: 0, flexDirection: 'row', justifyContent: 'center', children: () => segment(center, 'center') })
+      box({ flexGrow: 1, flexBasis: 0, flexDirection: 'row', justifyContent: 'flex-end', overflow: 'hidden', children: () => segment(right, 'right') })
+    },
+  })
+}
@@ -0,0 +1,271 @@
+/**
+ * SparkTUI Components - Stepper
+ *
+ * A container for installer-style flows: one step showing at a time, with
+ * the way through them laid out above.
+ *
+ * - Step titles (✓ done, ● current, ○ ahead) over a progress bar
+ * - Next runs the step's `validate`, which may be async (checking a path,
+ *   a licence key…); Next and Back wait while it runs, and its error shows
+ *   under the step
+ * - `skip` passes a step over in both directions; `noBack` keeps the user
+ *   from returning past a step that did something (an install)
+ * - `setBusy` holds the buttons while a step works
+ * - Enter and Escape that no component inside took mean Next and Back
+ *
+ * It isn't modal and owns no values: steps keep their state in signals of
+ * their own. `wizard` is a stepper in a modal panel that keeps its steps'
+ * field values.
+ *
+ * ```ts
+ * const target = signal('/opt/app')
+ * stepper({
+ *   steps: [
+ *     { title: 'Welcome', render: () => text({ content: 'This installs App.' }) },
+ *     {
+ *       title: 'Location',
+ *       render: (ctx) => input({ id: 'target', value: target, onSubmit: ctx.next }),
+ *       validate: async () => ((await isWritable(target.value)) ? null : `Can't write to ${target.value}`),
+ *       focus: 'target',
+ *     },
+ *     { title: 'Install', render: (ctx) => installStep(ctx), noBack: true },
+ *   ],
+ *   onComplete: () => exit(),
+ * })
+ * ```
+ */
+
+import { signal, derived } from '@rlabs-inc/signals'
+import type { ReadableSignal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { show } from '../primitives/show'
+import { scoped } from '../primitives/scope'
+import type { BoxProps, Cleanup } from '../primitives/types'
+import { matchesKey, isPress } from '../state/keyboard'
+import { focus } from '../state/focus'
+import { t } from '../state/theme'
+import { button } from './button'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export type StepValidation = string | null | undefined
+
+export interface StepperContext {
+  /** Index of the step showing */
+  index: ReadableSignal<number>
+  /** Validate this step and go on (or complete after the last) */
+  next(): void
+  /** Go back a step (or cancel on the first) */
+  back(): void
+  /** While busy, Next and Back are held */
+  setBusy(busy: boolean): void
+}
+
+export interface StepperStep {
+  title: string
+  /** Render the step body; runs each time the step opens */
+  render(ctx: StepperContext): void
+  /** Error message when the step can't be left forward, checked on Next */
+  validate?(): StepValidation | Promise<StepValidation>
+  /** Passed over while true */
+  skip?(): boolean
+  /** Back from here is unavailable */
+  noBack?: boolean
+  /** Component ID to focus when the step opens */
+  focus?: string
+}
+
+export interface StepperProps extends Omit<BoxProps, 'children' | 'onKey'> {
+  steps: StepperStep[]
+  /** Called after the last step validates */
+  onComplete(): void
+  /** Called on Back from the first step */
+  onCancel?(): void
+  /** default: 'Next' */
+  nextLabel?: string
+  /** default: 'Back' */
+  backLabel?: string
+  /** Label of Back on the first step (default: `backLabel`) */
+  cancelLabel?: string
+  /** Label of Next on the last step (default: 'Finish') */
+  finishLabel?: string
+}
+
+// =============================================================================
+// STEPPER
+// =============================================================================
+
+export function stepper(props: StepperProps): Cleanup {
+  const {
+    steps,
+    onComplete,
+    onCancel,
+    nextLabel = 'Next',
+    backLabel = 'Back',
+    cancelLabel = backLabel,
+    finishLabel = 'Finish',
+    ...rest
+  } = props
+  if (steps.length === 0) {
+    throw new Error('stepper() requires at least one step')
+  }
+
+  return scoped(() => {
+    const isSkipped = (i: number) => steps[i]!.skip?.() ?? false
+    const firstStep = steps.findIndex((_, i) => !isSkipped(i))
+    const index = signal(firstStep < 0 ? 0 : firstStep)
+    const error = signal<string | null>(null)
+    const busy = signal(false)
+    const validating = signal(false)
+    const held = () => busy.value || validating.value
+
+    /** The next step not skipped, `direction` ways from the current one; -1 past the ends */
+    const neighbour = (direction: 1 | -1) => {
+      for (let i = index.value + direction; i >= 0 && i < steps.length; i += direction) {
+        if (!isSkipped(i)) return i
+      }
+      return -1
+    }
+    const isFirst = derived(() => neighbour(-1) < 0)
+    const isLast = derived(() => neighbour(1) < 0)
+    const shown = derived(() => steps.map((_, i) => i).filter((i) => i === index.value || !isSkipped(i)))
+
+    const go = (to: number) => {
+      error.value = null
+      if (to < 0) return
+      index.value = to
+    }
+
+    const next = async () => {
+      if (held()) return
+      const from = index.value
+      validating.value = true
+      let message: StepValidation
+      try {
+        message = await steps[from]!.validate?.()
+      } catch (err) {
+        message = err instanceof Error ? err.message : String(err)
+      } finally {
+        validating.value = false
+      }
+      // Moved away while it was checking: the answer is for another step
+      if (index.value !== from) return
+      if (message) {
+        error.value = message
+        return
+      }
+      const to = neighbour(1)
+      if (to < 0) {
+        error.value = null
+        onComplete()
+      } else go(to)
+    }
+
+    const back = () => {
+      if (held() || steps[index.value]!.noBack) return
+      const to = neighbour(-1)
+      if (to < 0) onCancel?.()
+      else go(to)
+    }
+
+    const context: StepperContext = {
+      index,
+      next: () => void next(),
+      back,
+      setBusy: (value) => (busy.value = value),
+    }
+
+    box({
+      flexDirection: 'column',
+      gap: 1,
+      ...rest,
+      onKey: (event) => {
+        if (!isPress(event)) return
+        if (matchesKey(event, 'Enter')) void next()
+        else if (matchesKey(event, 'Escape')) back()
+        else return
+        return true
+      },
+      children: () => {
+        // Step titles
+        text({
+          content: () =>
+            shown.value
+              .map((i) => `${i < index.value ? '✓' : i === index.value ? '●' : '○'} ${steps[i]!.title}`)
+              .join('  ─  '),
+          fg: t.textMuted,
+        })
+
+        // Progress: the share of (unskipped) steps done
+        box({
+          flexDirection: 'row',
+          gap: 1,
+          children: () => {
+            box({
+              grow: 1,
+              height: 1,
+              bg: t.surface,
+              children: () => {
+                box({
+                  height: 1,
+                  width: () => `${Math.round((shown.value.indexOf(index.value) / Math.max(1, shown.value.length - 1)) * 100)}%`,
+                  bg: t.primary,
+                })
+              },
+            })
+            text({
+              content: () => `${shown.value.indexOf(index.value) + 1}/${shown.value.length}`,
+              fg: t.textMuted,
+              shrink: 0,
+            })
+          },
+        })
+
+        // Body: one show() per step so each step's components live only while it's open
+        box({
+          flexDirection: 'column',
+          grow: 1,
+          children: () => {
+            steps.forEach((step, i) => {
+              show(
+                () => index.value === i,
+                () => {
+                  const cleanup = scoped(() => step.render(context))
+                  if (step.focus) focus(step.focus)
+                  return cleanup
+                }
+              )
+            })
+          },
+        })
+
+        text({
+          content: () => (validating.value ? 'Checking…' : error.value ?? ''),
+          fg: () => (validating.value ? t.textMuted.value : t.error.value),
+        })
+
+        box({
+          flexDirection: 'row',
+          justifyContent: 'flex-end',
+          gap: 2,
+          children: () => {
+            button({
+              label: () => (isFirst.value ? cancelLabel : backLabel),
+              variant: 'secondary',
+              disabled: () => held() || !!steps[index.value]!.noBack,
+              onClick: back,
+            })
+            button({
+              label: () => (isLast.value ? finishLabel : nextLabel),
+              disabled: held,
+              onClick: () => void next(),
+            })
+          },
+        })
+      },
+    })
+  })
+}
@@ -0,0 +1,168 @@
+/**
+ * SparkTUI Components - Wizard
+ *
+ * A multi-step modal flow: a `stepper` in a modal panel that keeps the
+ * values entered along the way.
+ *
+ * - Step indicator and progress, validation gating Next, `skip`/`noBack`,
+ *   focus moved into each step as it opens — all from `stepper`
+ * - Back navigation that keeps everything already entered
+ * - Focus kept inside the wizard (Tab cycles its fields and buttons),
+ *   handed back when the wizard closes
+ * - One submit callback with every step's values
+ *
+ * It's modal like a dialog: a backdrop covers what's behind it (show it at
+ * the top of the tree, as below, so that's the whole app), and keys reach
+ * nothing outside it. Enter that nothing inside took means Next (or
+ * Submit), Escape means Back (or cancel on the first step). Inputs swallow
+ * both keys, so wire their `onSubmit` to `ctx.next` for Enter-to-advance
+ * from a field.
+ *
+ * ```ts
+ * show(() => open.value, () => wizard({
+ *   title: 'New project',
+ *   steps: [
+ *     {
+ *       id: 'project',
+ *       title: 'Project',
+ *       focus: 'project-name',
+ *       render: (ctx) => input({ id: 'project-name', value: ctx.field('name'), onSubmit: ctx.next }),
+ *       validate: (v) => (v.name ? null : 'Name is required'),
+ *     },
+ *     { id: 'confirm', title: 'Confirm', render: () => text({ content: 'Ready!' }) },
+ *   ],
+ *   onSubmit: (data) => create(data.project.name),
+ *   onCancel: () => (open.value = false),
+ * }))
+ * ```
+ */
+
+import { signal } from '@rlabs-inc/signals'
+import type { WritableSignal } from '@rlabs-inc/signals'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+import { scoped } from '../primitives/scope'
+import type { Cleanup } from '../primitives/types'
+import { Attr, BorderStyle } from '../types'
+import type { Dimension } from '../types'
+import { opaque } from '../types/color'
+import { t } from '../state/theme'
+import { stepper } from './stepper'
+import type { StepperContext, StepperStep, StepValidation } from './stepper'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+/** Field values of one step, by field name */
+export type WizardValues = Record<string, string>
+
+export interface WizardStepContext extends StepperContext {
+  /** The signal behind a field. Values survive Back/Next — the wizard owns them. */
+  field(name: string, initial?: string): WritableSignal<string>
+}
+
+export interface WizardStep extends Omit<StepperStep, 'render' | 'validate'> {
+  /** Key for this step's values in the submitted data */
+  id: string
+  /** Render the step body */
+  render(ctx: WizardStepContext): void
+  /** Error message when the step can't be left forward, checked on Next */
+  validate?(values: WizardValues): StepValidation | Promise<StepValidation>
+}
+
+export interface WizardProps {
+  steps: WizardStep[]
+  /** Called with every step's values after the last step validates */
+  onSubmit(data: Record<string, WizardValues>): void
+  /** Called on Back from the first step */
+  onCancel?(): void
+  /** Heading above the step indicator */
+  title?: string
+  /** Panel width (default: 60) */
+  width?: Dimension
+  /** Label of the final button (default: 'Submit') */
+  submitLabel?: string
+}
+
+const DEFAULT_WIDTH = 60
+/** Under dialogs, so a step can still ask something */
+const WIZARD_Z_INDEX = 1900
+
+// =============================================================================
+// WIZARD
+// =============================================================================
+
+export function wizard(props: WizardProps): Cleanup {
+  const { steps, title, width = DEFAULT_WIDTH, submitLabel = 'Submit' } = props
+  if (steps.length === 0) {
+    throw new Error('wizard() requires at least one step')
+  }
+
+  return scoped(() => {
+    const fields = steps.map(() => new Map<string, WritableSignal<string>>())
+
+    const valuesOf = (step: number): WizardValues => {
+      const values: WizardValues = {}
+      for (const [name, sig] of fields[step]!) values[name] = sig.value
+      return values
+    }
+
+    const field = (step: number) => (name: string, initial = '') => {
+      let sig = fields[step]!.get(name)
+      if (!sig) {
+        sig = signal(initial)
+        fields[step]!.set(name, sig)
+      }
+      return sig
+    }
+
+    const submit = () => {
+      const data: Record<string, WizardValues> = {}
+      steps.forEach((step, i) => (data[step.id] = valuesOf(i)))
+      props.onSubmit(data)
+    }
+
+    // Modal: a backdrop trapping focus (which the engine hands back when
+    // it goes); keys from inside that nothing took stop here
+    box({
+      width: '100%',
+      height: '100%',
+      justifyContent: 'center',
+      alignItems: 'center',
+      zIndex: WIZARD_Z_INDEX,
+      pointerEvents: 'all',
+      trapFocus: true,
+      role: 'dialog',
+      label: title ?? 'Wizard',
+      onKey: () => true,
+      children: () => {
+        box({
+          width,
+          border: BorderStyle.ROUNDED,
+          borderColor: t.primary,
+          bg: () => opaque(t.surface.value),
+          padding: 1,
+          gap: 1,
+          flexDirection: 'column',
+          children: () => {
+            if (title) text({ content: title, attrs: Attr.BOLD })
+
+            stepper({
+              steps: steps.map((step, i) => ({
+                ...step,
+                render: (ctx) => step.render({ ...ctx, field: field(i) }),
+                validate: step.validate && (() => step.validate!(valuesOf(i))),
+              })),
+              onComplete: submit,
+              onCancel: props.onCancel,
+              cancelLabel: 'Cancel',
+              nextLabel: 'Next',
+              finishLabel: submitLabel,
+            })
+          },
+        })
+      },
+    })
+  })
+}
@@ -7,9 +7,21 @@
  *
  * Event flow:
  *   Rust writes event -> Atomics.notify(wake_ts) -> TS wakes -> reads ring -> dispatches
+ *
+ * Key events go to global handlers, then bubble from the focused node up to
+ * the root, then reach the fallback handlers if nothing stopped them.
+ *
+ * Mouse events propagate like the DOM's: capture handlers run from the root
+ * down to the target, then handlers run from the target back up, until one
+ * calls stopPropagation() or returns true. Enter and leave don't propagate —
+ * Rust sends one to each node the mouse comes into or goes out of.
+ *
+ * Handlers are kept per bridge, like the registry's indices: each mounted
+ * instance hears only its own events.
  */
 
 import { join } from 'path'
+import { signal } from '@rlabs-inc/signals'
 import {
   type SharedBuffer,
   H_EVENT_WRITE_IDX,
@@ -18,7 +30,14 @@ import {
   EVENT_SLOT_SIZE,
   MAX_EVENTS,
   getParentIndex,
+  getTerminalSize,
+  getHoverPayload,
+  getNodeGeneration,
+  generationTag,
+  EVENT_UNTAGGED,
+  ErrorSource,
 } from '../bridge/shared-buffer'
+import { type Bridge, bridgeState, withBridge, getBuffer, isInitialized } from '../bridge'
 
 // =============================================================================
 // EVENT TYPES
@@ -42,6 +61,16 @@ export const enum EventType {
   Cancel = 13,
   Exit = 14,
   Resize = 15,
+  Screenshot = 16,
+  ScrollAnimate = 17,
+  Capabilities = 18,
+  LayoutChange = 19,
+  Error = 20,
+  ContextMenu = 21,
+  TerminalColor = 22,
+  ScrollChange = 23,
+  ReachEnd = 24,
+  Hover = 25,
 }
 
 /** Keyboard event */
@@ -51,6 +80,10 @@ export interface KeyEvent {
   keycode: number
   modifiers: number // ctrl=1, alt=2, shift=4, meta=8
   keyState: number // press=0, repeat=1, release=2
+  /** Node whose handler is running: the focused node (componentIndex) or one of its ancestors */
+  currentTarget: number
+  /** Keep the event from any handler after this one, fallbacks included (as returning true) */
+  stopPropagation(): void
 }
 
 /** Mouse button events */
@@ -62,10 +95,18 @@ export interface MouseEvent {
     | EventType.MouseEnter
     | EventType.MouseLeave
     | EventType.MouseMove
+    | EventType.ContextMenu
   componentIndex: number
   x: number
   y: number
-  button: number // left=0, middle=1, right=2
+  button: number // left=0, middle=1, right=2, back=3, forward=4, 10=5, 11=6
+  /** Pixel position, from terminals that report it (SGR-pixels) */
+  pixelX?: number
+  pixelY?: number
+  /** Node whose handler is running: the target (componentIndex) or one of its ancestors */
+  currentTarget: number
+  /** Keep the event from nodes after this one; the rest of this node's handlers still run */
+  stopPropagation(): void
 }
 
 /** Scroll wheel event */
@@ -100,6 +141,80 @@ export interface ExitEvent {
   type: EventType.Exit
 }
 
+/** Screenshot binding pressed — files are named after `timestamp` */
+export interface ScreenshotEvent {
+  type: EventType.Screenshot
+  /** Unix ms; files are `spark-screenshot-<timestamp>.txt` / `.html` in the working directory */
+  timestamp: number
+  /** False if writing the files failed */
+  saved: boolean
+}
+
+/** Smooth-scroll target set on a node — spring N_SCROLL_X/Y toward N_SCROLL_TARGET_X/Y */
+export interface ScrollAnimateEvent {
+  type: EventType.ScrollAnimate
+  componentIndex: number
+}
+
+/** Rust moved a node's scroll offset (sent for nodes with SCROLL_EVENTS) */
+export interface ScrollChangeEvent {
+  type: EventType.ScrollChange
+  componentIndex: number
+  x: number
+  y: number
+}
+
+/** A scrolled node came within its reach-end threshold of the bottom */
+export interface ReachEndEvent {
+  type: EventType.ReachEnd
+  componentIndex: number
+  /** Rows left below the viewport */
+  remaining: number
+}
+
+/** The nearest node with a `hoverTag` under the mouse changed, or its region moved */
+export interface HoverEvent {
+  type: EventType.Hover
+  /** Tagged node under the mouse, -1 = none */
+  componentIndex: number
+  /** Its `hoverTag`, 0 = none */
+  tag: number
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+/** Terminal capabilities changed — re-read the H_COLOR_DEPTH.. header slots */
+export interface CapabilitiesEvent {
+  type: EventType.Capabilities
+}
+
+/** The terminal reported one of its colors (OSC 10/11 reply) */
+export interface TerminalColorEvent {
+  type: EventType.TerminalColor
+  /** TERMINAL_COLOR_FOREGROUND or TERMINAL_COLOR_BACKGROUND */
+  slot: number
+  r: number
+  g: number
+  b: number
+}
+
+/** A layout pass gave nodes new computed rects (sent while H_LAYOUT_EVENTS is set) */
+export interface LayoutChangeEvent {
+  type: EventType.LayoutChange
+  /** How many nodes moved or resized */
+  changed: number
+}
+
+/** Rust caught a panic; the engine carries on */
+export interface EngineErrorEvent {
+  type: EventType.Error
+  /** Component whose callback (or focused input) panicked, -1 if none */
+  componentIndex: number
+  source: ErrorSource
+}
+
 /** Union of all event types */
 export type SparkEvent =
   | KeyEvent
@@ -109,6 +224,15 @@ export type SparkEvent =
   | ValueEvent
   | ResizeEvent
   | ExitEvent
+  | ScreenshotEvent
+  | ScrollAnimateEvent
+  | ScrollChangeEvent
+  | ReachEndEvent
+  | HoverEvent
+  | CapabilitiesEvent
+  | TerminalColorEvent
+  | LayoutChangeEvent
+  | EngineErrorEvent
 
 // =============================================================================
 // MODIFIER FLAGS
@@ -134,6 +258,17 @@ export const KEY_STATE_RELEASE = 2
 export const MOUSE_BUTTON_LEFT = 0
 export const MOUSE_BUTTON_MIDDLE = 1
 export const MOUSE_BUTTON_RIGHT = 2
+/** Side buttons (X11 buttons 8 and 9) */
+export const MOUSE_BUTTON_BACK = 3
+export const MOUSE_BUTTON_FORWARD = 4
+
+// =============================================================================
+// TERMINAL COLOR SLOTS
+// =============================================================================
+
+/** TerminalColorEvent slots */
+export const TERMINAL_COLOR_FOREGROUND = 0
+export const TERMINAL_COLOR_BACKGROUND = 1
 
 // =============================================================================
 // HANDLER TYPES
@@ -141,12 +276,23 @@ export const MOUSE_BUTTON_RIGHT = 2
 
 /** Return true to consume the event (stop propagation) */
 export type KeyHandler = (event: KeyEvent) => boolean | void
-export type MouseHandler = (event: MouseEvent) => void
+/** Return true to stop propagation, as event.stopPropagation() */
+export type MouseHandler = (event: MouseEvent) => boolean | void
 export type FocusHandler = (event: FocusEvent) => void
 export type ValueHandler = (event: ValueEvent) => void
 export type ResizeHandler = (event: ResizeEvent) => void
 export type ExitHandler = (event: ExitEvent) => void
+export type ScreenshotHandler = (event: ScreenshotEvent) => void
+export type ScrollAnimateHandler = (event: ScrollAnimateEvent) => void
+export type CapabilitiesHandler = (event: CapabilitiesEvent) => void
+export type TerminalColorHandler = (event: TerminalColorEvent) => void
+export type LayoutChangeHandler = (event: LayoutChangeEvent) => void
+/** Takes an error thrown by a component's callback; returns true if handled */
+export type ComponentErrorHandler = (componentIndex: number, error: unknown) => boolean
 export type ScrollHandler = (event: ScrollEvent) => void
+export type ScrollChangeHandler = (event: ScrollChangeEvent) => void
+export type ReachEndHandler = (event: ReachEndEvent) => void
+export type HoverHandler = (event: HoverEvent) => void
 
 // =============================================================================
 // EVENT RING READER
@@ -177,18 +323,29 @@ function parseEvent(buf: SharedBuffer, slot: number): SparkEvent | null {
   const eventType = view.getUint8(offset) as EventType
   if (eventType === EventType.None) return null
 
+  // Drop component events whose target was released after Rust pushed
+  // them: the index may be someone else's by now
+  const tag = view.getUint8(offset + 1)
+  if (tag !== EVENT_UNTAGGED && tag !== generationTag(getNodeGeneration(buf, view.getUint16(offset + 2, true)))) {
+    return null
+  }
+
   const componentIndex = view.getUint16(offset + 2, true)
   const dataOffset = offset + 4
 
   switch (eventType) {
-    case EventType.Key:
-      return {
+    case EventType.Key: {
+      const event: KeyEvent = {
         type: eventType,
         componentIndex,
         keycode: view.getUint32(dataOffset, true),
         modifiers: view.getUint8(dataOffset + 4),
         keyState: view.getUint8(dataOffset + 5),
+        currentTarget: componentIndex,
+        stopPropagation: () => stoppedEvents.add(event),
       }
+      return event
+    }
 
     case EventType.MouseDown:
     case EventType.MouseUp:
@@ -196,13 +353,22 @@ function parseEvent(buf: SharedBuffer, slot: number): SparkEvent | null {
     case EventType.MouseEnter:
     case EventType.MouseLeave:
     case EventType.MouseMove:
-      return {
+    case EventType.ContextMenu: {
+      const event: MouseEvent = {
         type: eventType,
         componentIndex,
         x: view.getUint16(dataOffset, true),
         y: view.getUint16(dataOffset + 2, true),
         button: view.getUint8(dataOffset + 4),
+        ...(view.getUint8(dataOffset + 5) !== 0 && {
+          pixelX: view.getUint16(dataOffset + 6, true),
+          pixelY: view.getUint16(dataOffset + 8, true),
+        }),
+        currentTarget: componentIndex,
+        stopPropagation: () => stoppedEvents.add(event),
       }
+      return event
+    }
 
     case EventType.Scroll:
       return {
@@ -237,6 +403,60 @@ function parseEvent(buf: SharedBuffer, slot: number): SparkEvent | null {
     case EventType.Exit:
       return { type: eventType }
 
+    case EventType.Screenshot:
+      return {
+        type: eventType,
+        timestamp: Number(view.getBigUint64(dataOffset, true)),
+        saved: view.getUint8(dataOffset + 8) !== 0,
+      }
+
+    case EventType.ScrollAnimate:
+      return { type: eventType, componentIndex }
+
+    case EventType.ScrollChange:
+      return {
+        type: eventType,
+        componentIndex,
+        x: view.getInt32(dataOffset, true),
+        y: view.getInt32(dataOffset + 4, true),
+      }
+
+    case EventType.ReachEnd:
+      return { type: eventType, componentIndex, remaining: view.getInt32(dataOffset, true) }
+
+    case EventType.Hover:
+      return {
+        type: eventType,
+        componentIndex: componentIndex === 0xffff ? -1 : componentIndex,
+        tag: view.getUint32(dataOffset, true),
+        x: view.getUint16(dataOffset + 4, true),
+        y: view.getUint16(dataOffset + 6, true),
+        width: view.getUint16(dataOffset + 8, true),
+        height: view.getUint16(dataOffset + 10, true),
+      }
+
+    case EventType.Capabilities:
+      return { type: eventType }
+
+    case EventType.TerminalColor:
+      return {
+        type: eventType,
+        slot: view.getUint8(dataOffset),
+        r: view.getUint8(dataOffset + 1),
+        g: view.getUint8(dataOffset + 2),
+        b: view.getUint8(dataOffset + 3),
+      }
+
+    case EventType.LayoutChange:
+      return { type: eventType, changed: view.getUint32(dataOffset, true) }
+
+    case EventType.Error:
+      return {
+        type: eventType,
+        componentIndex: componentIndex === 0xffff ? -1 : componentIndex,
+        source: view.getUint8(dataOffset) as ErrorSource,
+      }
+
     default:
       return null
   }
@@ -265,23 +485,67 @@ export function readEvents(buf: SharedBuffer): SparkEvent[] {
 // HANDLER REGISTRIES
 // =============================================================================
 
-const keyHandlers = new Map<number, KeyHandler[]>()
-const mouseHandlers = new Map<number, Partial<Record<MouseEvent['type'], MouseHandler[]>>>()
-const focusHandlers = new Map<number, FocusHandler[]>()
-const valueHandlers = new Map<number, ValueHandler[]>()
-const scrollHandlers = new Map<number, ScrollHandler[]>()
+type MouseHandlerRegistry = Map<number, Partial<Record<MouseEvent['type'], MouseHandler[]>>>
+
+/** One instance's handlers: node-keyed ones by its indices */
+interface HandlerRegistries {
+  keyHandlers: Map<number, KeyHandler[]>
+  mouseHandlers: MouseHandlerRegistry
+  /** Handlers for the capture phase, run before the target's own */
+  mouseCaptureHandlers: MouseHandlerRegistry
+  focusHandlers: Map<number, FocusHandler[]>
+  valueHandlers: Map<number, ValueHandler[]>
+  scrollHandlers: Map<number, ScrollHandler[]>
+  scrollChangeHandlers: Map<number, ScrollChangeHandler[]>
+  reachEndHandlers: Map<number, ReachEndHandler[]>
+
+  globalKeyHandlers: KeyHandler[]
+  /** Key handlers for events no component stopped */
+  fallbackKeyHandlers: KeyHandler[]
+  globalMouseHandlers: MouseHandler[]
+  globalScrollHandlers: ScrollHandler[]
+  resizeHandlers: ResizeHandler[]
+  exitHandlers: ExitHandler[]
+  screenshotHandlers: ScreenshotHandler[]
+  scrollAnimateHandlers: ScrollAnimateHandler[]
+  hoverHandlers: HoverHandler[]
+  capabilitiesHandlers: CapabilitiesHandler[]
+  terminalColorHandlers: TerminalColorHandler[]
+  layoutChangeHandlers: LayoutChangeHandler[]
+  componentErrorHandlers: ComponentErrorHandler[]
+}
 
-const globalKeyHandlers: KeyHandler[] = []
-const globalMouseHandlers: MouseHandler[] = []
-const globalScrollHandlers: ScrollHandler[] = []
-const resizeHandlers: ResizeHandler[] = []
-const exitHandlers: ExitHandler[] = []
+/** The active instance's handlers (see `bridgeState`) */
+const registries = bridgeState<HandlerRegistries>(() => ({
+  keyHandlers: new Map(),
+  mouseHandlers: new Map(),
+  mouseCaptureHandlers: new Map(),
+  focusHandlers: new Map(),
+  valueHandlers: new Map(),
+  scrollHandlers: new Map(),
+  scrollChangeHandlers: new Map(),
+  reachEndHandlers: new Map(),
+  globalKeyHandlers: [],
+  fallbackKeyHandlers: [],
+  globalMouseHandlers: [],
+  globalScrollHandlers: [],
+  resizeHandlers: [],
+  exitHandlers: [],
+  screenshotHandlers: [],
+  scrollAnimateHandlers: [],
+  hoverHandlers: [],
+  capabilitiesHandlers: [],
+  terminalColorHandlers: [],
+  layoutChangeHandlers: [],
+  componentErrorHandlers: [],
+}))
 
 // =============================================================================
 // HANDLER REGISTRATION
 // =============================================================================
 
 export function registerKeyHandler(index: number, handler: KeyHandler): () => void {
+  const { keyHandlers } = registries()
   if (!keyHandlers.has(index)) keyHandlers.set(index, [])
   keyHandlers.get(index)!.push(handler)
 
@@ -296,6 +560,7 @@ export function registerKeyHandler(index: number, handler: KeyHandler): () => vo
 }
 
 export function registerGlobalKeyHandler(handler: KeyHandler): () => void {
+  const { globalKeyHandlers } = registries()
   globalKeyHandlers.push(handler)
   return () => {
     const i = globalKeyHandlers.indexOf(handler)
@@ -303,13 +568,33 @@ export function registerGlobalKeyHandler(handler: KeyHandler): () => void {
   }
 }
 
+/**
+ * Hear key events after they've bubbled to the root unstopped: app-level
+ * defaults that any focused component can override.
+ */
+export function registerFallbackKeyHandler(handler: KeyHandler): () => void {
+  const { fallbackKeyHandlers } = registries()
+  fallbackKeyHandlers.push(handler)
+  return () => {
+    const i = fallbackKeyHandlers.indexOf(handler)
+    if (i >= 0) fallbackKeyHandlers.splice(i, 1)
+  }
+}
+
+/**
+ * Hear mouse events on `index` and inside it. With `capture`, the handler
+ * runs on the way down to the target, before any handler beneath it.
+ */
 export function registerMouseHandler(
   index: number,
   eventType: MouseEvent['type'],
-  handler: MouseHandler
+  handler: MouseHandler,
+  capture = false
 ): () => void {
-  if (!mouseHandlers.has(index)) mouseHandlers.set(index, {})
-  const componentHandlers = mouseHandlers.get(index)!
+  const { mouseHandlers, mouseCaptureHandlers } = registries()
+  const registry = capture ? mouseCaptureHandlers : mouseHandlers
+  if (!registry.has(index)) registry.set(index, {})
+  const componentHandlers = registry.get(index)!
   if (!componentHandlers[eventType]) componentHandlers[eventType] = []
   componentHandlers[eventType]!.push(handler)
 
@@ -320,11 +605,12 @@ export function registerMouseHandler(
       if (i >= 0) handlers.splice(i, 1)
       if (handlers.length === 0) delete componentHandlers[eventType]
     }
-    if (Object.keys(componentHandlers).length === 0) mouseHandlers.delete(index)
+    if (Object.keys(componentHandlers).length === 0) registry.delete(index)
   }
 }
 
 export function registerGlobalMouseHandler(handler: MouseHandler): () => void {
+  const { globalMouseHandlers } = registries()
   globalMouseHandlers.push(handler)
   return () => {
     const i = globalMouseHandlers.indexOf(handler)
@@ -333,6 +619,7 @@ export function registerGlobalMouseHandler(handler: MouseHandler): () => void {
 }
 
 export function registerFocusHandler(index: number, handler: FocusHandler): () => void {
+  const { focusHandlers } = registries()
   if (!focusHandlers.has(index)) focusHandlers.set(index, [])
   focusHandlers.get(index)!.push(handler)
 
@@ -347,6 +634,7 @@ export function registerFocusHandler(index: number, handler: FocusHandler): () =
 }
 
 export function registerValueHandler(index: number, handler: ValueHandler): () => void {
+  const { valueHandlers } = registries()
   if (!valueHandlers.has(index)) valueHandlers.set(index, [])
   valueHandlers.get(index)!.push(handler)
 
@@ -361,6 +649,7 @@ export function registerValueHandler(index: number, handler: ValueHandler): () =
 }
 
 export function registerScrollHandler(index: number, handler: ScrollHandler): () => void {
+  const { scrollHandlers } = registries()
   if (!scrollHandlers.has(index)) scrollHandlers.set(index, [])
   scrollHandlers.get(index)!.push(handler)
 
@@ -374,7 +663,49 @@ export function registerScrollHandler(index: number, handler: ScrollHandler): ()
   }
 }
 
+/**
+ * Tell `index`'s ScrollChange handlers about an offset TS wrote itself
+ * (smooth-scroll springs, keymap actions, restored layout state), as Rust
+ * does for the offsets it commits.
+ */
+export function reportScrollChange(index: number, x: number, y: number): void {
+  dispatchEvent({ type: EventType.ScrollChange, componentIndex: index, x, y })
+}
+
+/** Hear where Rust scrolls `index` to (the node needs SCROLL_EVENTS set) */
+export function registerScrollChangeHandler(index: number, handler: ScrollChangeHandler): () => void {
+  const { scrollChangeHandlers } = registries()
+  if (!scrollChangeHandlers.has(index)) scrollChangeHandlers.set(index, [])
+  scrollChangeHandlers.get(index)!.push(handler)
+
+  return () => {
+    const handlers = scrollChangeHandlers.get(index)
+    if (handlers) {
+      const i = handlers.indexOf(handler)
+      if (i >= 0) handlers.splice(i, 1)
+      if (handlers.length === 0) scrollChangeHandlers.delete(index)
+    }
+  }
+}
+
+/** Hear when `index` nears its end (the node needs SCROLL_REACH_END set) */
+export function registerReachEndHandler(index: number, handler: ReachEndHandler): () => void {
+  const { reachEndHandlers } = registries()
+  if (!reachEndHandlers.has(index)) reachEndHandlers.set(index, [])
+  reachEndHandlers.get(index)!.push(handler)
+
+  return () => {
+    const handlers = reachEndHandlers.get(index)
+    if (handlers) {
+      const i = handlers.indexOf(handler)
+      if (i >= 0) handlers.splice(i, 1)
+      if (handlers.length === 0) reachEndHandlers.delete(index)
+    }
+  }
+}
+
 export function registerGlobalScrollHandler(handler: ScrollHandler): () => void {
+  const { globalScrollHandlers } = registries()
   globalScrollHandlers.push(handler)
   return () => {
     const i = globalScrollHandlers.indexOf(handler)
@@ -383,6 +714,7 @@ export function registerGlobalScrollHandler(handler: ScrollHandler): () => void
 }
 
 export function registerResizeHandler(handler: ResizeHandler): () => void {
+  const { resizeHandlers } = registries()
   resizeHandlers.push(handler)
   return () => {
     const i = resizeHandlers.indexOf(handler)
@@ -390,7 +722,49 @@ export function registerResizeHandler(handler: ResizeHandler): () => void {
   }
 }
 
+/** The active instance's terminal size, kept current by resize events */
+const terminalSizes = bridgeState(() =>
+  signal(isInitialized() ? getTerminalSize(getBuffer()) : { width: 0, height: 0 })
+)
+
+/**
+ * The terminal size, reactively: a derived or effect reading it runs again
+ * when the terminal is resized.
+ */
+export function terminalSize(): { width: number; height: number } {
+  return terminalSizes().value
+}
+
+/** Hear every change of the tagged node under the mouse (nodes opt in with `hoverTag`) */
+export function registerHoverHandler(handler: HoverHandler): () => void {
+  const { hoverHandlers } = registries()
+  hoverHandlers.push(handler)
+  return () => {
+    const i = hoverHandlers.indexOf(handler)
+    if (i >= 0) hoverHandlers.splice(i, 1)
+  }
+}
+
+export type HoverTarget = Omit<HoverEvent, 'type'>
+
+/** The active instance's hover target, kept current by hover events */
+const hoverTargets = bridgeState(() => {
+  if (!isInitialized()) return signal<HoverTarget>({ componentIndex: -1, tag: 0, x: 0, y: 0, width: 0, height: 0 })
+  const { index, tag, x, y, width, height } = getHoverPayload(getBuffer())
+  return signal<HoverTarget>({ componentIndex: index, tag, x, y, width, height })
+})
+
+/**
+ * The nearest node with a `hoverTag` under the mouse and its screen region,
+ * reactively: a derived or effect reading it runs again when the mouse
+ * moves onto another tagged node, off all of them, or the region moves.
+ */
+export function hoverTarget(): HoverTarget {
+  return hoverTargets().value
+}
+
 export function registerExitHandler(handler: ExitHandler): () => void {
+  const { exitHandlers } = registries()
   exitHandlers.push(handler)
   return () => {
     const i = exitHandlers.indexOf(handler)
@@ -398,37 +772,144 @@ export function registerExitHandler(handler: ExitHandler): () => void {
   }
 }
 
+export function registerScreenshotHandler(handler: ScreenshotHandler): () => void {
+  const { screenshotHandlers } = registries()
+  screenshotHandlers.push(handler)
+  return () => {
+    const i = screenshotHandlers.indexOf(handler)
+    if (i >= 0) screenshotHandlers.splice(i, 1)
+  }
+}
+
+export function registerScrollAnimateHandler(handler: ScrollAnimateHandler): () => void {
+  const { scrollAnimateHandlers } = registries()
+  scrollAnimateHandlers.push(handler)
+  return () => {
+    const i = scrollAnimateHandlers.indexOf(handler)
+    if (i >= 0) scrollAnimateHandlers.splice(i, 1)
+  }
+}
+
+export function registerCapabilitiesHandler(handler: CapabilitiesHandler): () => void {
+  const { capabilitiesHandlers } = registries()
+  capabilitiesHandlers.push(handler)
+  return () => {
+    const i = capabilitiesHandlers.indexOf(handler)
+    if (i >= 0) capabilitiesHandlers.splice(i, 1)
+  }
+}
+
+export function registerTerminalColorHandler(handler: TerminalColorHandler): () => void {
+  const { terminalColorHandlers } = registries()
+  terminalColorHandlers.push(handler)
+  return () => {
+    const i = terminalColorHandlers.indexOf(handler)
+    if (i >= 0) terminalColorHandlers.splice(i, 1)
+  }
+}
+
+export function registerComponentErrorHandler(handler: ComponentErrorHandler): () => void {
+  const { componentErrorHandlers } = registries()
+  componentErrorHandlers.push(handler)
+  return () => {
+    const i = componentErrorHandlers.indexOf(handler)
+    if (i >= 0) componentErrorHandlers.splice(i, 1)
+  }
+}
+
+export function registerLayoutChangeHandler(handler: LayoutChangeHandler): () => void {
+  const { layoutChangeHandlers } = registries()
+  layoutChangeHandlers.push(handler)
+  return () => {
+    const i = layoutChangeHandlers.indexOf(handler)
+    if (i >= 0) layoutChangeHandlers.splice(i, 1)
+  }
+}
+
 // =============================================================================
 // EVENT DISPATCHER
 // =============================================================================
 
-let currentBuffer: SharedBuffer | null = null
+const ERROR_SOURCE_NAMES = ['key dispatch', 'mouse dispatch', 'timer', 'task', 'layout', 'frame callback'] as const
+
+/** A panic Rust caught, surfaced to error boundaries like a thrown error */
+export class EnginePanicError extends Error {
+  constructor(readonly source: ErrorSource) {
+    super(`Engine panicked in ${ERROR_SOURCE_NAMES[source] ?? 'unknown code'}`)
+    this.name = 'EnginePanicError'
+  }
+}
+
+/**
+ * Hand an error from a component's callback to the error handlers (error
+ * boundaries); logged if none takes it. `componentIndex` -1 = no component.
+ */
+export function reportComponentError(componentIndex: number, error: unknown): void {
+  const { componentErrorHandlers } = registries()
+  for (const handler of componentErrorHandlers) {
+    if (handler(componentIndex, error)) return
+  }
+  console.error(`[TUI] Uncaught error${componentIndex >= 0 ? ` in component ${componentIndex}` : ''}:`, error)
+}
+
+/** Key and mouse events a handler stopped */
+const stoppedEvents = new WeakSet<KeyEvent | MouseEvent>()
+
+/** Run key handlers for node `index` (-1 = app level); true once propagation stopped */
+function runKeyHandlers(handlers: KeyHandler[], index: number, event: KeyEvent): boolean {
+  if (index >= 0) event.currentTarget = index
+  for (const handler of [...handlers]) {
+    if (guard(index, () => handler(event)) === true) stoppedEvents.add(event)
+    if (stoppedEvents.has(event)) return true
+  }
+  return false
+}
+
+/** Run node `index`'s handlers for the event; true once propagation stopped */
+function runMouseHandlers(registry: MouseHandlerRegistry, index: number, event: MouseEvent): boolean {
+  const handlers = registry.get(index)?.[event.type]
+  if (handlers) {
+    event.currentTarget = index
+    for (const handler of [...handlers]) {
+      if (guard(index, () => handler(event)) === true) stoppedEvents.add(event)
+    }
+  }
+  return stoppedEvents.has(event)
+}
+
+/** Run a handler; a throw is reported instead of aborting the dispatch */
+function guard<R>(componentIndex: number, call: () => R): R | undefined {
+  try {
+    return call()
+  } catch (error) {
+    reportComponentError(componentIndex, error)
+    return undefined
+  }
+}
 
 function dispatchEvent(event: SparkEvent): void {
+  const reg = registries()
+  const buf = isInitialized() ? getBuffer() : null
   switch (event.type) {
     case EventType.Key: {
-      for (const handler of globalKeyHandlers) {
-        if (handler(event) === true) return
-      }
+      if (runKeyHandlers(reg.globalKeyHandlers, -1, event)) return
 
-      if (currentBuffer) {
+      if (buf) {
         let target = event.componentIndex
         let depth = 0
 
         while (depth < 100) {
-          const handlers = keyHandlers.get(target)
-          if (handlers) {
-            for (const handler of handlers) {
-              if (handler(event) === true) return
-            }
-          }
+          const handlers = reg.keyHandlers.get(target)
+          if (handlers && runKeyHandlers(handlers, target, event)) return
 
-          const parent = getParentIndex(currentBuffer, target)
+          const parent = getParentIndex(buf, target)
           if (parent < 0) break
           target = parent
           depth++
         }
       }
+
+      runKeyHandlers(reg.fallbackKeyHandlers, -1, event)
       break
     }
 
@@ -437,43 +918,42 @@ function dispatchEvent(event: SparkEvent): void {
     case EventType.Click:
     case EventType.MouseEnter:
     case EventType.MouseLeave:
-    case EventType.MouseMove: {
-      for (const handler of globalMouseHandlers) {
-        handler(event)
+    case EventType.MouseMove:
+    case EventType.ContextMenu: {
+      for (const handler of reg.globalMouseHandlers) {
+        guard(-1, () => handler(event))
       }
 
-      if (currentBuffer) {
-        let target = event.componentIndex
-        let depth = 0
-
-        while (depth < 100) {
-          const componentHandlers = mouseHandlers.get(target)
-          if (componentHandlers) {
-            const typeHandlers = componentHandlers[event.type]
-            if (typeHandlers) {
-              for (const handler of typeHandlers) {
-                handler(event)
-              }
-            }
+      if (buf) {
+        // Target first, root last; enter and leave stay on their node
+        const path = [event.componentIndex]
+        if (event.type !== EventType.MouseEnter && event.type !== EventType.MouseLeave) {
+          let parent = getParentIndex(buf, event.componentIndex)
+          while (parent >= 0 && path.length < 100) {
+            path.push(parent)
+            parent = getParentIndex(buf, parent)
           }
+        }
 
-          const parent = getParentIndex(currentBuffer, target)
-          if (parent < 0) break
-          target = parent
-          depth++
+        let stopped = false
+        for (let i = path.length - 1; i >= 0 && !stopped; i--) {
+          stopped = runMouseHandlers(reg.mouseCaptureHandlers, path[i], event)
+        }
+        for (let i = 0; i < path.length && !stopped; i++) {
+          stopped = runMouseHandlers(reg.mouseHandlers, path[i], event)
         }
       }
       break
     }
 
     case EventType.Scroll: {
-      for (const handler of globalScrollHandlers) {
-        handler(event)
+      for (const handler of reg.globalScrollHandlers) {
+        guard(-1, () => handler(event))
       }
-      const handlers = scrollHandlers.get(event.componentIndex)
+      const handlers = reg.scrollHandlers.get(event.componentIndex)
       if (handlers) {
         for (const handler of handlers) {
-          handler(event)
+          guard(event.componentIndex, () => handler(event))
         }
       }
       break
@@ -481,10 +961,10 @@ function dispatchEvent(event: SparkEvent): void {
 
     case EventType.Focus:
     case EventType.Blur: {
-      const handlers = focusHandlers.get(event.componentIndex)
+      const handlers = reg.focusHandlers.get(event.componentIndex)
       if (handlers) {
         for (const handler of handlers) {
-          handler(event)
+          guard(event.componentIndex, () => handler(event))
         }
       }
       break
@@ -493,28 +973,98 @@ function dispatchEvent(event: SparkEvent): void {
     case EventType.ValueChange:
     case EventType.Submit:
     case EventType.Cancel: {
-      const handlers = valueHandlers.get(event.componentIndex)
+      const handlers = reg.valueHandlers.get(event.componentIndex)
       if (handlers) {
         for (const handler of handlers) {
-          handler(event)
+          guard(event.componentIndex, () => handler(event))
         }
       }
       break
     }
 
     case EventType.Resize: {
-      for (const handler of resizeHandlers) {
+      terminalSizes().value = { width: event.width, height: event.height }
+      for (const handler of reg.resizeHandlers) {
         handler(event)
       }
       break
     }
 
     case EventType.Exit: {
-      for (const handler of exitHandlers) {
+      for (const handler of reg.exitHandlers) {
+        handler(event)
+      }
+      break
+    }
+
+    case EventType.Screenshot: {
+      for (const handler of reg.screenshotHandlers) {
+        handler(event)
+      }
+      break
+    }
+
+    case EventType.ScrollAnimate: {
+      for (const handler of reg.scrollAnimateHandlers) {
         handler(event)
       }
       break
     }
+
+    case EventType.ScrollChange: {
+      const handlers = reg.scrollChangeHandlers.get(event.componentIndex)
+      if (handlers) {
+        for (const handler of handlers) {
+          guard(event.componentIndex, () => handler(event))
+        }
+      }
+      break
+    }
+
+    case EventType.ReachEnd: {
+      const handlers = reg.reachEndHandlers.get(event.componentIndex)
+      if (handlers) {
+        for (const handler of handlers) {
+          guard(event.componentIndex, () => handler(event))
+        }
+      }
+      break
+    }
+
+    case EventType.Hover: {
+      const { componentIndex, tag, x, y, width, height } = event
+      hoverTargets().value = { componentIndex, tag, x, y, width, height }
+      for (const handler of reg.hoverHandlers) {
+        guard(-1, () => handler(event))
+      }
+      break
+    }
+
+    case EventType.Capabilities: {
+      for (const handler of reg.capabilitiesHandlers) {
+        handler(event)
+      }
+      break
+    }
+
+    case EventType.TerminalColor: {
+      for (const handler of reg.terminalColorHandlers) {
+        handler(event)
+      }
+      break
+    }
+
+    case EventType.LayoutChange: {
+      for (const handler of reg.layoutChangeHandlers) {
+        handler(event)
+      }
+      break
+    }
+
+    case EventType.Error: {
+      reportComponentError(event.componentIndex, new EnginePanicError(event.source))
+      break
+    }
   }
 }
 
@@ -524,6 +1074,8 @@ function dispatchEvent(event: SparkEvent): void {
 
 let running = false
 let eventWorker: Worker | null = null
+/** Instance the terminal engine's events belong to */
+let listenerBridge: Bridge | null = null
 
 /**
  * Start the event listener.
@@ -534,12 +1086,14 @@ let eventWorker: Worker | null = null
  *
  * - Main thread: 0% CPU when idle (no timers), animations work
  * - Worker thread: 0% CPU (blocked on condvar), instant wake on input
+ *
+ * Events are dispatched with `bridge` active, to its handlers.
  */
-export function startEventListener(buf: SharedBuffer, libPath: string): void {
+export function startEventListener(bridge: Bridge, libPath: string): void {
   if (running) return
 
   running = true
-  currentBuffer = buf
+  listenerBridge = bridge
 
   // Create worker
   const workerPath = join(import.meta.dir, 'event-worker.ts')
@@ -547,13 +1101,15 @@ export function startEventListener(buf: SharedBuffer, libPath: string): void {
 
   // Handle events from worker
   eventWorker.onmessage = () => {
-    if (!running || !currentBuffer) return
+    const bridge = listenerBridge
+    if (!running || !bridge) return
 
     // Process all pending events
-    const events = readEvents(currentBuffer)
-    for (const event of events) {
-      dispatchEvent(event)
-    }
+    withBridge(bridge, () => {
+      for (const event of readEvents(bridge.buffer)) {
+        dispatchEvent(event)
+      }
+    })
   }
 
   // Start the worker with the library path
@@ -569,7 +1125,7 @@ export function stopEventListener(): void {
     eventWorker = null
   }
 
-  currentBuffer = null
+  listenerBridge = null
 }
 
 export function isEventListenerRunning(): boolean {
@@ -581,25 +1137,41 @@ export function isEventListenerRunning(): boolean {
 // =============================================================================
 
 export function cleanupHandlers(index: number): void {
-  keyHandlers.delete(index)
-  mouseHandlers.delete(index)
-  focusHandlers.delete(index)
-  valueHandlers.delete(index)
-  scrollHandlers.delete(index)
+  const reg = registries()
+  reg.keyHandlers.delete(index)
+  reg.mouseHandlers.delete(index)
+  reg.mouseCaptureHandlers.delete(index)
+  reg.focusHandlers.delete(index)
+  reg.valueHandlers.delete(index)
+  reg.scrollHandlers.delete(index)
+  reg.scrollChangeHandlers.delete(index)
+  reg.reachEndHandlers.delete(index)
 }
 
 export function cleanupAllHandlers(): void {
-  keyHandlers.clear()
-  mouseHandlers.clear()
-  focusHandlers.clear()
-  valueHandlers.clear()
-  scrollHandlers.clear()
-
-  globalKeyHandlers.length = 0
-  globalMouseHandlers.length = 0
-  globalScrollHandlers.length = 0
-  resizeHandlers.length = 0
-  exitHandlers.length = 0
+  const reg = registries()
+  reg.keyHandlers.clear()
+  reg.mouseHandlers.clear()
+  reg.mouseCaptureHandlers.clear()
+  reg.focusHandlers.clear()
+  reg.valueHandlers.clear()
+  reg.scrollHandlers.clear()
+  reg.scrollChangeHandlers.clear()
+  reg.reachEndHandlers.clear()
+
+  reg.globalKeyHandlers.length = 0
+  reg.fallbackKeyHandlers.length = 0
+  reg.globalMouseHandlers.length = 0
+  reg.globalScrollHandlers.length = 0
+  reg.resizeHandlers.length = 0
+  reg.exitHandlers.length = 0
+  reg.screenshotHandlers.length = 0
+  reg.scrollAnimateHandlers.length = 0
+  reg.hoverHandlers.length = 0
+  reg.capabilitiesHandlers.length = 0
+  reg.terminalColorHandlers.length = 0
+  reg.layoutChangeHandlers.length = 0
+  reg.componentErrorHandlers.length = 0
 }
 
 // =============================================================================
@@ -683,6 +1255,8 @@ export const KEY_F9 = 0x2009
 export const KEY_F10 = 0x200A
 export const KEY_F11 = 0x200B
 export const KEY_F12 = 0x200C
+/** Highest function key reported (kitty sends up to F35) */
+const KEY_F_LAST = 0x2023
 
 // =============================================================================
 // KEY HELPERS
@@ -713,7 +1287,7 @@ export function getKeyName(event: KeyEvent): string {
     case KEY_PAGE_DOWN: return 'pagedown'
     case KEY_INSERT: return 'insert'
     default:
-      if (event.keycode >= KEY_F1 && event.keycode <= KEY_F12) {
+      if (event.keycode >= KEY_F1 && event.keycode <= KEY_F_LAST) {
         return `f${event.keycode - 0x2000}`
       }
       if (event.keycode >= 32 && event.keycode <= 126) {
@@ -740,7 +1314,7 @@ export function isArrowKey(event: KeyEvent): boolean {
 }
 
 export function isFunctionKey(event: KeyEvent): boolean {
-  return event.keycode >= KEY_F1 && event.keycode <= KEY_F12
+  return event.keycode >= KEY_F1 && event.keycode <= KEY_F_LAST
 }
 
 export function isChar(event: KeyEvent, char: string): boolean {
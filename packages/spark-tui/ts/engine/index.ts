@@ -15,7 +15,11 @@ export {
   getRenderMode,
   type MountOptions,
   type MountHandle,
+  type NotifyOptions,
+  type NotifyProtocol,
+  type HistorySpan,
   type MountRenderMode,
+  type InlineHeight,
 } from './mount'
 
 // Registry
@@ -28,9 +32,16 @@ export {
   isAllocated,
   getAllocatedCount,
   getCurrentParentIndex,
+  withRegion,
   pushParentContext,
   popParentContext,
   resetRegistry,
+  componentRef,
+  getGeneration,
+  isLive,
+  resolveRef,
+  releaseRef,
+  type ComponentRef,
 } from './registry'
 
 // Keyboard helpers - friendly API for key events
@@ -14,22 +14,43 @@
  * ```
  */
 
+import { bridgeState } from '../bridge'
+
+/** One instance's lifecycle state: indices are per bridge */
+interface Lifecycle {
+  /**
+   * Stack of component indices currently being created.
+   * Needed because children are created synchronously inside parent's children() callback.
+   */
+  componentStack: number[]
+  /**
+   * Mount callbacks by component index.
+   * Called after component is fully set up in arrays.
+   */
+  mountCallbacks: Map<number, Array<() => void>>
+  /**
+   * Destroy callbacks by component index.
+   * Called when component is released.
+   */
+  destroyCallbacks: Map<number, Array<() => void>>
+}
+
+const lifecycle = bridgeState<Lifecycle>(() => ({
+  componentStack: [],
+  mountCallbacks: new Map(),
+  destroyCallbacks: new Map(),
+}))
+
 // =============================================================================
 // Current Component Tracking
 // =============================================================================
 
-/**
- * Stack of component indices currently being created.
- * Needed because children are created synchronously inside parent's children() callback.
- */
-const componentStack: number[] = []
-
 /**
  * Push a component index onto the creation stack.
  * Called by primitives (box, text) at the start of creation.
  */
 export function pushCurrentComponent(index: number): void {
-  componentStack.push(index)
+  lifecycle().componentStack.push(index)
 }
 
 /**
@@ -37,7 +58,7 @@ export function pushCurrentComponent(index: number): void {
  * Called by primitives (box, text) after setup is complete.
  */
 export function popCurrentComponent(): void {
-  componentStack.pop()
+  lifecycle().componentStack.pop()
 }
 
 /**
@@ -45,24 +66,35 @@ export function popCurrentComponent(): void {
  * Returns -1 if not inside a component creation.
  */
 export function getCurrentComponentIndex(): number {
+  const { componentStack } = lifecycle()
   return componentStack.length > 0 ? componentStack[componentStack.length - 1]! : -1
 }
 
 // =============================================================================
-// Lifecycle Callbacks Storage
+// Lifecycle Listeners
 // =============================================================================
 
 /**
- * Mount callbacks by component index.
- * Called after component is fully set up in arrays.
+ * Framework-wide listeners, told about every component (component observers).
  */
-const mountCallbacks = new Map<number, Array<() => void>>()
+export interface LifecycleListener {
+  mounted(index: number): void
+  destroyed(index: number): void
+}
+
+const listeners: LifecycleListener[] = []
 
 /**
- * Destroy callbacks by component index.
- * Called when component is released.
+ * Listen to every component's mount and destroy.
+ * Returns a function that removes the listener.
  */
-const destroyCallbacks = new Map<number, Array<() => void>>()
+export function addLifecycleListener(listener: LifecycleListener): () => void {
+  listeners.push(listener)
+  return () => {
+    const i = listeners.indexOf(listener)
+    if (i >= 0) listeners.splice(i, 1)
+  }
+}
 
 // =============================================================================
 // Lifecycle Hook APIs
@@ -92,6 +124,7 @@ export function onMount(fn: () => void): void {
     return
   }
 
+  const { mountCallbacks } = lifecycle()
   let callbacks = mountCallbacks.get(index)
   if (!callbacks) {
     callbacks = []
@@ -121,7 +154,15 @@ export function onDestroy(fn: () => void): void {
     console.warn('onDestroy called outside of component creation')
     return
   }
+  onDestroyOf(index, fn)
+}
 
+/**
+ * Register a destroy callback for a specific component index.
+ * For framework code that needs a release hook after creation has finished.
+ */
+export function onDestroyOf(index: number, fn: () => void): void {
+  const { destroyCallbacks } = lifecycle()
   let callbacks = destroyCallbacks.get(index)
   if (!callbacks) {
     callbacks = []
@@ -139,7 +180,7 @@ export function onDestroy(fn: () => void): void {
  * Called by primitives after setup is complete.
  */
 export function runMountCallbacks(index: number): void {
-  const callbacks = mountCallbacks.get(index)
+  const callbacks = lifecycle().mountCallbacks.get(index)
   if (callbacks) {
     for (const fn of callbacks) {
       try {
@@ -150,6 +191,7 @@ export function runMountCallbacks(index: number): void {
     }
     // Don't delete - keep for potential re-mount scenarios
   }
+  for (const listener of listeners) listener.mounted(index)
 }
 
 /**
@@ -157,6 +199,8 @@ export function runMountCallbacks(index: number): void {
  * Called by releaseIndex before cleanup.
  */
 export function runDestroyCallbacks(index: number): void {
+  for (const listener of listeners) listener.destroyed(index)
+  const { mountCallbacks, destroyCallbacks } = lifecycle()
   const callbacks = destroyCallbacks.get(index)
   if (callbacks) {
     for (const fn of callbacks) {
@@ -180,7 +224,8 @@ export function runDestroyCallbacks(index: number): void {
  * Reset all lifecycle state (for testing)
  */
 export function resetLifecycle(): void {
-  componentStack.length = 0
-  mountCallbacks.clear()
-  destroyCallbacks.clear()
+  const state = lifecycle()
+  state.componentStack.length = 0
+  state.mountCallbacks.clear()
+  state.destroyCallbacks.clear()
 }
@@ -0,0 +1,43 @@
+import { describe, expect, test } from 'bun:test'
+import { mountForTest } from './mount'
+import { getIndex, getAllocatedCount } from './registry'
+import { withBridge } from '../bridge'
+import { getNodeCount, getParentIndex } from '../bridge/shared-buffer'
+import { box } from '../primitives/box'
+import { text } from '../primitives/text'
+
+describe('mountSync', () => {
+  test('two instances keep their own buffer and registry', () => {
+    const a = mountForTest(() => {
+      box({ id: 'root', children: () => { text({ id: 'label', content: 'a' }) } })
+    })
+    const b = mountForTest(() => {
+      box({
+        id: 'root',
+        children: () => {
+          text({ id: 'first', content: 'b' })
+          text({ id: 'label', content: 'b' })
+        },
+      })
+    })
+
+    expect(b.buffer).not.toBe(a.buffer)
+    expect(getNodeCount(a.buffer)).toBe(2)
+    expect(getNodeCount(b.buffer)).toBe(3)
+
+    // Each numbers its nodes from 0, and ids resolve per instance
+    expect(withBridge(a.bridge, () => getIndex('root'))).toBe(0)
+    expect(withBridge(b.bridge, () => getIndex('root'))).toBe(0)
+    expect(withBridge(a.bridge, () => getIndex('label'))).toBe(1)
+    expect(withBridge(b.bridge, () => getIndex('label'))).toBe(2)
+    expect(getParentIndex(b.buffer, 2)).toBe(0)
+
+    // Unmounting one leaves the other's tree alone
+    a.unmount()
+    expect(withBridge(b.bridge, () => getAllocatedCount())).toBe(3)
+    expect(getNodeCount(b.buffer)).toBe(3)
+
+    b.unmount()
+    expect(withBridge(b.bridge, () => getAllocatedCount())).toBe(0)
+  })
+})
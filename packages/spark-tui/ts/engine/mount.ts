@@ -12,7 +12,7 @@
  *   mountSync() - sync, returns handle for manual control (power users, tests)
  */
 
-import { initBridge, resetBridge, getBuffer } from '../bridge'
+import { type Bridge, initBridge, createBridge, resetBridge, withBridge, getBridge, isInitialized } from '../bridge'
 import {
   startEventListener,
   stopEventListener,
@@ -20,6 +20,7 @@ import {
   cleanupAllHandlers,
 } from './events'
 import { scoped } from '../primitives/scope'
+import { withRegion } from './registry'
 import {
   type SharedBuffer,
   setTerminalSize,
@@ -30,10 +31,28 @@ import {
   CONFIG_EXIT_ON_CTRL_C,
   CONFIG_TAB_NAVIGATION,
   CONFIG_MOUSE_ENABLED,
+  CONFIG_SPATIAL_NAVIGATION,
+  CONFIG_NATIVE_CURSOR,
+  setControlChars,
+  ControlChars,
+  setBatchWindow,
+  setInlineHeight,
+  packColor,
 } from '../bridge/shared-buffer'
+import { Attr, type ColorInput } from '../types'
+import { parseColor } from '../types/color'
 import { loadEngine, getLibPath, type SparkEngine } from '../bridge/ffi'
 import { ptr } from 'bun:ffi'
 import type { Cleanup } from '../primitives/types'
+import { configureScreenshot, type ScreenshotOptions } from '../state/screenshot'
+import { configurePerfHud, type PerfHudOptions } from '../state/perfHud'
+import { configureInspector, type InspectorOptions } from '../state/inspector'
+import { setDebugLayout } from '../state/layoutDebug'
+import { setKeymapPreset, type KeymapPresetName } from '../state/keymap'
+import { trackCapabilities, configureCellAspect } from '../state/capabilities'
+import { trackInteraction } from '../state/interaction'
+import { setAnnouncer, type AnnouncerSink } from '../state/announcer'
+import { trackLayoutObservers } from './observers'
 
 // =============================================================================
 // TYPES
@@ -48,10 +67,30 @@ import type { Cleanup } from '../primitives/types'
  */
 export type MountRenderMode = 'fullscreen' | 'inline' | 'append'
 
+/** Bounds on the inline UI's height, in rows (0 or unset = no bound) */
+export interface InlineHeight {
+  min?: number
+  max?: number
+}
+
 export interface MountOptions {
   /** Render mode: fullscreen (default), inline, or append */
   mode?: MountRenderMode
 
+  /**
+   * Inline mode: rows the UI takes, at least `min` (up to 255) and at most
+   * `max` (default: as tall as its content). It grows and shrinks with the
+   * content in between; past `max` it is clipped, and a root sized in % fills
+   * `max` — give it overflow 'scroll' to scroll inside.
+   */
+  inlineHeight?: InlineHeight
+
+  /**
+   * Cell height ÷ width that `aspectCells` boxes are corrected by (default:
+   * measured from the terminal's cell pixel size, else 2)
+   */
+  cellAspect?: number
+
   /** Terminal width (auto-detected if not specified) */
   width?: number
 
@@ -67,6 +106,67 @@ export interface MountOptions {
   /** Disable mouse support (default: enabled) */
   disableMouse?: boolean
 
+  /**
+   * Arrow keys move focus to the nearest focusable in that direction,
+   * for grid-like UIs (default: disabled). A focused scroll box scrolls
+   * first and hands focus on at its edge.
+   */
+  spatialNavigation?: boolean
+
+  /**
+   * Show the terminal's own cursor at the focused input instead of drawing
+   * one (default: disabled). Its shape follows the input's cursor style and
+   * the terminal blinks it; screen readers and IMEs can track it.
+   */
+  nativeCursor?: boolean
+
+  /**
+   * Where accessibility announcements go (default: nowhere): 'notify' posts
+   * desktop notifications, 'title' sets the window title, or pass a sink.
+   * See `setAnnouncer`.
+   */
+  announcer?: AnnouncerSink | 'notify' | 'title'
+
+  /**
+   * Keys for framework navigation and input editing (default: 'standard').
+   * 'vim' adds hjkl, Ctrl+D/U, Ctrl+F/B and g/G; 'emacs' adds Ctrl+N/P/F/B,
+   * Ctrl+V/Alt+V, Alt+</> and readline editing in inputs. Override single
+   * actions through the keymap registry (`onAction('scroll-down', …)`).
+   */
+  keymap?: KeymapPresetName
+
+  /**
+   * How control characters in text are shown, so logs and untrusted input
+   * can't corrupt the screen: 'pictures' (␛, default) or 'caret' (^[)
+   */
+  controlChars?: 'pictures' | 'caret'
+
+  /**
+   * Coalesce changes into at most one frame per this many milliseconds
+   * (default: 0, every change renders at once). For signals updated far more
+   * often than anyone can read — tick data, sensors, log tails. The first
+   * change after a quiet spell still renders immediately.
+   */
+  batchWindow?: number
+
+  /** Performance HUD toggle key and initial visibility (default: off, unbound) */
+  perfHud?: PerfHudOptions | false
+
+  /** Component inspector toggle key and whether it starts open (default: off, unbound) */
+  inspector?: InspectorOptions | false
+
+  /** Outline boxes, hatch padding and tint gaps to debug layout (default: false) */
+  debugLayout?: boolean
+
+  /** Record the session to an asciinema cast at this path (see MountHandle.recordCast) */
+  recordCast?: string
+
+  /**
+   * Screenshot key binding, off by default: a shot writes files to the
+   * working directory. `{}` binds F12.
+   */
+  screenshot?: ScreenshotOptions | false
+
   /** Callback when app is unmounted */
   onUnmount?: () => void
 
@@ -78,6 +178,77 @@ export interface MountOptions {
 
   /** Text pool size in bytes (default: 10MB) */
   textPoolSize?: number
+
+  /**
+   * Region the app draws into in inline and append mode (default 0). More
+   * trees join with `mountRegion`; regions stack top to bottom in ascending
+   * order and each redraws only when it changes.
+   */
+  region?: number
+}
+
+/** Desktop notification protocol (NotifyProtocol in Rust) */
+export type NotifyProtocol = 'osc9' | 'osc777'
+
+const NOTIFY_OSC9 = 0
+const NOTIFY_OSC777 = 1
+
+export interface NotifyOptions {
+  /** Notification title (default: none) */
+  title?: string
+  /** Which escape the terminal understands (default: 'osc9') */
+  protocol?: NotifyProtocol
+}
+
+/** A run of styled text in a history line (see `MountHandle.println`) */
+export interface HistorySpan {
+  text: string
+  /** Foreground (default: the terminal's own) */
+  fg?: ColorInput
+  /** Background (default: the terminal's own) */
+  bg?: ColorInput
+  bold?: boolean
+  dim?: boolean
+  italic?: boolean
+  underline?: boolean
+  inverse?: boolean
+  strikethrough?: boolean
+}
+
+/** Packed ARGB for a history span; 0 = the terminal's default */
+function historyColor(c: ColorInput | undefined): number {
+  if (c === null || c === undefined) return 0
+  if (typeof c === 'number') return c >>> 0
+  const { r, g, b, a } = typeof c === 'string' ? parseColor(c) : { ...c, a: c.a ?? 255 }
+  return packColor(r, g, b, a) >>> 0
+}
+
+/**
+ * Encode a history line for `spark_println`: per span, fg and bg (u32 LE),
+ * attrs (u8), UTF-8 length (u32 LE) and the text.
+ */
+export function encodeStyledLine(line: string | HistorySpan[]): Uint8Array {
+  const spans = typeof line === 'string' ? [{ text: line }] : line
+  const encoder = new TextEncoder()
+  const parts = spans.map((span) => ({ span, text: encoder.encode(span.text) }))
+  const out = new Uint8Array(parts.reduce((len, { text }) => len + 13 + text.length, 0))
+  const view = new DataView(out.buffer)
+  let at = 0
+  for (const { span, text } of parts) {
+    const attrs = (span.bold ? Attr.BOLD : 0)
+      | (span.dim ? Attr.DIM : 0)
+      | (span.italic ? Attr.ITALIC : 0)
+      | (span.underline ? Attr.UNDERLINE : 0)
+      | (span.inverse ? Attr.INVERSE : 0)
+      | (span.strikethrough ? Attr.STRIKETHROUGH : 0)
+    view.setUint32(at, historyColor(span.fg), true)
+    view.setUint32(at + 4, historyColor(span.bg), true)
+    view.setUint8(at + 8, attrs)
+    view.setUint32(at + 9, text.length, true)
+    out.set(text, at + 13)
+    at += 13 + text.length
+  }
+  return out
 }
 
 export interface MountHandle {
@@ -87,6 +258,9 @@ export interface MountHandle {
   /** Get the shared buffer for direct access */
   buffer: SharedBuffer
 
+  /** The instance's bridge: run code against it with `withBridge` */
+  bridge: Bridge
+
   /** Get the Rust engine for direct access */
   engine: SparkEngine
 
@@ -96,6 +270,51 @@ export interface MountHandle {
   /** Get current render mode */
   getMode(): MountRenderMode
 
+  /** Change the batch window (milliseconds, 0 = off) at runtime */
+  setBatchWindow(ms: number): void
+
+  /** Change the inline height bounds at runtime */
+  setInlineHeight(bounds: InlineHeight): void
+
+  /** Change the cell aspect at runtime (0 = measured) */
+  setCellAspect(aspect: number): void
+
+  /**
+   * Record everything drawn to an asciinema cast at `path` (replacing a
+   * recording already running). Play it back with `asciinema play`.
+   * Returns false if the file couldn't be created.
+   */
+  recordCast(path: string): boolean
+
+  /** Finish the cast recording, if one is running */
+  stopCast(): void
+
+  /** Set the terminal's window title; its own title is restored on exit */
+  setTitle(title: string): void
+
+  /** Ring the terminal bell */
+  bell(): void
+
+  /**
+   * Post a desktop notification. OSC 9 (the default) shows the body only,
+   * with the title prefixed; OSC 777 has a separate title.
+   */
+  notify(body: string, options?: NotifyOptions): void
+
+  /**
+   * Print a line above the app in append mode, where it stays as history
+   * while the app below keeps updating (a build log over a progress bar).
+   * Goes out with the next frame. Other modes ignore it.
+   */
+  println(line: string | HistorySpan[]): void
+
+  /**
+   * Mount another tree into `region`, beside the app (inline and append
+   * mode): a status footer under a prompt, say. Returns its unmount; it's
+   * also unmounted with the app.
+   */
+  mountRegion(app: () => void, region: number): Cleanup
+
   /** Block until the app exits (for power users who use mountSync) */
   waitForExit(): Promise<void>
 }
@@ -104,12 +323,12 @@ export interface MountHandle {
 // STATE
 // =============================================================================
 
-let currentCleanup: Cleanup | null = null
-let currentMode: MountRenderMode = 'fullscreen'
-let mounted = false
-let exitUnsubscribe: Cleanup | null = null
-let currentEngine: SparkEngine | null = null
-let exitResolver: (() => void) | null = null
+/** Bridges an app is mounted on */
+const mountedBridges = new Set<Bridge>()
+/** Each mounted instance's render mode */
+const renderModes = new WeakMap<Bridge, MountRenderMode>()
+/** Whether an app holds the process terminal (it has one engine) */
+let terminalMounted = false
 
 // =============================================================================
 // RENDER MODE
@@ -124,9 +343,17 @@ function renderModeToEnum(mode: MountRenderMode): RenderMode {
   }
 }
 
-function applyRenderMode(buffer: SharedBuffer, mode: MountRenderMode): void {
-  setRenderMode(buffer, renderModeToEnum(mode))
-  currentMode = mode
+function applyRenderMode(bridge: Bridge, mode: MountRenderMode): void {
+  setRenderMode(bridge.buffer, renderModeToEnum(mode))
+  renderModes.set(bridge, mode)
+}
+
+// =============================================================================
+// FRAME PACING
+// =============================================================================
+
+function batchWindowMicros(ms: number): number {
+  return Math.max(0, Math.round(ms * 1000))
 }
 
 // =============================================================================
@@ -157,6 +384,10 @@ function getTerminalSize(): { width: number; height: number } {
  *
  * For most apps, use `mount()` instead which handles everything.
  *
+ * Each mount is an instance with its own bridge: its own buffer, component
+ * registry and handlers. Only one can hold the terminal at a time; with
+ * `noopNotifier` any number can run beside it.
+ *
  * @param app - The app function that creates the UI
  * @param options - Mount options (render mode, terminal size, etc.)
  * @returns A handle to control the mounted app
@@ -178,10 +409,6 @@ function getTerminalSize(): { width: number; height: number } {
  * ```
  */
 export function mountSync(app: () => void, options: MountOptions = {}): MountHandle {
-  if (mounted) {
-    throw new Error('SparkTUI is already mounted. Call unmount() first.')
-  }
-
   const {
     mode = 'fullscreen',
     width,
@@ -189,17 +416,34 @@ export function mountSync(app: () => void, options: MountOptions = {}): MountHan
     disableCtrlC = false,
     disableTabNavigation = false,
     disableMouse = false,
+    spatialNavigation = false,
+    nativeCursor = false,
+    announcer,
+    keymap = 'standard',
+    controlChars = 'pictures',
+    batchWindow = 0,
+    inlineHeight = {},
+    cellAspect = 0,
+    screenshot = false,
+    perfHud = false,
+    inspector = false,
+    debugLayout = false,
+    recordCast,
     onUnmount,
     noopNotifier = false,
     maxNodes,
     textPoolSize,
+    region = 0,
   } = options
 
+  if (!noopNotifier && terminalMounted) {
+    throw new Error('SparkTUI is already mounted. Call unmount() first.')
+  }
+
   // Load engine FIRST (we need engine.wake for the notifier)
   let engine: SparkEngine
   if (!noopNotifier) {
     engine = loadEngine()
-    currentEngine = engine
   } else {
     // Create a noop engine for tests
     engine = {
@@ -208,24 +452,36 @@ export function mountSync(app: () => void, options: MountOptions = {}): MountHan
       wake: () => { },
       waitForEvents: () => { },
       cleanup: () => { },
+      recordCast: () => 0,
+      setTitle: () => 0,
+      bell: () => { },
+      notify: () => 0,
+      println: () => 0,
+      startSession: () => 0,
+      wakeSession: () => { },
+      waitForSessionEvents: () => { },
+      resizeSession: () => { },
+      stopSession: () => { },
+      recordSessionCast: () => 0,
+      printlnSession: () => 0,
       close: () => { },
     }
   }
 
-  // Initialize bridge with FFI wake function (~5ns vs 500-2000μs!)
-  const { buffer } = initBridge({
-    noopNotifier,
-    maxNodes,
-    textPoolSize,
-    wakeFn: engine.wake,
-  })
+  // Initialize bridge with FFI wake function (~5ns vs 500-2000μs!).
+  // The first mount takes the default bridge, which code running outside
+  // any instance (a timer, a promise) falls back to; another gets its own.
+  const bridgeOptions = { noopNotifier, maxNodes, textPoolSize, wakeFn: engine.wake }
+  const shared = initBridge(bridgeOptions)
+  const bridge = mountedBridges.has(shared) ? createBridge(bridgeOptions) : shared
+  const { buffer } = bridge
 
   // Set terminal size
   const termSize = getTerminalSize()
   setTerminalSize(buffer, width ?? termSize.width, height ?? termSize.height)
 
   // Set render mode in shared buffer
-  applyRenderMode(buffer, mode)
+  applyRenderMode(bridge, mode)
 
   // Set config flags
   let flags = CONFIG_DEFAULT
@@ -238,7 +494,39 @@ export function mountSync(app: () => void, options: MountOptions = {}): MountHan
   if (disableMouse) {
     flags &= ~CONFIG_MOUSE_ENABLED
   }
+  if (spatialNavigation) {
+    flags |= CONFIG_SPATIAL_NAVIGATION
+  }
+  if (nativeCursor) {
+    flags |= CONFIG_NATIVE_CURSOR
+  }
   setConfigFlags(buffer, flags)
+  setControlChars(buffer, controlChars === 'caret' ? ControlChars.Caret : ControlChars.Pictures)
+  setBatchWindow(buffer, batchWindowMicros(batchWindow))
+  setInlineHeight(buffer, inlineHeight.min ?? 0, inlineHeight.max ?? 0)
+
+  const stopTracking = withBridge(bridge, () => {
+    configureCellAspect(cellAspect)
+    setKeymapPreset(keymap)
+    configureScreenshot(screenshot)
+    configurePerfHud(perfHud)
+    configureInspector(inspector)
+    setDebugLayout(debugLayout)
+    const stopCapabilities = trackCapabilities()
+    const stopInteraction = trackInteraction()
+    trackLayoutObservers()
+    return () => {
+      stopCapabilities()
+      stopInteraction()
+    }
+  })
+
+  let mounted = true
+  let cleanup: Cleanup | null = null
+  /** Trees added with `mountRegion`, still mounted */
+  const regionCleanups = new Set<Cleanup>()
+  let exitUnsubscribe: Cleanup | null = null
+  let exitResolver: (() => void) | null = null
 
   // Create exit promise that resolves when app exits
   const exitPromise = new Promise<void>((resolve) => {
@@ -250,30 +538,37 @@ export function mountSync(app: () => void, options: MountOptions = {}): MountHan
     unmount() {
       if (!mounted) return
 
-      if (exitUnsubscribe) {
-        exitUnsubscribe()
-        exitUnsubscribe = null
-      }
+      withBridge(bridge, () => {
+        if (exitUnsubscribe) {
+          exitUnsubscribe()
+          exitUnsubscribe = null
+        }
 
-      stopEventListener()
-      cleanupAllHandlers()
+        if (!noopNotifier) stopEventListener()
+        stopTracking()
+        cleanupAllHandlers()
+        if (announcer) setAnnouncer(null)
 
-      if (currentCleanup) {
-        currentCleanup()
-        currentCleanup = null
-      }
+        for (const unmountRegion of regionCleanups) unmountRegion()
+        regionCleanups.clear()
+
+        if (cleanup) {
+          cleanup()
+          cleanup = null
+        }
+      })
 
       // Cleanup engine
-      if (currentEngine) {
-        currentEngine.cleanup()
-        currentEngine.close()
-        currentEngine = null
+      if (!noopNotifier) {
+        engine.cleanup()
+        engine.close()
+        terminalMounted = false
       }
 
-      resetBridge()
+      mountedBridges.delete(bridge)
+      if (bridge === shared) resetBridge()
 
       mounted = false
-      currentMode = 'fullscreen'
 
       // Resolve the exit promise
       if (exitResolver) {
@@ -285,14 +580,63 @@ export function mountSync(app: () => void, options: MountOptions = {}): MountHan
     },
 
     buffer,
+    bridge,
     engine,
 
     setMode(newMode: MountRenderMode) {
-      applyRenderMode(buffer, newMode)
+      applyRenderMode(bridge, newMode)
     },
 
     getMode() {
-      return currentMode
+      return renderModes.get(bridge) ?? mode
+    },
+
+    setBatchWindow(ms: number) {
+      setBatchWindow(buffer, batchWindowMicros(ms))
+    },
+
+    setInlineHeight(bounds: InlineHeight) {
+      setInlineHeight(buffer, bounds.min ?? 0, bounds.max ?? 0)
+      bridge.notifier.notify()
+    },
+
+    setCellAspect(aspect: number) {
+      withBridge(bridge, () => configureCellAspect(aspect))
+      bridge.notifier.notify()
+    },
+
+    recordCast(path: string) {
+      return engine.recordCast(path) === 0
+    },
+
+    stopCast() {
+      engine.recordCast(null)
+    },
+
+    setTitle(title: string) {
+      engine.setTitle(title)
+    },
+
+    bell() {
+      engine.bell()
+    },
+
+    notify(body: string, options: NotifyOptions = {}) {
+      const protocol = options.protocol === 'osc777' ? NOTIFY_OSC777 : NOTIFY_OSC9
+      engine.notify(protocol, options.title ?? '', body)
+    },
+
+    println(line: string | HistorySpan[]) {
+      engine.println(encodeStyledLine(line))
+    },
+
+    mountRegion(regionApp: () => void, target: number) {
+      const cleanupRegion = withBridge(bridge, () => scoped(() => withRegion(target, regionApp)))
+      const unmount = () => {
+        if (regionCleanups.delete(unmount)) withBridge(bridge, cleanupRegion)
+      }
+      regionCleanups.add(unmount)
+      return unmount
     },
 
     waitForExit() {
@@ -302,19 +646,19 @@ export function mountSync(app: () => void, options: MountOptions = {}): MountHan
 
   // Register exit handler (Ctrl+C, 'q', etc. from Rust)
   if (!disableCtrlC) {
-    exitUnsubscribe = registerExitHandler(() => {
+    exitUnsubscribe = withBridge(bridge, () => registerExitHandler(() => {
       handle.unmount()
       process.exit(0)
-    })
+    }))
   }
 
   // Run app in scoped context BEFORE starting engine.
   // The component tree must be fully constructed before Rust's initial render.
   // Wake calls during construction are safe — spark_wake() no-ops when
   // BUFFER isn't initialized yet (guard added in lib.rs).
-  currentCleanup = scoped(() => {
-    app()
-  })
+  cleanup = withBridge(bridge, () => scoped(() => {
+    withRegion(region, app)
+  }))
 
   // Initialize Rust engine AFTER tree is ready.
   // This eliminates the race where the engine thread's initial render runs
@@ -324,15 +668,28 @@ export function mountSync(app: () => void, options: MountOptions = {}): MountHan
     if (result !== 0) {
       throw new Error(`SparkTUI engine init failed with code ${result}`)
     }
+    if (recordCast && !handle.recordCast(recordCast)) {
+      console.error(`[TUI] Could not record to ${recordCast}`)
+    }
+  }
+
+  if (!noopNotifier) terminalMounted = true
+  mountedBridges.add(bridge)
+
+  // Announcements through the terminal need the engine running
+  if (announcer === 'notify') {
+    setAnnouncer(({ message }) => handle.notify(message))
+  } else if (announcer === 'title') {
+    setAnnouncer(({ message }) => handle.setTitle(message))
+  } else if (announcer) {
+    setAnnouncer(announcer)
   }
 
   // Start event listener (worker-based - TRUE 0% CPU, non-blocking main thread)
   if (!noopNotifier) {
-    startEventListener(buffer, getLibPath())
+    startEventListener(bridge, getLibPath())
   }
 
-  mounted = true
-
   return handle
 }
 
@@ -393,12 +750,12 @@ export async function mount(app: () => void, options: MountOptions = {}): Promis
 
 /** Check if SparkTUI is currently mounted */
 export function isMounted(): boolean {
-  return mounted
+  return mountedBridges.size > 0
 }
 
-/** Get the current render mode */
+/** Get the active instance's render mode */
 export function getRenderMode(): MountRenderMode {
-  return currentMode
+  return (isInitialized() && renderModes.get(getBridge())) || 'fullscreen'
 }
 
 /**
@@ -0,0 +1,233 @@
+/**
+ * TUI Framework - Component Observers
+ *
+ * Lets external tooling (inspectors, test drivers) watch components being
+ * created, destroyed and laid out, without touching widget code.
+ *
+ * Usage:
+ * ```ts
+ * const inspector = {
+ *   created: (c) => tree.add(c),
+ *   destroyed: (c) => tree.remove(c),
+ *   layoutChanged: (c, rect) => tree.move(c, rect),
+ * }
+ * const stop = observeComponents(inspector)
+ * ```
+ *
+ * Nothing here keeps anything alive:
+ * - Observers are held weakly. Once the observer object is collected it is
+ *   dropped, as if unsubscribed — keep a reference for as long as it should
+ *   listen.
+ * - Components are reported as handles (index + id). A handle holds no
+ *   component state and turns `alive === false` when its component is
+ *   destroyed, even if the index is recycled.
+ *
+ * Layout changes are sent by Rust (EventType.LayoutChange) only while some
+ * observer has `layoutChanged`; the first layout of a new component counts
+ * as a change.
+ */
+
+import { addLifecycleListener } from './lifecycle'
+import { getAllocatedIndices, getId, getGeneration, isLive, type ComponentRef } from './registry'
+import { registerLayoutChangeHandler } from './events'
+import { getArrays, getBuffer, isInitialized } from '../bridge'
+import { setLayoutEvents, COMPONENT_NONE } from '../bridge/shared-buffer'
+
+export interface ComponentRect {
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+/** Weak reference to a component */
+export class ComponentHandle implements ComponentRef {
+  /** Generation of the index when the component was created */
+  readonly generation: number
+
+  constructor(
+    readonly index: number,
+    readonly id: string
+  ) {
+    this.generation = getGeneration(index)
+  }
+
+  /** False once the component is destroyed */
+  get alive(): boolean {
+    return !released.has(this) && isLive(this)
+  }
+
+  /** COMPONENT_* type (COMPONENT_NONE once destroyed) */
+  get type(): number {
+    return this.alive && isInitialized() ? getArrays().componentType.get(this.index) : COMPONENT_NONE
+  }
+
+  /** Current computed rect (zero once destroyed) */
+  get rect(): ComponentRect {
+    if (!this.alive || !isInitialized()) return { x: 0, y: 0, width: 0, height: 0 }
+    return readRect(this.index)
+  }
+}
+
+export interface ComponentObserver {
+  created?(component: ComponentHandle): void
+  destroyed?(component: ComponentHandle): void
+  layoutChanged?(component: ComponentHandle, rect: ComponentRect): void
+}
+
+// =============================================================================
+// State
+// =============================================================================
+
+/** Handles whose component was destroyed */
+const released = new WeakSet<ComponentHandle>()
+
+const observers = new Set<WeakRef<ComponentObserver>>()
+const finalizer = new FinalizationRegistry<WeakRef<ComponentObserver>>((ref) => unobserve(ref))
+
+/** One handle per live component, while anyone observes */
+const handles = new Map<number, ComponentHandle>()
+/** Rects as of the last layout, while anyone observes layout */
+const rects = new Map<number, ComponentRect>()
+
+let removeListener: (() => void) | null = null
+let removeLayoutHandler: (() => void) | null = null
+
+function readRect(index: number): ComponentRect {
+  const arrays = getArrays()
+  return {
+    x: arrays.computedX.get(index),
+    y: arrays.computedY.get(index),
+    width: arrays.computedWidth.get(index),
+    height: arrays.computedHeight.get(index),
+  }
+}
+
+function sameRect(a: ComponentRect, b: ComponentRect): boolean {
+  return a.x === b.x && a.y === b.y && a.width === b.width && a.height === b.height
+}
+
+function notify(fn: (observer: ComponentObserver) => void): void {
+  for (const ref of observers) {
+    const observer = ref.deref()
+    if (!observer) continue
+    try {
+      fn(observer)
+    } catch (err) {
+      console.error('Error in component observer:', err)
+    }
+  }
+}
+
+function observesLayout(): boolean {
+  for (const ref of observers) {
+    if (ref.deref()?.layoutChanged) return true
+  }
+  return false
+}
+
+// =============================================================================
+// Notifications
+// =============================================================================
+
+function created(index: number): void {
+  const id = getId(index)
+  if (id === undefined) return
+  const handle = new ComponentHandle(index, id)
+  handles.set(index, handle)
+  notify((observer) => observer.created?.(handle))
+}
+
+function destroyed(index: number): void {
+  // Slot anchors and components from before the first observer aren't known
+  const handle = handles.get(index)
+  if (!handle) return
+  handles.delete(index)
+  rects.delete(index)
+  released.add(handle)
+  notify((observer) => observer.destroyed?.(handle))
+}
+
+function layoutChanged(): void {
+  if (!isInitialized()) return
+  for (const [index, handle] of handles) {
+    const rect = readRect(index)
+    const last = rects.get(index)
+    if (last && sameRect(last, rect)) continue
+    rects.set(index, rect)
+    notify((observer) => observer.layoutChanged?.(handle, rect))
+  }
+}
+
+/** Adopt components that exist before the first observer */
+function adoptExisting(): void {
+  if (!isInitialized()) return
+  const arrays = getArrays()
+  for (const index of getAllocatedIndices()) {
+    const id = getId(index)
+    if (id === undefined || arrays.componentType.get(index) === COMPONENT_NONE) continue
+    handles.set(index, new ComponentHandle(index, id))
+  }
+}
+
+/**
+ * Turn Rust's layout events on or off to match the observers, and listen
+ * to them. Called at mount and whenever observers change.
+ */
+export function trackLayoutObservers(): void {
+  const enabled = observesLayout()
+  if (isInitialized()) setLayoutEvents(getBuffer(), enabled)
+
+  // Unmounting clears event handlers, so always register afresh
+  removeLayoutHandler?.()
+  removeLayoutHandler = null
+  if (!enabled) {
+    rects.clear()
+    return
+  }
+  // Baseline: only later moves are reported for what's already laid out
+  if (isInitialized()) {
+    for (const index of handles.keys()) {
+      if (!rects.has(index)) rects.set(index, readRect(index))
+    }
+  }
+  removeLayoutHandler = registerLayoutChangeHandler(layoutChanged)
+}
+
+function unobserve(ref: WeakRef<ComponentObserver>): void {
+  if (!observers.delete(ref)) return
+  finalizer.unregister(ref)
+
+  if (observers.size === 0 && removeListener) {
+    removeListener()
+    removeListener = null
+    handles.clear()
+  }
+  trackLayoutObservers()
+}
+
+// =============================================================================
+// API
+// =============================================================================
+
+/**
+ * Observe every component's creation, destruction and layout.
+ *
+ * Components that already exist are not reported as created, but their
+ * later destruction and moves are.
+ *
+ * @returns Unsubscribe function
+ */
+export function observeComponents(observer: ComponentObserver): () => void {
+  if (!removeListener) {
+    adoptExisting()
+    removeListener = addLifecycleListener({ mounted: created, destroyed })
+  }
+
+  const ref = new WeakRef(observer)
+  observers.add(ref)
+  finalizer.register(observer, ref, ref)
+  trackLayoutObservers()
+
+  return () => unobserve(ref)
+}
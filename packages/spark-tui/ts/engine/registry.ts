@@ -9,67 +9,116 @@
  * - Free index pool for O(1) reuse
  * - ReactiveSet for allocatedIndices (deriveds react to add/remove)
  * - O(1) parent-child hierarchy via doubly-linked sibling list
+ * - Generation per index, so a ComponentRef outliving its component
+ *   doesn't resolve to whatever reuses the index
+ * - One registry per bridge, so mounted instances number their own nodes
  */
 
 import { ReactiveSet } from '@rlabs-inc/signals'
 import { runDestroyCallbacks, resetLifecycle } from './lifecycle'
-import { getBuffer, getArrays, isInitialized } from '../bridge'
+import { getBuffer, getArrays, isInitialized, bridgeState } from '../bridge'
 import {
   setNodeCount,
   getChildren,
   linkChild,
   unlinkChild,
   initNodeHierarchy,
+  setNodeGeneration,
+  getU8,
+  setU8,
   COMPONENT_NONE,
+  N_REGION,
 } from '../bridge/shared-buffer'
 
 // =============================================================================
 // Registry State
 // =============================================================================
 
-/** Map component ID to array index */
-const idToIndex = new Map<string, number>()
-
-/** Map array index to component ID */
-const indexToId = new Map<number, string>()
-
-/**
- * Set of currently allocated indices (for iteration).
- *
- * Using ReactiveSet so deriveds that iterate over this set
- * automatically react when components are added or removed.
- */
-const allocatedIndices = new ReactiveSet<number>()
-
-/** Pool of freed indices for reuse */
-const freeIndices: number[] = []
-
-/** Next index to allocate if pool is empty */
-let nextIndex = 0
+/** One instance's registry (see `bridgeState`) */
+interface Registry {
+  /** Map component ID to array index */
+  idToIndex: Map<string, number>
+  /** Map array index to component ID */
+  indexToId: Map<number, string>
+  /**
+   * Set of currently allocated indices (for iteration).
+   *
+   * Using ReactiveSet so deriveds that iterate over this set
+   * automatically react when components are added or removed.
+   */
+  allocatedIndices: ReactiveSet<number>
+  /** Pool of freed indices for reuse */
+  freeIndices: number[]
+  /**
+   * Times each index has been released. Never reset — not even with the
+   * registry — so refs from before stay stale. Mirrored to N_GENERATION
+   * for Rust.
+   */
+  generations: number[]
+  /** Next index to allocate if pool is empty */
+  nextIndex: number
+  /** Counter for generating unique IDs */
+  idCounter: number
+  /** Stack of parent indices for nested component creation */
+  parentStack: number[]
+  /** Region new root components draw into (N_REGION) */
+  currentRegion: number
+  /** Active captureChildren() calls, innermost last (nested lists share a parent) */
+  captures: { parent: number; indices: number[] }[]
+}
 
-/** Counter for generating unique IDs */
-let idCounter = 0
+/** The active instance's registry: each bridge numbers its own nodes */
+const registry = bridgeState<Registry>(() => ({
+  idToIndex: new Map(),
+  indexToId: new Map(),
+  allocatedIndices: new ReactiveSet(),
+  freeIndices: [],
+  generations: [],
+  nextIndex: 0,
+  idCounter: 0,
+  parentStack: [],
+  currentRegion: 0,
+  captures: [],
+}))
 
 // =============================================================================
 // Parent Context Stack
 // =============================================================================
 
-/** Stack of parent indices for nested component creation */
-const parentStack: number[] = []
-
 /** Get current parent index (-1 if at root) */
 export function getCurrentParentIndex(): number {
+  const { parentStack } = registry()
   return parentStack.length > 0 ? (parentStack[parentStack.length - 1] ?? -1) : -1
 }
 
+// =============================================================================
+// Render Regions
+// =============================================================================
+
+/**
+ * Run `fn` with the trees it creates drawn into `region`: in inline and
+ * append mode, regions stack top to bottom in ascending order and redraw
+ * independently.
+ */
+export function withRegion<T>(region: number, fn: () => T): T {
+  const state = registry()
+  const previous = state.currentRegion
+  state.currentRegion = region
+  try {
+    return fn()
+  } finally {
+    state.currentRegion = previous
+  }
+}
+
 /** Push a parent index onto the stack */
 export function pushParentContext(index: number): void {
-  parentStack.push(index)
+  registry().parentStack.push(index)
 }
 
 /** Pop a parent index from the stack */
 export function popParentContext(): void {
-  parentStack.pop()
+  registry().parentStack.pop()
 }
 
 // =============================================================================
@@ -83,8 +132,11 @@ export function popParentContext(): void {
  * @returns The allocated index.
  */
 export function allocateIndex(id?: string): number {
+  const state = registry()
+  const { idToIndex, indexToId, allocatedIndices, freeIndices } = state
+
   // Generate ID if not provided
-  const componentId = id ?? `c${idCounter++}`
+  const componentId = id ?? `c${state.idCounter++}`
 
   // Check if already allocated
   const existing = idToIndex.get(componentId)
@@ -95,7 +147,7 @@ export function allocateIndex(id?: string): number {
   // Reuse free index or allocate new
   const index = freeIndices.length > 0
     ? freeIndices.pop()!
-    : nextIndex++
+    : state.nextIndex++
 
   // Register mappings
   idToIndex.set(componentId, index)
@@ -111,9 +163,13 @@ export function allocateIndex(id?: string): number {
     // Without this, getFirstChild(0) returns 0 instead of -1, causing infinite loops.
     initNodeHierarchy(buf, index)
 
+    // A root takes the region being mounted, anything else its parent's
+    const parent = getCurrentParentIndex()
+    setU8(buf, index, N_REGION, parent >= 0 ? getU8(buf, parent, N_REGION) : state.currentRegion)
+
     // Update node count
     const count = allocatedIndices.size
-    setNodeCount(buf, count > nextIndex ? count : nextIndex)
+    setNodeCount(buf, count > state.nextIndex ? count : state.nextIndex)
   }
 
   return index
@@ -127,6 +183,10 @@ export function allocateIndex(id?: string): number {
  * @param parentIndex - The parent component index (-1 for root)
  */
 export function registerParent(childIndex: number, parentIndex: number): void {
+  for (const c of registry().captures) {
+    if (c.parent === parentIndex) c.indices.push(childIndex)
+  }
+
   if (!isInitialized()) return
 
   const buf = getBuffer()
@@ -137,6 +197,25 @@ export function registerParent(childIndex: number, parentIndex: number): void {
   }
 }
 
+/**
+ * Run `fn` and return the nodes it created directly under `parentIndex`,
+ * in creation order — the top-level nodes of one rendered item.
+ * If `fn` throws, the nodes it created so far are released.
+ */
+export function captureChildren<T>(parentIndex: number, fn: () => T): { result: T; indices: number[] } {
+  const { captures } = registry()
+  const current = { parent: parentIndex, indices: [] as number[] }
+  captures.push(current)
+  try {
+    return { result: fn(), indices: current.indices }
+  } catch (error) {
+    for (const index of current.indices) releaseIndex(index)
+    throw error
+  } finally {
+    captures.pop()
+  }
+}
+
 /**
  * Release an index back to the pool.
  * Also recursively releases all children using O(1) linked list traversal!
@@ -144,6 +223,8 @@ export function registerParent(childIndex: number, parentIndex: number): void {
  * @param index - The index to release.
  */
 export function releaseIndex(index: number): void {
+  const state = registry()
+  const { idToIndex, indexToId, allocatedIndices, freeIndices } = state
   const id = indexToId.get(index)
   if (id === undefined) return
 
@@ -170,6 +251,11 @@ export function releaseIndex(index: number): void {
   // Run destroy callbacks before cleanup
   runDestroyCallbacks(index)
 
+  // Refs to this component go stale, and so do its queued events
+  const generation = (getGeneration(index) + 1) >>> 0
+  state.generations[index] = generation
+  if (isInitialized()) setNodeGeneration(getBuffer(), index, generation)
+
   // Clean up mappings
   idToIndex.delete(id)
   indexToId.delete(index)
@@ -181,7 +267,7 @@ export function releaseIndex(index: number): void {
   // AUTO-CLEANUP: When all components destroyed, reset counters
   if (allocatedIndices.size === 0) {
     freeIndices.length = 0
-    nextIndex = 0
+    state.nextIndex = 0
     if (isInitialized()) {
       setNodeCount(getBuffer(), 0)
     }
@@ -194,32 +280,74 @@ export function releaseIndex(index: number): void {
 
 /** Get index for a component ID */
 export function getIndex(id: string): number | undefined {
-  return idToIndex.get(id)
+  return registry().idToIndex.get(id)
 }
 
 /** Get ID for an index */
 export function getId(index: number): string | undefined {
-  return indexToId.get(index)
+  return registry().indexToId.get(index)
 }
 
 /** Get all currently allocated indices */
 export function getAllocatedIndices(): Set<number> {
-  return allocatedIndices
+  return registry().allocatedIndices
 }
 
 /** Check if an index is currently allocated */
 export function isAllocated(index: number): boolean {
-  return allocatedIndices.has(index)
+  return registry().allocatedIndices.has(index)
 }
 
 /** Get the current capacity (highest index that would be allocated next) */
 export function getCapacity(): number {
-  return nextIndex
+  return registry().nextIndex
 }
 
 /** Get the count of currently allocated components */
 export function getAllocatedCount(): number {
-  return allocatedIndices.size
+  return registry().allocatedIndices.size
+}
+
+// =============================================================================
+// Component Refs
+// =============================================================================
+
+/**
+ * A component's index plus the generation it was allocated in. Hold one
+ * instead of a bare index wherever the component may be destroyed first:
+ * the index is recycled, the ref just stops resolving.
+ */
+export interface ComponentRef {
+  readonly index: number
+  readonly generation: number
+}
+
+/** Times `index` has been released */
+export function getGeneration(index: number): number {
+  return registry().generations[index] ?? 0
+}
+
+/** A ref to the component currently at `index` */
+export function componentRef(index: number): ComponentRef {
+  return { index, generation: getGeneration(index) }
+}
+
+/** Whether `ref`'s component still exists */
+export function isLive(ref: ComponentRef): boolean {
+  return registry().allocatedIndices.has(ref.index) && getGeneration(ref.index) === ref.generation
+}
+
+/** `ref`'s index, or -1 once its component is gone */
+export function resolveRef(ref: ComponentRef): number {
+  return isLive(ref) ? ref.index : -1
+}
+
+/**
+ * Release `ref`'s component. A stale ref (a cleanup run twice, a handle
+ * kept past destroy) releases nothing: the index belongs to someone else.
+ */
+export function releaseRef(ref: ComponentRef): void {
+  if (isLive(ref)) releaseIndex(ref.index)
 }
 
 // =============================================================================
@@ -228,13 +356,16 @@ export function getAllocatedCount(): number {
 
 /** Reset all registry state (for testing) */
 export function resetRegistry(): void {
-  idToIndex.clear()
-  indexToId.clear()
-  allocatedIndices.clear()
-  freeIndices.length = 0
-  nextIndex = 0
-  idCounter = 0
-  parentStack.length = 0
+  const state = registry()
+  state.idToIndex.clear()
+  state.indexToId.clear()
+  state.allocatedIndices.clear()
+  state.freeIndices.length = 0
+  state.nextIndex = 0
+  state.idCounter = 0
+  state.parentStack.length = 0
+  state.currentRegion = 0
+  state.captures.length = 0
   resetLifecycle()
   if (isInitialized()) {
     setNodeCount(getBuffer(), 0)
@@ -21,7 +21,11 @@ export {
   getRenderMode,
   type MountOptions,
   type MountHandle,
+  type NotifyOptions,
+  type NotifyProtocol,
+  type HistorySpan,
   type MountRenderMode,
+  type InlineHeight,
 } from './engine/mount'
 
 // =============================================================================
@@ -32,17 +36,42 @@ export {
   text,
   input,
   each,
+  forEach,
   show,
+  match,
   when,
+  suspense,
+  resource,
+  errorBoundary,
   scoped,
   onCleanup,
   cycle,
   pulse,
   Frames,
+  tween,
+  Easing,
+  spring,
+  setReducedMotion,
+  getReducedMotion,
+  shake,
+  flashBorder,
+  mergeStyles,
+  themed,
+  numberValue,
+  dateValue,
+  patternValue,
+  parseNumber,
+  parseDate,
+  parsePattern,
 } from './primitives'
 
 export type {
   BoxProps,
+  BackgroundFillProp,
+  ScrollPosition,
+  Style,
+  StyleProp,
+  PaddingShorthand,
   TextProps,
   InputProps,
   CursorConfig,
@@ -50,11 +79,93 @@ export type {
   BlinkConfig,
   Cleanup,
   MouseProps,
+  AccessibilityProps,
   AnimationOptions,
   CycleOptions,
   PulseOptions,
+  TweenOptions,
+  Tween,
+  EasingName,
+  EasingFn,
+  SpringConfig,
+  Spring,
+  ShakeOptions,
+  FlashBorderOptions,
+  NumberRange,
+  TransitionProps,
+  TransitionSpec,
+  TransitionEffect,
+  Resource,
+  ResourceState,
+  ResourceContext,
+  MatchArms,
+  ErrorBoundaryOptions,
 } from './primitives'
 
+// =============================================================================
+// COMPONENTS - Composites built from the primitives
+// =============================================================================
+export { wizard, button, helpBar, statusBar, appShell, form, createForm, contextMenu, openContextMenu, closeContextMenu, isContextMenuOpen, logView, createRouter, routerView, dialog, filePicker, fuzzyList, fuzzyMatch, pager, calendar, datePicker, menuBar, breadcrumbs, stepper, dashboardGrid } from './components'
+
+export type {
+  ButtonProps,
+  HelpBarProps,
+  HelpEntry,
+  StatusBarProps,
+  StatusSegment,
+  AppShellProps,
+  Form,
+  FormProps,
+  FormOptions,
+  FormValues,
+  FieldValue,
+  FieldValidator,
+  InputFieldProps,
+  CheckboxFieldProps,
+  SelectFieldProps,
+  SelectOption,
+  WizardProps,
+  WizardStep,
+  WizardStepContext,
+  WizardValues,
+  MenuItem,
+  MenuEntry,
+  MenuItems,
+  MenuOptions,
+  RadioBinding,
+  LogViewProps,
+  LogLineStyle,
+  Router,
+  RouterOptions,
+  RouterViewProps,
+  RouteRender,
+  RouteParams,
+  RouteLocation,
+  RouteDirection,
+  ConfirmOptions,
+  AlertOptions,
+  PromptOptions,
+  FilePickerProps,
+  FileEntry,
+  FileSortKey,
+  FuzzyListProps,
+  FuzzyMatch,
+  PagerProps,
+  CalendarProps,
+  DatePickerProps,
+  MenuBarProps,
+  MenuBarMenu,
+  BreadcrumbsProps,
+  StepperProps,
+  StepperStep,
+  StepperContext,
+  StepValidation,
+  DashboardGridProps,
+  DashboardPanel,
+  DashboardLayout,
+  PanelPlacement,
+} from './components'
+
 // =============================================================================
 // KEYBOARD & EVENTS - Ergonomic event handling
 // =============================================================================
@@ -92,14 +203,42 @@ export {
   KEY_END,
   KEY_PAGE_UP,
   KEY_PAGE_DOWN,
+  // Terminal
+  terminalSize,   // Reactive { width, height }, current after every resize
+  hoverTarget,    // Reactive tagged node under the mouse (hoverTag prop) and its region
   // Types
   type KeyEvent,
   type MouseEvent,
   type ScrollEvent,
   type FocusEvent,
   type SparkEvent,
+  type ScreenshotEvent,
+  type ScrollAnimateEvent,
+  type ScrollChangeEvent,
+  type ReachEndEvent,
+  type HoverEvent,
+  type HoverTarget,
+  type CapabilitiesEvent,
+  type LayoutChangeEvent,
+  type EngineErrorEvent,
+  EnginePanicError,
 } from './engine/events'
 
+export {
+  observeComponents,      // Weakly held created/destroyed/layoutChanged observers (inspectors, test drivers)
+  ComponentHandle,
+  type ComponentObserver,
+  type ComponentRect,
+} from './engine/observers'
+
+export {
+  componentRef,           // { index, generation } handle that stops resolving once the component is gone
+  isLive,
+  resolveRef,
+  releaseRef,
+  type ComponentRef,
+} from './engine/registry'
+
 // =============================================================================
 // THEME - Reactive styling system
 // =============================================================================
@@ -108,8 +247,154 @@ export {
   themes,         // Theme presets: dracula, nord, catppuccin, etc.
   setTheme,       // Switch theme: setTheme('dracula') or setTheme({ primary: '#ff0000' })
   getThemeNames,  // List available: ['terminal', 'dracula', 'nord', ...]
+  terminalAdaptive,       // Theme built from the terminal's reported colors
+  followTerminalPalette,  // Keep the terminal-adaptive theme applied as the palette changes
+  setThemePair,           // Light + dark themes, switched with the terminal's background
+  terminalScheme,         // 'light' | 'dark' | null, from the reported background
+  type ColorScheme,
+  type ThemeSource,
+  type ThemePairOptions,
 } from './state/theme'
 
+export {
+  themeFromSeed,  // Whole theme from one brand color: setTheme(themeFromSeed('#7c3aed'))
+  type SeedThemeOptions,
+} from './state/themeSeed'
+
+export {
+  setColorVisionOverlay,  // Dev overlay: setColorVisionOverlay('deuteranopia'), null to turn off
+  getColorVisionOverlay,
+  auditThemeContrast,     // Theme pairs whose contrast collapses under color-blind simulation
+  type ColorVisionMode,
+  type ContrastFinding,
+} from './state/colorVision'
+
+export {
+  setPerfHud,             // Dev overlay: frame rate, stage times, cells/bytes written
+  getPerfHud,
+  togglePerfHud,
+  configurePerfHud,       // Bind its toggle key at runtime (mount's `perfHud`)
+  getFrameOutput,         // Cells and bytes the last frame wrote
+  type PerfHudOptions,
+  type FrameOutput,
+} from './state/perfHud'
+
+export {
+  setInspector,           // Dev overlay: component tree, selected node highlighted
+  getInspector,
+  toggleInspector,
+  configureInspector,     // Bind its toggle key at runtime (mount's `inspector`)
+  inspectTree,            // The live component tree as data
+  type InspectorOptions,
+  type InspectedNode,
+} from './state/inspector'
+
+export {
+  setDebugLayout,         // Dev overlay: box outlines, hatched padding, tinted gaps
+  getDebugLayout,
+} from './state/layoutDebug'
+
+export {
+  configureScreenshot,    // Rebind the screenshot key at runtime (false disables)
+  onScreenshot,           // Run a callback after each capture
+  type ScreenshotOptions,
+} from './state/screenshot'
+
+export {
+  designAssist,           // Snap-to-grid + alignment guides for drag/resize editors
+  snapRect,
+  snapToGrid,
+  type DesignAssist,
+  type DesignAssistOptions,
+  type SnapRect,
+  type AlignmentGuide,
+} from './state/snap'
+
+export {
+  defineKeymap,           // Named actions → key sequences/chords, per context
+  onAction,
+  rebind,
+  getBindings,
+  activeBindings,         // What the keys do where focus is now (for help bars)
+  findConflicts,
+  pendingKeys,            // Half-typed chord, e.g. 'ctrl+k' — for a hint line
+  CHORD_TIMEOUT,
+  setKeymapPreset,        // 'standard' | 'vim' | 'emacs' framework keys (mount's `keymap`)
+  getKeymapPreset,
+  NAV_ACTIONS,            // Framework navigation actions an app can take over
+  type KeymapContext,
+  type KeymapConflict,
+  type KeyBindingSpec,
+  type ActiveBinding,
+  type KeymapPresetName,
+  type NavAction,
+} from './state/keymap'
+
+export {
+  createHistory,          // Input history: Up/Down recall, Ctrl+R search, optional file
+  type History,
+  type HistoryOptions,
+  type HistorySearch,
+} from './state/history'
+
+export {
+  snapshotLayout,         // Scroll offsets + registered UI state by component ID, JSON-ready
+  restoreLayout,          // Apply now to what's mounted, later to what mounts
+  persistLayout,
+  persistSignal,
+  loadLayout,
+  saveLayout,
+  type LayoutSnapshot,
+  type LayoutValue,
+  type ComponentLayoutState,
+  type LayoutStateProvider,
+} from './state/layoutState'
+
+export {
+  setPreedit,             // Embedders: IME composition text, drawn underlined at the focused input's cursor
+  commitPreedit,
+  cancelPreedit,
+  composition,
+  registerComposer,
+  type Composer,
+  type Composition,
+} from './state/ime'
+
+export {
+  getInputLatency,        // Input-to-paint p50/p95/p99 measured by the engine
+  type InputLatency,
+} from './state/metrics'
+
+export {
+  createContext,
+  provide,                // Subtree-scoped inside children, app-wide elsewhere
+  useContext,             // Nearest provider up the parent chain
+  useContextSignal,
+  hasContext,
+  clearContext,
+  type Context,
+} from './state/context'
+
+export {
+  setAnnouncer,           // Send focus/live-region/manual announcements to a sink (mount's `announcer`)
+  hasAnnouncer,
+  announce,
+  describe,               // "Save, button" — how a node is announced
+  lastAnnouncement,
+  type Announcement,
+  type AnnouncerSink,
+  type Politeness,
+  type A11yRoleName,
+} from './state/announcer'
+
+export {
+  capabilities,           // Reactive terminal facts: colorDepth, unicode, mouse, cellPixels, cellAspect, palette
+  type ColorDepthName,
+  type UnicodeSupport,
+  type CellPixels,
+  type TerminalPalette,
+} from './state/capabilities'
+
 // =============================================================================
 // TEXT STYLING - Shorthand constants for clean syntax
 // =============================================================================
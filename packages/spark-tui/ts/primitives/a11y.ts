@@ -0,0 +1,68 @@
+/**
+ * TUI Framework - Accessibility props
+ *
+ * `role`, `label`, `valueText` and `live` on a box, text or input store what
+ * the node is for assistive tooling. Nothing is drawn from them; the
+ * announcer (state/announcer) reads them when focus lands on the node, and
+ * a live node announces its label, value or content whenever they change.
+ *
+ *   box({ focusable: true, role: 'button', label: 'Save', children: ... })
+ *   text({ content: () => `${count.value} results`, live: 'polite' })
+ *   box({ role: 'slider', label: 'Volume', valueText: () => `${volume.value}%` })
+ */
+
+import { effect } from '@rlabs-inc/signals'
+import {
+  setA11yLabel,
+  setA11yValue,
+  setU8,
+  LiveRegion,
+  N_A11Y_LIVE,
+  N_A11Y_ROLE,
+} from '../bridge/shared-buffer'
+import type { SharedBuffer } from '../bridge/shared-buffer'
+import { announce, roleFromName } from '../state/announcer'
+import type { AccessibilityProps, Reactive } from './types'
+import { unwrap } from './utils'
+
+const LIVE = { off: LiveRegion.Off, polite: LiveRegion.Polite, assertive: LiveRegion.Assertive } as const
+
+/**
+ * Write node `index`'s accessibility props and keep them in sync. `content`
+ * is the node's own text, announced by live regions without a label.
+ */
+export function bindA11y(
+  buf: SharedBuffer,
+  index: number,
+  props: AccessibilityProps,
+  disposals: (() => void)[],
+  content?: Reactive<unknown>,
+): void {
+  if (props.role !== undefined) setU8(buf, index, N_A11Y_ROLE, roleFromName(props.role))
+
+  const pool = (ok: boolean, what: string) => {
+    if (!ok) throw new Error(`Text pool full: cannot store ${what} for node ${index}. Increase textPoolSize in mount() config.`)
+  }
+  const label = props.label
+  if (label !== undefined) disposals.push(effect(() => pool(setA11yLabel(buf, index, unwrap(label)), 'label')))
+  const valueText = props.valueText
+  if (valueText !== undefined) disposals.push(effect(() => pool(setA11yValue(buf, index, unwrap(valueText)), 'valueText')))
+
+  const live = props.live ?? 'off'
+  setU8(buf, index, N_A11Y_LIVE, LIVE[live])
+  if (live === 'off') return
+
+  // Announce changes, not the first value
+  let first = true
+  disposals.push(effect(() => {
+    const name = label !== undefined ? unwrap(label) : ''
+    const value = valueText !== undefined ? unwrap(valueText) : ''
+    const text = content !== undefined && !name ? String(unwrap(content)) : ''
+    const message = [name || text, value].filter(Boolean).join(', ')
+    if (first) {
+      first = false
+      return
+    }
+    announce(message, live, index)
+  }))
+}
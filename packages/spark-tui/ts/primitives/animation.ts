@@ -101,6 +101,27 @@ function releaseClock(fps: number, subscriber: () => void): void {
   }
 }
 
+// =============================================================================
+// REDUCED MOTION
+// =============================================================================
+
+let reduceMotion = false
+
+/**
+ * Turn motion down app-wide, for users who find it distracting or
+ * disorienting. Tweens (and so transitions) jump straight to their end
+ * value, and purely decorative feedback like shake() is skipped. Looping
+ * signals (cycle, pulse) and springs are left to the app.
+ */
+export function setReducedMotion(reduce: boolean): void {
+  reduceMotion = reduce
+}
+
+/** Whether motion is turned down (see setReducedMotion) */
+export function getReducedMotion(): boolean {
+  return reduceMotion
+}
+
 // =============================================================================
 // CYCLE - Core animation primitive
 // =============================================================================
@@ -275,6 +296,291 @@ export function pulse(options: PulseOptions = {}): WritableSignal<boolean> {
   return cycle([true, false], { fps, active, autoStart })
 }
 
+// =============================================================================
+// TWEEN - Interpolated numeric signals
+// =============================================================================
+
+/** Maps linear progress 0..1 to eased progress. */
+export type EasingFn = (t: number) => number
+
+/**
+ * Built-in easing curves.
+ */
+export const Easing = {
+  linear: (t: number) => t,
+  easeInQuad: (t: number) => t * t,
+  easeOutQuad: (t: number) => t * (2 - t),
+  easeInOutQuad: (t: number) => (t < 0.5 ? 2 * t * t : -1 + (4 - 2 * t) * t),
+  easeInCubic: (t: number) => t * t * t,
+  easeOutCubic: (t: number) => 1 - Math.pow(1 - t, 3),
+  easeInOutCubic: (t: number) => (t < 0.5 ? 4 * t * t * t : 1 - Math.pow(-2 * t + 2, 3) / 2),
+} satisfies Record<string, EasingFn>
+
+export type EasingName = keyof typeof Easing
+
+export interface TweenOptions {
+  /** Duration in ms (default: 200) */
+  duration?: number
+  /** Easing curve name or function (default: 'easeOutCubic') */
+  easing?: EasingName | EasingFn
+  /** Update rate while running (default: 30). Shares the clock with same-FPS animations. */
+  fps?: number
+}
+
+export interface Tween {
+  /** Stop where it is. `finished` resolves to false. */
+  cancel(): void
+  /** Resolves true when the target is reached, false if cancelled */
+  finished: Promise<boolean>
+}
+
+const DEFAULT_TWEEN_DURATION = 200
+const DEFAULT_TWEEN_FPS = 30
+
+/** Tween currently driving each signal — a new tween replaces the old one */
+const activeTweens = new WeakMap<WritableSignal<number>, Tween>()
+
+/**
+ * Drive `onFrame` from `from` to `to` on the shared clock.
+ *
+ * The clock is only subscribed while the tween runs. Progress comes from
+ * wall time, so a slow frame skips ahead rather than stretching the tween.
+ * Under reduced motion it lands on `to` at once. Not tied to any scope —
+ * callers own cancellation.
+ */
+export function animateValue(
+  from: number,
+  to: number,
+  options: TweenOptions,
+  onFrame: (value: number) => void
+): Tween {
+  const { duration = DEFAULT_TWEEN_DURATION, easing = 'easeOutCubic', fps = DEFAULT_TWEEN_FPS } = options
+  const ease = typeof easing === 'function' ? easing : Easing[easing]
+
+  let resolve!: (done: boolean) => void
+  const finished = new Promise<boolean>((r) => (resolve = r))
+
+  if (duration <= 0 || from === to || reduceMotion) {
+    onFrame(to)
+    resolve(true)
+    return { cancel: () => {}, finished }
+  }
+
+  const start = performance.now()
+  let running = true
+
+  const stop = (done: boolean) => {
+    if (!running) return
+    running = false
+    releaseClock(fps, tick)
+    resolve(done)
+  }
+
+  const tick = () => {
+    const t = Math.min(1, (performance.now() - start) / duration)
+    onFrame(t === 1 ? to : from + (to - from) * ease(t))
+    if (t === 1) stop(true)
+  }
+
+  getOrCreateClock(fps).subscribers.add(tick)
+
+  return { cancel: () => stop(false), finished }
+}
+
+/**
+ * Animate a numeric signal to `target`.
+ *
+ * Starting a new tween on a signal cancels the one already running on it,
+ * so retargeting mid-flight continues from the current value.
+ *
+ * @example Animated progress bar
+ * ```ts
+ * const width = signal(0)
+ * box({ width, height: 1, bg: t.primary })
+ * tween(width, 40, { duration: 300, easing: 'easeInOutCubic' })
+ * ```
+ */
+export function tween(sig: WritableSignal<number>, target: number, options: TweenOptions = {}): Tween {
+  activeTweens.get(sig)?.cancel()
+
+  const handle = animateValue(sig.value, target, options, (value) => {
+    sig.value = value
+  })
+  activeTweens.set(sig, handle)
+  handle.finished.then(() => {
+    if (activeTweens.get(sig) === handle) activeTweens.delete(sig)
+  })
+
+  // Auto-cleanup with scope
+  const scope = getActiveScope()
+  if (scope) {
+    scope.cleanups.push(() => handle.cancel())
+  }
+
+  return handle
+}
+
+// =============================================================================
+// SPRING - Physics-based motion that follows a moving target
+// =============================================================================
+
+export interface SpringConfig {
+  /** Pull toward the target (default: 170) */
+  stiffness?: number
+  /** Resistance to motion; higher settles without overshoot (default: 26) */
+  damping?: number
+  /** Inertia (default: 1) */
+  mass?: number
+  /** Settled once distance and speed are both below this (default: 0.01) */
+  precision?: number
+  /** Update rate while moving (default: 60). Shares the clock with same-FPS animations. */
+  fps?: number
+}
+
+export interface SpringState {
+  position: number
+  /** Units per second */
+  velocity: number
+}
+
+export interface SpringDriver {
+  /** Stop where it is, keeping the current velocity in the state */
+  cancel(): void
+  /** Whether the spring is still moving */
+  readonly running: boolean
+}
+
+/** A spring-driven signal with its velocity alongside */
+export interface Spring extends ReadableSignal<number> {
+  velocity: ReadableSignal<number>
+}
+
+const DEFAULT_SPRING: Required<SpringConfig> = {
+  stiffness: 170,
+  damping: 26,
+  mass: 1,
+  precision: 0.01,
+  fps: 60,
+}
+
+/** Longest integration step in seconds — larger frame gaps are subdivided to stay stable */
+const MAX_SPRING_STEP = 1 / 120
+
+/** Fill in defaults for a partial spring config */
+export function springConfig(config: SpringConfig = {}): Required<SpringConfig> {
+  return { ...DEFAULT_SPRING, ...config }
+}
+
+/** Advance `state` toward `target` by `dt` seconds (semi-implicit Euler) */
+export function stepSpring(state: SpringState, target: number, config: Required<SpringConfig>, dt: number): void {
+  let remaining = dt
+  while (remaining > 0) {
+    const h = Math.min(remaining, MAX_SPRING_STEP)
+    const force = -config.stiffness * (state.position - target) - config.damping * state.velocity
+    state.velocity += (force / config.mass) * h
+    state.position += state.velocity * h
+    remaining -= h
+  }
+}
+
+/**
+ * Move `state` toward `target()` on the shared clock until it settles.
+ *
+ * The target is re-read every frame, so it may move while the spring runs —
+ * velocity carries over instead of restarting. On settle the state snaps to
+ * the target with zero velocity, `onFrame` sees that final value, then
+ * `onSettle` runs. Not tied to any scope — callers own cancellation.
+ */
+export function driveSpring(
+  state: SpringState,
+  target: () => number,
+  config: SpringConfig,
+  onFrame: (state: SpringState) => void,
+  onSettle?: () => void
+): SpringDriver {
+  const cfg = springConfig(config)
+  let last = performance.now()
+  let running = true
+
+  const stop = () => {
+    if (!running) return
+    running = false
+    releaseClock(cfg.fps, tick)
+  }
+
+  const tick = () => {
+    const now = performance.now()
+    const goal = target()
+    stepSpring(state, goal, cfg, (now - last) / 1000)
+    last = now
+
+    const settled = Math.abs(state.position - goal) < cfg.precision && Math.abs(state.velocity) < cfg.precision
+    if (settled) {
+      state.position = goal
+      state.velocity = 0
+    }
+    onFrame(state)
+    if (settled) {
+      stop()
+      onSettle?.()
+    }
+  }
+
+  getOrCreateClock(cfg.fps).subscribers.add(tick)
+
+  return {
+    cancel: stop,
+    get running() {
+      return running
+    },
+  }
+}
+
+/**
+ * A signal that springs toward `source` whenever it changes.
+ *
+ * Unlike tween() there is no duration: motion follows from stiffness,
+ * damping and mass, and a target that moves mid-flight keeps the current
+ * momentum. The clock is only subscribed while the spring is moving.
+ *
+ * @example Panel that slides with a little bounce
+ * ```ts
+ * const open = signal(false)
+ * const x = spring(() => (open.value ? 0 : -30), { damping: 14 })
+ * box({ position: 'absolute', left: x, width: 30 })
+ * ```
+ */
+export function spring(
+  source: ReadableSignal<number> | (() => number),
+  config: SpringConfig = {}
+): Spring {
+  const read = typeof source === 'function' ? source : () => source.value
+  const state: SpringState = { position: read(), velocity: 0 }
+  const value = signal(state.position)
+  const velocity = signal(0)
+  let driver: SpringDriver | null = null
+
+  const stopFollowing = effect(() => {
+    const target = read()
+    if (driver?.running || target === state.position) return
+    driver = driveSpring(state, read, config, (s) => {
+      value.value = s.position
+      velocity.value = s.velocity
+    })
+  })
+
+  // Auto-cleanup with scope
+  const scope = getActiveScope()
+  if (scope) {
+    scope.cleanups.push(() => {
+      stopFollowing()
+      driver?.cancel()
+    })
+  }
+
+  return Object.assign(value, { velocity }) as Spring
+}
+
 // =============================================================================
 // BUILT-IN FRAME SETS
 // =============================================================================
@@ -24,12 +24,12 @@
  */
 
 import { repeat } from '@rlabs-inc/signals'
+import { bindPercentBases, pointerEventsToNum, toDim, unwrap } from './utils'
 import { ComponentType } from '../types'
 import type { RGBA, ColorInput } from '../types'
 import { parseColor } from '../types/color'
 import {
   allocateIndex,
-  releaseIndex,
   getCurrentParentIndex,
   pushParentContext,
   popParentContext,
@@ -41,10 +41,19 @@ import {
   runMountCallbacks,
 } from '../engine/lifecycle'
 import { cleanupIndex as cleanupKeyboardListeners, onFocused } from '../state/keyboard'
+import { cleanupIndex as cleanupInteraction } from '../state/interaction'
 import { registerFocusCallbacks, focus as focusComponent } from '../state/focus'
 import { onComponent as onMouseComponent } from '../state/mouse'
-import { getVariantStyle } from '../state/theme'
-import { getActiveScope } from './scope'
+import { registerScrollChangeHandler, registerReachEndHandler } from '../engine/events'
+import { registerSmoothScroll } from '../state/smoothScroll'
+import { t } from '../state/theme'
+import { getActiveScope, reserveCleanup } from './scope'
+import { withTransition } from './transition'
+import { bindGridPlacement, bindGridTemplateAreas } from './gridPlacement'
+import { bindHref } from './href'
+import { bindA11y } from './a11y'
+import { applyStyle } from './style'
+import { bindInteractionFlags, unlessDisabled, variantStyleFor } from './interactive'
 import { getArrays, getBuffer } from '../bridge'
 import {
   packColor,
@@ -54,35 +63,28 @@ import {
   TrackType,
   Display,
   FLAG_FOCUSABLE,
+  FLAG_FOCUS_TRAP,
+  FLAG_CAPTURE_TAB,
+  SCROLL_STICK_BOTTOM,
+  SCROLL_NO_ANCHOR,
+  SCROLL_EVENTS,
+  SCROLL_REACH_END,
   DIRTY_LAYOUT,
+  getScrollX,
+  getScrollY,
   markDirty,
   type GridTrack,
+  BackgroundFill,
   type SharedBuffer,
 } from '../bridge/shared-buffer'
 import type { ReactiveArrays } from '../bridge/reactive-arrays'
-import type { BoxProps, Cleanup, GridTrackSize, GridTemplate, GridLine } from './types'
+import type { BoxProps, Cleanup, GridTrackSize, GridTemplate, GridLine, BackgroundFillProp, FillColor } from './types'
 
 // =============================================================================
 // CONVERSION HELPERS
 // =============================================================================
 
-/** Dimension → Taffy float: NaN = auto, negative = percentage, positive = pixels */
-function toDim(dim: number | string | undefined | null): number {
-  if (dim === undefined || dim === null || dim === 0) return NaN
-  if (typeof dim === 'string') {
-    if (dim.endsWith('%')) return -parseFloat(dim) // '100%' → -100.0
-    return parseFloat(dim) || NaN
-  }
-  return dim
-}
-
 /** Unwrap any prop shape to its current value */
-function unwrap<T>(prop: T | (() => T) | { readonly value: T }): T {
-  if (typeof prop === 'function') return (prop as () => T)()
-  if (prop !== null && typeof prop === 'object' && 'value' in prop) return (prop as { value: T }).value
-  return prop
-}
-
 /** Is this prop reactive (not a static value)? */
 function isReactive(prop: unknown): boolean {
   return typeof prop === 'function' || (prop !== null && typeof prop === 'object' && 'value' in (prop as any))
@@ -158,6 +160,23 @@ function flexDirectionToNum(dir: string | undefined): number {
   }
 }
 
+function stickyToNum(value: string | false | undefined): number {
+  switch (value) {
+    case 'top': return 1
+    case 'bottom': return 2
+    default: return 0 // scrolls with the content
+  }
+}
+
+function directionToNum(dir: string | undefined): number {
+  switch (dir) {
+    case 'ltr': return 1
+    case 'rtl': return 2
+    case 'auto': return 3
+    default: return 0 // inherit
+  }
+}
+
 function flexWrapToNum(wrap: string | undefined): number {
   switch (wrap) {
     case 'wrap': return 1
@@ -252,6 +271,24 @@ function justifySelfToNum(j: string | undefined): number {
   }
 }
 
+function bgFillToNum(type: BackgroundFillProp['type'] | undefined): number {
+  switch (type) {
+    case 'linear-gradient': return BackgroundFill.LinearGradient
+    case 'checker': return BackgroundFill.Checker
+    case 'stripes-horizontal': return BackgroundFill.StripesHorizontal
+    case 'stripes-vertical': return BackgroundFill.StripesVertical
+    case 'stripes-diagonal': return BackgroundFill.StripesDiagonal
+    default: return BackgroundFill.Solid
+  }
+}
+
+/** Resolve a fill color, looking up theme names in `t` (tracks theme changes) */
+function resolveFillColor(color: FillColor): ColorInput {
+  const value = unwrap(color)
+  if (typeof value === 'string' && value in t) return t[value as keyof typeof t].value
+  return value
+}
+
 // =============================================================================
 // GRID TRACK PARSING
 // =============================================================================
@@ -300,12 +337,17 @@ function parseGridLine(line: GridLine | undefined): number {
 // =============================================================================
 
 export function box(props: BoxProps = {}): Cleanup {
+  props = applyStyle(props)
   const buf = getBuffer()
   const arrays = getArrays()
   const index = allocateIndex(props.id)
   const disposals: (() => void)[] = []
   const parentIdx = getCurrentParentIndex()
 
+  // An exit transition has to start before the children's cleanups run,
+  // so claim our place in the scope ahead of them
+  const placeCleanup = props.transition?.exit ? reserveCleanup() : null
+
   pushCurrentComponent(index)
 
   // --------------------------------------------------------------------------
@@ -336,9 +378,15 @@ export function box(props: BoxProps = {}): Cleanup {
   if (props.maxWidth !== undefined) disposals.push(repeat(dimInput(props.maxWidth), arrays.maxWidth, index))
   if (props.minHeight !== undefined) disposals.push(repeat(dimInput(props.minHeight), arrays.minHeight, index))
   if (props.maxHeight !== undefined) disposals.push(repeat(dimInput(props.maxHeight), arrays.maxHeight, index))
+  disposals.push(...bindPercentBases(props, arrays, index))
+  if (props.aspectRatio !== undefined) disposals.push(repeat(numInput(props.aspectRatio, NaN), arrays.aspectRatio, index))
+  if (props.aspectCells !== undefined) disposals.push(repeat(boolInput(props.aspectCells, 0), arrays.aspectCells, index))
 
   // Overflow
   if (props.overflow !== undefined) disposals.push(repeat(enumInput(props.overflow, overflowToNum), arrays.overflow, index))
+  // Per-axis overrides are stored +1 so 0 can mean "same as overflow"
+  if (props.overflowX !== undefined) disposals.push(repeat(enumInput(props.overflowX, (o) => overflowToNum(o) + 1), arrays.overflowX, index))
+  if (props.overflowY !== undefined) disposals.push(repeat(enumInput(props.overflowY, (o) => overflowToNum(o) + 1), arrays.overflowY, index))
 
   // --------------------------------------------------------------------------
   // FLEXBOX CONTAINER
@@ -348,6 +396,7 @@ export function box(props: BoxProps = {}): Cleanup {
   if (props.justifyContent !== undefined) disposals.push(repeat(enumInput(props.justifyContent, justifyToNum), arrays.justifyContent, index))
   if (props.alignItems !== undefined) disposals.push(repeat(enumInput(props.alignItems, alignItemsToNum), arrays.alignItems, index))
   if (props.alignContent !== undefined) disposals.push(repeat(enumInput(props.alignContent, alignContentToNum), arrays.alignContent, index))
+  if (props.direction !== undefined) disposals.push(repeat(enumInput(props.direction, directionToNum), arrays.direction, index))
 
   // --------------------------------------------------------------------------
   // FLEXBOX ITEM
@@ -476,6 +525,9 @@ export function box(props: BoxProps = {}): Cleanup {
     arrays.gridAutoRowsType.set(index, track.trackType)
     arrays.gridAutoRowsValue.set(index, track.value)
   }
+  if (props.gridTemplateAreas !== undefined) {
+    disposals.push(bindGridTemplateAreas(index, props.gridTemplateAreas))
+  }
 
   // --------------------------------------------------------------------------
   // GRID ITEM PROPERTIES
@@ -511,34 +563,57 @@ export function box(props: BoxProps = {}): Cleanup {
   if (props.justifySelf !== undefined) {
     disposals.push(repeat(enumInput(props.justifySelf, justifySelfToNum), arrays.justifySelf, index))
   }
+  bindGridPlacement(props, arrays, index, parentIdx, disposals)
 
   // --------------------------------------------------------------------------
   // VISUAL — colors and border styles
   // --------------------------------------------------------------------------
+  // bgFill owns the bg slot: its `from` color is the plain bg children inherit
+  const fill = props.bgFill
+  const bg: BoxProps['bg'] = fill !== undefined ? () => resolveFillColor(unwrap(fill).from) : props.bg
+  if (fill !== undefined) {
+    disposals.push(repeat(() => bgFillToNum(unwrap(fill).type), arrays.bgFill, index))
+    disposals.push(repeat(() => toPackedColor(resolveFillColor(unwrap(fill).to)), arrays.bgColor2, index))
+    disposals.push(repeat(() => {
+      const f = unwrap(fill)
+      return f.type === 'linear-gradient' ? 0 : unwrap(f.size ?? 1)
+    }, arrays.bgFillSize, index))
+    disposals.push(repeat(() => {
+      const f = unwrap(fill)
+      const angle = f.type === 'linear-gradient' ? unwrap(f.angle ?? 0) : 0
+      return ((Math.round(angle) % 360) + 360) % 360
+    }, arrays.bgFillAngle, index))
+  }
+
   if (props.variant && props.variant !== 'default') {
-    const variant = props.variant
+    // Something the user can act on shows its hover/focus/press/disabled state
+    const interactive = !!(props.focusable || props.onClick || props.onMouseDown) || props.disabled !== undefined
+    const style = variantStyleFor(index, props.variant, props.disabled, interactive)
     if (props.fg !== undefined) {
       disposals.push(repeat(colorInput(props.fg), arrays.fgColor, index))
     } else {
-      disposals.push(repeat(() => toPackedColor(getVariantStyle(variant).fg), arrays.fgColor, index))
+      disposals.push(repeat(() => toPackedColor(style.value.fg), arrays.fgColor, index))
     }
-    if (props.bg !== undefined) {
-      disposals.push(repeat(colorInput(props.bg), arrays.bgColor, index))
+    if (bg !== undefined) {
+      disposals.push(repeat(colorInput(bg), arrays.bgColor, index))
     } else {
-      disposals.push(repeat(() => toPackedColor(getVariantStyle(variant).bg), arrays.bgColor, index))
+      disposals.push(repeat(() => toPackedColor(style.value.bg), arrays.bgColor, index))
     }
     if (props.borderColor !== undefined) {
       disposals.push(repeat(colorInput(props.borderColor), arrays.borderColor, index))
     } else {
-      disposals.push(repeat(() => toPackedColor(getVariantStyle(variant).border), arrays.borderColor, index))
+      disposals.push(repeat(() => toPackedColor(style.value.border), arrays.borderColor, index))
     }
   } else {
     if (props.fg !== undefined) disposals.push(repeat(colorInput(props.fg), arrays.fgColor, index))
-    if (props.bg !== undefined) disposals.push(repeat(colorInput(props.bg), arrays.bgColor, index))
+    if (bg !== undefined) disposals.push(repeat(colorInput(bg), arrays.bgColor, index))
     if (props.borderColor !== undefined) disposals.push(repeat(colorInput(props.borderColor), arrays.borderColor, index))
   }
   if (props.opacity !== undefined) disposals.push(repeat(numInput(props.opacity), arrays.opacity, index))
   if (props.zIndex !== undefined) disposals.push(repeat(numInput(props.zIndex), arrays.zIndex, index))
+  if (props.sticky !== undefined) disposals.push(repeat(enumInput(props.sticky, stickyToNum), arrays.sticky, index))
+  if (props.pointerEvents !== undefined) disposals.push(repeat(enumInput(props.pointerEvents, pointerEventsToNum), arrays.pointerEvents, index))
+  if (props.hoverTag !== undefined) disposals.push(repeat(numInput(props.hoverTag), arrays.hoverTag, index))
 
   // Border style for rendering
   if (props.border !== undefined) disposals.push(repeat(numInput(props.border), arrays.borderStyle, index))
@@ -546,16 +621,72 @@ export function box(props: BoxProps = {}): Cleanup {
   if (props.borderRight !== undefined) disposals.push(repeat(numInput(props.borderRight), arrays.borderStyleRight, index))
   if (props.borderBottom !== undefined) disposals.push(repeat(numInput(props.borderBottom), arrays.borderStyleBottom, index))
   if (props.borderLeft !== undefined) disposals.push(repeat(numInput(props.borderLeft), arrays.borderStyleLeft, index))
+  if (props.borderInner !== undefined) disposals.push(repeat(numInput(props.borderInner), arrays.borderInnerStyle, index))
+  if (props.borderInnerColor !== undefined) disposals.push(repeat(colorInput(props.borderInnerColor), arrays.borderInnerColor, index))
+  if (props.borderFill !== undefined) disposals.push(repeat(colorInput(props.borderFill), arrays.borderFrameFill, index))
+
+  // Render cache for static subtrees
+  if (props.cache !== undefined) disposals.push(repeat(boolInput(props.cache, 0), arrays.renderCache, index))
+
+  // Hyperlink (OSC 8) over the box and its children
+  bindHref(buf, index, props.href, disposals)
+
+  // Accessibility metadata
+  bindA11y(buf, index, props, disposals)
 
   // --------------------------------------------------------------------------
-  // INTERACTION — focusable, tab index
+  // INTERACTION — focusable, tab index, focus trap, Tab capture
   // --------------------------------------------------------------------------
   // Auto-focusable when overflow is scroll or auto (enables keyboard scrolling)
+  const scrolls = (o: unknown) => o === 'scroll' || o === 'auto'
   const shouldBeFocusable = props.focusable ||
-    ((props.overflow === 'scroll' || props.overflow === 'auto') && props.focusable !== false)
-  if (shouldBeFocusable) {
-    arrays.interactionFlags.set(index, FLAG_FOCUSABLE)
-    if (props.tabIndex !== undefined) disposals.push(repeat(numInput(props.tabIndex, -1), arrays.tabIndex, index))
+    ((scrolls(props.overflow) || scrolls(props.overflowX) || scrolls(props.overflowY)) && props.focusable !== false)
+  const interactionFlags = (shouldBeFocusable ? FLAG_FOCUSABLE : 0) |
+    (props.trapFocus ? FLAG_FOCUS_TRAP : 0) |
+    (props.captureTab ? FLAG_CAPTURE_TAB : 0)
+  bindInteractionFlags(arrays, index, interactionFlags, props.disabled, disposals)
+  if (shouldBeFocusable && props.tabIndex !== undefined) {
+    disposals.push(repeat(numInput(props.tabIndex, -1), arrays.tabIndex, index))
+  }
+
+  // Smooth scrolling: Rust sets targets, the spring in state/smoothScroll moves the offset
+  let unsubSmoothScroll: (() => void) | undefined
+  if (props.smoothScroll) {
+    arrays.smoothScroll.set(index, 1)
+    unsubSmoothScroll = registerSmoothScroll(index, props.smoothScroll === true ? {} : props.smoothScroll)
+  }
+
+  // Following the bottom, anchoring and reporting offsets and the end: Rust
+  // does all of it as it lays out and scrolls
+  let unsubScrollChange: (() => void) | undefined
+  let unsubReachEnd: (() => void) | undefined
+  const { onScrollChange, scrollPosition, onReachEnd } = props
+  if (props.stickToBottom !== undefined || props.scrollAnchor !== undefined || onScrollChange || scrollPosition || onReachEnd) {
+    const stick = boolInput(props.stickToBottom, 0)
+    const anchor = boolInput(props.scrollAnchor, 1)
+    const report = (onScrollChange || scrollPosition ? SCROLL_EVENTS : 0) | (onReachEnd ? SCROLL_REACH_END : 0)
+    const flags = (stickOn: number, anchorOn: number) =>
+      (stickOn ? SCROLL_STICK_BOTTOM : 0) | (anchorOn ? 0 : SCROLL_NO_ANCHOR) | report
+    const read = (input: number | (() => number)) => (typeof input === 'number' ? input : input())
+    disposals.push(repeat(
+      typeof stick === 'number' && typeof anchor === 'number' ? flags(stick, anchor) : () => flags(read(stick), read(anchor)),
+      arrays.scrollFlags,
+      index,
+    ))
+    // Start from the offset the node has now, not whatever the signal held
+    if (scrollPosition) scrollPosition.value = { x: getScrollX(buf, index), y: getScrollY(buf, index) }
+    if (onScrollChange || scrollPosition) {
+      unsubScrollChange = registerScrollChangeHandler(index, (event) => {
+        if (scrollPosition) scrollPosition.value = { x: event.x, y: event.y }
+        onScrollChange?.(event.x, event.y)
+      })
+    }
+    if (onReachEnd) {
+      if (props.reachEndThreshold !== undefined) {
+        disposals.push(repeat(numInput(props.reachEndThreshold), arrays.reachEndThreshold, index))
+      }
+      unsubReachEnd = registerReachEndHandler(index, () => onReachEnd())
+    }
   }
 
   // --------------------------------------------------------------------------
@@ -565,8 +696,9 @@ export function box(props: BoxProps = {}): Cleanup {
   let unsubFocusCallbacks: (() => void) | undefined
 
   // Key handlers: register for ALL components (not just focusable) to support
-  // event bubbling — root boxes can handle global shortcuts like +/-/q
-  if (props.onKey) unsubKeyboard = onFocused(index, props.onKey)
+  // event bubbling — root boxes can handle global shortcuts like +/-/q.
+  // While disabled they pass keys on, as the mouse handlers do
+  if (props.onKey) unsubKeyboard = onFocused(index, unlessDisabled(props.disabled, props.onKey)!)
 
   if (shouldBeFocusable) {
     if (props.onFocus || props.onBlur) {
@@ -581,19 +713,25 @@ export function box(props: BoxProps = {}): Cleanup {
   // MOUSE HANDLERS
   // --------------------------------------------------------------------------
   let unsubMouse: (() => void) | undefined
-  const hasMouseHandlers = props.onMouseDown || props.onMouseUp || props.onClick || props.onMouseEnter || props.onMouseLeave || props.onScroll
+  const hasMouseHandlers = props.onMouseDown || props.onMouseUp || props.onClick || props.onContextMenu || props.onMouseEnter || props.onMouseLeave || props.onScroll
+    || props.onMouseDownCapture || props.onMouseUpCapture || props.onClickCapture || props.onContextMenuCapture
 
   if (shouldBeFocusable || hasMouseHandlers) {
     unsubMouse = onMouseComponent(index, {
-      onMouseDown: props.onMouseDown,
-      onMouseUp: props.onMouseUp,
-      onClick: (event) => {
+      onMouseDown: unlessDisabled(props.disabled, props.onMouseDown),
+      onMouseUp: unlessDisabled(props.disabled, props.onMouseUp),
+      onClick: unlessDisabled(props.disabled, (event) => {
         if (shouldBeFocusable) focusComponent(index)
         return props.onClick?.(event)
-      },
+      }),
+      onContextMenu: unlessDisabled(props.disabled, props.onContextMenu),
       onMouseEnter: props.onMouseEnter,
       onMouseLeave: props.onMouseLeave,
       onScroll: props.onScroll,
+      onMouseDownCapture: unlessDisabled(props.disabled, props.onMouseDownCapture),
+      onMouseUpCapture: unlessDisabled(props.disabled, props.onMouseUpCapture),
+      onClickCapture: unlessDisabled(props.disabled, props.onClickCapture),
+      onContextMenuCapture: unlessDisabled(props.disabled, props.onContextMenuCapture),
     })
   }
 
@@ -616,18 +754,21 @@ export function box(props: BoxProps = {}): Cleanup {
   // --------------------------------------------------------------------------
   // CLEANUP
   // --------------------------------------------------------------------------
-  const cleanup = () => {
+  const cleanup = withTransition(index, props.transition, () => {
     for (const dispose of disposals) dispose()
     disposals.length = 0
     unsubFocusCallbacks?.()
     unsubMouse?.()
     unsubKeyboard?.()
+    unsubSmoothScroll?.()
+    unsubScrollChange?.()
+    unsubReachEnd?.()
     cleanupKeyboardListeners(index)
-    releaseIndex(index)
-  }
+    cleanupInteraction(index)
+  })
 
-  const scope = getActiveScope()
-  if (scope) scope.cleanups.push(cleanup)
+  if (placeCleanup) placeCleanup(cleanup)
+  else getActiveScope()?.cleanups.push(cleanup)
 
   return cleanup
 }
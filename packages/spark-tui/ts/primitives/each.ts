@@ -12,84 +12,132 @@
  *   // getItem() returns current item value (reactive!)
  *   text({ content: () => getItem().name, id: `item-${key}` })
  * }, { key: item => item.id })
+ *
+ * forEach(todos, (todo) => todo.id, (todo, index) =>
+ *   text({ content: () => `${index() + 1}. ${todo().title}` })
+ * )
  * ```
+ *
+ * Keyed reconciliation: on every change the keys are diffed against the
+ * previous list. Kept items keep their component indices (only their item
+ * and index signals update), new keys mount, missing keys unmount.
+ *
+ * Order is the array's, not allocation order: items mount through a slot
+ * (see ./slot) created where the list is declared, so reordering moves
+ * nodes without remounting them and inserted items land in place even on
+ * recycled indices.
  */
 
-import { signal, effect, effectScope, onScopeDispose, type WritableSignal } from '@rlabs-inc/signals'
-import { getCurrentParentIndex, pushParentContext, popParentContext } from '../engine/registry'
+import { signal, effect, effectScope, onScopeDispose, type WritableSignal, type ReadableSignal } from '@rlabs-inc/signals'
+import { getCurrentParentIndex } from '../engine/registry'
+import { createSlot } from './slot'
 import type { Cleanup } from './types'
 
+interface Entry<T> {
+  item: WritableSignal<T>
+  index: WritableSignal<number>
+  cleanup: Cleanup
+  /** Top-level nodes the item rendered */
+  nodes: number[]
+}
+
 /**
- * Render a list of components reactively.
- *
- * Uses fine-grained reactivity: each item is stored in a signal.
- * When items change, signals are updated (not recreated).
- * Components read from signals via getter - truly reactive!
- *
- * @param itemsGetter - Getter that returns the items array
- * @param renderFn - Receives: getItem() for reactive item access, key (stable ID)
- * @param options.key - Function to get unique key for each item
+ * Keyed list core shared by `each` and `forEach`.
  */
-export function each<T>(
+function keyedList<T, K>(
   itemsGetter: () => T[],
-  renderFn: (getItem: () => T, key: string) => Cleanup,
-  options: { key: (item: T) => string }
+  keyOf: (item: T, index: number) => K,
+  render: (getItem: () => T, getIndex: () => number, key: K) => Cleanup,
+  label: string
 ): Cleanup {
-  const cleanups = new Map<string, Cleanup>()
-  const itemSignals = new Map<string, WritableSignal<T>>()
-  const parentIndex = getCurrentParentIndex()
+  let entries = new Map<K, Entry<T>>()
+  const slot = createSlot(getCurrentParentIndex())
   const scope = effectScope()
 
   scope.run(() => {
     effect(() => {
       const items = itemsGetter()
-      const currentKeys = new Set<string>()
+      const next = new Map<K, Entry<T>>()
+      const order: Entry<T>[] = []
 
-      pushParentContext(parentIndex)
-      try {
-        for (let i = 0; i < items.length; i++) {
-          const item = items[i]!
-          const key = options.key(item)
+      for (let i = 0; i < items.length; i++) {
+        const item = items[i]!
+        const key = keyOf(item, i)
 
-          // Warn about duplicate keys in the same render pass
-          if (currentKeys.has(key)) {
-            console.warn(
-              `[TUI each()] Duplicate key detected: "${key}". ` +
-                `Keys must be unique. This may cause unexpected behavior.`
-            )
-          }
-          currentKeys.add(key)
+        if (next.has(key)) {
+          console.warn(
+            `[TUI ${label}()] Duplicate key detected: "${String(key)}". ` +
+              `Keys must be unique. This may cause unexpected behavior.`
+          )
+          continue
+        }
 
-          if (!itemSignals.has(key)) {
-            // NEW item - create signal and component
-            const itemSignal = signal(item)
-            itemSignals.set(key, itemSignal)
-            cleanups.set(key, renderFn(() => itemSignal.value, key))
-          } else {
-            // EXISTING item - just update the signal (fine-grained!)
-            itemSignals.get(key)!.value = item
-          }
+        let entry = entries.get(key)
+        if (entry) {
+          // EXISTING item - just update the signals (fine-grained!)
+          entry.item.value = item
+          entry.index.value = i
+        } else {
+          // NEW item - create signals and component
+          const itemSignal = signal(item)
+          const indexSignal = signal(i)
+          const { cleanup, nodes } = slot.mount(() => render(() => itemSignal.value, () => indexSignal.value, key))
+          entry = { item: itemSignal, index: indexSignal, cleanup, nodes }
         }
-      } finally {
-        popParentContext()
+        next.set(key, entry)
+        order.push(entry)
       }
 
-      // Cleanup removed items
-      for (const [key, cleanup] of cleanups) {
-        if (!currentKeys.has(key)) {
-          cleanup()
-          cleanups.delete(key)
-          itemSignals.delete(key)
-        }
+      // Unmount removed items
+      for (const [key, entry] of entries) {
+        if (!next.has(key)) entry.cleanup()
       }
+      entries = next
+
+      slot.place(order.map((entry) => entry.nodes))
     })
 
     onScopeDispose(() => {
-      for (const cleanup of cleanups.values()) cleanup()
-      cleanups.clear()
-      itemSignals.clear()
+      for (const entry of entries.values()) entry.cleanup()
+      entries.clear()
+      slot.dispose()
     })
   })
 
   return () => scope.stop()
 }
+
+/**
+ * Render a list of components reactively.
+ *
+ * Uses fine-grained reactivity: each item is stored in a signal.
+ * When items change, signals are updated (not recreated).
+ * Components read from signals via getter - truly reactive!
+ *
+ * @param itemsGetter - Getter that returns the items array
+ * @param renderFn - Receives: getItem() for reactive item access, key (stable ID)
+ * @param options.key - Function to get unique key for each item
+ */
+export function each<T>(
+  itemsGetter: () => T[],
+  renderFn: (getItem: () => T, key: string) => Cleanup,
+  options: { key: (item: T) => string }
+): Cleanup {
+  return keyedList(itemsGetter, options.key, (getItem, _getIndex, key) => renderFn(getItem, key), 'each')
+}
+
+/**
+ * Keyed list over a signal (or getter) of items.
+ *
+ * @param items - Signal or getter of the items array
+ * @param key - Unique, stable key per item (any type compared with `===`)
+ * @param render - Receives item() and index() getters (both reactive) and the key
+ */
+export function forEach<T, K>(
+  items: ReadableSignal<T[]> | (() => T[]),
+  key: (item: T, index: number) => K,
+  render: (item: () => T, index: () => number, key: K) => Cleanup
+): Cleanup {
+  const itemsGetter = typeof items === 'function' ? items : () => items.value
+  return keyedList(itemsGetter, key, render, 'forEach')
+}
@@ -0,0 +1,169 @@
+/**
+ * TUI Framework - Error Boundary Primitive
+ *
+ * Contains failures to a subtree. When rendering the children throws, or a
+ * handler of a component inside them throws (key, mouse, focus, value,
+ * scroll), or Rust catches a panic in one of their timers or while one has
+ * focus, the children are unmounted and the fallback takes their place.
+ * The rest of the app keeps running.
+ *
+ * Usage:
+ * ```ts
+ * errorBoundary({
+ *   children: () => Chart(data),
+ *   fallback: (error, reset) => text({ content: `Chart failed: ${error}`, onClick: reset }),
+ *   onError: (error) => log(error),
+ * })
+ * ```
+ *
+ * Without a fallback a themed error box with the message is shown. Errors
+ * from the fallback itself go to the next boundary up. Errors thrown in
+ * prop getters and effects are not caught here — they surface from the
+ * signal that ran them.
+ */
+
+import { effectScope, onScopeDispose, type EffectScope } from '@rlabs-inc/signals'
+import { getCurrentParentIndex } from '../engine/registry'
+import { registerComponentErrorHandler, reportComponentError } from '../engine/events'
+import { getArrays, isInitialized } from '../bridge'
+import { createSlot } from './slot'
+import { box } from './box'
+import { text } from './text'
+import { t } from '../state/theme'
+import { BorderStyle } from '../types'
+import type { Cleanup } from './types'
+
+export interface ErrorBoundaryOptions {
+  children: () => Cleanup
+  /** Rendered instead of the children after an error; `reset` remounts them */
+  fallback?: (error: unknown, reset: () => void) => Cleanup
+  /** Told about every error the boundary takes */
+  onError?: (error: unknown) => void
+}
+
+interface Boundary {
+  fail(error: unknown): void
+}
+
+/** Top-level node → the boundary it was mounted by */
+const boundaryOf = new Map<number, Boundary>()
+
+/** Nearest boundary at or above `index` */
+function findBoundary(index: number): Boundary | undefined {
+  if (!isInitialized()) return undefined
+  const arrays = getArrays()
+  for (let i = index; i >= 0; i = arrays.parentIndex.get(i)) {
+    const boundary = boundaryOf.get(i)
+    if (boundary) return boundary
+  }
+  return undefined
+}
+
+function defaultFallback(error: unknown): Cleanup {
+  const message = error instanceof Error ? error.message : String(error)
+  return box({
+    border: BorderStyle.ROUNDED,
+    borderColor: t.error,
+    paddingLeft: 1,
+    paddingRight: 1,
+    children: () => {
+      text({ content: 'Something went wrong', fg: t.error })
+      text({ content: message, fg: t.textMuted })
+    },
+  })
+}
+
+/**
+ * Render children, replacing them with a fallback when they fail.
+ */
+export function errorBoundary(options: ErrorBoundaryOptions): Cleanup {
+  const parentIndex = getCurrentParentIndex()
+  const slot = createSlot(parentIndex)
+  const scope = effectScope()
+  let content: EffectScope | null = null
+  let cleanup: Cleanup | null = null
+  let nodes: number[] = []
+  let failed = false
+  let disposed = false
+
+  const unmount = () => {
+    for (const node of nodes) boundaryOf.delete(node)
+    nodes = []
+    cleanup?.()
+    cleanup = null
+    content?.stop()
+    content = null
+  }
+
+  const mount = (render: () => Cleanup) => {
+    content = effectScope()
+    content.run(() => {
+      const mounted = slot.mount(render)
+      cleanup = mounted.cleanup
+      nodes = mounted.nodes
+    })
+    slot.place([nodes])
+  }
+
+  const showFallback = (error: unknown) => {
+    unmount()
+    if (disposed) return
+    try {
+      options.onError?.(error)
+    } catch (err) {
+      console.error('[errorBoundary] onError threw:', err)
+    }
+    try {
+      mount(() => (options.fallback ?? defaultFallback)(error, reset))
+    } catch (err) {
+      unmount()
+      reportComponentError(parentIndex, err)
+    }
+  }
+
+  const showChildren = () => {
+    failed = false
+    try {
+      mount(options.children)
+      for (const node of nodes) boundaryOf.set(node, boundary)
+    } catch (error) {
+      failed = true
+      showFallback(error)
+    }
+  }
+
+  const reset = () => {
+    if (!failed || disposed) return
+    unmount()
+    showChildren()
+  }
+
+  const boundary: Boundary = {
+    fail(error) {
+      if (failed || disposed) return
+      failed = true
+      // Not while the failing handler's dispatch is still walking its nodes
+      queueMicrotask(() => showFallback(error))
+    },
+  }
+
+  // Takes errors whose nearest boundary is this one
+  const unregister = registerComponentErrorHandler((index, error) => {
+    if (findBoundary(index) !== boundary) return false
+    boundary.fail(error)
+    return true
+  })
+
+  scope.run(() => {
+    showChildren()
+
+    onScopeDispose(() => {
+      disposed = true
+      unregister()
+      unmount()
+      slot.dispose()
+    })
+  })
+
+  return () => scope.stop()
+}
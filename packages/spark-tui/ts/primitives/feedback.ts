@@ -0,0 +1,163 @@
+/**
+ * TUI Framework - Visual Feedback
+ *
+ * Bell-free ways to say "no": jolt a component sideways or flash its
+ * border, e.g. when a form is submitted with invalid fields.
+ *
+ * ```ts
+ * onSubmit: () => {
+ *   if (!valid.value) {
+ *     shake(formIndex)
+ *     flashBorder(emailIndex, t.error)
+ *   }
+ * }
+ * ```
+ *
+ * Both write straight to the node's slots (insetLeft, borderColor) from the
+ * shared animation clock, like transitions, and put the resting value back
+ * when done. Playing one again on the same component restarts it from the
+ * resting value. A component destroyed mid-way is left alone.
+ *
+ * Under reduced motion (setReducedMotion) both are skipped.
+ */
+
+import { getArrays, getBuffer, isInitialized } from '../bridge'
+import { markDirty, packColor, DIRTY_LAYOUT } from '../bridge/shared-buffer'
+import { getId } from '../engine/registry'
+import { parseColor } from '../types/color'
+import type { ColorInput } from '../types'
+import { t } from '../state/theme'
+import { animateValue, getReducedMotion, type Tween } from './animation'
+
+export interface ShakeOptions {
+  /** Total duration in ms (default: 300) */
+  duration?: number
+  /** Furthest offset in cells (default: 2) */
+  distance?: number
+  /** Back-and-forth swings (default: 3) */
+  swings?: number
+}
+
+export interface FlashBorderOptions {
+  /** Total duration in ms (default: 600) */
+  duration?: number
+  /** Times the color comes on (default: 3) */
+  flashes?: number
+}
+
+const DEFAULT_SHAKE_DURATION = 300
+const DEFAULT_SHAKE_DISTANCE = 2
+const DEFAULT_SHAKE_SWINGS = 3
+const DEFAULT_FLASH_DURATION = 600
+const DEFAULT_FLASH_COUNT = 3
+
+/** Feedback playing on a component, with the slot value to return to */
+interface Playing {
+  tween: Tween
+  resting: number
+}
+
+const shaking = new Map<number, Playing>()
+const flashing = new Map<number, Playing>()
+
+/** A tween that has already ended without running */
+function skipped(): Tween {
+  return { cancel: () => {}, finished: Promise.resolve(false) }
+}
+
+/**
+ * Drive `write` from 0 to 1 on `index`, restoring the slot afterwards.
+ * Stops quietly if the index is released or recycled along the way.
+ */
+function play(
+  playing: Map<number, Playing>,
+  index: number,
+  duration: number,
+  read: () => number,
+  write: (value: number) => void,
+  frame: (resting: number, progress: number) => number
+): Tween {
+  const previous = playing.get(index)
+  previous?.tween.cancel()
+  const resting = previous?.resting ?? read()
+  const id = getId(index)
+
+  let tween: Tween | undefined
+  tween = animateValue(0, 1, { duration, easing: 'linear' }, (progress) => {
+    if (getId(index) !== id) {
+      tween?.cancel()
+      return
+    }
+    write(progress === 1 ? resting : frame(resting, progress))
+  })
+  playing.set(index, { tween, resting })
+  tween.finished.then(() => {
+    if (playing.get(index)?.tween !== tween) return
+    playing.delete(index)
+    if (getId(index) === id) write(resting)
+  })
+  return tween
+}
+
+/**
+ * Jolt a component left and right, settling back where it was.
+ * Moves it through `insetLeft`, so siblings don't reflow.
+ */
+export function shake(index: number, options: ShakeOptions = {}): Tween {
+  if (getReducedMotion() || !isInitialized() || getId(index) === undefined) return skipped()
+  const {
+    duration = DEFAULT_SHAKE_DURATION,
+    distance = DEFAULT_SHAKE_DISTANCE,
+    swings = DEFAULT_SHAKE_SWINGS,
+  } = options
+  const arrays = getArrays()
+
+  return play(
+    shaking,
+    index,
+    duration,
+    () => arrays.insetLeft.get(index),
+    (value) => {
+      markDirty(getBuffer(), index, DIRTY_LAYOUT)
+      arrays.insetLeft.set(index, value)
+    },
+    // Decaying swing: full distance first, back to rest at the end. An
+    // unset inset (NaN) swings about 0 and is unset again after
+    (resting, p) => (Number.isNaN(resting) ? 0 : resting) + Math.round(distance * (1 - p) * Math.sin(p * swings * 2 * Math.PI))
+  )
+}
+
+/**
+ * Blink a component's border in `color` (default: theme error), then put
+ * its own color back. Needs a visible border to show anything.
+ */
+export function flashBorder(
+  index: number,
+  color: ColorInput | { readonly value: ColorInput } = t.error,
+  options: FlashBorderOptions = {}
+): Tween {
+  if (getReducedMotion() || !isInitialized() || getId(index) === undefined) return skipped()
+  const { duration = DEFAULT_FLASH_DURATION, flashes = DEFAULT_FLASH_COUNT } = options
+  const arrays = getArrays()
+  const packed = toPackedColor(color !== null && typeof color === 'object' && 'value' in color ? color.value : color)
+
+  return play(
+    flashing,
+    index,
+    duration,
+    () => arrays.borderColor.get(index),
+    (value) => arrays.borderColor.set(index, value),
+    // On for the first half of each flash
+    (resting, p) => (Math.floor(p * flashes * 2) % 2 === 0 ? packed : resting)
+  )
+}
+
+function toPackedColor(c: ColorInput): number {
+  if (c === null) return 0
+  if (typeof c === 'number') return c
+  if (typeof c === 'string') {
+    const parsed = parseColor(c)
+    return packColor(parsed.r, parsed.g, parsed.b, parsed.a)
+  }
+  return packColor(c.r, c.g, c.b, c.a ?? 255)
+}
@@ -0,0 +1,162 @@
+/**
+ * TUI Framework - Grid span and named-area placement
+ *
+ * Sugar over the four grid line slots (gridColumnStart/End, gridRowStart/End):
+ *
+ *   colSpan / rowSpan  → end line = span n
+ *   gridTemplateAreas  → named rectangles on the container
+ *   gridArea           → an item takes its parent's named rectangle
+ *
+ * Explicit gridColumnStart/End and gridRowStart/End always win. Placement is
+ * validated against the parent's track counts in Rust, so a span or area that
+ * overruns the template is clamped instead of spilling into implicit tracks.
+ */
+
+import { derived, repeat, signal, type WritableSignal } from '@rlabs-inc/signals'
+import type { ReactiveArrays } from '../bridge/reactive-arrays'
+import type { GridItemProps, GridTemplateAreas, Reactive } from './types'
+import { unwrap } from './utils'
+
+/** A named area as grid lines (1-based, end exclusive). */
+export interface GridArea {
+  colStart: number
+  colEnd: number
+  rowStart: number
+  rowEnd: number
+}
+
+type GridAreaMap = Record<string, GridArea>
+
+const NO_AREAS = (): GridAreaMap => ({})
+
+/**
+ * Named areas per grid container index. Created on first use from either
+ * side, so items may bind before their container publishes.
+ */
+const areasByContainer = new Map<number, WritableSignal<() => GridAreaMap>>()
+
+function areasFor(index: number): WritableSignal<() => GridAreaMap> {
+  let areas = areasByContainer.get(index)
+  if (!areas) {
+    areas = signal<() => GridAreaMap>(NO_AREAS)
+    areasByContainer.set(index, areas)
+  }
+  return areas
+}
+
+function isReactive(prop: unknown): boolean {
+  return typeof prop === 'function' || (prop !== null && typeof prop === 'object' && 'value' in (prop as any))
+}
+
+// =============================================================================
+// PARSING
+// =============================================================================
+
+/**
+ * Parse `grid-template-areas` into named rectangles.
+ *
+ * Accepts CSS syntax (`'"head head" "side main"'`) or one string per row
+ * (`['head head', 'side main']`). `.` marks an empty cell. Ragged rows and
+ * names that don't form a single rectangle are reported and skipped.
+ */
+export function parseGridTemplateAreas(template: GridTemplateAreas): GridAreaMap {
+  const rows = (Array.isArray(template) ? template : splitQuotedRows(template))
+    .map((row) => row.trim().split(/\s+/).filter(Boolean))
+    .filter((row) => row.length > 0)
+
+  const columns = rows[0]?.length ?? 0
+  if (rows.some((row) => row.length !== columns)) {
+    console.warn(`gridTemplateAreas: every row needs ${columns} cells`, template)
+    return {}
+  }
+
+  const areas: GridAreaMap = {}
+  const cells = new Map<string, number>()
+  rows.forEach((row, r) => row.forEach((name, c) => {
+    if (name === '.') return
+    cells.set(name, (cells.get(name) ?? 0) + 1)
+    const area = areas[name]
+    if (!area) {
+      areas[name] = { colStart: c + 1, colEnd: c + 2, rowStart: r + 1, rowEnd: r + 2 }
+      return
+    }
+    area.colStart = Math.min(area.colStart, c + 1)
+    area.colEnd = Math.max(area.colEnd, c + 2)
+    area.rowStart = Math.min(area.rowStart, r + 1)
+    area.rowEnd = Math.max(area.rowEnd, r + 2)
+  }))
+
+  // A rectangle covers exactly as many cells as its bounding box
+  for (const [name, area] of Object.entries(areas)) {
+    const box = (area.colEnd - area.colStart) * (area.rowEnd - area.rowStart)
+    if (cells.get(name) !== box) {
+      console.warn(`gridTemplateAreas: area '${name}' is not a rectangle`)
+      delete areas[name]
+    }
+  }
+  return areas
+}
+
+function splitQuotedRows(template: string): string[] {
+  const quoted = template.match(/"[^"]*"|'[^']*'/g)
+  if (quoted) return quoted.map((row) => row.slice(1, -1))
+  return template.split('\n')
+}
+
+// =============================================================================
+// BINDING
+// =============================================================================
+
+/**
+ * Publish a container's named areas for its children.
+ * Returns the cleanup that forgets them.
+ */
+export function bindGridTemplateAreas(index: number, template: Reactive<GridTemplateAreas>): () => void {
+  if (isReactive(template)) {
+    const parsed = derived(() => parseGridTemplateAreas(unwrap(template)))
+    areasFor(index).value = () => parsed.value
+  } else {
+    const parsed = parseGridTemplateAreas(template as GridTemplateAreas)
+    areasFor(index).value = () => parsed
+  }
+  return () => {
+    areasByContainer.delete(index)
+  }
+}
+
+/**
+ * Bind colSpan, rowSpan and gridArea for a grid item.
+ * Line props the caller already bound explicitly are left alone.
+ */
+export function bindGridPlacement(
+  props: GridItemProps,
+  arrays: ReactiveArrays,
+  index: number,
+  parentIndex: number,
+  disposals: (() => void)[],
+): void {
+  const { gridArea, colSpan, rowSpan } = props
+
+  if (gridArea !== undefined) {
+    const areas = areasFor(parentIndex)
+    // Unknown name → auto placement
+    const line = (pick: (area: GridArea) => number) => () => {
+      const area = areas.value()[unwrap(gridArea)]
+      return area ? pick(area) : 0
+    }
+    if (props.gridColumnStart === undefined) disposals.push(repeat(line((a) => a.colStart), arrays.gridColumnStart, index))
+    if (props.gridColumnEnd === undefined) disposals.push(repeat(line((a) => a.colEnd), arrays.gridColumnEnd, index))
+    if (props.gridRowStart === undefined) disposals.push(repeat(line((a) => a.rowStart), arrays.gridRowStart, index))
+    if (props.gridRowEnd === undefined) disposals.push(repeat(line((a) => a.rowEnd), arrays.gridRowEnd, index))
+    return
+  }
+
+  // Negative end = span
+  const span = (n: Reactive<number>) => () => -Math.max(1, Math.floor(unwrap(n)))
+  if (colSpan !== undefined && props.gridColumnEnd === undefined) {
+    disposals.push(repeat(span(colSpan), arrays.gridColumnEnd, index))
+  }
+  if (rowSpan !== undefined && props.gridRowEnd === undefined) {
+    disposals.push(repeat(span(rowSpan), arrays.gridRowEnd, index))
+  }
+}
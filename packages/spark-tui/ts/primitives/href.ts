@@ -0,0 +1,33 @@
+/**
+ * TUI Framework - Hyperlinks
+ *
+ * `href` on a text or box links every cell the node and its descendants
+ * paint to a URL. Rust wraps those cells in OSC 8 sequences, so terminals
+ * that support it (kitty, WezTerm, iTerm2, GNOME Terminal, Windows Terminal…)
+ * make them clickable; others show the text as usual.
+ *
+ *   text({ content: 'docs', href: 'https://example.com/docs', underline: true })
+ *   text({ content: path, href: () => `file://${hostname}${path.value}` })
+ */
+
+import { effect } from '@rlabs-inc/signals'
+import { setHref } from '../bridge/shared-buffer'
+import type { SharedBuffer } from '../bridge/shared-buffer'
+import type { Reactive } from './types'
+import { unwrap } from './utils'
+
+/** Keep node `index`'s link target in sync with `href` ('' = no link). */
+export function bindHref(
+  buf: SharedBuffer,
+  index: number,
+  href: Reactive<string> | undefined,
+  disposals: (() => void)[],
+): void {
+  if (href === undefined) return
+  disposals.push(effect(() => {
+    const url = unwrap(href)
+    if (!setHref(buf, index, url)) {
+      throw new Error(`Text pool full: cannot store href for node ${index}. Increase textPoolSize in mount() config.`)
+    }
+  }))
+}
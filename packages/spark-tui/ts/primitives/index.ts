@@ -8,13 +8,24 @@
 export { box } from './box'
 export { text } from './text'
 export { input } from './input'
-export { each } from './each'
+export { each, forEach } from './each'
 export { show } from './show'
+export { match } from './match'
 export { when } from './when'
+export { suspense, resource } from './suspense'
+export { errorBoundary } from './errorBoundary'
 export { scoped, onCleanup, componentScope, cleanupCollector } from './scope'
-export { cycle, pulse, Frames } from './animation'
+export { cycle, pulse, Frames, tween, Easing, spring, driveSpring, stepSpring, springConfig, setReducedMotion, getReducedMotion } from './animation'
+export { shake, flashBorder } from './feedback'
+export { mergeStyles, themed } from './style'
+export { numberValue, dateValue, patternValue, parseNumber, parseDate, parsePattern } from './masked'
 
 // Types
-export type { BoxProps, TextProps, InputProps, CursorConfig, CursorStyle, BlinkConfig, Cleanup, MouseProps } from './types'
+export type { BoxProps, TextProps, InputProps, Style, StyleProp, PaddingShorthand, CursorConfig, CursorStyle, BlinkConfig, Cleanup, MouseProps, AccessibilityProps, BackgroundFillProp, TransitionProps, TransitionSpec, TransitionEffect, ScrollPosition } from './types'
 export type { ComponentScopeResult } from './scope'
-export type { AnimationOptions, CycleOptions, PulseOptions } from './animation'
+export type { MatchArms } from './match'
+export type { ErrorBoundaryOptions } from './errorBoundary'
+export type { Resource, ResourceState, ResourceContext } from './suspense'
+export type { ShakeOptions, FlashBorderOptions } from './feedback'
+export type { NumberRange } from './masked'
+export type { AnimationOptions, CycleOptions, PulseOptions, TweenOptions, Tween, EasingName, EasingFn, SpringConfig, SpringState, SpringDriver, Spring } from './animation'
@@ -9,9 +9,13 @@
  * - Cursor navigation (arrows, home, end)
  * - Text editing (backspace, delete)
  * - Password mode
+ * - Number, date and pattern masks (see ./masked)
  * - Placeholder text
  * - Theme variants
  * - Cursor configuration (style, blink, color)
+ * - Inline completion (ghost text)
+ * - History: Up/Down recall, Ctrl+R search (see ../state/history)
+ * - IME composition forwarded by embedders (see ../state/ime)
  *
  * Usage:
  * ```ts
@@ -24,13 +28,13 @@
  * ```
  */
 
-import { signal, repeat } from '@rlabs-inc/signals'
+import { signal, effect, repeat } from '@rlabs-inc/signals'
+import { bindPercentBases, pointerEventsToNum, toDim, unwrap } from './utils'
 import { ComponentType } from '../types'
 import type { RGBA, ColorInput } from '../types'
 import { parseColor } from '../types/color'
 import {
   allocateIndex,
-  releaseIndex,
   getCurrentParentIndex,
   registerParent,
 } from '../engine/registry'
@@ -39,26 +43,49 @@ import {
   popCurrentComponent,
   runMountCallbacks,
 } from '../engine/lifecycle'
-import { cleanupIndex as cleanupKeyboardListeners, onFocused } from '../state/keyboard'
+import { cleanupIndex as cleanupKeyboardListeners, onFocused, matchesKey, isRelease } from '../state/keyboard'
 import type { KeyEvent } from '../state/keyboard'
-import { hasCtrl, hasAlt, hasMeta } from '../engine/events'
+import { EventType, hasCtrl, hasAlt, hasMeta, registerValueHandler } from '../engine/events'
 import { onComponent as onMouseComponent } from '../state/mouse'
-import { getVariantStyle, t } from '../state/theme'
+import { t } from '../state/theme'
 import { focus as focusComponent, registerFocusCallbacks } from '../state/focus'
+import { registerComposer, composition, commitPreedit } from '../state/ime'
 import { getActiveScope } from './scope'
+import { withTransition } from './transition'
+import { bindGridPlacement } from './gridPlacement'
+import { bindA11y } from './a11y'
+import { applyStyle } from './style'
+import { bindInteractionFlags, unlessDisabled, variantStyleFor } from './interactive'
 import { pulse } from './animation'
+import { clampNumber, inputMode, parseNumber } from './masked'
 import { getArrays, getBuffer } from '../bridge'
 import {
   packColor,
   setText,
+  setGhostText,
+  clearGhostText,
+  getGhostText,
   setU8,
   setU32,
+  getI32,
+  getText,
+  setInputMask,
+  setPreedit,
+  getConfigFlags,
   FLAG_FOCUSABLE,
+  CONFIG_NATIVE_CURSOR,
   N_CURSOR_FLAGS,
   N_CURSOR_STYLE,
   N_CURSOR_BLINK_RATE,
   N_CURSOR_CHAR,
   N_MAX_LENGTH,
+  N_INPUT_TYPE,
+  N_INPUT_FLAGS,
+  N_CURSOR_POSITION,
+  INPUT_PLACEHOLDER,
+  INPUT_SCRIPTED,
+  InputType,
+  N_WORD_BOUNDARY,
   N_CURSOR_FG_COLOR,
   N_CURSOR_BG_COLOR,
   type SharedBuffer,
@@ -69,22 +96,6 @@ import type { InputProps, Cleanup, BlinkConfig, GridLine } from './types'
 // CONVERSION HELPERS
 // =============================================================================
 
-/** Dimension → Taffy float: NaN = auto, negative = percentage, positive = pixels */
-function toDim(dim: number | string | undefined | null): number {
-  if (dim === undefined || dim === null || dim === 0) return NaN
-  if (typeof dim === 'string') {
-    if (dim.endsWith('%')) return -parseFloat(dim) // '100%' → -100.0
-    return parseFloat(dim) || NaN
-  }
-  return dim
-}
-
-function unwrap<T>(prop: T | (() => T) | { readonly value: T }): T {
-  if (typeof prop === 'function') return (prop as () => T)()
-  if (prop !== null && typeof prop === 'object' && 'value' in prop) return (prop as { value: T }).value
-  return prop
-}
-
 function isReactive(prop: unknown): boolean {
   return typeof prop === 'function' || (prop !== null && typeof prop === 'object' && 'value' in (prop as any))
 }
@@ -164,6 +175,15 @@ function cursorStyleToNum(style: string | undefined): number {
   }
 }
 
+function wordBoundaryToNum(rule: string | undefined): number {
+  switch (rule) {
+    case 'alphanumeric': return 1
+    case 'identifier': return 2
+    case 'whitespace': return 3
+    default: return 0 // unicode
+  }
+}
+
 function justifySelfToNum(j: string | undefined): number {
   switch (j) {
     case 'start': return 1
@@ -221,6 +241,47 @@ function getSpecialKeyName(keycode: number): string | null {
 // TEXT POOL WRITER
 // =============================================================================
 
+// =============================================================================
+// GRAPHEME CLUSTERS — positions are chars (code points), as Rust counts them;
+// the cursor moves and deletes by cluster, and maxLength counts clusters
+// =============================================================================
+
+const graphemeSegmenter = new Intl.Segmenter(undefined, { granularity: 'grapheme' })
+
+/** Grapheme clusters of `text` */
+function graphemes(text: string): string[] {
+  return Array.from(graphemeSegmenter.segment(text), (s) => s.segment)
+}
+
+/** Char index of every cluster boundary in `text`, 0 through its char count */
+function graphemeBoundaries(text: string): number[] {
+  const bounds = [0]
+  let pos = 0
+  for (const cluster of graphemes(text)) {
+    pos += [...cluster].length
+    bounds.push(pos)
+  }
+  return bounds
+}
+
+function prevBoundary(text: string, pos: number): number {
+  return graphemeBoundaries(text).findLast((b) => b < pos) ?? 0
+}
+
+function nextBoundary(text: string, pos: number): number {
+  const bounds = graphemeBoundaries(text)
+  return bounds.find((b) => b > pos) ?? bounds[bounds.length - 1]!
+}
+
+function charCount(text: string): number {
+  return [...text].length
+}
+
+/** Chars `[start, end)` of `text` */
+function sliceChars(text: string, start: number, end?: number): string {
+  return [...text].slice(start, end).join('')
+}
+
 function writeTextToPool(buf: SharedBuffer, index: number, text: string): void {
   const result = setText(buf, index, text)
   if (!result.success) {
@@ -240,6 +301,7 @@ function writeTextToPool(buf: SharedBuffer, index: number, text: string): void {
 // =============================================================================
 
 export function input(props: InputProps): Cleanup {
+  props = applyStyle(props)
   const buf = getBuffer()
   const arrays = getArrays()
   const index = allocateIndex(props.id)
@@ -285,6 +347,9 @@ export function input(props: InputProps): Cleanup {
   if (props.maxWidth !== undefined) disposals.push(repeat(dimInput(props.maxWidth), arrays.maxWidth, index))
   if (props.minHeight !== undefined) disposals.push(repeat(dimInput(props.minHeight), arrays.minHeight, index))
   if (props.maxHeight !== undefined) disposals.push(repeat(dimInput(props.maxHeight), arrays.maxHeight, index))
+  disposals.push(...bindPercentBases(props, arrays, index))
+  if (props.aspectRatio !== undefined) disposals.push(repeat(numInput(props.aspectRatio, NaN), arrays.aspectRatio, index))
+  if (props.aspectCells !== undefined) disposals.push(repeat(boolInput(props.aspectCells, 0), arrays.aspectCells, index))
 
   // Flex item
   if (props.grow !== undefined) disposals.push(repeat(numInput(props.grow), arrays.flexGrow, index))
@@ -328,6 +393,8 @@ export function input(props: InputProps): Cleanup {
 
   // Z-index
   if (props.zIndex !== undefined) disposals.push(repeat(numInput(props.zIndex), arrays.zIndex, index))
+  if (props.pointerEvents !== undefined) disposals.push(repeat(enumInput(props.pointerEvents, pointerEventsToNum), arrays.pointerEvents, index))
+  if (props.hoverTag !== undefined) disposals.push(repeat(numInput(props.hoverTag), arrays.hoverTag, index))
 
   // Border widths (layout spacing: 0 or 1)
   if (props.border !== undefined) {
@@ -388,6 +455,7 @@ export function input(props: InputProps): Cleanup {
   if (props.justifySelf !== undefined) {
     disposals.push(repeat(enumInput(props.justifySelf, justifySelfToNum), arrays.justifySelf, index))
   }
+  bindGridPlacement(props, arrays, index, parentIdx, disposals)
 
   // ==========================================================================
   // TEXT CONTENT - Display via text pool
@@ -398,7 +466,7 @@ export function input(props: InputProps): Cleanup {
     if (val.length === 0 && props.placeholder) {
       return props.placeholder
     }
-    return props.password ? maskChar.repeat(val.length) : val
+    return props.password ? maskChar.repeat(graphemes(val).length) : val
   }
 
   // Text content is reactive since getValue() reads from a signal
@@ -408,6 +476,41 @@ export function input(props: InputProps): Cleanup {
     index
   ))
 
+  // ==========================================================================
+  // INPUT TYPE — masks Rust enforces per keystroke
+  // ==========================================================================
+
+  const mode = inputMode(props)
+  setU8(buf, index, N_INPUT_TYPE, mode.type)
+  if (mode.mask && !setInputMask(buf, index, mode.mask)) {
+    throw new Error(`Text pool full: cannot store mask for node ${index}. Increase textPoolSize in mount() config.`)
+  }
+
+  // Passwords and `accept` patterns are edited here (the text slot holds
+  // the bullets, and RegExps don't cross the buffer); Rust sends their keys on
+  const scripted = props.password || props.accept !== undefined
+  const inputFlags = mode.flags | (scripted ? INPUT_SCRIPTED : 0)
+  disposals.push(effect(() => {
+    const placeholder = getValue().length === 0 && !!props.placeholder
+    setU8(buf, index, N_INPUT_FLAGS, inputFlags | (placeholder ? INPUT_PLACEHOLDER : 0))
+  }))
+
+  /** Number inputs settle into min/max when left */
+  const clampOnBlur = () => {
+    if (props.type !== 'number') return
+    const val = getValue()
+    const parsed = parseNumber(val)
+    if (parsed === null) return
+    const clamped = clampNumber(parsed, props)
+    if (clamped !== parsed) {
+      setValue(String(clamped))
+      props.onChange?.(String(clamped))
+    }
+  }
+
+  // Accessibility: a text box by default; a password is never read out
+  bindA11y(buf, index, { ...props, role: props.role ?? 'text box' }, disposals, props.password ? undefined : getValue)
+
   // ==========================================================================
   // CURSOR CONFIGURATION
   // ==========================================================================
@@ -426,8 +529,14 @@ export function input(props: InputProps): Cleanup {
     blinkFps = blinkConfig.fps ?? 2
   }
 
+  // The terminal blinks a native cursor itself (mount's nativeCursor)
+  const nativeCursor = (getConfigFlags(buf) & CONFIG_NATIVE_CURSOR) !== 0
+
   // Cursor visibility (blink effect via pulse signal)
-  if (blinkEnabled) {
+  if (blinkEnabled && nativeCursor) {
+    setU8(buf, index, N_CURSOR_FLAGS, 1)
+    setU8(buf, index, N_CURSOR_BLINK_RATE, blinkFps)
+  } else if (blinkEnabled) {
     const blinkSignal = pulse({ fps: blinkFps })
     disposals.push(repeat(() => blinkSignal.value ? 1 : 0, arrays.cursorPosition, index))
     // Set cursor flags to indicate cursor should blink
@@ -464,9 +573,14 @@ export function input(props: InputProps): Cleanup {
     }
   }
 
-  // Sync cursor position (clamped to value length)
+  // Sync cursor position (clamped to value length). A password shows one
+  // mask char per cluster, so its cursor counts clusters.
   disposals.push(repeat(
-    () => Math.min(cursorPos.value, getValue().length),
+    () => {
+      const val = getValue()
+      const pos = Math.min(cursorPos.value, charCount(val))
+      return props.password ? graphemeBoundaries(val).filter((b) => b > 0 && b <= pos).length : pos
+    },
     arrays.cursorPosition,
     index
   ))
@@ -476,25 +590,61 @@ export function input(props: InputProps): Cleanup {
     setU8(buf, index, N_MAX_LENGTH, props.maxLength)
   }
 
+  // Word boundary rule (Ctrl+arrows, word delete, double-click select)
+  if (props.wordBoundary !== undefined) {
+    setU8(buf, index, N_WORD_BOUNDARY, wordBoundaryToNum(props.wordBoundary))
+  }
+
+  // ==========================================================================
+  // INLINE COMPLETION — ghost text
+  // ==========================================================================
+
+  // Provider re-runs on every value change; Rust draws the suggestion dimmed
+  // past the cursor and appends it on Right/Tab.
+  if (props.completion) {
+    const completion = props.completion
+    disposals.push(effect(() => {
+      const val = getValue()
+      const suggestion = props.password ? null : completion(val)
+      if (suggestion) setGhostText(buf, index, suggestion)
+      else clearGhostText(buf, index)
+    }))
+  }
+
+  /** Accept the ghost text when the cursor sits at the end of the value. */
+  const acceptGhost = (val: string, pos: number): boolean => {
+    if (!props.completion || pos !== charCount(val)) return false
+    const maxLen = props.maxLength ?? 0
+    let ghost = getGhostText(buf, index)
+    if (maxLen > 0) ghost = graphemes(ghost).slice(0, Math.max(0, maxLen - graphemes(val).length)).join('')
+    if (!ghost) return false
+    const newVal = val + ghost
+    clearGhostText(buf, index)
+    setValue(newVal)
+    cursorPos.value = charCount(newVal)
+    props.onChange?.(newVal)
+    return true
+  }
+
   // ==========================================================================
   // VISUAL — colors with variant support
   // ==========================================================================
 
   if (props.variant && props.variant !== 'default') {
-    const variant = props.variant
-    // Variant-based colors with user overrides
+    // Variant-based colors, following hover/focus/press/disabled, with user overrides
+    const style = variantStyleFor(index, props.variant, props.disabled, true)
     disposals.push(repeat(
-      props.fg !== undefined ? colorInput(props.fg) : () => toPackedColor(getVariantStyle(variant).fg),
+      props.fg !== undefined ? colorInput(props.fg) : () => toPackedColor(style.value.fg),
       arrays.fgColor, index
     ))
     disposals.push(repeat(
-      props.bg !== undefined ? colorInput(props.bg) : () => toPackedColor(getVariantStyle(variant).bg),
+      props.bg !== undefined ? colorInput(props.bg) : () => toPackedColor(style.value.bg),
       arrays.bgColor, index
     ))
     if (props.borderColor !== undefined) {
       disposals.push(repeat(colorInput(props.borderColor), arrays.borderColor, index))
     } else {
-      disposals.push(repeat(() => toPackedColor(getVariantStyle(variant).border), arrays.borderColor, index))
+      disposals.push(repeat(() => toPackedColor(style.value.border), arrays.borderColor, index))
     }
   } else {
     // Default styling - use colorInput for theme colors to handle derived signals properly
@@ -513,12 +663,15 @@ export function input(props: InputProps): Cleanup {
   if (props.borderRight !== undefined) disposals.push(repeat(numInput(props.borderRight), arrays.borderStyleRight, index))
   if (props.borderBottom !== undefined) disposals.push(repeat(numInput(props.borderBottom), arrays.borderStyleBottom, index))
   if (props.borderLeft !== undefined) disposals.push(repeat(numInput(props.borderLeft), arrays.borderStyleLeft, index))
+  if (props.borderInner !== undefined) disposals.push(repeat(numInput(props.borderInner), arrays.borderInnerStyle, index))
+  if (props.borderInnerColor !== undefined) disposals.push(repeat(colorInput(props.borderInnerColor), arrays.borderInnerColor, index))
+  if (props.borderFill !== undefined) disposals.push(repeat(colorInput(props.borderFill), arrays.borderFrameFill, index))
 
   // ==========================================================================
   // INTERACTION — inputs are always focusable
   // ==========================================================================
 
-  arrays.interactionFlags.set(index, FLAG_FOCUSABLE)
+  bindInteractionFlags(arrays, index, FLAG_FOCUSABLE, props.disabled, disposals)
   if (props.tabIndex !== undefined) {
     disposals.push(repeat(numInput(props.tabIndex, -1), arrays.tabIndex, index))
   }
@@ -527,9 +680,90 @@ export function input(props: InputProps): Cleanup {
   // KEYBOARD HANDLERS
   // ==========================================================================
 
+  // ==========================================================================
+  // HISTORY
+  // ==========================================================================
+
+  const history = props.history
+
+  /** Show a recalled entry, cursor at its end */
+  const recall = (entry: string) => {
+    setValue(entry)
+    cursorPos.value = charCount(entry)
+    props.onChange?.(entry)
+  }
+
+  /** Enter: takes the search match while searching, otherwise records and submits */
+  const submit = (val: string) => {
+    if (history?.search.value) {
+      recall(history.acceptSearch())
+      return
+    }
+    history?.add(val)
+    props.onSubmit?.(val)
+  }
+
+  /** Escape: abandons the search while searching, otherwise cancels */
+  const cancel = () => {
+    if (history?.search.value) {
+      recall(history.cancelSearch())
+      return
+    }
+    props.onCancel?.()
+  }
+
+  /** The user changed the text: it is the new search query, or navigation starts over */
+  const edited = (val: string) => {
+    if (!history) return
+    if (history.search.value) history.setQuery(val)
+    else history.reset()
+  }
+
+  // ==========================================================================
+  // COMPOSITION — IME preedit/commit forwarded by an embedder
+  // ==========================================================================
+
+  /** Insert committed text at the cursor, as far as maxLength and `accept` allow */
+  const insertText = (inserted: string) => {
+    const val = getValue()
+    const pos = Math.min(cursorPos.value, charCount(val))
+    const maxLen = props.maxLength ?? 0
+    if (maxLen > 0) inserted = graphemes(inserted).slice(0, Math.max(0, maxLen - graphemes(val).length)).join('')
+    if (!inserted) return
+    const newVal = sliceChars(val, 0, pos) + inserted + sliceChars(val, pos)
+    if (props.accept && !props.accept.test(newVal)) return
+    setValue(newVal)
+    cursorPos.value = pos + charCount(inserted)
+    props.onChange?.(newVal)
+    edited(newVal)
+  }
+
+  // Masked inputs only take their own characters; a password's preedit would show
+  const unsubComposer = mode.type === InputType.Text
+    ? registerComposer(index, {
+        preedit: (text) => setPreedit(buf, index, text),
+        commit: insertText,
+      })
+    : () => {}
+
+  const handleHistoryKey = (event: KeyEvent): boolean => {
+    if (!history || isRelease(event)) return false
+    if (matchesKey(event, 'Ctrl+R')) {
+      history.searchOlder(getValue())
+      return true
+    }
+    const up = matchesKey(event, 'ArrowUp')
+    if (!up && !matchesKey(event, 'ArrowDown')) return false
+    if (history.search.value) recall(history.acceptSearch())
+    const entry = up ? history.previous(getValue()) : history.next()
+    if (entry !== null) recall(entry)
+    return true
+  }
+
   const handleKeyEvent = (event: KeyEvent): boolean => {
     const val = getValue()
-    const pos = Math.min(cursorPos.value, val.length)
+    const len = charCount(val)
+    const pos = Math.min(cursorPos.value, len)
     const maxLen = props.maxLength ?? 0
 
     // Get key name or character
@@ -539,11 +773,12 @@ export function input(props: InputProps): Cleanup {
     if (specialKey) {
       switch (specialKey) {
         case 'ArrowLeft':
-          if (pos > 0) cursorPos.value = pos - 1
+          cursorPos.value = prevBoundary(val, pos)
           return true
 
         case 'ArrowRight':
-          if (pos < val.length) cursorPos.value = pos + 1
+          if (acceptGhost(val, pos)) return true
+          cursorPos.value = nextBoundary(val, pos)
           return true
 
         case 'Home':
@@ -551,32 +786,33 @@ export function input(props: InputProps): Cleanup {
           return true
 
         case 'End':
-          cursorPos.value = val.length
+          cursorPos.value = len
           return true
 
         case 'Backspace':
           if (pos > 0) {
-            const newVal = val.slice(0, pos - 1) + val.slice(pos)
+            const start = prevBoundary(val, pos)
+            const newVal = sliceChars(val, 0, start) + sliceChars(val, pos)
             setValue(newVal)
-            cursorPos.value = pos - 1
+            cursorPos.value = start
             props.onChange?.(newVal)
           }
           return true
 
         case 'Delete':
-          if (pos < val.length) {
-            const newVal = val.slice(0, pos) + val.slice(pos + 1)
+          if (pos < len) {
+            const newVal = sliceChars(val, 0, pos) + sliceChars(val, nextBoundary(val, pos))
             setValue(newVal)
             props.onChange?.(newVal)
           }
           return true
 
         case 'Enter':
-          props.onSubmit?.(val)
+          submit(val)
           return true
 
         case 'Escape':
-          props.onCancel?.()
+          cancel()
           return true
 
         default:
@@ -586,12 +822,13 @@ export function input(props: InputProps): Cleanup {
 
     // Handle printable characters
     if (charKey && !hasCtrl(event) && !hasAlt(event) && !hasMeta(event)) {
-      if (maxLen > 0 && val.length >= maxLen) {
-        return true
-      }
-      const newVal = val.slice(0, pos) + charKey + val.slice(pos)
+      const newVal = sliceChars(val, 0, pos) + charKey + sliceChars(val, pos)
+      // A combining mark joining the last letter still fits maxLength
+      const count = graphemes(newVal).length
+      if (maxLen > 0 && count > maxLen && count > graphemes(val).length) return true
+      if (props.accept && !props.accept.test(newVal)) return true
       setValue(newVal)
-      cursorPos.value = pos + 1
+      cursorPos.value = pos + charCount(charKey)
       props.onChange?.(newVal)
       return true
     }
@@ -599,11 +836,44 @@ export function input(props: InputProps): Cleanup {
     return false
   }
 
-  const unsubKeyboard = onFocused(index, handleKeyEvent)
+  // A disabled input doesn't edit, even if it still had focus
+  const unsubKeyboard = onFocused(index, unlessDisabled(props.disabled, (event: KeyEvent) => {
+    if (handleHistoryKey(event)) return true
+    const before = getValue()
+    const handled = handleKeyEvent(event)
+    if (getValue() !== before) edited(getValue())
+    return handled
+  })!)
+
+  // Rust edits the text in place and reports it: mirror it into the value
+  const unsubValue = registerValueHandler(index, (event) => {
+    switch (event.type) {
+      case EventType.ValueChange: {
+        const text = getText(buf, index)
+        cursorPos.value = getI32(buf, index, N_CURSOR_POSITION)
+        if (text !== getValue()) {
+          setValue(text)
+          props.onChange?.(text)
+          edited(text)
+        }
+        break
+      }
+      case EventType.Submit:
+        submit(getValue())
+        break
+      case EventType.Cancel:
+        cancel()
+        break
+    }
+  })
 
   const unsubFocusCallbacks = registerFocusCallbacks(index, {
     onFocus: props.onFocus,
-    onBlur: props.onBlur,
+    onBlur: () => {
+      if (composition.value?.index === index) commitPreedit(undefined, index)
+      clampOnBlur()
+      props.onBlur?.()
+    },
   })
 
   // ==========================================================================
@@ -611,15 +881,19 @@ export function input(props: InputProps): Cleanup {
   // ==========================================================================
 
   const unsubMouse = onMouseComponent(index, {
-    onMouseDown: props.onMouseDown,
-    onMouseUp: props.onMouseUp,
-    onClick: (event) => {
+    onMouseDown: unlessDisabled(props.disabled, props.onMouseDown),
+    onMouseUp: unlessDisabled(props.disabled, props.onMouseUp),
+    onClick: unlessDisabled(props.disabled, (event) => {
       focusComponent(index)
       return props.onClick?.(event)
-    },
+    }),
     onMouseEnter: props.onMouseEnter,
     onMouseLeave: props.onMouseLeave,
     onScroll: props.onScroll,
+    onMouseDownCapture: unlessDisabled(props.disabled, props.onMouseDownCapture),
+    onMouseUpCapture: unlessDisabled(props.disabled, props.onMouseUpCapture),
+    onClickCapture: unlessDisabled(props.disabled, props.onClickCapture),
+    onContextMenuCapture: unlessDisabled(props.disabled, props.onContextMenuCapture),
   })
 
   // ==========================================================================
@@ -641,15 +915,16 @@ export function input(props: InputProps): Cleanup {
   // CLEANUP
   // ==========================================================================
 
-  const cleanup = () => {
+  const cleanup = withTransition(index, props.transition, () => {
     for (const dispose of disposals) dispose()
     disposals.length = 0
     unsubFocusCallbacks()
     unsubMouse()
     unsubKeyboard()
+    unsubValue()
+    unsubComposer()
     cleanupKeyboardListeners(index)
-    releaseIndex(index)
-  }
+  })
 
   const scope = getActiveScope()
   if (scope) {
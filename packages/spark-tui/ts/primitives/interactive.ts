@@ -0,0 +1,73 @@
+/**
+ * TUI Framework - Interactive variants
+ *
+ * A box or input with a `variant` that can be hovered, focused, pressed or
+ * disabled restyles itself for each state (see getVariantStateStyle), so a
+ * button needs no color getters of its own:
+ *
+ *   box({ variant: 'primary', focusable: true, onClick: save, disabled: () => !dirty.value, ... })
+ *
+ * `disabled` also sets FLAG_DISABLED, takes the node out of focus order
+ * and swallows its click/press handlers.
+ */
+
+import { derived, repeat } from '@rlabs-inc/signals'
+import { FLAG_DISABLED, FLAG_FOCUSABLE, FLAG_FOCUSED, FLAG_HOVERED, FLAG_PRESSED } from '../bridge/shared-buffer'
+import type { ReactiveArrays } from '../bridge/reactive-arrays'
+import { getVariantStateStyle, getVariantStyle } from '../state/theme'
+import type { Variant } from '../state/theme'
+import { interactionState } from '../state/interaction'
+import type { Reactive } from './types'
+import { unwrap } from './utils'
+
+/** Bits Rust maintains in the interaction flags byte */
+const LIVE_FLAGS = FLAG_FOCUSED | FLAG_HOVERED | FLAG_PRESSED
+
+/** Is the `disabled` prop currently on? */
+export function isDisabled(disabled: Reactive<boolean> | undefined): boolean {
+  return disabled !== undefined && unwrap(disabled)
+}
+
+/**
+ * Node `index`'s variant colors. `interactive` nodes follow their hover,
+ * focus, press and disabled state; others get the plain variant.
+ */
+export function variantStyleFor(
+  index: number,
+  variant: Variant,
+  disabled: Reactive<boolean> | undefined,
+  interactive: boolean,
+) {
+  if (!interactive) return derived(() => getVariantStyle(variant))
+  return derived(() => getVariantStateStyle(variant, interactionState(index, isDisabled(disabled))))
+}
+
+/**
+ * Write node `index`'s interaction flags. With a `disabled` prop they follow
+ * it: FLAG_DISABLED on, FLAG_FOCUSABLE off while disabled.
+ */
+export function bindInteractionFlags(
+  arrays: ReactiveArrays,
+  index: number,
+  flags: number,
+  disabled: Reactive<boolean> | undefined,
+  disposals: (() => void)[],
+): void {
+  if (disabled === undefined) {
+    if (flags) arrays.interactionFlags.set(index, flags)
+    return
+  }
+  disposals.push(repeat(() => {
+    const live = arrays.interactionFlags.peek(index) & LIVE_FLAGS
+    return live | (unwrap(disabled) ? (flags & ~FLAG_FOCUSABLE) | FLAG_DISABLED : flags)
+  }, arrays.interactionFlags, index))
+}
+
+/** `handler`, skipped while `disabled` is on */
+export function unlessDisabled<A extends unknown[], R>(
+  disabled: Reactive<boolean> | undefined,
+  handler: ((...args: A) => R) | undefined,
+): ((...args: A) => R | undefined) | undefined {
+  if (disabled === undefined || !handler) return handler
+  return (...args) => (unwrap(disabled) ? undefined : handler(...args))
+}
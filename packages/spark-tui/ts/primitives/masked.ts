@@ -0,0 +1,145 @@
+/**
+ * TUI Framework - Masked input values
+ *
+ * An input with `type: 'number' | 'date' | 'pattern'` only ever holds text
+ * that fits (Rust rejects keystrokes that don't). These turn that text
+ * into a typed value, as a signal alongside the raw string:
+ *
+ *   const raw = signal('')
+ *   input({ value: raw, type: 'date', dateFormat: 'YYYY-MM-DD' })
+ *   const due = dateValue(raw, 'YYYY-MM-DD')   // Date | null
+ *
+ *   input({ value: qty, type: 'number', min: 1, max: 99 })
+ *   const count = numberValue(qty, { min: 1, max: 99 })   // number | null
+ *
+ * Each is null until the text is complete and valid.
+ */
+
+import { derived } from '@rlabs-inc/signals'
+import type { ReadableSignal } from '@rlabs-inc/signals'
+import { InputType, INPUT_DECIMAL, INPUT_SIGNED } from '../bridge/shared-buffer'
+import type { InputProps, Reactive } from './types'
+import { unwrap } from './utils'
+
+export const DEFAULT_DATE_FORMAT = 'DD/MM/YYYY'
+
+export interface NumberRange {
+  min?: number
+  max?: number
+}
+
+// =============================================================================
+// PARSING
+// =============================================================================
+
+/** The number in `text`, or null when empty, partial ('-', '1.') or out of range. */
+export function parseNumber(text: string, range: NumberRange = {}): number | null {
+  if (!/^-?\d+(\.\d+)?$/.test(text)) return null
+  const value = Number(text)
+  if (range.min !== undefined && value < range.min) return null
+  if (range.max !== undefined && value > range.max) return null
+  return value
+}
+
+/** `value` pulled into `range` */
+export function clampNumber(value: number, range: NumberRange): number {
+  return Math.min(range.max ?? Infinity, Math.max(range.min ?? -Infinity, value))
+}
+
+/**
+ * The date in `text` laid out as `format` (DD, MM and YYYY or YY segments),
+ * or null until every segment is filled with a real date. Two-digit years
+ * are 2000–2099.
+ */
+export function parseDate(text: string, format: string = DEFAULT_DATE_FORMAT): Date | null {
+  if (text.length !== format.length) return null
+  const field = (letter: string): number | null => {
+    const start = format.toUpperCase().indexOf(letter)
+    if (start < 0) return null
+    let end = start
+    while (format[end]?.toUpperCase() === letter) end++
+    const digits = text.slice(start, end)
+    return /^\d+$/.test(digits) ? Number(digits) : null
+  }
+
+  const day = field('D')
+  const month = field('M')
+  let year = field('Y')
+  if (day === null || month === null || year === null) return null
+  if (format.toUpperCase().split('Y').length - 1 === 2) year += 2000
+
+  const date = new Date(year, month - 1, day)
+  // Rolled over (31/02) means the day doesn't exist in that month
+  if (date.getFullYear() !== year || date.getMonth() !== month - 1 || date.getDate() !== day) return null
+  return date
+}
+
+/** What a pattern mask slot accepts, null for literals */
+function slotTest(m: string): RegExp | null {
+  switch (m) {
+    case '9': return /\d/
+    case 'a': return /\p{L}/u
+    case '*': return /[\p{L}\p{N}]/u
+    default: return null
+  }
+}
+
+/**
+ * The characters typed into `mask`'s slots (`(555) 123-4567` → `5551234567`),
+ * or null until every slot is filled.
+ */
+export function parsePattern(text: string, mask: string): string | null {
+  const chars = [...text]
+  const slots = [...mask]
+  if (chars.length !== slots.length) return null
+  let raw = ''
+  for (let i = 0; i < slots.length; i++) {
+    const test = slotTest(slots[i]!)
+    if (!test) continue
+    if (!test.test(chars[i]!)) return null
+    raw += chars[i]
+  }
+  return raw
+}
+
+// =============================================================================
+// SIGNALS
+// =============================================================================
+
+export function numberValue(value: Reactive<string>, range: NumberRange = {}): ReadableSignal<number | null> {
+  return derived(() => parseNumber(unwrap(value), range))
+}
+
+export function dateValue(value: Reactive<string>, format: string = DEFAULT_DATE_FORMAT): ReadableSignal<Date | null> {
+  return derived(() => parseDate(unwrap(value), format))
+}
+
+export function patternValue(value: Reactive<string>, mask: string): ReadableSignal<string | null> {
+  return derived(() => parsePattern(unwrap(value), mask))
+}
+
+// =============================================================================
+// INPUT CONFIGURATION
+// =============================================================================
+
+/** InputType, mask and INPUT_* flags for an input's `type` props */
+export function inputMode(props: InputProps): { type: InputType; mask: string; flags: number } {
+  switch (props.type) {
+    case 'number': {
+      const signed = props.min === undefined || props.min < 0
+      return {
+        type: InputType.Number,
+        mask: '',
+        flags: (signed ? INPUT_SIGNED : 0) | (props.decimal ? INPUT_DECIMAL : 0),
+      }
+    }
+    case 'date':
+      return { type: InputType.Date, mask: props.dateFormat ?? DEFAULT_DATE_FORMAT, flags: 0 }
+    case 'pattern':
+      return props.mask
+        ? { type: InputType.Pattern, mask: props.mask, flags: 0 }
+        : { type: InputType.Text, mask: '', flags: 0 }
+    default:
+      return { type: props.password ? InputType.Password : InputType.Text, mask: '', flags: 0 }
+  }
+}
@@ -0,0 +1,88 @@
+/**
+ * TUI Framework - Match Primitive
+ *
+ * Multi-way conditional rendering: one arm per value of a signal, mounted
+ * while the value selects it. `show` for more than two branches.
+ *
+ * Usage:
+ * ```ts
+ * const tab = signal<'files' | 'search' | 'git'>('files')
+ *
+ * match(tab, {
+ *   files: () => fileTree(),
+ *   search: () => searchPanel(),
+ * }, () => text({ content: 'Nothing here' }))
+ * ```
+ *
+ * Only a change of arm remounts: moving between two values without an arm
+ * keeps the fallback. The old arm is cleaned up before the new one mounts,
+ * at the place where `match` was declared (see ./slot).
+ */
+
+import { effect, effectScope, onScopeDispose, type ReadableSignal } from '@rlabs-inc/signals'
+import { getCurrentParentIndex } from '../engine/registry'
+import { createSlot } from './slot'
+import type { Cleanup } from './types'
+
+/** Render function per value */
+export type MatchArms<V extends PropertyKey> = Partial<Record<V, () => Cleanup>>
+
+/**
+ * Render the arm selected by the current value.
+ *
+ * @param value - Signal or getter of the selecting value (creates dependency)
+ * @param arms - Render function per value
+ * @param fallback - Rendered when no arm matches
+ */
+export function match<V extends PropertyKey>(
+  value: ReadableSignal<V> | (() => V),
+  arms: MatchArms<V>,
+  fallback?: () => Cleanup
+): Cleanup {
+  const valueGetter = typeof value === 'function' ? value : () => value.value
+  let cleanup: Cleanup | null = null
+  // undefined = nothing mounted yet
+  let shown: (() => Cleanup) | null | undefined = undefined
+  const slot = createSlot(getCurrentParentIndex())
+  const scope = effectScope()
+
+  const update = (current: V) => {
+    const arm = Object.hasOwn(arms, current) ? arms[current]! : (fallback ?? null)
+    if (arm === shown) return
+    shown = arm
+
+    if (cleanup) {
+      cleanup()
+      cleanup = null
+    }
+
+    if (arm) {
+      const mounted = slot.mount(arm)
+      cleanup = mounted.cleanup
+      slot.place([mounted.nodes])
+    }
+  }
+
+  scope.run(() => {
+    // Initial render
+    update(valueGetter())
+
+    // Tracks the value; the first run only subscribes
+    let initialized = false
+    effect(() => {
+      const current = valueGetter() // Must read to track dependency!
+      if (!initialized) {
+        initialized = true
+        return
+      }
+      update(current)
+    })
+
+    onScopeDispose(() => {
+      if (cleanup) cleanup()
+      slot.dispose()
+    })
+  })
+
+  return () => scope.stop()
+}
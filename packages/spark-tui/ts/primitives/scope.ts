@@ -65,6 +65,20 @@ export function trackCleanup<T extends Cleanup>(cleanup: T): T {
   return cleanup
 }
 
+/**
+ * Claim a cleanup position in the active scope now and fill it in later.
+ * Lets a component's cleanup run ahead of the ones its children register
+ * (used by exit transitions, which must start before children are torn down).
+ */
+export function reserveCleanup(): (cleanup: Cleanup) => void {
+  if (!activeContext) return () => {}
+  const cleanups = activeContext.cleanups
+  const slot = cleanups.push(() => {}) - 1
+  return (cleanup) => {
+    cleanups[slot] = cleanup
+  }
+}
+
 // =============================================================================
 // SCOPED EXECUTION
 // =============================================================================
@@ -9,28 +9,35 @@
  * show(() => isVisible.value, () => {
  *   text({ content: 'I am visible!' })
  * })
+ * show(isVisible, () => text({ content: 'A signal works too' }))
  * ```
+ *
+ * Unlike `visible: false`, the hidden branch has no nodes at all. The old
+ * branch is fully cleaned up before the new one mounts, and the new one
+ * takes the place where `show` was declared (see ./slot).
  */
 
-import { effect, effectScope, onScopeDispose } from '@rlabs-inc/signals'
-import { getCurrentParentIndex, pushParentContext, popParentContext } from '../engine/registry'
+import { effect, effectScope, onScopeDispose, type ReadableSignal } from '@rlabs-inc/signals'
+import { getCurrentParentIndex } from '../engine/registry'
+import { createSlot } from './slot'
 import type { Cleanup } from './types'
 
 /**
  * Conditionally render components.
  *
- * @param conditionGetter - Getter that returns boolean (creates dependency)
+ * @param condition - Signal or getter of the condition (creates dependency)
  * @param renderFn - Function to render when condition is true (returns cleanup)
  * @param elseFn - Optional function to render when condition is false
  */
 export function show(
-  conditionGetter: () => boolean,
+  condition: ReadableSignal<boolean> | (() => boolean),
   renderFn: () => Cleanup,
   elseFn?: () => Cleanup
 ): Cleanup {
+  const conditionGetter = typeof condition === 'function' ? condition : () => condition.value
   let cleanup: Cleanup | null = null
   let wasTrue: boolean | null = null
-  const parentIndex = getCurrentParentIndex()
+  const slot = createSlot(getCurrentParentIndex())
   const scope = effectScope()
 
   const update = (condition: boolean) => {
@@ -42,15 +49,11 @@ export function show(
       cleanup = null
     }
 
-    pushParentContext(parentIndex)
-    try {
-      if (condition) {
-        cleanup = renderFn()
-      } else if (elseFn) {
-        cleanup = elseFn()
-      }
-    } finally {
-      popParentContext()
+    const branch = condition ? renderFn : elseFn
+    if (branch) {
+      const mounted = slot.mount(branch)
+      cleanup = mounted.cleanup
+      slot.place([mounted.nodes])
     }
   }
 
@@ -72,6 +75,7 @@ export function show(
 
     onScopeDispose(() => {
       if (cleanup) cleanup()
+      slot.dispose()
     })
   })
 
@@ -0,0 +1,89 @@
+/**
+ * TUI Framework - Slots
+ *
+ * Keeps dynamically mounted content where it was declared. Nodes are laid
+ * out in index order, so a subtree mounted later (a `show` turning true, a
+ * new list item) would otherwise land wherever its — possibly recycled —
+ * index falls. A slot allocates an anchor index at declaration time; the
+ * top-level nodes mounted through it carry that anchor (N_SLOT_ANCHOR) and
+ * their position (N_SLOT_POSITION), and Rust sorts them at the anchor's
+ * place among their siblings.
+ *
+ * This is the anchor `each` keys its items to, shared so that `show` and
+ * `match` branches keep their place the same way; `each` only adds the
+ * keyed diff on top.
+ *
+ * The anchor is never rendered. Only nodes created directly under the
+ * parent while mounting are placed — content an item creates at its top
+ * level later (a nested `show`) uses its own slot.
+ */
+
+import {
+  allocateIndex,
+  componentRef,
+  releaseRef,
+  captureChildren,
+  isAllocated,
+  pushParentContext,
+  popParentContext,
+} from '../engine/registry'
+import { getArrays, isInitialized } from '../bridge'
+import type { Cleanup } from './types'
+
+export interface Mounted {
+  cleanup: Cleanup
+  /** Top-level nodes, in creation order */
+  nodes: number[]
+}
+
+export interface Slot {
+  /** Mount `render` under the slot's parent */
+  mount(render: () => Cleanup): Mounted
+  /** Put these node groups at the slot, in this order */
+  place(groups: number[][]): void
+  /** Release the anchor */
+  dispose(): void
+}
+
+/**
+ * Reserve a place among `parentIndex`'s children, at the current point of
+ * declaration.
+ */
+export function createSlot(parentIndex: number): Slot {
+  const anchor = allocateIndex()
+  const anchorRef = componentRef(anchor)
+
+  return {
+    mount(render) {
+      pushParentContext(parentIndex)
+      try {
+        const { result, indices } = captureChildren(parentIndex, render)
+        return { cleanup: result, nodes: indices }
+      } finally {
+        popParentContext()
+      }
+    },
+
+    place(groups) {
+      if (!isInitialized()) return
+      const arrays = getArrays()
+      const anchorUpdates: [number, number][] = []
+      const positionUpdates: [number, number][] = []
+      let position = 0
+      for (const nodes of groups) {
+        for (const node of nodes) {
+          if (!isAllocated(node)) continue
+          if (arrays.slotAnchor.get(node) !== anchor + 1) anchorUpdates.push([node, anchor + 1])
+          if (arrays.slotPosition.get(node) !== position) positionUpdates.push([node, position])
+          position++
+        }
+      }
+      if (anchorUpdates.length > 0) arrays.slotAnchor.setBatch(anchorUpdates)
+      if (positionUpdates.length > 0) arrays.slotPosition.setBatch(positionUpdates)
+    },
+
+    dispose() {
+      releaseRef(anchorRef)
+    },
+  }
+}
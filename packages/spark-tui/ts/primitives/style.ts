@@ -0,0 +1,65 @@
+/**
+ * TUI Framework - Style objects
+ *
+ * A Style bundles visual props (colors, text attributes, border, padding)
+ * so a look is defined once and reused across an app:
+ *
+ * ```ts
+ * const card = mergeStyles(themed('surface'), { border: BorderStyle.ROUNDED, padding: [0, 1] })
+ * const label = { bold: true, fg: t.textMuted }
+ *
+ * box({ style: card, children: ... })
+ * text({ style: [label, invalid && themed('error')], content: 'Name' })
+ * ```
+ *
+ * Styles merge left to right, later fields winning; false/null entries are
+ * skipped, so conditions read inline. Explicit props always win over the
+ * style. Fields a primitive has no prop for are ignored: text has no
+ * border, and input takes `attrs` but not the attribute flags.
+ */
+
+import { getVariantStyle } from '../state/theme'
+import type { Variant } from '../state/theme'
+import type { Style, StyleProp } from './types'
+
+/** Merge styles left to right (later fields win), skipping false/null entries. */
+export function mergeStyles(...parts: StyleProp[]): Style {
+  const merged: Style = {}
+  for (const part of parts) {
+    if (!part) continue
+    Object.assign(merged, Array.isArray(part) ? mergeStyles(...part) : part)
+  }
+  return merged
+}
+
+/**
+ * A theme variant's colors as a Style: fg, bg and borderColor follow the
+ * current theme. Pass `extra` to add or override fields.
+ */
+export function themed(variant: Variant, extra?: Style): Style {
+  return {
+    fg: () => getVariantStyle(variant).fg,
+    bg: () => getVariantStyle(variant).bg,
+    borderColor: () => getVariantStyle(variant).border,
+    ...extra,
+  }
+}
+
+/**
+ * Props with their `style` folded in: style fields fill whatever the props
+ * leave undefined, and padding shorthand expands to the per-side props.
+ */
+export function applyStyle<P extends { style?: StyleProp; padding?: unknown }>(props: P): P {
+  if (!props.style) return props
+  const { padding, ...fields } = mergeStyles(props.style)
+
+  let sides = {}
+  if (padding !== undefined && props.padding === undefined) {
+    const values: number[] = typeof padding === 'number' ? [padding] : padding
+    const [top, right = top, bottom = top, left = right] = values
+    sides = { paddingTop: top, paddingRight: right, paddingBottom: bottom, paddingLeft: left }
+  }
+
+  const own = Object.fromEntries(Object.entries(props).filter(([, value]) => value !== undefined))
+  return { ...fields, ...sides, ...own } as P
+}
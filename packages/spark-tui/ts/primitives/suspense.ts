@@ -0,0 +1,189 @@
+/**
+ * TUI Framework - Suspense Primitive
+ *
+ * Loading boundaries for async data. A `resource` wraps an async fetcher in
+ * signals; `suspense` shows a fallback while its resources are pending, the
+ * real children once they resolve, and an error subtree if one fails.
+ *
+ * Usage:
+ * ```ts
+ * const user = resource(({ signal }) => fetchUser(userId.value, { signal }))
+ *
+ * suspense({
+ *   on: user,
+ *   fallback: () => text({ content: 'Loading…' }),
+ *   error: (err, retry) => text({ content: `Failed: ${err}`, onClick: retry }),
+ *   children: () => text({ content: () => user.value!.name }),
+ * })
+ * ```
+ *
+ * Signals read synchronously in the fetcher are dependencies: when
+ * `userId` changes the resource refetches, the previous request is aborted
+ * and its late result ignored. Unlike `when`, several boundaries can share
+ * one resource, and the children read its value reactively.
+ */
+
+import { effect, effectScope, onScopeDispose, signal } from '@rlabs-inc/signals'
+import { getCurrentParentIndex, pushParentContext, popParentContext } from '../engine/registry'
+import type { Cleanup } from './types'
+
+// =============================================================================
+// RESOURCE
+// =============================================================================
+
+export type ResourceState = 'pending' | 'ready' | 'error'
+
+export interface Resource<T> {
+  /** Last resolved value (kept while a refetch is pending) */
+  readonly value: T | undefined
+  readonly state: ResourceState
+  readonly loading: boolean
+  /** Rejection reason while state is 'error' */
+  readonly error: unknown
+  /** Run the fetcher again */
+  refetch(): void
+}
+
+export interface ResourceContext {
+  /** Aborted when a newer fetch supersedes this one or the owner is disposed */
+  signal: AbortSignal
+}
+
+/**
+ * Async value as signals. Create inside a component (or any scope) — the
+ * fetch is aborted when the scope is disposed.
+ */
+export function resource<T>(fetcher: (ctx: ResourceContext) => Promise<T>): Resource<T> {
+  const value = signal<T | undefined>(undefined)
+  const state = signal<ResourceState>('pending')
+  const error = signal<unknown>(undefined)
+  const trigger = signal(0)
+
+  let controller: AbortController | null = null
+
+  const run = () => {
+    void trigger.value // refetch() dependency
+    controller?.abort()
+    const current = new AbortController()
+    controller = current
+
+    state.value = 'pending'
+    let promise: Promise<T>
+    try {
+      // Synchronous signal reads here become dependencies of this effect
+      promise = fetcher({ signal: current.signal })
+    } catch (err) {
+      promise = Promise.reject(err)
+    }
+
+    promise.then(
+      (result) => {
+        if (current.signal.aborted) return
+        value.value = result
+        error.value = undefined
+        state.value = 'ready'
+      },
+      (err) => {
+        if (current.signal.aborted) return
+        error.value = err
+        state.value = 'error'
+      }
+    )
+  }
+
+  effect(run)
+  onScopeDispose(() => controller?.abort())
+
+  return {
+    get value() { return value.value },
+    get state() { return state.value },
+    get loading() { return state.value === 'pending' },
+    get error() { return error.value },
+    refetch() { trigger.value++ },
+  }
+}
+
+// =============================================================================
+// SUSPENSE
+// =============================================================================
+
+interface SuspenseOptions {
+  /** Resource(s) this boundary waits for */
+  on: Resource<unknown> | Resource<unknown>[]
+  /** Shown while any resource is pending */
+  fallback?: () => Cleanup
+  /** Shown when any resource failed; `retry` refetches the failed ones */
+  error?: (error: unknown, retry: () => void) => Cleanup
+  /** Shown once every resource is ready */
+  children: () => Cleanup
+  /**
+   * Keep the children mounted while a ready boundary refetches
+   * (default false: back to the fallback).
+   */
+  keepContent?: boolean
+}
+
+/**
+ * Loading boundary: fallback → children (or error) as resources settle.
+ */
+export function suspense(options: SuspenseOptions): Cleanup {
+  const resources = Array.isArray(options.on) ? options.on : [options.on]
+  let cleanup: Cleanup | null = null
+  let shown: ResourceState | null = null
+  const parentIndex = getCurrentParentIndex()
+  const scope = effectScope()
+
+  const combined = (): ResourceState => {
+    if (resources.some((r) => r.state === 'error')) return 'error'
+    if (resources.some((r) => r.state === 'pending')) return 'pending'
+    return 'ready'
+  }
+
+  const update = (next: ResourceState) => {
+    if (next === 'pending' && shown === 'ready' && options.keepContent) return
+    if (next === shown) return
+    shown = next
+
+    if (cleanup) {
+      cleanup()
+      cleanup = null
+    }
+
+    pushParentContext(parentIndex)
+    try {
+      if (next === 'ready') {
+        cleanup = options.children()
+      } else if (next === 'pending') {
+        cleanup = options.fallback?.() ?? null
+      } else {
+        const failed = resources.filter((r) => r.state === 'error')
+        const retry = () => failed.forEach((r) => r.refetch())
+        if (options.error) cleanup = options.error(failed[0]!.error, retry)
+        else console.error('[suspense] Unhandled resource error:', failed[0]!.error)
+      }
+    } finally {
+      popParentContext()
+    }
+  }
+
+  scope.run(() => {
+    // Initial render
+    update(combined())
+
+    let initialized = false
+    effect(() => {
+      const next = combined() // Must read to track dependency!
+      if (!initialized) {
+        initialized = true
+        return
+      }
+      update(next)
+    })
+
+    onScopeDispose(() => {
+      if (cleanup) cleanup()
+    })
+  })
+
+  return () => scope.stop()
+}
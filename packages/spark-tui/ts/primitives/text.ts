@@ -17,12 +17,12 @@
  */
 
 import { repeat } from '@rlabs-inc/signals'
+import { bindPercentBases, pointerEventsToNum, toDim, unwrap } from './utils'
 import { ComponentType, Attr } from '../types'
 import type { RGBA, ColorInput } from '../types'
 import { parseColor } from '../types/color'
 import {
   allocateIndex,
-  releaseIndex,
   getCurrentParentIndex,
   registerParent,
 } from '../engine/registry'
@@ -35,6 +35,11 @@ import { cleanupIndex as cleanupKeyboardListeners } from '../state/keyboard'
 import { onComponent as onMouseComponent } from '../state/mouse'
 import { getVariantStyle } from '../state/theme'
 import { getActiveScope } from './scope'
+import { withTransition } from './transition'
+import { bindGridPlacement } from './gridPlacement'
+import { bindHref } from './href'
+import { bindA11y } from './a11y'
+import { applyStyle } from './style'
 import { getArrays, getBuffer } from '../bridge'
 import {
   packColor,
@@ -51,22 +56,6 @@ import type { TextProps, Cleanup, GridLine } from './types'
 // CONVERSION HELPERS
 // =============================================================================
 
-/** Dimension → Taffy float: NaN = auto, negative = percentage, positive = pixels */
-function toDim(dim: number | string | undefined | null): number {
-  if (dim === undefined || dim === null || dim === 0) return NaN
-  if (typeof dim === 'string') {
-    if (dim.endsWith('%')) return -parseFloat(dim) // '100%' → -100.0
-    return parseFloat(dim) || NaN
-  }
-  return dim
-}
-
-function unwrap<T>(prop: T | (() => T) | { readonly value: T }): T {
-  if (typeof prop === 'function') return (prop as () => T)()
-  if (prop !== null && typeof prop === 'object' && 'value' in prop) return (prop as { value: T }).value
-  return prop
-}
-
 function isReactive(prop: unknown): boolean {
   return typeof prop === 'function' || (prop !== null && typeof prop === 'object' && 'value' in (prop as any))
 }
@@ -132,9 +121,21 @@ function alignSelfToNum(a: string | undefined): number {
 
 function textAlignToNum(align: string | undefined): number {
   switch (align) {
+    case 'left': return 0
     case 'center': return 1
     case 'right': return 2
-    default: return 0 // left
+    case 'end': return 4
+    case 'justify': return 5
+    default: return 3 // start
+  }
+}
+
+function directionToNum(dir: string | undefined): number {
+  switch (dir) {
+    case 'ltr': return 1
+    case 'rtl': return 2
+    case 'auto': return 3
+    default: return 0 // inherit
   }
 }
 
@@ -142,6 +143,7 @@ function textWrapToNum(wrap: string | undefined): number {
   switch (wrap) {
     case 'nowrap': return 0
     case 'truncate': return 2
+    case 'hyphenate': return 3
     default: return 1 // wrap
   }
 }
@@ -197,6 +199,7 @@ function writeTextToPool(buf: SharedBuffer, index: number, text: string): number
 // =============================================================================
 
 export function text(props: TextProps): Cleanup {
+  props = applyStyle(props)
   const buf = getBuffer()
   const arrays = getArrays()
   const index = allocateIndex(props.id)
@@ -250,6 +253,9 @@ export function text(props: TextProps): Cleanup {
   if (props.maxWidth !== undefined) disposals.push(repeat(dimInput(props.maxWidth), arrays.maxWidth, index))
   if (props.minHeight !== undefined) disposals.push(repeat(dimInput(props.minHeight), arrays.minHeight, index))
   if (props.maxHeight !== undefined) disposals.push(repeat(dimInput(props.maxHeight), arrays.maxHeight, index))
+  disposals.push(...bindPercentBases(props, arrays, index))
+  if (props.aspectRatio !== undefined) disposals.push(repeat(numInput(props.aspectRatio, NaN), arrays.aspectRatio, index))
+  if (props.aspectCells !== undefined) disposals.push(repeat(boolInput(props.aspectCells, 0), arrays.aspectCells, index))
 
   // Flex item
   if (props.grow !== undefined) disposals.push(repeat(numInput(props.grow), arrays.flexGrow, index))
@@ -290,9 +296,12 @@ export function text(props: TextProps): Cleanup {
 
   // Z-index
   if (props.zIndex !== undefined) disposals.push(repeat(numInput(props.zIndex), arrays.zIndex, index))
+  if (props.pointerEvents !== undefined) disposals.push(repeat(enumInput(props.pointerEvents, pointerEventsToNum), arrays.pointerEvents, index))
+  if (props.hoverTag !== undefined) disposals.push(repeat(numInput(props.hoverTag), arrays.hoverTag, index))
 
   // Text styling
   if (props.align !== undefined) disposals.push(repeat(enumInput(props.align, textAlignToNum), arrays.textAlign, index))
+  if (props.direction !== undefined) disposals.push(repeat(enumInput(props.direction, directionToNum), arrays.direction, index))
   if (props.wrap !== undefined) disposals.push(repeat(enumInput(props.wrap, textWrapToNum), arrays.textWrap, index))
 
   // --------------------------------------------------------------------------
@@ -329,6 +338,7 @@ export function text(props: TextProps): Cleanup {
   if (props.justifySelf !== undefined) {
     disposals.push(repeat(enumInput(props.justifySelf, justifySelfToNum), arrays.justifySelf, index))
   }
+  bindGridPlacement(props, arrays, index, parentIdx, disposals)
 
   // --------------------------------------------------------------------------
   // VISUAL — colors with variant support
@@ -349,6 +359,12 @@ export function text(props: TextProps): Cleanup {
   }
   if (props.opacity !== undefined) disposals.push(repeat(numInput(props.opacity), arrays.opacity, index))
 
+  // Hyperlink (OSC 8)
+  bindHref(buf, index, props.href, disposals)
+
+  // Accessibility metadata; live text announces its content
+  bindA11y(buf, index, props, disposals, props.content)
+
   // --------------------------------------------------------------------------
   // TEXT ATTRIBUTES (bold, italic, underline, etc.)
   // --------------------------------------------------------------------------
@@ -400,14 +416,20 @@ export function text(props: TextProps): Cleanup {
   // --------------------------------------------------------------------------
   let unsubMouse: (() => void) | undefined
 
-  if (props.onMouseDown || props.onMouseUp || props.onClick || props.onMouseEnter || props.onMouseLeave || props.onScroll) {
+  if (props.onMouseDown || props.onMouseUp || props.onClick || props.onContextMenu || props.onMouseEnter || props.onMouseLeave || props.onScroll
+    || props.onMouseDownCapture || props.onMouseUpCapture || props.onClickCapture || props.onContextMenuCapture) {
     unsubMouse = onMouseComponent(index, {
       onMouseDown: props.onMouseDown,
       onMouseUp: props.onMouseUp,
       onClick: props.onClick,
+      onContextMenu: props.onContextMenu,
       onMouseEnter: props.onMouseEnter,
       onMouseLeave: props.onMouseLeave,
       onScroll: props.onScroll,
+      onMouseDownCapture: props.onMouseDownCapture,
+      onMouseUpCapture: props.onMouseUpCapture,
+      onClickCapture: props.onClickCapture,
+      onContextMenuCapture: props.onContextMenuCapture,
     })
   }
 
@@ -418,13 +440,12 @@ export function text(props: TextProps): Cleanup {
   // --------------------------------------------------------------------------
   // CLEANUP
   // --------------------------------------------------------------------------
-  const cleanup = () => {
+  const cleanup = withTransition(index, props.transition, () => {
     for (const dispose of disposals) dispose()
     disposals.length = 0
     unsubMouse?.()
     cleanupKeyboardListeners(index)
-    releaseIndex(index)
-  }
+  })
 
   const scope = getActiveScope()
   if (scope) scope.cleanups.push(cleanup)
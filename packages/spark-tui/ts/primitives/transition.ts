@@ -0,0 +1,204 @@
+/**
+ * TUI Framework - Enter/Exit Transitions
+ *
+ * The `transition` prop on box/text/input:
+ *
+ * ```ts
+ * show(() => saved.value, () => box({
+ *   transition: {
+ *     enter: { effect: ['fade', 'slide-up'] },
+ *     exit: { effect: 'fade', duration: 300 },
+ *   },
+ *   children: () => text({ content: 'Saved!' }),
+ * }))
+ * ```
+ *
+ * Enter plays as soon as the component is created. Exit plays when its
+ * cleanup runs: the release is postponed until the animation ends, and any
+ * descendant cleaned up in the meantime waits for it too, so the whole
+ * subtree stays on screen while it fades or slides away.
+ *
+ * Frames are written straight to the node's slots (opacity, insets) from the
+ * shared animation clock. Nothing here is an effect, so an exit keeps running
+ * after the owning scope has been stopped.
+ */
+
+import { getArrays, getBuffer } from '../bridge'
+import { markDirty, DIRTY_LAYOUT } from '../bridge/shared-buffer'
+import { releaseIndex, componentRef, isLive } from '../engine/registry'
+import { onDestroyOf } from '../engine/lifecycle'
+import { animateValue, type Tween } from './animation'
+import type { Cleanup, TransitionProps, TransitionSpec, TransitionEffect } from './types'
+
+const DEFAULT_TRANSITION_DURATION = 150
+const DEFAULT_SLIDE_DISTANCE = 2
+/** Rust reads opacity 0 as "unset" (opaque) — fades bottom out just above it */
+const MIN_FADE_OPACITY = 0.01
+
+/** Nodes currently playing their exit transition */
+const exiting = new Set<number>()
+
+/** Finishers of descendants whose cleanup is waiting on an exiting ancestor */
+const waiting = new Map<number, () => void>()
+
+function effectsOf(spec: TransitionSpec): TransitionEffect[] {
+  return Array.isArray(spec.effect) ? spec.effect : [spec.effect]
+}
+
+function hasAncestor(index: number, matches: (ancestor: number) => boolean): boolean {
+  const arrays = getArrays()
+  for (let i = arrays.parentIndex.get(index); i >= 0; i = arrays.parentIndex.get(i)) {
+    if (matches(i)) return true
+  }
+  return false
+}
+
+/** Release every waiting descendant of `ancestor` (before the ancestor itself goes). */
+function flushWaiting(ancestor: number): void {
+  for (const [index, finish] of [...waiting]) {
+    if (hasAncestor(index, (i) => i === ancestor)) finish()
+  }
+}
+
+/** Slot values the transition animates relative to */
+interface Resting {
+  opacity: number
+  insetLeft: number
+  insetTop: number
+}
+
+function readResting(index: number): Resting {
+  const arrays = getArrays()
+  return {
+    opacity: arrays.opacity.get(index),
+    insetLeft: arrays.insetLeft.get(index),
+    insetTop: arrays.insetTop.get(index),
+  }
+}
+
+/**
+ * Write one frame. `progress` runs 0 (hidden) → 1 (resting); exits run it
+ * back down, with slides continuing in the same direction instead of
+ * reversing.
+ */
+function applyFrame(index: number, spec: TransitionSpec, resting: Resting, progress: number, leaving: boolean): void {
+  const arrays = getArrays()
+  const distance = spec.distance ?? DEFAULT_SLIDE_DISTANCE
+  const offset = Math.round(distance * (1 - progress)) * (leaving ? -1 : 1)
+
+  for (const effect of effectsOf(spec)) {
+    switch (effect) {
+      case 'fade': {
+        const base = resting.opacity || 1
+        arrays.opacity.set(index, Math.max(MIN_FADE_OPACITY, base * progress))
+        break
+      }
+      case 'slide-up':
+      case 'slide-down':
+        markDirty(getBuffer(), index, DIRTY_LAYOUT)
+        arrays.insetTop.set(index, resting.insetTop + (effect === 'slide-up' ? offset : -offset))
+        break
+      case 'slide-left':
+      case 'slide-right':
+        markDirty(getBuffer(), index, DIRTY_LAYOUT)
+        arrays.insetLeft.set(index, resting.insetLeft + (effect === 'slide-left' ? offset : -offset))
+        break
+    }
+  }
+}
+
+function restore(index: number, spec: TransitionSpec, resting: Resting): void {
+  const arrays = getArrays()
+  for (const effect of effectsOf(spec)) {
+    if (effect === 'fade') arrays.opacity.set(index, resting.opacity)
+    else {
+      markDirty(getBuffer(), index, DIRTY_LAYOUT)
+      arrays.insetTop.set(index, resting.insetTop)
+      arrays.insetLeft.set(index, resting.insetLeft)
+    }
+  }
+}
+
+function tweenSpec(spec: TransitionSpec): TransitionSpec {
+  return { ...spec, duration: spec.duration ?? DEFAULT_TRANSITION_DURATION }
+}
+
+/**
+ * Play `transition` on a freshly created component and build its cleanup.
+ *
+ * Every box/text/input goes through here, transition or not: a component
+ * without one still has to wait when an ancestor is mid-exit. Nothing is
+ * registered unless a cleanup actually gets deferred.
+ *
+ * @param teardown - Everything the component's cleanup does except releasing its index
+ * @returns The component's cleanup
+ */
+export function withTransition(index: number, transition: TransitionProps | undefined, teardown: () => void): Cleanup {
+  const ref = componentRef(index)
+  const resting = readResting(index)
+  let progress = 1
+  let running: Tween | null = null
+  let done = false
+
+  const enter = transition?.enter
+  if (enter) {
+    progress = 0
+    applyFrame(index, enter, resting, 0, false)
+    running = animateValue(0, 1, tweenSpec(enter), (p) => {
+      progress = p
+      applyFrame(index, enter, resting, p, false)
+    })
+    running.finished.then((completed) => {
+      if (completed && !done) restore(index, enter, resting)
+    })
+  }
+
+  // Released out from under us (an ancestor went without waiting):
+  // the index is already being freed, so only tear down.
+  const onReleased = () => {
+    if (done) return
+    done = true
+    running?.cancel()
+    exiting.delete(index)
+    waiting.delete(index)
+    teardown()
+  }
+
+  const finish = () => {
+    if (done) return
+    flushWaiting(index)
+    done = true
+    running?.cancel()
+    exiting.delete(index)
+    waiting.delete(index)
+    teardown()
+    releaseIndex(index)
+  }
+
+  return () => {
+    if (done || exiting.has(index) || waiting.has(index) || !isLive(ref)) return
+
+    const exit = transition?.exit
+    if (exit) {
+      running?.cancel()
+      exiting.add(index)
+      onDestroyOf(index, onReleased)
+      running = animateValue(progress, 0, tweenSpec(exit), (p) => {
+        progress = p
+        applyFrame(index, exit, resting, p, true)
+      })
+      running.finished.then((completed) => {
+        if (completed) finish()
+      })
+      return
+    }
+
+    if (exiting.size > 0 && hasAncestor(index, (i) => exiting.has(i))) {
+      waiting.set(index, finish)
+      onDestroyOf(index, onReleased)
+      return
+    }
+
+    finish()
+  }
+}
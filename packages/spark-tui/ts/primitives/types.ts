@@ -7,9 +7,12 @@
 
 import type { RGBA, CellAttrs, Dimension, ColorInput } from '../types'
 import type { WritableSignal, ReadableSignal, Binding, ReadonlyBinding } from '@rlabs-inc/signals'
-import type { Variant } from '../state/theme'
+import type { Variant, t } from '../state/theme'
 import type { KeyEvent } from '../state/keyboard'
 import type { MouseEvent, MouseHandlers, ScrollEvent } from '../state/mouse'
+import type { A11yRoleName } from '../state/announcer'
+import type { History } from '../state/history'
+import type { TweenOptions, SpringConfig } from './animation'
 
 /** Keyboard event handler */
 export type KeyHandler = (event: KeyEvent) => boolean | void
@@ -42,15 +45,93 @@ export type WithReactive<T, K extends keyof T> = Omit<T, K> & {
 // COMMON PROPS
 // =============================================================================
 
+/** Padding shorthand: all sides, [vertical, horizontal], or [top, right, bottom, left] */
+export type PaddingShorthand = number | [number, number] | [number, number, number, number]
+
+/**
+ * Reusable visual props; see mergeStyles() and themed().
+ * Explicit props on a primitive win over its style.
+ */
+export interface Style {
+  fg?: Reactive<ColorInput>
+  bg?: Reactive<ColorInput>
+  opacity?: Reactive<number>
+  bold?: Reactive<boolean>
+  dim?: Reactive<boolean>
+  italic?: Reactive<boolean>
+  underline?: Reactive<boolean>
+  blink?: Reactive<boolean>
+  inverse?: Reactive<boolean>
+  hidden?: Reactive<boolean>
+  strikethrough?: Reactive<boolean>
+  attrs?: Reactive<CellAttrs>
+  border?: Reactive<number>
+  borderColor?: Reactive<ColorInput>
+  padding?: PaddingShorthand
+}
+
+/** One style, or a list merged left to right; false/null entries are skipped */
+export type StyleProp = Style | false | null | undefined | StyleProp[]
+
 export interface StyleProps {
+  /** Shared visual props (colors, attributes, border, padding) under the explicit ones */
+  style?: StyleProp
   /** Foreground color (text) - accepts hex, CSS names, rgb(), oklch(), integer, or RGBA */
   fg?: Reactive<ColorInput>
   /** Background color - accepts hex, CSS names, rgb(), oklch(), integer, or RGBA */
   bg?: Reactive<ColorInput>
   /** Opacity 0-1 */
   opacity?: Reactive<number>
+  /** Animate in on mount and/or out on cleanup */
+  transition?: TransitionProps
 }
 
+/** Built-in transition effects. Slides move from/to `distance` cells away. */
+export type TransitionEffect = 'fade' | 'slide-up' | 'slide-down' | 'slide-left' | 'slide-right'
+
+export interface TransitionSpec extends TweenOptions {
+  /** One effect, or several played together (e.g. `['fade', 'slide-up']`) */
+  effect: TransitionEffect | TransitionEffect[]
+  /** Slide distance in cells (default: 2) */
+  distance?: number
+}
+
+/**
+ * Enter/exit transitions.
+ * `exit` delays the component's teardown until the animation finishes;
+ * its children stay on screen until then.
+ */
+export interface TransitionProps {
+  enter?: TransitionSpec
+  exit?: TransitionSpec
+}
+
+/** Theme color names (`t.primary` → `'primary'`) accepted by background fills */
+export type ThemeColorName = keyof typeof t
+
+/** A background fill color: any ColorInput, or a theme color name that follows theme changes */
+export type FillColor = Reactive<ColorInput | ThemeColorName>
+
+/**
+ * Gradient or pattern background, blending `from` → `to`.
+ * `from` also serves as the box's plain bg (what children inherit).
+ */
+export type BackgroundFillProp =
+  | {
+      type: 'linear-gradient'
+      from: FillColor
+      to: FillColor
+      /** Direction in degrees: 0 = left→right (default), 90 = top→bottom */
+      angle?: Reactive<number>
+    }
+  | {
+      type: 'checker' | 'stripes-horizontal' | 'stripes-vertical' | 'stripes-diagonal'
+      from: FillColor
+      to: FillColor
+      /** Square/band size in cells (default 1) */
+      size?: Reactive<number>
+    }
+
 export interface BorderProps {
   /** Border style (0=none, 1=single, 2=double, 3=rounded, etc.) */
   border?: Reactive<number>
@@ -61,10 +142,20 @@ export interface BorderProps {
   borderRight?: Reactive<number>
   borderBottom?: Reactive<number>
   borderLeft?: Reactive<number>
+  /**
+   * Inner line style for a framed border. Any style but 0 turns the border
+   * into a two-cell frame — `border` outside, this inside — and insets the
+   * content by two cells per bordered side.
+   */
+  borderInner?: Reactive<number>
+  /** Inner line color of a framed border (default: borderColor) */
+  borderInnerColor?: Reactive<ColorInput>
+  /** Background of a framed border's two-cell band, e.g. a contrasting mat */
+  borderFill?: Reactive<ColorInput>
 }
 
 export interface DimensionProps {
-  /** Width (0 = auto, '100%' = full parent, '50%' = half parent) */
+  /** Width (0 = auto, '100%' = full parent, '50%' = half parent, '50vw' = half the terminal) */
   width?: Reactive<Dimension>
   /** Height (0 = auto, '100%' = full parent, '50%' = half parent) */
   height?: Reactive<Dimension>
@@ -76,6 +167,18 @@ export interface DimensionProps {
   minHeight?: Reactive<Dimension>
   /** Maximum height (0 = no max) */
   maxHeight?: Reactive<Dimension>
+  /**
+   * ID of an ancestor the `%` dimensions are of, instead of the parent —
+   * e.g. a dropdown as wide as its panel, however deep it sits
+   */
+  relativeTo?: Reactive<string>
+  /** Width ÷ height, with the other dimension derived from the one given */
+  aspectRatio?: Reactive<number>
+  /**
+   * Take `aspectRatio` as the on-screen shape rather than cells: a 1:1 box
+   * comes out square instead of twice as tall as it is wide
+   */
+  aspectCells?: Reactive<boolean>
 }
 
 export interface SpacingProps {
@@ -104,7 +207,7 @@ export interface LayoutProps {
   flexWrap?: Reactive<'nowrap' | 'wrap' | 'wrap-reverse'>
   /** Justify content */
   justifyContent?: Reactive<'flex-start' | 'center' | 'flex-end' | 'space-between' | 'space-around' | 'space-evenly'>
-  /** Align items (container) */
+  /** Align items (container); 'baseline' lines texts up on their first line in rows */
   alignItems?: Reactive<'stretch' | 'flex-start' | 'center' | 'flex-end' | 'baseline'>
   /** Align content (multi-line flex container) */
   alignContent?: Reactive<'flex-start' | 'center' | 'flex-end' | 'space-between' | 'space-around' | 'space-evenly' | 'stretch'>
@@ -118,6 +221,24 @@ export interface LayoutProps {
   flexBasis?: Reactive<number>
   /** Overflow: 'visible' | 'hidden' | 'scroll' | 'auto' */
   overflow?: Reactive<'visible' | 'hidden' | 'scroll' | 'auto'>
+  /** Horizontal overflow, overriding `overflow` on that axis */
+  overflowX?: Reactive<'visible' | 'hidden' | 'scroll' | 'auto'>
+  /** Vertical overflow, overriding `overflow` on that axis */
+  overflowY?: Reactive<'visible' | 'hidden' | 'scroll' | 'auto'>
+  /** Ease scrolling to its target on a spring instead of jumping (default: false) */
+  smoothScroll?: boolean | SpringConfig
+  /** Scrolled to the bottom, stay there as content is added (logs, chat) (default: false) */
+  stickToBottom?: Reactive<boolean>
+  /**
+   * Scrolled down, keep what's showing in place when content above it is
+   * added, removed or resized (default: true)
+   */
+  scrollAnchor?: Reactive<boolean>
+  /**
+   * In a scrolling parent, stay pinned to the top or bottom edge of its
+   * viewport instead of scrolling out of view (table headers, footers)
+   */
+  sticky?: Reactive<'top' | 'bottom' | false>
   /** Z-index for stacking */
   zIndex?: Reactive<number>
   /** Row gap (overrides gap for rows) */
@@ -148,6 +269,9 @@ export type GridAutoFlow = 'row' | 'column' | 'row dense' | 'column dense'
 /** Grid line position: line number, span, or auto */
 export type GridLine = number | `span ${number}` | 'auto'
 
+/** Named grid areas: CSS quoted rows (`'"a a" "b c"'`) or one string per row. `.` = empty cell */
+export type GridTemplateAreas = string | string[]
+
 export interface GridContainerProps {
   /** Display mode: 'flex' | 'grid' | 'none' */
   display?: Reactive<'flex' | 'grid' | 'none'>
@@ -163,6 +287,8 @@ export interface GridContainerProps {
   gridAutoRows?: Reactive<GridTrackSize>
   /** Justify items within their grid area */
   justifyItems?: Reactive<'start' | 'end' | 'center' | 'stretch'>
+  /** Named areas children can be placed into with `gridArea` */
+  gridTemplateAreas?: Reactive<GridTemplateAreas>
 }
 
 export interface GridItemProps {
@@ -174,6 +300,12 @@ export interface GridItemProps {
   gridRowStart?: Reactive<GridLine>
   /** Grid row end line (1-based, or negative for span) */
   gridRowEnd?: Reactive<GridLine>
+  /** Columns to span (ignored when gridColumnEnd is set) */
+  colSpan?: Reactive<number>
+  /** Rows to span (ignored when gridRowEnd is set) */
+  rowSpan?: Reactive<number>
+  /** Named area of the parent's gridTemplateAreas; unknown names auto-place */
+  gridArea?: Reactive<string>
   /** Justify self within grid area */
   justifySelf?: Reactive<'auto' | 'start' | 'end' | 'center' | 'stretch'>
 }
@@ -183,6 +315,8 @@ export interface InteractionProps {
   focusable?: Reactive<boolean>
   /** Tab order for focus navigation (-1 = not in tab order) */
   tabIndex?: Reactive<number>
+  /** Out of focus order, click/press/key handlers ignored, variant shown in its disabled state */
+  disabled?: Reactive<boolean>
 }
 
 export interface MouseProps {
@@ -192,55 +326,144 @@ export interface MouseProps {
   onMouseUp?: (event: MouseEvent) => void | boolean
   /** Called on click (down + up on same component). Return true to consume event. */
   onClick?: (event: MouseEvent) => void | boolean
-  /** Called when mouse enters this component */
+  /** Called on right-click over this component */
+  onContextMenu?: (event: MouseEvent) => void
+  /** Called when mouse enters this component (or anything in it) from outside. Doesn't bubble. */
   onMouseEnter?: (event: MouseEvent) => void
-  /** Called when mouse leaves this component */
+  /** Called when mouse leaves this component and everything in it. Doesn't bubble. */
   onMouseLeave?: (event: MouseEvent) => void
   /** Called on scroll over this component. Return true to consume event. */
   onScroll?: (event: ScrollEvent) => void
+  /**
+   * 'none' lets the mouse through to whatever is beneath, for this component
+   * and its children (default: the parent's, 'auto' at the root). A box
+   * without a background only takes the mouse over its border; 'all' makes
+   * it take its whole area, for backdrops that catch clicks outside a panel.
+   */
+  pointerEvents?: Reactive<'auto' | 'none' | 'all'>
+  /**
+   * Nonzero tag that makes this component (with everything in it) a hover
+   * target: while the mouse is over it, `hoverTarget()` reports this tag and
+   * the component's screen region.
+   */
+  hoverTag?: Reactive<number>
+  /**
+   * Capture-phase handlers: run on the way down, before any handler inside
+   * this component. `event.componentIndex` is the node hit; return true (or
+   * call event.stopPropagation()) to keep the event from it.
+   */
+  onMouseDownCapture?: (event: MouseEvent) => void | boolean
+  onMouseUpCapture?: (event: MouseEvent) => void | boolean
+  onClickCapture?: (event: MouseEvent) => void | boolean
+  onContextMenuCapture?: (event: MouseEvent) => void | boolean
+}
+
+export interface AccessibilityProps {
+  /** What this is to assistive tools, announced after its label */
+  role?: A11yRoleName
+  /** Accessible name (default: the node's text) */
+  label?: Reactive<string>
+  /** Current value, announced after the role ("40%", "checked") */
+  valueText?: Reactive<string>
+  /** Announce changes to label, value or text: 'polite' waits, 'assertive' interrupts (default: 'off') */
+  live?: 'off' | 'polite' | 'assertive'
 }
 
 // =============================================================================
 // BOX PROPS
 // =============================================================================
 
-export interface BoxProps extends StyleProps, BorderProps, DimensionProps, SpacingProps, LayoutProps, GridContainerProps, GridItemProps, InteractionProps, MouseProps {
+export interface BoxProps extends StyleProps, BorderProps, DimensionProps, SpacingProps, LayoutProps, GridContainerProps, GridItemProps, InteractionProps, MouseProps, AccessibilityProps {
   /** Component ID (optional, auto-generated if not provided) */
   id?: string
   /** Is visible */
   visible?: Reactive<boolean>
   /** Children renderer */
   children?: () => void
+  /** Keep Tab and spatial navigation inside this box while it's visible (the newest trap wins) */
+  trapFocus?: boolean
+  /** Tab and Shift+Tab inside this box reach its onKey handlers instead of moving focus */
+  captureTab?: boolean
   /**
    * Style variant - applies theme colors automatically.
    * Variants: 'default' | 'primary' | 'secondary' | 'success' | 'warning' | 'error' | 'info' | 'ghost' | 'outline'
    */
   variant?: Variant
   /**
-   * Keyboard handler - fires only when this box has focus.
-   * Return true to consume the event (prevent propagation).
-   * Requires focusable: true or overflow: 'scroll'.
+   * Gradient or pattern background (overrides bg).
+   * Colors may be theme names: `{ type: 'linear-gradient', from: 'primary', to: 'secondary' }`
+   */
+  bgFill?: Reactive<BackgroundFillProp>
+  /**
+   * Keep this box's rendered cells and reuse them while nothing inside it
+   * changes. For large, mostly static subtrees (sidebars, headers).
+   */
+  cache?: Reactive<boolean>
+  /** Link the whole box, and everything in it, to this URL (OSC 8 hyperlink) */
+  href?: Reactive<string>
+  /**
+   * Direction inherited by the text inside (default: the parent's, 'auto' at
+   * the root). 'rtl' also lays rows out right to left.
+   */
+  direction?: Reactive<'ltr' | 'rtl' | 'auto'>
+  /**
+   * Keyboard handler - fires when this box or anything inside it has focus,
+   * innermost handlers first. Return true (or call event.stopPropagation())
+   * to consume the event (prevent propagation).
    */
   onKey?: KeyHandler
   /** Called when this box receives focus */
   onFocus?: () => void
   /** Called when this box loses focus */
   onBlur?: () => void
+  /**
+   * Called with the new offset whenever the box is scrolled — by keys, wheel,
+   * scroll-into-view, stickToBottom, each step of a smoothScroll spring, or
+   * restored layout state
+   */
+  onScrollChange?: (x: number, y: number) => void
+  /** Kept up to date with the box's scroll offset, for deriving from it */
+  scrollPosition?: WritableSignal<ScrollPosition>
+  /**
+   * Called when the box scrolls (or its content shrinks) to within
+   * `reachEndThreshold` rows of the bottom, and again each time its content
+   * changes size while still that close — fetch the next page here
+   */
+  onReachEnd?: () => void
+  /** Rows short of the bottom that count as reaching the end (default: 0) */
+  reachEndThreshold?: Reactive<number>
+}
+
+/** A scroll offset in cells */
+export interface ScrollPosition {
+  x: number
+  y: number
 }
 
 // =============================================================================
 // TEXT PROPS
 // =============================================================================
 
-export interface TextProps extends StyleProps, DimensionProps, SpacingProps, LayoutProps, GridItemProps, MouseProps {
+export interface TextProps extends StyleProps, DimensionProps, SpacingProps, LayoutProps, GridItemProps, MouseProps, AccessibilityProps {
   /** Component ID (optional, auto-generated if not provided) */
   id?: string
   /** Text content (strings and numbers auto-converted) */
   content: Reactive<string | number>
-  /** Text alignment: 'left' | 'center' | 'right' */
-  align?: Reactive<'left' | 'center' | 'right'>
-  /** Text wrapping: 'wrap' | 'nowrap' | 'truncate' */
-  wrap?: Reactive<'wrap' | 'nowrap' | 'truncate'>
+  /**
+   * Text alignment: 'start' (default) and 'end' follow each paragraph's
+   * direction; 'justify' stretches wrapped lines to the full width
+   */
+  align?: Reactive<'left' | 'center' | 'right' | 'start' | 'end' | 'justify'>
+  /**
+   * Base direction for bidirectional text (default: the parent's). 'auto'
+   * takes each paragraph's direction from its first strong character.
+   */
+  direction?: Reactive<'ltr' | 'rtl' | 'auto'>
+  /**
+   * Text wrapping: 'wrap' | 'nowrap' | 'truncate', or 'hyphenate' to also
+   * break words at soft hyphens ('\u00AD'), drawing a '-' there
+   */
+  wrap?: Reactive<'wrap' | 'nowrap' | 'truncate' | 'hyphenate'>
   /** Is visible */
   visible?: Reactive<boolean>
   /**
@@ -248,6 +471,11 @@ export interface TextProps extends StyleProps, DimensionProps, SpacingProps, Lay
    * Variants: 'default' | 'primary' | 'secondary' | 'success' | 'warning' | 'error' | 'info' | 'ghost' | 'outline'
    */
   variant?: Variant
+  /**
+   * Make the text a hyperlink (OSC 8): clickable in terminals that support
+   * it, e.g. `https://…` or `file://host/path`
+   */
+  href?: Reactive<string>
 
   // ==========================================================================
   // TEXT STYLE ATTRIBUTES
@@ -304,7 +532,7 @@ export interface CursorConfig {
   bg?: Reactive<RGBA>
 }
 
-export interface InputProps extends StyleProps, BorderProps, DimensionProps, SpacingProps, LayoutProps, GridItemProps, InteractionProps, MouseProps {
+export interface InputProps extends StyleProps, BorderProps, DimensionProps, SpacingProps, LayoutProps, GridItemProps, InteractionProps, MouseProps, AccessibilityProps {
   /** Component ID (optional, auto-generated if not provided) */
   id?: string
   /** Current value (two-way bound) */
@@ -327,8 +555,43 @@ export interface InputProps extends StyleProps, BorderProps, DimensionProps, Spa
   password?: boolean
   /** Password mask character (default: '•') */
   maskChar?: string
+  /**
+   * What the input accepts, enforced per keystroke: 'number' (see min, max,
+   * decimal), 'date' (see dateFormat) or 'pattern' (see mask). Pair with
+   * numberValue / dateValue / patternValue for the parsed value.
+   */
+  type?: 'text' | 'number' | 'date' | 'pattern'
+  /** Number: lowest value. A minus sign is accepted only when this is unset or negative. */
+  min?: number
+  /** Number: highest value. The value is clamped into min/max on blur. */
+  max?: number
+  /** Number: accept one decimal point (default: false) */
+  decimal?: boolean
+  /** Date: layout of DD, MM and YYYY (or YY) segments (default: 'DD/MM/YYYY') */
+  dateFormat?: string
+  /** Pattern: `9` digit, `a` letter, `*` either, anything else typed for you — '(999) 999-9999' */
+  mask?: string
+  /** Reject any keystroke that would leave a value not matching this, e.g. /^[A-Z]{0,3}$/ */
+  accept?: RegExp
   /** Cursor configuration */
   cursor?: CursorConfig
+  /**
+   * Inline completion provider. Returns the text to suggest after the current
+   * value (rendered dimmed past the cursor), or null for no suggestion.
+   * Right/Tab at the end of the value accepts it; typing dismisses it.
+   */
+  completion?: (value: string) => string | null | undefined
+  /**
+   * Submitted values are added to it; Up/Down recall them (filtered by what
+   * was typed first) and Ctrl+R searches them. See createHistory().
+   */
+  history?: History
+  /**
+   * Word rule for Ctrl+arrow motion, word deletion and double-click selection.
+   * 'unicode' (default) follows UAX #29; 'identifier' keeps snake_case together;
+   * 'whitespace' treats paths and URLs as one word.
+   */
+  wordBoundary?: 'unicode' | 'alphanumeric' | 'identifier' | 'whitespace'
   /**
    * Style variant - applies theme colors automatically.
    * Variants: 'default' | 'primary' | 'secondary' | 'success' | 'warning' | 'error' | 'info' | 'ghost' | 'outline'
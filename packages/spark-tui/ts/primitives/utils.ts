@@ -5,7 +5,12 @@
  * Most conversion functions are now inline in each primitive.
  */
 
-import { isBinding, type WritableSignal, type Binding } from '@rlabs-inc/signals'
+import { isBinding, repeat, type WritableSignal, type Binding } from '@rlabs-inc/signals'
+import type { Dimension } from '../types'
+import type { ReactiveArrays } from '../bridge/reactive-arrays'
+import { PercentBasis, PointerEvents } from '../bridge/shared-buffer'
+import { getIndex } from '../engine/registry'
+import type { DimensionProps } from './types'
 
 // =============================================================================
 // TYPE GUARDS
@@ -36,6 +41,17 @@ export function getValue<T>(prop: T | { value: T } | (() => T) | undefined, defa
   return prop as T
 }
 
+/**
+ * Read a prop's current value: calls getters, reads `.value` off signals
+ * and bindings, passes static values through. Tracked when called inside
+ * a derived or effect.
+ */
+export function unwrap<T>(prop: T | { readonly value: T } | (() => T)): T {
+  if (typeof prop === 'function') return (prop as () => T)()
+  if (prop !== null && typeof prop === 'object' && 'value' in prop) return (prop as { value: T }).value
+  return prop as T
+}
+
 // =============================================================================
 // ENUM SOURCE
 // =============================================================================
@@ -61,3 +77,77 @@ export function enumSource<T extends string>(
   // Static value
   return converter(prop as T | undefined)
 }
+
+/** `pointerEvents` prop → PointerEvents (unset inherits the parent's) */
+export function pointerEventsToNum(value: 'auto' | 'none' | 'all' | undefined): number {
+  switch (value) {
+    case 'auto': return PointerEvents.Auto
+    case 'none': return PointerEvents.None
+    case 'all': return PointerEvents.All
+    default: return PointerEvents.Inherit
+  }
+}
+
+// =============================================================================
+// PERCENT BASES
+// =============================================================================
+
+/**
+ * Dimension → Taffy float: NaN = auto, negative = percentage, positive = pixels.
+ * '50vw' and '50vh' are percentages too (-50.0); bindPercentBases writes what of.
+ */
+export function toDim(dim: number | string | undefined | null): number {
+  if (dim === undefined || dim === null || dim === 0) return NaN
+  if (typeof dim === 'string') {
+    if (dim.endsWith('%') || dim.endsWith('vw') || dim.endsWith('vh')) return -parseFloat(dim) // '100%' → -100.0
+    return parseFloat(dim) || NaN
+  }
+  return dim
+}
+
+const BASIS_FIELDS = [
+  ['width', 'widthBasis'],
+  ['height', 'heightBasis'],
+  ['minWidth', 'minWidthBasis'],
+  ['minHeight', 'minHeightBasis'],
+  ['maxWidth', 'maxWidthBasis'],
+  ['maxHeight', 'maxHeightBasis'],
+] as const
+
+/** What a dimension is a percentage of: vw/vh, the `relativeTo` ancestor, or the parent */
+function percentBasis(dim: Dimension | undefined, named: boolean): PercentBasis {
+  if (typeof dim !== 'string') return PercentBasis.Parent
+  if (dim.endsWith('vw')) return PercentBasis.ViewportWidth
+  if (dim.endsWith('vh')) return PercentBasis.ViewportHeight
+  return named ? PercentBasis.Named : PercentBasis.Parent
+}
+
+/**
+ * Write what each percentage dimension is of — the viewport for `vw`/`vh`,
+ * the `relativeTo` ancestor for `%` when there is one — next to the values
+ * the primitive writes. Dimensions that can only ever be of the parent are
+ * left at the default.
+ *
+ * @returns Disposers for the bindings
+ */
+export function bindPercentBases(props: DimensionProps, arrays: ReactiveArrays, index: number): (() => void)[] {
+  const disposals: (() => void)[] = []
+  const { relativeTo } = props
+  const named = relativeTo !== undefined
+
+  if (named) {
+    disposals.push(repeat(() => getIndex(unwrap(relativeTo)) ?? -1, arrays.relativeTo, index))
+  }
+  for (const [prop, field] of BASIS_FIELDS) {
+    const dim = props[prop]
+    if (dim === undefined) continue
+    // Static numbers and plain percentages of the parent: the default already says so
+    if (!named && (typeof dim === 'number' || (typeof dim === 'string' && dim.endsWith('%')))) continue
+    const source = typeof dim === 'function' || typeof dim === 'object'
+      ? () => percentBasis(unwrap(dim), named)
+      : percentBasis(dim, named)
+    disposals.push(repeat(source, arrays[field], index))
+  }
+  return disposals
+}
+
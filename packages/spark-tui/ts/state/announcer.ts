@@ -0,0 +1,163 @@
+/**
+ * SparkTUI Announcer
+ *
+ * Turns accessibility metadata into spoken-style messages for assistive
+ * tooling. Nodes carry a role, label, value and live-region setting in the
+ * SharedBuffer (`role`, `label`, `valueText`, `live` props); the announcer
+ * reads them back and hands messages to a sink:
+ *
+ * - focus moves           → "Save, button"
+ * - a live region changes → "3 results"
+ * - `announce(message)`   → anything the app wants said
+ *
+ * A sink is any function; mount's `announcer` option also accepts 'notify'
+ * (desktop notifications, OSC 9) and 'title' (the window title, which
+ * screen readers speak when it changes).
+ *
+ * ```ts
+ * mount(App, { announcer: 'notify' })
+ * setAnnouncer(({ message }) => speechQueue.push(message))
+ * text({ content: () => `${results.value.length} results`, live: 'polite' })
+ * ```
+ *
+ * Polite messages wait for the current batch of updates to settle (a
+ * microtask) and collapse to the latest per node; assertive ones go out
+ * at once and drop whatever polite ones were waiting.
+ *
+ * PURELY REACTIVE: focus announcements follow the focusedIndex signal.
+ */
+
+import { signal, effect } from '@rlabs-inc/signals'
+import { isInitialized, getBuffer } from '../bridge'
+import {
+  getA11yLabel,
+  getA11yValue,
+  getText,
+  getU8,
+  A11yRole,
+  InputType,
+  N_A11Y_ROLE,
+  N_INPUT_TYPE,
+} from '../bridge/shared-buffer'
+import { focusedIndex } from './focus'
+
+export type Politeness = 'polite' | 'assertive'
+
+export interface Announcement {
+  message: string
+  politeness: Politeness
+  /** Node the message is about, -1 for `announce()` calls */
+  index: number
+}
+
+export type AnnouncerSink = (announcement: Announcement) => void
+
+/** Role names, indexed by A11yRole */
+export const ROLE_NAMES = [
+  '', 'button', 'checkbox', 'radio button', 'switch', 'text box', 'link',
+  'list', 'list item', 'menu', 'menu item', 'tab', 'tab panel', 'dialog',
+  'alert', 'status', 'heading', 'progress bar', 'slider', 'table', 'group',
+] as const
+
+export type A11yRoleName = Exclude<(typeof ROLE_NAMES)[number], ''>
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+let sink: AnnouncerSink | null = null
+let stopFocus: (() => void) | null = null
+
+/** Polite messages waiting for the microtask, latest per node */
+const pending = new Map<number, Announcement>()
+let flushQueued = false
+
+/** The last message sent, for apps that show it on screen */
+export const lastAnnouncement = signal<Announcement | null>(null)
+
+// =============================================================================
+// API
+// =============================================================================
+
+/**
+ * Send announcements to `target`, or stop with null. While a sink is set,
+ * focus changes are announced too.
+ */
+export function setAnnouncer(target: AnnouncerSink | null): void {
+  sink = target
+  pending.clear()
+  stopFocus?.()
+  stopFocus = null
+  if (!target) return
+
+  let previous = focusedIndex.value
+  stopFocus = effect(() => {
+    const index = focusedIndex.value
+    if (index === previous) return
+    previous = index
+    if (index < 0) return
+    const message = describe(index)
+    if (message) announce(message, 'polite', index)
+  })
+}
+
+/** Whether announcements currently go anywhere. */
+export function hasAnnouncer(): boolean {
+  return sink !== null
+}
+
+/** Say `message` through the sink. No-op without one. */
+export function announce(message: string, politeness: Politeness = 'polite', index = -1): void {
+  if (!sink || !message) return
+  const announcement: Announcement = { message, politeness, index }
+
+  if (politeness === 'assertive') {
+    pending.clear()
+    deliver(announcement)
+    return
+  }
+
+  // Manual announcements never collapse into each other
+  pending.set(index < 0 ? -1 - pending.size : index, announcement)
+  if (!flushQueued) {
+    flushQueued = true
+    queueMicrotask(flushPending)
+  }
+}
+
+/**
+ * What a node is, as it would be announced: label (or its text), role,
+ * then value — "Save, button", "Volume, slider, 40%". Password inputs
+ * never read their text.
+ */
+export function describe(index: number): string {
+  if (!isInitialized()) return ''
+  const buf = getBuffer()
+  const role = getU8(buf, index, N_A11Y_ROLE) as A11yRole
+  const password = getU8(buf, index, N_INPUT_TYPE) === InputType.Password
+  const label = getA11yLabel(buf, index) || (password ? '' : getText(buf, index).trim())
+  return [label, ROLE_NAMES[role] ?? '', getA11yValue(buf, index)].filter(Boolean).join(', ')
+}
+
+/** A11yRole for a role name (None for unknown names). */
+export function roleFromName(name: A11yRoleName | undefined): A11yRole {
+  const role = name ? ROLE_NAMES.indexOf(name) : -1
+  return role > 0 ? (role as A11yRole) : A11yRole.None
+}
+
+// =============================================================================
+// INTERNAL
+// =============================================================================
+
+function flushPending(): void {
+  flushQueued = false
+  const batch = [...pending.values()]
+  pending.clear()
+  for (const announcement of batch) deliver(announcement)
+}
+
+function deliver(announcement: Announcement): void {
+  if (!sink) return
+  lastAnnouncement.value = announcement
+  sink(announcement)
+}
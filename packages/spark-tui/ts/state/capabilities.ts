@@ -0,0 +1,152 @@
+/**
+ * SparkTUI Terminal Capabilities
+ *
+ * Reactive facts about the terminal, for adapting presentation — think
+ * media queries:
+ *
+ * - colorDepth: 'mono' | '16' | '256' | 'truecolor'
+ * - unicode: 'full' | 'basic' (no wide glyphs) | 'ascii'
+ * - mouse: whether mouse events are reported at all
+ * - cellPixels: cell size in pixels, once the terminal answers (null before)
+ * - cellAspect: cell height ÷ width that `aspectCells` boxes are corrected
+ *   by — the mount's `cellAspect`, else measured from cellPixels, else 2
+ * - palette: the terminal's actual default foreground and background, as
+ *   far as it answered the OSC 10/11 queries
+ *
+ * Rust detects them at startup and re-evaluates on resize and focus-in (a
+ * tmux reattach from another terminal), so reads inside deriveds and props
+ * update by themselves:
+ *
+ * ```ts
+ * box({ bg: () => capabilities.colorDepth === 'truecolor' ? gradientBg : t.surface })
+ * text({ content: () => capabilities.unicode === 'ascii' ? '*' : '●' })
+ * ```
+ *
+ * Borders already fall back to ASCII in Rust when unicode is 'ascii'.
+ */
+
+import { signal } from '@rlabs-inc/signals'
+import type { RGBA } from '../types'
+import { isInitialized, getBuffer } from '../bridge'
+import {
+  ColorDepth,
+  UnicodeLevel,
+  getColorDepth,
+  getUnicodeLevel,
+  getMouseAvailable,
+  getCellPixelWidth,
+  getCellPixelHeight,
+  getCellAspect,
+  setCellAspect,
+} from '../bridge/shared-buffer'
+import {
+  registerCapabilitiesHandler,
+  registerTerminalColorHandler,
+  TERMINAL_COLOR_FOREGROUND,
+  TERMINAL_COLOR_BACKGROUND,
+  type TerminalColorEvent,
+} from '../engine/events'
+
+export type ColorDepthName = 'mono' | '16' | '256' | 'truecolor'
+export type UnicodeSupport = 'full' | 'basic' | 'ascii'
+
+export interface CellPixels {
+  width: number
+  height: number
+}
+
+const colorDepth = signal<ColorDepthName>('16')
+const unicode = signal<UnicodeSupport>('full')
+const mouse = signal(false)
+const cellPixels = signal<CellPixels | null>(null)
+const configuredAspect = signal(0)
+
+/** Cells are about twice as tall as wide when nothing says otherwise */
+const DEFAULT_CELL_ASPECT = 2
+
+/** Colors the terminal reported; null where it hasn't (or can't) */
+export interface TerminalPalette {
+  foreground: RGBA | null
+  background: RGBA | null
+}
+
+const palette = signal<TerminalPalette>({ foreground: null, background: null })
+
+function depthName(depth: ColorDepth): ColorDepthName {
+  switch (depth) {
+    case ColorDepth.Mono: return 'mono'
+    case ColorDepth.Ansi256: return '256'
+    case ColorDepth.TrueColor: return 'truecolor'
+    default: return '16'
+  }
+}
+
+function unicodeName(level: UnicodeLevel): UnicodeSupport {
+  switch (level) {
+    case UnicodeLevel.Basic: return 'basic'
+    case UnicodeLevel.Ascii: return 'ascii'
+    default: return 'full'
+  }
+}
+
+/** Copy the published header slots into the signals (only what changed propagates) */
+function sync(): void {
+  if (!isInitialized()) return
+  const buf = getBuffer()
+  colorDepth.value = depthName(getColorDepth(buf))
+  unicode.value = unicodeName(getUnicodeLevel(buf))
+  mouse.value = getMouseAvailable(buf)
+  configuredAspect.value = getCellAspect(buf)
+
+  const width = getCellPixelWidth(buf)
+  const height = getCellPixelHeight(buf)
+  const current = cellPixels.value
+  if (width === 0 || height === 0) {
+    if (current !== null) cellPixels.value = null
+  } else if (current?.width !== width || current?.height !== height) {
+    cellPixels.value = { width, height }
+  }
+}
+
+/** Rust only sends colors that changed, so every event is a new palette */
+function paletteColor({ slot, r, g, b }: TerminalColorEvent): void {
+  const color: RGBA = { r, g, b, a: 255 }
+  const current = palette.value
+  if (slot === TERMINAL_COLOR_FOREGROUND) {
+    palette.value = { ...current, foreground: color }
+  } else if (slot === TERMINAL_COLOR_BACKGROUND) {
+    palette.value = { ...current, background: color }
+  }
+}
+
+/** Follow capability changes for this mount. Called by mount() */
+export function trackCapabilities(): () => void {
+  sync()
+  const stopCapabilities = registerCapabilitiesHandler(sync)
+  const stopPalette = registerTerminalColorHandler(paletteColor)
+  return () => {
+    stopCapabilities()
+    stopPalette()
+  }
+}
+
+/** Set the cell aspect (0 = measured). Called by mount() and its handle */
+export function configureCellAspect(aspect: number): void {
+  if (!isInitialized()) return
+  const buf = getBuffer()
+  setCellAspect(buf, aspect)
+  configuredAspect.value = getCellAspect(buf)
+}
+
+/** Current terminal capabilities — reactive when read in deriveds, effects and props */
+export const capabilities = {
+  get colorDepth(): ColorDepthName { return colorDepth.value },
+  get unicode(): UnicodeSupport { return unicode.value },
+  get mouse(): boolean { return mouse.value },
+  get cellPixels(): CellPixels | null { return cellPixels.value },
+  get cellAspect(): number {
+    const pixels = cellPixels.value
+    return configuredAspect.value || (pixels ? pixels.height / pixels.width : DEFAULT_CELL_ASPECT)
+  },
+  get palette(): TerminalPalette { return palette.value },
+}
@@ -0,0 +1,123 @@
+/**
+ * SparkTUI Color-Vision Tools
+ *
+ * Developer tools for checking that a theme stays readable with a
+ * color-vision deficiency:
+ *
+ * - setColorVisionOverlay(): Rust re-renders the whole frame as seen with
+ *   protanopia, deuteranopia or tritanopia (toggle off with null).
+ * - auditThemeContrast(): lists theme color pairs that pass contrast with
+ *   normal vision but collapse under a simulation.
+ */
+
+import { isInitialized, getBuffer, getNotifier } from '../bridge'
+import { ColorVision, setColorVision, getColorVision } from '../bridge/shared-buffer'
+import type { RGBA } from '../types'
+import { contrastRatio, simulateColorVision, isAnsiColor, isTerminalDefault } from '../types/color'
+import type { ColorVisionMode } from '../types/color'
+import { resolvedTheme, getVariantStyle } from './theme'
+import type { Variant } from './theme'
+
+export type { ColorVisionMode }
+
+const SIMULATIONS: ColorVisionMode[] = ['protanopia', 'deuteranopia', 'tritanopia']
+
+// =============================================================================
+// OVERLAY
+// =============================================================================
+
+function modeToEnum(mode: ColorVisionMode | null): ColorVision {
+  switch (mode) {
+    case 'protanopia': return ColorVision.Protanopia
+    case 'deuteranopia': return ColorVision.Deuteranopia
+    case 'tritanopia': return ColorVision.Tritanopia
+    default: return ColorVision.None
+  }
+}
+
+/** Show the rendered UI as seen with a color-vision deficiency (null turns it off) */
+export function setColorVisionOverlay(mode: ColorVisionMode | null): void {
+  if (!isInitialized()) return
+  setColorVision(getBuffer(), modeToEnum(mode))
+  getNotifier().notify()
+}
+
+/** Currently simulated deficiency, or null when the overlay is off */
+export function getColorVisionOverlay(): ColorVisionMode | null {
+  if (!isInitialized()) return null
+  switch (getColorVision(getBuffer())) {
+    case ColorVision.Protanopia: return 'protanopia'
+    case ColorVision.Deuteranopia: return 'deuteranopia'
+    case ColorVision.Tritanopia: return 'tritanopia'
+    default: return null
+  }
+}
+
+// =============================================================================
+// AUDIT
+// =============================================================================
+
+/** A theme pair that loses its contrast under a simulation */
+export interface ContrastFinding {
+  /** Which pair, e.g. 'textMuted on background' or 'variant primary' */
+  pair: string
+  fg: RGBA
+  bg: RGBA
+  mode: ColorVisionMode
+  /** Contrast with normal vision */
+  normal: number
+  /** Contrast under the simulation */
+  simulated: number
+}
+
+const AUDIT_VARIANTS: Variant[] = [
+  'primary', 'secondary', 'tertiary', 'accent',
+  'success', 'warning', 'error', 'info',
+]
+
+/** Theme pairs worth auditing: text on each background, and variant fills */
+function themePairs(): Array<[string, RGBA, RGBA]> {
+  const r = resolvedTheme.value
+  const pairs: Array<[string, RGBA, RGBA]> = []
+
+  const fgs = { text: r.text, textMuted: r.textMuted, textBright: r.textBright,
+    primary: r.primary, success: r.success, warning: r.warning, error: r.error, info: r.info }
+  const bgs = { background: r.background, surface: r.surface }
+
+  for (const [bgName, bg] of Object.entries(bgs)) {
+    for (const [fgName, fg] of Object.entries(fgs)) {
+      pairs.push([`${fgName} on ${bgName}`, fg, bg])
+    }
+  }
+  for (const variant of AUDIT_VARIANTS) {
+    const style = getVariantStyle(variant)
+    pairs.push([`variant ${variant}`, style.fg, style.bg])
+  }
+
+  return pairs
+}
+
+/**
+ * Flag theme pairs that meet `minRatio` with normal vision but fall below
+ * it under a simulated deficiency. Pairs involving terminal palette colors
+ * (ANSI/default) are skipped — only the terminal knows their real value.
+ */
+export function auditThemeContrast(minRatio: number = 4.5): ContrastFinding[] {
+  const findings: ContrastFinding[] = []
+
+  for (const [pair, fg, bg] of themePairs()) {
+    if (isAnsiColor(fg) || isAnsiColor(bg) || isTerminalDefault(fg) || isTerminalDefault(bg)) continue
+
+    const normal = contrastRatio(fg, bg)
+    if (normal < minRatio) continue
+
+    for (const mode of SIMULATIONS) {
+      const simulated = contrastRatio(simulateColorVision(fg, mode), simulateColorVision(bg, mode))
+      if (simulated < minRatio) {
+        findings.push({ pair, fg, bg, mode, normal, simulated })
+      }
+    }
+  }
+
+  return findings
+}
@@ -27,9 +27,30 @@
  * // Update context - all consumers re-render
  * provide(ThemeContext, lightTheme)
  * ```
+ *
+ * Scoping: `provide` called while a component's children are being created
+ * provides for that component's subtree only. `useContext` walks up the
+ * parent-index chain from the point of creation and takes the nearest
+ * provider, then the app-wide one (`provide` outside any component), then
+ * the default. Nested subtrees can override a context without globals:
+ *
+ * ```ts
+ * box({ children: () => {
+ *   provide(ThemeContext, lightTheme)
+ *   Sidebar()                      // sees lightTheme
+ * }})
+ * Main()                           // sees the app-wide theme
+ * ```
+ *
+ * The provider is resolved when `useContext` is called, so call it while
+ * creating the component. `useContextSignal` returns a signal that keeps
+ * tracking the resolved provider for use in getters.
  */
 
-import { ReactiveMap, signal, type WritableSignal } from '@rlabs-inc/signals'
+import { ReactiveMap, derived, signal, type ReadableSignal, type WritableSignal } from '@rlabs-inc/signals'
+import { getCurrentParentIndex } from '../engine/registry'
+import { onDestroyOf } from '../engine/lifecycle'
+import { getArrays, isInitialized } from '../bridge'
 
 // =============================================================================
 // Context Types
@@ -70,6 +91,38 @@ const contextValues = new ReactiveMap<symbol, unknown>()
  */
 const contextSignals = new ReactiveMap<symbol, WritableSignal<unknown>>()
 
+/**
+ * Subtree providers: component index → context id → provided value (static
+ * or a signal). Dropped when the component is destroyed.
+ */
+const scopedValues = new Map<number, Map<symbol, WritableSignal<unknown>>>()
+
+/** Signals carry a Symbol('signal.source') property that plain objects don't have */
+function isSignal(value: unknown): value is WritableSignal<unknown> {
+  return (
+    value !== null &&
+    typeof value === 'object' &&
+    'value' in value &&
+    Object.getOwnPropertySymbols(value).some((sym) => sym.description === 'signal.source')
+  )
+}
+
+/** Nearest subtree provider of `id`, walking up from the current parent */
+function findScoped(id: symbol): WritableSignal<unknown> | undefined {
+  if (!isInitialized()) return undefined
+  const arrays = getArrays()
+  for (let index = getCurrentParentIndex(); index >= 0; index = arrays.parentIndex.get(index)) {
+    const provided = scopedValues.get(index)?.get(id)
+    if (provided) return provided
+  }
+  return undefined
+}
+
+function unwrap(provided: WritableSignal<unknown>): unknown {
+  const value = provided.value
+  return isSignal(value) ? value.value : value
+}
+
 // =============================================================================
 // Context API
 // =============================================================================
@@ -98,7 +151,8 @@ export function createContext<T>(defaultValue: T, displayName?: string): Context
 /**
  * Provide a value for a context.
  *
- * Can be called multiple times to update the context value.
+ * Inside a component's children, provides for that subtree; elsewhere,
+ * app-wide. Can be called multiple times to update the context value.
  * All consumers using useContext() will automatically update.
  *
  * @param context - The context to provide
@@ -116,14 +170,21 @@ export function createContext<T>(defaultValue: T, displayName?: string): Context
  * ```
  */
 export function provide<T>(context: Context<T>, value: T | WritableSignal<T>): void {
-  // Check if value is a signal from @rlabs-inc/signals
-  // Signals have a Symbol('signal.source') property that plain objects don't have
-  if (
-    value !== null &&
-    typeof value === 'object' &&
-    'value' in value &&
-    Object.getOwnPropertySymbols(value).some((sym) => sym.description === 'signal.source')
-  ) {
+  const scope = getCurrentParentIndex()
+  if (scope >= 0) {
+    let provided = scopedValues.get(scope)
+    if (!provided) {
+      provided = new Map()
+      scopedValues.set(scope, provided)
+      onDestroyOf(scope, () => scopedValues.delete(scope))
+    }
+    const existing = provided.get(context.id)
+    if (existing) existing.value = value
+    else provided.set(context.id, signal<unknown>(value))
+    return
+  }
+
+  if (isSignal(value)) {
     // It's a signal - store it directly for reactive access
     contextSignals.set(context.id, value as WritableSignal<unknown>)
     contextValues.set(context.id, value)
@@ -162,6 +223,9 @@ export function provide<T>(context: Context<T>, value: T | WritableSignal<T>): v
  * ```
  */
 export function useContext<T>(context: Context<T>): T {
+  const scoped = findScoped(context.id)
+  if (scoped) return unwrap(scoped) as T
+
   // Check for signal wrapper first (for .value reactivity)
   const sig = contextSignals.get(context.id)
   if (sig !== undefined) {
@@ -178,21 +242,49 @@ export function useContext<T>(context: Context<T>): T {
 }
 
 /**
- * Check if a context has been provided.
+ * Resolve a context's provider now and return a signal of its value.
+ *
+ * For getters and effects that run after creation, when the parent chain
+ * is no longer current.
+ *
+ * @example
+ * ```ts
+ * function ThemedText(content: string) {
+ *   const theme = useContextSignal(ThemeContext)
+ *   return text({ content, fg: () => theme.value.foreground })
+ * }
+ * ```
+ */
+export function useContextSignal<T>(context: Context<T>): ReadableSignal<T> {
+  const scoped = findScoped(context.id)
+  if (scoped) return derived(() => unwrap(scoped) as T)
+  return derived(() => useContext(context))
+}
+
+/**
+ * Check if a context has been provided (for this subtree or app-wide).
  *
  * @param context - The context to check
  * @returns true if provide() has been called for this context
  */
 export function hasContext<T>(context: Context<T>): boolean {
-  return contextValues.has(context.id)
+  return findScoped(context.id) !== undefined || contextValues.has(context.id)
 }
 
 /**
  * Clear a context, returning to default value.
  *
+ * Inside a component's children, clears that subtree's provider; elsewhere,
+ * the app-wide one.
+ *
  * @param context - The context to clear
  */
 export function clearContext<T>(context: Context<T>): void {
+  const scope = getCurrentParentIndex()
+  if (scope >= 0) {
+    scopedValues.get(scope)?.delete(context.id)
+    return
+  }
   contextValues.delete(context.id)
   contextSignals.delete(context.id)
 }
@@ -207,4 +299,5 @@ export function clearContext<T>(context: Context<T>): void {
 export function resetContexts(): void {
   contextValues.clear()
   contextSignals.clear()
+  scopedValues.clear()
 }
@@ -9,7 +9,7 @@
  */
 
 import { signal, derived } from '@rlabs-inc/signals'
-import { isInitialized, getBuffer } from '../bridge'
+import { isInitialized, getBuffer, bridgeState } from '../bridge'
 import type { FocusEvent } from '../engine/events'
 import { EventType, registerFocusHandler } from '../engine/events'
 
@@ -40,11 +40,13 @@ export const hasFocus = derived(() => focusedIndexSignal.value >= 0)
 // ID <-> INDEX MAPPING
 // =============================================================================
 
-/** Map from component ID to index for programmatic focus */
-const idToIndex = new Map<string, number>()
-
-/** Map from index to ID for reverse lookup */
-const indexToId = new Map<number, string>()
+/** The active instance's ID mappings: indices are per bridge */
+const mappings = bridgeState(() => ({
+  /** Map from component ID to index for programmatic focus */
+  idToIndex: new Map<string, number>(),
+  /** Map from index to ID for reverse lookup */
+  indexToId: new Map<number, string>(),
+}))
 
 /**
  * Register an ID to index mapping.
@@ -52,6 +54,7 @@ const indexToId = new Map<number, string>()
  * @internal
  */
 export function _registerIdMapping(id: string, index: number): void {
+  const { idToIndex, indexToId } = mappings()
   idToIndex.set(id, index)
   indexToId.set(index, id)
 }
@@ -62,6 +65,7 @@ export function _registerIdMapping(id: string, index: number): void {
  * @internal
  */
 export function _unregisterIdMapping(id: string): void {
+  const { idToIndex, indexToId } = mappings()
   const index = idToIndex.get(id)
   if (index !== undefined) {
     indexToId.delete(index)
@@ -75,6 +79,7 @@ export function _unregisterIdMapping(id: string): void {
  * @internal
  */
 export function _unregisterIndexMapping(index: number): void {
+  const { idToIndex, indexToId } = mappings()
   const id = indexToId.get(index)
   if (id !== undefined) {
     idToIndex.delete(id)
@@ -131,6 +136,7 @@ export function _updateFocus(event: FocusEvent): void {
  * ```
  */
 export function useFocusedId(): { readonly value: string | null } {
+  const { indexToId } = mappings()
   return derived(() => {
     const index = focusedIndexSignal.value
     if (index < 0) return null
@@ -145,7 +151,7 @@ export function useFocusedId(): { readonly value: string | null } {
  * @param indexOrId - Component index or ID
  */
 export function isFocused(indexOrId: number | string): { readonly value: boolean } {
-  const index = typeof indexOrId === 'number' ? indexOrId : idToIndex.get(indexOrId)
+  const index = typeof indexOrId === 'number' ? indexOrId : mappings().idToIndex.get(indexOrId)
 
   return derived(() => {
     if (index === undefined) return false
@@ -176,7 +182,7 @@ export function isFocused(indexOrId: number | string): { readonly value: boolean
 export function focus(indexOrId: number | string): void {
   const index = typeof indexOrId === 'number'
     ? indexOrId
-    : idToIndex.get(indexOrId)
+    : mappings().idToIndex.get(indexOrId)
 
   if (index === undefined) {
     if (typeof indexOrId === 'string') {
@@ -283,8 +289,8 @@ interface FocusCallbacks {
   onBlur?: () => void
 }
 
-/** Registered focus callbacks per component */
-const focusCallbacks = new Map<number, FocusCallbacks>()
+/** Registered focus callbacks per component, per instance */
+const focusCallbacks = bridgeState(() => new Map<number, FocusCallbacks>())
 
 /**
  * Register focus/blur callbacks for a component.
@@ -310,10 +316,11 @@ const focusCallbacks = new Map<number, FocusCallbacks>()
  * ```
  */
 export function registerFocusCallbacks(index: number, callbacks: FocusCallbacks): () => void {
-  focusCallbacks.set(index, callbacks)
+  const registered = focusCallbacks()
+  registered.set(index, callbacks)
 
   return () => {
-    focusCallbacks.delete(index)
+    registered.delete(index)
   }
 }
 
@@ -322,7 +329,7 @@ export function registerFocusCallbacks(index: number, callbacks: FocusCallbacks)
  * @internal
  */
 export function _dispatchFocusCallback(index: number, type: 'focus' | 'blur'): void {
-  const callbacks = focusCallbacks.get(index)
+  const callbacks = focusCallbacks().get(index)
   if (callbacks) {
     if (type === 'focus') {
       callbacks.onFocus?.()
@@ -337,6 +344,6 @@ export function _dispatchFocusCallback(index: number, type: 'focus' | 'blur'): v
  * Called when component unmounts.
  */
 export function cleanupFocusCallbacks(index: number): void {
-  focusCallbacks.delete(index)
+  focusCallbacks().delete(index)
   _unregisterIndexMapping(index)
 }
@@ -0,0 +1,230 @@
+/**
+ * SparkTUI - Input history
+ *
+ * Shell-style history for an input. Pass it as `history` and the input
+ * records what is submitted and recalls it from the keyboard:
+ *
+ * - Up/Down walk older/newer entries that start with what was typed before
+ *   the first Up (empty recalls everything); Down past the newest brings the
+ *   typed text back
+ * - Ctrl+R searches backwards: the input's text becomes the query, each
+ *   Ctrl+R finds an older entry containing it, Enter takes the match and
+ *   Escape restores what was there before
+ *
+ * ```ts
+ * const history = createHistory({ file: join(homedir(), '.myapp_history'), maxSize: 500 })
+ * input({ value: command, history, onSubmit: run })
+ * show(() => history.search.value !== null, () =>
+ *   text({ content: () => `(search) '${history.search.value!.query}': ${history.search.value!.match ?? ''}` }))
+ * ```
+ *
+ * With `file`, entries are read when the history is created and the file
+ * is rewritten on every add, one entry per line.
+ */
+
+import { signal } from '@rlabs-inc/signals'
+import type { ReadableSignal } from '@rlabs-inc/signals'
+import { existsSync, mkdirSync, readFileSync, writeFileSync } from 'fs'
+import { dirname } from 'path'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+export interface HistoryOptions {
+  /** Entries kept, oldest dropped first (default: 1000) */
+  maxSize?: number
+  /** Adding an entry already in the history moves it to the newest instead of repeating it (default: true) */
+  dedupe?: boolean
+  /** Load from and save to this file */
+  file?: string
+  /** Initial entries, oldest first (after the file's) */
+  entries?: string[]
+}
+
+/** A Ctrl+R search in progress */
+export interface HistorySearch {
+  query: string
+  /** Newest entry containing the query at or before the search position, null when none */
+  match: string | null
+}
+
+export interface History {
+  /** Entries, oldest first */
+  readonly entries: ReadableSignal<readonly string[]>
+  /** The search in progress, null when not searching */
+  readonly search: ReadableSignal<HistorySearch | null>
+  /** Record an entry as the newest (blank entries are ignored) */
+  add(entry: string): void
+  /** Older entry matching the prefix, starting from `current`; null at the oldest */
+  previous(current: string): string | null
+  /** Newer entry matching the prefix, or the text from before navigating; null when not navigating */
+  next(): string | null
+  /** Start a search for `query`, or look further back when one is running */
+  searchOlder(query: string): void
+  /** The query changed: search again from the newest */
+  setQuery(query: string): void
+  /** End the search, returning the match (or the query when nothing matched) */
+  acceptSearch(): string
+  /** End the search, returning the text from before it started */
+  cancelSearch(): string
+  /** Stop navigating, so the next Up starts from the newest again */
+  reset(): void
+  /** Remove every entry (and empty the file) */
+  clear(): void
+}
+
+const DEFAULT_MAX_SIZE = 1000
+
+// =============================================================================
+// PERSISTENCE
+// =============================================================================
+
+function load(file: string): string[] {
+  if (!existsSync(file)) return []
+  return readFileSync(file, 'utf8').split('\n').filter((line) => line.length > 0)
+}
+
+function save(file: string, entries: readonly string[]): void {
+  mkdirSync(dirname(file), { recursive: true })
+  writeFileSync(file, entries.length > 0 ? entries.join('\n') + '\n' : '')
+}
+
+/** One entry per line: newlines in an entry become spaces */
+function normalize(entry: string): string {
+  return entry.replace(/\r?\n/g, ' ')
+}
+
+// =============================================================================
+// HISTORY
+// =============================================================================
+
+export function createHistory(options: HistoryOptions = {}): History {
+  const maxSize = Math.max(1, options.maxSize ?? DEFAULT_MAX_SIZE)
+  const dedupe = options.dedupe ?? true
+  const file = options.file
+
+  const trim = (list: string[]) => list.slice(Math.max(0, list.length - maxSize))
+
+  let initial: string[] = []
+  for (const entry of [...(file ? load(file) : []), ...(options.entries ?? [])]) {
+    const line = normalize(entry)
+    if (dedupe) initial = initial.filter((e) => e !== line)
+    initial.push(line)
+  }
+  const entries = signal<readonly string[]>(trim(initial))
+  const search = signal<HistorySearch | null>(null)
+
+  // Up/Down navigation: position in entries (length = the draft), null when idle
+  let position: number | null = null
+  let prefix = ''
+  // Text in the input before navigating or searching
+  let draft = ''
+  // Search position: matches are looked for at or before it
+  let searchFrom = 0
+
+  /** Newest index at or before `from` whose entry contains `query` */
+  const findOlder = (query: string, from: number): number => {
+    const list = entries.value
+    for (let i = Math.min(from, list.length - 1); i >= 0; i--) {
+      if (list[i]!.includes(query)) return i
+    }
+    return -1
+  }
+
+  const runSearch = (query: string, from: number) => {
+    const found = findOlder(query, from)
+    if (found >= 0) {
+      searchFrom = found
+      search.value = { query, match: entries.value[found]! }
+    } else if (search.value?.query !== query) {
+      search.value = { query, match: null }
+    }
+    // Same query, nothing older: the current match stays
+  }
+
+  const history: History = {
+    entries,
+    search,
+
+    add(entry) {
+      const line = normalize(entry)
+      position = null
+      if (line.trim() === '') return
+      let list = [...entries.value]
+      if (dedupe) list = list.filter((e) => e !== line)
+      list.push(line)
+      entries.value = trim(list)
+      if (file) save(file, entries.value)
+    },
+
+    previous(current) {
+      const list = entries.value
+      if (position === null) {
+        position = list.length
+        prefix = current
+        draft = current
+      }
+      for (let i = position - 1; i >= 0; i--) {
+        const entry = list[i]!
+        if (entry.startsWith(prefix) && entry !== current) {
+          position = i
+          return entry
+        }
+      }
+      return null
+    },
+
+    next() {
+      if (position === null) return null
+      const list = entries.value
+      for (let i = position + 1; i < list.length; i++) {
+        if (list[i]!.startsWith(prefix)) {
+          position = i
+          return list[i]!
+        }
+      }
+      position = null
+      return draft
+    },
+
+    searchOlder(query) {
+      if (search.value === null) {
+        draft = query
+        position = null
+        runSearch(query, entries.value.length - 1)
+      } else {
+        runSearch(query, searchFrom - 1)
+      }
+    },
+
+    setQuery(query) {
+      if (search.value === null) return
+      runSearch(query, entries.value.length - 1)
+    },
+
+    acceptSearch() {
+      const result = search.value?.match ?? search.value?.query ?? draft
+      search.value = null
+      return result
+    },
+
+    cancelSearch() {
+      search.value = null
+      return draft
+    },
+
+    reset() {
+      position = null
+    },
+
+    clear() {
+      position = null
+      search.value = null
+      entries.value = []
+      if (file) save(file, [])
+    },
+  }
+
+  return history
+}
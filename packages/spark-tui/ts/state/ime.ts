@@ -0,0 +1,93 @@
+/**
+ * SparkTUI - IME composition
+ *
+ * A terminal only ever sends committed text: dead keys, compose sequences
+ * and input methods finish composing before a byte arrives, and the engine
+ * parses that text as typed characters. Nothing here is needed for that.
+ *
+ * Embedders that host SparkTUI where composition IS visible (a GUI window,
+ * a browser terminal with compositionstart/update/end) forward it here.
+ * The focused input draws the preedit underlined at its cursor, outside its
+ * value, and inserts the text on commit:
+ *
+ * ```ts
+ * term.on('compositionupdate', (e) => setPreedit(e.data))
+ * term.on('compositionend', (e) => commitPreedit(e.data))
+ * ```
+ *
+ * Number, date and pattern inputs don't compose; leaving an input while
+ * composing commits what was there.
+ */
+
+import { signal } from '@rlabs-inc/signals'
+import type { ReadableSignal } from '@rlabs-inc/signals'
+import { focusedIndex } from './focus'
+
+/** How an input shows and takes composed text */
+export interface Composer {
+  /** Show `text` at the cursor ('' hides it); false if it can't be stored */
+  preedit(text: string): boolean
+  /** Insert `text` at the cursor as if typed */
+  commit(text: string): void
+}
+
+/** The composition in progress, null when none */
+export interface Composition {
+  index: number
+  text: string
+}
+
+const composers = new Map<number, Composer>()
+const current = signal<Composition | null>(null)
+
+/** The composition in progress (for a candidate window or status hint) */
+export const composition: ReadableSignal<Composition | null> = current
+
+/** Make an input a composition target. Returns the unregister function. */
+export function registerComposer(index: number, composer: Composer): () => void {
+  composers.set(index, composer)
+  return () => {
+    if (composers.get(index) !== composer) return
+    composers.delete(index)
+    if (current.value?.index === index) current.value = null
+  }
+}
+
+/**
+ * Show uncommitted composition text in the focused input (or `index`).
+ * Returns false when the target doesn't compose or the text pool is full.
+ */
+export function setPreedit(text: string, index: number = focusedIndex.value): boolean {
+  const composer = composers.get(index)
+  if (!composer) return false
+  const active = current.value
+  if (active && active.index !== index) cancelPreedit()
+  if (!composer.preedit(text)) return false
+  current.value = text ? { index, text } : null
+  return true
+}
+
+/**
+ * Finish the composition in its input (or the focused one, or `index`):
+ * clear the preedit and insert `text`, by default the preedit. Returns
+ * false when the target doesn't compose.
+ */
+export function commitPreedit(text?: string, index?: number): boolean {
+  const active = current.value
+  const target = index ?? active?.index ?? focusedIndex.value
+  const composer = composers.get(target)
+  if (!composer) return false
+  const committed = text ?? (active?.index === target ? active.text : '')
+  composer.preedit('')
+  if (active?.index === target) current.value = null
+  if (committed) composer.commit(committed)
+  return true
+}
+
+/** Drop the composition without inserting anything */
+export function cancelPreedit(): void {
+  const active = current.value
+  if (!active) return
+  composers.get(active.index)?.preedit('')
+  current.value = null
+}
@@ -0,0 +1,144 @@
+/**
+ * SparkTUI Component Inspector
+ *
+ * A dev overlay with the live component tree: index, type, computed size,
+ * screen position and key props for every node. Rust draws it beside the
+ * selected node and tints that node's region; arrows walk the tree
+ * (↑↓ previous/next, ← parent, → first child), a click selects the node
+ * under the mouse, Esc closes. While it's open, keys and mouse go to the
+ * inspector instead of the app.
+ *
+ * ```ts
+ * mount(App, { inspector: { key: KEY_F10 } })   // F10 toggles it
+ * setInspector(true)                           // or open it from code
+ * console.log(inspectTree())                   // the same tree, as data
+ * ```
+ */
+
+import { isInitialized, getBuffer, getNotifier } from '../bridge'
+import {
+  getInspector as readInspector,
+  setInspector as writeInspector,
+  setInspectorBinding,
+  getNodeCount,
+  getComponentType,
+  getParentIndex,
+  getFirstChild,
+  getNextSibling,
+  getComputedX,
+  getComputedY,
+  getComputedWidth,
+  getComputedHeight,
+  getText,
+  COMPONENT_NONE,
+  COMPONENT_BOX,
+  COMPONENT_TEXT,
+  COMPONENT_INPUT,
+  COMPONENT_SELECT,
+  COMPONENT_PROGRESS,
+  type SharedBuffer,
+} from '../bridge/shared-buffer'
+import { encodeKeyBinding } from './keyboard'
+import type { KeyBinding } from './keyboard'
+import { getAllocatedIndices, getId } from '../engine/registry'
+import type { ComponentRect } from '../engine/observers'
+
+/** Toggle key: key code (KEY_* constant) or a single character (default: none) */
+export interface InspectorOptions extends KeyBinding {
+  /** Open the inspector from the start (default: false) */
+  open?: boolean
+}
+
+/** One component in the tree returned by inspectTree() */
+export interface InspectedNode {
+  index: number
+  /** Registry id (the component's `id` prop, or the generated one) */
+  id: string | undefined
+  type: string
+  /** Computed layout, relative to the parent */
+  rect: ComponentRect
+  text: string
+  children: InspectedNode[]
+}
+
+const TYPE_NAMES: Record<number, string> = {
+  [COMPONENT_BOX]: 'box',
+  [COMPONENT_TEXT]: 'text',
+  [COMPONENT_INPUT]: 'input',
+  [COMPONENT_SELECT]: 'select',
+  [COMPONENT_PROGRESS]: 'progress',
+}
+
+/**
+ * Bind (or rebind) the inspector toggle key and open or close it.
+ * Called by mount() with its `inspector` option.
+ */
+export function configureInspector(options: InspectorOptions | false): void {
+  if (!isInitialized()) return
+  const buf = getBuffer()
+
+  if (options === false) {
+    setInspectorBinding(buf, 0, 0)
+    setInspector(false)
+    return
+  }
+
+  const { open = false } = options
+  const { keycode, modifiers } = encodeKeyBinding(options)
+  setInspectorBinding(buf, keycode, modifiers)
+  setInspector(open)
+}
+
+/** Open or close the inspector overlay */
+export function setInspector(open: boolean): void {
+  if (!isInitialized()) return
+  writeInspector(getBuffer(), open)
+  getNotifier().notify()
+}
+
+/** Whether the inspector is open (the toggle key and Esc change it on the Rust side) */
+export function getInspector(): boolean {
+  return isInitialized() && readInspector(getBuffer())
+}
+
+export function toggleInspector(): void {
+  setInspector(!getInspector())
+}
+
+/**
+ * The live component tree, roots by index, children in order — what the
+ * overlay shows, for logging or tests.
+ */
+export function inspectTree(): InspectedNode[] {
+  if (!isInitialized()) return []
+  const buf = getBuffer()
+  const nodeCount = getNodeCount(buf)
+  const roots = [...getAllocatedIndices()]
+    .filter((i) => i < nodeCount && getComponentType(buf, i) !== COMPONENT_NONE && getParentIndex(buf, i) < 0)
+    .sort((a, b) => a - b)
+  return roots.map((i) => inspectNode(buf, i, nodeCount))
+}
+
+function inspectNode(buf: SharedBuffer, index: number, nodeCount: number): InspectedNode {
+  const children: InspectedNode[] = []
+  // Bounded by nodeCount in case the links are mid-update
+  for (let child = getFirstChild(buf, index); child >= 0 && children.length < nodeCount; child = getNextSibling(buf, child)) {
+    if (getComponentType(buf, child) !== COMPONENT_NONE) {
+      children.push(inspectNode(buf, child, nodeCount))
+    }
+  }
+  const type = getComponentType(buf, index)
+  return {
+    index,
+    id: getId(index),
+    type: TYPE_NAMES[type] ?? 'node',
+    rect: {
+      x: getComputedX(buf, index),
+      y: getComputedY(buf, index),
+      width: getComputedWidth(buf, index),
+      height: getComputedHeight(buf, index),
+    },
+    text: getText(buf, index),
+    children,
+  }
+}
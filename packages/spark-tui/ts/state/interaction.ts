@@ -0,0 +1,98 @@
+/**
+ * SparkTUI Interaction State
+ *
+ * Which node the mouse is over and which one is held down, as Rust
+ * publishes them (H_HOVERED_INDEX, H_PRESSED_INDEX), made reactive: every
+ * mouse enter, leave, down and up bumps a version the reads depend on.
+ *
+ * A node counts as hovered or pressed while the mouse is on it or on any of
+ * its descendants, so a button's label doesn't take the hover from it.
+ * Keyboard activation has no release to wait for, so `flashPressed` shows
+ * the pressed state for a moment instead.
+ *
+ * PURELY REACTIVE: driven by mouse and key events, nothing is sampled.
+ */
+
+import { signal } from '@rlabs-inc/signals'
+import { isInitialized, getBuffer } from '../bridge'
+import { getHoveredIndex, getParentIndex, getPressedIndex } from '../bridge/shared-buffer'
+import type { SharedBuffer } from '../bridge/shared-buffer'
+import { EventType, registerGlobalMouseHandler } from '../engine/events'
+import { focusedIndex } from './focus'
+
+/** What a node is going through right now */
+export interface InteractionState {
+  hovered: boolean
+  focused: boolean
+  pressed: boolean
+  disabled: boolean
+}
+
+/** Parent walk limit, as in event bubbling */
+const MAX_DEPTH = 100
+
+/** How long a keyboard activation shows as pressed */
+const PRESS_FLASH_MS = 120
+
+const version = signal(0)
+
+/** Nodes pressed from the keyboard, with the timer ending each flash */
+const flashing = new Map<number, ReturnType<typeof setTimeout>>()
+
+/** Follow hover and press changes for this mount. Called by mount() */
+export function trackInteraction(): () => void {
+  return registerGlobalMouseHandler((event) => {
+    switch (event.type) {
+      case EventType.MouseEnter:
+      case EventType.MouseLeave:
+      case EventType.MouseDown:
+      case EventType.MouseUp:
+        version.value++
+    }
+  })
+}
+
+/** Whether `index` is `ancestor` or inside it */
+function within(buf: SharedBuffer, index: number, ancestor: number): boolean {
+  for (let depth = 0; index >= 0 && depth < MAX_DEPTH; depth++) {
+    if (index === ancestor) return true
+    index = getParentIndex(buf, index)
+  }
+  return false
+}
+
+/**
+ * Node `index`'s interaction state — reactive when read in deriveds and
+ * effects. `disabled` comes from the node's own prop.
+ */
+export function interactionState(index: number, disabled = false): InteractionState {
+  void version.value // re-read on every hover/press change
+  const focused = focusedIndex.value === index
+  if (!isInitialized()) return { hovered: false, focused, pressed: flashing.has(index), disabled }
+  const buf = getBuffer()
+  return {
+    hovered: within(buf, getHoveredIndex(buf), index),
+    focused,
+    pressed: flashing.has(index) || within(buf, getPressedIndex(buf), index),
+    disabled,
+  }
+}
+
+/** Show node `index` as pressed for a moment, e.g. after Enter or Space. */
+export function flashPressed(index: number): void {
+  clearTimeout(flashing.get(index))
+  flashing.set(index, setTimeout(() => {
+    flashing.delete(index)
+    version.value++
+  }, PRESS_FLASH_MS))
+  version.value++
+}
+
+/** End node `index`'s press flash now. Called when the node is destroyed. */
+export function cleanupIndex(index: number): void {
+  const timer = flashing.get(index)
+  if (timer === undefined) return
+  clearTimeout(timer)
+  flashing.delete(index)
+  version.value++
+}
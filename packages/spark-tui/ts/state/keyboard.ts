@@ -14,6 +14,7 @@ import {
   EventType,
   registerKeyHandler,
   registerGlobalKeyHandler,
+  registerFallbackKeyHandler,
   cleanupHandlers,
   MODIFIER_CTRL,
   MODIFIER_ALT,
@@ -27,6 +28,7 @@ import {
   hasShift,
   hasMeta,
 } from '../engine/events'
+import { PARSER_MOD_SHIFT, PARSER_MOD_ALT, PARSER_MOD_CTRL } from '../bridge/shared-buffer'
 
 // =============================================================================
 // RE-EXPORTS for convenience
@@ -125,6 +127,25 @@ export function on(handler: (event: KeyEvent) => boolean | void): () => void {
   return registerGlobalKeyHandler(handler)
 }
 
+/**
+ * Register an app-level fallback key handler.
+ * Called for key events that bubbled from the focused component to the root
+ * without any handler stopping them, so focused components get first say.
+ *
+ * @example
+ * ```ts
+ * import { onUnhandled, matchesKey } from './state/keyboard'
+ *
+ * // 'q' quits, unless an input (or anything else focused) takes it
+ * const unsub = onUnhandled((event) => {
+ *   if (matchesKey(event, 'q')) exit()
+ * })
+ * ```
+ */
+export function onUnhandled(handler: (event: KeyEvent) => boolean | void): () => void {
+  return registerFallbackKeyHandler(handler)
+}
+
 /**
  * Register a handler for a specific key.
  *
@@ -166,10 +187,12 @@ export function onKey(key: string, handler: () => boolean | void): () => void {
 }
 
 /**
- * Register a key handler for when a specific component is focused.
+ * Register a key handler for when a specific component, or anything inside
+ * it, is focused. Keys bubble from the focused component up to the root;
+ * return `true` or call `event.stopPropagation()` to stop them.
  *
  * @param index - Component index
- * @param handler - Called when key is pressed while component is focused
+ * @param handler - Called when key is pressed while component (or a descendant) is focused
  * @returns Unsubscribe function
  *
  * @example
@@ -218,11 +241,31 @@ function getSpecialKeyName(keycode: number): string | undefined {
     case 0x1b5b42: return 'ArrowDown'
     case 0x1b5b43: return 'ArrowRight'
     case 0x1b5b44: return 'ArrowLeft'
+    // Navigation keys (engine key-event encoding)
+    case 0x1001: return 'ArrowUp'
+    case 0x1002: return 'ArrowDown'
+    case 0x1003: return 'ArrowLeft'
+    case 0x1004: return 'ArrowRight'
+    case 0x1005: return 'Home'
+    case 0x1006: return 'End'
+    case 0x1007: return 'PageUp'
+    case 0x1008: return 'PageDown'
     // Function keys would go here
     default: return undefined
   }
 }
 
+/** Whether `keycode` is a printable character without case ('<', '1') */
+function isCaseless(keycode: number): boolean {
+  if (keycode <= 32 || keycode === 127 || getSpecialKeyName(keycode) !== undefined) return false
+  try {
+    const ch = String.fromCodePoint(keycode)
+    return ch.toLowerCase() === ch.toUpperCase()
+  } catch {
+    return false
+  }
+}
+
 /**
  * Check if a key event matches a key combination.
  *
@@ -238,10 +281,13 @@ export function matchesKey(event: KeyEvent, combo: string): boolean {
   const key = parts.pop()!
   const mods = new Set(parts)
 
-  // Check modifiers
+  // Check modifiers. The engine reports Shift with uppercase letters even
+  // from legacy terminals; a shifted symbol ('<', '?') is its own key, so
+  // whether Shift was reported with it doesn't matter
+  const symbol = isCaseless(event.keycode)
   if (mods.has('ctrl') !== hasCtrl(event)) return false
   if (mods.has('alt') !== hasAlt(event)) return false
-  if (mods.has('shift') !== hasShift(event)) return false
+  if (!symbol && mods.has('shift') !== hasShift(event)) return false
   if (mods.has('meta') !== hasMeta(event)) return false
 
   // Check key
@@ -276,3 +322,35 @@ export function isRepeat(event: KeyEvent): boolean {
 export function isRelease(event: KeyEvent): boolean {
   return event.keyState === KEY_STATE_RELEASE
 }
+
+// =============================================================================
+// ENGINE KEY BINDINGS
+// =============================================================================
+
+/** A key the engine watches for itself (screenshot, perf HUD, inspector) */
+export interface KeyBinding {
+  /** Key code (KEY_* constant) or a single character */
+  key?: number | string
+  ctrl?: boolean
+  alt?: boolean
+  shift?: boolean
+}
+
+/**
+ * Key code and modifier bits for a binding, as the engine reports the key
+ * (it compares both exactly). A letter typed with Shift arrives uppercase
+ * with Shift, so `'G'` and `{ key: 'g', shift: true }` both bind Shift+G.
+ * A keycode of 0 leaves the binding unset.
+ */
+export function encodeKeyBinding(binding: KeyBinding): { keycode: number; modifiers: number } {
+  const { key, ctrl, alt } = binding
+  let keycode = typeof key === 'string' ? key.codePointAt(0) ?? 0 : key ?? 0
+  let shift = binding.shift ?? false
+  const letter = String.fromCodePoint(keycode)
+  if (/^[a-z]$/i.test(letter) && (shift || letter === letter.toUpperCase())) {
+    keycode = letter.toUpperCase().codePointAt(0)!
+    shift = true
+  }
+  const modifiers = (shift ? PARSER_MOD_SHIFT : 0) | (alt ? PARSER_MOD_ALT : 0) | (ctrl ? PARSER_MOD_CTRL : 0)
+  return { keycode, modifiers }
+}
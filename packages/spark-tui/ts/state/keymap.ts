@@ -0,0 +1,591 @@
+/**
+ * SparkTUI Keymap
+ *
+ * Named actions bound to key sequences, instead of scattered `onKey`
+ * handlers for app-level shortcuts:
+ *
+ * - Sequences and chords: `'q'`, `'Ctrl+S'`, `'g g'`, `'Ctrl+K Ctrl+S'`
+ * - Per-context maps, most specific wins:
+ *     `'#sidebar'` (focus inside the component with that id)
+ *       → `'input'` / `'box'` / `'text'` (focused component type)
+ *       → `'global'`
+ * - Runtime rebinding and conflict detection
+ *
+ * ```ts
+ * defineKeymap('global', {
+ *   save: 'Ctrl+S',
+ *   quit: ['q', 'Ctrl+Q'],
+ *   'go-top': 'g g',
+ *   'save-all': 'Ctrl+K Ctrl+S',
+ * })
+ * defineKeymap('#file-tree', { 'new-file': 'a', delete: { keys: 'd d', help: 'Delete' } })
+ *
+ * onAction('save', () => save())
+ * rebind('quit', 'Ctrl+X')
+ * ```
+ *
+ * `activeBindings()` lists what the keys do where focus is now, with each
+ * action's `help` text — what a help bar shows.
+ *
+ * While a chord is half-typed, `pendingKeys` holds what was pressed so far
+ * (for a "ctrl+k …" hint). If a sequence is both complete and the start of
+ * a longer one (`g` and `g g`), the shorter fires after `CHORD_TIMEOUT`.
+ *
+ * Framework navigation (scrolling the focused box) runs in the engine with
+ * the keys of the mount's `keymap` preset — 'standard', 'vim' or 'emacs'.
+ * Its actions are named (`NAV_ACTIONS`) and an app takes one over by
+ * binding it here: `onAction('half-page-down', …)`, `rebind('scroll-down',
+ * 'n')`, or any binding that reuses one of its preset keys. A taken-over
+ * action is dispatched by this registry instead; without a handler (or if
+ * every handler returns false) it still scrolls.
+ *
+ * A Rust host binds its own shortcuts with rust/src/state/keymap.rs, which
+ * takes the same key syntax and context rules.
+ */
+
+import { signal } from '@rlabs-inc/signals'
+import type { ReadableSignal } from '@rlabs-inc/signals'
+import { isInitialized, getBuffer, getNotifier } from '../bridge'
+import {
+  getComponentType,
+  getParentIndex,
+  getComputedHeight,
+  getComputedWidth,
+  getScrollX,
+  getScrollY,
+  getMaxScrollX,
+  getMaxScrollY,
+  setScroll,
+  setKeymapOverrides,
+  setKeymapPreset as writeKeymapPreset,
+  KeymapPreset,
+} from '../bridge/shared-buffer'
+import { getIndex } from '../engine/registry'
+import { reportScrollChange } from '../engine/events'
+import { ComponentType } from '../types'
+import { on, matchesKey, isPress } from './keyboard'
+import type { KeyEvent } from './keyboard'
+import { focusedIndex } from './focus'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+/** `'global'`, a component type (`'input'`, `'box'`, `'text'`), or `'#id'` for a focus zone */
+export type KeymapContext = string
+
+export interface KeymapConflict {
+  context: KeymapContext
+  /** 'duplicate': same sequence; 'prefix': one sequence starts the other (the shorter waits for the timeout) */
+  kind: 'duplicate' | 'prefix'
+  actions: [string, string]
+  keys: [string, string]
+}
+
+/** Key sequence(s) of an action, optionally with a short description for help */
+export type KeyBindingSpec = string | string[] | { keys: string | string[]; help?: string }
+
+/** An action reachable from the current focus */
+export interface ActiveBinding {
+  action: string
+  /** Sequences in the most specific context binding it, e.g. ['q', 'ctrl+q'] */
+  keys: string[]
+  /** Description from `defineKeymap` (empty if none) */
+  help: string
+  context: KeymapContext
+}
+
+interface Binding {
+  action: string
+  context: KeymapContext
+  /** Normalized strokes, e.g. ['ctrl+k', 'ctrl+s'] */
+  strokes: string[]
+  /** Help text from defineKeymap, gone with the binding */
+  help?: string
+}
+
+/** Wait this long for the next key of a chord */
+export const CHORD_TIMEOUT = 1000
+
+const GLOBAL: KeymapContext = 'global'
+
+/** Preset keys of taken-over navigation actions; below every other context */
+const PRESET: KeymapContext = 'preset'
+
+/** Framework navigation actions, in engine bit order (`NavAction` in input/keymap.rs) */
+export const NAV_ACTIONS = [
+  'scroll-up',
+  'scroll-down',
+  'scroll-left',
+  'scroll-right',
+  'page-up',
+  'page-down',
+  'half-page-up',
+  'half-page-down',
+  'scroll-top',
+  'scroll-bottom',
+  'page-left',
+  'page-right',
+  'scroll-left-edge',
+  'scroll-right-edge',
+] as const
+
+export type NavAction = (typeof NAV_ACTIONS)[number]
+
+export type KeymapPresetName = 'standard' | 'vim' | 'emacs'
+
+const STANDARD_KEYS: Partial<Record<NavAction, string[]>> = {
+  'scroll-up': ['ArrowUp'],
+  'scroll-down': ['ArrowDown'],
+  'scroll-left': ['ArrowLeft'],
+  'scroll-right': ['ArrowRight'],
+  'page-up': ['PageUp'],
+  'page-down': ['PageDown'],
+  'scroll-top': ['Home'],
+  'scroll-bottom': ['End'],
+  'page-left': ['Shift+PageUp'],
+  'page-right': ['Shift+PageDown'],
+  'scroll-left-edge': ['Shift+Home'],
+  'scroll-right-edge': ['Shift+End'],
+}
+
+/** Keys each preset adds to the standard ones — mirrors the engine's tables */
+const PRESET_KEYS: Record<KeymapPresetName, Partial<Record<NavAction, string[]>>> = {
+  standard: {},
+  vim: {
+    'scroll-up': ['k'],
+    'scroll-down': ['j'],
+    'scroll-left': ['h'],
+    'scroll-right': ['l'],
+    'page-up': ['Ctrl+B'],
+    'page-down': ['Ctrl+F'],
+    'half-page-up': ['Ctrl+U'],
+    'half-page-down': ['Ctrl+D'],
+    'scroll-top': ['g g'],
+    'scroll-bottom': ['Shift+G'],
+  },
+  emacs: {
+    'scroll-up': ['Ctrl+P'],
+    'scroll-down': ['Ctrl+N'],
+    'scroll-left': ['Ctrl+B'],
+    'scroll-right': ['Ctrl+F'],
+    'page-up': ['Alt+V'],
+    'page-down': ['Ctrl+V'],
+    'scroll-top': ['Alt+<'],
+    'scroll-bottom': ['Alt+>'],
+  },
+}
+
+const PRESET_IDS: Record<KeymapPresetName, KeymapPreset> = {
+  standard: KeymapPreset.Standard,
+  vim: KeymapPreset.Vim,
+  emacs: KeymapPreset.Emacs,
+}
+
+const TYPE_CONTEXTS: Record<number, KeymapContext> = {
+  [ComponentType.BOX]: 'box',
+  [ComponentType.TEXT]: 'text',
+  [ComponentType.INPUT]: 'input',
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+const bindings: Binding[] = []
+const actionHandlers = new Map<string, Set<() => boolean | void>>()
+
+/** Bumped whenever bindings change, for activeBindings() readers */
+const revision = signal(0)
+
+/** Keys of the chord typed so far */
+let pending: KeyEvent[] = []
+let pendingTimer: ReturnType<typeof setTimeout> | null = null
+const pendingKeysSignal = signal('')
+
+/** Keys of a half-typed chord, e.g. `'ctrl+k'` — empty when none */
+export const pendingKeys: ReadableSignal<string> = pendingKeysSignal
+
+let unsubscribe: (() => void) | null = null
+
+let preset: KeymapPresetName = 'standard'
+
+// =============================================================================
+// PARSING
+// =============================================================================
+
+const MODIFIER_ORDER = ['ctrl', 'alt', 'shift', 'meta']
+
+/** Canonical form of one stroke: lowercase, modifiers in a fixed order */
+function normalizeStroke(stroke: string): string {
+  const parts = stroke.split('+').map((p) => p.trim().toLowerCase())
+  const key = parts.pop() || '+'
+  const mods = MODIFIER_ORDER.filter((m) => parts.includes(m))
+  return [...mods, key].join('+')
+}
+
+function parseSequence(keys: string): string[] {
+  return keys.trim().split(/\s+/).filter(Boolean).map(normalizeStroke)
+}
+
+const formatStrokes = (strokes: string[]) => strokes.join(' ')
+
+const isNavAction = (action: string): action is NavAction => (NAV_ACTIONS as readonly string[]).includes(action)
+
+/** Standard + preset keys of a navigation action */
+function presetKeys(action: NavAction): string[] {
+  return [...(STANDARD_KEYS[action] ?? []), ...(PRESET_KEYS[preset][action] ?? [])]
+}
+
+function isPrefix(short: string[], long: string[]): boolean {
+  return short.length <= long.length && short.every((s, i) => s === long[i])
+}
+
+// =============================================================================
+// CONTEXT RESOLUTION
+// =============================================================================
+
+/** Active contexts, most specific first */
+function activeContexts(): KeymapContext[] {
+  const focused = focusedIndex.value
+  if (focused < 0 || !isInitialized()) return [GLOBAL, PRESET]
+  const buf = getBuffer()
+
+  const contexts: KeymapContext[] = []
+  const zones = new Set(bindings.filter((b) => b.context.startsWith('#')).map((b) => b.context))
+  if (zones.size > 0) {
+    // Innermost zone first
+    for (let i = focused; i >= 0; i = getParentIndex(buf, i)) {
+      for (const zone of zones) {
+        if (getIndex(zone.slice(1)) === i) contexts.push(zone)
+      }
+    }
+  }
+  const type = TYPE_CONTEXTS[getComponentType(buf, focused)]
+  if (type) contexts.push(type)
+  contexts.push(GLOBAL, PRESET)
+  return contexts
+}
+
+// =============================================================================
+// DISPATCH
+// =============================================================================
+
+function clearPending(): void {
+  if (pendingTimer) clearTimeout(pendingTimer)
+  pendingTimer = null
+  pending = []
+  pendingKeysSignal.value = ''
+}
+
+/** Run an action's handlers; true if any handled it */
+function fire(action: string): boolean {
+  for (const handler of actionHandlers.get(action) ?? []) {
+    if (handler() !== false) return true
+  }
+  // A taken-over navigation action still does its default
+  return isNavAction(action) && navigate(action)
+}
+
+const clampScroll = (value: number, max: number) => Math.max(0, Math.min(value, Math.floor(max)))
+
+/** Framework default of a navigation action: scroll the focused box */
+function navigate(action: NavAction): boolean {
+  const focused = focusedIndex.value
+  if (focused < 0 || !isInitialized()) return false
+  const buf = getBuffer()
+  const page = Math.floor(getComputedHeight(buf, focused))
+  const pageWidth = Math.floor(getComputedWidth(buf, focused))
+  const maxX = getMaxScrollX(buf, focused)
+  const maxY = getMaxScrollY(buf, focused)
+  let x = getScrollX(buf, focused)
+  let y = getScrollY(buf, focused)
+
+  switch (action) {
+    case 'scroll-up': y -= 1; break
+    case 'scroll-down': y += 1; break
+    case 'scroll-left': x -= 1; break
+    case 'scroll-right': x += 1; break
+    case 'page-up': y -= page; break
+    case 'page-down': y += page; break
+    case 'half-page-up': y -= Math.floor(page / 2); break
+    case 'half-page-down': y += Math.floor(page / 2); break
+    case 'scroll-top': x = 0; y = 0; break
+    case 'scroll-bottom': x = 0; y = maxY; break
+    case 'page-left': x -= pageWidth; break
+    case 'page-right': x += pageWidth; break
+    case 'scroll-left-edge': x = 0; break
+    case 'scroll-right-edge': x = maxX; break
+  }
+
+  const [scrollX, scrollY] = [clampScroll(x, maxX), clampScroll(y, maxY)]
+  setScroll(buf, focused, scrollX, scrollY)
+  getNotifier().notify()
+  reportScrollChange(focused, scrollX, scrollY)
+  return true
+}
+
+/** Bindings in the most specific context that has any candidate for `seq` */
+function candidatesFor(seq: KeyEvent[]): Binding[] {
+  for (const context of activeContexts()) {
+    const matches = bindings.filter(
+      (b) => b.context === context && b.strokes.length >= seq.length && seq.every((ev, i) => matchesKey(ev, b.strokes[i]!))
+    )
+    if (matches.length > 0) return matches
+  }
+  return []
+}
+
+function handleKey(event: KeyEvent): boolean | void {
+  if (!isPress(event)) return
+
+  let seq = [...pending, event]
+  let candidates = candidatesFor(seq)
+  if (candidates.length === 0 && pending.length > 0) {
+    // Broken chord: start over from this key
+    clearPending()
+    seq = [event]
+    candidates = candidatesFor(seq)
+  }
+  if (candidates.length === 0) return
+
+  const complete = candidates.find((b) => b.strokes.length === seq.length)
+  const longer = candidates.some((b) => b.strokes.length > seq.length)
+
+  if (complete && !longer) {
+    clearPending()
+    return fire(complete.action) || undefined
+  }
+
+  // Wait for the next key; a complete shorter binding fires if none comes
+  if (pendingTimer) clearTimeout(pendingTimer)
+  pending = seq
+  pendingKeysSignal.value = formatStrokes(candidates[0]!.strokes.slice(0, seq.length))
+  pendingTimer = setTimeout(() => {
+    clearPending()
+    if (complete) fire(complete.action)
+  }, CHORD_TIMEOUT)
+  return true
+}
+
+function ensureListening(): void {
+  unsubscribe ??= on(handleKey)
+}
+
+/**
+ * Recompute which navigation actions this registry owns: those with a
+ * handler, an app binding, or a preset key that starts an app binding.
+ * Their remaining preset keys move into the 'preset' context and the engine
+ * is told to leave them alone.
+ */
+function syncNavigation(): void {
+  for (let i = bindings.length - 1; i >= 0; i--) {
+    if (bindings[i]!.context === PRESET) bindings.splice(i, 1)
+  }
+  // A preset key is reused when its first stroke starts an app binding:
+  // `g` is reused by `g` and by `g g`, and vim's `g g` by either
+  const appFirstStrokes = new Set(bindings.map((b) => b.strokes[0]!))
+
+  let overrides = 0
+  NAV_ACTIONS.forEach((action, bit) => {
+    const sequences = presetKeys(action).map(parseSequence)
+    const bound = bindings.some((b) => b.action === action)
+    const reused = sequences.some((strokes) => appFirstStrokes.has(strokes[0]!))
+    if (!actionHandlers.has(action) && !bound && !reused) return
+
+    overrides |= 1 << bit
+    // Explicit keys (rebind/defineKeymap) replace the preset's
+    if (bound) return
+    for (const strokes of sequences) {
+      if (!appFirstStrokes.has(strokes[0]!)) bindings.push({ action, context: PRESET, strokes })
+    }
+  })
+
+  if (isInitialized()) setKeymapOverrides(getBuffer(), overrides)
+  revision.value++
+}
+
+function stopIfUnused(): void {
+  if (bindings.length > 0 || actionHandlers.size > 0) return
+  clearPending()
+  unsubscribe?.()
+  unsubscribe = null
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/**
+ * Bind actions to key sequences in a context. Each action takes one
+ * sequence or a list of alternatives. Returns a function that removes
+ * exactly these bindings.
+ */
+export function defineKeymap(context: KeymapContext, map: Record<string, KeyBindingSpec>): () => void {
+  const added: Binding[] = []
+  for (const [action, spec] of Object.entries(map)) {
+    const keys = typeof spec === 'object' && !Array.isArray(spec) ? spec.keys : spec
+    const help = typeof spec === 'object' && !Array.isArray(spec) ? spec.help : undefined
+    for (const sequence of Array.isArray(keys) ? keys : [keys]) {
+      const strokes = parseSequence(sequence)
+      if (strokes.length > 0) added.push({ action, context, strokes, help })
+    }
+  }
+  bindings.push(...added)
+  syncNavigation()
+  ensureListening()
+
+  for (const conflict of findConflicts(context)) {
+    if (added.some((b) => conflict.actions.includes(b.action))) {
+      console.warn(
+        `[keymap] ${conflict.kind} in '${conflict.context}': ` +
+          `'${conflict.keys[0]}' (${conflict.actions[0]}) vs '${conflict.keys[1]}' (${conflict.actions[1]})`
+      )
+    }
+  }
+
+  return () => {
+    for (const binding of added) {
+      const i = bindings.indexOf(binding)
+      if (i >= 0) bindings.splice(i, 1)
+    }
+    syncNavigation()
+    stopIfUnused()
+  }
+}
+
+/**
+ * Run `handler` when `action` is triggered. Return false from the handler to
+ * let the key fall through to the next handler for the same action.
+ */
+export function onAction(action: string, handler: () => boolean | void): () => void {
+  let handlers = actionHandlers.get(action)
+  if (!handlers) {
+    handlers = new Set()
+    actionHandlers.set(action, handlers)
+  }
+  handlers.add(handler)
+  syncNavigation()
+  ensureListening()
+
+  return () => {
+    handlers.delete(handler)
+    if (handlers.size === 0) actionHandlers.delete(action)
+    syncNavigation()
+    stopIfUnused()
+  }
+}
+
+/**
+ * Replace the keys of `action` in `context` (default: every context it is
+ * bound in, or 'global' if unbound). Returns the conflicts this creates.
+ */
+export function rebind(action: string, keys: string | string[], context?: KeymapContext): KeymapConflict[] {
+  const contexts = context
+    ? [context]
+    : [...new Set(bindings.filter((b) => b.action === action && b.context !== PRESET).map((b) => b.context))]
+  if (contexts.length === 0) contexts.push(GLOBAL)
+
+  // The new keys keep the help of the ones they replace
+  const helps = new Map<KeymapContext, string>()
+  for (let i = bindings.length - 1; i >= 0; i--) {
+    const b = bindings[i]!
+    if (b.action !== action || !contexts.includes(b.context)) continue
+    if (b.help) helps.set(b.context, b.help)
+    bindings.splice(i, 1)
+  }
+  for (const ctx of contexts) {
+    for (const sequence of Array.isArray(keys) ? keys : [keys]) {
+      const strokes = parseSequence(sequence)
+      if (strokes.length > 0) bindings.push({ action, context: ctx, strokes, help: helps.get(ctx) })
+    }
+  }
+  syncNavigation()
+  clearPending()
+  ensureListening()
+
+  return contexts.flatMap((ctx) => findConflicts(ctx)).filter((c) => c.actions.includes(action))
+}
+
+/** Current key sequences of `action`, by context ('preset' for framework navigation keys) */
+export function getBindings(action: string): Record<KeymapContext, string[]> {
+  const result: Record<KeymapContext, string[]> = {}
+  for (const b of bindings) {
+    if (b.action === action) (result[b.context] ??= []).push(formatStrokes(b.strokes))
+  }
+  // Still handled by the engine: report the preset's keys
+  if (isNavAction(action) && Object.keys(result).length === 0 && !actionHandlers.has(action)) {
+    result[PRESET] = presetKeys(action).map(normalizeStroke)
+  }
+  return result
+}
+
+/**
+ * Actions bound in the contexts active for the current focus, most specific
+ * context first; an action bound in several shows only its innermost keys.
+ * Framework navigation keys are left out. Reactive: follows focus and every
+ * binding change.
+ */
+export function activeBindings(): ActiveBinding[] {
+  void revision.value
+  const result = new Map<string, ActiveBinding>()
+  for (const context of activeContexts()) {
+    if (context === PRESET) continue
+    const seen = new Set<string>()
+    for (const b of bindings) {
+      if (b.context !== context) continue
+      const entry = result.get(b.action)
+      if (entry && !seen.has(b.action)) continue
+      seen.add(b.action)
+      if (entry) {
+        entry.keys.push(formatStrokes(b.strokes))
+        entry.help ||= b.help ?? ''
+      } else {
+        result.set(b.action, { action: b.action, keys: [formatStrokes(b.strokes)], help: b.help ?? '', context })
+      }
+    }
+  }
+  return [...result.values()]
+}
+
+/**
+ * Select the framework navigation and input-editing keys. Called by mount
+ * with its `keymap` option; navigation actions already taken over keep
+ * going through this registry.
+ */
+export function setKeymapPreset(name: KeymapPresetName): void {
+  preset = name
+  if (isInitialized()) writeKeymapPreset(getBuffer(), PRESET_IDS[name])
+  syncNavigation()
+  clearPending()
+}
+
+/** The selected preset */
+export function getKeymapPreset(): KeymapPresetName {
+  return preset
+}
+
+/**
+ * Sequences within a context that collide: identical sequences bound to
+ * different actions, or one sequence that starts another.
+ */
+export function findConflicts(context?: KeymapContext): KeymapConflict[] {
+  const conflicts: KeymapConflict[] = []
+  const scoped = context ? bindings.filter((b) => b.context === context) : bindings
+  for (let i = 0; i < scoped.length; i++) {
+    for (let j = i + 1; j < scoped.length; j++) {
+      const a = scoped[i]!
+      const b = scoped[j]!
+      if (a.context !== b.context || a.action === b.action) continue
+      const [short, long] = a.strokes.length <= b.strokes.length ? [a, b] : [b, a]
+      if (!isPrefix(short.strokes, long.strokes)) continue
+      conflicts.push({
+        context: a.context,
+        kind: short.strokes.length === long.strokes.length ? 'duplicate' : 'prefix',
+        actions: [short.action, long.action],
+        keys: [formatStrokes(short.strokes), formatStrokes(long.strokes)],
+      })
+    }
+  }
+  return conflicts
+}
@@ -0,0 +1,28 @@
+/**
+ * SparkTUI Layout Debug
+ *
+ * A dev overlay that shows what flexbox did, like a browser's devtools:
+ * every visible node's border box outlined (colored by depth), its padding
+ * hatched, and the gaps between neighbouring children tinted. Marks only go
+ * on blank cells, so the app stays readable underneath.
+ *
+ * ```ts
+ * mount(App, { debugLayout: true })
+ * setDebugLayout(!getDebugLayout())   // or flip it from a key handler
+ * ```
+ */
+
+import { isInitialized, getBuffer, getNotifier } from '../bridge'
+import { getDebugLayout as readDebugLayout, setDebugLayout as writeDebugLayout } from '../bridge/shared-buffer'
+
+/** Turn the layout debug overlay on or off */
+export function setDebugLayout(on: boolean): void {
+  if (!isInitialized()) return
+  writeDebugLayout(getBuffer(), on)
+  getNotifier().notify()
+}
+
+/** Whether the layout debug overlay is on */
+export function getDebugLayout(): boolean {
+  return isInitialized() && readDebugLayout(getBuffer())
+}
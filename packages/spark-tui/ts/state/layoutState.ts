@@ -0,0 +1,250 @@
+/**
+ * SparkTUI - Layout persistence
+ *
+ * Remembers what the user arranged — scroll positions, panel layouts, split
+ * ratios, column widths, collapsed tree nodes — between runs:
+ *
+ * - `snapshotLayout()` collects it into a `LayoutSnapshot`: plain data keyed
+ *   by component ID, ready for JSON.stringify
+ * - `restoreLayout(snapshot)` puts it back: what's mounted now at once, the
+ *   rest as each component mounts (a tab opened later, a dialog)
+ * - Scroll offsets of every scrollable component with an ID are kept
+ *   without asking; anything else is kept by registering it with
+ *   `persistLayout` or, for a signal, `persistSignal`
+ * - State of components that didn't mount this run is carried over into the
+ *   next snapshot instead of being forgotten
+ *
+ * Components need IDs of their own to be remembered: generated ones
+ * (`c12`) depend on mount order and are skipped.
+ *
+ * Kept out of the box: scroll offsets and dashboardGrid panels, the only
+ * user-adjustable layout the framework's own components have. Split
+ * ratios, column widths and collapsed tree nodes belong to the app's
+ * components; persist them with `persistSignal`, as below.
+ *
+ * The file format is `LayoutSnapshot` in rust/src/state/layout.rs too
+ * (serde with the `serde` feature), for Rust hosts reading the same file.
+ *
+ * ```ts
+ * const collapsed = signal<string[]>([])
+ * persistSignal('files', 'collapsed', collapsed)
+ * loadLayout(join(homedir(), '.myapp', 'layout.json'))
+ * process.on('exit', () => saveLayout(join(homedir(), '.myapp', 'layout.json')))
+ * ```
+ */
+
+import { existsSync, mkdirSync, readFileSync, writeFileSync } from 'fs'
+import { dirname } from 'path'
+import type { WritableSignal } from '@rlabs-inc/signals'
+import { getAllocatedIndices, getId, getIndex } from '../engine/registry'
+import { observeComponents } from '../engine/observers'
+import { reportScrollChange } from '../engine/events'
+import type { ComponentObserver } from '../engine/observers'
+import { getBuffer, getNotifier, isInitialized } from '../bridge'
+import { getMaxScrollX, getMaxScrollY, getScrollX, getScrollY, setScroll } from '../bridge/shared-buffer'
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+/** What survives a JSON round trip */
+export type LayoutValue = null | boolean | number | string | LayoutValue[] | { [key: string]: LayoutValue }
+
+/** One component's remembered state, by key (`scroll` is kept automatically) */
+export type ComponentLayoutState = Record<string, LayoutValue>
+
+export interface LayoutSnapshot {
+  version: 1
+  components: Record<string, ComponentLayoutState>
+}
+
+/** A piece of a component's state to remember */
+export interface LayoutStateProvider {
+  /** The state now; undefined leaves it out of the snapshot */
+  save(): LayoutValue | undefined
+  /** Put back a saved value */
+  restore(value: LayoutValue): void
+}
+
+const SNAPSHOT_VERSION = 1
+const SCROLL_KEY = 'scroll'
+/** IDs the registry makes up for components given none */
+const GENERATED_ID = /^c\d+$/
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+/** Registered providers: component ID → key → provider */
+const providers = new Map<string, Map<string, LayoutStateProvider>>()
+
+/** Restored state not yet handed to a component */
+const pending = new Map<string, ComponentLayoutState>()
+
+/** Waits for components with a pending scroll to be laid out; held here so it isn't collected */
+let scrollObserver: ComponentObserver | null = null
+let stopScrollObserver: (() => void) | null = null
+
+// =============================================================================
+// REGISTRATION
+// =============================================================================
+
+/**
+ * Remember `key` of component `id` in snapshots. A restored value waiting
+ * for it is applied straight away.
+ *
+ * @returns Unregister function
+ */
+export function persistLayout(id: string, key: string, provider: LayoutStateProvider): () => void {
+  let keys = providers.get(id)
+  if (!keys) providers.set(id, (keys = new Map()))
+  keys.set(key, provider)
+
+  const waiting = pending.get(id)
+  if (waiting && key in waiting) {
+    provider.restore(waiting[key]!)
+    consume(id, key)
+  }
+
+  return () => {
+    const current = providers.get(id)
+    if (current?.get(key) !== provider) return
+    // Keep the last value, so a component that comes and goes keeps its state
+    const value = provider.save()
+    if (value !== undefined) pending.set(id, { ...pending.get(id), [key]: value })
+    current.delete(key)
+    if (current.size === 0) providers.delete(id)
+  }
+}
+
+/** Remember a signal holding JSON-safe state */
+export function persistSignal<T extends LayoutValue>(id: string, key: string, state: WritableSignal<T>): () => void {
+  return persistLayout(id, key, {
+    save: () => state.value,
+    restore: (value) => (state.value = value as T),
+  })
+}
+
+// =============================================================================
+// SNAPSHOT / RESTORE
+// =============================================================================
+
+/** Everything remembered right now, including state still waiting for its component */
+export function snapshotLayout(): LayoutSnapshot {
+  const components: Record<string, ComponentLayoutState> = {}
+  for (const [id, state] of pending) components[id] = { ...state }
+
+  const set = (id: string, key: string, value: LayoutValue) => {
+    components[id] = { ...components[id], [key]: value }
+  }
+
+  if (isInitialized()) {
+    const buf = getBuffer()
+    for (const index of getAllocatedIndices()) {
+      const id = getId(index)
+      if (!id || GENERATED_ID.test(id)) continue
+      if (getMaxScrollX(buf, index) <= 0 && getMaxScrollY(buf, index) <= 0) continue
+      set(id, SCROLL_KEY, { x: getScrollX(buf, index), y: getScrollY(buf, index) })
+    }
+  }
+
+  for (const [id, keys] of providers) {
+    for (const [key, provider] of keys) {
+      const value = provider.save()
+      if (value !== undefined) set(id, key, value)
+    }
+  }
+
+  return { version: SNAPSHOT_VERSION, components }
+}
+
+/**
+ * Put a snapshot back. Components mounted now get their state at once;
+ * the rest get it when they mount. Snapshots of another version are ignored.
+ */
+export function restoreLayout(snapshot: LayoutSnapshot): void {
+  if (snapshot?.version !== SNAPSHOT_VERSION || typeof snapshot.components !== 'object') return
+
+  for (const [id, state] of Object.entries(snapshot.components)) {
+    pending.set(id, { ...state })
+    const keys = providers.get(id)
+    if (keys) {
+      for (const [key, provider] of keys) {
+        if (key in state) {
+          provider.restore(state[key]!)
+          consume(id, key)
+        }
+      }
+    }
+    const index = getIndex(id)
+    if (index !== undefined) applyScroll(id, index)
+  }
+
+  watchScrolls()
+}
+
+/** Restore from a file written by `saveLayout`; false when there is none or it can't be read */
+export function loadLayout(file: string): boolean {
+  if (!existsSync(file)) return false
+  try {
+    restoreLayout(JSON.parse(readFileSync(file, 'utf8')) as LayoutSnapshot)
+    return true
+  } catch {
+    return false
+  }
+}
+
+/** Write a snapshot to `file` as JSON */
+export function saveLayout(file: string): void {
+  mkdirSync(dirname(file), { recursive: true })
+  writeFileSync(file, JSON.stringify(snapshotLayout(), null, 2) + '\n')
+}
+
+// =============================================================================
+// INTERNALS
+// =============================================================================
+
+function consume(id: string, key: string): void {
+  const state = pending.get(id)
+  if (!state) return
+  delete state[key]
+  if (Object.keys(state).length === 0) pending.delete(id)
+}
+
+/** Scroll a laid-out component to its saved offset (clamped to what it can scroll now) */
+function applyScroll(id: string, index: number): void {
+  const saved = pending.get(id)?.[SCROLL_KEY]
+  if (!saved || typeof saved !== 'object' || Array.isArray(saved) || !isInitialized()) return
+  const buf = getBuffer()
+  const maxX = getMaxScrollX(buf, index)
+  const maxY = getMaxScrollY(buf, index)
+  // Not laid out yet (or nothing to scroll): wait for its layout
+  if (maxX <= 0 && maxY <= 0) return
+  const x = Math.max(0, Math.min(Number(saved.x) || 0, maxX))
+  const y = Math.max(0, Math.min(Number(saved.y) || 0, maxY))
+  setScroll(buf, index, Math.round(x), Math.round(y))
+  getNotifier().notify()
+  reportScrollChange(index, Math.round(x), Math.round(y))
+  consume(id, SCROLL_KEY)
+}
+
+const hasPendingScroll = () => [...pending.values()].some((state) => SCROLL_KEY in state)
+
+/** Observe layouts while some scroll waits for its component, and only then */
+function watchScrolls(): void {
+  if (!hasPendingScroll()) {
+    stopScrollObserver?.()
+    stopScrollObserver = null
+    scrollObserver = null
+    return
+  }
+  if (scrollObserver) return
+  scrollObserver = {
+    layoutChanged: (component) => {
+      if (!pending.get(component.id)?.[SCROLL_KEY]) return
+      applyScroll(component.id, component.index)
+      if (!hasPendingScroll()) watchScrolls()
+    },
+  }
+  stopScrollObserver = observeComponents(scrollObserver)
+}
@@ -0,0 +1,48 @@
+/**
+ * SparkTUI Metrics
+ *
+ * Responsiveness numbers measured by the engine, for dashboards, perf
+ * overlays and regression checks.
+ *
+ * Input-to-paint latency runs from the moment stdin bytes are read to the
+ * first frame that changes the screen afterwards — including any round trip
+ * through TS handlers. Percentiles cover the last 256 samples; keys, paste,
+ * clicks and wheel count, bare mouse motion does not.
+ *
+ * ```ts
+ * const { p95Us } = getInputLatency()
+ * if (p95Us > 16_000) console.warn('typing feels sluggish')
+ * ```
+ */
+
+import { isInitialized, getBuffer } from '../bridge'
+import {
+  getInputLatencyP50Us,
+  getInputLatencyP95Us,
+  getInputLatencyP99Us,
+  getInputLatencyLastUs,
+  getInputLatencyCount,
+} from '../bridge/shared-buffer'
+
+export interface InputLatency {
+  p50Us: number
+  p95Us: number
+  p99Us: number
+  /** Most recent sample */
+  lastUs: number
+  /** Samples recorded since mount (0 = no data yet) */
+  samples: number
+}
+
+/** Current input-to-paint latency percentiles, in microseconds */
+export function getInputLatency(): InputLatency {
+  if (!isInitialized()) return { p50Us: 0, p95Us: 0, p99Us: 0, lastUs: 0, samples: 0 }
+  const buf = getBuffer()
+  return {
+    p50Us: getInputLatencyP50Us(buf),
+    p95Us: getInputLatencyP95Us(buf),
+    p99Us: getInputLatencyP99Us(buf),
+    lastUs: getInputLatencyLastUs(buf),
+    samples: getInputLatencyCount(buf),
+  }
+}
@@ -19,12 +19,14 @@ import {
   MOUSE_BUTTON_LEFT,
   MOUSE_BUTTON_MIDDLE,
   MOUSE_BUTTON_RIGHT,
+  MOUSE_BUTTON_BACK,
+  MOUSE_BUTTON_FORWARD,
 } from '../engine/events'
 
 // Re-export types and constants for convenience
 export type { MouseEvent } from '../engine/events'
 export type { ScrollEvent } from '../engine/events'
-export { MOUSE_BUTTON_LEFT, MOUSE_BUTTON_MIDDLE, MOUSE_BUTTON_RIGHT }
+export { MOUSE_BUTTON_LEFT, MOUSE_BUTTON_MIDDLE, MOUSE_BUTTON_RIGHT, MOUSE_BUTTON_BACK, MOUSE_BUTTON_FORWARD }
 
 // =============================================================================
 // REACTIVE STATE
@@ -104,12 +106,18 @@ export function _updateMouseState(event: SparkMouseEvent): void {
  * Mouse handlers that can be registered per component.
  */
 export interface MouseHandlers {
-  onMouseDown?: (event: SparkMouseEvent) => void
-  onMouseUp?: (event: SparkMouseEvent) => void
-  onClick?: (event: SparkMouseEvent) => void
+  onMouseDown?: (event: SparkMouseEvent) => boolean | void
+  onMouseUp?: (event: SparkMouseEvent) => boolean | void
+  onClick?: (event: SparkMouseEvent) => boolean | void
+  onContextMenu?: (event: SparkMouseEvent) => boolean | void
   onMouseEnter?: (event: SparkMouseEvent) => void
   onMouseLeave?: (event: SparkMouseEvent) => void
   onScroll?: (event: ScrollEvent) => void
+  /** Capture phase: run before any handler inside the component */
+  onMouseDownCapture?: (event: SparkMouseEvent) => boolean | void
+  onMouseUpCapture?: (event: SparkMouseEvent) => boolean | void
+  onClickCapture?: (event: SparkMouseEvent) => boolean | void
+  onContextMenuCapture?: (event: SparkMouseEvent) => boolean | void
 }
 
 /**
@@ -156,6 +164,11 @@ export function onComponent(index: number, handlers: MouseHandlers): () => void
       registerMouseHandler(index, EventType.Click, handlers.onClick)
     )
   }
+  if (handlers.onContextMenu) {
+    unsubscribers.push(
+      registerMouseHandler(index, EventType.ContextMenu, handlers.onContextMenu)
+    )
+  }
   if (handlers.onMouseEnter) {
     unsubscribers.push(
       registerMouseHandler(index, EventType.MouseEnter, handlers.onMouseEnter)
@@ -171,6 +184,26 @@ export function onComponent(index: number, handlers: MouseHandlers): () => void
       registerScrollHandler(index, handlers.onScroll)
     )
   }
+  if (handlers.onMouseDownCapture) {
+    unsubscribers.push(
+      registerMouseHandler(index, EventType.MouseDown, handlers.onMouseDownCapture, true)
+    )
+  }
+  if (handlers.onMouseUpCapture) {
+    unsubscribers.push(
+      registerMouseHandler(index, EventType.MouseUp, handlers.onMouseUpCapture, true)
+    )
+  }
+  if (handlers.onClickCapture) {
+    unsubscribers.push(
+      registerMouseHandler(index, EventType.Click, handlers.onClickCapture, true)
+    )
+  }
+  if (handlers.onContextMenuCapture) {
+    unsubscribers.push(
+      registerMouseHandler(index, EventType.ContextMenu, handlers.onContextMenuCapture, true)
+    )
+  }
 
   // Return combined unsubscribe function
   return () => {
@@ -274,11 +307,13 @@ export function isPointInBounds(
 /**
  * Get the mouse button name from event.
  */
-export function getButtonName(event: SparkMouseEvent): 'left' | 'middle' | 'right' {
+export function getButtonName(event: SparkMouseEvent): 'left' | 'middle' | 'right' | 'back' | 'forward' {
   switch (event.button) {
     case MOUSE_BUTTON_LEFT: return 'left'
     case MOUSE_BUTTON_MIDDLE: return 'middle'
     case MOUSE_BUTTON_RIGHT: return 'right'
+    case MOUSE_BUTTON_BACK: return 'back'
+    case MOUSE_BUTTON_FORWARD: return 'forward'
     default: return 'left'
   }
 }
@@ -303,3 +338,17 @@ export function isMiddleButton(event: SparkMouseEvent): boolean {
 export function isRightButton(event: SparkMouseEvent): boolean {
   return event.button === MOUSE_BUTTON_RIGHT
 }
+
+/**
+ * Check if the event was a back (side) button click.
+ */
+export function isBackButton(event: SparkMouseEvent): boolean {
+  return event.button === MOUSE_BUTTON_BACK
+}
+
+/**
+ * Check if the event was a forward (side) button click.
+ */
+export function isForwardButton(event: SparkMouseEvent): boolean {
+  return event.button === MOUSE_BUTTON_FORWARD
+}
@@ -0,0 +1,81 @@
+/**
+ * SparkTUI Performance HUD
+ *
+ * A dev overlay in the top-right corner with the engine's frame stats:
+ * frame rate, layout / framebuffer / render times, cells and bytes written
+ * to the terminal, node count, and input-to-paint latency percentiles (as
+ * `getInputLatency()` reports them). Rust draws it over the frame, so it
+ * costs nothing while off.
+ *
+ * ```ts
+ * mount(App, { perfHud: { key: KEY_F9 } })   // F9 toggles it
+ * setPerfHud(true)                           // or switch it from code
+ * const { cells, bytes } = getFrameOutput()  // the same numbers, for logging
+ * ```
+ */
+
+import { isInitialized, getBuffer, getNotifier } from '../bridge'
+import {
+  getPerfHud as readPerfHud,
+  setPerfHud as writePerfHud,
+  setPerfHudBinding,
+  getFrameCells,
+  getFrameBytes,
+} from '../bridge/shared-buffer'
+import { encodeKeyBinding } from './keyboard'
+import type { KeyBinding } from './keyboard'
+
+/** Toggle key: key code (KEY_* constant) or a single character (default: none) */
+export interface PerfHudOptions extends KeyBinding {
+  /** Show the HUD from the start (default: false) */
+  visible?: boolean
+}
+
+/** What the last frame wrote to the terminal */
+export interface FrameOutput {
+  cells: number
+  bytes: number
+}
+
+/**
+ * Bind (or rebind) the HUD toggle key and set its visibility.
+ * Called by mount() with its `perfHud` option.
+ */
+export function configurePerfHud(options: PerfHudOptions | false): void {
+  if (!isInitialized()) return
+  const buf = getBuffer()
+
+  if (options === false) {
+    setPerfHudBinding(buf, 0, 0)
+    setPerfHud(false)
+    return
+  }
+
+  const { visible = false } = options
+  const { keycode, modifiers } = encodeKeyBinding(options)
+  setPerfHudBinding(buf, keycode, modifiers)
+  setPerfHud(visible)
+}
+
+/** Show or hide the performance HUD */
+export function setPerfHud(visible: boolean): void {
+  if (!isInitialized()) return
+  writePerfHud(getBuffer(), visible)
+  getNotifier().notify()
+}
+
+/** Whether the HUD is showing (the toggle key flips it on the Rust side) */
+export function getPerfHud(): boolean {
+  return isInitialized() && readPerfHud(getBuffer())
+}
+
+export function togglePerfHud(): void {
+  setPerfHud(!getPerfHud())
+}
+
+/** Cells and bytes the last frame wrote to the terminal */
+export function getFrameOutput(): FrameOutput {
+  if (!isInitialized()) return { cells: 0, bytes: 0 }
+  const buf = getBuffer()
+  return { cells: getFrameCells(buf), bytes: getFrameBytes(buf) }
+}
@@ -0,0 +1,94 @@
+/**
+ * SparkTUI Screenshot Binding
+ *
+ * An app-level key that saves the current frame — handy for bug reports
+ * and documentation. Rust does the capture (it owns the framebuffer):
+ *
+ * - writes `spark-screenshot-<ms>.txt` and `.html` to the working directory,
+ *   plus a `.png` with `image` or `copyImage`
+ * - optionally puts the PNG on the clipboard (kitty), falling back to its path
+ * - optionally copies the text file's path to the clipboard (OSC 52)
+ * - flashes a confirmation toast in the bottom-right corner
+ *
+ * Off unless mount() is given a `screenshot` option. TS only configures the
+ * binding and takes the toast down again.
+ *
+ * ```ts
+ * mount(App, { screenshot: {} })  // F12
+ * mount(App, { screenshot: { key: KEY_F12, copyPath: true } })
+ * mount(App, { screenshot: { key: 's', ctrl: true, copyImage: true } })
+ * onScreenshot(({ timestamp, saved }) => log(saved ? `saved ${timestamp}` : 'failed'))
+ * ```
+ */
+
+import { isInitialized, getBuffer, getNotifier } from '../bridge'
+import {
+  setScreenshotBinding,
+  setScreenshotToast,
+  SCREENSHOT_COPY_PATH,
+  SCREENSHOT_IMAGE,
+  SCREENSHOT_COPY_IMAGE,
+} from '../bridge/shared-buffer'
+import { encodeKeyBinding } from './keyboard'
+import type { KeyBinding } from './keyboard'
+import { registerScreenshotHandler, KEY_F12 } from '../engine/events'
+import type { ScreenshotHandler } from '../engine/events'
+
+/** Key: key code (KEY_* constant) or a single character (default: KEY_F12) */
+export interface ScreenshotOptions extends KeyBinding {
+  /** Copy the saved path to the clipboard via OSC 52 (default: false) */
+  copyPath?: boolean
+  /** Also save a `.png` image (default: false) */
+  image?: boolean
+  /**
+   * Put the PNG on the clipboard (default: false). Needs a terminal with
+   * image clipboard support (kitty); elsewhere the PNG's path is copied.
+   */
+  copyImage?: boolean
+  /** How long the confirmation toast stays up, in ms (default: 1500) */
+  toastDuration?: number
+}
+
+const DEFAULT_TOAST_DURATION = 1500
+
+let toastTimer: ReturnType<typeof setTimeout> | null = null
+let unsubscribe: (() => void) | null = null
+
+/**
+ * Bind (or rebind) the screenshot key. Pass `false` to turn it off.
+ * Called by mount() with its `screenshot` option.
+ */
+export function configureScreenshot(options: ScreenshotOptions | false): void {
+  if (!isInitialized()) return
+  const buf = getBuffer()
+
+  unsubscribe?.()
+  unsubscribe = null
+
+  if (options === false) {
+    setScreenshotBinding(buf, 0, 0, 0)
+    return
+  }
+
+  const { key = KEY_F12, copyPath, image, copyImage, toastDuration = DEFAULT_TOAST_DURATION } = options
+  const { keycode, modifiers } = encodeKeyBinding({ ...options, key })
+  const flags =
+    (copyPath ? SCREENSHOT_COPY_PATH : 0) | (image ? SCREENSHOT_IMAGE : 0) | (copyImage ? SCREENSHOT_COPY_IMAGE : 0)
+  setScreenshotBinding(buf, keycode, modifiers, flags)
+
+  // Rust raised the toast with the capture — take it down after a moment
+  unsubscribe = registerScreenshotHandler(() => {
+    if (toastTimer) clearTimeout(toastTimer)
+    toastTimer = setTimeout(() => {
+      toastTimer = null
+      if (!isInitialized()) return
+      setScreenshotToast(getBuffer(), false)
+      getNotifier().notify()
+    }, toastDuration)
+  })
+}
+
+/** Run `handler` after every screenshot (e.g. to log or upload the files) */
+export function onScreenshot(handler: ScreenshotHandler): () => void {
+  return registerScreenshotHandler(handler)
+}
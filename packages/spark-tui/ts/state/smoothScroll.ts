@@ -0,0 +1,149 @@
+/**
+ * SparkTUI Smooth Scrolling
+ *
+ * Scrollables with `smoothScroll` ease to their new offset on a spring
+ * instead of jumping. The work is split across the bridge:
+ *
+ * - Rust handles wheel, keys, chaining and scroll-into-view as usual, but
+ *   writes the result to N_SCROLL_TARGET_X/Y, raises N_SCROLL_ANIMATING and
+ *   pushes a ScrollAnimate event. Further scrolls build on the pending
+ *   target, so fast wheel ticks accumulate.
+ * - TS springs N_SCROLL_X/Y toward the target on the animation clock,
+ *   publishing velocity to N_SCROLL_VELOCITY_X/Y, and clears
+ *   N_SCROLL_ANIMATING once it settles.
+ *
+ * ```ts
+ * box({ overflow: 'scroll', smoothScroll: { stiffness: 260, damping: 30 }, children })
+ * ```
+ */
+
+import { isInitialized, getBridge, getBuffer, getNotifier, bridgeState, withBridge } from '../bridge'
+import {
+  setScroll,
+  getScrollX,
+  getScrollY,
+  getScrollTargetX,
+  getScrollTargetY,
+  setScrollVelocity,
+  clearScrollAnimating,
+  isScrollAnimating,
+  getMaxScrollX,
+  getMaxScrollY,
+} from '../bridge/shared-buffer'
+import { registerScrollAnimateHandler, reportScrollChange } from '../engine/events'
+import { driveSpring } from '../primitives/animation'
+import type { SpringConfig, SpringDriver, SpringState } from '../primitives/animation'
+
+interface ScrollAnimation {
+  x: SpringState
+  y: SpringState
+  drivers: SpringDriver[]
+  /** Axes still moving */
+  pending: number
+}
+
+interface SmoothScrollState {
+  /** Spring per smooth-scrolling node */
+  configs: Map<number, SpringConfig>
+  animations: Map<number, ScrollAnimation>
+  /** The ScrollAnimate handler, installed while any node scrolls smoothly */
+  unsubscribe: (() => void) | null
+}
+
+const smoothScroll = bridgeState<SmoothScrollState>(() => ({
+  configs: new Map(),
+  animations: new Map(),
+  unsubscribe: null,
+}))
+
+const clamp = (value: number, max: number) => Math.max(0, Math.min(Math.round(value), Math.floor(max)))
+
+/** Snap to the target and acknowledge it — used when no spring applies */
+function jump(index: number): void {
+  const buf = getBuffer()
+  const x = getScrollTargetX(buf, index)
+  const y = getScrollTargetY(buf, index)
+  setScroll(buf, index, x, y)
+  setScrollVelocity(buf, index, 0, 0)
+  clearScrollAnimating(buf, index)
+  getNotifier().notify()
+  reportScrollChange(index, x, y)
+}
+
+function animate(index: number): void {
+  const { configs, animations } = smoothScroll()
+  if (!isInitialized() || animations.has(index)) return // running springs re-read the target every frame
+  // The clock ticks outside any instance: frames run in this one
+  const bridge = getBridge()
+  const buf = getBuffer()
+  const config = configs.get(index)
+  if (!config) {
+    if (isScrollAnimating(buf, index)) jump(index)
+    return
+  }
+
+  const anim: ScrollAnimation = {
+    x: { position: getScrollX(buf, index), velocity: 0 },
+    y: { position: getScrollY(buf, index), velocity: 0 },
+    drivers: [],
+    pending: 2,
+  }
+
+  const onFrame = () => withBridge(bridge, () => {
+    const x = clamp(anim.x.position, getMaxScrollX(buf, index))
+    const y = clamp(anim.y.position, getMaxScrollY(buf, index))
+    const moved = x !== getScrollX(buf, index) || y !== getScrollY(buf, index)
+    setScroll(buf, index, x, y)
+    setScrollVelocity(buf, index, anim.x.velocity, anim.y.velocity)
+    getNotifier().notify()
+    if (moved) reportScrollChange(index, x, y)
+  })
+
+  const onSettle = () => withBridge(bridge, () => {
+    if (--anim.pending > 0 || animations.get(index) !== anim) return
+    animations.delete(index)
+    clearScrollAnimating(buf, index)
+    // Rust may have moved the target between the last frame and the clear
+    if (getScrollTargetX(buf, index) !== getScrollX(buf, index) || getScrollTargetY(buf, index) !== getScrollY(buf, index)) {
+      animate(index)
+    }
+  })
+
+  animations.set(index, anim)
+  anim.drivers.push(
+    driveSpring(anim.x, () => getScrollTargetX(buf, index), config, onFrame, onSettle),
+    driveSpring(anim.y, () => getScrollTargetY(buf, index), config, onFrame, onSettle)
+  )
+}
+
+function stop(index: number): void {
+  const { animations } = smoothScroll()
+  const anim = animations.get(index)
+  if (!anim) return
+  animations.delete(index)
+  for (const driver of anim.drivers) driver.cancel()
+}
+
+/**
+ * Spring a node's scroll offset toward the targets Rust sets.
+ * Called by box() for `smoothScroll`; returns the unregister function.
+ */
+export function registerSmoothScroll(index: number, config: SpringConfig = {}): () => void {
+  const state = smoothScroll()
+  // A recycled index may still have the last node's springs running
+  stop(index)
+  state.configs.set(index, config)
+  state.unsubscribe ??= registerScrollAnimateHandler((event) => animate(event.componentIndex))
+
+  return () => {
+    if (state.configs.get(index) !== config) return
+    state.configs.delete(index)
+    stop(index)
+    // Land a half-finished scroll where it was headed
+    if (isInitialized() && isScrollAnimating(getBuffer(), index)) jump(index)
+    if (state.configs.size === 0) {
+      state.unsubscribe?.()
+      state.unsubscribe = null
+    }
+  }
+}
@@ -0,0 +1,184 @@
+/**
+ * SparkTUI Design Assist - Snapping & Alignment Guides
+ *
+ * Geometry for apps that let the user move or resize panels (dashboard
+ * editors, window managers built on mouse drags):
+ *
+ * - Snap a rect to a cell grid
+ * - Snap its edges and centers to nearby siblings, reporting the guide
+ *   lines that matched so the app can draw them
+ *
+ * SparkTUI has no built-in window/drag layer yet, so this is the piece such
+ * a layer calls from its move handler. Feed it the proposed rect, apply the
+ * returned one, and render `assist.guides` while the drag is active.
+ *
+ * ```ts
+ * const assist = designAssist({ grid: 2, threshold: 1 })
+ * onGlobalMove((x, y) => {
+ *   if (!dragging) return
+ *   panel.value = assist.move({ ...panel.value, x: x - grabX, y: y - grabY }, otherPanels)
+ * })
+ * onGlobalMouse((e) => e.type === EventType.MouseUp && assist.end())
+ * ```
+ */
+
+import { signal } from '@rlabs-inc/signals'
+import type { ReadableSignal } from '@rlabs-inc/signals'
+
+export interface SnapRect {
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+/** A matched alignment: a vertical line at `position` (axis 'x') or a horizontal one (axis 'y') */
+export interface AlignmentGuide {
+  axis: 'x' | 'y'
+  /** Column (axis 'x') or row (axis 'y') of the line */
+  position: number
+  /** Extent along the line, covering both aligned rects */
+  start: number
+  end: number
+}
+
+export interface DesignAssistOptions {
+  /** Grid cell size in columns/rows; a number applies to both (default: 1 = no grid) */
+  grid?: number | { x: number; y: number }
+  /** Snap to a sibling edge/center within this many cells (default: 1, 0 disables) */
+  threshold?: number
+  /** Report alignment guides (default: true) */
+  guides?: boolean
+}
+
+export interface DesignAssist {
+  /** Snap a proposed position; updates `guides` */
+  move(rect: SnapRect, siblings: readonly SnapRect[]): SnapRect
+  /** Snap a proposed size (right/bottom edges move, x/y stay); updates `guides` */
+  resize(rect: SnapRect, siblings: readonly SnapRect[]): SnapRect
+  /** Alignment guides from the last move */
+  guides: ReadableSignal<AlignmentGuide[]>
+  /** Clear the guides (call when the drag ends) */
+  end(): void
+}
+
+const DEFAULT_THRESHOLD = 1
+
+const roundTo = (value: number, step: number) => (step > 1 ? Math.round(value / step) * step : value)
+
+/** Round a rect's position (or, for a resize, its size) to the grid */
+export function snapToGrid(rect: SnapRect, grid: number | { x: number; y: number }, resize = false): SnapRect {
+  const { x: gx, y: gy } = typeof grid === 'number' ? { x: grid, y: grid } : grid
+  return resize
+    ? { ...rect, width: Math.max(1, roundTo(rect.width, gx)), height: Math.max(1, roundTo(rect.height, gy)) }
+    : { ...rect, x: roundTo(rect.x, gx), y: roundTo(rect.y, gy) }
+}
+
+/** Start, center and end of a rect on one axis */
+function anchors(rect: SnapRect, axis: 'x' | 'y'): number[] {
+  const start = axis === 'x' ? rect.x : rect.y
+  const size = axis === 'x' ? rect.width : rect.height
+  return [start, start + Math.floor(size / 2), start + size - 1]
+}
+
+/**
+ * Find the shift on `axis` to the nearest sibling anchor within `threshold`
+ * (only the far edge when resizing) and the guides at the resulting alignment.
+ */
+function alignAxis(
+  rect: SnapRect,
+  siblings: readonly SnapRect[],
+  axis: 'x' | 'y',
+  threshold: number,
+  resize: boolean
+): { offset: number; guides: AlignmentGuide[] } {
+  const own = resize ? anchors(rect, axis).slice(2) : anchors(rect, axis)
+  let offset = 0
+  let best = threshold + 1
+  for (const sibling of siblings) {
+    for (const target of anchors(sibling, axis)) {
+      for (const anchor of own) {
+        const delta = target - anchor
+        if (Math.abs(delta) < Math.abs(best)) best = delta
+      }
+    }
+  }
+  if (Math.abs(best) <= threshold) offset = best
+
+  // Every anchor that now lines up with a sibling gets a guide
+  const moved = shift(rect, axis, offset, resize)
+  const cross = axis === 'x' ? 'y' : 'x'
+  const guides: AlignmentGuide[] = []
+  for (const sibling of siblings) {
+    const shared = anchors(moved, axis).filter((a) => anchors(sibling, axis).includes(a))
+    for (const position of shared) {
+      const [movedStart, , movedEnd] = anchors(moved, cross)
+      const [siblingStart, , siblingEnd] = anchors(sibling, cross)
+      guides.push({
+        axis,
+        position,
+        start: Math.min(movedStart!, siblingStart!),
+        end: Math.max(movedEnd!, siblingEnd!),
+      })
+    }
+  }
+  return { offset, guides }
+}
+
+/** Apply an alignment offset: to the position, or to the size when resizing */
+function shift(rect: SnapRect, axis: 'x' | 'y', offset: number, resize: boolean): SnapRect {
+  if (resize) {
+    return axis === 'x'
+      ? { ...rect, width: Math.max(1, rect.width + offset) }
+      : { ...rect, height: Math.max(1, rect.height + offset) }
+  }
+  return axis === 'x' ? { ...rect, x: rect.x + offset } : { ...rect, y: rect.y + offset }
+}
+
+/**
+ * Snap a rect: grid first, then sibling alignment (which wins when in range,
+ * so panels line up even off-grid).
+ */
+export function snapRect(
+  rect: SnapRect,
+  siblings: readonly SnapRect[],
+  options: DesignAssistOptions = {},
+  resize = false
+): { rect: SnapRect; guides: AlignmentGuide[] } {
+  const { grid = 1, threshold = DEFAULT_THRESHOLD, guides: wantGuides = true } = options
+  const gridded = snapToGrid(rect, grid, resize)
+  if (threshold <= 0 && !wantGuides) return { rect: gridded, guides: [] }
+
+  const limit = Math.max(0, threshold)
+  const x = alignAxis(gridded, siblings, 'x', limit, resize)
+  const alignedX = shift(gridded, 'x', x.offset, resize)
+  const y = alignAxis(alignedX, siblings, 'y', limit, resize)
+  return {
+    rect: shift(alignedX, 'y', y.offset, resize),
+    guides: wantGuides ? [...x.guides, ...y.guides] : [],
+  }
+}
+
+/**
+ * Design-assist session for one drag/resize interaction.
+ * `guides` is a signal, so an overlay can render it reactively.
+ */
+export function designAssist(options: DesignAssistOptions = {}): DesignAssist {
+  const guides = signal<AlignmentGuide[]>([])
+  return {
+    move(rect, siblings) {
+      const result = snapRect(rect, siblings, options)
+      guides.value = result.guides
+      return result.rect
+    },
+    resize(rect, siblings) {
+      const result = snapRect(rect, siblings, options, true)
+      guides.value = result.guides
+      return result.rect
+    },
+    guides,
+    end() {
+      if (guides.value.length > 0) guides.value = []
+    },
+  }
+}
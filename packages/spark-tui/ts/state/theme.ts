@@ -12,18 +12,30 @@
  * - string: CSS color parsed via parseColor()
  *
  * The DEFAULT theme uses terminal colors so the UI matches the user's terminal.
+ * terminalAdaptive() goes further, deriving muted/surface/border colors from
+ * the colors the terminal reports for itself, and setThemePair() switches
+ * between a light and a dark theme as the terminal's background changes.
  * Custom themes (Dracula, Nord, etc.) override with specific RGB values.
  */
 
-import { state, derived } from '@rlabs-inc/signals'
+import { state, derived, effect } from '@rlabs-inc/signals'
 import type { RGBA } from '../types'
 import {
   parseColor,
   TERMINAL_DEFAULT,
   ansiColor,
   isAnsiColor,
+  isTerminalDefault,
+  getAnsiIndex,
   adjustLightnessForContrast,
+  brighten,
+  dim,
+  rgbaLerp,
+  rgbToOklch,
+  toHex,
 } from '../types/color'
+import { capabilities } from './capabilities'
+import type { InteractionState } from './interaction'
 
 // =============================================================================
 // THEME COLOR TYPE
@@ -117,15 +129,15 @@ export const theme = state({
   /** Overlay (modals) - terminal default */
   overlay: null as ThemeColor,
 
-  // // =========================================================================
-  // // BORDER COLORS
-  // // =========================================================================
+  // =========================================================================
+  // BORDER COLORS
+  // =========================================================================
 
-  // /** Default border - ANSI white (7) */
-  // border: 7 as ThemeColor,
+  /** Default border - ANSI white (7) */
+  border: 7 as ThemeColor,
 
-  // /** Focused border - primary color */
-  // borderFocus: 12 as ThemeColor,
+  /** Focused border - primary color */
+  borderFocus: 12 as ThemeColor,
 
   // =========================================================================
   // METADATA
@@ -164,8 +176,8 @@ export const themes = {
     backgroundMuted: null,
     surface: null,
     overlay: null,
-    // border: 7,
-    // borderFocus: 12,
+    border: 7,
+    borderFocus: 12,
   },
 
   /**
@@ -192,8 +204,8 @@ export const themes = {
     backgroundMuted: 0x343746,
     surface: 0x44475a,
     overlay: 0x21222c,
-    // border: 0x6272a4,
-    // borderFocus: 0xbd93f9,
+    border: 0x6272a4,
+    borderFocus: 0xbd93f9,
   },
 
   /**
@@ -219,8 +231,8 @@ export const themes = {
     backgroundMuted: 0x3b4252,
     surface: 0x434c5e,
     overlay: 0x2e3440,
-    // border: 0x4c566a,
-    // borderFocus: 0x88c0d0,
+    border: 0x4c566a,
+    borderFocus: 0x88c0d0,
   },
 
   /**
@@ -246,8 +258,8 @@ export const themes = {
     backgroundMuted: 0x3e3d32,
     surface: 0x49483e,
     overlay: 0x1e1f1c,
-    // border: 0x75715e,
-    // borderFocus: 0xf92672,
+    border: 0x75715e,
+    borderFocus: 0xf92672,
   },
 
   /**
@@ -273,8 +285,8 @@ export const themes = {
     backgroundMuted: 0x073642, // base02
     surface: 0x073642,
     overlay: 0x002b36,
-    // border: 0x586e75,
-    // borderFocus: 0x268bd2,
+    border: 0x586e75,
+    borderFocus: 0x268bd2,
   },
 
   /**
@@ -300,8 +312,35 @@ export const themes = {
     backgroundMuted: 0x313244, // surface0
     surface: 0x45475a, // surface1
     overlay: 0x181825, // mantle
-    // border: 0x6c7086, // overlay0
-    // borderFocus: 0x89b4fa, // blue
+    border: 0x6c7086, // overlay0
+    borderFocus: 0x89b4fa, // blue
+  },
+
+  /**
+   * Catppuccin Latte - the light flavor, a day counterpart for setThemePair().
+   */
+  catppuccinLatte: {
+    name: 'catppuccinLatte',
+    description: 'Catppuccin Latte theme',
+    primary: 0x1e66f5, // blue
+    secondary: 0x8839ef, // mauve
+    tertiary: 0x179299, // teal
+    accent: 0xdf8e1d, // yellow
+    success: 0x40a02b, // green
+    warning: 0xdf8e1d, // yellow
+    error: 0xd20f39, // red
+    info: 0x04a5e5, // sky
+    text: 0x4c4f69, // text
+    textMuted: 0x6c6f85, // subtext0
+    textDim: 0x8c8fa1, // overlay1
+    textDisabled: 0xacb0be, // surface2
+    textBright: 0x11111b,
+    background: 0xeff1f5, // base
+    backgroundMuted: 0xe6e9ef, // mantle
+    surface: 0xccd0da, // surface0
+    overlay: 0xdce0e8, // crust
+    border: 0x9ca0b0, // overlay0
+    borderFocus: 0x1e66f5, // blue
   },
 
   /**
@@ -327,8 +366,8 @@ export const themes = {
     backgroundMuted: 0x3c3836, // bg1
     surface: 0x504945, // bg2
     overlay: 0x1d2021, // bg0_h
-    // border: 0x665c54, // bg3
-    // borderFocus: 0xfe8019, // orange
+    border: 0x665c54, // bg3
+    borderFocus: 0xfe8019, // orange
   },
 
   /**
@@ -354,8 +393,8 @@ export const themes = {
     backgroundMuted: 0x24283b, // bg_highlight
     surface: 0x414868, // dark3
     overlay: 0x16161e, // bg_dark
-    // border: 0x414868, // dark3
-    // borderFocus: 0x7aa2f7, // blue
+    border: 0x414868, // dark3
+    borderFocus: 0x7aa2f7, // blue
   },
 
   /**
@@ -381,8 +420,8 @@ export const themes = {
     backgroundMuted: 0x21252b, // bg_dark
     surface: 0x3e4451, // guide
     overlay: 0x1e2127, // bg_darker
-    // border: 0x3e4451, // guide
-    // borderFocus: 0x61afef, // blue
+    border: 0x3e4451, // guide
+    borderFocus: 0x61afef, // blue
   },
 
   /**
@@ -408,8 +447,8 @@ export const themes = {
     backgroundMuted: 0x1f1d2e, // surface
     surface: 0x26233a, // overlay
     overlay: 0x16141f, // nc
-    // border: 0x524f67, // highlight_med
-    // borderFocus: 0xebbcba, // rose
+    border: 0x524f67, // highlight_med
+    borderFocus: 0xebbcba, // rose
   },
 
   /**
@@ -435,8 +474,8 @@ export const themes = {
     backgroundMuted: 0x2a2a37, // sumiInk2
     surface: 0x363646, // sumiInk3
     overlay: 0x16161d, // sumiInk0
-    // border: 0x54546d, // sumiInk4
-    // borderFocus: 0x7e9cd8, // crystalBlue
+    border: 0x54546d, // sumiInk4
+    borderFocus: 0x7e9cd8, // crystalBlue
   },
 
   /**
@@ -462,8 +501,8 @@ export const themes = {
     backgroundMuted: 0x343f44, // bg1
     surface: 0x3d484d, // bg3
     overlay: 0x272e33, // bg0
-    // border: 0x5c6a72, // bg5
-    // borderFocus: 0xa7c080, // green
+    border: 0x5c6a72, // bg5
+    borderFocus: 0xa7c080, // green
   },
 
   /**
@@ -489,8 +528,8 @@ export const themes = {
     backgroundMuted: 0x0b2942, // bg_light
     surface: 0x1d3b53, // selection
     overlay: 0x010e1a, // bg_dark
-    // border: 0x5f7e97, // lineHighlight
-    // borderFocus: 0x82aaff, // blue
+    border: 0x5f7e97, // lineHighlight
+    borderFocus: 0x82aaff, // blue
   },
 }
 
@@ -523,6 +562,133 @@ export function getThemeNames(): string[] {
   return Object.keys(themes)
 }
 
+// =============================================================================
+// TERMINAL-ADAPTIVE THEME
+// =============================================================================
+
+const BLACK: RGBA = { r: 0, g: 0, b: 0, a: 255 }
+const WHITE: RGBA = { r: 255, g: 255, b: 255, a: 255 }
+
+/** OKLCH lightness below which a background counts as dark */
+const DARK_BACKGROUND_LIGHTNESS = 0.5
+
+/** Light or dark, as a background or a theme is */
+export type ColorScheme = 'light' | 'dark'
+
+function schemeOf(background: RGBA): ColorScheme {
+  return rgbToOklch(background).l < DARK_BACKGROUND_LIGHTNESS ? 'dark' : 'light'
+}
+
+/**
+ * The terminal's scheme, judged from the background it reports (null until
+ * it does). Terminals with color scheme notifications report again when the
+ * system switches between light and dark.
+ */
+export const terminalScheme = derived<ColorScheme | null>(() => {
+  const { background } = capabilities.palette
+  return background ? schemeOf(background) : null
+})
+
+/**
+ * A theme derived from the colors the terminal reports (OSC 10/11).
+ *
+ * Accents stay ANSI indices - they already are the terminal's palette. What
+ * the terminal theme can only approximate with index 8 or leave to the
+ * default background is synthesized instead: muted text blends the
+ * foreground toward the background, surfaces and borders lift the background
+ * toward the foreground, and a light or dark background decides which way
+ * bright text and overlays go.
+ *
+ * Until the terminal answers, or when it shows only 16 colors, this is the
+ * terminal theme. Reading the palette is reactive, so inside an effect it
+ * follows late replies - see followTerminalPalette().
+ */
+export function terminalAdaptive(): Partial<typeof theme> {
+  const base = {
+    ...themes.terminal,
+    name: 'terminalAdaptive',
+    description: "Derived from the terminal's reported colors",
+  }
+  const { foreground, background } = capabilities.palette
+  const depth = capabilities.colorDepth
+  if (!foreground || !background || depth === 'mono' || depth === '16') return base
+
+  const dark = schemeOf(background) === 'dark'
+  // Hex strings - numbers 0-255 would read as ANSI indices
+  const text = (amount: number) => toHex(rgbaLerp(foreground, background, amount))
+  const lift = (amount: number) => toHex(rgbaLerp(background, foreground, amount))
+
+  return {
+    ...base,
+    textMuted: text(0.4),
+    textDim: text(0.5),
+    textDisabled: text(0.65),
+    textBright: toHex(rgbaLerp(foreground, dark ? WHITE : BLACK, 0.5)),
+    backgroundMuted: lift(0.05),
+    surface: lift(0.1),
+    overlay: toHex(rgbaLerp(background, BLACK, dark ? 0.25 : 0.06)),
+    border: lift(0.3),
+  }
+}
+
+/**
+ * Apply terminalAdaptive() now and again whenever the terminal reports new
+ * colors. Returns a function that stops following.
+ *
+ * ```ts
+ * mount(App)
+ * followTerminalPalette()
+ * ```
+ */
+export function followTerminalPalette(): () => void {
+  return effect(() => setTheme(terminalAdaptive()))
+}
+
+// =============================================================================
+// LIGHT/DARK THEME PAIR
+// =============================================================================
+
+/** A preset name, a theme object, or a function building one (read reactively) */
+export type ThemeSource = keyof typeof themes | Partial<typeof theme> | (() => Partial<typeof theme>)
+
+export interface ThemePairOptions {
+  /**
+   * Which half to show. Defaults to the terminal's scheme (terminalScheme);
+   * pass a function to follow your own signal instead. Returning null falls
+   * back to `fallback`.
+   */
+  scheme?: () => ColorScheme | null
+  /** Scheme while none is known yet (default 'dark') */
+  fallback?: ColorScheme
+}
+
+let stopPair: (() => void) | null = null
+
+/**
+ * Register a light and a dark theme and keep whichever matches the current
+ * scheme applied. Components follow through the reactive theme; nothing
+ * remounts. A new pair replaces the previous one; pass null to stop.
+ *
+ * ```ts
+ * setThemePair({ light: 'catppuccinLatte', dark: 'catppuccin' })
+ * setThemePair({ light: lightTheme, dark: terminalAdaptive }, { scheme: () => prefs.scheme })
+ * ```
+ */
+export function setThemePair(
+  pair: { light: ThemeSource; dark: ThemeSource } | null,
+  options: ThemePairOptions = {}
+): void {
+  stopPair?.()
+  stopPair = null
+  if (!pair) return
+
+  const { scheme = () => terminalScheme.value, fallback = 'dark' } = options
+  stopPair = effect(() => {
+    const source = pair[scheme() ?? fallback]
+    setTheme(typeof source === 'function' ? source() : source)
+  })
+}
+
 // =============================================================================
 // COLOR RESOLUTION
 // =============================================================================
@@ -586,8 +752,8 @@ export const resolvedTheme = derived(() => ({
   backgroundMuted: resolveColor(theme.backgroundMuted),
   surface: resolveColor(theme.surface),
   overlay: resolveColor(theme.overlay),
-  // border: resolveColor(theme.border),
-  // borderFocus: resolveColor(theme.borderFocus),
+  border: resolveColor(theme.border),
+  borderFocus: resolveColor(theme.borderFocus),
 }))
 
 // =============================================================================
@@ -638,8 +804,8 @@ export const t = {
   overlay: derived(() => resolveColor(theme.overlay)),
 
   // Borders
-  // border: derived(() => resolveColor(theme.border)),
-  // borderFocus: derived(() => resolveColor(theme.borderFocus)),
+  border: derived(() => resolveColor(theme.border)),
+  borderFocus: derived(() => resolveColor(theme.borderFocus)),
 }
 
 // =============================================================================
@@ -809,3 +975,57 @@ export function getVariantStyle(variant: Variant): VariantStyle {
 export function variantStyle(variant: Variant) {
   return derived(() => getVariantStyle(variant))
 }
+
+// =============================================================================
+// INTERACTIVE STATES
+// =============================================================================
+
+/** How far hover lightens and press darkens an RGB fill */
+const HOVER_BRIGHTEN = 1.15
+const PRESS_DIM = 0.8
+
+/** The other half of an ANSI color's normal/bright pair (4 ↔ 12), for indices 0-15 */
+const ANSI_BRIGHT_BIT = 8
+const ANSI_BASIC_COLORS = 16
+
+/**
+ * A variant's colors for an interaction state:
+ * - focused: the variant's focus border
+ * - hovered: filled variants lighten (RGB) or brighten their text (ANSI);
+ *   unfilled ones (ghost, outline, default) brighten their text
+ * - pressed: filled variants darken (RGB) or swap to the normal/bright
+ *   partner (ANSI); unfilled ones fill with the focus color
+ * - disabled: disabled text and border, no other state shows
+ */
+export function getVariantStateStyle(variant: Variant, state: InteractionState): VariantStyle {
+  const base = getVariantStyle(variant)
+  const resolved = resolvedTheme.value
+
+  if (state.disabled) {
+    return { ...base, fg: resolved.textDisabled, border: resolved.textDisabled, borderFocus: resolved.textDisabled }
+  }
+
+  let { fg, bg } = base
+  const border = state.focused ? base.borderFocus : base.border
+
+  if (isTerminalDefault(bg)) {
+    if (state.pressed) {
+      bg = base.borderFocus
+      fg = getContrastFg(resolved.textBright, bg)
+    } else if (state.hovered) {
+      fg = resolved.textBright
+    }
+  } else if (isAnsiColor(bg)) {
+    const index = getAnsiIndex(bg)
+    if (state.pressed && index < ANSI_BASIC_COLORS) bg = ansiColor(index ^ ANSI_BRIGHT_BIT)
+    else if (state.hovered || state.pressed) fg = resolved.textBright
+  } else if (state.pressed) {
+    bg = dim(bg, PRESS_DIM)
+    fg = getContrastFg(fg, bg)
+  } else if (state.hovered) {
+    bg = brighten(bg, HOVER_BRIGHTEN)
+    fg = getContrastFg(fg, bg)
+  }
+
+  return { fg, bg, border, borderFocus: base.borderFocus }
+}
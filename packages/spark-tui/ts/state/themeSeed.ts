@@ -0,0 +1,141 @@
+/**
+ * SparkTUI - Themes from a seed color
+ *
+ * themeFromSeed() builds every theme slot from one brand color, for apps
+ * that want their own look without picking each color by hand:
+ *
+ * ```ts
+ * setTheme(themeFromSeed('#7c3aed'))
+ * setThemePair({ light: themeFromSeed(brand, { scheme: 'light' }), dark: themeFromSeed(brand) })
+ * ```
+ *
+ * Everything is laid out in OKLCH, where equal steps look equal:
+ * - neutrals (text, backgrounds, surfaces, borders) are a lightness ramp
+ *   tinted with a trace of the seed's hue
+ * - primary keeps the seed's hue and chroma; secondary, tertiary and accent
+ *   sit at fixed hue offsets from it
+ * - success/warning/error/info keep their conventional hues, at the same
+ *   lightness and chroma as the accents so the set reads as one palette
+ *
+ * Text and accents are then nudged until they reach WCAG contrast against
+ * the background: 7:1 for text, 4.5:1 for muted text and accents.
+ * Disabled text stays faint on purpose.
+ */
+
+import type { RGBA } from '../types'
+import { adjustLightnessForContrast, oklch, parseColor, rgbToOklch, toHex } from '../types/color'
+import type { theme, ColorScheme } from './theme'
+
+export interface SeedThemeOptions {
+  /** Build for a dark or a light background (default 'dark') */
+  scheme?: ColorScheme
+  /** Theme name (default 'seed') */
+  name?: string
+}
+
+/** Lightness stops per scheme */
+interface Ramp {
+  background: number
+  backgroundMuted: number
+  surface: number
+  overlay: number
+  border: number
+  text: number
+  textMuted: number
+  textDim: number
+  textDisabled: number
+  textBright: number
+  /** Accents and semantic colors */
+  accent: number
+}
+
+const RAMPS: Record<ColorScheme, Ramp> = {
+  dark: {
+    background: 0.18,
+    backgroundMuted: 0.22,
+    surface: 0.27,
+    overlay: 0.14,
+    border: 0.4,
+    text: 0.92,
+    textMuted: 0.7,
+    textDim: 0.6,
+    textDisabled: 0.45,
+    textBright: 0.98,
+    accent: 0.72,
+  },
+  light: {
+    background: 0.98,
+    backgroundMuted: 0.95,
+    surface: 0.91,
+    overlay: 0.93,
+    border: 0.78,
+    text: 0.25,
+    textMuted: 0.45,
+    textDim: 0.55,
+    textDisabled: 0.72,
+    textBright: 0.12,
+    accent: 0.55,
+  },
+}
+
+/** Neutrals carry this fraction of the seed's chroma, up to NEUTRAL_CHROMA_MAX */
+const NEUTRAL_TINT = 0.15
+const NEUTRAL_CHROMA_MAX = 0.02
+
+/** Accent chroma bounds: a grey seed still gets colored accents, a neon one is tamed */
+const ACCENT_CHROMA_MIN = 0.08
+const ACCENT_CHROMA_MAX = 0.2
+
+/** Hue offsets from the seed */
+const SECONDARY_HUE = 60
+const TERTIARY_HUE = -60
+const ACCENT_HUE = 180
+
+/** Conventional hues for semantic colors */
+const SEMANTIC_HUES = { success: 145, warning: 80, error: 25, info: 230 } as const
+
+/** WCAG contrast targets against the background */
+const TEXT_CONTRAST = 7
+const MUTED_CONTRAST = 4.5
+
+/**
+ * A complete theme from one color. The seed may be any CSS color, an
+ * 0xRRGGBB number or RGBA.
+ */
+export function themeFromSeed(seed: string | number | RGBA, options: SeedThemeOptions = {}): Partial<typeof theme> {
+  const { scheme = 'dark', name = 'seed' } = options
+  const ramp = RAMPS[scheme]
+  const { c, h } = rgbToOklch(parseColor(seed))
+
+  const neutral = (l: number) => oklch(l, Math.min(c * NEUTRAL_TINT, NEUTRAL_CHROMA_MAX), h)
+  const chroma = Math.min(Math.max(c, ACCENT_CHROMA_MIN), ACCENT_CHROMA_MAX)
+  const hue = (offset: number) => oklch(ramp.accent, chroma, (h + offset + 360) % 360)
+
+  const background = neutral(ramp.background)
+  const readable = (color: RGBA, ratio: number) => toHex(adjustLightnessForContrast(color, background, ratio))
+
+  const primary = readable(hue(0), MUTED_CONTRAST)
+  return {
+    name,
+    description: `Generated from ${toHex(parseColor(seed))} (${scheme})`,
+    primary,
+    secondary: readable(hue(SECONDARY_HUE), MUTED_CONTRAST),
+    tertiary: readable(hue(TERTIARY_HUE), MUTED_CONTRAST),
+    accent: readable(hue(ACCENT_HUE), MUTED_CONTRAST),
+    success: readable(oklch(ramp.accent, chroma, SEMANTIC_HUES.success), MUTED_CONTRAST),
+    warning: readable(oklch(ramp.accent, chroma, SEMANTIC_HUES.warning), MUTED_CONTRAST),
+    error: readable(oklch(ramp.accent, chroma, SEMANTIC_HUES.error), MUTED_CONTRAST),
+    info: readable(oklch(ramp.accent, chroma, SEMANTIC_HUES.info), MUTED_CONTRAST),
+    text: readable(neutral(ramp.text), TEXT_CONTRAST),
+    textMuted: readable(neutral(ramp.textMuted), MUTED_CONTRAST),
+    textDim: readable(neutral(ramp.textDim), MUTED_CONTRAST),
+    textDisabled: toHex(neutral(ramp.textDisabled)),
+    textBright: readable(neutral(ramp.textBright), TEXT_CONTRAST),
+    background: toHex(background),
+    backgroundMuted: toHex(neutral(ramp.backgroundMuted)),
+    surface: toHex(neutral(ramp.surface)),
+    overlay: toHex(neutral(ramp.overlay)),
+    border: toHex(neutral(ramp.border)),
+    borderFocus: primary,
+  }
+}
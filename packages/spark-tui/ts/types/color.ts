@@ -53,6 +53,14 @@ export function ansiColor(index: number): RGBA {
   return { r: -2, g: index, b: 0, a: 255 }
 }
 
+/**
+ * `color`, or ANSI black where it's the terminal default: a panel painted
+ * with it hides what's beneath on any terminal background.
+ */
+export function opaque(color: RGBA): RGBA {
+  return isTerminalDefault(color) ? ansiColor(0) : color
+}
+
 export function isAnsiColor(color: RGBA): boolean {
   return color.r === -2
 }
@@ -70,6 +78,11 @@ export function rgba(r: number, g: number, b: number, a: number = 255): RGBA {
   return { r, g, b, a }
 }
 
+/** `#rrggbb` for a color (alpha dropped) */
+export function toHex({ r, g, b }: RGBA): string {
+  return '#' + [r, g, b].map((c) => c.toString(16).padStart(2, '0')).join('')
+}
+
 // =============================================================================
 // Color Parsing - Bun.color() integration
 // =============================================================================
@@ -328,7 +341,7 @@ export function adjustLightnessForContrast(fg: RGBA, bg: RGBA, minRatio: number
 /**
  * Calculate contrast ratio between two colors (WCAG formula).
  */
-function contrastRatio(fg: RGBA, bg: RGBA): number {
+export function contrastRatio(fg: RGBA, bg: RGBA): number {
   const lumFg = relativeLuminance(fg)
   const lumBg = relativeLuminance(bg)
   const lighter = Math.max(lumFg, lumBg)
@@ -347,6 +360,59 @@ function relativeLuminance(color: RGBA): number {
   return 0.2126 * toLinear(color.r) + 0.7152 * toLinear(color.g) + 0.0722 * toLinear(color.b)
 }
 
+// =============================================================================
+// Color-Vision Simulation
+// =============================================================================
+
+/** Color-vision deficiencies that can be simulated */
+export type ColorVisionMode = 'protanopia' | 'deuteranopia' | 'tritanopia'
+
+/**
+ * Machado et al. (2009) simulation matrices at full severity, applied in
+ * linear RGB. Must match rust/src/devtools/colorblind.rs.
+ */
+const COLOR_VISION_MATRICES: Record<ColorVisionMode, number[][]> = {
+  protanopia: [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+  ],
+  deuteranopia: [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.011820, 0.042940, 0.968881],
+  ],
+  tritanopia: [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.303900],
+  ],
+}
+
+/**
+ * How a color looks with the given deficiency.
+ * Terminal-default and ANSI colors are returned unchanged (the terminal owns their real value).
+ */
+export function simulateColorVision(color: RGBA, mode: ColorVisionMode): RGBA {
+  if (isTerminalDefault(color) || isAnsiColor(color)) return color
+
+  const toLinear = (c: number) => {
+    const s = Math.max(0, Math.min(255, c)) / 255
+    return s <= 0.04045 ? s / 12.92 : Math.pow((s + 0.055) / 1.055, 2.4)
+  }
+  const toSrgb = (l: number) => {
+    const c = Math.max(0, Math.min(1, l))
+    const s = c <= 0.0031308 ? c * 12.92 : 1.055 * Math.pow(c, 1 / 2.4) - 0.055
+    return Math.round(s * 255)
+  }
+
+  const rgb = [toLinear(color.r), toLinear(color.g), toLinear(color.b)]
+  const [r, g, b] = COLOR_VISION_MATRICES[mode].map(
+    (row) => toSrgb(row[0]! * rgb[0]! + row[1]! * rgb[1]! + row[2]! * rgb[2]!)
+  )
+  return { r: r!, g: g!, b: b!, a: color.a }
+}
+
 // =============================================================================
 // Color Comparison
 // =============================================================================
@@ -53,16 +53,19 @@ export type ColorInput = RGBA | string | number | null
  * A dimension value that can be absolute (number) or percentage (string).
  *
  * - number: Absolute value in terminal cells (e.g., 50 = 50 chars)
- * - string: Percentage of parent (e.g., '50%' = half of parent)
+ * - string: Percentage of parent (e.g., '50%' = half of parent), or of the
+ *   `relativeTo` ancestor when one is named
+ * - 'Nvw' / 'Nvh': Percentage of the terminal's width / height
  * - 0 or '0': Auto-size based on content
  *
  * Examples:
  *   width: 50        // 50 characters
  *   width: '100%'    // Full parent width
  *   width: '50%'     // Half of parent width
+ *   width: '80vw'    // 80% of the terminal's width
  *   height: 0        // Auto-height based on content
  */
-export type Dimension = number | `${number}%`
+export type Dimension = number | `${number}%` | `${number}vw` | `${number}vh`
 
 /**
  * Parsed dimension for internal use.